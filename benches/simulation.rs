@@ -0,0 +1,62 @@
+use criterion::{criterion_group, criterion_main, Criterion};
+use crypto_trading_bot::config::Config;
+use crypto_trading_bot::models::{OrderSide, OrderType};
+use crypto_trading_bot::simulation::SimulationExchange;
+use rust_decimal_macros::dec;
+use std::sync::Arc;
+
+fn exchange(runtime: &tokio::runtime::Runtime) -> Arc<SimulationExchange> {
+    let config = Config::defaults();
+    Arc::new(runtime.block_on(SimulationExchange::new(&config)).expect("simulation exchange"))
+}
+
+fn bench_simulation(c: &mut Criterion) {
+    let runtime = tokio::runtime::Builder::new_multi_thread().enable_all().build().unwrap();
+    let sim = exchange(&runtime);
+
+    c.bench_function("sim_get_price", |b| {
+        b.to_async(&runtime).iter(|| async { sim.get_price("BTCUSDT").await.unwrap() })
+    });
+
+    c.bench_function("sim_get_balance", |b| {
+        b.to_async(&runtime).iter(|| async { sim.get_balance().await.unwrap() })
+    });
+
+    c.bench_function("sim_free_balance", |b| {
+        b.to_async(&runtime).iter(|| async { sim.free_balance("USDT").await })
+    });
+
+    c.bench_function("sim_round_trip_order", |b| {
+        b.to_async(&runtime).iter(|| async {
+            sim.place_order("BTCUSDT", OrderSide::Buy, OrderType::Market, dec!(0.001), None).await.unwrap();
+            sim.place_order("BTCUSDT", OrderSide::Sell, OrderType::Market, dec!(0.001), None).await.unwrap();
+        })
+    });
+
+    // Many symbols' loops hitting the same exchange at once
+    c.bench_function("sim_concurrent_16_tasks", |b| {
+        b.to_async(&runtime).iter(|| {
+            let sim = sim.clone();
+            async move {
+                let tasks: Vec<_> = (0..16)
+                    .map(|i| {
+                        let sim = sim.clone();
+                        tokio::spawn(async move {
+                            if i % 4 == 0 {
+                                sim.get_balance().await.map(|_| ())
+                            } else {
+                                sim.get_price("BTCUSDT").await.map(|_| ())
+                            }
+                        })
+                    })
+                    .collect();
+                for task in tasks {
+                    task.await.unwrap().unwrap();
+                }
+            }
+        })
+    });
+}
+
+criterion_group!(benches, bench_simulation);
+criterion_main!(benches);
@@ -0,0 +1,28 @@
+//! Generates the gRPC service stubs for src/grpc.rs. The service is declared here instead of
+//! compiled from proto/trading_bot.proto so building needs no protoc; keep the two in step
+//! (`test_messages_match_proto` in src/grpc.rs checks).
+
+use tonic_build::manual::{Builder, Method, Service};
+
+fn method(name: &str, route: &str, input: &str, output: &str) -> tonic_build::manual::MethodBuilder {
+    Method::builder()
+        .name(name)
+        .route_name(route)
+        .input_type(format!("crate::grpc::{}", input))
+        .output_type(format!("crate::grpc::{}", output))
+        .codec_path("tonic_prost::ProstCodec")
+}
+
+fn main() {
+    let service = Service::builder()
+        .name("BotControl")
+        .package("trading_bot")
+        .method(method("get_status", "GetStatus", "StatusRequest", "StatusReply").build())
+        .method(method("pause", "Pause", "PauseRequest", "ControlReply").build())
+        .method(method("resume", "Resume", "ResumeRequest", "ControlReply").build())
+        .method(method("place_order", "PlaceOrder", "OrderRequest", "OrderReply").build())
+        .method(method("stream_events", "StreamEvents", "EventsRequest", "Event").server_streaming().build())
+        .build();
+    Builder::new().compile(&[service]);
+    println!("cargo:rerun-if-changed=build.rs");
+}
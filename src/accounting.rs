@@ -0,0 +1,215 @@
+use anyhow::{anyhow, Result};
+use rust_decimal::Decimal;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use tracing::debug;
+
+#[derive(Debug, Default)]
+struct LedgerState {
+    /// Quote funds held for orders that have been decided but not yet filled
+    pending: HashMap<String, Decimal>,
+    /// Quote cost of filled, still-open positions
+    allocated: HashMap<String, Decimal>,
+}
+
+impl LedgerState {
+    fn available(&self, free_balance: Decimal) -> Decimal {
+        let pending: Decimal = self.pending.values().copied().sum();
+        (free_balance - pending).max(Decimal::ZERO)
+    }
+}
+
+/// Funds reservation ledger for one quote asset.
+///
+/// Before an entry order is sent, its quote budget is reserved so other symbols (or
+/// the rebalancer) can't size an order with the same free balance. Once the order
+/// fills, the exchange balance reflects the spend, so the reservation is turned into
+/// the position's allocation and released again when the position closes.
+///
+/// The ledger is cheap to clone; clones share the same state.
+#[derive(Debug, Clone)]
+pub struct FundsLedger {
+    quote_asset: String,
+    state: Arc<Mutex<LedgerState>>,
+}
+
+impl FundsLedger {
+    pub fn new(quote_asset: &str) -> Self {
+        Self {
+            quote_asset: quote_asset.to_string(),
+            state: Arc::new(Mutex::new(LedgerState::default())),
+        }
+    }
+
+    pub fn quote_asset(&self) -> &str {
+        &self.quote_asset
+    }
+
+    /// Free quote balance not yet reserved for pending orders
+    pub fn available(&self, free_balance: Decimal) -> Decimal {
+        self.state.lock().unwrap().available(free_balance)
+    }
+
+    /// Reserve `amount` of the quote asset for an entry order on `symbol`; the check and
+    /// the hold happen under one lock, so concurrent entries can't both spend the same funds
+    pub fn reserve(&self, symbol: &str, amount: Decimal, free_balance: Decimal) -> Result<()> {
+        let mut state = self.state.lock().unwrap();
+        let available = state.available(free_balance);
+        if state.pending.contains_key(symbol) {
+            return Err(anyhow!("{} already has a pending reservation", symbol));
+        }
+        if amount > available {
+            return Err(anyhow!(
+                "Insufficient unreserved {}: need {:.2}, available {:.2}",
+                self.quote_asset, amount, available
+            ));
+        }
+        state.pending.insert(symbol.to_string(), amount);
+        debug!("Reserved {:.2} {} for {}", amount, self.quote_asset, symbol);
+        Ok(())
+    }
+
+    /// The entry order filled: drop the pending hold and record the position's cost
+    pub fn commit(&self, symbol: &str, cost: Decimal) {
+        let mut state = self.state.lock().unwrap();
+        state.pending.remove(symbol);
+        *state.allocated.entry(symbol.to_string()).or_default() += cost;
+    }
+
+    /// The entry order was not placed; return the reserved funds
+    pub fn cancel(&self, symbol: &str) {
+        self.state.lock().unwrap().pending.remove(symbol);
+    }
+
+    /// The position was closed; returns the cost that was allocated to it
+    pub fn release(&self, symbol: &str) -> Option<Decimal> {
+        self.state.lock().unwrap().allocated.remove(symbol)
+    }
+
+    /// Quote cost currently allocated to an open position
+    #[allow(dead_code)]
+    pub fn allocated(&self, symbol: &str) -> Decimal {
+        self.state.lock().unwrap().allocated.get(symbol).copied().unwrap_or_default()
+    }
+
+    /// Pending reservations plus allocations of open positions
+    pub fn total_committed(&self) -> Decimal {
+        let state = self.state.lock().unwrap();
+        state.pending.values().copied().sum::<Decimal>() + state.allocated.values().copied().sum::<Decimal>()
+    }
+}
+
+/// Long and short legs of one contract (futures hedge mode) netted into the exposure
+/// they add up to. Quantities are positive; each side keeps its average entry.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct NetPosition {
+    pub long_qty: Decimal,
+    pub long_entry: Decimal,
+    pub short_qty: Decimal,
+    pub short_entry: Decimal,
+}
+
+impl NetPosition {
+    /// Add a leg; `amount` is negative for a short
+    pub fn add(&mut self, amount: Decimal, entry: Decimal) {
+        let (qty, avg) = if amount > Decimal::ZERO {
+            (&mut self.long_qty, &mut self.long_entry)
+        } else if amount < Decimal::ZERO {
+            (&mut self.short_qty, &mut self.short_entry)
+        } else {
+            return;
+        };
+        let added = amount.abs();
+        *avg = (*avg * *qty + entry * added) / (*qty + added);
+        *qty += added;
+    }
+
+    /// Long minus short; negative when net short
+    pub fn net_qty(&self) -> Decimal {
+        self.long_qty - self.short_qty
+    }
+
+    /// Quantity held on both sides, which no longer moves with the price
+    pub fn hedged_qty(&self) -> Decimal {
+        self.long_qty.min(self.short_qty)
+    }
+
+    /// P&L fixed by the hedged quantity; realized once both legs close
+    pub fn locked_pnl(&self) -> Decimal {
+        self.hedged_qty() * (self.short_entry - self.long_entry)
+    }
+
+    /// Combined open P&L of both legs at `price`
+    pub fn unrealized_pnl(&self, price: Decimal) -> Decimal {
+        self.long_qty * (price - self.long_entry) + self.short_qty * (self.short_entry - price)
+    }
+
+    /// Price at which closing both legs breaks even; none when fully hedged
+    pub fn break_even(&self) -> Option<Decimal> {
+        let net = self.net_qty();
+        (!net.is_zero()).then(|| (self.long_qty * self.long_entry - self.short_qty * self.short_entry) / net)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rust_decimal_macros::dec;
+
+    #[test]
+    fn test_reservations_are_not_spent_twice() {
+        let ledger = FundsLedger::new("USDT");
+        let other = ledger.clone();
+
+        ledger.reserve("BTCUSDT", dec!(600), dec!(1000)).unwrap();
+        assert_eq!(other.available(dec!(1000)), dec!(400));
+        assert!(other.reserve("ETHUSDT", dec!(500), dec!(1000)).is_err());
+        assert!(ledger.reserve("BTCUSDT", dec!(100), dec!(1000)).is_err());
+
+        // After the fill the balance itself went down; the hold becomes an allocation
+        ledger.commit("BTCUSDT", dec!(600));
+        assert_eq!(other.available(dec!(400)), dec!(400));
+        assert_eq!(ledger.allocated("BTCUSDT"), dec!(600));
+        assert_eq!(ledger.release("BTCUSDT"), Some(dec!(600)));
+
+        other.reserve("ETHUSDT", dec!(300), dec!(400)).unwrap();
+        other.cancel("ETHUSDT");
+        assert_eq!(ledger.total_committed(), Decimal::ZERO);
+    }
+
+    #[test]
+    fn test_concurrent_reservations_share_one_balance() {
+        let ledger = FundsLedger::new("USDT");
+        let start = Arc::new(std::sync::Barrier::new(8));
+        let threads: Vec<_> = (0..8)
+            .map(|i| {
+                let (ledger, start) = (ledger.clone(), start.clone());
+                std::thread::spawn(move || {
+                    start.wait();
+                    ledger.reserve(&format!("SYM{}USDT", i), dec!(600), dec!(1000)).is_ok()
+                })
+            })
+            .collect();
+        let reserved = threads.into_iter().map(|t| t.join().unwrap()).filter(|ok| *ok).count();
+        assert_eq!(reserved, 1);
+        assert_eq!(ledger.total_committed(), dec!(600));
+    }
+
+    #[test]
+    fn test_hedged_legs_net_out() {
+        let mut net = NetPosition::default();
+        net.add(dec!(2), dec!(100));
+        net.add(dec!(-1), dec!(110));
+        net.add(dec!(-1), dec!(120));
+        assert_eq!((net.short_qty, net.short_entry), (dec!(2), dec!(115)));
+        assert_eq!((net.net_qty(), net.hedged_qty()), (dec!(0), dec!(2)));
+        // Fully hedged: 15 per contract is locked in whatever the price does
+        assert_eq!(net.locked_pnl(), dec!(30));
+        assert_eq!(net.unrealized_pnl(dec!(90)), dec!(30));
+        assert_eq!(net.break_even(), None);
+
+        net.add(dec!(1), dec!(130));
+        assert_eq!(net.net_qty(), dec!(1));
+        assert_eq!(net.unrealized_pnl(net.break_even().unwrap()), dec!(0));
+    }
+}
@@ -1,28 +1,111 @@
-use anyhow::{anyhow, Result};
+use crate::config::Config;
+use crate::error::{BotError, ReqwestExt};
+use anyhow::Result;
 use rust_decimal::Decimal;
 use rust_decimal_macros::dec;
 use serde::{Deserialize, Serialize};
 use tracing::{info, warn};
 
 /// Ollama API client for AI-powered trading target calculations
+#[derive(Clone)]
 pub struct OllamaClient {
     base_url: String,
     model: String,
+    options: GenerationOptions,
     client: reqwest::Client,
 }
 
 #[derive(Debug, Serialize)]
-struct OllamaRequest {
+struct OllamaRequest<'a> {
     model: String,
     prompt: String,
     stream: bool,
-    options: OllamaOptions,
+    options: &'a GenerationOptions,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    keep_alive: Option<&'a str>,
 }
 
-#[derive(Debug, Serialize)]
-struct OllamaOptions {
-    temperature: f32,
-    num_predict: i32,
+/// Generation settings sent with each request: OLLAMA_TEMPERATURE and friends, with
+/// OLLAMA_MODEL_OPTIONS overriding them for the configured model
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct GenerationOptions {
+    pub temperature: f32,
+    /// Most tokens to generate (-1 = unlimited)
+    pub num_predict: i32,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub top_p: Option<f32>,
+    /// Fixed seed for reproducible answers
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub seed: Option<i64>,
+    /// How long Ollama keeps the model loaded after a request (`10m`, `-1` = forever);
+    /// sent beside the options rather than among them
+    #[serde(skip)]
+    pub keep_alive: Option<String>,
+}
+
+impl Default for GenerationOptions {
+    fn default() -> Self {
+        Self {
+            temperature: 0.3, // Lower temperature for more consistent analysis
+            num_predict: 1000,
+            top_p: None,
+            seed: None,
+            keep_alive: None,
+        }
+    }
+}
+
+impl GenerationOptions {
+    pub fn from_config(config: &Config) -> Result<Self> {
+        let mut options = Self {
+            temperature: config.ollama_temperature,
+            num_predict: config.ollama_num_predict,
+            top_p: config.ollama_top_p,
+            seed: config.ollama_seed,
+            keep_alive: config.ollama_keep_alive.clone(),
+        };
+        // Entries look like `mistral=temperature:0,seed:42;llama3:8b=num_predict:400`
+        for entry in config.ollama_model_options.split(';').map(str::trim).filter(|e| !e.is_empty()) {
+            let (model, settings) = entry.split_once('=').ok_or_else(|| BotError::config(
+                format!("OLLAMA_MODEL_OPTIONS entry '{}' must look like model=key:value,...", entry)))?;
+            if model.trim() != config.ollama_model {
+                continue;
+            }
+            for setting in settings.split(',').map(str::trim).filter(|s| !s.is_empty()) {
+                let (key, value) = setting.split_once(':').unwrap_or((setting, ""));
+                options.set(key.trim(), value.trim())?;
+            }
+        }
+        options.validate()?;
+        Ok(options)
+    }
+
+    fn set(&mut self, key: &str, value: &str) -> Result<()> {
+        let invalid = || BotError::config(format!("Invalid OLLAMA_MODEL_OPTIONS value for {}: '{}'", key, value));
+        match key {
+            "temperature" => self.temperature = value.parse().map_err(|_| invalid())?,
+            "num_predict" => self.num_predict = value.parse().map_err(|_| invalid())?,
+            "top_p" => self.top_p = Some(value.parse().map_err(|_| invalid())?),
+            "seed" => self.seed = Some(value.parse().map_err(|_| invalid())?),
+            "keep_alive" => self.keep_alive = Some(value.to_string()).filter(|v| !v.is_empty()),
+            _ => return Err(BotError::config(format!(
+                "Unknown OLLAMA_MODEL_OPTIONS key '{}' (expected temperature, num_predict, top_p, seed or keep_alive)", key)).into()),
+        }
+        Ok(())
+    }
+
+    fn validate(&self) -> Result<()> {
+        if !self.temperature.is_finite() || self.temperature < 0.0 {
+            return Err(BotError::config(format!("Ollama temperature must be 0 or more, got {}", self.temperature)).into());
+        }
+        if self.top_p.is_some_and(|p| !(p > 0.0 && p <= 1.0)) {
+            return Err(BotError::config(format!("Ollama top_p must be in (0, 1], got {:?}", self.top_p)).into());
+        }
+        if self.num_predict == 0 || self.num_predict < -2 {
+            return Err(BotError::config(format!("Ollama num_predict must be positive, -1 or -2, got {}", self.num_predict)).into());
+        }
+        Ok(())
+    }
 }
 
 #[derive(Debug, Deserialize)]
@@ -31,7 +114,7 @@ struct OllamaResponse {
 }
 
 /// AI-calculated trading targets
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct AiTradingTargets {
     pub stop_loss_price: Decimal,
     pub take_profit_price: Decimal,
@@ -46,9 +129,15 @@ pub struct AiTradingTargets {
     pub resistance: Option<Decimal>,
     pub strong_resistance: Option<Decimal>,
     pub pivot_point: Option<Decimal>,
+    /// Share of the portfolio the model would hold in the base asset, the rest in the quote
+    #[serde(default)]
+    pub allocation_percent: Option<Decimal>,
+    /// Share of the free quote balance the model would put into the next entry
+    #[serde(default)]
+    pub position_size_percent: Option<Decimal>,
 }
 
-#[derive(Debug, Clone, PartialEq)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub enum TradingRecommendation {
     StrongBuy,
     Buy,
@@ -71,7 +160,7 @@ impl std::fmt::Display for TradingRecommendation {
 
 /// Market data to send to the AI for analysis
 #[allow(dead_code)]
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Default, Serialize)]
 pub struct MarketContext {
     pub symbol: String,
     pub current_price: Decimal,
@@ -90,6 +179,26 @@ pub struct MarketContext {
     pub low_12h: Option<Decimal>,
     pub high_48h: Option<Decimal>,
     pub low_48h: Option<Decimal>,
+    /// Deribit put/call ratio and max pain, pre-formatted
+    pub options_summary: Option<String>,
+    /// Current hour's volume against its trailing average, pre-formatted
+    pub volume_summary: Option<String>,
+    /// RSI/MACD divergences on the hourly candles, pre-formatted
+    pub divergence_summary: Option<String>,
+    /// Point of control and value area of the hourly volume, pre-formatted
+    pub volume_profile_summary: Option<String>,
+    /// BTC's share of the total crypto market cap, in percent
+    pub btc_dominance: Option<Decimal>,
+    /// 24h change of the total crypto market cap, in percent
+    pub market_cap_change_24h_percent: Option<Decimal>,
+}
+
+/// How long loading a model may take
+const WARM_UP_TIMEOUT: std::time::Duration = std::time::Duration::from_secs(600);
+
+/// `mistral` matches `mistral:latest`
+pub fn model_available(models: &[String], wanted: &str) -> bool {
+    models.iter().any(|m| m == wanted || m.strip_suffix(":latest") == Some(wanted))
 }
 
 impl OllamaClient {
@@ -101,10 +210,66 @@ impl OllamaClient {
         Ok(Self {
             base_url: base_url.unwrap_or("http://localhost:11434").to_string(),
             model: model.unwrap_or("mistral").to_string(),
+            options: GenerationOptions::default(),
             client,
         })
     }
 
+    /// Client for OLLAMA_URL and OLLAMA_MODEL with the configured generation options
+    pub fn from_config(config: &Config) -> Result<Self> {
+        Ok(Self::new(Some(&config.ollama_url), Some(&config.ollama_model))?
+            .with_options(GenerationOptions::from_config(config)?))
+    }
+
+    pub fn with_options(self, options: GenerationOptions) -> Self {
+        Self { options, ..self }
+    }
+
+    /// Error unless the model has been pulled, naming the command that fixes it
+    pub async fn verify_model(&self) -> Result<()> {
+        let models = self.list_models().await?;
+        if model_available(&models, &self.model) {
+            return Ok(());
+        }
+        let available = if models.is_empty() { "none".to_string() } else { models.join(", ") };
+        Err(BotError::config(format!("Ollama model '{}' is not pulled at {} - run `ollama pull {}` (available: {})",
+            self.model, self.base_url, self.model, available)).into())
+    }
+
+    /// Load the model into memory with an empty prompt, or keep it loaded for another
+    /// keep-alive period; returns how long it took
+    pub async fn warm_up(&self) -> Result<std::time::Duration> {
+        let started = std::time::Instant::now();
+        let request = OllamaRequest {
+            model: self.model.clone(),
+            prompt: String::new(),
+            stream: false,
+            options: &self.options,
+            keep_alive: self.options.keep_alive.as_deref(),
+        };
+        let response = self.client
+            .post(format!("{}/api/generate", self.base_url))
+            .json(&request)
+            // A cold load of a large model can outlast the analysis timeout
+            .timeout(WARM_UP_TIMEOUT)
+            .send()
+            .await
+            .or_ai()?;
+        self.check_status(response.status())?;
+        Ok(started.elapsed())
+    }
+
+    /// Ollama answers 404 for a model that isn't pulled
+    fn check_status(&self, status: reqwest::StatusCode) -> Result<()> {
+        if status == reqwest::StatusCode::NOT_FOUND {
+            return Err(BotError::config(format!("Ollama model '{}' not found - run `ollama pull {}`", self.model, self.model)).into());
+        }
+        if !status.is_success() {
+            return Err(BotError::ai(status).into());
+        }
+        Ok(())
+    }
+
     /// Check if Ollama is running and the model is available
     pub async fn health_check(&self) -> Result<bool> {
         let url = format!("{}/api/tags", self.base_url);
@@ -126,20 +291,42 @@ impl OllamaClient {
         }
     }
 
+    pub fn model(&self) -> &str {
+        &self.model
+    }
+
+    /// Names of the models pulled into Ollama (`mistral:latest`, ...)
+    pub async fn list_models(&self) -> Result<Vec<String>> {
+        let url = format!("{}/api/tags", self.base_url);
+        let response = self.client.get(&url).send().await.or_ai()?;
+        if !response.status().is_success() {
+            return Err(BotError::ai(response.status()).into());
+        }
+        let tags: serde_json::Value = response.json().await.or_ai()?;
+        Ok(tags["models"].as_array().into_iter().flatten()
+            .filter_map(|m| m["name"].as_str().map(str::to_string))
+            .collect())
+    }
+
     /// Calculate trading targets using AI
     pub async fn calculate_targets(&self, context: &MarketContext) -> Result<AiTradingTargets> {
         let prompt = self.build_analysis_prompt(context);
         
         info!("🤖 Requesting AI analysis for {} targets...", context.symbol);
+        let response = self.generate(prompt).await?;
         
+        // Parse the AI response
+        self.parse_ai_response(&response, context)
+    }
+
+    /// The model's answer to `prompt`
+    pub async fn generate(&self, prompt: String) -> Result<String> {
         let request = OllamaRequest {
             model: self.model.clone(),
             prompt,
             stream: false,
-            options: OllamaOptions {
-                temperature: 0.3, // Lower temperature for more consistent analysis
-                num_predict: 1000,
-            },
+            options: &self.options,
+            keep_alive: self.options.keep_alive.as_deref(),
         };
 
         let url = format!("{}/api/generate", self.base_url);
@@ -148,16 +335,12 @@ impl OllamaClient {
             .post(&url)
             .json(&request)
             .send()
-            .await?;
+            .await
+            .or_ai()?;
+        self.check_status(response.status())?;
 
-        if !response.status().is_success() {
-            return Err(anyhow!("Ollama API error: {}", response.status()));
-        }
-
-        let ollama_response: OllamaResponse = response.json().await?;
-        
-        // Parse the AI response
-        self.parse_ai_response(&ollama_response.response, context)
+        let ollama_response: OllamaResponse = response.json().await.or_ai()?;
+        Ok(ollama_response.response)
     }
 
     fn build_analysis_prompt(&self, ctx: &MarketContext) -> String {
@@ -193,6 +376,17 @@ impl OllamaClient {
 
         // Add hourly data if available
         let hourly_info = ctx.hourly_data_summary.clone().unwrap_or_else(|| "Not available".to_string());
+        let options_info = ctx.options_summary.clone().unwrap_or_else(|| "Not available".to_string());
+        let volume_info = ctx.volume_summary.clone().unwrap_or_else(|| "Not available".to_string());
+        let divergence_info = ctx.divergence_summary.clone().unwrap_or_else(|| "Not available".to_string());
+        let profile_info = ctx.volume_profile_summary.clone().unwrap_or_else(|| "Not available".to_string());
+        let breadth_info = match (ctx.btc_dominance, ctx.market_cap_change_24h_percent) {
+            (Some(dominance), Some(change)) => {
+                let market = if change >= Decimal::ZERO { "RISING" } else { "FALLING" };
+                format!("BTC dominance {:.2}%, total market cap {:+.2}% in 24h ({})", dominance, change, market)
+            }
+            _ => "Not available".to_string(),
+        };
         
         let price_ranges = format!(
             "12h Range: ${:.2} - ${:.2}, 48h Range: ${:.2} - ${:.2}",
@@ -212,11 +406,18 @@ MARKET DATA FOR {symbol}:
 - Price Ranges: {price_ranges}
 - Moving Averages: {sma}
 - RSI (14): {rsi}
-- Account Balance: ${balance:.2} USDT
+- Divergences: {divergence}
+- Volume: {volume}
+- Volume Profile: {profile}
+- Market Breadth: {breadth}
+- Account Balance: {balance:.2} {quote_asset}
 
 HOURLY PRICE DATA:
 {hourly_info}
 
+OPTIONS MARKET (Deribit):
+{options_info}
+
 CURRENT POSITION:
 {position}
 
@@ -240,6 +441,8 @@ STRONG_SUPPORT: $[S2 price]
 RESISTANCE: $[R1 price]
 STRONG_RESISTANCE: $[R2 price]
 PIVOT: $[pivot point price]
+ALLOCATION: [0-100]% {base_asset}, the rest in {quote_asset} (optional)
+POSITION_SIZE: [1-100]% of the free {quote_asset} balance for the next entry (optional)
 REASONING: [Your 2-3 sentence explanation including support/resistance analysis]
 
 Rules:
@@ -249,7 +452,12 @@ Rules:
 4. Stop-loss should be below strong support
 5. Take-profit should be near or above resistance
 6. Even for HOLD recommendations, provide buy/sell targets for future reference
-7. Provide specific dollar amounts, not percentages"#,
+7. Provide specific dollar amounts, not percentages
+8. UNUSUAL ACTIVITY volume means the move has conviction behind it; factor it into your confidence
+9. Altcoins follow BTC: a falling total market cap or rising BTC dominance argues against altcoin longs
+10. ALLOCATION and POSITION_SIZE are optional; give them only when your confidence supports a size
+11. A BULLISH divergence (price lower low, RSI/MACD higher low) hints at a reversal up near support; a BEARISH one (price higher high, indicator lower high) at a reversal down near resistance
+12. The volume profile's point of control and value-area edges are levels where the market did most of its business; they tend to act as support or resistance"#,
             symbol = ctx.symbol,
             current_price = ctx.current_price,
             high = ctx.high_24h,
@@ -258,13 +466,21 @@ Rules:
             price_ranges = price_ranges,
             sma = sma_info,
             rsi = rsi_info,
+            divergence = divergence_info,
+            volume = volume_info,
+            profile = profile_info,
+            breadth = breadth_info,
             balance = ctx.account_balance,
+            base_asset = crate::symbols::lookup(&ctx.symbol).base,
+            quote_asset = crate::symbols::lookup(&ctx.symbol).quote,
             hourly_info = hourly_info,
+            options_info = options_info,
             position = position_info,
         )
     }
 
-    fn parse_ai_response(&self, response: &str, context: &MarketContext) -> Result<AiTradingTargets> {
+    /// Targets from the model's answer; implausible or missing levels fall back to defaults
+    pub fn parse_ai_response(&self, response: &str, context: &MarketContext) -> Result<AiTradingTargets> {
         let response_upper = response.to_uppercase();
         
         // Parse recommendation
@@ -282,22 +498,28 @@ Rules:
 
         // Parse confidence
         let confidence = self.extract_percentage(response, "CONFIDENCE")
-            .unwrap_or(dec!(50));
+            .unwrap_or(dec!(50))
+            .min(dec!(100));
 
-        // Parse prices
+        // Parse prices; a stop above the price or a target below it is a misread, not a plan
+        let current = context.current_price;
         let stop_loss = self.extract_price(response, "STOP_LOSS")
             .or_else(|| self.extract_price(response, "STOP LOSS"))
-            .unwrap_or_else(|| context.current_price * dec!(0.95)); // Default 5% below
+            .filter(|p| Self::sane_level(*p, current, "stop-loss", |p| p < current))
+            .unwrap_or_else(|| current * dec!(0.95)); // Default 5% below
 
         let take_profit = self.extract_price(response, "TAKE_PROFIT")
             .or_else(|| self.extract_price(response, "TAKE PROFIT"))
-            .unwrap_or_else(|| context.current_price * dec!(1.10)); // Default 10% above
+            .filter(|p| Self::sane_level(*p, current, "take-profit", |p| p > current))
+            .unwrap_or_else(|| current * dec!(1.10)); // Default 10% above
 
         let buy_target = self.extract_price(response, "BUY_TARGET")
-            .or_else(|| self.extract_price(response, "BUY TARGET"));
+            .or_else(|| self.extract_price(response, "BUY TARGET"))
+            .filter(|p| Self::sane_level(*p, current, "buy target", |_| true));
 
         let sell_target = self.extract_price(response, "SELL_TARGET")
-            .or_else(|| self.extract_price(response, "SELL TARGET"));
+            .or_else(|| self.extract_price(response, "SELL TARGET"))
+            .filter(|p| Self::sane_level(*p, current, "sell target", |_| true));
 
         // Parse support and resistance levels
         let support = self.extract_price(response, "SUPPORT:");
@@ -308,6 +530,12 @@ Rules:
             .or_else(|| self.extract_price(response, "STRONG RESISTANCE"));
         let pivot = self.extract_price(response, "PIVOT");
 
+        // Sizing is optional; anything over 100% is a misread
+        let allocation = self.extract_percentage(response, "ALLOCATION:").filter(|p| *p <= dec!(100));
+        let position_size = self.extract_percentage(response, "POSITION_SIZE")
+            .or_else(|| self.extract_percentage(response, "POSITION SIZE"))
+            .filter(|p| *p > Decimal::ZERO && *p <= dec!(100));
+
         // Extract reasoning
         let reasoning = self.extract_reasoning(response)
             .unwrap_or_else(|| "AI analysis completed".to_string());
@@ -325,16 +553,35 @@ Rules:
             resistance,
             strong_resistance,
             pivot_point: pivot,
+            allocation_percent: allocation,
+            position_size_percent: position_size,
         })
     }
 
+    /// Reject zero prices, prices on the wrong side of the market and anything more
+    /// than 10x away from it
+    fn sane_level(price: Decimal, current: Decimal, name: &str, right_side: impl Fn(Decimal) -> bool) -> bool {
+        let sane = price > Decimal::ZERO && right_side(price)
+            && (current.is_zero() || (price / current > dec!(0.1) && price / current < dec!(10)));
+        if !sane {
+            warn!("⚠️ Ignoring implausible AI {} {} (price {})", name, price, current);
+        }
+        sane
+    }
+
+    /// Byte offset just past the first case-insensitive match of an ASCII `label`.
+    /// Matching on the original text keeps offsets valid for non-ASCII responses.
+    fn find_after_label(text: &str, label: &str) -> Option<usize> {
+        text.char_indices()
+            .map(|(i, _)| i)
+            .find(|&i| text.get(i..i + label.len()).is_some_and(|s| s.eq_ignore_ascii_case(label)))
+            .map(|i| i + label.len())
+    }
+
     fn extract_price(&self, text: &str, label: &str) -> Option<Decimal> {
         // Look for pattern like "STOP_LOSS: $42000" or "STOP_LOSS: 42000"
-        let text_upper = text.to_uppercase();
-        let label_upper = label.to_uppercase();
-        
-        if let Some(pos) = text_upper.find(&label_upper) {
-            let after_label = &text[pos + label.len()..];
+        if let Some(end) = Self::find_after_label(text, label) {
+            let after_label = &text[end..];
             // Find the number after $ or : 
             let number_str: String = after_label
                 .chars()
@@ -351,11 +598,8 @@ Rules:
     }
 
     fn extract_percentage(&self, text: &str, label: &str) -> Option<Decimal> {
-        let text_upper = text.to_uppercase();
-        let label_upper = label.to_uppercase();
-        
-        if let Some(pos) = text_upper.find(&label_upper) {
-            let after_label = &text[pos + label.len()..];
+        if let Some(end) = Self::find_after_label(text, label) {
+            let after_label = &text[end..];
             let number_str: String = after_label
                 .chars()
                 .skip_while(|c| !c.is_ascii_digit())
@@ -370,10 +614,8 @@ Rules:
     }
 
     fn extract_reasoning(&self, text: &str) -> Option<String> {
-        let text_upper = text.to_uppercase();
-        
-        if let Some(pos) = text_upper.find("REASONING:") {
-            let after_label = &text[pos + 10..];
+        if let Some(end) = Self::find_after_label(text, "REASONING:") {
+            let after_label = &text[end..];
             let reasoning: String = after_label
                 .lines()
                 .next()
@@ -452,6 +694,8 @@ impl FallbackTargetCalculator {
             resistance: Some(resistance),
             strong_resistance: Some(strong_resistance),
             pivot_point: Some(pivot),
+            allocation_percent: None,
+            position_size_percent: None,
         }
     }
 
@@ -548,3 +792,157 @@ impl FallbackTargetCalculator {
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use proptest::prelude::*;
+
+    fn context(price: Decimal, high: Decimal, low: Decimal) -> MarketContext {
+        MarketContext {
+            symbol: "BTCUSDT".to_string(),
+            current_price: price,
+            high_24h: high,
+            low_24h: low,
+            price_change_24h_percent: Decimal::ZERO,
+            sma_short: None,
+            sma_long: None,
+            rsi: None,
+            volume_24h: None,
+            position_entry_price: None,
+            account_balance: dec!(1000),
+            hourly_data_summary: None,
+            options_summary: None,
+            volume_summary: None,
+            divergence_summary: None,
+            volume_profile_summary: None,
+            btc_dominance: None,
+            market_cap_change_24h_percent: None,
+            high_12h: None,
+            low_12h: None,
+            high_48h: None,
+            low_48h: None,
+        }
+    }
+
+    /// Building a reqwest client per proptest case is slow; the parser only needs one
+    fn client() -> &'static OllamaClient {
+        static CLIENT: std::sync::LazyLock<OllamaClient> =
+            std::sync::LazyLock::new(|| OllamaClient::new(None, None).unwrap());
+        &CLIENT
+    }
+
+    #[test]
+    fn test_parse_well_formed_response() {
+        let ctx = context(dec!(60000), dec!(61000), dec!(59000));
+        let response = "RECOMMENDATION: BUY\nCONFIDENCE: 72%\nSTOP_LOSS: $57,500.50\nTAKE_PROFIT: $66000\n\
+                        SUPPORT: $58000\nRESISTANCE: $62000\nPIVOT: $60000\nREASONING: Trend is up";
+        let targets = client().parse_ai_response(response, &ctx).unwrap();
+        assert_eq!(targets.recommendation, TradingRecommendation::Buy);
+        assert_eq!(targets.confidence, dec!(72));
+        assert_eq!(targets.stop_loss_price, dec!(57500.50));
+        assert_eq!(targets.take_profit_price, dec!(66000));
+        assert_eq!(targets.pivot_point, Some(dec!(60000)));
+        assert_eq!(targets.reasoning, "Trend is up");
+        assert_eq!((targets.allocation_percent, targets.position_size_percent), (None, None));
+
+        let sized = format!("{}\nALLOCATION: 60% BTC, 40% cash\nPOSITION_SIZE: 15%", response);
+        let targets = client().parse_ai_response(&sized, &ctx).unwrap();
+        assert_eq!((targets.allocation_percent, targets.position_size_percent), (Some(dec!(60)), Some(dec!(15))));
+    }
+
+    #[test]
+    fn test_implausible_levels_fall_back_to_defaults() {
+        let ctx = context(dec!(60000), dec!(61000), dec!(59000));
+        // Stop above the price, take-profit below it, confidence over 100
+        let response = "CONFIDENCE: 450%\nSTOP_LOSS: $65000\nTAKE_PROFIT: $6000\nBUY_TARGET: $0";
+        let targets = client().parse_ai_response(response, &ctx).unwrap();
+        assert_eq!(targets.stop_loss_price, dec!(57000));
+        assert_eq!(targets.take_profit_price, dec!(66000));
+        assert_eq!(targets.confidence, dec!(100));
+        assert_eq!(targets.buy_target_price, None);
+    }
+
+    #[test]
+    fn test_generation_options_per_model() {
+        let mut config = Config::defaults();
+        config.ollama_model = "llama3:8b".to_string();
+        config.ollama_temperature = 0.5;
+        config.ollama_model_options = "mistral=temperature:0.9; llama3:8b=temperature:0,seed:42,keep_alive:10m".to_string();
+        let options = GenerationOptions::from_config(&config).unwrap();
+        assert_eq!(options.temperature, 0.0);
+        assert_eq!(options.seed, Some(42));
+        assert_eq!(options.num_predict, 1000);
+
+        let request = OllamaRequest {
+            model: config.ollama_model.clone(),
+            prompt: String::new(),
+            stream: false,
+            options: &options,
+            keep_alive: options.keep_alive.as_deref(),
+        };
+        let body = serde_json::to_value(&request).unwrap();
+        assert_eq!(body["keep_alive"], "10m");
+        assert_eq!(body["options"]["seed"], 42);
+        assert!(body["options"].get("top_p").is_none() && body["options"].get("keep_alive").is_none());
+
+        config.ollama_model_options = "llama3:8b=top_p:1.5".to_string();
+        assert!(GenerationOptions::from_config(&config).is_err());
+        config.ollama_model_options = "llama3:8b=mirostat:2".to_string();
+        assert!(GenerationOptions::from_config(&config).is_err());
+    }
+
+    fn price() -> impl Strategy<Value = Decimal> {
+        (100i64..100_000_000).prop_map(|cents| Decimal::new(cents, 2))
+    }
+
+    /// LLM-ish noise: labels, numbers, currency symbols and arbitrary unicode
+    fn llm_output() -> impl Strategy<Value = String> {
+        let fragment = prop_oneof![
+            Just("STOP_LOSS: ".to_string()),
+            Just("TAKE PROFIT: $".to_string()),
+            Just("CONFIDENCE: ".to_string()),
+            Just("REASONING:".to_string()),
+            Just("RECOMMENDATION: STRONG_BUY".to_string()),
+            Just("Straße ĸ İ ﬁ 💰".to_string()),
+            "[0-9.,$%-]{0,12}",
+            any::<String>(),
+        ];
+        prop::collection::vec(fragment, 0..12).prop_map(|parts| parts.concat())
+    }
+
+    proptest! {
+        #[test]
+        fn parser_never_panics_and_keeps_levels_sane(response in llm_output(), current in price()) {
+            let ctx = context(current, current * dec!(1.02), current * dec!(0.98));
+            let targets = client().parse_ai_response(&response, &ctx).unwrap();
+            prop_assert!(targets.stop_loss_price > Decimal::ZERO && targets.stop_loss_price < current);
+            prop_assert!(targets.take_profit_price > current);
+            prop_assert!(targets.confidence >= Decimal::ZERO && targets.confidence <= dec!(100));
+        }
+
+        #[test]
+        fn parser_reads_back_generated_levels(current in price(), sl_pct in 1u32..50, tp_pct in 1u32..200) {
+            let sl = (current * (dec!(1) - Decimal::from(sl_pct) / dec!(100))).round_dp(2);
+            let tp = (current * (dec!(1) + Decimal::from(tp_pct) / dec!(100))).round_dp(2);
+            let response = format!("Recommendation: hold\nStop_Loss: ${}\ntake_profit: {}\n", sl, tp);
+            let targets = client().parse_ai_response(&response, &context(current, current, current)).unwrap();
+            prop_assert_eq!(targets.stop_loss_price, sl);
+            prop_assert_eq!(targets.take_profit_price, tp);
+        }
+
+        #[test]
+        fn fallback_pivot_levels_are_ordered(low in price(), spread in 0i64..10_000_000, pos in 0u32..=100) {
+            let high = low + Decimal::new(spread, 2);
+            let current = low + (high - low) * Decimal::from(pos) / dec!(100);
+            let targets = FallbackTargetCalculator::calculate_targets(&context(current, high, low));
+
+            let pivot = targets.pivot_point.unwrap();
+            prop_assert!(targets.strong_support.unwrap() <= targets.support.unwrap());
+            prop_assert!(targets.support.unwrap() <= pivot);
+            prop_assert!(pivot <= targets.resistance.unwrap());
+            prop_assert!(targets.resistance.unwrap() <= targets.strong_resistance.unwrap());
+            prop_assert!(targets.stop_loss_price < current && targets.take_profit_price > current);
+        }
+    }
+}
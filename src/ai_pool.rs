@@ -0,0 +1,104 @@
+use crate::ai_advisor::{AiTradingTargets, MarketContext, OllamaClient};
+use crate::config::Config;
+use crate::error::BotError;
+use anyhow::{anyhow, Result};
+use futures_util::stream::{self, StreamExt};
+use std::time::Duration;
+
+/// How long one analysis may take
+const ANALYSIS_TIMEOUT: Duration = Duration::from_secs(120);
+
+/// A symbol waiting for analysis
+#[derive(Debug, Clone)]
+pub struct AiJob {
+    pub context: MarketContext,
+    /// Symbols with an open position go first: their exits depend on fresh targets
+    pub has_position: bool,
+}
+
+/// Analyses several symbols against one Ollama server, AI_MAX_CONCURRENCY at a time, so
+/// a watchlist doesn't queue into a chain of two-minute requests. Ollama itself runs
+/// OLLAMA_NUM_PARALLEL requests per model at once; more than that just waits server-side.
+pub struct AiPool {
+    client: OllamaClient,
+    concurrency: usize,
+    timeout: Duration,
+}
+
+impl AiPool {
+    pub fn new(client: OllamaClient, concurrency: usize) -> Self {
+        Self { client, concurrency: concurrency.max(1), timeout: ANALYSIS_TIMEOUT }
+    }
+
+    pub fn from_config(config: &Config, client: OllamaClient) -> Result<Self> {
+        if config.ai_max_concurrency == 0 {
+            return Err(BotError::config("AI_MAX_CONCURRENCY must be at least 1").into());
+        }
+        Ok(Self::new(client, config.ai_max_concurrency))
+    }
+
+    pub fn with_timeout(self, timeout: Duration) -> Self {
+        Self { timeout, ..self }
+    }
+
+    /// Open positions first, otherwise in the order given
+    fn prioritize(jobs: &mut [AiJob]) {
+        jobs.sort_by_key(|job| !job.has_position);
+    }
+
+    /// Targets per symbol, in priority order; a failed or timed-out analysis doesn't hold up the rest
+    pub async fn analyze(&self, mut jobs: Vec<AiJob>) -> Vec<(String, Result<AiTradingTargets>)> {
+        Self::prioritize(&mut jobs);
+        stream::iter(jobs)
+            .map(|job| async move {
+                let result = tokio::time::timeout(self.timeout, self.client.calculate_targets(&job.context)).await
+                    .unwrap_or_else(|_| Err(anyhow!("AI analysis timed out after {}s", self.timeout.as_secs())));
+                (job.context.symbol, result)
+            })
+            .buffered(self.concurrency)
+            .collect()
+            .await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rust_decimal_macros::dec;
+    use serde_json::json;
+    use std::time::Instant;
+    use wiremock::matchers::{method, path};
+    use wiremock::{Mock, MockServer, ResponseTemplate};
+
+    fn job(symbol: &str, has_position: bool) -> AiJob {
+        let context = MarketContext { symbol: symbol.to_string(), current_price: dec!(100), ..Default::default() };
+        AiJob { context, has_position }
+    }
+
+    #[tokio::test]
+    async fn test_bounded_concurrency_and_priority() {
+        let server = MockServer::start().await;
+        Mock::given(method("POST"))
+            .and(path("/api/generate"))
+            .respond_with(ResponseTemplate::new(200)
+                .set_body_json(json!({"response": "RECOMMENDATION: BUY"}))
+                .set_delay(Duration::from_millis(300)))
+            .mount(&server)
+            .await;
+        let pool = AiPool::new(OllamaClient::new(Some(&server.uri()), None).unwrap(), 2);
+
+        let started = Instant::now();
+        let jobs = vec![job("ETHUSDT", false), job("SOLUSDT", true), job("XRPUSDT", false), job("BTCUSDT", true)];
+        let results = pool.analyze(jobs).await;
+        // Four analyses, two at a time: two rounds (600ms) rather than four (1200ms)
+        let elapsed = started.elapsed();
+        assert!(elapsed >= Duration::from_millis(600) && elapsed < Duration::from_millis(1200), "{:?}", elapsed);
+        let symbols: Vec<_> = results.iter().map(|(symbol, _)| symbol.as_str()).collect();
+        assert_eq!(symbols, ["SOLUSDT", "BTCUSDT", "ETHUSDT", "XRPUSDT"]);
+        assert!(results.iter().all(|(_, r)| r.is_ok()));
+
+        let pool = pool.with_timeout(Duration::from_millis(50));
+        let results = pool.analyze(vec![job("BTCUSDT", false)]).await;
+        assert!(results[0].1.as_ref().unwrap_err().to_string().contains("timed out"));
+    }
+}
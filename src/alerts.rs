@@ -0,0 +1,560 @@
+use crate::audit::{AuditEvent, AuditLog};
+use crate::config::Config;
+use crate::error::BotError;
+use crate::notifier::TelegramNotifier;
+use crate::portfolio::{PortfolioReporter, PortfolioStatus};
+use anyhow::Result;
+use axum::extract::Path as UrlPath;
+use axum::http::StatusCode;
+use axum::routing::{delete, get};
+use axum::Router;
+use chrono::{DateTime, Duration, Utc};
+use rust_decimal::Decimal;
+use rust_decimal_macros::dec;
+use std::collections::VecDeque;
+use std::sync::{Arc, Mutex};
+use tracing::{info, warn};
+
+/// Values a rule can test, by the name used in ALERT_RULES
+const VARIABLES: [&str; 25] = [
+    "price", "change_24h", "high_24h", "low_24h",
+    "rsi", "sma_short", "sma_long", "volume_ratio",
+    "support", "strong_support", "resistance", "strong_resistance", "pivot",
+    "stop_loss", "take_profit", "buy_target", "sell_target", "ai_confidence",
+    "position_size", "unrealized_pnl", "unrealized_pnl_percent",
+    "realized_pnl", "day_pnl", "portfolio_value", "trades_today",
+];
+
+fn variable(status: &PortfolioStatus, name: &str) -> Option<Decimal> {
+    let has_position = status.position_size > Decimal::ZERO;
+    match name {
+        "price" => Some(status.current_price).filter(|p| *p > Decimal::ZERO),
+        "change_24h" => Some(status.price_change_24h_percent),
+        "high_24h" => Some(status.high_24h).filter(|p| *p > Decimal::ZERO),
+        "low_24h" => Some(status.low_24h).filter(|p| *p > Decimal::ZERO),
+        "rsi" => status.rsi,
+        "sma_short" => status.sma_short,
+        "sma_long" => status.sma_long,
+        "volume_ratio" => status.volume_ratio,
+        "support" => status.support,
+        "strong_support" => status.strong_support,
+        "resistance" => status.resistance,
+        "strong_resistance" => status.strong_resistance,
+        "pivot" => status.pivot_point,
+        "stop_loss" => status.stop_loss_price,
+        "take_profit" => status.take_profit_price,
+        "buy_target" => status.buy_target_price,
+        "sell_target" => status.sell_target_price,
+        "ai_confidence" => status.ai_confidence,
+        "position_size" => Some(status.position_size),
+        // Only meaningful while a position is open
+        "unrealized_pnl" => has_position.then_some(status.unrealized_pnl),
+        "unrealized_pnl_percent" => has_position.then_some(status.unrealized_pnl_percent),
+        "realized_pnl" => Some(status.realized_pnl),
+        "day_pnl" => Some(status.day_realized_pnl),
+        "portfolio_value" => Some(status.total_portfolio_value),
+        "trades_today" => Some(Decimal::from(status.trades_today)),
+        _ => None,
+    }
+}
+
+/// A number as people write them in rules: `-200`, `$200`, `65k`, `65,000`, `3%`
+pub fn parse_amount(text: &str) -> Option<Decimal> {
+    let text = text.trim().replace([',', '_'], "");
+    let (sign, text) = match text.strip_prefix('-') {
+        Some(rest) => (-Decimal::ONE, rest),
+        None => (Decimal::ONE, text.as_str()),
+    };
+    let text = text.trim_start_matches('$').trim_end_matches('%');
+    let (text, multiplier) = match text.strip_suffix(['k', 'K']) {
+        Some(rest) => (rest, dec!(1000)),
+        None => (text, Decimal::ONE),
+    };
+    text.parse::<Decimal>().ok().map(|v| sign * v * multiplier)
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Op {
+    Lt,
+    Le,
+    Gt,
+    Ge,
+    Eq,
+    Ne,
+}
+
+impl Op {
+    /// Longest first, so `<=` isn't read as `<`
+    const SYMBOLS: [(&str, Op); 6] = [
+        ("<=", Op::Le), (">=", Op::Ge), ("==", Op::Eq), ("!=", Op::Ne), ("<", Op::Lt), (">", Op::Gt),
+    ];
+
+    fn holds(self, left: Decimal, right: Decimal) -> bool {
+        match self {
+            Op::Lt => left < right,
+            Op::Le => left <= right,
+            Op::Gt => left > right,
+            Op::Ge => left >= right,
+            Op::Eq => left == right,
+            Op::Ne => left != right,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+enum Operand {
+    Variable(&'static str),
+    Value(Decimal),
+}
+
+impl Operand {
+    fn parse(text: &str) -> Option<Self> {
+        let name = text.trim().to_lowercase();
+        VARIABLES.iter().find(|v| **v == name).map(|v| Operand::Variable(v))
+            .or_else(|| parse_amount(&name).map(Operand::Value))
+    }
+
+    fn value(&self, status: &PortfolioStatus) -> Option<Decimal> {
+        match self {
+            Operand::Variable(name) => variable(status, name),
+            Operand::Value(value) => Some(*value),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+struct Clause {
+    left: Operand,
+    op: Op,
+    right: Operand,
+}
+
+impl Clause {
+    fn parse(text: &str) -> Option<Self> {
+        let (symbol, op) = Op::SYMBOLS.iter().find(|(symbol, _)| text.contains(symbol))?;
+        let (left, right) = text.split_once(symbol)?;
+        Some(Self { left: Operand::parse(left)?, op: *op, right: Operand::parse(right)? })
+    }
+
+    /// False while either side has no value yet (no RSI before enough candles, no P&L while flat)
+    fn holds(&self, status: &PortfolioStatus) -> bool {
+        match (self.left.value(status), self.right.value(status)) {
+            (Some(left), Some(right)) => self.op.holds(left, right),
+            _ => false,
+        }
+    }
+}
+
+/// One user-defined alert: `name: condition`, the condition being comparisons joined by
+/// AND and OR (AND binds tighter). Fires when the condition becomes true, then rearms once
+/// it is false again, so a lasting condition alerts once.
+#[derive(Debug, Clone)]
+pub struct AlertRule {
+    pub name: String,
+    pub condition: String,
+    /// Any of these groups, each of which needs all its clauses
+    any_of: Vec<Vec<Clause>>,
+    armed: bool,
+}
+
+impl AlertRule {
+    pub fn parse(spec: &str) -> Result<Self> {
+        let (name, condition) = match spec.split_once(':') {
+            Some((name, condition)) => (name.trim(), condition.trim()),
+            None => (spec.trim(), spec.trim()),
+        };
+        let invalid = |why: String| BotError::config(format!("Invalid alert rule '{}': {}", spec.trim(), why));
+        let tokens: Vec<&str> = condition.split_whitespace().collect();
+        let mut any_of = Vec::new();
+        for group in tokens.split(|t| t.eq_ignore_ascii_case("OR")) {
+            let mut all_of = Vec::new();
+            for clause in group.split(|t| t.eq_ignore_ascii_case("AND")) {
+                let text = clause.join(" ");
+                let clause = Clause::parse(&text).ok_or_else(|| invalid(format!(
+                    "'{}' is not a comparison like `rsi < 25` (values: {})", text, VARIABLES.join(", "))))?;
+                all_of.push(clause);
+            }
+            any_of.push(all_of);
+        }
+        if name.is_empty() || condition.is_empty() {
+            return Err(invalid("expected `name: condition`".to_string()).into());
+        }
+        Ok(Self { name: name.to_string(), condition: condition.to_string(), any_of, armed: true })
+    }
+
+    pub fn matches(&self, status: &PortfolioStatus) -> bool {
+        self.any_of.iter().any(|all_of| all_of.iter().all(|clause| clause.holds(status)))
+    }
+
+    /// Whether the rule fires now; updates the arming
+    fn triggers(&mut self, status: &PortfolioStatus) -> bool {
+        let matches = self.matches(status);
+        let fires = matches && self.armed;
+        self.armed = !matches;
+        fires
+    }
+
+    /// The variables the rule reads, with their current values
+    fn readings(&self, status: &PortfolioStatus) -> String {
+        let mut names: Vec<&str> = Vec::new();
+        for clause in self.any_of.iter().flatten() {
+            for operand in [&clause.left, &clause.right] {
+                if let Operand::Variable(name) = operand
+                    && !names.contains(name) {
+                    names.push(name);
+                }
+            }
+        }
+        names.iter()
+            .map(|name| format!("{} {}", name, variable(status, name).map_or("n/a".to_string(), |v| v.round_dp(2).to_string())))
+            .collect::<Vec<_>>()
+            .join(", ")
+    }
+}
+
+/// Which side of a watched level the price was last seen on, outside the hysteresis band
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Side {
+    Above,
+    Below,
+}
+
+#[derive(Debug, Clone)]
+struct WatchedLevel {
+    price: Decimal,
+    side: Option<Side>,
+}
+
+/// Price levels from PRICE_ALERTS and the control API, watched for crossings. Only notifies,
+/// never trades. Cheap to clone; clones share the levels, so the API can change them while
+/// the loop runs.
+#[derive(Debug, Clone, Default)]
+pub struct PriceWatchlist {
+    levels: Arc<Mutex<Vec<WatchedLevel>>>,
+    /// Distance past a level, in percent of it, before a crossing counts
+    hysteresis_percent: Decimal,
+}
+
+impl PriceWatchlist {
+    pub fn from_config(config: &Config) -> Result<Self> {
+        if config.price_alert_hysteresis_percent < Decimal::ZERO || config.price_alert_hysteresis_percent >= dec!(10) {
+            return Err(BotError::config(format!("PRICE_ALERT_HYSTERESIS_PERCENT must be between 0 and 10, got {}",
+                config.price_alert_hysteresis_percent)).into());
+        }
+        let watchlist = Self { hysteresis_percent: config.price_alert_hysteresis_percent, ..Self::default() };
+        for level in &config.price_alerts {
+            match parse_amount(level).filter(|p| *p > Decimal::ZERO) {
+                Some(price) => { watchlist.add(price); }
+                None => return Err(BotError::config(format!("Invalid PRICE_ALERTS level '{}' (expected a price, e.g. 65000 or 65k)", level)).into()),
+            }
+        }
+        Ok(watchlist)
+    }
+
+    /// Watch `price`; false when it already is
+    pub fn add(&self, price: Decimal) -> bool {
+        let mut levels = self.levels.lock().unwrap();
+        if levels.iter().any(|l| l.price == price) {
+            return false;
+        }
+        levels.push(WatchedLevel { price, side: None });
+        levels.sort_by_key(|l| l.price);
+        true
+    }
+
+    /// Stop watching `price`; false when it wasn't
+    pub fn remove(&self, price: Decimal) -> bool {
+        let mut levels = self.levels.lock().unwrap();
+        let before = levels.len();
+        levels.retain(|l| l.price != price);
+        levels.len() < before
+    }
+
+    pub fn levels(&self) -> Vec<Decimal> {
+        self.levels.lock().unwrap().iter().map(|l| l.price).collect()
+    }
+
+    /// Levels `price` has crossed since the last call, with the side it's now on. A level's
+    /// first sighting only records the side, and the price must clear the hysteresis band so
+    /// a price hovering on the level doesn't alert every cycle.
+    pub fn crossings(&self, price: Decimal) -> Vec<(Decimal, Side)> {
+        let mut crossed = Vec::new();
+        for level in self.levels.lock().unwrap().iter_mut() {
+            let band = level.price * self.hysteresis_percent / dec!(100);
+            let side = if price >= level.price + band {
+                Side::Above
+            } else if price <= level.price - band {
+                Side::Below
+            } else {
+                continue;
+            };
+            if level.side.is_some_and(|was| was != side) {
+                crossed.push((level.price, side));
+            }
+            level.side = Some(side);
+        }
+        crossed
+    }
+
+    /// Control endpoints, served next to the metrics: `GET /alerts/levels` lists the levels,
+    /// `POST /alerts/levels` with a price as the body adds one, `DELETE /alerts/levels/{price}` removes it
+    pub fn routes(&self) -> Router {
+        let (list, add, remove) = (self.clone(), self.clone(), self.clone());
+        Router::new()
+            .route("/alerts/levels", get(move || {
+                let watchlist = list.clone();
+                async move { watchlist.levels().iter().map(|p| format!("{}\n", p)).collect::<String>() }
+            }).post(move |body: String| {
+                let watchlist = add.clone();
+                async move {
+                    match parse_amount(&body).filter(|p| *p > Decimal::ZERO) {
+                        Some(price) if watchlist.add(price) => {
+                            info!("📍 Watching price level {}", price);
+                            (StatusCode::CREATED, format!("Watching {}\n", price))
+                        }
+                        Some(price) => (StatusCode::OK, format!("Already watching {}\n", price)),
+                        None => (StatusCode::BAD_REQUEST, "Expected a price, e.g. 65000 or 65k\n".to_string()),
+                    }
+                }
+            }))
+            .route("/alerts/levels/{price}", delete(move |UrlPath(price): UrlPath<String>| {
+                let watchlist = remove.clone();
+                async move {
+                    match parse_amount(&price) {
+                        Some(price) if watchlist.remove(price) => {
+                            info!("📍 Stopped watching price level {}", price);
+                            StatusCode::NO_CONTENT
+                        }
+                        _ => StatusCode::NOT_FOUND,
+                    }
+                }
+            }))
+    }
+}
+
+/// A window length as written in MOVE_ALERTS: `90s`, `15m`, `1h`
+fn parse_window(text: &str) -> Option<Duration> {
+    let text = text.trim();
+    let (amount, unit) = text.split_at(text.find(|c: char| !c.is_ascii_digit())?);
+    let amount: i64 = amount.parse().ok().filter(|a| *a > 0)?;
+    match unit {
+        "s" => Some(Duration::seconds(amount)),
+        "m" => Some(Duration::minutes(amount)),
+        "h" => Some(Duration::hours(amount)),
+        _ => None,
+    }
+}
+
+/// A rapid-move alert from MOVE_ALERTS, e.g. `3%/15m`: the price moving at least `percent`
+/// either way within `window`
+#[derive(Debug, Clone)]
+struct MoveRule {
+    label: String,
+    percent: Decimal,
+    window: Duration,
+    armed: bool,
+}
+
+impl MoveRule {
+    fn parse(spec: &str) -> Result<Self> {
+        let parsed = spec.split_once('/').and_then(|(percent, window)| Some((
+            parse_amount(percent).filter(|p| *p > Decimal::ZERO && *p < dec!(100))?,
+            parse_window(window)?,
+        )));
+        let Some((percent, window)) = parsed else {
+            return Err(BotError::config(format!(
+                "Invalid MOVE_ALERTS entry '{}' (expected percent/window, e.g. 3%/15m)", spec)).into());
+        };
+        Ok(Self { label: spec.trim().to_string(), percent, window, armed: true })
+    }
+}
+
+/// Rapid moves found in the per-cycle price snapshots. Moves are measured from the lowest and
+/// highest price in each window, so a drop that started before the window's first snapshot
+/// still counts once it is deep enough.
+#[derive(Debug, Clone, Default)]
+pub struct MoveWatch {
+    rules: Vec<MoveRule>,
+    /// One price per cycle, as long as the longest window
+    snapshots: VecDeque<(DateTime<Utc>, Decimal)>,
+}
+
+impl MoveWatch {
+    pub fn from_config(config: &Config) -> Result<Self> {
+        let rules = config.move_alerts.iter().map(|spec| MoveRule::parse(spec)).collect::<Result<Vec<_>>>()?;
+        Ok(Self { rules, snapshots: VecDeque::new() })
+    }
+
+    /// Record `price` and return the moves that just reached a threshold, as (rule, percent
+    /// change, price it moved from). A rule rearms once its window no longer holds such a move.
+    pub fn record(&mut self, now: DateTime<Utc>, price: Decimal) -> Vec<(String, Decimal, Decimal)> {
+        let Some(longest) = self.rules.iter().map(|r| r.window).max() else {
+            return Vec::new();
+        };
+        self.snapshots.push_back((now, price));
+        while self.snapshots.front().is_some_and(|(t, _)| now - *t > longest) {
+            self.snapshots.pop_front();
+        }
+        let mut moves = Vec::new();
+        for rule in &mut self.rules {
+            let window = self.snapshots.iter().filter(|(t, _)| now - *t <= rule.window).map(|(_, p)| *p);
+            let (low, high) = window.fold((price, price), |(low, high), p| (low.min(p), high.max(p)));
+            let rise = (price - low) / low * dec!(100);
+            let fall = (price - high) / high * dec!(100);
+            let (change, from) = if rise >= -fall { (rise, low) } else { (fall, high) };
+            let moving = change.abs() >= rule.percent;
+            if moving && rule.armed {
+                moves.push((rule.label.clone(), change, from));
+            }
+            rule.armed = !moving;
+        }
+        moves
+    }
+}
+
+/// Evaluates the ALERT_RULES and watched price levels each cycle against the portfolio status
+/// and sends what fires to every notification sink: the log, the report's alerts, the audit
+/// log and Telegram
+pub struct AlertEngine {
+    rules: Vec<AlertRule>,
+    watchlist: PriceWatchlist,
+    moves: MoveWatch,
+    audit: AuditLog,
+    telegram: Option<TelegramNotifier>,
+}
+
+impl AlertEngine {
+    pub fn from_config(config: &Config, audit: &AuditLog, watchlist: PriceWatchlist) -> Result<Self> {
+        let rules = config.alert_rules.iter().map(|spec| AlertRule::parse(spec)).collect::<Result<Vec<_>>>()?;
+        let telegram = match (&config.telegram_bot_token, &config.telegram_chat_id) {
+            (Some(token), Some(chat)) => Some(TelegramNotifier::new(token.expose(), chat)?),
+            _ => None,
+        };
+        Ok(Self { rules, watchlist, moves: MoveWatch::from_config(config)?, audit: audit.clone(), telegram })
+    }
+
+    /// What fires on `status`, as (rule, message)
+    pub fn evaluate(&mut self, status: &PortfolioStatus, now: DateTime<Utc>) -> Vec<(String, String)> {
+        let mut alerts: Vec<(String, String)> = self.rules.iter_mut()
+            .filter_map(|rule| rule.triggers(status).then(|| {
+                let message = format!("🔔 ALERT {}: {} ({})", rule.name, rule.condition, rule.readings(status));
+                (rule.name.clone(), message)
+            }))
+            .collect();
+        if status.current_price > Decimal::ZERO {
+            for (level, side) in self.watchlist.crossings(status.current_price) {
+                let direction = if side == Side::Above { "above" } else { "below" };
+                alerts.push((format!("price {}", level), format!("📍 PRICE ALERT {} crossed {} {} (now {})",
+                    status.symbol, direction, level, status.current_price.round_dp(2))));
+            }
+            for (rule, change, from) in self.moves.record(now, status.current_price) {
+                let mut message = format!("⚡ MOVE ALERT {} {:+}% within {} ({} -> {})", status.symbol,
+                    change.round_dp(2), rule, from.round_dp(2), status.current_price.round_dp(2));
+                // A fast fall can gap through the stop between two checks
+                if status.position_size > Decimal::ZERO
+                    && let Some(stop) = status.stop_loss_price {
+                    let distance = (status.current_price - stop) / status.current_price * dec!(100);
+                    message.push_str(&format!(", stop-loss {} is {}% away", stop.round_dp(2), distance.round_dp(2)));
+                }
+                alerts.push((format!("move {}", rule), message));
+            }
+        }
+        alerts
+    }
+
+    /// Evaluate the rules and notify every sink of the ones that fire
+    pub async fn check(&mut self, reporter: &mut PortfolioReporter) {
+        for (rule, message) in self.evaluate(reporter.status(), Utc::now()) {
+            warn!("{}", message);
+            self.raise(reporter, rule, message).await;
+        }
+    }
+
+    /// Send an alert raised by `rule` to the report, the audit log and Telegram; the caller logs it
+    pub async fn raise(&self, reporter: &mut PortfolioReporter, rule: String, message: String) {
+        reporter.raise_alert(message.clone());
+        self.audit.record(AuditEvent::Alert { symbol: reporter.status().symbol.clone(), rule, message: message.clone() });
+        if let Some(telegram) = &self.telegram
+            && let Err(e) = telegram.send_message(&message).await {
+            warn!("⚠️ Failed to send the alert: {}", e);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_rules_parse_and_fire_once() {
+        let mut status = PortfolioStatus::new("BTCUSDT", true);
+        status.current_price = dec!(58900);
+        status.rsi = Some(dec!(23));
+        status.support = Some(dec!(59000));
+
+        let mut rule = AlertRule::parse("oversold: RSI < 25 and price < support OR unrealized_pnl < -$200").unwrap();
+        assert!(rule.triggers(&status));
+        assert!(!rule.triggers(&status), "a lasting condition alerts once");
+        status.rsi = Some(dec!(30));
+        assert!(!rule.triggers(&status));
+        status.rsi = Some(dec!(24));
+        assert!(rule.triggers(&status), "rearmed once the condition was false");
+        assert_eq!(rule.readings(&status), "rsi 24, price 58900, support 59000, unrealized_pnl n/a");
+
+        // P&L only counts while a position is open
+        status.rsi = None;
+        status.unrealized_pnl = dec!(-250);
+        assert!(!rule.matches(&status));
+        status.position_size = dec!(0.1);
+        assert!(rule.matches(&status));
+
+        assert_eq!(parse_amount("65k"), Some(dec!(65000)));
+        assert_eq!(parse_amount("-$1,250.5"), Some(dec!(-1250.5)));
+        assert!(AlertRule::parse("price crosses 65k").is_err());
+        assert!(AlertRule::parse("x: rsi < 25 AND").is_err());
+        assert!(AlertRule::parse("x: funding < 0").unwrap_err().to_string().contains("unrealized_pnl"));
+    }
+
+    #[test]
+    fn test_price_levels_cross_with_hysteresis() {
+        let watchlist = PriceWatchlist { hysteresis_percent: dec!(0.5), ..Default::default() };
+        assert!(watchlist.add(dec!(65000)));
+        assert!(!watchlist.add(dec!(65000)));
+
+        // The first price only records the side
+        assert!(watchlist.crossings(dec!(64000)).is_empty());
+        // Within 0.5% of the level: not yet
+        assert!(watchlist.crossings(dec!(65200)).is_empty());
+        assert_eq!(watchlist.crossings(dec!(65400)), vec![(dec!(65000), Side::Above)]);
+        // Dipping back into the band and out above again isn't a crossing
+        assert!(watchlist.crossings(dec!(64900)).is_empty());
+        assert!(watchlist.crossings(dec!(65500)).is_empty());
+        assert_eq!(watchlist.crossings(dec!(64600)), vec![(dec!(65000), Side::Below)]);
+
+        // Clones share the levels, as the control API's does
+        assert!(watchlist.clone().remove(dec!(65000)));
+        assert!(watchlist.levels().is_empty());
+    }
+
+    #[test]
+    fn test_rapid_moves_alert_once_per_window() {
+        let mut watch = MoveWatch { rules: vec![MoveRule::parse("3%/15m").unwrap()], ..Default::default() };
+        let start = Utc::now();
+        let at = |minutes: i64| start + Duration::minutes(minutes);
+        assert!(watch.record(at(0), dec!(100)).is_empty());
+        assert!(watch.record(at(5), dec!(101)).is_empty());
+        // 101 -> 98 is -2.97%, 101 -> 97.9 is past -3%
+        assert!(watch.record(at(10), dec!(98)).is_empty());
+        let moves = watch.record(at(12), dec!(97.9));
+        assert_eq!(moves.len(), 1);
+        assert_eq!((moves[0].1.round_dp(2), moves[0].2), (dec!(-3.07), dec!(101)));
+        assert!(watch.record(at(14), dec!(97)).is_empty(), "still the same move");
+        // The 101 high has left the window; a rebound from the 97 low alerts again
+        assert!(watch.record(at(25), dec!(97.5)).is_empty());
+        assert_eq!(watch.record(at(26), dec!(100))[0].1.round_dp(2), dec!(3.09));
+
+        assert_eq!(parse_window("90s"), Some(Duration::seconds(90)));
+        assert!(MoveRule::parse("3%").is_err());
+        assert!(MoveRule::parse("3%/15x").is_err());
+    }
+}
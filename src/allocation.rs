@@ -0,0 +1,315 @@
+use crate::config::Config;
+use crate::error::BotError;
+use crate::models::{OrderSide, Signal};
+use crate::runtime_state::{push_equity_point, EquityPoint};
+use crate::state_store::StateStore;
+use crate::strategy::{RsiStrategy, SmaCrossover};
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+use rust_decimal::Decimal;
+use rust_decimal_macros::dec;
+use serde::{Deserialize, Serialize};
+use tracing::{info, warn};
+
+/// Share of its cash the AI sleeve commits per entry, as the single-strategy bot does
+/// with the whole balance
+const AI_ENTRY_FRACTION: Decimal = dec!(0.10);
+const RSI_OVERSOLD: Decimal = dec!(30);
+const RSI_OVERBOUGHT: Decimal = dec!(70);
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SleeveStrategy {
+    /// The AI/fallback target logic of the main loop
+    AiTargets,
+    /// Long while SMA(10) is above SMA(20) on hourly closes
+    SmaCrossover,
+    /// Buy below RSI(14) 30, sell above 70
+    Rsi,
+}
+
+impl SleeveStrategy {
+    fn parse(name: &str) -> Option<Self> {
+        match name.trim().to_lowercase().as_str() {
+            "ai" | "ai_targets" => Some(Self::AiTargets),
+            "sma" | "sma_crossover" => Some(Self::SmaCrossover),
+            "rsi" => Some(Self::Rsi),
+            _ => None,
+        }
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::AiTargets => "AI targets",
+            Self::SmaCrossover => "SMA crossover",
+            Self::Rsi => "RSI",
+        }
+    }
+
+    /// Signal from hourly closes; the AI sleeve is driven by targets instead
+    fn signal(self, closes: &[Decimal]) -> Signal {
+        match self {
+            Self::AiTargets => Signal::Hold,
+            Self::SmaCrossover => match (SmaCrossover::calculate_sma(closes, 10), SmaCrossover::calculate_sma(closes, 20)) {
+                (Some(short), Some(long)) if short > long => Signal::Buy,
+                (Some(short), Some(long)) if short < long => Signal::Sell,
+                _ => Signal::Hold,
+            },
+            Self::Rsi => match RsiStrategy::calculate_rsi(closes, 14) {
+                Some(rsi) if rsi < RSI_OVERSOLD => Signal::Buy,
+                Some(rsi) if rsi > RSI_OVERBOUGHT => Signal::Sell,
+                _ => Signal::Hold,
+            },
+        }
+    }
+}
+
+/// A strategy's virtual sub-portfolio: its share of the capital, the position it holds
+/// and its own equity curve
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Sleeve {
+    pub strategy: SleeveStrategy,
+    pub weight_percent: Decimal,
+    pub allocated: Decimal,
+    pub cash: Decimal,
+    pub qty: Decimal,
+    pub entry_price: Option<Decimal>,
+    pub realized_pnl: Decimal,
+    pub trades: u32,
+    /// Commissions paid to open the current position
+    #[serde(default)]
+    pub entry_fees: Decimal,
+    #[serde(default)]
+    pub equity_history: Vec<EquityPoint>,
+}
+
+impl Sleeve {
+    pub fn equity(&self, price: Decimal) -> Decimal {
+        self.cash + self.qty * price
+    }
+}
+
+/// One line of the report's strategy section
+#[derive(Debug, Clone, PartialEq)]
+pub struct StrategySummary {
+    pub name: &'static str,
+    pub weight_percent: Decimal,
+    pub equity: Decimal,
+    pub return_percent: Decimal,
+    /// Equity change over the last 24 hourly points
+    pub change_24h_percent: Option<Decimal>,
+    pub realized_pnl: Decimal,
+    pub position_qty: Decimal,
+    pub trades: u32,
+}
+
+/// Several strategies trading one symbol in one process, each with its own capital.
+/// Saved to the state backend so sleeves survive restarts.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct StrategyBook {
+    sleeves: Vec<Sleeve>,
+}
+
+impl StrategyBook {
+    /// A freshly funded book, or `None` without `STRATEGY_ALLOCATION`
+    pub fn from_config(config: &Config, capital: Decimal) -> Result<Option<Self>> {
+        let allocation = parse_allocation(&config.strategy_allocation)?;
+        Ok((!allocation.is_empty()).then(|| Self::funded(&allocation, capital)))
+    }
+
+    /// Like [`from_config`](Self::from_config), but resumed from `store` when the
+    /// allocation is unchanged
+    pub fn load(config: &Config, store: &StateStore, capital: Decimal) -> Result<Option<Self>> {
+        let allocation = parse_allocation(&config.strategy_allocation)?;
+        if allocation.is_empty() {
+            return Ok(None);
+        }
+
+        let saved = store.read()
+            .and_then(|content| Ok(content.map(|c| serde_json::from_str::<StrategyBook>(&c)).transpose()?))
+            .unwrap_or_else(|e| {
+                warn!("⚠️ Failed to read strategy allocation state: {}", e);
+                None
+            });
+        if let Some(book) = saved {
+            let unchanged = book.sleeves.len() == allocation.len()
+                && book.sleeves.iter().zip(&allocation).all(|(s, (strategy, weight))| s.strategy == *strategy && s.weight_percent == *weight);
+            if unchanged {
+                info!("♻️ Resuming {} strategy sleeves", book.sleeves.len());
+                return Ok(Some(book));
+            }
+            warn!("⚠️ STRATEGY_ALLOCATION changed - re-funding sleeves from the current balance");
+        }
+        Ok(Some(Self::funded(&allocation, capital)))
+    }
+
+    fn funded(allocation: &[(SleeveStrategy, Decimal)], capital: Decimal) -> Self {
+        let sleeves = allocation.iter().map(|&(strategy, weight_percent)| {
+            let allocated = (capital * weight_percent / dec!(100)).round_dp(8);
+            info!("🧩 {} sleeve: {}% = {}", strategy.label(), weight_percent, allocated);
+            Sleeve {
+                strategy,
+                weight_percent,
+                allocated,
+                cash: allocated,
+                qty: Decimal::ZERO,
+                entry_price: None,
+                realized_pnl: Decimal::ZERO,
+                trades: 0,
+                entry_fees: Decimal::ZERO,
+                equity_history: Vec::new(),
+            }
+        }).collect();
+        Self { sleeves }
+    }
+
+    pub fn save(&self, store: &StateStore) {
+        if let Ok(json) = serde_json::to_string_pretty(self)
+            && let Err(e) = store.write(&json) {
+            warn!("Failed to save strategy allocation state: {}", e);
+        }
+    }
+
+    pub fn sleeves(&self) -> &[Sleeve] {
+        &self.sleeves
+    }
+
+    fn sleeve_mut(&mut self, strategy: SleeveStrategy) -> Option<&mut Sleeve> {
+        self.sleeves.iter_mut().find(|s| s.strategy == strategy)
+    }
+
+    /// Quote amount the AI target logic may spend on its next entry (zero without an AI sleeve)
+    pub fn ai_entry_budget(&self) -> Decimal {
+        self.sleeves.iter()
+            .find(|s| s.strategy == SleeveStrategy::AiTargets)
+            .map_or(Decimal::ZERO, |s| s.cash * AI_ENTRY_FRACTION)
+    }
+
+    /// Orders the signal-driven sleeves want at `price`: a flat sleeve buys with all its
+    /// cash on a buy signal, a holding sleeve sells everything on a sell signal
+    pub fn pending_orders(&self, closes: &[Decimal], price: Decimal) -> Vec<(SleeveStrategy, OrderSide, Decimal)> {
+        if price <= Decimal::ZERO {
+            return Vec::new();
+        }
+        self.sleeves.iter().filter_map(|s| match s.strategy.signal(closes) {
+            Signal::Buy if s.qty.is_zero() && s.cash > Decimal::ZERO => Some((s.strategy, OrderSide::Buy, s.cash / price)),
+            Signal::Sell if s.qty > Decimal::ZERO => Some((s.strategy, OrderSide::Sell, s.qty)),
+            _ => None,
+        }).collect()
+    }
+
+    pub fn record_entry(&mut self, strategy: SleeveStrategy, price: Decimal, qty: Decimal, fee: Decimal) {
+        if let Some(s) = self.sleeve_mut(strategy) {
+            let cost = qty * price;
+            let held = s.qty;
+            s.entry_price = Some(match s.entry_price {
+                Some(entry) if !held.is_zero() => (entry * held + cost) / (held + qty),
+                _ => price,
+            });
+            s.cash -= cost + fee;
+            s.entry_fees += fee;
+            s.qty += qty;
+            s.trades += 1;
+        }
+    }
+
+    /// Close the sleeve's position at `price`; returns the realized P&L net of entry and exit fees
+    pub fn record_exit(&mut self, strategy: SleeveStrategy, price: Decimal, fee: Decimal) -> Decimal {
+        let Some(s) = self.sleeve_mut(strategy) else {
+            return Decimal::ZERO;
+        };
+        let pnl = (price - s.entry_price.unwrap_or(price)) * s.qty - s.entry_fees - fee;
+        s.cash += s.qty * price - fee;
+        s.realized_pnl += pnl;
+        s.qty = Decimal::ZERO;
+        s.entry_price = None;
+        s.entry_fees = Decimal::ZERO;
+        s.trades += 1;
+        pnl
+    }
+
+    /// Extend each sleeve's hourly equity curve
+    pub fn mark(&mut self, price: Decimal, now: DateTime<Utc>) {
+        for sleeve in &mut self.sleeves {
+            let equity = sleeve.equity(price);
+            push_equity_point(&mut sleeve.equity_history, equity, now);
+        }
+    }
+
+    pub fn summaries(&self, price: Decimal) -> Vec<StrategySummary> {
+        self.sleeves.iter().map(|s| {
+            let equity = s.equity(price);
+            let percent_change = |from: Decimal| (from > Decimal::ZERO).then(|| (equity - from) / from * dec!(100));
+            let day_ago = s.equity_history.iter().rev().nth(23).map(|p| p.value);
+            StrategySummary {
+                name: s.strategy.label(),
+                weight_percent: s.weight_percent,
+                equity,
+                return_percent: percent_change(s.allocated).unwrap_or(Decimal::ZERO),
+                change_24h_percent: day_ago.and_then(percent_change),
+                realized_pnl: s.realized_pnl,
+                position_qty: s.qty,
+                trades: s.trades,
+            }
+        }).collect()
+    }
+}
+
+/// `ai:60,sma:40` -> strategies and weights in percent
+fn parse_allocation(entries: &[String]) -> Result<Vec<(SleeveStrategy, Decimal)>> {
+    let mut allocation: Vec<(SleeveStrategy, Decimal)> = Vec::new();
+    for entry in entries {
+        let invalid = || BotError::config(format!(
+            "Invalid STRATEGY_ALLOCATION entry '{}' (expected <ai|sma|rsi>:<percent>)", entry));
+        let (name, weight) = entry.split_once(':').ok_or_else(invalid)?;
+        let strategy = SleeveStrategy::parse(name).ok_or_else(invalid)?;
+        let weight: Decimal = weight.trim().trim_end_matches('%').parse().map_err(|_| invalid())?;
+        if weight <= Decimal::ZERO {
+            return Err(invalid().into());
+        }
+        if allocation.iter().any(|(s, _)| *s == strategy) {
+            return Err(BotError::config(format!("STRATEGY_ALLOCATION lists {} twice", strategy.label())).into());
+        }
+        allocation.push((strategy, weight));
+    }
+    let total: Decimal = allocation.iter().map(|(_, w)| *w).sum();
+    if total > dec!(100) {
+        return Err(BotError::config(format!("STRATEGY_ALLOCATION adds up to {}% (max 100%)", total)).into());
+    }
+    Ok(allocation)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sleeves_trade_their_own_capital() {
+        let allocation = parse_allocation(&["ai:60".to_string(), "sma:40".to_string()]).unwrap();
+        assert!(parse_allocation(&["ai:70".to_string(), "sma:40".to_string()]).is_err());
+        assert!(parse_allocation(&["macd:10".to_string()]).is_err());
+
+        let mut book = StrategyBook::funded(&allocation, dec!(10000));
+        assert_eq!(book.ai_entry_budget(), dec!(600));
+
+        // Rising closes: the SMA sleeve buys with its whole 40%
+        let rising: Vec<Decimal> = (0..24).map(|i| Decimal::from(100 + i)).collect();
+        let orders = book.pending_orders(&rising, dec!(125));
+        assert_eq!(orders, vec![(SleeveStrategy::SmaCrossover, OrderSide::Buy, dec!(32))]);
+        book.record_entry(SleeveStrategy::SmaCrossover, dec!(125), dec!(32), dec!(4));
+        book.record_entry(SleeveStrategy::AiTargets, dec!(125), dec!(4), dec!(0));
+        assert!(book.pending_orders(&rising, dec!(125)).is_empty());
+
+        // Falling closes: only the SMA sleeve exits; the AI sleeve's position is untouched.
+        // Its P&L is net of both commissions.
+        let falling: Vec<Decimal> = rising.iter().rev().copied().collect();
+        assert_eq!(book.pending_orders(&falling, dec!(150)), vec![(SleeveStrategy::SmaCrossover, OrderSide::Sell, dec!(32))]);
+        assert_eq!(book.record_exit(SleeveStrategy::SmaCrossover, dec!(150), dec!(4.8)), dec!(791.2));
+
+        let summaries = book.summaries(dec!(150));
+        assert_eq!(summaries[0].equity, dec!(6100));
+        assert_eq!(summaries[1].equity, dec!(4791.2));
+        assert_eq!(summaries[1].return_percent, dec!(19.78));
+        assert_eq!(summaries[1].trades, 2);
+    }
+}
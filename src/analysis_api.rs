@@ -0,0 +1,189 @@
+use crate::ai_advisor::{MarketContext, TradingRecommendation};
+use crate::divergence::Divergence;
+use crate::models::Signal;
+use crate::portfolio::PortfolioStatus;
+use axum::http::{header, StatusCode};
+use axum::response::{IntoResponse, Response};
+use axum::routing::get;
+use axum::Router;
+use chrono::{DateTime, Utc};
+use rust_decimal::Decimal;
+use serde::Serialize;
+use std::sync::{Arc, Mutex};
+
+/// Current targets and support/resistance levels, as served at `GET /targets`
+#[derive(Debug, Clone, Serialize)]
+pub struct TargetsView {
+    pub symbol: String,
+    pub updated_at: DateTime<Utc>,
+    pub recommendation: Option<TradingRecommendation>,
+    pub confidence: Option<Decimal>,
+    pub reasoning: Option<String>,
+    pub stop_loss: Option<Decimal>,
+    pub take_profit: Option<Decimal>,
+    pub buy_target: Option<Decimal>,
+    pub sell_target: Option<Decimal>,
+    pub support: Option<Decimal>,
+    pub strong_support: Option<Decimal>,
+    pub resistance: Option<Decimal>,
+    pub strong_resistance: Option<Decimal>,
+    pub pivot_point: Option<Decimal>,
+}
+
+/// Indicator readings and whether entries are paused, as served at `GET /signals`
+#[derive(Debug, Clone, Serialize)]
+pub struct SignalsView {
+    pub symbol: String,
+    pub updated_at: DateTime<Utc>,
+    pub price: Decimal,
+    pub signal: Signal,
+    pub sma_short: Option<Decimal>,
+    pub sma_long: Option<Decimal>,
+    pub rsi: Option<Decimal>,
+    pub volume_ratio: Option<Decimal>,
+    pub divergences: Vec<Divergence>,
+    pub entries_paused: Option<String>,
+}
+
+/// The data the last analysis ran on, as served at `GET /market-context`
+#[derive(Debug, Clone, Serialize)]
+struct ContextView {
+    updated_at: DateTime<Utc>,
+    #[serde(flatten)]
+    context: MarketContext,
+}
+
+#[derive(Debug, Default)]
+struct Latest {
+    targets: Option<TargetsView>,
+    signals: Option<SignalsView>,
+    context: Option<ContextView>,
+}
+
+/// The bot's latest analysis, shared with the dashboard server's JSON endpoints so other
+/// systems can act on it. Cheap to clone; clones share the same analysis.
+#[derive(Debug, Clone, Default)]
+pub struct AnalysisFeed {
+    latest: Arc<Mutex<Latest>>,
+}
+
+impl AnalysisFeed {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Take the targets and signals from this cycle's status; targets only once there are any
+    pub fn publish(&self, status: &PortfolioStatus) {
+        let now = Utc::now();
+        let mut latest = self.latest.lock().unwrap();
+        if status.stop_loss_price.is_some() || status.ai_recommendation.is_some() {
+            latest.targets = Some(TargetsView {
+                symbol: status.symbol.clone(),
+                updated_at: now,
+                recommendation: status.ai_recommendation.clone(),
+                confidence: status.ai_confidence,
+                reasoning: status.ai_reasoning.clone(),
+                stop_loss: status.stop_loss_price,
+                take_profit: status.take_profit_price,
+                buy_target: status.buy_target_price,
+                sell_target: status.sell_target_price,
+                support: status.support,
+                strong_support: status.strong_support,
+                resistance: status.resistance,
+                strong_resistance: status.strong_resistance,
+                pivot_point: status.pivot_point,
+            });
+        }
+        latest.signals = Some(SignalsView {
+            symbol: status.symbol.clone(),
+            updated_at: now,
+            price: status.current_price,
+            signal: status.current_signal,
+            sma_short: status.sma_short,
+            sma_long: status.sma_long,
+            rsi: status.rsi,
+            volume_ratio: status.volume_ratio,
+            divergences: status.divergences.clone(),
+            entries_paused: status.entries_paused.clone(),
+        });
+    }
+
+    /// The market context the targets were just calculated from
+    pub fn publish_context(&self, context: &MarketContext) {
+        self.latest.lock().unwrap().context = Some(ContextView { updated_at: Utc::now(), context: context.clone() });
+    }
+
+    pub fn targets(&self) -> Option<TargetsView> {
+        self.latest.lock().unwrap().targets.clone()
+    }
+
+    pub fn signals(&self) -> Option<SignalsView> {
+        self.latest.lock().unwrap().signals.clone()
+    }
+
+    /// Endpoints served next to the metrics: `GET /targets`, `GET /signals` and
+    /// `GET /market-context`, each 404 until the first analysis
+    pub fn routes(&self) -> Router {
+        let (targets, signals, context) = (self.clone(), self.clone(), self.clone());
+        Router::new()
+            .route("/targets", get(move || {
+                let feed = targets.clone();
+                async move { json(feed.targets()) }
+            }))
+            .route("/signals", get(move || {
+                let feed = signals.clone();
+                async move { json(feed.signals()) }
+            }))
+            .route("/market-context", get(move || {
+                let feed = context.clone();
+                async move { json(feed.latest.lock().unwrap().context.clone()) }
+            }))
+    }
+}
+
+fn json<T: Serialize>(value: Option<T>) -> Response {
+    let Some(value) = value else {
+        return (StatusCode::NOT_FOUND, "No analysis yet\n").into_response();
+    };
+    match serde_json::to_string_pretty(&value) {
+        Ok(body) => ([(header::CONTENT_TYPE, "application/json")], body).into_response(),
+        Err(e) => (StatusCode::INTERNAL_SERVER_ERROR, e.to_string()).into_response(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rust_decimal_macros::dec;
+
+    #[test]
+    fn test_feed_serves_latest_analysis() {
+        let feed = AnalysisFeed::new();
+        let response = json(feed.targets());
+        assert_eq!(response.status(), StatusCode::NOT_FOUND);
+
+        let mut status = PortfolioStatus::new("BTCUSDT", true);
+        status.current_price = dec!(64000);
+        status.rsi = Some(dec!(55));
+        status.entries_paused = Some("outside trading sessions".to_string());
+        feed.clone().publish(&status);
+        assert!(feed.targets().is_none());
+        let signals = serde_json::to_value(feed.signals().unwrap()).unwrap();
+        assert_eq!(signals["signal"], "Hold");
+        assert_eq!(signals["rsi"], "55");
+        assert_eq!(signals["entries_paused"], "outside trading sessions");
+
+        status.stop_loss_price = Some(dec!(62000));
+        status.ai_recommendation = Some(TradingRecommendation::Buy);
+        feed.publish(&status);
+        let targets = serde_json::to_value(feed.targets().unwrap()).unwrap();
+        assert_eq!(targets["recommendation"], "Buy");
+        assert_eq!(targets["stop_loss"], "62000");
+        assert_eq!(targets["take_profit"], serde_json::Value::Null);
+
+        feed.publish_context(&MarketContext { symbol: "BTCUSDT".to_string(), current_price: dec!(64000), ..Default::default() });
+        let response = json(feed.latest.lock().unwrap().context.clone());
+        assert_eq!(response.status(), StatusCode::OK);
+        assert_eq!(response.headers()[header::CONTENT_TYPE], "application/json");
+    }
+}
@@ -0,0 +1,242 @@
+use crate::error::BotError;
+use crate::state_crypto::StateCipher;
+use crate::trade_limiter::TradeContext;
+use anyhow::{anyhow, Result};
+use chrono::{DateTime, Utc};
+use rust_decimal::Decimal;
+use serde::Serialize;
+use std::fs::OpenOptions;
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use tokio::sync::broadcast;
+use tracing::{debug, warn};
+
+/// Events that must leave a permanent trace, independent of log verbosity
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "event", rename_all = "snake_case")]
+pub enum AuditEvent {
+    /// A protective exit was executed even though the trade limiter would block it
+    LimitOverride {
+        symbol: String,
+        reason: String,
+        price: Decimal,
+        quantity: Decimal,
+        trades_executed: u32,
+    },
+    /// A deposit (positive amount) or withdrawal (negative) detected from balance changes
+    CapitalFlow {
+        asset: String,
+        amount: Decimal,
+        value: Option<Decimal>,
+    },
+    /// Targets chosen at a recalculation, kept so the weekly review can score them
+    Targets {
+        symbol: String,
+        /// `ai` or `fallback`
+        source: String,
+        price: Decimal,
+        stop_loss: Decimal,
+        take_profit: Decimal,
+        support: Option<Decimal>,
+        resistance: Option<Decimal>,
+        recommendation: String,
+        confidence: Decimal,
+    },
+    /// An executed trade with the decision context behind it
+    Trade {
+        symbol: String,
+        side: String,
+        price: Decimal,
+        quantity: Decimal,
+        position_id: Option<String>,
+        #[serde(flatten)]
+        context: TradeContext,
+    },
+    /// A tracked balance or position size that no longer matched the exchange
+    Drift {
+        subject: String,
+        tracked: Decimal,
+        exchange: Decimal,
+        corrected: bool,
+    },
+    /// An hour trading at a multiple of its trailing average volume
+    VolumeSpike {
+        symbol: String,
+        price: Decimal,
+        volume: Decimal,
+        average: Decimal,
+        ratio: Decimal,
+    },
+    /// The loop went longer than CYCLE_GAP_SECS without a price check; the range traded
+    /// meanwhile and the positions whose stop it crossed
+    CycleGap {
+        symbol: String,
+        from: DateTime<Utc>,
+        to: DateTime<Utc>,
+        low: Option<Decimal>,
+        high: Option<Decimal>,
+        missed_stops: Vec<String>,
+    },
+    /// A user-defined alert rule fired
+    Alert {
+        symbol: String,
+        rule: String,
+        message: String,
+    },
+    /// The model's critique of a closed trade, for the weekly review of recurring mistakes
+    TradeReview {
+        symbol: String,
+        position_id: String,
+        pnl: Decimal,
+        exit_reason: String,
+        critique: String,
+        tags: Vec<String>,
+    },
+}
+
+#[derive(Serialize)]
+struct AuditEntry<'a> {
+    timestamp: DateTime<Utc>,
+    #[serde(flatten)]
+    event: &'a AuditEvent,
+}
+
+/// Events a slow subscriber may fall behind by before it misses some
+const EVENT_BUFFER: usize = 256;
+
+/// Append-only audit trail, one JSON object per line. With a state key configured each
+/// line is the hex-encoded ciphertext of the entry instead; plaintext lines written before
+/// the key was set are still read.
+#[derive(Clone)]
+pub struct AuditLog {
+    path: PathBuf,
+    cipher: Option<StateCipher>,
+    /// Every recorded event, for the MQTT and gRPC event streams
+    events: broadcast::Sender<AuditEvent>,
+}
+
+impl AuditLog {
+    pub fn new(path: &Path) -> Self {
+        Self {
+            path: path.to_path_buf(),
+            cipher: None,
+            events: broadcast::channel(EVENT_BUFFER).0,
+        }
+    }
+
+    /// Encrypt appended entries, and decrypt them on read, with the state key
+    pub fn with_cipher(mut self, cipher: Option<StateCipher>) -> Self {
+        self.cipher = cipher;
+        self
+    }
+
+    /// Sender the recorded events are broadcast on; `subscribe` to it for a stream of them
+    pub fn events(&self) -> broadcast::Sender<AuditEvent> {
+        self.events.clone()
+    }
+
+    /// Append an event. Failures are logged rather than returned so auditing
+    /// never interrupts the trade it describes.
+    pub fn record(&self, event: AuditEvent) {
+        match event {
+            // Routine, every recalculation
+            AuditEvent::Targets { .. } => debug!("📝 AUDIT: {:?}", event),
+            // Already logged by the trade itself
+            AuditEvent::Trade { .. } | AuditEvent::TradeReview { .. } | AuditEvent::Alert { .. } => debug!("📝 AUDIT: {:?}", event),
+            _ => warn!("📝 AUDIT: {:?}", event),
+        }
+        if let Err(e) = self.append(&event) {
+            warn!("Failed to write audit log {}: {}", self.path.display(), e);
+        }
+        // No subscribers is fine
+        let _ = self.events.send(event);
+    }
+
+    fn append(&self, event: &AuditEvent) -> Result<()> {
+        let json = serde_json::to_string(&AuditEntry {
+            timestamp: Utc::now(),
+            event,
+        })?;
+        let line = self.encode(json)?;
+        let mut file = OpenOptions::new().create(true).append(true).open(&self.path)?;
+        writeln!(file, "{}", line)?;
+        file.sync_data()?;
+        Ok(())
+    }
+
+    /// Every entry as its JSON line, decrypted where needed; empty if nothing was logged yet
+    pub fn read_lines(&self) -> Result<Vec<String>> {
+        let content = match std::fs::read_to_string(&self.path) {
+            Ok(content) => content,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+            Err(e) => return Err(BotError::storage(&self.path, e).into()),
+        };
+        content.lines()
+            .filter(|line| !line.is_empty())
+            .enumerate()
+            .map(|(i, line)| self.decode(line).map_err(|e| anyhow!("{} line {}: {}", self.path.display(), i + 1, e)))
+            .collect()
+    }
+
+    /// The log as it would be written now: every entry re-encoded through the current
+    /// cipher, so a copy never holds plaintext the state key should have covered
+    pub fn export(&self) -> Result<Vec<u8>> {
+        let mut out = Vec::new();
+        for json in self.read_lines()? {
+            writeln!(out, "{}", self.encode(json)?)?;
+        }
+        Ok(out)
+    }
+
+    fn encode(&self, json: String) -> Result<String> {
+        match &self.cipher {
+            Some(cipher) => Ok(hex::encode(cipher.encrypt(json.as_bytes())?)),
+            None => Ok(json),
+        }
+    }
+
+    fn decode(&self, line: &str) -> Result<String> {
+        if line.starts_with('{') {
+            return Ok(line.to_string());
+        }
+        let cipher = self.cipher.as_ref()
+            .ok_or_else(|| anyhow!("entry is encrypted; set STATE_PASSPHRASE or STATE_KEY_FILE to read it"))?;
+        let data = hex::decode(line).map_err(|e| anyhow!("not a JSON or encrypted entry: {}", e))?;
+        Ok(String::from_utf8(cipher.decrypt(&data)?)?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rust_decimal_macros::dec;
+
+    #[test]
+    fn test_encrypted_round_trip() {
+        let dir = std::env::temp_dir().join(format!("audit_test_{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("audit.jsonl");
+        // Written before the key was set
+        std::fs::write(&path, "{\"timestamp\":\"2025-03-03T00:00:00Z\",\"event\":\"alert\",\"symbol\":\"BTCUSDT\",\"rule\":\"old\",\"message\":\"kept\"}\n").unwrap();
+
+        let key = b"0123456789abcdef0123456789abcdef";
+        let audit = AuditLog::new(&path).with_cipher(Some(StateCipher::from_key_bytes(key)));
+        audit.record(AuditEvent::CapitalFlow { asset: "USDT".to_string(), amount: dec!(250), value: None });
+        let on_disk = std::fs::read_to_string(&path).unwrap();
+        assert_eq!(on_disk.lines().count(), 2);
+        assert!(!on_disk.contains("capital_flow") && !on_disk.contains("USDT\",\"amount"));
+
+        // Another run, with its own salt, reads both entries
+        let lines = AuditLog::new(&path).with_cipher(Some(StateCipher::from_key_bytes(key))).read_lines().unwrap();
+        assert!(lines[0].contains("\"rule\":\"old\""));
+        assert!(lines[1].contains("\"event\":\"capital_flow\"") && lines[1].contains("\"amount\":\"250\""));
+        assert!(AuditLog::new(&path).read_lines().is_err());
+
+        // An export covers the old plaintext line too
+        let exported = String::from_utf8(audit.export().unwrap()).unwrap();
+        assert!(!exported.contains('{'));
+        std::fs::write(&path, exported).unwrap();
+        assert_eq!(audit.read_lines().unwrap(), lines);
+        std::fs::remove_dir_all(&dir).ok();
+    }
+}
@@ -0,0 +1,313 @@
+use crate::candles::CandleInterval;
+use crate::html_report;
+use crate::models::Kline;
+use crate::optimizer::{Genome, FEE_RATE};
+use chrono::{DateTime, Utc};
+use rust_decimal::Decimal;
+use rust_decimal::prelude::ToPrimitive;
+use std::fmt::Write as _;
+
+/// Columns of the text equity chart
+const CHART_WIDTH: usize = 60;
+const CHART_BLOCKS: [char; 8] = ['▁', '▂', '▃', '▄', '▅', '▆', '▇', '█'];
+
+/// Why a backtested position was closed
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BacktestExit {
+    StopLoss,
+    TakeProfit,
+    Crossover,
+    /// Still held on the last candle, valued at its close
+    Open,
+}
+
+impl BacktestExit {
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::StopLoss => "stop-loss",
+            Self::TakeProfit => "take-profit",
+            Self::Crossover => "crossover",
+            Self::Open => "open",
+        }
+    }
+}
+
+/// One round trip of a backtest
+#[derive(Debug, Clone, PartialEq)]
+pub struct BacktestTrade {
+    pub entry_time: DateTime<Utc>,
+    pub entry_price: f64,
+    pub exit_time: DateTime<Utc>,
+    pub exit_price: f64,
+    pub exit: BacktestExit,
+    /// Net of fees on both sides
+    pub return_percent: f64,
+    /// Maximum adverse excursion: the lowest low while held, below entry (<= 0)
+    pub mae_percent: f64,
+    /// Maximum favorable excursion: the highest high while held, above entry (>= 0)
+    pub mfe_percent: f64,
+    pub candles_held: usize,
+}
+
+impl BacktestTrade {
+    pub fn holding_hours(&self) -> f64 {
+        (self.exit_time - self.entry_time).num_minutes() as f64 / 60.0
+    }
+}
+
+/// A genome replayed over candles trade by trade, with the same rules and fees as the
+/// optimizer's fitness backtest
+#[derive(Debug, Clone)]
+pub struct BacktestReport {
+    pub symbol: String,
+    pub genome: Genome,
+    pub interval: CandleInterval,
+    pub candles: usize,
+    pub start: Option<DateTime<Utc>>,
+    pub end: Option<DateTime<Utc>>,
+    pub trades: Vec<BacktestTrade>,
+    /// Equity after each candle, starting from 1.0
+    pub equity: Vec<(DateTime<Utc>, f64)>,
+    pub return_percent: f64,
+    pub max_drawdown_percent: f64,
+    /// Holding the asset from the first evaluated close to the last
+    pub buy_and_hold_percent: f64,
+}
+
+fn candle_time(kline: &Kline) -> DateTime<Utc> {
+    DateTime::from_timestamp_millis(kline.open_time).unwrap_or_default()
+}
+
+fn mean(values: impl Iterator<Item = f64>) -> Option<f64> {
+    let (sum, n) = values.fold((0.0, 0usize), |(sum, n), v| (sum + v, n + 1));
+    (n > 0).then(|| sum / n as f64)
+}
+
+impl BacktestReport {
+    /// All-in/all-out long-only replay on `klines` (oldest first): enters on the close where
+    /// the short SMA is above the long one, exits on the close that crosses the stop-loss,
+    /// take-profit or turns the crossover bearish
+    pub fn run(symbol: &str, genome: &Genome, interval: CandleInterval, klines: &[Kline]) -> Self {
+        let closes: Vec<f64> = klines.iter().map(|k| k.close.to_f64().unwrap_or(0.0)).collect();
+        let (short, long) = (genome.sma_short as usize, genome.sma_long as usize);
+        let mut prefix = Vec::with_capacity(closes.len() + 1);
+        prefix.push(0.0);
+        for close in &closes {
+            prefix.push(prefix.last().copied().unwrap_or(0.0) + close);
+        }
+        let sma = |i: usize, n: usize| (prefix[i + 1] - prefix[i + 1 - n]) / n as f64;
+
+        let first = long.saturating_sub(1);
+        let (mut cash, mut qty) = (1.0, 0.0);
+        let (mut peak, mut max_drawdown) = (1.0_f64, 0.0_f64);
+        // Entry index, price, cash spent, lowest low and highest high while held
+        let mut held: Option<(usize, f64, f64, f64, f64)> = None;
+        let mut trades = Vec::new();
+        let mut equity = Vec::new();
+        for (i, kline) in klines.iter().enumerate().skip(first) {
+            let price = closes[i];
+            let bullish = sma(i, short) > sma(i, long);
+            if let Some((entry_index, entry_price, spent, low, high)) = held.as_mut() {
+                *low = low.min(kline.low.to_f64().unwrap_or(price));
+                *high = high.max(kline.high.to_f64().unwrap_or(price));
+                let change = (price - *entry_price) / *entry_price * 100.0;
+                let exit = if change <= -genome.stop_loss_percent {
+                    Some(BacktestExit::StopLoss)
+                } else if change >= genome.take_profit_percent {
+                    Some(BacktestExit::TakeProfit)
+                } else if !bullish {
+                    Some(BacktestExit::Crossover)
+                } else {
+                    None
+                };
+                if let Some(exit) = exit {
+                    cash = qty * price * (1.0 - FEE_RATE);
+                    qty = 0.0;
+                    trades.push(BacktestTrade {
+                        entry_time: candle_time(&klines[*entry_index]),
+                        entry_price: *entry_price,
+                        exit_time: candle_time(kline),
+                        exit_price: price,
+                        exit,
+                        return_percent: (cash / *spent - 1.0) * 100.0,
+                        mae_percent: ((*low - *entry_price) / *entry_price * 100.0).min(0.0),
+                        mfe_percent: ((*high - *entry_price) / *entry_price * 100.0).max(0.0),
+                        candles_held: i - *entry_index,
+                    });
+                    held = None;
+                }
+            } else if bullish {
+                qty = cash * (1.0 - FEE_RATE) / price;
+                held = Some((i, price, cash, price, price));
+                cash = 0.0;
+            }
+            let value = cash + qty * price;
+            peak = peak.max(value);
+            max_drawdown = max_drawdown.max((peak - value) / peak * 100.0);
+            equity.push((candle_time(kline), value));
+        }
+        let last_close = closes.last().copied().unwrap_or(0.0);
+        if let (Some((entry_index, entry_price, spent, low, high)), Some(last)) = (held, klines.last()) {
+            trades.push(BacktestTrade {
+                entry_time: candle_time(&klines[entry_index]),
+                entry_price,
+                exit_time: candle_time(last),
+                exit_price: last_close,
+                exit: BacktestExit::Open,
+                return_percent: (qty * last_close / spent - 1.0) * 100.0,
+                mae_percent: ((low - entry_price) / entry_price * 100.0).min(0.0),
+                mfe_percent: ((high - entry_price) / entry_price * 100.0).max(0.0),
+                candles_held: klines.len() - 1 - entry_index,
+            });
+        }
+        let first_close = closes.get(first).copied().filter(|c| *c > 0.0);
+        Self {
+            symbol: symbol.to_string(),
+            genome: *genome,
+            interval,
+            candles: klines.len(),
+            start: klines.first().map(candle_time),
+            end: klines.last().map(candle_time),
+            trades,
+            equity,
+            return_percent: (cash + qty * last_close - 1.0) * 100.0,
+            max_drawdown_percent: max_drawdown,
+            buy_and_hold_percent: first_close.map_or(0.0, |c| (last_close / c - 1.0) * 100.0),
+        }
+    }
+
+    fn closed(&self) -> impl Iterator<Item = &BacktestTrade> + Clone {
+        self.trades.iter().filter(|t| t.exit != BacktestExit::Open)
+    }
+
+    /// Share of closed trades with a positive net return
+    pub fn win_rate(&self) -> Option<f64> {
+        mean(self.closed().map(|t| if t.return_percent > 0.0 { 100.0 } else { 0.0 }))
+    }
+
+    /// Sum of winning returns over the sum of losing ones
+    pub fn profit_factor(&self) -> Option<f64> {
+        let wins: f64 = self.closed().map(|t| t.return_percent).filter(|r| *r > 0.0).sum();
+        let losses: f64 = self.closed().map(|t| t.return_percent).filter(|r| *r < 0.0).sum();
+        (losses < 0.0).then(|| wins / -losses)
+    }
+
+    /// The report as plain text: parameters, aggregate stats, an equity chart and the trade list
+    pub fn render_text(&self) -> String {
+        let g = &self.genome;
+        let closed = self.closed();
+        let fmt_pct = |v: Option<f64>| v.map_or("-".to_string(), |v| format!("{:+.2}%", v));
+        let mut out = String::new();
+        let _ = writeln!(out, "BACKTEST {} {} | SL -{:.1}% TP {:.1}% | fee {:.1}% per side",
+            self.symbol, g.competition_spec(), g.stop_loss_percent, g.take_profit_percent, FEE_RATE * 100.0);
+        if let (Some(start), Some(end)) = (self.start, self.end) {
+            let _ = writeln!(out, "{} {} candles, {} to {}", self.candles, self.interval.as_str(),
+                start.format("%Y-%m-%d %H:%M"), end.format("%Y-%m-%d %H:%M UTC"));
+        }
+        let _ = writeln!(out, "\nSummary");
+        let _ = writeln!(out, "  Return:            {:+.2}% (buy & hold {:+.2}%)", self.return_percent, self.buy_and_hold_percent);
+        let _ = writeln!(out, "  Max drawdown:      {:.2}%", self.max_drawdown_percent);
+        let _ = writeln!(out, "  Trades:            {} closed{}", closed.clone().count(),
+            if self.trades.len() > closed.clone().count() { ", 1 open" } else { "" });
+        let _ = writeln!(out, "  Win rate:          {}", self.win_rate().map_or("-".to_string(), |v| format!("{:.1}%", v)));
+        let _ = writeln!(out, "  Profit factor:     {}", self.profit_factor().map_or("-".to_string(), |v| format!("{:.2}", v)));
+        let _ = writeln!(out, "  Avg trade:         {}", fmt_pct(mean(closed.clone().map(|t| t.return_percent))));
+        let _ = writeln!(out, "  Avg win / loss:    {} / {}",
+            fmt_pct(mean(closed.clone().map(|t| t.return_percent).filter(|r| *r > 0.0))),
+            fmt_pct(mean(closed.clone().map(|t| t.return_percent).filter(|r| *r <= 0.0))));
+        let _ = writeln!(out, "  Avg MAE / MFE:     {} / {}",
+            fmt_pct(mean(closed.clone().map(|t| t.mae_percent))), fmt_pct(mean(closed.clone().map(|t| t.mfe_percent))));
+        let _ = writeln!(out, "  Avg holding time:  {}",
+            mean(closed.clone().map(|t| t.holding_hours())).map_or("-".to_string(), |h| format!("{:.1}h", h)));
+        for (label, exit) in [("Stop-loss exits:", BacktestExit::StopLoss), ("Take-profit exits:", BacktestExit::TakeProfit),
+                              ("Crossover exits:", BacktestExit::Crossover)] {
+            let _ = writeln!(out, "  {:<19}{}", label, closed.clone().filter(|t| t.exit == exit).count());
+        }
+
+        let _ = writeln!(out, "\nEquity");
+        let _ = writeln!(out, "  {}", self.equity_chart());
+
+        let _ = writeln!(out, "\nTrades");
+        let _ = writeln!(out, "  {:<17} {:>12} {:<17} {:>12} {:<12} {:>8} {:>8} {:>8} {:>7}",
+            "Entry", "Price", "Exit", "Price", "Reason", "Return", "MAE", "MFE", "Held");
+        for t in &self.trades {
+            let _ = writeln!(out, "  {:<17} {:>12.2} {:<17} {:>12.2} {:<12} {:>+7.2}% {:>+7.2}% {:>+7.2}% {:>6.0}h",
+                t.entry_time.format("%Y-%m-%d %H:%M"), t.entry_price, t.exit_time.format("%Y-%m-%d %H:%M"),
+                t.exit_price, t.exit.label(), t.return_percent, t.mae_percent, t.mfe_percent, t.holding_hours());
+        }
+        out
+    }
+
+    /// Equity curve as one line of block characters, resampled to [`CHART_WIDTH`] columns
+    fn equity_chart(&self) -> String {
+        let values: Vec<f64> = self.equity.iter().map(|(_, v)| *v).collect();
+        if values.is_empty() {
+            return String::new();
+        }
+        let columns = values.len().min(CHART_WIDTH);
+        let sampled: Vec<f64> = (0..columns).map(|c| values[c * (values.len() - 1) / (columns - 1).max(1)]).collect();
+        let min = sampled.iter().copied().fold(f64::INFINITY, f64::min);
+        let max = sampled.iter().copied().fold(f64::NEG_INFINITY, f64::max);
+        let range = if max > min { max - min } else { 1.0 };
+        let chart: String = sampled.iter()
+            .map(|v| CHART_BLOCKS[((v - min) / range * (CHART_BLOCKS.len() - 1) as f64).round() as usize])
+            .collect();
+        format!("{} {:.3} → {:.3}", chart, values[0], values[values.len() - 1])
+    }
+
+    /// The text report in an HTML page, with the equity curve drawn as an SVG sparkline
+    pub fn render_html(&self) -> String {
+        let values: Vec<Decimal> = self.equity.iter().filter_map(|(_, v)| Decimal::try_from(*v).ok()).collect();
+        let color = if self.return_percent < 0.0 { "#ef4444" } else { "#10b981" };
+        format!(
+            "<!DOCTYPE html>\n<html><head><meta charset=\"utf-8\"><title>Backtest {symbol}</title>\
+             <style>body{{font-family:system-ui,sans-serif;background:#111827;color:#e5e7eb;margin:2rem}}</style></head>\n\
+             <body><h1>Backtest {symbol}</h1>\n{chart}\n<pre>{text}</pre></body></html>\n",
+            symbol = self.symbol,
+            chart = html_report::sparkline(&values, color),
+            text = self.render_text().replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;"),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::optimizer;
+
+    fn klines(closes: &[f64]) -> Vec<Kline> {
+        closes.iter().enumerate().map(|(i, &c)| {
+            let close = Decimal::try_from(c).unwrap();
+            Kline {
+                open_time: i as i64 * 3_600_000,
+                open: close,
+                high: close * Decimal::new(101, 2),
+                low: close * Decimal::new(99, 2),
+                close,
+                volume: Decimal::ONE,
+                close_time: (i as i64 + 1) * 3_600_000 - 1,
+            }
+        }).collect()
+    }
+
+    #[test]
+    fn test_report_matches_fitness_backtest() {
+        let closes: Vec<f64> = (0..400)
+            .map(|i| 100.0 + i as f64 * 0.2 + 8.0 * (i as f64 / 15.0).sin())
+            .collect();
+        let genome = Genome { sma_short: 5, sma_long: 20, stop_loss_percent: 3.0, take_profit_percent: 6.0 };
+        let report = BacktestReport::run("BTCUSDT", &genome, CandleInterval::H1, &klines(&closes));
+        let fitness = optimizer::backtest(&genome, &closes);
+
+        assert!((report.return_percent - fitness.return_percent).abs() < 1e-9);
+        assert!((report.max_drawdown_percent - fitness.max_drawdown_percent).abs() < 1e-9);
+        let open = report.trades.iter().filter(|t| t.exit == BacktestExit::Open).count() as u32;
+        assert_eq!(report.trades.len() as u32 * 2 - open, fitness.trades);
+        assert!(report.trades.iter().all(|t| t.mae_percent <= 0.0 && t.mfe_percent >= 0.0 && t.candles_held > 0));
+        assert!(report.trades[0].holding_hours() >= 1.0);
+
+        let text = report.render_text();
+        assert!(text.contains("sma:5/20") && text.contains("Avg MAE / MFE"));
+        assert!(report.render_html().contains("<svg"));
+    }
+}
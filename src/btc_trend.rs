@@ -0,0 +1,113 @@
+use crate::config::Config;
+use crate::error::BotError;
+use crate::exchange::Exchange;
+use crate::strategy::SmaCrossover;
+use anyhow::Result;
+use rust_decimal::Decimal;
+use std::time::{Duration, Instant};
+use tracing::{info, warn};
+
+/// Higher-timeframe candles barely change within a quarter hour
+const REFRESH_INTERVAL: Duration = Duration::from_secs(900);
+const INTERVALS: [&str; 5] = ["1h", "2h", "4h", "12h", "1d"];
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BtcTrend {
+    Bullish,
+    Bearish,
+}
+
+/// Fast SMA above the slow one is bullish; `None` without enough closes
+pub fn trend_of(closes: &[Decimal], fast: usize, slow: usize) -> Option<BtcTrend> {
+    let fast = SmaCrossover::calculate_sma(closes, fast)?;
+    let slow = SmaCrossover::calculate_sma(closes, slow)?;
+    Some(if fast >= slow { BtcTrend::Bullish } else { BtcTrend::Bearish })
+}
+
+/// Holds back long entries on altcoin pairs while BTC's higher-timeframe trend is bearish
+/// (BTC_TREND_FILTER). The BTC candles are fetched whatever the traded symbol, so the filter
+/// keeps working after the scanner switches to an altcoin.
+pub struct BtcTrendFilter {
+    symbol: String,
+    interval: String,
+    fast: usize,
+    slow: usize,
+    fetched: Option<Instant>,
+    /// Trend of the last fetched candles, reused until they are stale
+    trend: Option<BtcTrend>,
+}
+
+impl BtcTrendFilter {
+    /// `None` unless BTC_TREND_FILTER is on; BTC is read in the traded `quote` asset
+    pub fn from_config(config: &Config, quote: &str) -> Result<Option<Self>> {
+        if !config.btc_trend_filter {
+            return Ok(None);
+        }
+        if !INTERVALS.contains(&config.btc_trend_interval.as_str()) {
+            return Err(BotError::config(format!("BTC_TREND_INTERVAL must be one of {}, got '{}'",
+                INTERVALS.join(", "), config.btc_trend_interval)).into());
+        }
+        if config.btc_trend_fast_period == 0 || config.btc_trend_fast_period >= config.btc_trend_slow_period {
+            return Err(BotError::config("BTC_TREND_FAST_PERIOD must be positive and below BTC_TREND_SLOW_PERIOD").into());
+        }
+        Ok(Some(Self {
+            symbol: format!("BTC{}", quote),
+            interval: config.btc_trend_interval.clone(),
+            fast: config.btc_trend_fast_period,
+            slow: config.btc_trend_slow_period,
+            fetched: None,
+            trend: None,
+        }))
+    }
+
+    /// Refetch the BTC candles once the cached trend is stale. A failed fetch keeps the previous trend.
+    pub async fn refresh(&mut self, exchange: &impl Exchange) -> Option<BtcTrend> {
+        if self.fetched.is_none_or(|t| t.elapsed() >= REFRESH_INTERVAL) {
+            self.fetched = Some(Instant::now());
+            match exchange.get_klines(&self.symbol, &self.interval, self.slow as u32).await {
+                Ok(klines) => {
+                    let closes: Vec<Decimal> = klines.iter().map(|k| k.close).collect();
+                    let trend = trend_of(&closes, self.fast, self.slow);
+                    if trend != self.trend {
+                        info!("₿ {} {} trend: {:?}", self.symbol, self.interval, trend);
+                    }
+                    self.trend = trend;
+                }
+                Err(e) => warn!("⚠️ Failed to fetch {} candles for the trend filter: {}", self.symbol, e),
+            }
+        }
+        self.trend
+    }
+
+    /// Why a long entry in `base_asset` is held back; BTC itself is never filtered
+    pub fn long_entry_block(&self, base_asset: &str) -> Option<String> {
+        (base_asset != "BTC" && self.trend == Some(BtcTrend::Bearish)).then(|| format!(
+            "₿ BTC {} trend is bearish (SMA {} < SMA {})", self.interval, self.fast, self.slow))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_bearish_btc_blocks_altcoin_longs_only() {
+        let falling: Vec<Decimal> = (0..50).map(|i| Decimal::from(100 - i)).collect();
+        let rising: Vec<Decimal> = falling.iter().rev().copied().collect();
+        assert_eq!(trend_of(&falling, 20, 50), Some(BtcTrend::Bearish));
+        assert_eq!(trend_of(&rising, 20, 50), Some(BtcTrend::Bullish));
+        assert_eq!(trend_of(&rising[..30], 20, 50), None);
+
+        let mut config = Config::defaults();
+        config.btc_trend_filter = true;
+        let mut filter = BtcTrendFilter::from_config(&config, "USDT").unwrap().unwrap();
+        assert_eq!(filter.symbol, "BTCUSDT");
+        assert_eq!(filter.long_entry_block("SOL"), None);
+        filter.trend = trend_of(&falling, filter.fast, filter.slow);
+        assert!(filter.long_entry_block("SOL").is_some());
+        assert_eq!(filter.long_entry_block("BTC"), None);
+
+        config.btc_trend_fast_period = 50;
+        assert!(BtcTrendFilter::from_config(&config, "USDT").is_err());
+    }
+}
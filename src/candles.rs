@@ -0,0 +1,126 @@
+use crate::config::Config;
+use crate::error::BotError;
+use crate::exchange::Exchange;
+use crate::models::Kline;
+use anyhow::Result;
+use tracing::debug;
+
+/// Candles fetched per refresh; enough for the 20-period SMA and 14-period RSI several times over
+const CANDLE_LIMIT: u32 = 100;
+
+/// Candle length a strategy's indicators run on
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord, Default)]
+pub enum CandleInterval {
+    M1,
+    M5,
+    M15,
+    #[default]
+    H1,
+    H4,
+}
+
+impl CandleInterval {
+    pub const NAMES: [&str; 5] = ["1m", "5m", "15m", "1h", "4h"];
+
+    pub fn parse(name: &str) -> Result<Self> {
+        match name.trim().to_lowercase().as_str() {
+            "1m" => Ok(Self::M1),
+            "5m" => Ok(Self::M5),
+            "15m" => Ok(Self::M15),
+            "1h" => Ok(Self::H1),
+            "4h" => Ok(Self::H4),
+            other => Err(BotError::config(format!(
+                "Invalid INDICATOR_INTERVAL '{}' (expected {})", other, Self::NAMES.join(", "))).into()),
+        }
+    }
+
+    /// The exchange's name for the interval
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::M1 => "1m",
+            Self::M5 => "5m",
+            Self::M15 => "15m",
+            Self::H1 => "1h",
+            Self::H4 => "4h",
+        }
+    }
+
+    pub fn millis(self) -> i64 {
+        let minutes = match self {
+            Self::M1 => 1,
+            Self::M5 => 5,
+            Self::M15 => 15,
+            Self::H1 => 60,
+            Self::H4 => 240,
+        };
+        minutes * 60_000
+    }
+}
+
+/// Closed exchange candles at INDICATOR_INTERVAL for the spot loops' SMA and RSI, fetched
+/// again only once a newer candle has closed
+#[derive(Debug, Clone)]
+pub struct CandleFeed {
+    interval: CandleInterval,
+    symbol: String,
+    candles: Vec<Kline>,
+}
+
+impl CandleFeed {
+    /// `None` without INDICATOR_INTERVAL: the indicators keep running on CoinGecko's hourly candles
+    pub fn from_config(config: &Config) -> Result<Option<Self>> {
+        let Some(name) = &config.indicator_interval else {
+            return Ok(None);
+        };
+        Ok(Some(Self { interval: CandleInterval::parse(name)?, symbol: String::new(), candles: Vec::new() }))
+    }
+
+    pub fn interval(&self) -> CandleInterval {
+        self.interval
+    }
+
+    /// Whether a candle has closed since the cached ones (or there are none for `symbol`)
+    fn is_stale(&self, symbol: &str, now_ms: i64) -> bool {
+        self.symbol != symbol
+            || self.candles.last().is_none_or(|last| now_ms > last.close_time + self.interval.millis())
+    }
+
+    /// The latest closed candles of `symbol`, oldest first. The candle still forming is left out.
+    pub async fn candles(&mut self, exchange: &impl Exchange, symbol: &str, now_ms: i64) -> Result<&[Kline]> {
+        if self.is_stale(symbol, now_ms) {
+            let mut candles = exchange.get_klines(symbol, self.interval.as_str(), CANDLE_LIMIT).await?;
+            candles.retain(|k| k.close_time < now_ms);
+            debug!("Fetched {} closed {} {} candles", candles.len(), self.interval.as_str(), symbol);
+            self.symbol = symbol.to_string();
+            self.candles = candles;
+        }
+        Ok(&self.candles)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mock_exchange::MockExchange;
+    use rust_decimal_macros::dec;
+
+    #[tokio::test]
+    async fn test_feed_refetches_once_a_candle_closes() {
+        assert_eq!(CandleInterval::parse(" 4H ").unwrap(), CandleInterval::H4);
+        assert!(CandleInterval::parse("2h").unwrap_err().to_string().contains("1m, 5m, 15m, 1h, 4h"));
+
+        let exchange = MockExchange::new("BTCUSDT", dec!(1000)).with_prices([dec!(100), dec!(101), dec!(102)]);
+        for _ in 0..3 {
+            exchange.get_price("BTCUSDT").await.unwrap();
+        }
+        let mut feed = CandleFeed { interval: CandleInterval::M5, symbol: String::new(), candles: Vec::new() };
+        let now = chrono::Utc::now().timestamp_millis();
+        let candles = feed.candles(&exchange, "BTCUSDT", now).await.unwrap().to_vec();
+        assert_eq!(candles.iter().map(|k| k.close).collect::<Vec<_>>(), vec![dec!(100), dec!(101), dec!(102)]);
+
+        let last = candles.last().unwrap().close_time;
+        assert!(!feed.is_stale("BTCUSDT", last + CandleInterval::M5.millis()));
+        assert!(feed.is_stale("BTCUSDT", last + CandleInterval::M5.millis() + 1));
+        assert!(feed.is_stale("ETHUSDT", last));
+    }
+}
@@ -0,0 +1,161 @@
+use crate::portfolio::PortfolioStatus;
+use chrono::{DateTime, Utc};
+use rust_decimal::Decimal;
+use rust_decimal_macros::dec;
+use std::collections::HashMap;
+use tracing::info;
+
+/// Share of a trade's expected balance change that may differ because of fees and slippage
+const TRADE_TOLERANCE: Decimal = dec!(0.02);
+
+/// A deposit (positive) or withdrawal (negative) detected from balance changes
+#[derive(Debug, Clone, PartialEq)]
+pub struct CapitalFlow {
+    pub timestamp: DateTime<Utc>,
+    pub asset: String,
+    pub amount: Decimal,
+    /// Value of the flow in the quote asset, if the asset is priced
+    pub value: Option<Decimal>,
+}
+
+/// Detects deposits and withdrawals between cycles and keeps a time-weighted return.
+///
+/// Balance changes are compared with the changes the bot's own orders should have
+/// caused; whatever is left over (above `CAPITAL_FLOW_THRESHOLD` in quote value) is an
+/// external capital flow. Each cycle is a sub-period whose return excludes the flows,
+/// so depositing or withdrawing does not show up as profit or loss.
+pub struct CapitalTracker {
+    threshold: Decimal,
+    last_balances: Option<HashMap<String, Decimal>>,
+    /// Balance changes expected from our own fills that haven't shown up yet
+    expected: HashMap<String, Decimal>,
+    last_equity: Option<Decimal>,
+    growth: Decimal,
+    net_flows: Decimal,
+}
+
+impl CapitalTracker {
+    pub fn new(threshold: Decimal) -> Self {
+        Self {
+            threshold,
+            last_balances: None,
+            expected: HashMap::new(),
+            last_equity: None,
+            growth: Decimal::ONE,
+            net_flows: Decimal::ZERO,
+        }
+    }
+
+    /// Register the balance change caused by one of our own orders
+    pub fn expect_trade(&mut self, base: &str, quote: &str, base_delta: Decimal, quote_delta: Decimal) {
+        *self.expected.entry(base.to_string()).or_default() += base_delta;
+        *self.expected.entry(quote.to_string()).or_default() += quote_delta;
+    }
+
+    /// Compare the current balances with the previous cycle and return any external flows.
+    /// Margin liabilities are netted out, so borrowing isn't mistaken for a deposit.
+    pub fn observe(&mut self, status: &PortfolioStatus) -> Vec<CapitalFlow> {
+        let mut current = status.balances.clone();
+        for (asset, owed) in &status.liabilities {
+            *current.entry(asset.clone()).or_default() -= *owed;
+        }
+        let Some(previous) = self.last_balances.replace(current.clone()) else {
+            self.last_equity = Some(status.total_portfolio_value);
+            return Vec::new();
+        };
+
+        let mut assets: Vec<&String> = previous.keys().chain(current.keys()).collect();
+        assets.sort();
+        assets.dedup();
+
+        let mut flows = Vec::new();
+        for asset in assets {
+            let change = current.get(asset).copied().unwrap_or_default()
+                - previous.get(asset).copied().unwrap_or_default();
+            let expected = self.expected.get(asset).copied().unwrap_or_default();
+
+            // Our own fill may not be reflected yet; keep expecting it
+            if change.is_zero() {
+                continue;
+            }
+            self.expected.remove(asset);
+
+            let unexplained = change - expected;
+            if unexplained.abs() <= expected.abs() * TRADE_TOLERANCE {
+                continue;
+            }
+
+            let value = status.value_in_quote(asset, unexplained);
+            if value.is_some_and(|v| v.abs() < self.threshold) {
+                continue;
+            }
+
+            info!("🏦 {} of {} {} detected", if unexplained > Decimal::ZERO { "Deposit" } else { "Withdrawal" },
+                unexplained.abs(), asset);
+            flows.push(CapitalFlow {
+                timestamp: Utc::now(),
+                asset: asset.clone(),
+                amount: unexplained,
+                value,
+            });
+        }
+
+        let flow_value: Decimal = flows.iter().filter_map(|f| f.value).sum();
+        self.net_flows += flow_value;
+
+        // Flows are assumed to land at the end of the sub-period
+        let equity = status.total_portfolio_value;
+        if let Some(last) = self.last_equity
+            && last > Decimal::ZERO {
+            self.growth *= (equity - flow_value) / last;
+        }
+        self.last_equity = Some(equity);
+
+        flows
+    }
+
+    /// Net deposits minus withdrawals in the quote asset
+    pub fn net_flows(&self) -> Decimal {
+        self.net_flows
+    }
+
+    /// Time-weighted return in percent since the bot started
+    pub fn time_weighted_return(&self) -> Decimal {
+        (self.growth - Decimal::ONE) * dec!(100)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_deposit_is_not_profit() {
+        let mut status = PortfolioStatus::new("BTCUSDT", false);
+        status.current_price = dec!(50000);
+        status.balances = HashMap::from([("USDT".to_string(), dec!(1000))]);
+        status.update_total_value();
+
+        let mut tracker = CapitalTracker::new(dec!(1));
+        assert!(tracker.observe(&status).is_empty());
+
+        // Our own buy: 0.01 BTC for 500 USDT (fee taken in BTC)
+        tracker.expect_trade("BTC", "USDT", dec!(0.01), dec!(-500));
+        status.balances = HashMap::from([("USDT".to_string(), dec!(500)), ("BTC".to_string(), dec!(0.00999))]);
+        status.update_total_value();
+        assert!(tracker.observe(&status).is_empty());
+
+        // Price up 10%, then a 1000 USDT deposit
+        status.current_price = dec!(55000);
+        status.balances.insert("USDT".to_string(), dec!(1500));
+        status.update_total_value();
+        let flows = tracker.observe(&status);
+        assert_eq!(flows.len(), 1);
+        assert_eq!(flows[0].amount, dec!(1000));
+        assert_eq!(tracker.net_flows(), dec!(1000));
+
+        // Only the position's gain counts: (500 + 549.45) / 999.5
+        let twr = tracker.time_weighted_return();
+        assert!(twr > dec!(4.9) && twr < dec!(5.0), "twr = {}", twr);
+    }
+}
@@ -0,0 +1,130 @@
+use crate::config::Config;
+use crate::error::BotError;
+use anyhow::Result;
+use rand::Rng;
+use rand::seq::SliceRandom;
+use reqwest::StatusCode;
+use rust_decimal::Decimal;
+use rust_decimal_macros::dec;
+use std::time::Duration;
+use tracing::warn;
+
+/// Failures an exchange call can be answered with
+const INJECTED_STATUSES: [StatusCode; 4] = [
+    StatusCode::REQUEST_TIMEOUT,
+    StatusCode::TOO_MANY_REQUESTS,
+    StatusCode::SERVICE_UNAVAILABLE,
+    StatusCode::BAD_REQUEST,
+];
+
+/// Model output the response parser has to survive: refusals, truncation, nonsense levels
+const MALFORMED_AI_OUTPUTS: [&str; 5] = [
+    "",
+    "I'm sorry, but I can't provide financial advice.",
+    "RECOMMENDATION: STRONG_BUY\nCONFIDENCE: 9000%\nSTOP_LOSS: $999999999\nTAKE_PROFIT: $-5\nBUY_TARGET: $0",
+    "{\"recommendation\": \"BUY\", \"stop_loss\": ",
+    "STOP_LOSS: NaN\nTAKE_PROFIT: ∞\nBUY_TARGET: 1e400\n\u{fffd}\u{fffd}\u{fffd}",
+];
+
+/// Chaos mode for simulation runs: fails and delays simulated exchange calls and swaps AI
+/// analyses for malformed output at the CHAOS_* rates, to check the loop degrades
+/// gracefully - skipping the cycle or the order - instead of crashing or trading twice
+#[derive(Debug, Clone)]
+pub struct Chaos {
+    failure_percent: Decimal,
+    delay_percent: Decimal,
+    max_delay: Duration,
+    ai_garbage_percent: Decimal,
+}
+
+impl Chaos {
+    /// `None` when every rate is 0, or outside simulation
+    pub fn from_config(config: &Config) -> Result<Option<Self>> {
+        let rates = [
+            ("CHAOS_FAILURE_PERCENT", config.chaos_failure_percent),
+            ("CHAOS_DELAY_PERCENT", config.chaos_delay_percent),
+            ("CHAOS_AI_GARBAGE_PERCENT", config.chaos_ai_garbage_percent),
+        ];
+        if let Some((name, rate)) = rates.iter().find(|(_, rate)| *rate < Decimal::ZERO || *rate > dec!(100)) {
+            return Err(BotError::config(format!("{} must be between 0 and 100, got {}", name, rate)).into());
+        }
+        if rates.iter().all(|(_, rate)| rate.is_zero()) {
+            return Ok(None);
+        }
+        if !config.is_simulation() {
+            warn!("⚠️ CHAOS_* settings ignored: chaos mode only runs in simulation");
+            return Ok(None);
+        }
+        Ok(Some(Self {
+            failure_percent: config.chaos_failure_percent,
+            delay_percent: config.chaos_delay_percent,
+            max_delay: Duration::from_millis(config.chaos_max_delay_ms),
+            ai_garbage_percent: config.chaos_ai_garbage_percent,
+        }))
+    }
+
+    fn roll(percent: Decimal) -> bool {
+        percent > Decimal::ZERO && Decimal::from(rand::thread_rng().gen_range(0..10_000)) / dec!(100) < percent
+    }
+
+    /// Maybe hold up, then maybe fail, the exchange call `call`
+    pub async fn disrupt(&self, call: &str) -> Result<()> {
+        if Self::roll(self.delay_percent) {
+            let delay = self.max_delay.mul_f64(rand::thread_rng().gen_range(0.0..=1.0));
+            warn!("🐒 Chaos: delaying {} by {}ms", call, delay.as_millis());
+            tokio::time::sleep(delay).await;
+        }
+        if Self::roll(self.failure_percent) {
+            let status = *INJECTED_STATUSES.choose(&mut rand::thread_rng()).unwrap_or(&StatusCode::SERVICE_UNAVAILABLE);
+            warn!("🐒 Chaos: failing {} with {}", call, status);
+            return Err(BotError::exchange(status, "injected by chaos mode").into());
+        }
+        Ok(())
+    }
+
+    /// Malformed model output to analyse in place of asking the model, at CHAOS_AI_GARBAGE_PERCENT
+    pub fn garbled_ai_output(&self) -> Option<&'static str> {
+        Self::roll(self.ai_garbage_percent)
+            .then(|| *MALFORMED_AI_OUTPUTS.choose(&mut rand::thread_rng()).unwrap_or(&""))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ai_advisor::{MarketContext, OllamaClient};
+
+    #[tokio::test]
+    async fn test_rates_and_malformed_ai_output() {
+        let mut config = Config::defaults();
+        config.simulation_mode = true;
+        assert!(Chaos::from_config(&config).unwrap().is_none());
+        config.chaos_failure_percent = dec!(101);
+        assert!(Chaos::from_config(&config).is_err());
+
+        config.chaos_failure_percent = dec!(100);
+        config.chaos_ai_garbage_percent = dec!(100);
+        let chaos = Chaos::from_config(&config).unwrap().unwrap();
+        assert!(chaos.disrupt("get_price").await.is_err());
+        assert!(chaos.garbled_ai_output().is_some());
+        config.chaos_failure_percent = dec!(0);
+        assert!(Chaos::from_config(&config).unwrap().unwrap().disrupt("get_price").await.is_ok());
+
+        // Every malformed answer still yields a stop below and a take-profit above the price
+        let context = MarketContext {
+            symbol: "BTCUSDT".to_string(),
+            current_price: dec!(60000),
+            high_24h: dec!(61000),
+            low_24h: dec!(59000),
+            ..Default::default()
+        };
+        let client = OllamaClient::new(None, None).unwrap();
+        for output in MALFORMED_AI_OUTPUTS {
+            let targets = client.parse_ai_response(output, &context).unwrap();
+            assert!(targets.stop_loss_price < context.current_price, "{:?}", output);
+            assert!(targets.take_profit_price > context.current_price, "{:?}", output);
+            assert!(targets.confidence <= dec!(100));
+            assert!(targets.buy_target_price.is_none_or(|p| p > Decimal::ZERO));
+        }
+    }
+}
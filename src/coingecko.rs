@@ -1,8 +1,18 @@
+use crate::candles::CandleInterval;
+use crate::error::{BotError, ReqwestExt};
+use crate::models::Kline;
+use crate::resample::resample;
+use crate::symbols;
 use anyhow::{anyhow, Result};
 use rust_decimal::Decimal;
 use serde::Deserialize;
+use std::collections::HashMap;
 use std::str::FromStr;
-use tracing::{debug, info};
+use std::time::{Duration, Instant};
+use tracing::{debug, info, warn};
+
+/// Global stats move slowly; refreshing them every 10 minutes spares the free-tier rate limit
+const GLOBAL_REFRESH_INTERVAL: Duration = Duration::from_secs(600);
 
 /// CoinGecko API client for fetching crypto market data
 pub struct CoinGeckoClient {
@@ -20,6 +30,22 @@ pub struct OhlcData {
     pub close: Decimal,
 }
 
+impl OhlcData {
+    /// As an hourly exchange candle; CoinGecko reports no per-candle volume
+    pub fn to_kline(&self) -> Kline {
+        let open_time = self.timestamp * 1000;
+        Kline {
+            open_time,
+            open: self.open,
+            high: self.high,
+            low: self.low,
+            close: self.close,
+            volume: Decimal::ZERO,
+            close_time: open_time + CandleInterval::H1.millis() - 1,
+        }
+    }
+}
+
 /// Market data fetched from CoinGecko
 #[allow(dead_code)]
 #[derive(Debug, Clone)]
@@ -67,6 +93,31 @@ struct PriceData {
     usd_market_cap: Option<f64>,
 }
 
+/// Market breadth from `/global`: how the whole crypto market is moving
+#[derive(Debug, Clone, PartialEq)]
+pub struct GlobalMarket {
+    /// BTC's share of the total market cap, in percent
+    pub btc_dominance: Decimal,
+    /// Total market cap in USD
+    pub total_market_cap: Decimal,
+    pub market_cap_change_24h_percent: Decimal,
+}
+
+#[derive(Debug, Deserialize)]
+struct GlobalResponse {
+    data: GlobalData,
+}
+
+#[derive(Debug, Deserialize)]
+struct GlobalData {
+    #[serde(default)]
+    total_market_cap: HashMap<String, f64>,
+    #[serde(default)]
+    market_cap_percentage: HashMap<String, f64>,
+    #[serde(default)]
+    market_cap_change_percentage_24h_usd: f64,
+}
+
 #[derive(Debug, Deserialize)]
 struct MarketChartResponse {
     prices: Vec<Vec<f64>>,
@@ -87,70 +138,42 @@ struct CoinMarketData {
 
 impl CoinGeckoClient {
     pub fn new() -> Self {
+        Self::with_base_url("https://api.coingecko.com/api/v3")
+    }
+
+    /// Client for a CoinGecko-compatible API at another address (Pro API, proxies, test servers)
+    pub fn with_base_url(base_url: &str) -> Self {
         Self {
             client: reqwest::Client::builder()
                 .timeout(std::time::Duration::from_secs(30))
                 .user_agent("CryptoTradingBot/1.0 (Rust; Educational Project)")
                 .build()
                 .expect("Failed to create HTTP client"),
-            base_url: "https://api.coingecko.com/api/v3".to_string(),
+            base_url: base_url.trim_end_matches('/').to_string(),
         }
     }
 
     /// Map trading symbol to CoinGecko coin ID
-    fn symbol_to_coin_id(symbol: &str) -> &str {
-        match symbol.to_uppercase().as_str() {
-            "BTCUSDT" | "BTC" | "BTCUSD" => "bitcoin",
-            "ETHUSDT" | "ETH" | "ETHUSD" => "ethereum",
-            "BNBUSDT" | "BNB" => "binancecoin",
-            "XRPUSDT" | "XRP" => "ripple",
-            "ADAUSDT" | "ADA" => "cardano",
-            "SOLUSDT" | "SOL" => "solana",
-            "DOTUSDT" | "DOT" => "polkadot",
-            "DOGEUSDT" | "DOGE" => "dogecoin",
-            "MATICUSDT" | "MATIC" => "matic-network",
-            "LTCUSDT" | "LTC" => "litecoin",
-            "AVAXUSDT" | "AVAX" => "avalanche-2",
-            "LINKUSDT" | "LINK" => "chainlink",
-            "ATOMUSDT" | "ATOM" => "cosmos",
-            "UNIUSDT" | "UNI" => "uniswap",
-            "XLMUSDT" | "XLM" => "stellar",
-            _ => "bitcoin", // Default to bitcoin
-        }
+    fn symbol_to_coin_id(symbol: &str) -> &'static str {
+        symbols::lookup(symbol).coingecko_id.unwrap_or("bitcoin") // Default to bitcoin
     }
 
     /// Fetch comprehensive market data including hourly OHLC
     pub async fn fetch_market_data(&self, symbol: &str) -> Result<CoinGeckoMarketData> {
         let coin_id = Self::symbol_to_coin_id(symbol);
+        let vs_currency = symbols::lookup(symbol).coingecko_vs_currency();
         info!("Fetching CoinGecko data for {} ({})", symbol, coin_id);
 
-        // Fetch current market data
-        let market_url = format!(
-            "{}/coins/markets?vs_currency=usd&ids={}&order=market_cap_desc&sparkline=false",
-            self.base_url, coin_id
-        );
-
-        let response = self.client
-            .get(&market_url)
-            .header("Accept", "application/json")
-            .send()
-            .await?;
-        
-        if !response.status().is_success() {
-            let status = response.status();
-            let body = response.text().await.unwrap_or_default();
-            return Err(anyhow!("CoinGecko API error {}: {}", status, body));
-        }
-        
-        let market_response: Vec<CoinMarketData> = response.json().await?;
+        // The market snapshot and the hourly chart are independent requests
+        // (CoinGecko free API: 1-90 days = hourly data)
+        let (market_response, hourly_48h) = tokio::try_join!(
+            self.fetch_markets(coin_id, vs_currency),
+            self.fetch_hourly_prices(coin_id, vs_currency, 2),
+        )?;
 
         let market = market_response
             .first()
             .ok_or_else(|| anyhow!("No market data found for {}", coin_id))?;
-
-        // Fetch hourly data for different timeframes
-        // CoinGecko free API: 1-90 days = hourly data
-        let hourly_48h = self.fetch_hourly_prices(coin_id, 2).await?;
         
         // Split into timeframes
         let hourly_24h: Vec<OhlcData> = hourly_48h.iter()
@@ -187,11 +210,81 @@ impl CoinGeckoClient {
         })
     }
 
+    async fn fetch_markets(&self, coin_id: &str, vs_currency: &str) -> Result<Vec<CoinMarketData>> {
+        let market_url = format!(
+            "{}/coins/markets?vs_currency={}&ids={}&order=market_cap_desc&sparkline=false",
+            self.base_url, vs_currency, coin_id
+        );
+
+        let response = self.client
+            .get(&market_url)
+            .header("Accept", "application/json")
+            .send()
+            .await
+            .or_data_source("CoinGecko")?;
+        
+        if !response.status().is_success() {
+            let status = response.status();
+            let body = response.text().await.unwrap_or_default();
+            return Err(BotError::data_source("CoinGecko", status, &body).into());
+        }
+        
+        Ok(response.json().await.or_data_source("CoinGecko")?)
+    }
+
+    /// Symbols (uppercase, e.g. `SOL`) of the `n` coins with the highest 24h volume
+    pub async fn fetch_top_by_volume(&self, vs_currency: &str, n: u32) -> Result<Vec<String>> {
+        let url = format!(
+            "{}/coins/markets?vs_currency={}&order=volume_desc&per_page={}&page=1&sparkline=false",
+            self.base_url, vs_currency, n
+        );
+        let response = self.client
+            .get(&url)
+            .header("Accept", "application/json")
+            .send()
+            .await
+            .or_data_source("CoinGecko")?;
+
+        if !response.status().is_success() {
+            let status = response.status();
+            let body = response.text().await.unwrap_or_default();
+            return Err(BotError::data_source("CoinGecko", status, &body).into());
+        }
+
+        let markets: Vec<CoinMarketData> = response.json().await.or_data_source("CoinGecko")?;
+        Ok(markets.into_iter().map(|m| m.symbol.to_uppercase()).collect())
+    }
+
+    /// BTC dominance and total market cap from `/global`
+    pub async fn fetch_global(&self) -> Result<GlobalMarket> {
+        let url = format!("{}/global", self.base_url);
+        let response = self.client
+            .get(&url)
+            .header("Accept", "application/json")
+            .send()
+            .await
+            .or_data_source("CoinGecko global")?;
+
+        if !response.status().is_success() {
+            let status = response.status();
+            let body = response.text().await.unwrap_or_default();
+            return Err(BotError::data_source("CoinGecko global", status, &body).into());
+        }
+
+        let global: GlobalResponse = response.json().await.or_data_source("CoinGecko global")?;
+        let decimal = |v: f64| Decimal::from_str(&v.to_string());
+        Ok(GlobalMarket {
+            btc_dominance: decimal(global.data.market_cap_percentage.get("btc").copied().unwrap_or(0.0))?,
+            total_market_cap: decimal(global.data.total_market_cap.get("usd").copied().unwrap_or(0.0))?,
+            market_cap_change_24h_percent: decimal(global.data.market_cap_change_percentage_24h_usd)?,
+        })
+    }
+
     /// Fetch hourly price data for a given number of days
-    async fn fetch_hourly_prices(&self, coin_id: &str, days: u32) -> Result<Vec<OhlcData>> {
+    async fn fetch_hourly_prices(&self, coin_id: &str, vs_currency: &str, days: u32) -> Result<Vec<OhlcData>> {
         let url = format!(
-            "{}/coins/{}/market_chart?vs_currency=usd&days={}",
-            self.base_url, coin_id, days
+            "{}/coins/{}/market_chart?vs_currency={}&days={}",
+            self.base_url, coin_id, vs_currency, days
         );
 
         debug!("Fetching hourly data: {}", url);
@@ -200,15 +293,16 @@ impl CoinGeckoClient {
             .get(&url)
             .header("Accept", "application/json")
             .send()
-            .await?;
+            .await
+            .or_data_source("CoinGecko chart")?;
             
         if !response.status().is_success() {
             let status = response.status();
             let body = response.text().await.unwrap_or_default();
-            return Err(anyhow!("CoinGecko chart API error {}: {}", status, body));
+            return Err(BotError::data_source("CoinGecko chart", status, &body).into());
         }
         
-        let chart_data: MarketChartResponse = response.json().await?;
+        let chart_data: MarketChartResponse = response.json().await.or_data_source("CoinGecko chart")?;
 
         // Convert price data to OHLC format
         // CoinGecko returns [timestamp_ms, price] pairs
@@ -369,18 +463,17 @@ impl CoinGeckoClient {
             output.push_str(&format!("High: ${:.2}, Low: ${:.2}\n", high_48h, low_48h));
             output.push_str(&format!("Range: ${:.2}\n\n", high_48h - low_48h));
 
-            // Include hourly prices for AI
-            output.push_str("=== Hourly Prices (48h) ===\n");
-            for (i, ohlc) in data.hourly_data_48h.iter().enumerate() {
-                if i % 4 == 0 { // Every 4 hours to keep it concise
-                    let dt = chrono::DateTime::from_timestamp(ohlc.timestamp, 0)
-                        .map(|d| d.format("%m/%d %H:%M").to_string())
-                        .unwrap_or_default();
-                    output.push_str(&format!(
-                        "{}: O=${:.2} H=${:.2} L=${:.2} C=${:.2}\n",
-                        dt, ohlc.open, ohlc.high, ohlc.low, ohlc.close
-                    ));
-                }
+            // Hourly candles rolled up into 4h bars to keep it concise
+            output.push_str("=== 4-Hour Candles (48h) ===\n");
+            let hourly: Vec<Kline> = data.hourly_data_48h.iter().map(OhlcData::to_kline).collect();
+            for bar in resample(&hourly, CandleInterval::H4) {
+                let dt = chrono::DateTime::from_timestamp_millis(bar.open_time)
+                    .map(|d| d.format("%m/%d %H:%M").to_string())
+                    .unwrap_or_default();
+                output.push_str(&format!(
+                    "{}: O=${:.2} H=${:.2} L=${:.2} C=${:.2}\n",
+                    dt, bar.open, bar.high, bar.low, bar.close
+                ));
             }
         }
 
@@ -394,6 +487,36 @@ impl Default for CoinGeckoClient {
     }
 }
 
+/// Cached `/global` stats, shared by every symbol the loops trade
+pub struct BreadthFeed {
+    client: CoinGeckoClient,
+    latest: Option<GlobalMarket>,
+    fetched: Option<Instant>,
+}
+
+impl BreadthFeed {
+    pub fn new(client: CoinGeckoClient) -> Self {
+        Self { client, latest: None, fetched: None }
+    }
+
+    /// Latest stats, fetching new ones when the cache is stale. A failed fetch keeps the
+    /// previous values until the next interval.
+    pub async fn refresh(&mut self) -> Option<&GlobalMarket> {
+        if self.fetched.is_none_or(|t| t.elapsed() >= GLOBAL_REFRESH_INTERVAL) {
+            match self.client.fetch_global().await {
+                Ok(global) => {
+                    info!("🌐 BTC dominance {}% | total market cap {:+}% (24h)",
+                        global.btc_dominance.round_dp(2), global.market_cap_change_24h_percent.round_dp(2));
+                    self.latest = Some(global);
+                }
+                Err(e) => warn!("⚠️ Failed to fetch CoinGecko global stats: {}", e),
+            }
+            self.fetched = Some(Instant::now());
+        }
+        self.latest.as_ref()
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
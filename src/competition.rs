@@ -0,0 +1,280 @@
+use crate::config::Config;
+use crate::error::BotError;
+use crate::models::Signal;
+use crate::strategy::{RsiStrategy, SmaCrossover};
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+use rust_decimal::Decimal;
+use rust_decimal_macros::dec;
+use std::fmt::Write as _;
+
+/// Strategy and parameters one competing bot trades with
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum BotStrategy {
+    /// Long while SMA(short) is above SMA(long)
+    Sma { short: usize, long: usize },
+    /// Buy below `oversold`, sell above `overbought`
+    Rsi { period: usize, oversold: u32, overbought: u32 },
+}
+
+impl BotStrategy {
+    /// `sma:10/20` or `rsi:14/30/70`
+    pub fn parse(spec: &str) -> Result<Self> {
+        let invalid = || BotError::config(format!(
+            "Invalid COMPETITION_BOTS entry '{}' (expected sma:<short>/<long> or rsi:<period>/<oversold>/<overbought>)", spec));
+        let (kind, params) = spec.split_once(':').ok_or_else(invalid)?;
+        let params: Vec<usize> = params.split('/')
+            .map(|p| p.trim().parse().map_err(|_| invalid()))
+            .collect::<Result<_, _>>()?;
+        match (kind.trim().to_lowercase().as_str(), params.as_slice()) {
+            ("sma", &[short, long]) if 0 < short && short < long => Ok(Self::Sma { short, long }),
+            ("rsi", &[period, oversold, overbought]) if period > 0 && oversold < overbought && overbought <= 100 => {
+                Ok(Self::Rsi { period, oversold: oversold as u32, overbought: overbought as u32 })
+            }
+            _ => Err(invalid().into()),
+        }
+    }
+
+    fn signal(self, closes: &[Decimal]) -> Signal {
+        match self {
+            Self::Sma { short, long } => {
+                match (SmaCrossover::calculate_sma(closes, short), SmaCrossover::calculate_sma(closes, long)) {
+                    (Some(s), Some(l)) if s > l => Signal::Buy,
+                    (Some(s), Some(l)) if s < l => Signal::Sell,
+                    _ => Signal::Hold,
+                }
+            }
+            Self::Rsi { period, oversold, overbought } => match RsiStrategy::calculate_rsi(closes, period) {
+                Some(rsi) if rsi < Decimal::from(oversold) => Signal::Buy,
+                Some(rsi) if rsi > Decimal::from(overbought) => Signal::Sell,
+                _ => Signal::Hold,
+            },
+        }
+    }
+}
+
+/// One simulated bot with its own cash and position
+#[derive(Debug, Clone)]
+pub struct Competitor {
+    pub name: String,
+    pub strategy: BotStrategy,
+    pub cash: Decimal,
+    pub qty: Decimal,
+    pub entry_price: Option<Decimal>,
+    pub realized_pnl: Decimal,
+    pub trades: u32,
+    pub wins: u32,
+    peak_equity: Decimal,
+    pub max_drawdown_percent: Decimal,
+}
+
+impl Competitor {
+    pub fn equity(&self, price: Decimal) -> Decimal {
+        self.cash + self.qty * price
+    }
+
+    fn buy(&mut self, price: Decimal) {
+        self.qty = self.cash / price;
+        self.cash = Decimal::ZERO;
+        self.entry_price = Some(price);
+        self.trades += 1;
+    }
+
+    fn sell(&mut self, price: Decimal) {
+        let pnl = (price - self.entry_price.unwrap_or(price)) * self.qty;
+        self.cash += self.qty * price;
+        self.qty = Decimal::ZERO;
+        self.entry_price = None;
+        self.realized_pnl += pnl;
+        self.trades += 1;
+        if pnl > Decimal::ZERO {
+            self.wins += 1;
+        }
+    }
+}
+
+/// A leaderboard line
+#[derive(Debug, Clone, PartialEq)]
+pub struct Standing {
+    pub rank: usize,
+    pub name: String,
+    pub equity: Decimal,
+    pub return_percent: Decimal,
+    pub realized_pnl: Decimal,
+    pub trades: u32,
+    pub win_rate: Option<Decimal>,
+    pub max_drawdown_percent: Decimal,
+    pub in_position: bool,
+}
+
+/// Several paper bots racing on one price feed. Each starts with the same balance and
+/// shares the configured stop-loss and take-profit; only the strategy differs.
+pub struct Competition {
+    competitors: Vec<Competitor>,
+    initial_balance: Decimal,
+    stop_loss_percent: Decimal,
+    take_profit_percent: Decimal,
+    pub started: DateTime<Utc>,
+    pub cycles: u64,
+}
+
+impl Competition {
+    pub fn from_config(config: &Config) -> Result<Self> {
+        if config.competition_bots.len() < 2 {
+            return Err(BotError::config("COMPETITION_BOTS needs at least two entries, e.g. sma:10/20,sma:5/30,rsi:14/30/70").into());
+        }
+        let mut competitors: Vec<Competitor> = Vec::new();
+        for spec in &config.competition_bots {
+            let name = spec.to_lowercase();
+            if competitors.iter().any(|c| c.name == name) {
+                return Err(BotError::config(format!("COMPETITION_BOTS lists {} twice", name)).into());
+            }
+            competitors.push(Competitor {
+                strategy: BotStrategy::parse(spec)?,
+                name,
+                cash: config.simulation_initial_balance,
+                qty: Decimal::ZERO,
+                entry_price: None,
+                realized_pnl: Decimal::ZERO,
+                trades: 0,
+                wins: 0,
+                peak_equity: config.simulation_initial_balance,
+                max_drawdown_percent: Decimal::ZERO,
+            });
+        }
+        Ok(Self {
+            competitors,
+            initial_balance: config.simulation_initial_balance,
+            stop_loss_percent: config.stop_loss_percent,
+            take_profit_percent: config.take_profit_percent,
+            started: Utc::now(),
+            cycles: 0,
+        })
+    }
+
+    pub fn competitors(&self) -> &[Competitor] {
+        &self.competitors
+    }
+
+    /// Advance every bot one cycle at `price` with the same hourly closes; returns the
+    /// trades made as log lines
+    pub fn step(&mut self, closes: &[Decimal], price: Decimal) -> Vec<String> {
+        let mut trades = Vec::new();
+        if price <= Decimal::ZERO {
+            return trades;
+        }
+        self.cycles += 1;
+        for bot in &mut self.competitors {
+            let signal = bot.strategy.signal(closes);
+            if let Some(entry) = bot.entry_price {
+                let change = (price - entry) / entry * dec!(100);
+                let reason = if change <= self.stop_loss_percent {
+                    Some("stop-loss")
+                } else if change >= self.take_profit_percent {
+                    Some("take-profit")
+                } else if signal == Signal::Sell {
+                    Some("signal")
+                } else {
+                    None
+                };
+                if let Some(reason) = reason {
+                    bot.sell(price);
+                    trades.push(format!("{} SELL @ {} ({}, {:+.2}%)", bot.name, price, reason, change));
+                }
+            } else if signal == Signal::Buy && bot.cash > Decimal::ZERO {
+                bot.buy(price);
+                trades.push(format!("{} BUY @ {}", bot.name, price));
+            }
+
+            let equity = bot.equity(price);
+            bot.peak_equity = bot.peak_equity.max(equity);
+            if bot.peak_equity > Decimal::ZERO {
+                let drawdown = (bot.peak_equity - equity) / bot.peak_equity * dec!(100);
+                bot.max_drawdown_percent = bot.max_drawdown_percent.max(drawdown);
+            }
+        }
+        trades
+    }
+
+    /// Bots ranked by equity at `price`
+    pub fn leaderboard(&self, price: Decimal) -> Vec<Standing> {
+        let mut standings: Vec<Standing> = self.competitors.iter().map(|bot| {
+            let equity = bot.equity(price);
+            let closed = bot.trades / 2;
+            Standing {
+                rank: 0,
+                name: bot.name.clone(),
+                equity,
+                return_percent: if self.initial_balance > Decimal::ZERO {
+                    (equity - self.initial_balance) / self.initial_balance * dec!(100)
+                } else {
+                    Decimal::ZERO
+                },
+                realized_pnl: bot.realized_pnl,
+                trades: bot.trades,
+                win_rate: (closed > 0).then(|| Decimal::from(bot.wins) / Decimal::from(closed) * dec!(100)),
+                max_drawdown_percent: bot.max_drawdown_percent,
+                in_position: !bot.qty.is_zero(),
+            }
+        }).collect();
+        standings.sort_by_key(|s| std::cmp::Reverse(s.equity));
+        for (i, standing) in standings.iter_mut().enumerate() {
+            standing.rank = i + 1;
+        }
+        standings
+    }
+
+    /// Plain-text leaderboard for the competition report file
+    pub fn render(&self, symbol: &str, price: Decimal) -> String {
+        let mut out = String::new();
+        let _ = writeln!(out, "🏁 PAPER-TRADING COMPETITION - {}", symbol);
+        let _ = writeln!(out, "Started {} | {} cycles | price ${}",
+            self.started.format("%Y-%m-%d %H:%M UTC"), self.cycles, price);
+        let _ = writeln!(out, "Each bot started with ${}; stop-loss {}%, take-profit {}%",
+            self.initial_balance, self.stop_loss_percent, self.take_profit_percent);
+        let _ = writeln!(out);
+        let _ = writeln!(out, "{:<4} {:<16} {:>14} {:>9} {:>12} {:>7} {:>8} {:>8}  Pos",
+            "#", "Bot", "Equity", "Return", "Realized", "Trades", "Win %", "Max DD");
+        for s in self.leaderboard(price) {
+            let _ = writeln!(out, "{:<4} {:<16} {:>14} {:>8}% {:>12} {:>7} {:>8} {:>7}%  {}",
+                s.rank, s.name, format!("${:.2}", s.equity), format!("{:+.2}", s.return_percent),
+                format!("${:.2}", s.realized_pnl), s.trades,
+                s.win_rate.map_or("-".to_string(), |w| format!("{:.0}%", w)),
+                format!("{:.2}", s.max_drawdown_percent), if s.in_position { "long" } else { "flat" });
+        }
+        out
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_bots_race_on_the_same_prices() {
+        assert_eq!(BotStrategy::parse("sma:5/20").unwrap(), BotStrategy::Sma { short: 5, long: 20 });
+        assert!(BotStrategy::parse("sma:20/5").is_err());
+        assert!(BotStrategy::parse("macd:12/26").is_err());
+
+        let mut config = Config::defaults();
+        config.simulation_initial_balance = dec!(1000);
+        config.stop_loss_percent = dec!(-5);
+        config.take_profit_percent = dec!(10);
+        config.competition_bots = vec!["sma:3/6".to_string(), "rsi:14/30/70".to_string()];
+        let mut competition = Competition::from_config(&config).unwrap();
+
+        // Steady rise: the SMA bot buys, RSI stays out (overbought, never oversold)
+        let rising: Vec<Decimal> = (0..20).map(|i| Decimal::from(100 + i)).collect();
+        assert_eq!(competition.step(&rising, dec!(100)), vec!["sma:3/6 BUY @ 100".to_string()]);
+        // +12% hits the shared take-profit
+        let trades = competition.step(&rising, dec!(112));
+        assert_eq!(trades, vec!["sma:3/6 SELL @ 112 (take-profit, +12.00%)".to_string()]);
+
+        let board = competition.leaderboard(dec!(112));
+        assert_eq!(board[0].name, "sma:3/6");
+        assert_eq!(board[0].equity, dec!(1120));
+        assert_eq!(board[0].win_rate, Some(dec!(100)));
+        assert_eq!(board[1].return_percent, dec!(0));
+        assert!(competition.render("BTCUSDT", dec!(112)).contains("sma:3/6"));
+    }
+}
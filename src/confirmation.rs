@@ -0,0 +1,430 @@
+use crate::config::Config;
+use crate::i18n::{self, Language, Strings};
+use crate::models::OrderSide;
+use crate::notifier::TelegramNotifier;
+use anyhow::{anyhow, Result};
+use rust_decimal::Decimal;
+use rust_decimal_macros::dec;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+use tokio::sync::Mutex;
+use tracing::{info, warn};
+
+/// Where approval requests are sent
+#[derive(Debug, Clone, PartialEq)]
+pub enum ConfirmationChannel {
+    Telegram,
+    Http,
+}
+
+/// Outcome of an approval request
+#[derive(Debug, Clone, PartialEq)]
+pub enum ConfirmationDecision {
+    Approved { approver: String },
+    Rejected { approver: String },
+    /// Sent and not decided yet; asked about again next cycle
+    Pending,
+}
+
+/// A live order waiting for explicit approval
+#[derive(Debug, Clone, Serialize)]
+pub struct TradeProposal {
+    pub id: String,
+    pub symbol: String,
+    pub side: OrderSide,
+    pub quantity: Decimal,
+    pub price: Decimal,
+    pub notional: Decimal,
+    pub reason: String,
+}
+
+impl TradeProposal {
+    pub fn new(symbol: &str, side: OrderSide, quantity: Decimal, price: Decimal, reason: &str) -> Self {
+        Self {
+            id: format!("{:x}", chrono::Utc::now().timestamp_millis()),
+            symbol: symbol.to_string(),
+            side,
+            quantity,
+            price,
+            notional: quantity * price,
+            reason: reason.to_string(),
+        }
+    }
+}
+
+/// A proposal sent out for approval
+struct Waiting {
+    proposal: TradeProposal,
+    deadline: Instant,
+    decision: Option<ConfirmationDecision>,
+}
+
+/// Proposals waiting for approval, by slot, and where the Telegram chat was read up to
+#[derive(Default)]
+struct PendingProposals {
+    proposals: HashMap<String, Waiting>,
+    telegram_offset: Option<i64>,
+}
+
+#[derive(Debug, Deserialize)]
+struct HttpDecision {
+    status: String,
+    #[serde(default)]
+    approver: Option<String>,
+}
+
+/// Approval workflow for live orders above a notional threshold.
+/// Only users listed as approvers can confirm or reject a proposal.
+pub struct TradeConfirmer {
+    channel: ConfirmationChannel,
+    min_notional: Decimal,
+    timeout: Duration,
+    price_tolerance: Decimal,
+    approvers: Vec<String>,
+    telegram: Option<TelegramNotifier>,
+    http_url: Option<String>,
+    client: reqwest::Client,
+    strings: &'static Strings,
+    pending: Mutex<PendingProposals>,
+}
+
+impl TradeConfirmer {
+    /// Build the confirmer from config; returns None when confirmation is disabled
+    pub fn from_config(config: &Config) -> Result<Option<Self>> {
+        let Some(min_notional) = config.confirm_min_notional else {
+            return Ok(None);
+        };
+
+        let channel = match config.confirm_channel.to_lowercase().as_str() {
+            "telegram" => ConfirmationChannel::Telegram,
+            "http" => ConfirmationChannel::Http,
+            other => return Err(anyhow!("Unsupported CONFIRM_CHANNEL: {}", other)),
+        };
+
+        let telegram = if channel == ConfirmationChannel::Telegram {
+            match (&config.telegram_bot_token, &config.telegram_chat_id) {
+                (Some(token), Some(chat)) => Some(TelegramNotifier::new(token.expose(), chat)?),
+                _ => return Err(anyhow!(
+                    "CONFIRM_CHANNEL=telegram requires TELEGRAM_BOT_TOKEN and TELEGRAM_CHAT_ID"
+                )),
+            }
+        } else {
+            None
+        };
+
+        if channel == ConfirmationChannel::Http && config.confirm_http_url.is_none() {
+            return Err(anyhow!("CONFIRM_CHANNEL=http requires CONFIRM_HTTP_URL"));
+        }
+
+        if config.confirm_price_tolerance_percent <= Decimal::ZERO {
+            return Err(anyhow!("CONFIRM_PRICE_TOLERANCE_PERCENT must be positive, got {}",
+                config.confirm_price_tolerance_percent));
+        }
+
+        // Without approvers anyone in the chat, or any HTTP caller, could decide
+        if config.confirm_approvers.is_empty() {
+            return Err(anyhow!("CONFIRM_MIN_NOTIONAL requires CONFIRM_APPROVERS"));
+        }
+
+        Ok(Some(Self {
+            channel,
+            min_notional,
+            timeout: Duration::from_secs(config.confirm_timeout_mins * 60),
+            price_tolerance: config.confirm_price_tolerance_percent,
+            approvers: config.confirm_approvers.clone(),
+            telegram,
+            http_url: config.confirm_http_url.clone(),
+            client: reqwest::Client::builder()
+                .timeout(Duration::from_secs(30))
+                .build()?,
+            strings: Language::parse(&config.report_language).strings(),
+            pending: Mutex::default(),
+        }))
+    }
+
+    /// Orders at or above the configured notional need explicit approval
+    pub fn requires_confirmation(&self, notional: Decimal) -> bool {
+        notional >= self.min_notional
+    }
+
+    /// Whether the market is still within CONFIRM_PRICE_TOLERANCE_PERCENT of the proposed
+    /// price, so an approved trade can go out as it was proposed
+    pub fn price_holds(&self, proposal: &TradeProposal, price: Decimal) -> bool {
+        if proposal.price.is_zero() {
+            return false;
+        }
+        (price - proposal.price).abs() / proposal.price * dec!(100) <= self.price_tolerance
+    }
+
+    /// Whether the sender is listed in CONFIRM_APPROVERS; a decision without a sender never is
+    fn is_approver(&self, id: &str, username: Option<&str>) -> bool {
+        if id.is_empty() && username.is_none_or(str::is_empty) {
+            return false;
+        }
+        self.approvers.iter().any(|a| {
+            let a = a.trim_start_matches('@');
+            a == id || username.is_some_and(|u| u.eq_ignore_ascii_case(a))
+        })
+    }
+
+    /// Propose the trade, or check on the proposal already out for `slot` (a position or
+    /// sleeve and the side it trades). Never waits for the decision: until one arrives it
+    /// returns `Pending` right away and is asked again next cycle, so exits of other
+    /// positions keep being checked. Returns the decision with the proposal it is about.
+    pub async fn request(&self, slot: &str, proposal: TradeProposal) -> Result<(ConfirmationDecision, TradeProposal)> {
+        let mut pending = self.pending.lock().await;
+        if self.channel == ConfirmationChannel::Telegram && !pending.proposals.is_empty() {
+            self.poll_telegram(&mut pending).await;
+        }
+        self.expire(&mut pending).await;
+
+        let Some(waiting) = pending.proposals.get_mut(slot) else {
+            info!("✋ Requesting approval for {} {} {} (notional ${:.2}, id {})",
+                proposal.side, proposal.quantity.round_dp(6), proposal.symbol,
+                proposal.notional, proposal.id);
+            match self.channel {
+                ConfirmationChannel::Telegram => self.propose_telegram(&proposal, &mut pending.telegram_offset).await?,
+                ConfirmationChannel::Http => self.propose_http(&proposal).await?,
+            }
+            pending.proposals.insert(slot.to_string(), Waiting {
+                proposal: proposal.clone(),
+                deadline: Instant::now() + self.timeout,
+                decision: None,
+            });
+            return Ok((ConfirmationDecision::Pending, proposal));
+        };
+        if self.channel == ConfirmationChannel::Http && waiting.decision.is_none() {
+            waiting.decision = self.poll_http(&waiting.proposal).await;
+        }
+        let Some(decision) = waiting.decision.clone() else {
+            return Ok((ConfirmationDecision::Pending, waiting.proposal.clone()));
+        };
+        let proposal = pending.proposals.remove(slot).map(|w| w.proposal).unwrap_or(proposal);
+        match &decision {
+            ConfirmationDecision::Approved { approver } => info!("✅ Trade {} approved by {}", proposal.id, approver),
+            ConfirmationDecision::Rejected { approver } => warn!("🚫 Trade {} rejected by {}", proposal.id, approver),
+            _ => {}
+        }
+        Ok((decision, proposal))
+    }
+
+    /// Drop proposals nobody decided on within the timeout, and decided ones the loop
+    /// stopped asking about
+    async fn expire(&self, pending: &mut PendingProposals) {
+        let now = Instant::now();
+        let expired: Vec<String> = pending.proposals.iter()
+            .filter(|(_, w)| w.deadline <= now)
+            .map(|(slot, _)| slot.clone())
+            .collect();
+        for slot in expired {
+            let Some(waiting) = pending.proposals.remove(&slot) else {
+                continue;
+            };
+            if waiting.decision.is_some() {
+                continue;
+            }
+            warn!("⌛ Trade {} not confirmed within {} min, skipping", waiting.proposal.id, self.timeout.as_secs() / 60);
+            if let Some(telegram) = &self.telegram {
+                let expired = i18n::fill(self.strings.trade_expired, &[("id", &waiting.proposal.id)]);
+                telegram.send_message(&format!("⌛ {}", expired)).await.ok();
+            }
+        }
+    }
+
+    async fn propose_telegram(&self, proposal: &TradeProposal, offset: &mut Option<i64>) -> Result<()> {
+        let telegram = self.telegram.as_ref().ok_or_else(|| anyhow!("Telegram not configured"))?;
+
+        // Skip any backlog so old commands cannot approve this proposal
+        if offset.is_none() {
+            *offset = Some(telegram.get_updates(-1, 0).await?.0);
+        }
+
+        let t = self.strings;
+        let mins = self.timeout.as_secs() / 60;
+        telegram.send_message(&format!(
+            "✋ {requested} [{id}]\n{side} {qty} {symbol} @ ${price:.2}\n{notional_label} ${notional:.2}\n{reason_label} {reason}\n\n{reply}",
+            requested = t.approval_requested,
+            id = proposal.id,
+            side = proposal.side,
+            qty = proposal.quantity.round_dp(6),
+            symbol = proposal.symbol,
+            price = proposal.price,
+            notional_label = t.notional,
+            notional = proposal.notional,
+            reason_label = t.reason,
+            reason = proposal.reason,
+            reply = i18n::fill(t.approval_reply, &[("id", &proposal.id), ("mins", &mins)]),
+        )).await?;
+        Ok(())
+    }
+
+    /// Read the chat once, without waiting, and note the decisions on any pending proposal
+    async fn poll_telegram(&self, pending: &mut PendingProposals) {
+        let Some(telegram) = &self.telegram else {
+            return;
+        };
+        let (next, messages) = match telegram.get_updates(pending.telegram_offset.unwrap_or(-1), 0).await {
+            Ok(r) => r,
+            Err(e) => {
+                warn!("⚠️ Telegram poll failed: {}", e);
+                return;
+            }
+        };
+        pending.telegram_offset = Some(next);
+
+        let t = self.strings;
+        for msg in messages {
+            for waiting in pending.proposals.values_mut().filter(|w| w.decision.is_none()) {
+                let Some(command) = parse_command(&msg.text, &waiting.proposal.id) else {
+                    continue;
+                };
+                if !self.is_approver(&msg.from_id, msg.from_username.as_deref()) {
+                    warn!("Ignoring {} from non-approver {}", msg.text, msg.from_id);
+                    continue;
+                }
+                let approver = msg.from_username.clone().unwrap_or(msg.from_id.clone());
+                let (decision, template) = if command {
+                    (ConfirmationDecision::Approved { approver: approver.clone() }, t.trade_approved)
+                } else {
+                    (ConfirmationDecision::Rejected { approver: approver.clone() }, t.trade_rejected)
+                };
+                let reply = i18n::fill(template, &[("id", &waiting.proposal.id), ("approver", &approver)]);
+                telegram.send_message(&reply).await.ok();
+                waiting.decision = Some(decision);
+            }
+        }
+    }
+
+    async fn propose_http(&self, proposal: &TradeProposal) -> Result<()> {
+        let url = self.http_url.as_ref().ok_or_else(|| anyhow!("CONFIRM_HTTP_URL not configured"))?;
+
+        let response = self.client.post(url).json(proposal).send().await?;
+        if !response.status().is_success() {
+            return Err(anyhow!("Approval endpoint error: {}", response.status()));
+        }
+        Ok(())
+    }
+
+    /// Ask the approval endpoint once for the decision on `proposal`
+    async fn poll_http(&self, proposal: &TradeProposal) -> Option<ConfirmationDecision> {
+        let url = self.http_url.as_ref()?;
+        let status_url = format!("{}/{}", url.trim_end_matches('/'), proposal.id);
+        let decision: HttpDecision = match self.client.get(&status_url).send().await {
+            Ok(r) => match r.json().await {
+                Ok(d) => d,
+                Err(e) => {
+                    warn!("⚠️ Invalid approval response: {}", e);
+                    return None;
+                }
+            },
+            Err(e) => {
+                warn!("⚠️ Approval poll failed: {}", e);
+                return None;
+            }
+        };
+
+        let approver = decision.approver.unwrap_or_default();
+        match decision.status.to_lowercase().as_str() {
+            "approved" | "confirmed" if self.is_approver(&approver, None) => {
+                Some(ConfirmationDecision::Approved { approver })
+            }
+            "rejected" if self.is_approver(&approver, None) => {
+                Some(ConfirmationDecision::Rejected { approver })
+            }
+            "approved" | "confirmed" | "rejected" => {
+                warn!("Ignoring decision from non-approver '{}'", approver);
+                None
+            }
+            _ => None,
+        }
+    }
+}
+
+/// Parse "/confirm <id>" or "/reject <id>"; returns Some(true) for confirm, Some(false) for reject
+fn parse_command(text: &str, proposal_id: &str) -> Option<bool> {
+    let mut parts = text.split_whitespace();
+    let command = parts.next()?.split('@').next()?.to_lowercase();
+    if parts.next()? != proposal_id {
+        return None;
+    }
+    match command.as_str() {
+        "/confirm" | "/approve" => Some(true),
+        "/reject" | "/deny" => Some(false),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_only_listed_approvers_decide() {
+        let mut config = Config::defaults();
+        config.confirm_min_notional = Some(dec!(500));
+        config.confirm_channel = "http".to_string();
+        config.confirm_http_url = Some("http://127.0.0.1:9/approvals".to_string());
+        assert!(TradeConfirmer::from_config(&config).is_err());
+
+        config.confirm_approvers = vec!["alice".to_string(), "@bob".to_string()];
+        let confirmer = TradeConfirmer::from_config(&config).unwrap().unwrap();
+        assert!(confirmer.is_approver("alice", None));
+        assert!(confirmer.is_approver("42", Some("Bob")));
+        assert!(!confirmer.is_approver("mallory", None));
+        assert!(!confirmer.is_approver("", None));
+
+        // The price moved 0.4% and then 0.6% while the proposal waited (tolerance 0.5%)
+        let proposal = TradeProposal::new("BTCUSDT", OrderSide::Buy, dec!(0.01), dec!(50000), "Buy target");
+        assert!(confirmer.price_holds(&proposal, dec!(50200)));
+        assert!(!confirmer.price_holds(&proposal, dec!(49700)));
+    }
+
+    #[test]
+    fn test_parse_command() {
+        assert_eq!(parse_command("/confirm abc123", "abc123"), Some(true));
+        assert_eq!(parse_command("/reject@MyTradingBot abc123", "abc123"), Some(false));
+        assert_eq!(parse_command("/confirm other", "abc123"), None);
+        assert_eq!(parse_command("/confirm", "abc123"), None);
+        assert_eq!(parse_command("hello abc123", "abc123"), None);
+    }
+
+    #[tokio::test]
+    async fn test_request_does_not_wait_for_the_decision() {
+        use axum::routing::{get, post};
+        use std::sync::atomic::{AtomicU32, Ordering};
+        use std::sync::Arc;
+
+        // The endpoint decides on the second status poll
+        let polls = Arc::new(AtomicU32::new(0));
+        let counter = polls.clone();
+        let app = axum::Router::new()
+            .route("/approvals", post(|| async { "" }))
+            .route("/approvals/{id}", get(move || async move {
+                match counter.fetch_add(1, Ordering::SeqCst) {
+                    0 => r#"{"status":"pending"}"#,
+                    _ => r#"{"status":"approved","approver":"alice"}"#,
+                }
+            }));
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move { axum::serve(listener, app).await });
+
+        let mut config = Config::defaults();
+        config.confirm_min_notional = Some(dec!(500));
+        config.confirm_channel = "http".to_string();
+        config.confirm_http_url = Some(format!("http://{}/approvals", addr));
+        config.confirm_approvers = vec!["alice".to_string()];
+        let confirmer = TradeConfirmer::from_config(&config).unwrap().unwrap();
+        let propose = |price| TradeProposal::new("BTCUSDT", OrderSide::Buy, dec!(0.02), price, "Buy target");
+
+        let (decision, first) = confirmer.request("p1:BUY", propose(dec!(50000))).await.unwrap();
+        assert_eq!(decision, ConfirmationDecision::Pending);
+        assert_eq!(confirmer.request("p1:BUY", propose(dec!(50100))).await.unwrap().0, ConfirmationDecision::Pending);
+        // The decision is about the proposal that was sent, not the one asked with
+        let (decision, approved) = confirmer.request("p1:BUY", propose(dec!(50200))).await.unwrap();
+        assert_eq!(decision, ConfirmationDecision::Approved { approver: "alice".to_string() });
+        assert_eq!((approved.id, approved.price), (first.id, dec!(50000)));
+        assert!(confirmer.pending.lock().await.proposals.is_empty());
+    }
+}
@@ -0,0 +1,241 @@
+use crate::models::OrderSide;
+use crate::portfolio::PortfolioStatus;
+use crate::position::{Position, MAIN_POSITION};
+use chrono::{DateTime, Utc};
+use rust_decimal::Decimal;
+use std::collections::VecDeque;
+use std::sync::{Arc, Mutex};
+use tokio::sync::oneshot;
+use tracing::info;
+
+/// Manual orders waiting for the next cycle; further ones are refused
+const MAX_QUEUED_ORDERS: usize = 8;
+
+/// What became of a manual order: whether it traded and the cycle's decision for the slot
+#[derive(Debug, Clone, PartialEq)]
+pub struct OrderOutcome {
+    pub placed: bool,
+    pub message: String,
+}
+
+/// A buy or sell requested from outside the loop, executed at the next cycle's price in
+/// place of the slot's targets. Everything else still applies: a buy waits for no buy target
+/// but respects pauses, the daily trade limit and the risk limits.
+pub struct ManualOrder {
+    pub side: OrderSide,
+    /// Position slot, the main one when unset
+    slot: Option<String>,
+    reply: oneshot::Sender<OrderOutcome>,
+}
+
+impl ManualOrder {
+    fn slot(&self) -> &str {
+        self.slot.as_deref().unwrap_or(MAIN_POSITION)
+    }
+
+    /// A buy for a flat slot, or a sell for an open one
+    pub fn applies_to(&self, position: &Position) -> bool {
+        position.id == self.slot() && match self.side {
+            OrderSide::Buy => position.is_flat(),
+            OrderSide::Sell => position.is_open(),
+        }
+    }
+
+    /// Report the decision the cycle took for the slot
+    pub fn answer(self, position: &Position, decision: &str) {
+        let placed = match self.side {
+            OrderSide::Buy => !position.is_flat(),
+            OrderSide::Sell => !position.is_open(),
+        };
+        info!("🕹️ Manual {} for {}: {}", self.side, self.slot(), decision);
+        let _ = self.reply.send(OrderOutcome { placed, message: decision.to_string() });
+    }
+
+    /// The order could not apply this cycle, e.g. no targets yet or nothing to sell
+    pub fn decline(self, reason: &str) {
+        info!("🕹️ Manual {} for {} not placed: {}", self.side, self.slot(), reason);
+        let _ = self.reply.send(OrderOutcome { placed: false, message: format!("not placed - {}", reason) });
+    }
+
+    /// Why no slot took the order
+    pub fn unmatched_reason(&self, positions: &[Position]) -> String {
+        match positions.iter().find(|p| p.id == self.slot()) {
+            None => format!("no {} position slot", self.slot()),
+            Some(p) if self.side == OrderSide::Sell => format!("the {} position is {}, nothing to sell", p.id, p.state),
+            Some(p) => format!("the {} position is already {}", p.id, p.state),
+        }
+    }
+}
+
+/// One position as the control interface reports it
+#[derive(Debug, Clone, PartialEq)]
+pub struct PositionSummary {
+    pub id: String,
+    pub state: String,
+    pub short: bool,
+    pub qty: Decimal,
+    pub entry_price: Option<Decimal>,
+    pub stop_loss: Option<Decimal>,
+    pub take_profit: Option<Decimal>,
+}
+
+/// The bot's state as of the last cycle
+#[derive(Debug, Clone, PartialEq)]
+pub struct BotStatus {
+    pub symbol: String,
+    pub updated_at: DateTime<Utc>,
+    pub price: Decimal,
+    pub portfolio_value: Decimal,
+    pub positions: Vec<PositionSummary>,
+    pub realized_pnl: Decimal,
+    pub trades_today: u32,
+    pub max_trades_per_day: u32,
+    pub entries_paused: Option<String>,
+    pub recommendation: Option<String>,
+    pub confidence: Option<Decimal>,
+    pub stop_loss: Option<Decimal>,
+    pub take_profit: Option<Decimal>,
+    pub buy_target: Option<Decimal>,
+    pub sell_target: Option<Decimal>,
+}
+
+#[derive(Default)]
+struct ControlState {
+    paused: Option<String>,
+    orders: VecDeque<ManualOrder>,
+    status: Option<BotStatus>,
+}
+
+/// Pause/resume and manual orders from the control interfaces, and the status they read.
+/// The loop polls it once per cycle. Cheap to clone; clones share the same state.
+#[derive(Clone, Default)]
+pub struct BotControl {
+    state: Arc<Mutex<ControlState>>,
+}
+
+impl BotControl {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Hold back new entries until `resume`; exits carry on. False when already paused.
+    pub fn pause(&self, reason: &str) -> bool {
+        let mut state = self.state.lock().unwrap();
+        if state.paused.is_some() {
+            return false;
+        }
+        let reason = if reason.trim().is_empty() { "paused by the operator".to_string() } else { format!("paused - {}", reason.trim()) };
+        info!("⏸️ Entries {}", reason);
+        state.paused = Some(reason);
+        true
+    }
+
+    /// False when not paused
+    pub fn resume(&self) -> bool {
+        let resumed = self.state.lock().unwrap().paused.take().is_some();
+        if resumed {
+            info!("▶️ Entries resumed by the operator");
+        }
+        resumed
+    }
+
+    pub fn pause_reason(&self) -> Option<String> {
+        self.state.lock().unwrap().paused.clone()
+    }
+
+    /// Queue a manual order for the next cycle; its outcome arrives on the receiver.
+    /// `None` while the queue is full.
+    pub fn submit(&self, side: OrderSide, slot: Option<String>) -> Option<oneshot::Receiver<OrderOutcome>> {
+        let mut state = self.state.lock().unwrap();
+        if state.orders.len() >= MAX_QUEUED_ORDERS {
+            return None;
+        }
+        let (reply, outcome) = oneshot::channel();
+        state.orders.push_back(ManualOrder { side, slot: slot.filter(|s| !s.is_empty()), reply });
+        Some(outcome)
+    }
+
+    /// The next queued manual order whose caller is still waiting; one per cycle
+    pub fn take_order(&self) -> Option<ManualOrder> {
+        let mut state = self.state.lock().unwrap();
+        while let Some(order) = state.orders.pop_front() {
+            if !order.reply.is_closed() {
+                return Some(order);
+            }
+            info!("🕹️ Dropping a manual {} for {} - the caller stopped waiting", order.side, order.slot());
+        }
+        None
+    }
+
+    /// Record this cycle's status for the control interfaces
+    pub fn update(&self, s: &PortfolioStatus) {
+        let positions = s.positions.iter().map(|p| PositionSummary {
+            id: p.id.clone(),
+            state: p.state.to_string(),
+            short: p.short,
+            qty: p.qty,
+            entry_price: p.entry_price,
+            stop_loss: p.stop_loss,
+            take_profit: p.take_profit,
+        }).collect();
+        self.state.lock().unwrap().status = Some(BotStatus {
+            symbol: s.symbol.clone(),
+            updated_at: Utc::now(),
+            price: s.current_price,
+            portfolio_value: s.total_portfolio_value,
+            positions,
+            realized_pnl: s.realized_pnl,
+            trades_today: s.trades_today,
+            max_trades_per_day: s.max_trades_per_day,
+            entries_paused: s.entries_paused.clone(),
+            recommendation: s.ai_recommendation.as_ref().map(ToString::to_string),
+            confidence: s.ai_confidence,
+            stop_loss: s.stop_loss_price,
+            take_profit: s.take_profit_price,
+            buy_target: s.buy_target_price,
+            sell_target: s.sell_target_price,
+        });
+    }
+
+    /// `None` before the first cycle
+    pub fn status(&self) -> Option<BotStatus> {
+        self.state.lock().unwrap().status.clone()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rust_decimal_macros::dec;
+
+    #[tokio::test]
+    async fn test_pause_and_manual_orders() {
+        let control = BotControl::new();
+        assert!(control.pause(" news "));
+        assert!(!control.pause("again"));
+        assert_eq!(control.pause_reason().as_deref(), Some("paused - news"));
+        assert!(control.resume());
+        assert!(!control.resume());
+
+        let mut main = Position::new("BTCUSDT");
+        let sell = control.submit(OrderSide::Sell, None).unwrap();
+        let buy = control.submit(OrderSide::Buy, Some("main".to_string())).unwrap();
+        let order = control.take_order().unwrap();
+        assert!(!order.applies_to(&main));
+        let reason = order.unmatched_reason(std::slice::from_ref(&main));
+        order.decline(&reason);
+        assert_eq!(sell.await.unwrap().message, "not placed - the main position is CLOSED, nothing to sell");
+
+        let order = control.take_order().unwrap();
+        assert!(order.applies_to(&main));
+        main.sync(dec!(0.1), Some(dec!(64000)));
+        order.answer(&main, "entered @ 64000.00");
+        assert_eq!(buy.await.unwrap(), OrderOutcome { placed: true, message: "entered @ 64000.00".to_string() });
+        assert!(control.take_order().is_none());
+
+        for _ in 0..MAX_QUEUED_ORDERS {
+            assert!(control.submit(OrderSide::Buy, None).is_some());
+        }
+        assert!(control.submit(OrderSide::Buy, None).is_none());
+    }
+}
@@ -0,0 +1,139 @@
+use crate::config::Config;
+use crate::models::Kline;
+use crate::position::Position;
+use chrono::{DateTime, Duration, Utc};
+use rust_decimal::Decimal;
+use std::fmt;
+use tracing::warn;
+
+/// Binance serves at most this many candles per request
+const MAX_CANDLES: i64 = 1000;
+
+/// Time between two cycles that checked a price, on the wall clock so a host that slept
+/// counts too (the monotonic clock stops while it does)
+pub struct CycleClock {
+    threshold: Duration,
+    last: Option<DateTime<Utc>>,
+}
+
+impl CycleClock {
+    /// `None` with CYCLE_GAP_SECS=0
+    pub fn from_config(config: &Config) -> Option<Self> {
+        (config.cycle_gap_secs > 0).then(|| Self {
+            threshold: Duration::seconds(config.cycle_gap_secs as i64),
+            last: None,
+        })
+    }
+
+    /// A price was checked at `now`; the gap since the previous check when it exceeds
+    /// CYCLE_GAP_SECS. A clock stepped backwards just restarts the count.
+    pub fn tick(&mut self, now: DateTime<Utc>) -> Option<CycleGap> {
+        let last = self.last.replace(now)?;
+        if now < last {
+            warn!("⏰ Clock went back {}s since the last cycle - ignoring the step", (last - now).num_seconds());
+            return None;
+        }
+        (now - last > self.threshold).then_some(CycleGap { from: last, to: now })
+    }
+}
+
+/// A stretch without price checks: the host slept, the process stalled or the price feed was down
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct CycleGap {
+    pub from: DateTime<Utc>,
+    pub to: DateTime<Utc>,
+}
+
+impl CycleGap {
+    pub fn length(&self) -> Duration {
+        self.to - self.from
+    }
+
+    /// Candle interval and count covering the gap: minutes while they fit in one request, hours beyond
+    pub fn candles(&self) -> (&'static str, u32) {
+        let minutes = self.length().num_minutes() + 2;
+        if minutes <= MAX_CANDLES {
+            ("1m", minutes as u32)
+        } else {
+            ("1h", (self.length().num_hours() + 2).min(MAX_CANDLES) as u32)
+        }
+    }
+
+    /// Lowest low and highest high of the candles overlapping the gap
+    pub fn price_range(&self, klines: &[Kline]) -> Option<(Decimal, Decimal)> {
+        let (from, to) = (self.from.timestamp_millis(), self.to.timestamp_millis());
+        let overlapping = klines.iter().filter(|k| k.close_time >= from && k.open_time <= to);
+        overlapping.fold(None, |range, k| match range {
+            None => Some((k.low, k.high)),
+            Some((low, high)) => Some((low.min(k.low), high.max(k.high))),
+        })
+    }
+}
+
+/// "7m 30s without a price check (since 14:02:05 UTC)"
+impl fmt::Display for CycleGap {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let seconds = self.length().num_seconds();
+        if seconds >= 3600 {
+            write!(f, "{}h {}m", seconds / 3600, seconds % 3600 / 60)?;
+        } else {
+            write!(f, "{}m {}s", seconds / 60, seconds % 60)?;
+        }
+        write!(f, " without a price check (since {})", self.from.format("%H:%M:%S UTC"))
+    }
+}
+
+/// Flag the open positions whose stop the price crossed within `low`..`high` during the gap;
+/// they exit as stop-losses at this cycle's price. Ids of the flagged positions.
+pub fn recheck<'a>(positions: impl IntoIterator<Item = &'a mut Position>, low: Decimal, high: Decimal) -> Vec<String> {
+    positions.into_iter()
+        .filter(|p| p.is_open())
+        .filter_map(|p| p.check_missed_stop(low, high).then(|| p.id.clone()))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::position::ExitTrigger;
+    use rust_decimal_macros::dec;
+
+    fn minute(start: DateTime<Utc>, i: i64, low: Decimal, high: Decimal) -> Kline {
+        let open_time = start.timestamp_millis() + i * 60_000;
+        Kline { open_time, open: low, high, low, close: high, volume: dec!(1), close_time: open_time + 59_999 }
+    }
+
+    #[test]
+    fn test_gap_rechecks_stops_against_missed_candles() {
+        let mut config = Config::defaults();
+        config.cycle_gap_secs = 120;
+        let mut clock = CycleClock::from_config(&config).unwrap();
+        let start = DateTime::parse_from_rfc3339("2026-03-02T14:02:05Z").unwrap().with_timezone(&Utc);
+        assert_eq!(clock.tick(start), None);
+        assert_eq!(clock.tick(start + Duration::seconds(30)), None);
+        // Clock stepped back, then the host slept 7.5 minutes
+        assert_eq!(clock.tick(start), None);
+        let gap = clock.tick(start + Duration::seconds(450)).unwrap();
+        assert_eq!(gap.to_string(), "7m 30s without a price check (since 14:02:05 UTC)");
+        assert_eq!(gap.candles(), ("1m", 9));
+        assert_eq!(CycleGap { from: start, to: start + Duration::days(2) }.candles(), ("1h", 50));
+
+        // Only the candles overlapping the gap count
+        let klines = [minute(start, -5, dec!(80), dec!(120)), minute(start, 1, dec!(94), dec!(101)),
+                      minute(start, 4, dec!(97), dec!(104))];
+        let (low, high) = gap.price_range(&klines).unwrap();
+        assert_eq!((low, high), (dec!(94), dec!(104)));
+
+        let mut long = Position::resumed("BTCUSDT", dec!(1), Some(dec!(100)));
+        long.set_percent_targets(dec!(-5), dec!(10));
+        let mut short = Position::new_short("BTCUSDT");
+        short.sync(dec!(1), Some(dec!(100)));
+        short.set_percent_targets(dec!(-5), dec!(10));
+        assert_eq!(recheck([&mut long, &mut short], low, high), ["main"]);
+        // Back above the stop, the long still exits as a stop-loss
+        assert_eq!(long.exit_trigger(dec!(99)), Some(ExitTrigger::StopLoss));
+        assert_eq!(short.exit_trigger(dec!(99)), None);
+        assert_eq!(long.excursion.mae_percent, dec!(-6));
+        assert!(CycleClock::from_config(&Config { cycle_gap_secs: 0, ..config }).is_none());
+    }
+}
@@ -0,0 +1,294 @@
+use crate::coingecko::{CoinGeckoMarketData, OhlcData};
+use crate::config::Config;
+use crate::error::{BotError, ReqwestExt};
+use anyhow::{anyhow, Result};
+use chrono::Utc;
+use rust_decimal::Decimal;
+use serde::Deserialize;
+use std::collections::{BTreeMap, VecDeque};
+use std::str::FromStr;
+use std::time::Duration;
+use tracing::info;
+
+/// Price samples older than this are dropped (the longest window the AI prompt uses)
+const HISTORY_SECS: i64 = 48 * 3600;
+
+/// A token contract on a chain, from `DEX_TOKEN=<chain>:<address>`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DexToken {
+    /// DexScreener chain id: `ethereum`, `base`, `arbitrum`, `solana`, ...
+    pub chain: String,
+    pub address: String,
+}
+
+impl DexToken {
+    pub fn parse(value: &str) -> Result<Self> {
+        match value.split_once(':') {
+            Some((chain, address)) if !chain.trim().is_empty() && !address.trim().is_empty() => Ok(Self {
+                chain: chain.trim().to_lowercase(),
+                address: address.trim().to_string(),
+            }),
+            _ => Err(BotError::config(format!("DEX_TOKEN must be <chain>:<address>, got '{}'", value)).into()),
+        }
+    }
+
+    fn is_solana(&self) -> bool {
+        self.chain == "solana"
+    }
+}
+
+/// Latest on-chain price of a token. Jupiter only reports the price; pool statistics come
+/// from the most liquid Uniswap pool on EVM chains.
+#[derive(Debug, Clone, PartialEq)]
+pub struct DexQuote {
+    pub price_usd: Decimal,
+    pub liquidity_usd: Option<Decimal>,
+    pub volume_24h: Option<Decimal>,
+    pub price_change_24h_percent: Option<Decimal>,
+    /// Where the price came from, e.g. `uniswap v3` or `jupiter`
+    pub source: String,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(rename_all = "camelCase")]
+struct DexScreenerPair {
+    dex_id: String,
+    #[serde(default)]
+    labels: Vec<String>,
+    base_token: PairToken,
+    price_usd: Option<String>,
+    #[serde(default)]
+    liquidity: Option<PairLiquidity>,
+    #[serde(default)]
+    volume: Option<PairWindow>,
+    #[serde(default)]
+    price_change: Option<PairWindow>,
+}
+
+#[derive(Debug, Deserialize)]
+struct PairToken {
+    address: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct PairLiquidity {
+    usd: Option<f64>,
+}
+
+#[derive(Debug, Deserialize)]
+struct PairWindow {
+    h24: Option<f64>,
+}
+
+#[derive(Debug, Deserialize)]
+struct JupiterResponse {
+    data: BTreeMap<String, Option<JupiterPrice>>,
+}
+
+#[derive(Debug, Deserialize)]
+struct JupiterPrice {
+    price: String,
+}
+
+/// The deepest Uniswap pool quoting `address` as its base token
+fn best_uniswap_pool(pairs: Vec<DexScreenerPair>, address: &str) -> Option<DexQuote> {
+    let to_decimal = |v: Option<f64>| v.and_then(|v| Decimal::try_from(v).ok());
+    pairs.into_iter()
+        .filter(|p| p.dex_id.starts_with("uniswap") && p.base_token.address.eq_ignore_ascii_case(address))
+        .filter_map(|p| {
+            let price_usd = Decimal::from_str(p.price_usd.as_deref()?).ok()?;
+            Some(DexQuote {
+                price_usd,
+                liquidity_usd: to_decimal(p.liquidity.and_then(|l| l.usd)),
+                volume_24h: to_decimal(p.volume.and_then(|v| v.h24)),
+                price_change_24h_percent: to_decimal(p.price_change.and_then(|c| c.h24)),
+                source: match p.labels.first() {
+                    Some(version) => format!("{} {}", p.dex_id, version),
+                    None => p.dex_id,
+                },
+            })
+        })
+        .max_by_key(|q| q.liquidity_usd.unwrap_or_default())
+}
+
+/// Public DexScreener (Uniswap pools) and Jupiter (Solana) price APIs; no key needed
+pub struct DexClient {
+    client: reqwest::Client,
+    dexscreener_url: String,
+    jupiter_url: String,
+}
+
+impl DexClient {
+    pub fn new() -> Result<Self> {
+        Ok(Self {
+            client: reqwest::Client::builder().timeout(Duration::from_secs(15)).build()?,
+            dexscreener_url: "https://api.dexscreener.com".to_string(),
+            jupiter_url: "https://lite-api.jup.ag/price/v2".to_string(),
+        })
+    }
+
+    pub async fn fetch_quote(&self, token: &DexToken) -> Result<DexQuote> {
+        if token.is_solana() {
+            self.fetch_jupiter(token).await
+        } else {
+            self.fetch_uniswap(token).await
+        }
+    }
+
+    async fn fetch_uniswap(&self, token: &DexToken) -> Result<DexQuote> {
+        let url = format!("{}/token-pairs/v1/{}/{}", self.dexscreener_url, token.chain, token.address);
+        let response = self.client.get(&url).send().await.or_data_source("DexScreener")?;
+        let status = response.status();
+        if !status.is_success() {
+            let body = response.text().await.unwrap_or_default();
+            return Err(BotError::data_source("DexScreener", status, &body).into());
+        }
+        let pairs: Vec<DexScreenerPair> = response.json().await.or_data_source("DexScreener")?;
+        best_uniswap_pool(pairs, &token.address)
+            .ok_or_else(|| anyhow!("No Uniswap pool for {} on {}", token.address, token.chain))
+    }
+
+    async fn fetch_jupiter(&self, token: &DexToken) -> Result<DexQuote> {
+        let url = format!("{}?ids={}", self.jupiter_url, token.address);
+        let response = self.client.get(&url).send().await.or_data_source("Jupiter")?;
+        let status = response.status();
+        if !status.is_success() {
+            let body = response.text().await.unwrap_or_default();
+            return Err(BotError::data_source("Jupiter", status, &body).into());
+        }
+        let body: JupiterResponse = response.json().await.or_data_source("Jupiter")?;
+        let price = body.data.get(&token.address)
+            .and_then(|p| p.as_ref())
+            .ok_or_else(|| anyhow!("Jupiter has no price for {}", token.address))?;
+        Ok(DexQuote {
+            price_usd: Decimal::from_str(&price.price)?,
+            liquidity_usd: None,
+            volume_24h: None,
+            price_change_24h_percent: None,
+            source: "jupiter".to_string(),
+        })
+    }
+}
+
+/// Market data for a token without a reliable CoinGecko listing. DEX APIs don't serve history,
+/// so hourly candles are built from the prices sampled each cycle and fill in over 48 hours.
+pub struct DexFeed {
+    client: DexClient,
+    token: DexToken,
+    /// (unix seconds, price)
+    samples: VecDeque<(i64, Decimal)>,
+}
+
+impl DexFeed {
+    /// `None` unless DEX_TOKEN is set
+    pub fn from_config(config: &Config) -> Result<Option<Self>> {
+        let Some(token) = config.dex_token.as_deref() else {
+            return Ok(None);
+        };
+        let token = DexToken::parse(token)?;
+        info!("🦄 DEX price feed for {} on {}", token.address, token.chain);
+        Ok(Some(Self { client: DexClient::new()?, token, samples: VecDeque::new() }))
+    }
+
+    /// Fetch the current price and return it in the shape the rest of the bot expects from CoinGecko
+    pub async fn fetch_market_data(&mut self, symbol: &str) -> Result<CoinGeckoMarketData> {
+        let quote = self.client.fetch_quote(&self.token).await?;
+        self.record(Utc::now().timestamp(), quote.price_usd);
+        info!("🦄 {} @ ${} ({}{})", symbol, quote.price_usd, quote.source,
+            quote.liquidity_usd.map_or(String::new(), |l| format!(", ${:.0} liquidity", l)));
+        Ok(self.market_data(symbol, &quote))
+    }
+
+    fn record(&mut self, timestamp: i64, price: Decimal) {
+        self.samples.push_back((timestamp, price));
+        while self.samples.front().is_some_and(|(t, _)| *t < timestamp - HISTORY_SECS) {
+            self.samples.pop_front();
+        }
+    }
+
+    fn market_data(&self, symbol: &str, quote: &DexQuote) -> CoinGeckoMarketData {
+        let mut candles: BTreeMap<i64, OhlcData> = BTreeMap::new();
+        for &(timestamp, price) in &self.samples {
+            let hour = timestamp - timestamp.rem_euclid(3600);
+            candles.entry(hour)
+                .and_modify(|c| {
+                    c.high = c.high.max(price);
+                    c.low = c.low.min(price);
+                    c.close = price;
+                })
+                .or_insert(OhlcData { timestamp: hour, open: price, high: price, low: price, close: price });
+        }
+        let hourly_48h: Vec<OhlcData> = candles.into_values().collect();
+        let last = |n: usize| hourly_48h[hourly_48h.len().saturating_sub(n)..].to_vec();
+
+        let now = self.samples.back().map_or(0, |(t, _)| *t);
+        let day: Vec<Decimal> = self.samples.iter()
+            .filter(|(t, _)| *t >= now - 24 * 3600)
+            .map(|(_, p)| *p)
+            .collect();
+        let sampled_change = day.first()
+            .filter(|first| !first.is_zero())
+            .map(|first| (quote.price_usd - first) / first * Decimal::ONE_HUNDRED)
+            .unwrap_or_default();
+
+        CoinGeckoMarketData {
+            symbol: symbol.to_string(),
+            current_price: quote.price_usd,
+            high_24h: day.iter().copied().max().unwrap_or(quote.price_usd),
+            low_24h: day.iter().copied().min().unwrap_or(quote.price_usd),
+            price_change_24h_percent: quote.price_change_24h_percent.unwrap_or(sampled_change),
+            market_cap: Decimal::ZERO,
+            total_volume: quote.volume_24h.unwrap_or_default(),
+            hourly_data_12h: last(12),
+            hourly_data_24h: last(24),
+            hourly_data_48h: hourly_48h,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rust_decimal_macros::dec;
+
+    #[test]
+    fn test_uniswap_pool_selection_and_sampled_candles() {
+        let pairs: Vec<DexScreenerPair> = serde_json::from_value(serde_json::json!([
+            {"dexId": "uniswap", "labels": ["v2"], "baseToken": {"address": "0xPEPE"}, "priceUsd": "0.0000101",
+             "liquidity": {"usd": 2_000_000.0}, "volume": {"h24": 5_000_000.0}, "priceChange": {"h24": -3.5}},
+            {"dexId": "uniswap", "labels": ["v3"], "baseToken": {"address": "0xpepe"}, "priceUsd": "0.0000100",
+             "liquidity": {"usd": 9_000_000.0}, "volume": {"h24": 20_000_000.0}, "priceChange": {"h24": -3.0}},
+            // Deeper, but not Uniswap
+            {"dexId": "sushiswap", "baseToken": {"address": "0xpepe"}, "priceUsd": "0.0000099",
+             "liquidity": {"usd": 50_000_000.0}},
+            // Quotes PEPE as the quote token, so priceUsd is for another token
+            {"dexId": "uniswap", "baseToken": {"address": "0xweth"}, "priceUsd": "3000",
+             "liquidity": {"usd": 90_000_000.0}}
+        ])).unwrap();
+        let quote = best_uniswap_pool(pairs, "0xPePe").unwrap();
+        assert_eq!(quote.price_usd, dec!(0.0000100));
+        assert_eq!(quote.source, "uniswap v3");
+        assert_eq!(quote.price_change_24h_percent, Some(dec!(-3)));
+
+        let mut feed = DexFeed {
+            client: DexClient::new().unwrap(),
+            token: DexToken::parse("ethereum:0xpepe").unwrap(),
+            samples: VecDeque::new(),
+        };
+        let start = 1_700_000_000 - 1_700_000_000 % 3600;
+        feed.record(start - HISTORY_SECS - 1, dec!(1)); // ages out
+        for (offset, price) in [(0, dec!(10)), (1200, dec!(12)), (2400, dec!(9)), (3600, dec!(11))] {
+            feed.record(start + offset, price);
+        }
+        let quote = DexQuote { price_usd: dec!(11), liquidity_usd: None, volume_24h: None,
+            price_change_24h_percent: None, source: "jupiter".to_string() };
+        let market = feed.market_data("PEPEUSDT", &quote);
+        assert_eq!(market.hourly_data_48h.len(), 2);
+        let first = &market.hourly_data_48h[0];
+        assert_eq!((first.open, first.high, first.low, first.close), (dec!(10), dec!(12), dec!(9), dec!(9)));
+        assert_eq!((market.high_24h, market.low_24h), (dec!(12), dec!(9)));
+        assert_eq!(market.price_change_24h_percent, dec!(10));
+
+        assert!(DexToken::parse("0xpepe").is_err());
+    }
+}
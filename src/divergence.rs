@@ -0,0 +1,257 @@
+use crate::config::Config;
+use crate::error::BotError;
+use crate::exchange::Exchange;
+use crate::models::Kline;
+use crate::strategy::RsiStrategy;
+use anyhow::Result;
+use rust_decimal::Decimal;
+use rust_decimal_macros::dec;
+use serde::Serialize;
+use std::fmt;
+use std::time::{Duration, Instant};
+use tracing::{debug, warn};
+
+/// Hourly candles change slowly enough that once a minute is plenty
+const POLL_INTERVAL: Duration = Duration::from_secs(60);
+const RSI_PERIOD: usize = 14;
+const MACD_FAST: usize = 12;
+const MACD_SLOW: usize = 26;
+/// A swing low (high) closes below (above) this many candles on each side
+const SWING_STRENGTH: usize = 2;
+/// The later swing must be at most this many candles old, or the divergence is stale
+const MAX_SWING_AGE: usize = 10;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum DivergenceKind {
+    /// Lower low in price, higher low in the indicator: selling is losing momentum
+    Bullish,
+    /// Higher high in price, lower high in the indicator: buying is losing momentum
+    Bearish,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub enum Oscillator {
+    #[serde(rename = "RSI")]
+    Rsi,
+    /// The MACD line, EMA(12) minus EMA(26) of the closes
+    #[serde(rename = "MACD")]
+    Macd,
+}
+
+impl fmt::Display for Oscillator {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::Rsi => "RSI",
+            Self::Macd => "MACD",
+        })
+    }
+}
+
+/// Price and an oscillator disagreeing between the last two swing lows or highs
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Divergence {
+    pub kind: DivergenceKind,
+    pub oscillator: Oscillator,
+    /// Open time of the later swing's candle
+    pub open_time: i64,
+    pub previous_price: Decimal,
+    pub price: Decimal,
+    pub previous_value: Decimal,
+    pub value: Decimal,
+}
+
+/// "bullish RSI: price 61200 < 62000, RSI 34.1 > 28.5"
+impl fmt::Display for Divergence {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let (price, value) = match self.kind {
+            DivergenceKind::Bullish => ('<', '>'),
+            DivergenceKind::Bearish => ('>', '<'),
+        };
+        let dp = if self.oscillator == Oscillator::Rsi { 1 } else { 2 };
+        write!(f, "{} {}: price {} {} {}, {} {} {} {}",
+            if self.kind == DivergenceKind::Bullish { "bullish" } else { "bearish" }, self.oscillator,
+            self.price.normalize(), price, self.previous_price.normalize(),
+            self.oscillator, self.value.round_dp(dp), value, self.previous_value.round_dp(dp))
+    }
+}
+
+/// RSI of the closes up to each candle
+fn rsi_series(closes: &[Decimal]) -> Vec<Option<Decimal>> {
+    (0..closes.len()).map(|i| RsiStrategy::calculate_rsi(&closes[..=i], RSI_PERIOD)).collect()
+}
+
+/// MACD line at each candle, once the slow EMA has warmed up
+fn macd_series(closes: &[Decimal]) -> Vec<Option<Decimal>> {
+    let alpha = |period: usize| dec!(2) / Decimal::from(period + 1);
+    let (fast_alpha, slow_alpha) = (alpha(MACD_FAST), alpha(MACD_SLOW));
+    let mut ema = None::<(Decimal, Decimal)>;
+    closes.iter().enumerate().map(|(i, close)| {
+        let (fast, slow) = match ema {
+            Some((fast, slow)) => (fast + fast_alpha * (close - fast), slow + slow_alpha * (close - slow)),
+            None => (*close, *close),
+        };
+        ema = Some((fast, slow));
+        (i + 1 >= MACD_SLOW).then(|| fast - slow)
+    }).collect()
+}
+
+/// Indices of the confirmed swing lows (`lows`) or highs of the closes, from index `from` on
+fn swings(closes: &[Decimal], from: usize, lows: bool) -> Vec<usize> {
+    (from.max(SWING_STRENGTH)..closes.len().saturating_sub(SWING_STRENGTH)).filter(|&i| {
+        (i - SWING_STRENGTH..=i + SWING_STRENGTH).filter(|&j| j != i)
+            .all(|j| if lows { closes[i] < closes[j] } else { closes[i] > closes[j] })
+    }).collect()
+}
+
+/// Divergences between RSI or MACD and the last two swings of the closes (oldest first)
+/// within the last `lookback` candles, when the later swing is recent
+pub fn detect(klines: &[Kline], lookback: usize) -> Vec<Divergence> {
+    let closes: Vec<Decimal> = klines.iter().map(|k| k.close).collect();
+    let from = closes.len().saturating_sub(lookback);
+    let series = [(Oscillator::Rsi, rsi_series(&closes)), (Oscillator::Macd, macd_series(&closes))];
+    let mut found = Vec::new();
+    for kind in [DivergenceKind::Bullish, DivergenceKind::Bearish] {
+        let lows = kind == DivergenceKind::Bullish;
+        let &[.., first, last] = swings(&closes, from, lows).as_slice() else {
+            continue;
+        };
+        if closes.len() - 1 - last > MAX_SWING_AGE {
+            continue;
+        }
+        let price_diverges = if lows { closes[last] < closes[first] } else { closes[last] > closes[first] };
+        if !price_diverges {
+            continue;
+        }
+        for (oscillator, values) in &series {
+            let (Some(previous_value), Some(value)) = (values[first], values[last]) else {
+                continue;
+            };
+            if (lows && value > previous_value) || (!lows && value < previous_value) {
+                found.push(Divergence {
+                    kind,
+                    oscillator: *oscillator,
+                    open_time: klines[last].open_time,
+                    previous_price: closes[first],
+                    price: closes[last],
+                    previous_value,
+                    value,
+                });
+            }
+        }
+    }
+    found
+}
+
+/// Watches the traded symbol's hourly candles for RSI/MACD divergences (DIVERGENCE_ENABLED)
+pub struct DivergenceMonitor {
+    lookback: usize,
+    last_poll: Option<Instant>,
+    divergences: Vec<Divergence>,
+}
+
+impl DivergenceMonitor {
+    /// `None` unless DIVERGENCE_ENABLED is set
+    pub fn from_config(config: &Config) -> Result<Option<Self>> {
+        if !config.divergence_enabled {
+            return Ok(None);
+        }
+        if config.divergence_lookback_candles < 2 * SWING_STRENGTH + 2 {
+            return Err(BotError::config(format!("DIVERGENCE_LOOKBACK_CANDLES must be at least {}", 2 * SWING_STRENGTH + 2)).into());
+        }
+        Ok(Some(Self { lookback: config.divergence_lookback_candles, last_poll: None, divergences: Vec::new() }))
+    }
+
+    /// Refresh from `exchange`'s hourly candles (at most once a minute); returns the
+    /// divergences that were not there on the previous poll
+    pub async fn poll(&mut self, exchange: &impl Exchange, symbol: &str) -> Vec<Divergence> {
+        if self.last_poll.is_some_and(|t| t.elapsed() < POLL_INTERVAL) {
+            return Vec::new();
+        }
+        self.last_poll = Some(Instant::now());
+        // The oscillators warm up on the candles before the lookback window
+        match exchange.get_klines(symbol, "1h", (self.lookback + MACD_SLOW) as u32).await {
+            Ok(klines) => self.update(&klines),
+            Err(e) => {
+                warn!("⚠️ Divergence check failed: {}", e);
+                Vec::new()
+            }
+        }
+    }
+
+    fn update(&mut self, klines: &[Kline]) -> Vec<Divergence> {
+        let detected = detect(klines, self.lookback);
+        debug!("📐 Divergences: {:?}", detected);
+        let new = detected.iter().filter(|d| !self.divergences.contains(d)).cloned().collect();
+        self.divergences = detected;
+        new
+    }
+
+    pub fn divergences(&self) -> &[Divergence] {
+        &self.divergences
+    }
+
+    /// Divergence line for the AI prompt
+    pub fn format_for_ai(&self) -> Option<String> {
+        if self.divergences.is_empty() {
+            return Some("None on the hourly chart".to_string());
+        }
+        Some(self.divergences.iter().map(|d| d.to_string().to_uppercase()).collect::<Vec<_>>().join("; "))
+    }
+}
+
+/// Bullish minus bearish divergences: positive leans long, negative leans short
+pub fn bias(divergences: &[Divergence]) -> i32 {
+    divergences.iter().map(|d| match d.kind {
+        DivergenceKind::Bullish => 1,
+        DivergenceKind::Bearish => -1,
+    }).sum()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn candles(closes: &[Decimal]) -> Vec<Kline> {
+        closes.iter().enumerate().map(|(i, close)| Kline {
+            open_time: i as i64 * 3_600_000, open: *close, high: *close, low: *close, close: *close,
+            volume: dec!(1), close_time: i as i64 * 3_600_000 + 3_599_999,
+        }).collect()
+    }
+
+    #[test]
+    fn test_bullish_and_bearish_divergences() {
+        // A steep fall to a low, a bounce, then a slow drift to a marginally lower low
+        let mut closes: Vec<Decimal> = (0..30).map(|i| dec!(200) - Decimal::from(i * 3)).collect();
+        closes.extend((1..=6).map(|i| dec!(113) + Decimal::from(i * 2)));
+        closes.extend((1..=6).map(|i| dec!(125) - Decimal::from(i * 2) - dec!(0.5)));
+        closes.extend([dec!(116), dec!(118), dec!(120)]);
+        let found = detect(&candles(&closes), 60);
+        let rsi = found.iter().find(|d| d.oscillator == Oscillator::Rsi).unwrap();
+        assert_eq!((rsi.kind, rsi.previous_price, rsi.price), (DivergenceKind::Bullish, dec!(113), dec!(112.5)));
+        assert!(rsi.value > rsi.previous_value);
+        assert!(rsi.to_string().starts_with("bullish RSI: price 112.5 < 113, RSI "));
+        assert_eq!(bias(&found), found.len() as i32);
+
+        // The mirror image is bearish
+        let mirrored: Vec<Decimal> = closes.iter().map(|c| dec!(400) - c).collect();
+        let found = detect(&candles(&mirrored), 60);
+        assert!(!found.is_empty() && found.iter().all(|d| d.kind == DivergenceKind::Bearish));
+
+        // Stale once the later swing is too old
+        closes.extend((1..=MAX_SWING_AGE).map(|i| dec!(120) + Decimal::from(i)));
+        assert!(detect(&candles(&closes), 60).is_empty());
+
+        let mut config = Config::defaults();
+        config.divergence_enabled = true;
+        config.divergence_lookback_candles = 60;
+        let mut monitor = DivergenceMonitor::from_config(&config).unwrap().unwrap();
+        closes.truncate(closes.len() - MAX_SWING_AGE);
+        let klines = candles(&closes);
+        assert_eq!(monitor.update(&klines).len(), monitor.divergences().len());
+        assert!(monitor.update(&klines).is_empty());
+        assert!(monitor.format_for_ai().unwrap().starts_with("BULLISH RSI"));
+        config.divergence_lookback_candles = 3;
+        assert!(DivergenceMonitor::from_config(&config).is_err());
+    }
+}
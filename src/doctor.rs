@@ -0,0 +1,262 @@
+use crate::ai_advisor::{model_available, OllamaClient};
+use crate::alerts::{AlertRule, MoveWatch, PriceWatchlist};
+use crate::btc_trend::BtcTrendFilter;
+use crate::candles::CandleFeed;
+use crate::coingecko::CoinGeckoClient;
+use crate::config::Config;
+use crate::confirmation::TradeConfirmer;
+use crate::dex::DexFeed;
+use crate::divergence::DivergenceMonitor;
+use crate::entry_quality::EntryGate;
+use crate::exchange::ExchangeClient;
+use crate::futures::FuturesSettings;
+use crate::levels::LevelBook;
+use crate::liquidity::LiquidityMap;
+use crate::locale::{FxFeed, NumberLocale};
+use crate::maintenance::MaintenanceMonitor;
+use crate::optimizer::GaSettings;
+use crate::paths::{write_atomic, DataPaths};
+use crate::position::Position;
+use crate::grpc;
+use crate::mqtt::MqttSettings;
+use crate::retention::Retention;
+use crate::risk::{DrawdownKillSwitch, ExposureLimits, LossStreak, VarLimit};
+use crate::scanner::Scanner;
+use crate::sessions::SessionFilter;
+use crate::sizing::AiSizing;
+use crate::state_crypto::StateCipher;
+use crate::state_store;
+use crate::targets::ManualTargets;
+use crate::trade_limiter::LimiterSettings;
+use crate::volume::VolumeMonitor;
+use crate::volume_profile::VolumeProfiler;
+use crate::whales::WhaleMonitor;
+use anyhow::Result;
+use std::fmt;
+use std::fs;
+use std::path::Path;
+
+/// Local clock drift tolerated before signed requests become unreliable
+/// (Binance rejects them beyond the 5s receive window)
+const CLOCK_SKEW_WARN_MS: i64 = 1_000;
+const CLOCK_SKEW_FAIL_MS: i64 = 5_000;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CheckStatus {
+    Pass,
+    Warn,
+    Fail,
+    Skipped,
+}
+
+#[derive(Debug, Clone)]
+pub struct Check {
+    pub name: &'static str,
+    pub status: CheckStatus,
+    pub detail: String,
+}
+
+impl Check {
+    pub fn new(name: &'static str, status: CheckStatus, detail: impl Into<String>) -> Self {
+        Self { name, status, detail: detail.into() }
+    }
+
+    pub fn from_result(name: &'static str, result: Result<String>) -> Self {
+        match result {
+            Ok(detail) => Self::new(name, CheckStatus::Pass, detail),
+            Err(e) => Self::new(name, CheckStatus::Fail, e.to_string()),
+        }
+    }
+}
+
+impl fmt::Display for Check {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mark = match self.status {
+            CheckStatus::Pass => "✅ PASS",
+            CheckStatus::Warn => "⚠️ WARN",
+            CheckStatus::Fail => "❌ FAIL",
+            CheckStatus::Skipped => "➖ SKIP",
+        };
+        write!(f, "{:<8} {:<22} {}", mark, self.name, self.detail)
+    }
+}
+
+/// Run every check in order; nothing is traded and no state is modified
+pub async fn run(config: &Config) -> Vec<Check> {
+    let live = !config.is_simulation();
+    let exchange = if live { ExchangeClient::new(config).await.ok() } else { None };
+
+    let mut checks = vec![check_config(config)];
+    checks.push(match &exchange {
+        Some(exchange) => check_api_key(exchange, config).await,
+        None => Check::new("Exchange API key", CheckStatus::Skipped, "simulation mode"),
+    });
+    checks.push(match &exchange {
+        Some(exchange) => check_clock(exchange).await,
+        None => Check::new("Clock skew", CheckStatus::Skipped, "simulation mode"),
+    });
+    checks.push(check_coingecko(config).await);
+    checks.push(check_ollama(config).await);
+    checks.push(check_paths(config));
+    checks.push(Check::from_result("State backend", async {
+        let paths = DataPaths::from_config(config)?;
+        let backend = state_store::backend_from_config(config, &paths).await?;
+        backend.keys()?;
+        Ok(backend.describe())
+    }.await));
+    checks
+}
+
+fn check_config(config: &Config) -> Check {
+    let result = (|| -> Result<Vec<String>> {
+        LimiterSettings::from_config(config)?;
+        MaintenanceMonitor::from_config(config)?;
+        StateCipher::from_config(config)?;
+        TradeConfirmer::from_config(config)?;
+        ExposureLimits::from_config(config)?;
+        VarLimit::from_config(config)?;
+        LossStreak::from_config(config)?;
+        DrawdownKillSwitch::max_percent(config)?;
+        WhaleMonitor::from_config(config)?;
+        DexFeed::from_config(config)?;
+        GaSettings::from_config(config)?;
+        Position::slots(config, &[])?;
+        Scanner::from_config(config, "USDT")?;
+        VolumeMonitor::from_config(config)?;
+        DivergenceMonitor::from_config(config)?;
+        LevelBook::from_config(config)?;
+        LiquidityMap::from_config(config)?;
+        Retention::from_config(config)?;
+        VolumeProfiler::from_config(config)?;
+        BtcTrendFilter::from_config(config, "USDT")?;
+        NumberLocale::parse(&config.report_locale)?;
+        FxFeed::from_config(config, "USDT")?;
+        SessionFilter::from_config(config)?;
+        ManualTargets::from_config(config)?;
+        for rule in &config.alert_rules {
+            AlertRule::parse(rule)?;
+        }
+        PriceWatchlist::from_config(config)?;
+        MqttSettings::from_config(config)?;
+        grpc::listen_addr(config)?;
+        MoveWatch::from_config(config)?;
+        CandleFeed::from_config(config)?;
+        AiSizing::from_config(config)?;
+        EntryGate::from_config(config)?;
+        if config.is_futures() {
+            FuturesSettings::from_config(config)?;
+        }
+
+        let mut warnings = Vec::new();
+        if !config.is_simulation() {
+            if config.api_key.is_empty() || config.api_secret.is_empty() {
+                return Err(anyhow::anyhow!("API_KEY and API_SECRET are required for live trading"));
+            }
+            if !config.live_trading_enabled {
+                warnings.push("LIVE_TRADING_ENABLED=false (alert-only)".to_string());
+            }
+            if config.dex_token.is_some() {
+                warnings.push("DEX_TOKEN is only used in simulation".to_string());
+            }
+        }
+        if config.telegram_bot_token.is_some() != config.telegram_chat_id.is_some() {
+            warnings.push("set both TELEGRAM_BOT_TOKEN and TELEGRAM_CHAT_ID".to_string());
+        }
+        Ok(warnings)
+    })();
+
+    match result {
+        Ok(warnings) if warnings.is_empty() => Check::new("Configuration", CheckStatus::Pass,
+            format!("{} on {}", config.symbol, config.exchange)),
+        Ok(warnings) => Check::new("Configuration", CheckStatus::Warn, warnings.join("; ")),
+        Err(e) => Check::new("Configuration", CheckStatus::Fail, e.to_string()),
+    }
+}
+
+async fn check_api_key(exchange: &ExchangeClient, config: &Config) -> Check {
+    let permissions = match exchange.get_api_permissions().await {
+        Ok(permissions) => permissions,
+        Err(e) => return Check::new("Exchange API key", CheckStatus::Fail, e.to_string()),
+    };
+    let detail = format!("read={} trade={} withdraw={}",
+        permissions.can_read, permissions.can_trade, permissions.can_withdraw);
+    if let Err(e) = permissions.verify(config.live_trading_enabled) {
+        return Check::new("Exchange API key", CheckStatus::Fail, e.to_string());
+    }
+    if permissions.ip_restricted == Some(false) {
+        return Check::new("Exchange API key", CheckStatus::Warn, format!("{}, not IP-restricted", detail));
+    }
+    Check::new("Exchange API key", CheckStatus::Pass, detail)
+}
+
+async fn check_clock(exchange: &ExchangeClient) -> Check {
+    match exchange.get_server_time().await {
+        Ok(server_ms) => {
+            let skew_ms = chrono::Utc::now().timestamp_millis() - server_ms;
+            Check::new("Clock skew", skew_status(skew_ms), format!("{:+} ms vs exchange", skew_ms))
+        }
+        Err(e) => Check::new("Clock skew", CheckStatus::Fail, e.to_string()),
+    }
+}
+
+fn skew_status(skew_ms: i64) -> CheckStatus {
+    match skew_ms.abs() {
+        s if s >= CLOCK_SKEW_FAIL_MS => CheckStatus::Fail,
+        s if s >= CLOCK_SKEW_WARN_MS => CheckStatus::Warn,
+        _ => CheckStatus::Pass,
+    }
+}
+
+async fn check_coingecko(config: &Config) -> Check {
+    Check::from_result("CoinGecko", async {
+        let market = CoinGeckoClient::new().fetch_market_data(&config.symbol).await?;
+        Ok(format!("{} @ ${:.2}", market.symbol, market.current_price))
+    }.await)
+}
+
+async fn check_ollama(config: &Config) -> Check {
+    if !config.ollama_enabled {
+        return Check::new("Ollama model", CheckStatus::Skipped, "OLLAMA_ENABLED=false");
+    }
+    let models = async { OllamaClient::from_config(config)?.list_models().await };
+    match models.await {
+        Ok(models) if model_available(&models, &config.ollama_model) => {
+            Check::new("Ollama model", CheckStatus::Pass, format!("{} at {}", config.ollama_model, config.ollama_url))
+        }
+        // The bot falls back to calculated targets, so this isn't fatal
+        Ok(_) => Check::new("Ollama model", CheckStatus::Warn,
+            format!("{} not pulled - run `ollama pull {}`", config.ollama_model, config.ollama_model)),
+        Err(e) => Check::new("Ollama model", CheckStatus::Warn, format!("{} unreachable: {}", config.ollama_url, e)),
+    }
+}
+
+fn check_paths(config: &Config) -> Check {
+    Check::from_result("Filesystem", (|| {
+        let paths = DataPaths::from_config(config)?;
+        let report_dir = paths.report_file().parent().map(Path::to_path_buf);
+        let dirs = [Some(paths.state_dir()), Some(paths.logs_dir()), Some(paths.cache_dir()), report_dir];
+        for dir in dirs.into_iter().flatten() {
+            let probe = dir.join(".doctor-probe");
+            write_atomic(&probe, b"ok").map_err(|e| crate::error::BotError::storage(&dir, e))?;
+            fs::remove_file(&probe).ok();
+        }
+        Ok(format!("{} writable", paths.root().display()))
+    })())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_clock_skew_and_model_matching() {
+        assert_eq!(skew_status(120), CheckStatus::Pass);
+        assert_eq!(skew_status(-1_500), CheckStatus::Warn);
+        assert_eq!(skew_status(6_000), CheckStatus::Fail);
+
+        let models = vec!["mistral:latest".to_string(), "llama3:8b".to_string()];
+        assert!(model_available(&models, "mistral"));
+        assert!(model_available(&models, "llama3:8b"));
+        assert!(!model_available(&models, "llama3"));
+    }
+}
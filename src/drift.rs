@@ -0,0 +1,139 @@
+use crate::config::Config;
+use crate::notifier::TelegramNotifier;
+use anyhow::Result;
+use rust_decimal::Decimal;
+use rust_decimal_macros::dec;
+use std::collections::{BTreeSet, HashMap};
+use std::fmt;
+use std::time::{Duration, Instant};
+use tracing::warn;
+
+/// A figure the bot tracks that no longer matches the exchange
+#[derive(Debug, Clone, PartialEq)]
+pub struct Drift {
+    /// Asset, or `<base> position` for the open positions' size
+    pub subject: String,
+    pub tracked: Decimal,
+    pub exchange: Decimal,
+}
+
+impl fmt::Display for Drift {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: bot has {}, exchange has {}", self.subject, self.tracked.normalize(), self.exchange.normalize())
+    }
+}
+
+/// Every DRIFT_CHECK_MINUTES, compares the balances in the report (kept current by the user-data
+/// stream) and the open positions' size against fresh exchange balances, to catch missed fills
+/// and manual trades. Holding more of the base asset than the positions is not drift; holding
+/// less is.
+pub struct DriftMonitor {
+    interval: Duration,
+    tolerance_percent: Decimal,
+    pub auto_correct: bool,
+    last_check: Option<Instant>,
+    /// What was last alerted, so a lasting divergence alerts once
+    alerted: Vec<Drift>,
+    telegram: Option<TelegramNotifier>,
+}
+
+impl DriftMonitor {
+    /// `None` with DRIFT_CHECK_MINUTES=0
+    pub fn from_config(config: &Config) -> Result<Option<Self>> {
+        if config.drift_check_minutes == 0 {
+            return Ok(None);
+        }
+        let telegram = match (&config.telegram_bot_token, &config.telegram_chat_id) {
+            (Some(token), Some(chat)) => Some(TelegramNotifier::new(token.expose(), chat)?),
+            _ => None,
+        };
+        Ok(Some(Self {
+            interval: Duration::from_secs(config.drift_check_minutes * 60),
+            tolerance_percent: config.drift_tolerance_percent,
+            auto_correct: config.drift_auto_correct,
+            last_check: None,
+            alerted: Vec::new(),
+            telegram,
+        }))
+    }
+
+    /// Whether a check is due; the first one runs an interval after startup
+    pub fn is_due(&mut self) -> bool {
+        let last = *self.last_check.get_or_insert_with(Instant::now);
+        if last.elapsed() < self.interval {
+            return false;
+        }
+        self.last_check = Some(Instant::now());
+        true
+    }
+
+    fn diverges(&self, tracked: Decimal, exchange: Decimal) -> bool {
+        let larger = tracked.abs().max(exchange.abs());
+        larger > Decimal::ZERO && (tracked - exchange).abs() / larger * dec!(100) > self.tolerance_percent
+    }
+
+    /// Free balances and the positions' size (against the base asset's free plus locked
+    /// balance) that differ by more than DRIFT_TOLERANCE_PERCENT
+    pub fn compare(
+        &self,
+        tracked: &HashMap<String, Decimal>,
+        exchange_free: &HashMap<String, Decimal>,
+        base: &str,
+        base_held: Decimal,
+        position_qty: Decimal,
+    ) -> Vec<Drift> {
+        let assets: BTreeSet<&String> = tracked.keys().chain(exchange_free.keys()).collect();
+        let mut drifts: Vec<Drift> = assets.into_iter()
+            .map(|asset| Drift {
+                subject: asset.clone(),
+                tracked: tracked.get(asset).copied().unwrap_or_default(),
+                exchange: exchange_free.get(asset).copied().unwrap_or_default(),
+            })
+            .filter(|d| self.diverges(d.tracked, d.exchange))
+            .collect();
+        if position_qty > base_held && self.diverges(position_qty, base_held) {
+            drifts.push(Drift { subject: format!("{} position", base), tracked: position_qty, exchange: base_held });
+        }
+        drifts
+    }
+
+    /// Drifts not alerted yet; an empty check clears the memory so a recurrence alerts again
+    pub fn new_drifts(&mut self, drifts: Vec<Drift>) -> Vec<Drift> {
+        let fresh = drifts.iter().filter(|d| !self.alerted.contains(d)).cloned().collect();
+        self.alerted = drifts;
+        fresh
+    }
+
+    pub async fn notify(&self, message: &str) {
+        if let Some(telegram) = &self.telegram
+            && let Err(e) = telegram.send_message(message).await {
+            warn!("⚠️ Failed to send the drift alert: {}", e);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_drift_beyond_tolerance() {
+        let mut config = Config::defaults();
+        config.drift_check_minutes = 15;
+        config.drift_tolerance_percent = dec!(1);
+        let mut monitor = DriftMonitor::from_config(&config).unwrap().unwrap();
+
+        let tracked = HashMap::from([("USDT".to_string(), dec!(1000)), ("BTC".to_string(), dec!(0.5))]);
+        let exchange = HashMap::from([("USDT".to_string(), dec!(995)), ("BTC".to_string(), dec!(0.4))]);
+        // USDT is within 1%; BTC and a position larger than what is held are not
+        let drifts = monitor.compare(&tracked, &exchange, "BTC", dec!(0.4), dec!(0.5));
+        assert_eq!(drifts.iter().map(|d| d.subject.as_str()).collect::<Vec<_>>(), ["BTC", "BTC position"]);
+        // Holding more than the positions is fine
+        assert!(monitor.compare(&exchange, &exchange, "BTC", dec!(0.4), dec!(0.1)).is_empty());
+
+        assert_eq!(monitor.new_drifts(drifts.clone()).len(), 2);
+        assert!(monitor.new_drifts(drifts.clone()).is_empty());
+        monitor.new_drifts(Vec::new());
+        assert_eq!(monitor.new_drifts(drifts).len(), 2);
+    }
+}
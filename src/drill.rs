@@ -0,0 +1,258 @@
+use crate::ai_advisor::{FallbackTargetCalculator, MarketContext, OllamaClient};
+use crate::coingecko::CoinGeckoClient;
+use crate::config::Config;
+use crate::doctor::{Check, CheckStatus};
+use crate::exchange::Exchange;
+use crate::market_data::{self, SourceTimeouts};
+use crate::mock_exchange::{MockExchange, OrderFault};
+use crate::models::{BookTicker, OrderSide, OrderType};
+use crate::portfolio::PortfolioReporter;
+use crate::position::{ExitTrigger, Position};
+use crate::runtime_state::{RuntimeState, RuntimeStore};
+use crate::state_store::{StateStore, RUNTIME_STATE_KEY};
+use crate::symbols::{self, SymbolInfo};
+use anyhow::{anyhow, Result};
+use rust_decimal::Decimal;
+use rust_decimal_macros::dec;
+use std::path::PathBuf;
+use std::time::Duration;
+use tracing::{info, warn};
+
+/// Nothing listens here, so the AI and CoinGecko requests are refused at once
+const UNREACHABLE_URL: &str = "http://127.0.0.1:9";
+const ENTRY_PRICE: Decimal = dec!(100);
+const QUOTE_BALANCE: Decimal = dec!(1000);
+/// Price requests that time out while the price falls through the stop
+const OUTAGE_CYCLES: usize = 3;
+/// How far below the stop, in percent of the entry, the price is when the exchange comes back
+const GAP_PERCENT: Decimal = dec!(1);
+
+/// `bot drill`: an outage rehearsal against a scripted exchange. A position is opened, then
+/// the exchange stops answering while the price falls through the stop, the process "crashes"
+/// and resumes from its saved state, CoinGecko and the AI are unreachable, and the first stop
+/// order times out. Each step is checked against what the trading loop must do: hold still
+/// without a price, resume the position, trade on the exchange price alone, fall back to
+/// technical targets, retry the stop and alert on the gap. Nothing touches the real exchange
+/// or the bot's state.
+pub struct Drill {
+    symbol: SymbolInfo,
+    stop_loss_percent: Decimal,
+    take_profit_percent: Decimal,
+    fee_percent: Decimal,
+    exchange: MockExchange,
+    position: Position,
+    dir: PathBuf,
+    store: RuntimeStore,
+    reporter: PortfolioReporter,
+    exit_attempts: usize,
+}
+
+impl Drill {
+    pub fn new(config: &Config) -> Self {
+        let symbol = symbols::lookup(&config.symbol);
+        let stop = ENTRY_PRICE * (Decimal::ONE - config.stop_loss_percent.abs() / dec!(100));
+        let gap = stop - ENTRY_PRICE * GAP_PERCENT / dec!(100);
+        // The entry price, the outage (skipped), then the gapped price for the stop and its retry
+        let mut prices = vec![ENTRY_PRICE];
+        prices.extend([stop; OUTAGE_CYCLES]);
+        prices.extend([gap, gap]);
+        let exchange = (1..=OUTAGE_CYCLES)
+            .fold(MockExchange::new(&symbol.symbol, QUOTE_BALANCE).with_prices(prices), MockExchange::timeout_at)
+            .with_fee_percent(config.trading_fee_percent)
+            .fail_order(1, OrderFault::Timeout);
+        let dir = std::env::temp_dir().join(format!("ctb_drill_{}", std::process::id()));
+        Self {
+            stop_loss_percent: config.stop_loss_percent,
+            take_profit_percent: config.take_profit_percent,
+            fee_percent: config.trading_fee_percent,
+            exchange,
+            position: Position::new(&symbol.symbol),
+            store: RuntimeStore::new(StateStore::file(&dir.join(format!("{}.json", RUNTIME_STATE_KEY)), None)),
+            reporter: PortfolioReporter::new(&symbol.symbol, true, &dir.join("portfolio_status.txt")),
+            dir,
+            symbol,
+            exit_attempts: 0,
+        }
+    }
+
+    /// Run every step in order; once one fails the rest are skipped, as they build on it
+    pub async fn run(mut self) -> Vec<Check> {
+        if let Err(e) = std::fs::create_dir_all(&self.dir) {
+            return vec![Check::new("Drill state", CheckStatus::Fail, format!("{}: {}", self.dir.display(), e))];
+        }
+        let mut checks = Vec::new();
+        for name in ["Entry", "Exchange outage", "Crash and resume", "Market data outage", "AI outage",
+                     "Stop-loss retry", "Stop gap alert", "State after exit"] {
+            if checks.iter().any(|c: &Check| c.status == CheckStatus::Fail) {
+                checks.push(Check::new(name, CheckStatus::Skipped, "an earlier step failed"));
+                continue;
+            }
+            info!("🚒 Drill: {}", name);
+            let result = match name {
+                "Entry" => self.entry().await,
+                "Exchange outage" => self.exchange_outage().await,
+                "Crash and resume" => self.crash_and_resume(),
+                "Market data outage" => self.market_data_outage().await,
+                "AI outage" => self.ai_outage().await,
+                "Stop-loss retry" => self.stop_loss_retry().await,
+                "Stop gap alert" => self.stop_gap_alert(),
+                _ => self.state_after_exit(),
+            };
+            checks.push(Check::from_result(name, result));
+        }
+        std::fs::remove_dir_all(&self.dir).ok();
+        checks
+    }
+
+    fn save(&mut self) {
+        let mut state = RuntimeState::new(&self.symbol.symbol);
+        state.positions = [self.position.clone()].into_iter().filter(|p| !p.is_flat()).collect();
+        self.store.save(&mut state);
+    }
+
+    async fn entry(&mut self) -> Result<String> {
+        let price = self.exchange.get_price(&self.symbol.symbol).await?;
+        let qty = (QUOTE_BALANCE / dec!(2) / price).round_dp(5);
+        self.position.begin_entry(qty)?;
+        let order = self.exchange.place_order(&self.symbol.symbol, OrderSide::Buy, OrderType::Market, qty, None).await?;
+        self.position.entry_filled(order.execution(&self.symbol.base, &self.symbol.quote, price, qty, self.fee_percent))?;
+        self.position.set_percent_targets(self.stop_loss_percent, self.take_profit_percent);
+        self.save();
+        Ok(format!("bought {} {} @ {}, stop {}", qty, self.symbol.base, price,
+            self.position.stop_loss.unwrap_or_default().round_dp(2)))
+    }
+
+    /// No price, no trading: every cycle of the outage is skipped with the position untouched
+    async fn exchange_outage(&mut self) -> Result<String> {
+        for cycle in 1..=OUTAGE_CYCLES {
+            if let Ok(price) = self.exchange.get_price(&self.symbol.symbol).await {
+                return Err(anyhow!("cycle {} of the outage still got a price ({})", cycle, price));
+            }
+        }
+        let orders = self.exchange.orders().len();
+        if orders != 1 || !self.position.is_open() {
+            return Err(anyhow!("{} orders and an {} position after the outage", orders,
+                if self.position.is_open() { "open" } else { "unexpected" }));
+        }
+        Ok(format!("{} cycles without a price: skipped, no orders, position held", OUTAGE_CYCLES))
+    }
+
+    /// A restart mid-position picks up the saved position with its stop
+    fn crash_and_resume(&mut self) -> Result<String> {
+        let before = self.position.clone();
+        let mut state = self.store.load(&self.symbol.symbol);
+        let resumed = state.positions.pop().ok_or_else(|| anyhow!("no position in the saved state"))?;
+        if (resumed.qty, resumed.entry_price, resumed.stop_loss) != (before.qty, before.entry_price, before.stop_loss) {
+            return Err(anyhow!("resumed {} @ {:?} (stop {:?}), saved {} @ {:?} (stop {:?})", resumed.qty,
+                resumed.entry_price, resumed.stop_loss, before.qty, before.entry_price, before.stop_loss));
+        }
+        self.position = resumed;
+        Ok(format!("resumed {} {} @ {} with its stop from the saved state", self.position.qty, self.symbol.base,
+            self.position.entry_price.unwrap_or_default()))
+    }
+
+    /// CoinGecko down while the exchange is back: the cycle runs on the exchange price alone
+    async fn market_data_outage(&mut self) -> Result<String> {
+        let coingecko = CoinGeckoClient::with_base_url(UNREACHABLE_URL);
+        let timeouts = SourceTimeouts { exchange: Duration::from_secs(5), coingecko: Duration::from_secs(5) };
+        let snapshot = market_data::fetch_snapshot(
+            self.exchange.get_price(&self.symbol.symbol),
+            Some(coingecko.fetch_market_data(&self.symbol.symbol)),
+            None::<std::future::Ready<Result<BookTicker>>>,
+            timeouts,
+        ).await;
+        if snapshot.market.is_some() {
+            return Err(anyhow!("CoinGecko answered from {}", UNREACHABLE_URL));
+        }
+        let price = snapshot.price.ok_or_else(|| anyhow!("no exchange price once the exchange was back"))?;
+        self.reporter.update_price(price);
+        Ok(format!("no market data, cycle continued on the exchange price {}", price))
+    }
+
+    /// The model unreachable: targets come from the fallback calculator, stop below the price
+    async fn ai_outage(&mut self) -> Result<String> {
+        let price = self.reporter.status().current_price;
+        let context = MarketContext { symbol: self.symbol.symbol.clone(), current_price: price, high_24h: ENTRY_PRICE,
+            low_24h: price, ..Default::default() };
+        if OllamaClient::new(Some(UNREACHABLE_URL), None)?.calculate_targets(&context).await.is_ok() {
+            return Err(anyhow!("the AI answered from {}", UNREACHABLE_URL));
+        }
+        let targets = FallbackTargetCalculator::calculate_targets(&context);
+        if targets.stop_loss_price >= price || targets.take_profit_price <= price {
+            return Err(anyhow!("fallback stop {} / take-profit {} around price {}", targets.stop_loss_price,
+                targets.take_profit_price, price));
+        }
+        Ok(format!("AI unreachable, fallback targets: {} @ {}% confidence", targets.recommendation,
+            targets.confidence.round_dp(0)))
+    }
+
+    /// The price gapped below the stop during the outage; the first stop order times out and
+    /// the next cycle retries it
+    async fn stop_loss_retry(&mut self) -> Result<String> {
+        let mut price = self.reporter.status().current_price;
+        loop {
+            if self.position.exit_trigger(price) != Some(ExitTrigger::StopLoss) {
+                return Err(anyhow!("no stop-loss at {} (stop {:?})", price, self.position.stop_loss));
+            }
+            self.exit_attempts += 1;
+            let before = self.position.clone();
+            self.position.begin_exit()?;
+            let qty = self.position.qty;
+            match self.exchange.place_order(&self.symbol.symbol, OrderSide::Sell, OrderType::Market, qty, None).await {
+                Ok(order) => {
+                    let fill = order.execution(&self.symbol.base, &self.symbol.quote, price, qty, self.fee_percent);
+                    let pnl = self.position.exit_filled(fill)?;
+                    if let Some(gap) = before.stop_gap_alert(fill) {
+                        self.reporter.raise_alert(gap);
+                    }
+                    break if self.exchange.free_balance(&self.symbol.base) > Decimal::ZERO {
+                        Err(anyhow!("{} {} still held after the exit", self.exchange.free_balance(&self.symbol.base),
+                            self.symbol.base))
+                    } else {
+                        Ok(format!("stop order failed once, filled on attempt {} @ {} ({} P&L)", self.exit_attempts,
+                            fill.price, pnl.round_dp(2)))
+                    };
+                }
+                Err(e) if self.exit_attempts < 3 => {
+                    warn!("⚠️ Drill: stop order failed ({}), retrying next cycle", e);
+                    self.position.exit_failed()?;
+                    price = self.exchange.get_price(&self.symbol.symbol).await?;
+                }
+                Err(e) => return Err(e.context("stop order still failing after 3 attempts")),
+            }
+        }
+    }
+
+    fn stop_gap_alert(&self) -> Result<String> {
+        self.reporter.status().active_alerts.iter().find(|a| a.contains("STOP-LOSS GAPPED")).cloned()
+            .ok_or_else(|| anyhow!("no alert for the stop filled past its level"))
+    }
+
+    /// Saved flat, the next restart resumes nothing
+    fn state_after_exit(&mut self) -> Result<String> {
+        self.save();
+        let state = self.store.load(&self.symbol.symbol);
+        if !state.positions.is_empty() {
+            return Err(anyhow!("{} positions resumed after the exit", state.positions.len()));
+        }
+        Ok("flat position saved, nothing resumed on restart".to_string())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_drill_passes_every_step() {
+        let mut config = Config::defaults();
+        config.symbol = "BTCUSDT".to_string();
+        config.stop_loss_percent = dec!(-2);
+        let checks = Drill::new(&config).run().await;
+        assert_eq!(checks.len(), 8);
+        for check in &checks {
+            assert_eq!(check.status, CheckStatus::Pass, "{}", check);
+        }
+        assert!(checks[5].detail.contains("attempt 2 @ 97"));
+    }
+}
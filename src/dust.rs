@@ -0,0 +1,60 @@
+use crate::exchange::ExchangeClient;
+use rust_decimal::Decimal;
+use std::time::{Duration, Instant};
+use tracing::{info, warn};
+
+/// Binance accepts one small-balance conversion every 6 hours
+const SWEEP_INTERVAL: Duration = Duration::from_secs(6 * 3600);
+
+/// Converts leftover base-asset dust to BNB when `DUST_SWEEP_ENABLED` is set
+pub struct DustSweeper {
+    enabled: bool,
+    last_attempt: Option<Instant>,
+}
+
+impl DustSweeper {
+    pub fn new(enabled: bool) -> Self {
+        Self { enabled, last_attempt: None }
+    }
+
+    /// Whether `asset` may be converted now: enabled, not BNB itself, and the conversion
+    /// interval has passed since the last attempt
+    fn due(&self, asset: &str) -> bool {
+        self.enabled && asset != "BNB" && self.last_attempt.is_none_or(|t| t.elapsed() >= SWEEP_INTERVAL)
+    }
+
+    /// Sweep `qty` of `asset` if enabled and the conversion interval has passed
+    pub async fn sweep(&mut self, exchange: &ExchangeClient, asset: &str, qty: Decimal) {
+        if !self.due(asset) {
+            return;
+        }
+        self.last_attempt = Some(Instant::now());
+
+        match exchange.convert_dust(&[asset]).await {
+            Ok(bnb) => info!("🧹 Converted {} {} dust to {} BNB", qty, asset, bnb),
+            Err(e) => warn!("⚠️ Dust conversion for {} failed: {}", asset, e),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::symbols;
+    use rust_decimal_macros::dec;
+
+    #[test]
+    fn test_dust_is_swept_once_per_interval() {
+        // 0.000019 BTC rounds down to 0.00001, worth less than the minimum notional
+        let btc = symbols::lookup("BTCUSDT");
+        assert!(btc.is_dust(dec!(0.000019), dec!(42000)));
+        assert!(!btc.is_dust(dec!(0.01), dec!(42000)));
+
+        let mut sweeper = DustSweeper::new(true);
+        assert!(sweeper.due("BTC"));
+        assert!(!sweeper.due("BNB"));
+        sweeper.last_attempt = Some(Instant::now());
+        assert!(!sweeper.due("BTC"));
+        assert!(!DustSweeper::new(false).due("BTC"));
+    }
+}
@@ -0,0 +1,2406 @@
+use crate::accounting::FundsLedger;
+use crate::ai_advisor::{AiTradingTargets, FallbackTargetCalculator, MarketContext, OllamaClient};
+use crate::ai_pool::{AiJob, AiPool};
+use crate::alerts::{AlertEngine, PriceWatchlist};
+use crate::allocation::{SleeveStrategy, StrategyBook};
+use crate::analysis_api::AnalysisFeed;
+use crate::audit::{AuditEvent, AuditLog};
+use crate::btc_trend::BtcTrendFilter;
+use crate::candles::CandleFeed;
+use crate::capital::CapitalTracker;
+use crate::chaos::Chaos;
+use crate::coingecko::{BreadthFeed, CoinGeckoClient};
+use crate::competition::Competition;
+use crate::config::Config;
+use crate::confirmation::{ConfirmationDecision, TradeConfirmer, TradeProposal};
+use crate::control::{BotControl, ManualOrder};
+use crate::cycle_gap::CycleClock;
+use crate::dex::DexFeed;
+use crate::divergence::DivergenceMonitor;
+use crate::drift::DriftMonitor;
+use crate::dust::DustSweeper;
+use crate::entry_quality::{EntryGate, EntryScore};
+use crate::error::{BotError, ErrorKind};
+use crate::events::{BotEvent, EventBus};
+use crate::exchange::Exchange;
+use crate::futures::{FuturesClient, FuturesLeg, FuturesPosition, FuturesSettings, MarginType, PositionSide};
+use crate::grpc::ControlService;
+use crate::i18n::Language;
+use crate::levels::LevelBook;
+use crate::liquidity::{LiquidityMap, Wall};
+use crate::locale::{DisplayFormat, FxFeed, NumberLocale};
+use crate::maintenance::MaintenanceMonitor;
+use crate::market_data::SourceTimeouts;
+use crate::metrics::Metrics;
+use crate::mqtt::MqttPublisher;
+use crate::options::OptionsFeed;
+use crate::orders::OrderMonitor;
+use crate::paths::DataPaths;
+use crate::portfolio::{PortfolioReporter, PortfolioStatus};
+use crate::position::{ExitTrigger, Position};
+use crate::post_trade::{ClosedTrade, PostTradeReviewer};
+use crate::retention::Retention;
+use crate::risk::{DrawdownKillSwitch, ExposureLimits, LossStreak, VarLimit};
+use crate::rollover::DayRollover;
+use crate::runtime_state::{push_equity_point, EquityPoint, RuntimeStore};
+use crate::scanner::Scanner;
+use crate::sessions::SessionFilter;
+use crate::sizing::AiSizing;
+use crate::state_crypto::StateCipher;
+use crate::state_store::{StateStore, HIGH_WATER_MARK_KEY, RUNTIME_STATE_KEY, STRATEGY_BOOK_KEY, TRADE_STATE_KEY};
+use crate::stop_watch::StopWatcher;
+use crate::targets::{ManualTargets, TargetSource};
+use crate::trade_limiter::{LimiterSettings, TradeContext, TradeLimiter, TradePermission, TradingStatus};
+use crate::volume::VolumeMonitor;
+use crate::volume_profile::VolumeProfiler;
+use crate::watchdog::{CycleTimer, Heartbeat, Stage};
+use crate::whales::WhaleMonitor;
+use crate::{
+    cycle_gap, exchange, futures, grpc, market_data, models, paths, position, quote, retention, risk,
+    self_test, simulation, state_store, strategy, symbols, user_stream, watchdog
+};
+use anyhow::Result;
+use rust_decimal::Decimal;
+use rust_decimal_macros::dec;
+use std::sync::Arc;
+use std::time::Duration;
+use tracing::{error, info, warn};
+
+// How often to check prices (in seconds)
+const PRICE_CHECK_INTERVAL_SECS: u64 = 30;
+// How often to recalculate targets with AI (in seconds)
+const AI_RECALC_INTERVAL_SECS: u64 = 300; // 5 minutes
+
+/// Trade in the configured mode until the loop stops: futures, simulation or live spot.
+/// The simulation and live loops run under the watchdog and restart when a cycle hangs.
+pub async fn run(config: Config) -> Result<()> {
+    config.check_required()?;
+
+    if config.is_futures() {
+        if config.is_simulation() {
+            return Err(BotError::config("MARKET_TYPE=futures needs a live exchange - use EXCHANGE=binance_testnet to paper trade").into());
+        }
+        info!("📜 Running in FUTURES MODE on exchange: {}", config.exchange);
+        warn!("⚠️  Leveraged positions can be liquidated!");
+        run_futures_loop(config).await
+    } else if config.is_simulation() {
+        info!("🎮 Running in SIMULATION MODE - no real trades will be executed");
+        watchdog::supervise(config, run_simulation_loop).await
+    } else {
+        info!("💰 Running in LIVE MODE on exchange: {}", config.exchange);
+        warn!("⚠️  Real money is at risk!");
+        watchdog::supervise(config, run_live_loop).await
+    }
+}
+
+/// `bot compete`: race the COMPETITION_BOTS paper bots on one price feed and keep a leaderboard
+pub async fn run_competition(mut config: Config) -> Result<()> {
+    let (symbol, _) = quote::resolve_pair(&config.symbol, config.quote_asset.as_deref(), None)?;
+    config.symbol = symbol;
+    let mut competition = Competition::from_config(&config)?;
+    let paths = DataPaths::from_config(&config)?;
+    let coingecko = CoinGeckoClient::new();
+    let mut dex_feed = DexFeed::from_config(&config)?;
+    let timeout = SourceTimeouts::from_config(&config).coingecko;
+
+    info!("🏁 Competition: {} bots on {} - leaderboard at {}", competition.competitors().len(), config.symbol,
+        paths.competition_file().display());
+    loop {
+        let market = match dex_feed.as_mut() {
+            Some(feed) => tokio::time::timeout(timeout, feed.fetch_market_data(&config.symbol)).await,
+            None => tokio::time::timeout(timeout, coingecko.fetch_market_data(&config.symbol)).await,
+        };
+        match market {
+            Ok(Ok(data)) => {
+                let closes: Vec<Decimal> = data.hourly_data_48h.iter().map(|h| h.close).collect();
+                for trade in competition.step(&closes, data.current_price) {
+                    info!("🏁 {}", trade);
+                }
+                if let Some(leader) = competition.leaderboard(data.current_price).first() {
+                    info!("🥇 {} leads with ${:.2} ({:+.2}%)", leader.name, leader.equity, leader.return_percent);
+                }
+                if let Err(e) = paths::write_atomic(paths.competition_file(), competition.render(&config.symbol, data.current_price)) {
+                    warn!("⚠️ Failed to write leaderboard: {}", e);
+                }
+            }
+            Ok(Err(e)) => warn!("⚠️ Price feed failed, skipping cycle: {}", e),
+            Err(_) => warn!("⚠️ Price feed timed out after {:?}", timeout),
+        }
+        tokio::time::sleep(Duration::from_secs(PRICE_CHECK_INTERVAL_SECS)).await;
+    }
+}
+
+/// Continuous monitoring loop for simulation mode
+pub async fn run_simulation_loop(mut config: Config, heartbeat: Heartbeat) -> Result<()> {
+    // The simulated account is funded in the pair's quote asset
+    let (symbol, quote_asset) = quote::resolve_pair(&config.symbol, config.quote_asset.as_deref(), None)?;
+    config.symbol = symbol;
+
+    let exchange = simulation::SimulationExchange::new(&config).await?;
+    info!("✅ Simulation exchange initialized");
+    if self_test::enabled(&config) {
+        self_test::run_simulated(&exchange, &symbols::lookup(&config.symbol)).await?;
+    }
+
+    // Initialize components
+    let paths = DataPaths::from_config(&config)?;
+    info!("📁 Data directory: {}", paths.root().display());
+    let mut reporter = PortfolioReporter::new(&config.symbol, true, &paths.report_file());
+    let coingecko = CoinGeckoClient::new();
+    // Long-tail tokens are priced from DEX pools instead
+    let mut dex_feed = DexFeed::from_config(&config)?;
+    let state_backend = state_store::backend_from_config(&config, &paths).await?;
+    let state_cipher = StateCipher::from_config(&config)?;
+    let mut trade_limiter = TradeLimiter::with_store(
+        StateStore::new(state_backend.clone(), TRADE_STATE_KEY, state_cipher.clone()),
+        LimiterSettings::from_config(&config)?,
+    );
+    let audit = AuditLog::new(&paths.audit_log_file()).with_cipher(state_cipher.clone());
+    let mqtt = MqttPublisher::from_config(&config)?;
+    if let Some(mqtt) = &mqtt {
+        mqtt.forward_events(audit.events().subscribe());
+    }
+    let funds = FundsLedger::new(&quote_asset);
+    let mut maintenance = MaintenanceMonitor::from_config(&config)?;
+    let mut rollover = DayRollover::from_config(&config)?;
+    let price_watchlist = PriceWatchlist::from_config(&config)?;
+    let analysis = AnalysisFeed::new();
+    let metrics = start_metrics(&config, &paths, &price_watchlist, &analysis).await?;
+    let cycle_timer = CycleTimer::from_config(&config, heartbeat, metrics.clone());
+    let control = start_control(&config, &audit).await?;
+    if let Some(window) = maintenance.next_planned() {
+        info!("🛠️ Next planned maintenance: {} - {}", window.start, window.end);
+    }
+
+    // Set trading parameters
+    {
+        let status = reporter.status_mut();
+        status.stop_loss_percent = config.stop_loss_percent;
+        status.take_profit_percent = config.take_profit_percent;
+        status.max_trades_per_day = config.max_trades_per_day;
+    }
+
+    // Get initial balance
+    let balance = exchange.get_balance().await?;
+    let balance_map: std::collections::HashMap<String, Decimal> = balance
+        .iter()
+        .map(|(k, v)| (k.clone(), v.free))
+        .collect();
+    reporter.update_balances(balance_map);
+    info!("💰 Starting balance: {:?}", balance);
+
+    // Chaos starts once the run is set up, so startup itself stays deterministic
+    let chaos = Chaos::from_config(&config)?;
+    if chaos.is_some() {
+        warn!("🐒 Chaos mode: failing {}% and delaying {}% of exchange calls, garbling {}% of AI analyses",
+            config.chaos_failure_percent, config.chaos_delay_percent, config.chaos_ai_garbage_percent);
+    }
+    let retention = Retention::from_config(&config)?.spill_to(&config, state_backend.clone(), state_cipher.clone());
+    let exchange = exchange.with_chaos(chaos.clone()).with_retention(retention.clone());
+
+    // The simulated account starts over every run, so the sleeves do too
+    let starting_quote = reporter.status().balances.get(funds.quote_asset()).copied().unwrap_or(dec!(0));
+    let mut strategy_book = StrategyBook::from_config(&config, starting_quote)?;
+    let mut exposure = ExposureLimits::from_config(&config)?;
+    let var_limit = VarLimit::from_config(&config)?;
+    let mut equity_history: Vec<EquityPoint> = Vec::new();
+    let mut options_feed = OptionsFeed::from_config(&config, &reporter.status().base_asset)?;
+    let mut whale_monitor = WhaleMonitor::from_config(&config)?;
+    let mut breadth = BreadthFeed::new(CoinGeckoClient::new());
+    let mut volume_monitor = VolumeMonitor::from_config(&config)?;
+    let mut divergence_monitor = DivergenceMonitor::from_config(&config)?;
+    let mut level_book = LevelBook::from_config(&config)?;
+    let mut volume_profiler = VolumeProfiler::from_config(&config)?;
+    let entry_gate = EntryGate::from_config(&config)?;
+    let loss_streak = LossStreak::from_config(&config)?;
+    let mut cycle_clock = CycleClock::from_config(&config);
+    let mut kill_switch = DrawdownKillSwitch::from_config(&config,
+        StateStore::new(state_backend.clone(), HIGH_WATER_MARK_KEY, state_cipher.clone()))?;
+    let mut candle_feed = CandleFeed::from_config(&config)?;
+    let market_client = exchange::ExchangeClient::public(&config).await?;
+    let mut scanner = Scanner::from_config(&config, &quote_asset)?;
+    let mut btc_trend = BtcTrendFilter::from_config(&config, &quote_asset)?;
+    let session_filter = SessionFilter::from_config(&config)?;
+    // Bad OLLAMA_* settings stop startup; a missing or cold model only delays the AI
+    let ollama = if config.ollama_enabled { Some(OllamaClient::from_config(&config)?) } else { None };
+    if let Some(ollama) = &ollama
+        && config.ollama_warm_up {
+        prepare_ollama(ollama).await;
+    }
+    let ai_pool = ollama.clone().map(|client| AiPool::from_config(&config, client)).transpose()?;
+    let trade_reviewer = PostTradeReviewer::from_config(&config, &audit)?;
+    let manual_targets = ManualTargets::from_config(&config)?;
+    let mut alert_engine = AlertEngine::from_config(&config, &audit, price_watchlist.clone())?;
+    let ai_sizing = AiSizing::from_config(&config)?;
+    let mut last_ollama_request = std::time::Instant::now();
+    let locale = NumberLocale::parse(&config.report_locale)?;
+    reporter.set_language(Language::parse(&config.report_language));
+    reporter.set_retention(retention);
+    let mut fx_feed = FxFeed::from_config(&config, &quote_asset)?;
+
+    let timeouts = SourceTimeouts::from_config(&config);
+
+    // Track state
+    let mut last_ai_update = std::time::Instant::now();
+    let mut current_targets: Option<AiTradingTargets> = None;
+    let mut positions = Position::slots(&config, &[])?;
+    let mut loop_count: u64 = 0;
+
+    info!("━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━");
+    info!("🔄 Starting CONTINUOUS monitoring loop...");
+    info!("   Price check interval: {}s", PRICE_CHECK_INTERVAL_SECS);
+    info!("   AI recalculation interval: {}s", AI_RECALC_INTERVAL_SECS);
+    info!("   Press Ctrl+C to stop");
+    info!("━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━");
+
+    loop {
+        loop_count += 1;
+        info!("");
+        info!("━━━ Monitoring cycle #{} ━━━", loop_count);
+        cycle_timer.begin_cycle();
+        let stage_started = std::time::Instant::now();
+
+        // Fetch real market data from CoinGecko (or the DEX feed) alongside the simulated price
+        let snapshot = market_data::fetch_snapshot(
+            exchange.get_price(&config.symbol),
+            Some(async {
+                match dex_feed.as_mut() {
+                    Some(feed) => feed.fetch_market_data(&config.symbol).await,
+                    None => coingecko.fetch_market_data(&config.symbol).await,
+                }
+            }),
+            None::<std::future::Ready<Result<models::BookTicker>>>,
+            timeouts,
+        ).await;
+        cycle_timer.record(Stage::Fetch, stage_started.elapsed());
+        let market_data = snapshot.market;
+        if let Some(ref data) = market_data {
+            // Simulated fills follow the observed price rather than a random walk
+            exchange.set_price(&config.symbol, data.current_price).await;
+            if dex_feed.is_none() {
+                info!("✅ CoinGecko: {} @ ${:.2}", data.symbol, data.current_price);
+            }
+        }
+
+        // Get current price (from CoinGecko or simulated)
+        let current_price = match market_data {
+            Some(ref data) => data.current_price,
+            None => snapshot.price.unwrap_or(dec!(0)),
+        };
+
+        if current_price == dec!(0) {
+            warn!("❌ Could not get current price, skipping cycle");
+            cycle_timer.end_cycle();
+            tokio::time::sleep(Duration::from_secs(PRICE_CHECK_INTERVAL_SECS)).await;
+            continue;
+        }
+        recover_missed_cycles(cycle_clock.as_mut(), &market_client, &config.symbol, positions.iter_mut(),
+                              &audit, &alert_engine, &mut reporter).await;
+
+        // Update reporter with price
+        if let Some(event) = reporter.update_price(current_price) {
+            info!("🔔 ALERT: {}", event);
+        }
+
+        let closes: Vec<Decimal> = market_data.as_ref()
+            .map(|d| d.hourly_data_24h.iter().map(|h| h.close).collect())
+            .unwrap_or_default();
+        if !closes.is_empty() {
+            exposure.update_closes(&reporter.status().base_asset, &closes);
+        }
+
+        // Calculate support/resistance if we have market data
+        let stage_started = std::time::Instant::now();
+        let (sma_short, sma_long, rsi, high_24h, low_24h, change_24h) = if let Some(ref data) = market_data {
+            let closes = indicator_closes(candle_feed.as_mut(), &market_client, &config.symbol, &closes).await;
+            let sma_s = strategy::SmaCrossover::calculate_sma(&closes, 10);
+            let sma_l = strategy::SmaCrossover::calculate_sma(&closes, 20);
+            let rsi_val = strategy::RsiStrategy::calculate_rsi(&closes, 14);
+            (sma_s, sma_l, rsi_val, data.high_24h, data.low_24h, data.price_change_24h_percent)
+        } else {
+            (None, None, None, current_price * dec!(1.02), current_price * dec!(0.98), dec!(0))
+        };
+        cycle_timer.record(Stage::Indicators, stage_started.elapsed());
+
+        let options = match options_feed.as_mut() {
+            Some(feed) => feed.refresh().await.cloned(),
+            None => None,
+        };
+        reporter.update_options(options.clone());
+        let global = breadth.refresh().await.cloned();
+        reporter.update_global_market(global.clone());
+        watch_fx(&mut fx_feed, locale, &mut reporter).await;
+
+        watch_volume(&mut volume_monitor, &exchange, &config.symbol, current_price, &mut reporter, &audit).await;
+        watch_divergences(&mut divergence_monitor, &exchange, &config.symbol, &mut reporter).await;
+        watch_volume_profile(&mut volume_profiler, &exchange, &config.symbol, &mut reporter).await;
+
+        // Build market context
+        let market_context = MarketContext {
+            symbol: config.symbol.clone(),
+            current_price,
+            high_24h,
+            low_24h,
+            price_change_24h_percent: change_24h,
+            sma_short,
+            sma_long,
+            rsi,
+            volume_24h: market_data.as_ref().map(|d| d.total_volume),
+            position_entry_price: reporter.status().entry_price,
+            account_balance: reporter.status().total_portfolio_value,
+            hourly_data_summary: market_data.as_ref().map(|d| coingecko.format_for_ai(d)),
+            high_12h: market_data.as_ref().and_then(|d| d.hourly_data_12h.iter().map(|h| h.high).max()),
+            low_12h: market_data.as_ref().and_then(|d| d.hourly_data_12h.iter().map(|h| h.low).min()),
+            high_48h: market_data.as_ref().and_then(|d| d.hourly_data_48h.iter().map(|h| h.high).max()),
+            low_48h: market_data.as_ref().and_then(|d| d.hourly_data_48h.iter().map(|h| h.low).min()),
+            options_summary: options.as_ref().map(|o| o.format_for_ai(current_price)),
+            volume_summary: volume_monitor.as_ref().and_then(VolumeMonitor::format_for_ai),
+            divergence_summary: divergence_monitor.as_ref().and_then(DivergenceMonitor::format_for_ai),
+            volume_profile_summary: volume_profiler.as_ref().and_then(VolumeProfiler::profile).map(|p| p.format_for_ai()),
+            btc_dominance: global.as_ref().map(|g| g.btc_dominance),
+            market_cap_change_24h_percent: global.as_ref().map(|g| g.market_cap_change_24h_percent),
+        };
+        analysis.publish_context(&market_context);
+
+        // Update reporter market data
+        reporter.update_market_data(high_24h, low_24h, change_24h);
+
+        // Update signals
+        reporter.update_signals(crossover_signal(sma_short, sma_long), sma_short, sma_long, rsi);
+
+        // Recalculate targets periodically or if we don't have any
+        let should_recalc = current_targets.is_none() 
+            || last_ai_update.elapsed().as_secs() >= AI_RECALC_INTERVAL_SECS;
+
+        if should_recalc {
+            info!("🔄 Recalculating trading targets...");
+            
+            // Always calculate fallback first
+            let fallback = FallbackTargetCalculator::calculate_targets(&market_context);
+            current_targets = Some(fallback.clone());
+            let mut target_source = "fallback";
+            info!("📊 Fallback: {} @ {}% confidence", fallback.recommendation, fallback.confidence.round_dp(0));
+
+            // Try AI if enabled (non-blocking with timeout); chaos mode may answer for the model
+            let mut ai_targets = None;
+            if let Some(output) = chaos.as_ref().and_then(Chaos::garbled_ai_output) {
+                warn!("🐒 Chaos: analysing malformed AI output {:?}", output);
+                match OllamaClient::from_config(&config)
+                    .and_then(|ollama| ollama.parse_ai_response(output, &market_context)) {
+                    Ok(targets) => {
+                        info!("🧠 AI (chaos): {} @ {}% confidence", targets.recommendation, targets.confidence.round_dp(0));
+                        ai_targets = Some(targets);
+                    }
+                    Err(e) => warn!("⚠️ AI analysis failed: {}", e),
+                }
+            } else if let (Some(ollama), Some(pool)) = (&ollama, &ai_pool)
+                && ollama.health_check().await.unwrap_or(false) {
+                info!("🤖 Requesting AI analysis (timeout: 120s)...");
+
+                let job = AiJob { context: market_context.clone(), has_position: positions.iter().any(Position::is_open) };
+                let stage_started = std::time::Instant::now();
+                let result = pool.analyze(vec![job]).await.pop().map(|(_, result)| result);
+                cycle_timer.record(Stage::Ai, stage_started.elapsed());
+                match result {
+                    Some(Ok(targets)) => {
+                        info!("🧠 AI: {} @ {}% confidence",
+                            targets.recommendation, targets.confidence.round_dp(0));
+                        ai_targets = Some(targets);
+                    }
+                    Some(Err(e)) => warn!("⚠️ AI analysis failed: {}", e),
+                    None => {}
+                }
+                last_ollama_request = std::time::Instant::now();
+            }
+            match ai_targets {
+                Some(targets) if targets.confidence < config.min_ai_confidence => {
+                    info!("🧠 AI confidence below MIN_AI_CONFIDENCE ({}%) - keeping the fallback targets",
+                        config.min_ai_confidence.round_dp(0));
+                }
+                Some(targets) => {
+                    current_targets = Some(targets);
+                    target_source = "ai";
+                }
+                None => {}
+            }
+            // The weekly review scores the calculated targets, before configured prices are merged in
+            if let Some(mut targets) = current_targets.take() {
+                apply_levels(&mut level_book, &exchange, &config.symbol, current_price, &[], &mut targets, &mut reporter).await;
+                if let Some(profile) = volume_profiler.as_ref().and_then(VolumeProfiler::profile) {
+                    profile.apply(&mut targets, current_price);
+                }
+                audit_targets(&audit, &config.symbol, target_source, current_price, &targets);
+                let (targets, sources) = manual_targets.apply(targets, TargetSource::from_label(target_source));
+                reporter.update_ai_targets(&targets);
+                reporter.update_target_sources(sources);
+                current_targets = Some(targets);
+            }
+
+            last_ai_update = std::time::Instant::now();
+        } else if let Some(ollama) = &ollama
+            && config.ollama_ping_minutes > 0
+            && last_ollama_request.elapsed() >= Duration::from_secs(config.ollama_ping_minutes * 60) {
+            // Keep the model loaded between analyses so the next one doesn't pay for a cold load
+            if let Err(e) = ollama.warm_up().await {
+                warn!("⚠️ Ollama keep-alive ping failed: {}", e);
+            }
+            last_ollama_request = std::time::Instant::now();
+        }
+
+        // Check trade limits
+        let trade_status = trade_limiter.get_status();
+        reporter.update_trade_limits(
+            trade_status.trades_executed,
+            trade_status.can_trade,
+            trade_status.next_trading_day.clone(),
+        );
+
+        update_metrics(&metrics, &reporter);
+        if let Some(mqtt) = &mqtt {
+            mqtt.publish_status(reporter.status());
+        }
+
+        // Pause entries during planned maintenance, after a whale exchange inflow, while
+        // a bearish BTC trend drags altcoins down or outside TRADING_SESSIONS
+        let whale_pause = watch_whales(&mut whale_monitor, &mut reporter).await;
+        let btc_pause = watch_btc_trend(&mut btc_trend, &exchange, &reporter.status().base_asset).await;
+        let streak_pause = watch_loss_streak(loss_streak.as_ref(), &mut reporter);
+        let target_pause = trade_limiter.profit_target_reached();
+        let drawdown_halt = watch_drawdown(kill_switch.as_mut(), &alert_engine, &mut reporter).await;
+        let entries_paused = maintenance.update(None, &config.symbol).await
+            .map(|reason| (SkipReason::Maintenance, reason.to_string()))
+            .or(whale_pause.map(|reason| (SkipReason::Cooldown, reason)))
+            .or(btc_pause.map(|reason| (SkipReason::BtcTrend, reason)))
+            .or_else(|| session_filter.as_ref().and_then(|f| f.pause_reason(chrono::Utc::now()))
+                .map(|reason| (SkipReason::Session, reason)))
+            .or(streak_pause.map(|reason| (SkipReason::LossStreak, reason)))
+            .or(target_pause.map(|reason| (SkipReason::TargetAchieved, reason)))
+            .or(drawdown_halt.map(|reason| (SkipReason::Drawdown, reason)))
+            .or_else(|| control.pause_reason().map(|reason| (SkipReason::Paused, reason)));
+        reporter.update_entries_paused(entries_paused.as_ref().map(|(_, reason)| reason.clone()));
+        analysis.publish(reporter.status());
+        control.update(reporter.status());
+        alert_engine.check(&mut reporter).await;
+
+        // Trading logic - check if targets are hit, for each position slot; a manual order
+        // from the control interface stands in for its slot's targets
+        let mut manual_order = control.take_order();
+        if let Some(ref targets) = current_targets {
+            let mut decisions = Vec::new();
+            for position in positions.iter_mut() {
+                let can_trade = entries_paused.is_none()
+                    && matches!(trade_limiter.can_trade(), TradePermission::Allowed { .. });
+                let can_exit = trade_limiter.can_exit().is_allowed();
+                // The AI sleeve backs the main position only
+                let book = strategy_book.as_mut().filter(|_| position.is_main());
+                let manual = manual_order.take_if(|order| order.applies_to(position));
+
+                position.set_targets(targets.stop_loss_price, targets.take_profit_price, targets.sell_target_price);
+
+                if position.is_open() {
+                    // We have a position - check for exit signals
+                    position.track_excursion(current_price);
+                    let trigger = if manual.is_some() { Some(ExitTrigger::Manual) } else { position.exit_trigger(current_price) };
+                    match trigger {
+                        Some(ExitTrigger::StopLoss) => info!("🔴 STOP-LOSS TRIGGERED at ${:.2} ({})!", current_price, position.id),
+                        Some(ExitTrigger::TakeProfit) => info!("🟢 TAKE-PROFIT TRIGGERED at ${:.2} ({})!", current_price, position.id),
+                        Some(ExitTrigger::SellTarget) => info!("💜 SELL TARGET reached at ${:.2} ({})!", current_price, position.id),
+                        Some(ExitTrigger::MaxHoldingTime) => info!("⌛ MAX HOLDING TIME reached at ${:.2} ({})!", current_price, position.id),
+                        Some(ExitTrigger::Manual) => info!("🕹️ MANUAL SELL at ${:.2} ({})", current_price, position.id),
+                        None => {}
+                    }
+                    let decision = match trigger {
+                        // The stop-loss is always honored
+                        Some(ExitTrigger::StopLoss) => {
+                            let qty = position.qty;
+                            match execute_sell(&exchange, position, current_price, config.trading_fee_percent, ExitTrigger::StopLoss,
+                                               &mut reporter, &mut trade_limiter, &audit, &funds, book,
+                                               trade_reviewer.as_ref()).await {
+                                Ok(()) => {
+                                    if !can_exit {
+                                        audit_limit_override(&audit, &trade_status, &config.symbol, ExitTrigger::StopLoss.label(),
+                                                             current_price, qty);
+                                    }
+                                    exited(ExitTrigger::StopLoss.label(), current_price)
+                                }
+                                Err(e) => order_failed(&format!("{} exit", ExitTrigger::StopLoss.label()), e),
+                            }
+                        }
+                        Some(trigger) if can_exit => {
+                            match execute_sell(&exchange, position, current_price, config.trading_fee_percent, trigger,
+                                               &mut reporter, &mut trade_limiter, &audit, &funds, book,
+                                               trade_reviewer.as_ref()).await {
+                                Ok(()) => exited(trigger.label(), current_price),
+                                Err(e) => order_failed(&format!("{} exit", trigger.label()), e),
+                            }
+                        }
+                        Some(trigger) => {
+                            if trigger == ExitTrigger::TakeProfit {
+                                warn!("⚠️ Cannot execute - daily trade limit reached");
+                            }
+                            exit_held_back(trigger.label())
+                        }
+                        None => HOLDING.to_string(),
+                    };
+                    if let Some(order) = manual {
+                        order.answer(position, &decision);
+                    }
+                    decisions.push(format!("{}: {}", position.id, decision));
+                } else {
+                    // No position - check for entry signals
+                    let (score, low_score) = entry_quality(entry_gate.as_ref(), reporter.status());
+                    let (entry_reason, buy_target) = match manual {
+                        Some(_) => ("Manual", Some(current_price)),
+                        None => ("Buy target", targets.buy_target_price),
+                    };
+                    let decision = match buy_target {
+                        None => "no buy target".to_string(),
+                        Some(buy_target) if current_price > buy_target => waiting_for(buy_target),
+                        Some(_) if !can_trade => entry_blocked(&metrics, &mut trade_limiter, entries_paused.as_ref()),
+                        Some(_) if manual.is_none() && !volume_confirms(&volume_monitor, &position.id) => skipped(&metrics, SkipReason::Volume, NO_VOLUME),
+                        Some(_) if manual.is_none() && low_score.is_some() => skipped(&metrics, SkipReason::Quality, low_score.as_deref().unwrap_or_default()),
+                        Some(buy_target) => {
+                            info!("💚 {} entry at ${:.2} ({})!", entry_reason.to_uppercase(), current_price, position.id);
+
+                            let balance = reporter.status().balances.get(funds.quote_asset()).copied().unwrap_or(dec!(0));
+                            let trade_amount = entry_budget(position, &config, funds.available(balance), book.as_deref(),
+                                                            ai_sizing.as_ref(), targets, reporter.status());
+                            let qty = trade_amount / current_price;
+
+                            if qty <= dec!(0) {
+                                skipped(&metrics, SkipReason::Funds, "no funds available")
+                            } else if let Some(reason) = risk_check(&exposure, &var_limit, qty * current_price, reporter.status()) {
+                                skipped(&metrics, SkipReason::Risk, &reason)
+                            } else {
+                                match execute_buy(&exchange, position, qty, current_price, config.trading_fee_percent, entry_reason,
+                                                  Some(buy_target), &mut reporter, &mut trade_limiter, &audit, &funds, book).await {
+                                    Ok(()) => scored(entered(current_price), score),
+                                    Err(e) => order_failed("entry", e),
+                                }
+                            }
+                        }
+                    };
+                    if let Some(order) = manual {
+                        order.answer(position, &decision);
+                    }
+                    decisions.push(format!("{}: {}", position.id, decision));
+                }
+            }
+            reporter.update_positions(&positions);
+            reporter.record_decision(decisions.join(" | "));
+        } else {
+            reporter.record_decision(skipped(&metrics, SkipReason::WarmUp, NO_TARGETS));
+        }
+        decline_manual_order(manual_order, &positions, current_targets.is_none().then_some(NO_TARGETS));
+
+        // Signal-driven strategy sleeves trade their own share of the capital
+        if let Some(book) = strategy_book.as_mut() {
+            for (sleeve, side, qty) in book.pending_orders(&closes, current_price) {
+                let allowed = match side {
+                    models::OrderSide::Buy => entries_paused.is_none()
+                        && matches!(trade_limiter.can_trade(), TradePermission::Allowed { .. })
+                        && risk_allows(&exposure, &var_limit, qty * current_price, reporter.status()),
+                    models::OrderSide::Sell => trade_limiter.can_exit().is_allowed(),
+                };
+                if !allowed {
+                    continue;
+                }
+                match exchange.place_order(&config.symbol, side, models::OrderType::Market, qty, None).await {
+                    Ok(order) => {
+                        let fill = order_execution(&order, &config.symbol, current_price, qty, config.trading_fee_percent);
+                        let context = reporter.status().trade_context(&format!("{} sleeve", sleeve.label()), None);
+                        record_fill(&mut trade_limiter, &audit, None, &config.symbol, side, &fill, context)?;
+                        match side {
+                            models::OrderSide::Buy => book.record_entry(sleeve, fill.price, fill.qty, fill.fee),
+                            models::OrderSide::Sell => {
+                                let pnl = book.record_exit(sleeve, fill.price, fill.fee);
+                                trade_limiter.update_pnl(pnl);
+                            }
+                        }
+                        info!("🧩 {} {}: {} @ ${:.2}", sleeve.label(), side, fill.qty.round_dp(6), fill.price);
+                    }
+                    Err(e) => warn!("⚠️ {} order failed: {}", sleeve.label(), e),
+                }
+            }
+            book.mark(current_price, chrono::Utc::now());
+            reporter.update_strategies(book.summaries(current_price));
+        }
+
+        // Screen the watchlist; a promoted candidate replaces the symbol once everything is flat
+        if let Some(scanner) = scanner.as_mut() {
+            if scanner.is_due() {
+                if let Err(e) = scanner.scan(&exchange).await {
+                    warn!("⚠️ Scanner failed: {}", e);
+                }
+                reporter.update_scanner(scanner.candidates.clone());
+            }
+            if let Some(next) = scanner.promotion(&config.symbol)
+                && positions.iter().all(Position::is_flat)
+                && strategy_book.is_none() {
+                info!("🔀 Scanner promoted {} - switching from {}", next, config.symbol);
+                config.symbol = next.to_string();
+                reporter.switch_symbol(&config.symbol);
+                positions = Position::slots(&config, &[])?;
+                current_targets = None;
+            }
+        }
+
+        // Update balances: only the traded pair's move, so read just those two
+        let pair = symbols::lookup(&config.symbol);
+        for asset in [&pair.base, &pair.quote] {
+            let free = exchange.free_balance(asset).await;
+            if reporter.status().balances.get(asset).copied().unwrap_or_default() != free {
+                reporter.apply_balance_update(asset, free);
+            }
+        }
+        push_equity_point(&mut equity_history, reporter.status().total_portfolio_value, chrono::Utc::now());
+        reporter.update_value_at_risk(risk::equity_var(&equity_history, var_limit.confidence()), var_limit.max_percent());
+
+        // Write report, archiving it first at local midnight
+        let stage_started = std::time::Instant::now();
+        rollover.run_if_due(&mut reporter, &paths, &audit).await;
+        reporter.write_if_dirty()?;
+        cycle_timer.record(Stage::Report, stage_started.elapsed());
+
+        // Log current state summary
+        if let Some(ref targets) = current_targets {
+            info!("📍 Price: ${:.2} | SL: ${:.2} | TP: ${:.2}", 
+                current_price, targets.stop_loss_price, targets.take_profit_price);
+            if let (Some(buy), Some(sell)) = (targets.buy_target_price, targets.sell_target_price) {
+                info!("   Buy Target: ${:.2} | Sell Target: ${:.2}", buy, sell);
+            }
+            info!("   Position: {} | Trades today: {}/{}",
+                open_positions_label(&positions), trade_status.trades_executed, trade_status.max_trades);
+            info!("   Committed funds: ${:.2} {}", funds.total_committed(), funds.quote_asset());
+        }
+
+        // Wait before next cycle
+        cycle_timer.end_cycle();
+        info!("💤 Sleeping {}s until next check...", PRICE_CHECK_INTERVAL_SECS);
+        tokio::time::sleep(Duration::from_secs(PRICE_CHECK_INTERVAL_SECS)).await;
+    }
+}
+
+/// Check the model is pulled and load it before the first analysis, which would otherwise
+/// spend its timeout on the cold load
+async fn prepare_ollama(ollama: &OllamaClient) {
+    if let Err(e) = ollama.verify_model().await {
+        error!("❌ {} - AI analysis uses the fallback targets until it is available", e);
+        return;
+    }
+    info!("🔥 Warming up Ollama model {}...", ollama.model());
+    match ollama.warm_up().await {
+        Ok(took) => info!("✅ Ollama model {} loaded in {:.1}s", ollama.model(), took.as_secs_f64()),
+        Err(e) => warn!("⚠️ Ollama warm-up failed: {}", e),
+    }
+}
+
+/// Fill price, quantity and commission of an order placed at `price` for `qty`
+fn order_execution(order: &models::Order, symbol: &str, price: Decimal, qty: Decimal, fee_percent: Decimal) -> models::Execution {
+    let info = symbols::lookup(symbol);
+    order.execution(&info.base, &info.quote, price, qty, fee_percent)
+}
+
+/// Counts a fill against the daily limits and keeps the decision behind it in the trade
+/// record and the audit log
+fn record_fill(
+    trade_limiter: &mut TradeLimiter,
+    audit: &AuditLog,
+    position_id: Option<&str>,
+    symbol: &str,
+    side: models::OrderSide,
+    fill: &models::Execution,
+    context: TradeContext,
+) -> Result<()> {
+    audit.record(AuditEvent::Trade {
+        symbol: symbol.to_string(),
+        side: side.to_string(),
+        price: fill.price,
+        quantity: fill.qty,
+        position_id: position_id.map(str::to_string),
+        context: context.clone(),
+    });
+    trade_limiter.record_position_trade(position_id, symbol, &side.to_string(), fill.price, fill.qty, Some(context))
+}
+
+/// Market-buy `qty` into `position`, reserving the quote funds first and recording the fill
+#[allow(clippy::too_many_arguments)]
+pub async fn execute_buy(
+    exchange: &impl Exchange,
+    position: &mut Position,
+    qty: Decimal,
+    price: Decimal,
+    fee_percent: Decimal,
+    reason: &str,
+    target: Option<Decimal>,
+    reporter: &mut PortfolioReporter,
+    trade_limiter: &mut TradeLimiter,
+    audit: &AuditLog,
+    funds: &FundsLedger,
+    strategy_book: Option<&mut StrategyBook>,
+) -> Result<()> {
+    let symbol = &position.symbol.clone();
+    let key = &position.ledger_key();
+    let cost = qty * price;
+    let free = reporter.status().balances.get(funds.quote_asset()).copied().unwrap_or(dec!(0));
+    funds.reserve(key, cost, free)?;
+    position.begin_entry(qty)?;
+
+    let order = match exchange.place_position_order(
+        &position.id,
+        symbol,
+        models::OrderSide::Buy,
+        models::OrderType::Market,
+        qty,
+        None,
+    ).await {
+        Ok(order) => order,
+        Err(e) => {
+            funds.cancel(key);
+            position.entry_failed()?;
+            return Err(e);
+        }
+    };
+    let fill = order_execution(&order, symbol, price, qty, fee_percent);
+    funds.commit(key, fill.qty * fill.price);
+    position.entry_filled(fill)?;
+    
+    let context = reporter.status().trade_context(reason, target);
+    record_fill(trade_limiter, audit, Some(&position.id), symbol, models::OrderSide::Buy, &fill, context)?;
+    reporter.record_trade(models::OrderSide::Buy, fill.price, fill.qty, None, fill.fee);
+    if let Some(book) = strategy_book {
+        book.record_entry(SleeveStrategy::AiTargets, fill.price, fill.qty, fill.fee);
+    }
+    
+    info!("✅ BUY executed ({}): {} @ ${:.2} (trigger ${:.2}) | fee ${:.4}",
+        position.id, fill.qty.round_dp(6), fill.price.round_dp(2), price.round_dp(2), fill.fee);
+    Ok(())
+}
+
+/// Market-sell the whole of `position` on `trigger`, recording the fill and the realized P&L
+#[allow(clippy::too_many_arguments)]
+pub async fn execute_sell(
+    exchange: &impl Exchange,
+    position: &mut Position,
+    price: Decimal,
+    fee_percent: Decimal,
+    trigger: ExitTrigger,
+    reporter: &mut PortfolioReporter,
+    trade_limiter: &mut TradeLimiter,
+    audit: &AuditLog,
+    funds: &FundsLedger,
+    strategy_book: Option<&mut StrategyBook>,
+    reviewer: Option<&PostTradeReviewer>,
+) -> Result<()> {
+    let symbol = &position.symbol.clone();
+    let qty = position.qty;
+    let level = position.exit_level(trigger);
+    position.begin_exit()?;
+
+    let order = match exchange.place_position_order(
+        &position.id,
+        symbol,
+        models::OrderSide::Sell,
+        models::OrderType::Market,
+        qty,
+        None,
+    ).await {
+        Ok(order) => order,
+        Err(e) => {
+            position.exit_failed()?;
+            return Err(e);
+        }
+    };
+    funds.release(&position.ledger_key());
+    let fill = order_execution(&order, symbol, price, qty, fee_percent);
+    let before = position.clone();
+    let pnl = position.exit_filled(fill)?;
+    review_closed_trade(reviewer, &before, fill.price, pnl, trigger);
+    if trigger == ExitTrigger::StopLoss
+        && let Some(gap) = before.stop_gap_alert(fill) {
+        warn!("{}", gap);
+    }
+    
+    let context = reporter.status().trade_context(trigger.label(), level);
+    record_fill(trade_limiter, audit, Some(&position.id), symbol, models::OrderSide::Sell, &fill, context)?;
+    trade_limiter.update_pnl(pnl);
+    reporter.record_trade(models::OrderSide::Sell, fill.price, fill.qty, Some(pnl), fill.fee);
+    if let Some(book) = strategy_book {
+        book.record_exit(SleeveStrategy::AiTargets, fill.price, fill.fee);
+    }
+    
+    let pnl_emoji = if pnl >= dec!(0) { "🟢" } else { "🔴" };
+    info!("{} SELL executed ({}): {} @ ${:.2} (trigger ${:.2}) | P&L: ${:.2} after ${:.4} fees", 
+        pnl_emoji, position.id, fill.qty.round_dp(6), fill.price.round_dp(2), price.round_dp(2), pnl.round_dp(2), fill.fee);
+    Ok(())
+}
+
+/// Hand a just-closed trade to the post-trade reviewer, when AI_TRADE_REVIEW is on
+fn review_closed_trade(reviewer: Option<&PostTradeReviewer>, before: &Position, exit_price: Decimal, pnl: Decimal,
+                       trigger: ExitTrigger) {
+    if let Some(reviewer) = reviewer
+        && let Some(trade) = ClosedTrade::from_position(before, exit_price, pnl, trigger.label()) {
+        reviewer.spawn(trade);
+    }
+}
+
+/// Quote amount a flat position may enter with: the scalp takes SCALP_SIZE_PERCENT of the
+/// unreserved balance, the main position POSITION_SIZE_PERCENT of it (or the AI's size under
+/// AI_SIZING) or the AI sleeve's cash, any of them cut back during a losing streak
+fn entry_budget(position: &Position, config: &Config, available: Decimal, book: Option<&StrategyBook>,
+                ai_sizing: Option<&AiSizing>, targets: &AiTradingTargets, status: &PortfolioStatus) -> Decimal {
+    let budget = match book {
+        _ if !position.is_main() => available * config.scalp_size_percent / dec!(100),
+        Some(book) => book.ai_entry_budget(),
+        None => {
+            let configured = available * config.position_size_percent / dec!(100);
+            ai_sizing.map_or(configured, |sizing| sizing.entry_budget(targets, configured, available, status))
+        }
+    };
+    loss_backoff(budget, status)
+}
+
+/// `amount` cut to the losing streak's share of it, while LOSS_STREAK_LIMIT backs off
+fn loss_backoff(amount: Decimal, status: &PortfolioStatus) -> Decimal {
+    status.loss_backoff_percent.map_or(amount, |percent| amount * percent / dec!(100))
+}
+
+/// Refresh the losing-streak size cut shown in the report; the pause reason while it stops
+/// entries altogether
+fn watch_loss_streak(loss_streak: Option<&LossStreak>, reporter: &mut PortfolioReporter) -> Option<String> {
+    let streak = loss_streak?;
+    let losses = reporter.status().consecutive_losses;
+    reporter.update_loss_backoff(streak.backoff_percent(losses));
+    streak.pause_reason(losses)
+}
+
+/// After a stall, host sleep or price outage longer than CYCLE_GAP_SECS: fetch the candles
+/// the loop missed, flag the stops the price crossed meanwhile so they exit this cycle, and
+/// record the gap
+async fn recover_missed_cycles<'a>(clock: Option<&mut CycleClock>, exchange: &impl Exchange, symbol: &str,
+                                   positions: impl IntoIterator<Item = &'a mut Position>, audit: &AuditLog,
+                                   alert_engine: &AlertEngine, reporter: &mut PortfolioReporter) {
+    let Some(gap) = clock.and_then(|clock| clock.tick(chrono::Utc::now())) else {
+        return;
+    };
+    warn!("⏰ {} - re-checking the stops against the missed candles", gap);
+    let (interval, limit) = gap.candles();
+    let range = match exchange.get_klines(symbol, interval, limit).await {
+        Ok(klines) => gap.price_range(&klines),
+        Err(e) => {
+            warn!("⚠️ Failed to fetch the candles of the gap: {}", e);
+            None
+        }
+    };
+    let missed_stops = range.map(|(low, high)| cycle_gap::recheck(positions, low, high)).unwrap_or_default();
+    audit.record(AuditEvent::CycleGap {
+        symbol: symbol.to_string(),
+        from: gap.from,
+        to: gap.to,
+        low: range.map(|(low, _)| low),
+        high: range.map(|(_, high)| high),
+        missed_stops: missed_stops.clone(),
+    });
+    if missed_stops.is_empty() {
+        reporter.set_last_event(format!("⏰ {}", gap));
+    } else {
+        let message = format!("⏰ STOP CROSSED DURING A GAP ({}): {} - exiting now", missed_stops.join(", "), gap);
+        error!("{}", message);
+        alert_engine.raise(reporter, "cycle gap".to_string(), message).await;
+    }
+}
+
+/// Track the equity high-water mark; the halt reason once MAX_DRAWDOWN_PERCENT trips, alerting
+/// the cycle it does
+async fn watch_drawdown(kill_switch: Option<&mut DrawdownKillSwitch>, alert_engine: &AlertEngine,
+                        reporter: &mut PortfolioReporter) -> Option<String> {
+    let kill_switch = kill_switch?;
+    if let Some(tripped) = kill_switch.update(reporter.status().total_portfolio_value, chrono::Utc::now()) {
+        error!("🛑 Drawdown kill switch tripped: {} - entries halted until `crypto_trading_bot reset-drawdown`", tripped);
+        alert_engine.raise(reporter, "drawdown".to_string(), format!("🛑 Trading halted: {}", tripped)).await;
+    }
+    kill_switch.halt_reason()
+}
+
+/// "LONG (main, scalp)" for the open positions, "NONE" when all are flat
+fn open_positions_label(positions: &[Position]) -> String {
+    let open: Vec<&str> = positions.iter().filter(|p| p.is_open()).map(|p| p.id.as_str()).collect();
+    if open.is_empty() { "NONE".to_string() } else { format!("LONG ({})", open.join(", ")) }
+}
+
+/// Quote funds entries may be sized from; borrowed funds count only with MARGIN_BORROW_ENABLED
+fn spendable_quote(config: &Config, status: &PortfolioStatus, quote: &str) -> Decimal {
+    if config.margin_borrow_enabled {
+        status.balances.get(quote).copied().unwrap_or(dec!(0))
+    } else {
+        status.owned_balance(quote)
+    }
+}
+
+/// Report new whale inflows of the base asset; returns the entry pause reason while a cooldown runs
+async fn watch_whales(monitor: &mut Option<WhaleMonitor>, reporter: &mut PortfolioReporter) -> Option<String> {
+    let monitor = monitor.as_mut()?;
+    let asset = reporter.status().base_asset.clone();
+    for transfer in monitor.poll(&asset).await {
+        warn!("🐋 Whale alert: {} {} (${}) moved to {} - tx {}",
+            transfer.amount.round_dp(2), transfer.asset, transfer.value_usd.round_dp(0), transfer.exchange, transfer.hash);
+        reporter.set_last_event(format!("🐋 {} {} (${}) to {}",
+            transfer.amount.round_dp(2), transfer.asset, transfer.value_usd.round_dp(0), transfer.exchange));
+    }
+    monitor.pause_reason()
+}
+
+/// Refresh the BTC trend; returns the reason altcoin longs are held back while it is bearish
+async fn watch_btc_trend(filter: &mut Option<BtcTrendFilter>, exchange: &impl Exchange, base_asset: &str) -> Option<String> {
+    let filter = filter.as_mut()?;
+    filter.refresh(exchange).await;
+    filter.long_entry_block(base_asset)
+}
+
+/// Apply REPORT_LOCALE and the latest DISPLAY_CURRENCY rate to the report
+async fn watch_fx(feed: &mut Option<FxFeed>, locale: NumberLocale, reporter: &mut PortfolioReporter) {
+    let currency = match feed.as_mut() {
+        Some(feed) => feed.refresh().await,
+        None => None,
+    };
+    reporter.update_display(DisplayFormat { locale, currency });
+}
+
+/// Refresh the hourly volume ratio; a new spike is reported and audited
+async fn watch_volume(
+    monitor: &mut Option<VolumeMonitor>,
+    exchange: &impl Exchange,
+    symbol: &str,
+    price: Decimal,
+    reporter: &mut PortfolioReporter,
+    audit: &AuditLog,
+) {
+    let Some(monitor) = monitor.as_mut() else {
+        return;
+    };
+    if let Some(spike) = monitor.poll(exchange, symbol).await {
+        warn!("📊 Volume spike: {} traded {} this hour, {}x the average of {}",
+            symbol, spike.volume.round_dp(2), spike.ratio, spike.average.round_dp(2));
+        reporter.set_last_event(format!("📊 Volume spike: {}x average", spike.ratio));
+        audit.record(AuditEvent::VolumeSpike {
+            symbol: symbol.to_string(), price, volume: spike.volume, average: spike.average, ratio: spike.ratio,
+        });
+    }
+    reporter.update_volume_ratio(monitor.ratio());
+}
+
+/// With SR_LEVELS_ENABLED, rescore the levels, merge in the order book `walls` and take the
+/// support, resistance and buy/sell targets from the best ones near the price
+async fn apply_levels(
+    book: &mut Option<LevelBook>,
+    exchange: &impl Exchange,
+    symbol: &str,
+    price: Decimal,
+    walls: &[Wall],
+    targets: &mut AiTradingTargets,
+    reporter: &mut PortfolioReporter,
+) {
+    let Some(book) = book.as_mut() else {
+        return;
+    };
+    book.refresh(exchange, symbol).await;
+    book.merge_walls(walls, chrono::Utc::now().timestamp_millis());
+    book.apply(targets, price);
+    reporter.update_levels(book.levels());
+}
+
+/// Snapshot the order book when it's due and show the persistent walls in the report
+async fn watch_liquidity(
+    map: &mut Option<LiquidityMap>,
+    exchange: &exchange::ExchangeClient,
+    symbol: &str,
+    reporter: &mut PortfolioReporter,
+) {
+    let Some(map) = map.as_mut() else {
+        return;
+    };
+    map.poll(exchange, symbol).await;
+    reporter.update_liquidity_walls(map.walls());
+}
+
+/// Rebuild the volume profile when it's due and show it in the report
+async fn watch_volume_profile(
+    profiler: &mut Option<VolumeProfiler>,
+    exchange: &impl Exchange,
+    symbol: &str,
+    reporter: &mut PortfolioReporter,
+) {
+    let Some(profiler) = profiler.as_mut() else {
+        return;
+    };
+    profiler.poll(exchange, symbol).await;
+    reporter.update_volume_profile(profiler.profile());
+}
+
+/// Refresh the divergences and log the ones that just formed
+async fn watch_divergences(
+    monitor: &mut Option<DivergenceMonitor>,
+    exchange: &impl Exchange,
+    symbol: &str,
+    reporter: &mut PortfolioReporter,
+) {
+    let Some(monitor) = monitor.as_mut() else {
+        return;
+    };
+    for divergence in monitor.poll(exchange, symbol).await {
+        info!("📐 {} divergence: {}", symbol, divergence);
+        reporter.set_last_event(format!("📐 Divergence: {}", divergence));
+    }
+    reporter.update_divergences(monitor.divergences());
+}
+
+/// With VOLUME_CONFIRMATION, a buy target is only acted on during a volume spike
+fn volume_confirms(monitor: &Option<VolumeMonitor>, position_id: &str) -> bool {
+    match monitor {
+        Some(monitor) if !monitor.confirms_entry() => {
+            info!("⏳ Buy target reached ({}) - waiting for volume to confirm", position_id);
+            false
+        }
+        _ => true,
+    }
+}
+
+/// Correlated-exposure and VaR gate for new entries in the traded symbol
+fn risk_allows(exposure: &ExposureLimits, var_limit: &VarLimit, value: Decimal, status: &PortfolioStatus) -> bool {
+    risk_check(exposure, var_limit, value, status).is_none()
+}
+
+/// Why the risk limits reject an entry of `value`, if they do
+fn risk_check(exposure: &ExposureLimits, var_limit: &VarLimit, value: Decimal, status: &PortfolioStatus) -> Option<String> {
+    let check = exposure.check_entry(&status.base_asset, value, status)
+        .and_then(|()| var_limit.check_entry(status.value_at_risk.as_ref(), exposure.returns(&status.base_asset),
+            value, status.total_portfolio_value));
+    match check {
+        Ok(()) => None,
+        Err(reason) => {
+            warn!("⚠️ Skipping entry: {}", reason);
+            Some(reason.to_string())
+        }
+    }
+}
+
+// Lines of the report's decision log
+const HOLDING: &str = "holding - no exit target hit";
+const LIMIT_REACHED: &str = "daily trade limit reached";
+const NO_VOLUME: &str = "waiting for volume to confirm";
+const NO_TARGETS: &str = "no targets yet";
+
+fn entered(price: Decimal) -> String {
+    format!("entered @ {:.2}", price)
+}
+
+fn exited(reason: &str, price: Decimal) -> String {
+    format!("exited @ {:.2} - {}", price, reason.to_lowercase())
+}
+
+/// The order was not placed; the position is left as it was and the next cycle retries
+fn order_failed(what: &str, error: anyhow::Error) -> String {
+    error!("❌ {} order failed: {}", what, error);
+    format!("{} order failed", what.to_lowercase())
+}
+
+fn exit_held_back(reason: &str) -> String {
+    format!("{} hit, exit held back - {}", reason.to_lowercase(), LIMIT_REACHED)
+}
+
+/// Buy while the short SMA is above the long one, sell below it
+fn crossover_signal(sma_short: Option<Decimal>, sma_long: Option<Decimal>) -> models::Signal {
+    match (sma_short, sma_long) {
+        (Some(short), Some(long)) if short > long => models::Signal::Buy,
+        (Some(_), Some(_)) => models::Signal::Sell,
+        _ => models::Signal::Hold,
+    }
+}
+
+/// The entry score under ENTRY_MIN_SCORE, with the decision text when it falls short
+fn entry_quality(gate: Option<&EntryGate>, status: &PortfolioStatus) -> (Option<EntryScore>, Option<String>) {
+    let Some(gate) = gate else {
+        return (None, None);
+    };
+    let score = EntryScore::from_status(status);
+    let shortfall = (!gate.admits(&score)).then(|| format!("entry score {} below {}", score, gate.min_score()));
+    (Some(score), shortfall)
+}
+
+/// A decision with the entry score it was taken at
+fn scored(decision: String, score: Option<EntryScore>) -> String {
+    match score {
+        Some(score) => format!("{}, entry score {}", decision, score),
+        None => decision,
+    }
+}
+
+fn waiting_for(buy_target: Decimal) -> String {
+    format!("waiting - price above the buy target {:.2}", buy_target)
+}
+
+/// Why a slot did not enter at its target, the `reason` label of bot_entries_skipped_total
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum SkipReason {
+    /// No targets yet, before the first analysis
+    WarmUp,
+    /// Daily trade limit reached
+    Limit,
+    Maintenance,
+    /// Whale inflow cooldown
+    Cooldown,
+    BtcTrend,
+    Session,
+    Volume,
+    /// Entry score below ENTRY_MIN_SCORE
+    Quality,
+    /// Paused by LOSS_STREAK_LIMIT until a winning trade
+    LossStreak,
+    /// DAILY_PROFIT_TARGET reached for the day
+    TargetAchieved,
+    /// Halted by MAX_DRAWDOWN_PERCENT until `reset-drawdown`
+    Drawdown,
+    /// Paused through the control interface until resumed
+    Paused,
+    Risk,
+    MinSize,
+    Funds,
+    /// The position slot has an order in flight
+    State,
+}
+
+impl SkipReason {
+    fn code(self) -> &'static str {
+        match self {
+            Self::WarmUp => "warm_up",
+            Self::Limit => "limit",
+            Self::Maintenance => "maintenance",
+            Self::Cooldown => "cooldown",
+            Self::BtcTrend => "btc_trend",
+            Self::Session => "session",
+            Self::Volume => "volume",
+            Self::Quality => "quality",
+            Self::LossStreak => "loss_streak",
+            Self::TargetAchieved => "target_achieved",
+            Self::Drawdown => "drawdown",
+            Self::Paused => "paused",
+            Self::Risk => "risk",
+            Self::MinSize => "min_size",
+            Self::Funds => "funds",
+            Self::State => "state",
+        }
+    }
+}
+
+/// Count a skipped entry and describe it for the decision log
+fn skipped(metrics: &Metrics, reason: SkipReason, text: &str) -> String {
+    metrics.inc_labeled_counter("bot_entries_skipped_total", "Entries skipped, by reason", &[("reason", reason.code())]);
+    format!("skipped - {}", text)
+}
+
+/// An entry held back by a pause, or by the daily trade limit without one
+fn entry_blocked(metrics: &Metrics, trade_limiter: &mut TradeLimiter, paused: Option<&(SkipReason, String)>) -> String {
+    match paused {
+        Some((reason, text)) => skipped(metrics, *reason, text),
+        None => {
+            trade_limiter.record_blocked_entry();
+            skipped(metrics, SkipReason::Limit, LIMIT_REACHED)
+        }
+    }
+}
+
+/// Answer a manual order no position slot took this cycle; `blocked` when trading didn't run
+fn decline_manual_order(order: Option<ManualOrder>, positions: &[Position], blocked: Option<&str>) {
+    if let Some(order) = order {
+        let reason = blocked.map_or_else(|| order.unmatched_reason(positions), str::to_string);
+        order.decline(&reason);
+    }
+}
+
+/// Stop-loss exits are never blocked by the trade limiter; every bypass that filled is audited
+fn audit_limit_override(
+    audit: &AuditLog,
+    status: &TradingStatus,
+    symbol: &str,
+    reason: &str,
+    price: Decimal,
+    qty: Decimal,
+) {
+    warn!("⚠️ Daily trade limit reached - {} exit executed anyway", reason);
+    audit.record(AuditEvent::LimitOverride {
+        symbol: symbol.to_string(),
+        reason: reason.to_string(),
+        price,
+        quantity: qty,
+        trades_executed: status.trades_executed,
+    });
+}
+
+/// Log the targets chosen at a recalculation for the weekly review
+fn audit_targets(audit: &AuditLog, symbol: &str, source: &str, price: Decimal, targets: &AiTradingTargets) {
+    audit.record(AuditEvent::Targets {
+        symbol: symbol.to_string(),
+        source: source.to_string(),
+        price,
+        stop_loss: targets.stop_loss_price,
+        take_profit: targets.take_profit_price,
+        support: targets.support,
+        resistance: targets.resistance,
+        recommendation: targets.recommendation.to_string(),
+        confidence: targets.confidence,
+    });
+}
+
+/// Continuous monitoring loop for live trading
+pub async fn run_live_loop(mut config: Config, heartbeat: Heartbeat) -> Result<()> {
+    let exchange = Arc::new(exchange::ExchangeClient::new(&config).await?);
+    info!("✅ Connected to exchange");
+
+    // Refuse to run with a key that could move funds off the exchange
+    let permissions = exchange.get_api_permissions().await.map_err(|e| {
+        if BotError::find(&e).and_then(BotError::kind) == Some(ErrorKind::Unauthorized) {
+            e.context("API key rejected - check API_KEY/API_SECRET and the key's IP whitelist")
+        } else {
+            e
+        }
+    })?;
+    permissions.verify(config.live_trading_enabled)?;
+    info!("🔐 API key permissions: read={} trade={} withdraw={}",
+        permissions.can_read, permissions.can_trade, permissions.can_withdraw);
+    if !permissions.can_trade {
+        warn!("⚠️ API key cannot trade - running alert-only");
+    }
+    if permissions.ip_restricted == Some(false) {
+        warn!("⚠️ API key is not IP-restricted; consider restricting it to this host");
+    }
+
+    let balance = exchange.get_balance().await?;
+    let balance_map: std::collections::HashMap<String, Decimal> = balance
+        .iter()
+        .map(|(k, v)| (k.clone(), v.free))
+        .collect();
+    info!("💰 Account balance: {:?}", balance);
+
+    let (symbol, quote_asset) = quote::resolve_pair(
+        &config.symbol, config.quote_asset.as_deref(), Some(&balance_map))?;
+    if symbol != config.symbol {
+        info!("💱 Trading {} (quote asset {})", symbol, quote_asset);
+    }
+    config.symbol = symbol;
+
+    let mut symbol_info = match exchange.get_symbol_info(&config.symbol).await {
+        Ok(info) => info,
+        Err(e) => {
+            warn!("⚠️ Could not load exchange filters for {}: {} - using defaults", config.symbol, e);
+            symbols::lookup(&config.symbol)
+        }
+    };
+    info!("📏 {}: qty step {} dp, min qty {}, min notional {} {}", symbol_info.symbol,
+        symbol_info.qty_precision, symbol_info.min_qty, symbol_info.min_notional, symbol_info.quote);
+    if self_test::enabled(&config) {
+        self_test::run_live(&exchange, &symbol_info).await?;
+    }
+
+    let paths = DataPaths::from_config(&config)?;
+    info!("📁 Data directory: {}", paths.root().display());
+    let mut reporter = PortfolioReporter::new(&config.symbol, false, &paths.report_file());
+    let coingecko = CoinGeckoClient::new();
+    let state_backend = state_store::backend_from_config(&config, &paths).await?;
+    // Held until the loop exits; a second instance on the same account refuses to start
+    let instance_lock = state_backend.lock(&state_store::account_lock_name(&config))?;
+    info!("🔒 Instance lock acquired ({})", instance_lock.holder());
+    let state_cipher = StateCipher::from_config(&config)?;
+    let mut trade_limiter = TradeLimiter::with_store(
+        StateStore::new(state_backend.clone(), TRADE_STATE_KEY, state_cipher.clone()),
+        LimiterSettings::from_config(&config)?,
+    );
+    let audit = AuditLog::new(&paths.audit_log_file()).with_cipher(state_cipher.clone());
+    let mqtt = MqttPublisher::from_config(&config)?;
+    if let Some(mqtt) = &mqtt {
+        mqtt.forward_events(audit.events().subscribe());
+    }
+    let trade_reviewer = PostTradeReviewer::from_config(&config, &audit)?;
+    let manual_targets = ManualTargets::from_config(&config)?;
+    let funds = FundsLedger::new(&quote_asset);
+    let mut maintenance = MaintenanceMonitor::from_config(&config)?;
+    let mut rollover = DayRollover::from_config(&config)?;
+    let price_watchlist = PriceWatchlist::from_config(&config)?;
+    let analysis = AnalysisFeed::new();
+    let metrics = start_metrics(&config, &paths, &price_watchlist, &analysis).await?;
+    let cycle_timer = CycleTimer::from_config(&config, heartbeat, metrics.clone());
+    let control = start_control(&config, &audit).await?;
+    let mut alert_engine = AlertEngine::from_config(&config, &audit, price_watchlist.clone())?;
+    let ai_sizing = AiSizing::from_config(&config)?;
+    if let Some(window) = maintenance.next_planned() {
+        info!("🛠️ Next planned maintenance: {} - {}", window.start, window.end);
+    }
+    let confirmer = TradeConfirmer::from_config(&config)?;
+    let order_monitor = OrderMonitor::new(config.stale_order_minutes);
+    let mut dust_sweeper = DustSweeper::new(config.dust_sweep_enabled);
+    let mut capital = CapitalTracker::new(config.capital_flow_threshold);
+    // Margin balances are polled every cycle, so only spot accounts can drift
+    let mut drift_monitor = DriftMonitor::from_config(&config)?.filter(|_| !config.margin_account);
+    let timeouts = SourceTimeouts::from_config(&config);
+
+    {
+        let status = reporter.status_mut();
+        status.stop_loss_percent = config.stop_loss_percent;
+        status.take_profit_percent = config.take_profit_percent;
+        status.max_trades_per_day = config.max_trades_per_day;
+    }
+
+    reporter.update_balances(balance_map);
+    if config.margin_account {
+        let account = exchange.get_margin_account().await?;
+        info!("🏦 Margin account: {} assets borrowed, margin level {}", account.liabilities().len(),
+            account.margin_level.map_or("n/a".to_string(), |l| l.round_dp(2).to_string()));
+        reporter.update_margin(account.liabilities(), account.margin_level);
+    }
+    update_conversion_rates(exchange.as_ref(), &mut reporter).await;
+
+    // Fills and balance changes arrive over the user-data stream; balances are
+    // polled only while the stream is down. The margin account is always polled,
+    // since liabilities aren't part of the spot stream.
+    let bus = EventBus::new();
+    let mut events = bus.subscribe();
+    let mut stream_connected = false;
+    if config.user_stream_enabled && !config.margin_account {
+        user_stream::spawn(exchange.clone(), config.ws_url.clone(), bus.clone());
+    }
+    // Stops are checked on every trade between cycles; a hit starts the next cycle early
+    let stop_watcher = config.stop_watch_enabled.then(|| StopWatcher::spawn(config.ws_url.clone()));
+
+    // Position, targets and equity history survive restarts (and `snapshot`/`restore`)
+    let runtime_store = RuntimeStore::new(StateStore::new(state_backend.clone(), RUNTIME_STATE_KEY, state_cipher.clone()));
+    let mut runtime = runtime_store.load(&config.symbol);
+
+    // Sleeves are funded from the free quote balance the first time an allocation is seen
+    let strategy_store = StateStore::new(state_backend.clone(), STRATEGY_BOOK_KEY, state_cipher.clone());
+    let free_quote = spendable_quote(&config, reporter.status(), funds.quote_asset());
+    let mut strategy_book = StrategyBook::load(&config, &strategy_store, funds.available(free_quote))?;
+    let mut latest_closes: Vec<Decimal> = Vec::new();
+    let mut exposure = ExposureLimits::from_config(&config)?;
+    let var_limit = VarLimit::from_config(&config)?;
+    let mut options_feed = OptionsFeed::from_config(&config, &symbol_info.base)?;
+    let mut whale_monitor = WhaleMonitor::from_config(&config)?;
+    let mut breadth = BreadthFeed::new(CoinGeckoClient::new());
+    let mut volume_monitor = VolumeMonitor::from_config(&config)?;
+    let mut divergence_monitor = DivergenceMonitor::from_config(&config)?;
+    let mut level_book = LevelBook::from_config(&config)?;
+    let mut volume_profiler = VolumeProfiler::from_config(&config)?;
+    let mut liquidity = LiquidityMap::from_config(&config)?;
+    let entry_gate = EntryGate::from_config(&config)?;
+    let loss_streak = LossStreak::from_config(&config)?;
+    let mut cycle_clock = CycleClock::from_config(&config);
+    let mut kill_switch = DrawdownKillSwitch::from_config(&config,
+        StateStore::new(state_backend.clone(), HIGH_WATER_MARK_KEY, state_cipher.clone()))?;
+    let mut candle_feed = CandleFeed::from_config(&config)?;
+    let mut scanner = Scanner::from_config(&config, &quote_asset)?;
+    let mut btc_trend = BtcTrendFilter::from_config(&config, &quote_asset)?;
+    let session_filter = SessionFilter::from_config(&config)?;
+    let locale = NumberLocale::parse(&config.report_locale)?;
+    reporter.set_language(Language::parse(&config.report_language));
+    reporter.set_retention(Retention::from_config(&config)?.spill_to(&config, state_backend.clone(), state_cipher.clone()));
+    let mut fx_feed = FxFeed::from_config(&config, &quote_asset)?;
+
+    let mut last_ai_update = std::time::Instant::now();
+    let mut current_targets: Option<AiTradingTargets> = runtime.targets.clone();
+    let mut positions = Position::slots(&config, &runtime.positions)?;
+    reporter.update_positions(&positions);
+    reporter.status_mut().consecutive_losses = runtime.consecutive_losses;
+    if let Some(ref targets) = current_targets {
+        reporter.update_ai_targets(targets);
+    }
+
+    info!("━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━");
+    info!("🔄 Starting LIVE monitoring loop...");
+    if config.live_trading_enabled {
+        warn!("⚠️ This will execute REAL trades!");
+        if let Some(threshold) = config.confirm_min_notional {
+            info!("   Orders >= ${} require {} confirmation", threshold, config.confirm_channel);
+        }
+    } else {
+        info!("   Alert-only mode (set LIVE_TRADING_ENABLED=true to execute)");
+    }
+    info!("   Press Ctrl+C to stop");
+    info!("━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━");
+
+    loop {
+        // Price, order book and (when targets are due) CoinGecko data are fetched concurrently
+        cycle_timer.begin_cycle();
+        let stage_started = std::time::Instant::now();
+        let recalc_due = current_targets.is_none() || last_ai_update.elapsed().as_secs() >= AI_RECALC_INTERVAL_SECS;
+        let snapshot = market_data::fetch_snapshot(
+            exchange.get_price(&config.symbol),
+            recalc_due.then(|| coingecko.fetch_market_data(&config.symbol)),
+            Some(exchange.get_book_ticker(&config.symbol)),
+            timeouts,
+        ).await;
+        cycle_timer.record(Stage::Fetch, stage_started.elapsed());
+        let Some(current_price) = snapshot.price else {
+            error!("❌ Failed to get price");
+            cycle_timer.end_cycle();
+            tokio::time::sleep(Duration::from_secs(PRICE_CHECK_INTERVAL_SECS)).await;
+            continue;
+        };
+        recover_missed_cycles(cycle_clock.as_mut(), exchange.as_ref(), &config.symbol, positions.iter_mut(),
+                              &audit, &alert_engine, &mut reporter).await;
+        if let Some(ref book) = snapshot.book {
+            reporter.update_order_book(book);
+        }
+
+        info!("📊 {} @ ${:.2}", config.symbol, current_price);
+
+        // Update reporter
+        if let Some(event) = reporter.update_price(current_price) {
+            info!("🔔 ALERT: {}", event);
+        }
+
+        apply_stream_events(&mut events, &mut reporter, &mut stream_connected);
+        if config.margin_account {
+            match exchange.get_margin_account().await {
+                Ok(account) => {
+                    reporter.update_balances(account.balances().iter().map(|(k, v)| (k.clone(), v.free)).collect());
+                    reporter.update_margin(account.liabilities(), account.margin_level);
+                }
+                Err(e) => warn!("⚠️ Failed to refresh margin account: {}", e),
+            }
+        } else if !stream_connected {
+            match exchange.get_balance().await {
+                Ok(balance) => reporter.update_balances(
+                    balance.iter().map(|(k, v)| (k.clone(), v.free)).collect()),
+                Err(e) => warn!("⚠️ Failed to refresh balances: {}", e),
+            }
+        }
+        if let Some(monitor) = drift_monitor.as_mut()
+            && monitor.is_due() {
+            check_drift(monitor, exchange.as_ref(), &mut reporter, &mut positions, &funds, &symbol_info.base, &audit).await;
+        }
+
+        // Deposits and withdrawals are capital flows, not performance
+        for flow in capital.observe(reporter.status()) {
+            reporter.set_last_event(format!("🏦 {} {} {}",
+                if flow.amount > Decimal::ZERO { "Deposit" } else { "Withdrawal" }, flow.amount.abs(), flow.asset));
+            audit.record(AuditEvent::CapitalFlow { asset: flow.asset, amount: flow.amount, value: flow.value });
+        }
+        reporter.update_capital(capital.net_flows(), capital.time_weighted_return());
+
+        match order_monitor.refresh(&exchange, &config.symbol).await {
+            Ok(orders) => reporter.update_open_orders(orders),
+            Err(e) => warn!("⚠️ Failed to fetch open orders: {}", e),
+        }
+        watch_volume(&mut volume_monitor, exchange.as_ref(), &config.symbol, current_price, &mut reporter, &audit).await;
+        watch_divergences(&mut divergence_monitor, exchange.as_ref(), &config.symbol, &mut reporter).await;
+        watch_volume_profile(&mut volume_profiler, exchange.as_ref(), &config.symbol, &mut reporter).await;
+        watch_liquidity(&mut liquidity, exchange.as_ref(), &config.symbol, &mut reporter).await;
+
+        // Recalculate targets periodically
+        if recalc_due {
+            if let Some(market_data) = snapshot.market {
+                let closes: Vec<Decimal> = market_data.hourly_data_24h.iter().map(|d| d.close).collect();
+                latest_closes = closes.clone();
+                exposure.update_closes(&symbol_info.base, &closes);
+                let stage_started = std::time::Instant::now();
+                let indicator_closes = indicator_closes(candle_feed.as_mut(), exchange.as_ref(), &config.symbol, &closes).await;
+                cycle_timer.record(Stage::Indicators, stage_started.elapsed());
+                exposure.refresh(exchange.as_ref(), reporter.status()).await;
+                let options = match options_feed.as_mut() {
+                    Some(feed) => feed.refresh().await.cloned(),
+                    None => None,
+                };
+                reporter.update_options(options.clone());
+                let global = breadth.refresh().await.cloned();
+                reporter.update_global_market(global.clone());
+                watch_fx(&mut fx_feed, locale, &mut reporter).await;
+
+                let market_context = MarketContext {
+                    symbol: config.symbol.clone(),
+                    current_price,
+                    high_24h: market_data.high_24h,
+                    low_24h: market_data.low_24h,
+                    price_change_24h_percent: market_data.price_change_24h_percent,
+                    sma_short: strategy::SmaCrossover::calculate_sma(&indicator_closes, 10),
+                    sma_long: strategy::SmaCrossover::calculate_sma(&indicator_closes, 20),
+                    rsi: strategy::RsiStrategy::calculate_rsi(&indicator_closes, 14),
+                    volume_24h: Some(market_data.total_volume),
+                    position_entry_price: reporter.status().entry_price,
+                    account_balance: reporter.status().total_portfolio_value,
+                    hourly_data_summary: Some(coingecko.format_for_ai(&market_data)),
+                    high_12h: market_data.hourly_data_12h.iter().map(|h| h.high).max(),
+                    low_12h: market_data.hourly_data_12h.iter().map(|h| h.low).min(),
+                    high_48h: market_data.hourly_data_48h.iter().map(|h| h.high).max(),
+                    low_48h: market_data.hourly_data_48h.iter().map(|h| h.low).min(),
+                    options_summary: options.as_ref().map(|o| o.format_for_ai(current_price)),
+                    volume_summary: volume_monitor.as_ref().and_then(VolumeMonitor::format_for_ai),
+                    divergence_summary: divergence_monitor.as_ref().and_then(DivergenceMonitor::format_for_ai),
+                    volume_profile_summary: volume_profiler.as_ref().and_then(VolumeProfiler::profile).map(|p| p.format_for_ai()),
+                    btc_dominance: global.as_ref().map(|g| g.btc_dominance),
+                    market_cap_change_24h_percent: global.as_ref().map(|g| g.market_cap_change_24h_percent),
+                };
+                analysis.publish_context(&market_context);
+
+                reporter.update_signals(crossover_signal(market_context.sma_short, market_context.sma_long),
+                                        market_context.sma_short, market_context.sma_long, market_context.rsi);
+                let mut fallback = FallbackTargetCalculator::calculate_targets(&market_context);
+                let walls = liquidity.as_ref().map_or(&[][..], LiquidityMap::walls);
+                apply_levels(&mut level_book, exchange.as_ref(), &config.symbol, current_price, walls, &mut fallback, &mut reporter).await;
+                if let Some(profile) = volume_profiler.as_ref().and_then(VolumeProfiler::profile) {
+                    profile.apply(&mut fallback, current_price);
+                }
+                audit_targets(&audit, &config.symbol, "fallback", current_price, &fallback);
+                let (targets, sources) = manual_targets.apply(fallback, TargetSource::Fallback);
+                reporter.update_ai_targets(&targets);
+                reporter.update_target_sources(sources);
+                current_targets = Some(targets);
+                reporter.update_market_data(market_data.high_24h, market_data.low_24h,
+                                            market_data.price_change_24h_percent);
+            }
+
+            last_ai_update = std::time::Instant::now();
+        }
+
+        // Update trade limits
+        let trade_status = trade_limiter.get_status();
+        reporter.update_trade_limits(
+            trade_status.trades_executed,
+            trade_status.can_trade,
+            trade_status.next_trading_day.clone(),
+        );
+
+        update_metrics(&metrics, &reporter);
+        if let Some(mqtt) = &mqtt {
+            mqtt.publish_status(reporter.status());
+        }
+        let budget = exchange.weight_budget();
+        metrics.set_gauge("exchange_weight_used", "Request weight used in the current minute", budget.used as f64);
+        metrics.set_gauge("exchange_weight_remaining", "Request weight left in the current minute", budget.remaining() as f64);
+        metrics.set_gauge("exchange_weight_limit", "Request weight allowed per minute", budget.limit as f64);
+        metrics.set_gauge("exchange_weight_throttle_waits", "Requests delayed to stay under the weight limit",
+                          budget.throttle_count as f64);
+
+        // Pause entries while the exchange is under maintenance, the symbol isn't trading,
+        // a whale just moved coins onto an exchange, BTC's trend is bearish for altcoins or
+        // outside TRADING_SESSIONS
+        let whale_pause = watch_whales(&mut whale_monitor, &mut reporter).await;
+        let btc_pause = watch_btc_trend(&mut btc_trend, exchange.as_ref(), &symbol_info.base).await;
+        let streak_pause = watch_loss_streak(loss_streak.as_ref(), &mut reporter);
+        let target_pause = trade_limiter.profit_target_reached();
+        let drawdown_halt = watch_drawdown(kill_switch.as_mut(), &alert_engine, &mut reporter).await;
+        let entries_paused = maintenance.update(Some(exchange.as_ref()), &config.symbol).await
+            .map(|reason| (SkipReason::Maintenance, reason.to_string()))
+            .or(whale_pause.map(|reason| (SkipReason::Cooldown, reason)))
+            .or(btc_pause.map(|reason| (SkipReason::BtcTrend, reason)))
+            .or_else(|| session_filter.as_ref().and_then(|f| f.pause_reason(chrono::Utc::now()))
+                .map(|reason| (SkipReason::Session, reason)))
+            .or(streak_pause.map(|reason| (SkipReason::LossStreak, reason)))
+            .or(target_pause.map(|reason| (SkipReason::TargetAchieved, reason)))
+            .or(drawdown_halt.map(|reason| (SkipReason::Drawdown, reason)))
+            .or_else(|| control.pause_reason().map(|reason| (SkipReason::Paused, reason)));
+        reporter.update_entries_paused(entries_paused.as_ref().map(|(_, reason)| reason.clone()));
+        analysis.publish(reporter.status());
+        control.update(reporter.status());
+        alert_engine.check(&mut reporter).await;
+
+        // Without LIVE_TRADING_ENABLED we only ALERT - don't auto-execute. A manual order
+        // from the control interface stands in for its slot's targets.
+        let mut manual_order = control.take_order();
+        if let Some(ref targets) = current_targets {
+            if current_price <= targets.stop_loss_price {
+                warn!("🚨 STOP-LOSS ALERT: Price ${:.2} <= SL ${:.2}", 
+                    current_price, targets.stop_loss_price);
+            }
+            if current_price >= targets.take_profit_price {
+                info!("🎯 TAKE-PROFIT ALERT: Price ${:.2} >= TP ${:.2}", 
+                    current_price, targets.take_profit_price);
+            }
+
+            if config.live_trading_enabled {
+                let mut decisions = Vec::new();
+                for position in positions.iter_mut() {
+                    let can_trade = entries_paused.is_none()
+                        && matches!(trade_limiter.can_trade(), TradePermission::Allowed { .. });
+                    let can_exit = trade_limiter.can_exit().is_allowed();
+                    // The AI sleeve backs the main position only
+                    let mut book = strategy_book.as_mut().filter(|_| position.is_main());
+                    let key = position.ledger_key();
+                    let manual = manual_order.take_if(|order| order.applies_to(position));
+
+                    position.set_targets(targets.stop_loss_price, targets.take_profit_price, targets.sell_target_price);
+
+                    if position.is_open() {
+                        position.track_excursion(current_price);
+                        let exit_reason = if manual.is_some() { Some(ExitTrigger::Manual) } else { position.exit_trigger(current_price) };
+
+                        // Never sell more than is actually held (buy fees may be taken from the base asset)
+                        let base_free = reporter.status().balances.get(&symbol_info.base).copied();
+                        let sell_qty = symbol_info.round_qty(base_free.map_or(position.qty, |b| b.min(position.qty)));
+
+                        let decision = if exit_reason.is_some() && symbol_info.is_dust(sell_qty, current_price) {
+                            info!("🧹 Remaining {} {} of the {} position is below the minimum order size - treating it as closed",
+                                sell_qty, symbol_info.base, position.id);
+                            funds.release(&key);
+                            if let Some(book) = book {
+                                book.record_exit(SleeveStrategy::AiTargets, current_price, dec!(0));
+                            }
+                            position.abandon()?;
+                            "closed - remainder below the minimum order size".to_string()
+                        } else if let Some(trigger) = exit_reason
+                            && (can_exit || trigger == ExitTrigger::StopLoss) {
+                            let level = position.exit_level(trigger);
+                            let _in_flight = cycle_timer.order_in_flight();
+                            position.begin_exit()?;
+                            let confirmer = confirmer.as_ref().filter(|_| !trigger.is_protective());
+                            let result = execute_live_order(exchange.as_ref(), confirmer, &config.symbol, Some(&position.id),
+                                                            models::OrderSide::Sell, sell_qty, current_price, trigger.label()).await;
+                            if let LiveOrder::Placed(order) = result {
+                                let fill = order.execution(&symbol_info.base, &symbol_info.quote,
+                                    current_price, sell_qty, config.trading_fee_percent);
+                                let before = position.clone();
+                                let pnl = position.exit_filled(fill)?;
+                                review_closed_trade(trade_reviewer.as_ref(), &before, fill.price, pnl, trigger);
+                                if trigger == ExitTrigger::StopLoss
+                                    && let Some(gap) = before.stop_gap_alert(fill) {
+                                    error!("{}", gap);
+                                    alert_engine.raise(&mut reporter, "stop gap".to_string(), gap).await;
+                                }
+                                funds.release(&key);
+                                if !can_exit {
+                                    audit_limit_override(&audit, &trade_status, &config.symbol, trigger.label(),
+                                                         fill.price, fill.qty);
+                                }
+                                let context = reporter.status().trade_context(trigger.label(), level);
+                                record_fill(&mut trade_limiter, &audit, Some(&position.id), &config.symbol,
+                                            models::OrderSide::Sell, &fill, context)?;
+                                trade_limiter.update_pnl(pnl);
+                                reporter.record_trade(models::OrderSide::Sell, fill.price, fill.qty, Some(pnl), fill.fee);
+                                capital.expect_trade(&symbol_info.base, &symbol_info.quote, -fill.qty, fill.qty * fill.price);
+                                if let Some(book) = book {
+                                    book.record_exit(SleeveStrategy::AiTargets, fill.price, fill.fee);
+                                }
+                                exited(trigger.label(), fill.price)
+                            } else {
+                                position.exit_failed()?;
+                                result.not_placed(&format!("{} exit order failed", trigger.label()))
+                            }
+                        } else if let Some(trigger) = exit_reason {
+                            exit_held_back(trigger.label())
+                        } else {
+                            HOLDING.to_string()
+                        };
+                        if let Some(order) = manual {
+                            order.answer(position, &decision);
+                        }
+                        decisions.push(format!("{}: {}", position.id, decision));
+                    } else {
+                        let (score, low_score) = entry_quality(entry_gate.as_ref(), reporter.status());
+                        let (entry_reason, buy_target) = match manual {
+                            Some(_) => ("Manual", Some(current_price)),
+                            None => ("Buy target", targets.buy_target_price),
+                        };
+                        let decision = match buy_target {
+                            None => "no buy target".to_string(),
+                            Some(buy_target) if current_price > buy_target => waiting_for(buy_target),
+                            Some(_) if !can_trade => entry_blocked(&metrics, &mut trade_limiter, entries_paused.as_ref()),
+                            Some(_) if manual.is_none() && !volume_confirms(&volume_monitor, &position.id) => skipped(&metrics, SkipReason::Volume, NO_VOLUME),
+                            Some(_) if manual.is_none() && low_score.is_some() => skipped(&metrics, SkipReason::Quality, low_score.as_deref().unwrap_or_default()),
+                            Some(buy_target) => {
+                                let _in_flight = cycle_timer.order_in_flight();
+                                let balance = spendable_quote(&config, reporter.status(), funds.quote_asset());
+                                let budget = entry_budget(position, &config, funds.available(balance), book.as_deref(),
+                                                          ai_sizing.as_ref(), targets, reporter.status());
+                                let qty = symbol_info.round_qty(budget / current_price);
+                                let cost = qty * current_price;
+
+                                // The reservation holds the budget while the order fills; one waiting for approval reserves it again each cycle
+                                if !symbol_info.meets_minimums(qty, current_price) {
+                                    warn!("⚠️ {} entry size {} is below the {} minimum order size", position.id, qty, symbol_info.symbol);
+                                    skipped(&metrics, SkipReason::MinSize, "below the minimum order size")
+                                } else if let Some(reason) = risk_check(&exposure, &var_limit, cost, reporter.status()) {
+                                    skipped(&metrics, SkipReason::Risk, &reason)
+                                } else if let Err(e) = funds.reserve(&key, cost, balance) {
+                                    warn!("⚠️ Skipping {} entry: {}", position.id, e);
+                                    skipped(&metrics, SkipReason::Funds, &e.to_string())
+                                } else if let Err(e) = position.begin_entry(qty) {
+                                    funds.cancel(&key);
+                                    warn!("⚠️ Skipping {} entry: {}", position.id, e);
+                                    skipped(&metrics, SkipReason::State, &e.to_string())
+                                } else {
+                                    let result = execute_live_order(exchange.as_ref(), confirmer.as_ref(), &config.symbol, Some(&position.id),
+                                                                    models::OrderSide::Buy, qty, current_price, entry_reason).await;
+                                    if let LiveOrder::Placed(order) = result {
+                                        let fill = order.execution(&symbol_info.base, &symbol_info.quote,
+                                            current_price, qty, config.trading_fee_percent);
+                                        position.entry_filled(fill)?;
+                                        funds.commit(&key, fill.qty * fill.price);
+                                        let context = reporter.status().trade_context(entry_reason, Some(buy_target));
+                                        record_fill(&mut trade_limiter, &audit, Some(&position.id), &config.symbol,
+                                                    models::OrderSide::Buy, &fill, context)?;
+                                        reporter.record_trade(models::OrderSide::Buy, fill.price, fill.qty, None, fill.fee);
+                                        capital.expect_trade(&symbol_info.base, &symbol_info.quote, fill.qty, -fill.qty * fill.price);
+                                        if let Some(book) = book.as_mut() {
+                                            book.record_entry(SleeveStrategy::AiTargets, fill.price, fill.qty, fill.fee);
+                                        }
+                                        scored(entered(fill.price), score)
+                                    } else {
+                                        funds.cancel(&key);
+                                        position.entry_failed()?;
+                                        result.not_placed("entry order failed")
+                                    }
+                                }
+                            }
+                        };
+                        if let Some(order) = manual {
+                            order.answer(position, &decision);
+                        }
+                        decisions.push(format!("{}: {}", position.id, decision));
+                    }
+                }
+                reporter.update_positions(&positions);
+                reporter.record_decision(decisions.join(" | "));
+            } else {
+                reporter.record_decision("alert only - live trading disabled".to_string());
+            }
+        } else {
+            reporter.record_decision(skipped(&metrics, SkipReason::WarmUp, NO_TARGETS));
+        }
+        let blocked = if current_targets.is_none() {
+            Some(NO_TARGETS)
+        } else {
+            (!config.live_trading_enabled).then_some("live trading disabled")
+        };
+        decline_manual_order(manual_order, &positions, blocked);
+
+        // Signal-driven strategy sleeves trade their own share of the capital
+        if let Some(book) = strategy_book.as_mut() {
+            if config.live_trading_enabled {
+                for (sleeve, side, qty) in book.pending_orders(&latest_closes, current_price) {
+                    let qty = symbol_info.round_qty(qty);
+                    let allowed = match side {
+                        models::OrderSide::Buy => entries_paused.is_none()
+                            && matches!(trade_limiter.can_trade(), TradePermission::Allowed { .. })
+                            && risk_allows(&exposure, &var_limit, qty * current_price, reporter.status()),
+                        models::OrderSide::Sell => trade_limiter.can_exit().is_allowed(),
+                    };
+                    if !allowed || !symbol_info.meets_minimums(qty, current_price) {
+                        continue;
+                    }
+                    let reason = format!("{} sleeve", sleeve.label());
+                    let _in_flight = cycle_timer.order_in_flight();
+                    if let LiveOrder::Placed(order) = execute_live_order(exchange.as_ref(), confirmer.as_ref(), &config.symbol, None, side, qty, current_price, &reason).await {
+                        let fill = order.execution(&symbol_info.base, &symbol_info.quote,
+                            current_price, qty, config.trading_fee_percent);
+                        let context = reporter.status().trade_context(&reason, None);
+                        record_fill(&mut trade_limiter, &audit, None, &config.symbol, side, &fill, context)?;
+                        match side {
+                            models::OrderSide::Buy => {
+                                book.record_entry(sleeve, fill.price, fill.qty, fill.fee);
+                                capital.expect_trade(&symbol_info.base, &symbol_info.quote, fill.qty, -fill.qty * fill.price);
+                            }
+                            models::OrderSide::Sell => {
+                                trade_limiter.update_pnl(book.record_exit(sleeve, fill.price, fill.fee));
+                                capital.expect_trade(&symbol_info.base, &symbol_info.quote, -fill.qty, fill.qty * fill.price);
+                            }
+                        }
+                    }
+                }
+            }
+            book.mark(current_price, chrono::Utc::now());
+            book.save(&strategy_store);
+            reporter.update_strategies(book.summaries(current_price));
+        }
+
+        // Leftovers below the minimum order size can't be sold; optionally convert them
+        if positions.iter().all(Position::is_flat)
+            && let Some(&dust) = reporter.status().balances.get(&symbol_info.base)
+            && symbol_info.is_dust(dust, current_price) {
+            dust_sweeper.sweep(&exchange, &symbol_info.base, dust).await;
+        }
+
+        // Screen the watchlist; a promoted candidate replaces the symbol once everything is flat
+        if let Some(scanner) = scanner.as_mut() {
+            if scanner.is_due() {
+                if let Err(e) = scanner.scan(exchange.as_ref()).await {
+                    warn!("⚠️ Scanner failed: {}", e);
+                }
+                reporter.update_scanner(scanner.candidates.clone());
+            }
+            if let Some(next) = scanner.promotion(&config.symbol)
+                && positions.iter().all(Position::is_flat)
+                && strategy_book.is_none() {
+                info!("🔀 Scanner promoted {} - switching from {}", next, config.symbol);
+                config.symbol = next.to_string();
+                symbol_info = exchange.get_symbol_info(&config.symbol).await
+                    .unwrap_or_else(|_| symbols::lookup(&config.symbol));
+                options_feed = OptionsFeed::from_config(&config, &symbol_info.base)?;
+                reporter.switch_symbol(&config.symbol);
+                positions = Position::slots(&config, &[])?;
+                current_targets = None;
+                runtime.symbol = config.symbol.clone();
+            }
+        }
+
+        runtime.positions = positions.iter().filter(|p| !p.is_flat()).cloned().collect();
+        runtime.targets = current_targets.clone();
+        runtime.consecutive_losses = reporter.status().consecutive_losses;
+        runtime.record_equity(reporter.status().total_portfolio_value, chrono::Utc::now());
+        runtime_store.save(&mut runtime);
+        reporter.update_value_at_risk(risk::equity_var(&runtime.equity_history, var_limit.confidence()),
+            var_limit.max_percent());
+
+        let stage_started = std::time::Instant::now();
+        rollover.run_if_due(&mut reporter, &paths, &audit).await;
+        reporter.write_if_dirty()?;
+        cycle_timer.record(Stage::Report, stage_started.elapsed());
+        cycle_timer.end_cycle();
+
+        match &stop_watcher {
+            Some(watcher) => {
+                watcher.update(&config.symbol, &positions);
+                if watcher.wait(Duration::from_secs(PRICE_CHECK_INTERVAL_SECS)).await {
+                    info!("⚡ Stop hit between cycles - checking exits now");
+                }
+            }
+            None => tokio::time::sleep(Duration::from_secs(PRICE_CHECK_INTERVAL_SECS)).await,
+        }
+    }
+}
+
+/// USDⓈ-M perpetuals: long entries on an SMA crossover of futures candles, exits at the configured
+/// stop-loss/take-profit or a bearish cross, funding fees booked into P&L. Kept apart from the spot
+/// loops; the position on the exchange is the source of truth every cycle.
+pub async fn run_futures_loop(config: Config) -> Result<()> {
+    let client = FuturesClient::new(&config)?;
+    let settings = FuturesSettings::from_config(&config)?;
+    client.configure(&config.symbol, &settings).await?;
+    let maintenance_rate = match client.get_maintenance_margin_rate(&config.symbol).await {
+        Ok(rate) => rate,
+        Err(e) => {
+            warn!("⚠️ Could not load leverage brackets: {} - assuming {}% maintenance margin", e,
+                futures::DEFAULT_MAINTENANCE_MARGIN_RATE * dec!(100));
+            futures::DEFAULT_MAINTENANCE_MARGIN_RATE
+        }
+    };
+
+    let symbol_info = symbols::lookup(&config.symbol);
+    let paths = DataPaths::from_config(&config)?;
+    let mut reporter = PortfolioReporter::new(&config.symbol, false, &paths.report_file());
+    let audit = AuditLog::new(&paths.audit_log_file()).with_cipher(StateCipher::from_config(&config)?);
+    let mqtt = MqttPublisher::from_config(&config)?;
+    if let Some(mqtt) = &mqtt {
+        mqtt.forward_events(audit.events().subscribe());
+    }
+    let price_watchlist = PriceWatchlist::from_config(&config)?;
+    let analysis = AnalysisFeed::new();
+    let metrics = start_metrics(&config, &paths, &price_watchlist, &analysis).await?;
+    let control = start_control(&config, &audit).await?;
+    let mut alert_engine = AlertEngine::from_config(&config, &audit, price_watchlist.clone())?;
+    let state_backend = state_store::backend_from_config(&config, &paths).await?;
+    let instance_lock = state_backend.lock(&state_store::account_lock_name(&config))?;
+    info!("🔒 Instance lock acquired ({})", instance_lock.holder());
+    let mut trade_limiter = TradeLimiter::with_store(
+        StateStore::new(state_backend.clone(), TRADE_STATE_KEY, StateCipher::from_config(&config)?),
+        LimiterSettings::from_config(&config)?,
+    );
+    let strategy = strategy::SmaCrossover::new(10, 20)
+        .with_interval(CandleFeed::from_config(&config)?.map(|feed| feed.interval()).unwrap_or_default());
+    let mut rollover = DayRollover::from_config(&config)?;
+    {
+        let status = reporter.status_mut();
+        status.stop_loss_percent = config.stop_loss_percent;
+        status.take_profit_percent = config.take_profit_percent;
+        status.max_trades_per_day = config.max_trades_per_day;
+    }
+
+    // Funding before startup was already settled in earlier runs
+    let mut funding_since = chrono::Utc::now().timestamp_millis();
+    let mut funding_total = dec!(0);
+    let max_holding = position::max_holding(config.max_holding_hours, "MAX_HOLDING_HOURS")?;
+    let mut long = Position { max_holding, ..Position::new(&config.symbol) };
+    let mut short = Position { max_holding, ..Position::new_short(&config.symbol) };
+    let mut btc_trend = BtcTrendFilter::from_config(&config, &symbol_info.quote)?;
+    let session_filter = SessionFilter::from_config(&config)?;
+    let loss_streak = LossStreak::from_config(&config)?;
+    let mut cycle_clock = CycleClock::from_config(&config);
+    let mut kill_switch = DrawdownKillSwitch::from_config(&config,
+        StateStore::new(state_backend.clone(), HIGH_WATER_MARK_KEY, StateCipher::from_config(&config)?))?;
+    let locale = NumberLocale::parse(&config.report_locale)?;
+    reporter.set_language(Language::parse(&config.report_language));
+    reporter.set_retention(Retention::from_config(&config)?.spill_to(&config, state_backend.clone(), StateCipher::from_config(&config)?));
+    let mut fx_feed = FxFeed::from_config(&config, &symbol_info.quote)?;
+
+    info!("🔄 Starting FUTURES loop ({}x {}, maintenance margin {}%)", settings.leverage, settings.margin_type,
+        maintenance_rate * dec!(100));
+    if !config.live_trading_enabled {
+        info!("   Alert-only mode (set LIVE_TRADING_ENABLED=true to execute)");
+    }
+
+    loop {
+        let current_price = match client.get_price(&config.symbol).await {
+            Ok(price) => price,
+            Err(e) => {
+                error!("❌ Failed to get futures price: {}", e);
+                tokio::time::sleep(Duration::from_secs(PRICE_CHECK_INTERVAL_SECS)).await;
+                continue;
+            }
+        };
+        info!("📊 {} perp @ ${:.2}", config.symbol, current_price);
+        if let Some(event) = reporter.update_price(current_price) {
+            info!("🔔 ALERT: {}", event);
+        }
+
+        match client.get_funding_fees(&config.symbol, funding_since).await {
+            Ok(fees) => for fee in fees {
+                funding_since = funding_since.max(fee.time + 1);
+                funding_total += fee.income;
+                reporter.record_funding(fee.income);
+                trade_limiter.update_pnl(fee.income);
+                info!("💸 Funding {} {}", if fee.income >= dec!(0) { "received" } else { "paid" }, fee.income.abs());
+            },
+            Err(e) => warn!("⚠️ Failed to fetch funding fees: {}", e),
+        }
+
+        let (wallet, legs) = match tokio::try_join!(client.get_wallet(&symbol_info.quote), client.get_positions(&config.symbol)) {
+            Ok(result) => result,
+            Err(e) => {
+                warn!("⚠️ Failed to refresh futures account: {}", e);
+                tokio::time::sleep(Duration::from_secs(PRICE_CHECK_INTERVAL_SECS)).await;
+                continue;
+            }
+        };
+        reporter.update_balances(std::collections::HashMap::from([(symbol_info.quote.clone(), wallet.margin_balance())]));
+        watch_fx(&mut fx_feed, locale, &mut reporter).await;
+
+        // The exchange's legs are authoritative; shorts are only managed in hedge mode
+        for leg in [&mut long, &mut short] {
+            let held = legs.iter()
+                .find(|p| if leg.short { p.amount < dec!(0) } else { p.amount > dec!(0) })
+                .filter(|_| settings.hedge_mode || !leg.short);
+            if leg.sync(held.map_or(dec!(0), |p| p.amount.abs()), held.map(|p| p.entry_price)) {
+                // Opened or resized outside this loop, or carried over from a previous run
+                leg.set_percent_targets(config.stop_loss_percent, config.take_profit_percent);
+            }
+        }
+        recover_missed_cycles(cycle_clock.as_mut(), &client, &config.symbol, [&mut long, &mut short],
+                              &audit, &alert_engine, &mut reporter).await;
+        let margin_for = |p: &futures::PositionRisk| match settings.margin_type {
+            MarginType::Isolated => p.isolated_wallet,
+            MarginType::Cross => wallet.balance,
+        };
+        reporter.update_futures(Some(FuturesPosition {
+            leverage: settings.leverage,
+            margin_type: settings.margin_type,
+            hedge_mode: settings.hedge_mode,
+            mark_price: legs.first().map_or(current_price, |p| p.mark_price),
+            legs: legs.iter().map(|p| FuturesLeg {
+                side: p.position_side,
+                amount: p.amount,
+                entry_price: p.entry_price,
+                unrealized_pnl: p.unrealized_pnl,
+                liquidation_price: futures::liquidation_price(p.amount, p.entry_price, margin_for(p), maintenance_rate),
+            }).collect(),
+            funding_total,
+        }));
+
+        let signal = match client.get_klines(&config.symbol, strategy.interval.as_str(), 50).await {
+            Ok(klines) => {
+                let closes: Vec<Decimal> = klines.iter().map(|k| k.close).collect();
+                let signal = strategy.generate_signal(&klines);
+                reporter.update_signals(signal, strategy::SmaCrossover::calculate_sma(&closes, 10),
+                    strategy::SmaCrossover::calculate_sma(&closes, 20), strategy::RsiStrategy::calculate_rsi(&closes, 14));
+                signal
+            }
+            Err(e) => {
+                warn!("⚠️ Failed to fetch futures candles: {}", e);
+                models::Signal::Hold
+            }
+        };
+
+        let trade_status = trade_limiter.get_status();
+        reporter.update_trade_limits(trade_status.trades_executed, trade_status.can_trade, trade_status.next_trading_day.clone());
+        update_metrics(&metrics, &reporter);
+        if let Some(mqtt) = &mqtt {
+            mqtt.publish_status(reporter.status());
+        }
+        let btc_pause = watch_btc_trend(&mut btc_trend, &client, &symbol_info.base).await;
+        let session_pause = session_filter.as_ref().and_then(|f| f.pause_reason(chrono::Utc::now()));
+        let streak_pause = watch_loss_streak(loss_streak.as_ref(), &mut reporter);
+        let target_pause = trade_limiter.profit_target_reached();
+        let drawdown_halt = watch_drawdown(kill_switch.as_mut(), &alert_engine, &mut reporter).await;
+        let operator_pause = control.pause_reason();
+        // Same precedence as the entry check below; the BTC trend only holds back longs
+        reporter.update_entries_paused(session_pause.clone()
+            .or(streak_pause.clone())
+            .or(target_pause.clone())
+            .or(drawdown_halt.clone())
+            .or(operator_pause.clone())
+            .or_else(|| btc_pause.as_ref().map(|reason| format!("{} - longs only", reason))));
+        analysis.publish(reporter.status());
+        control.update(reporter.status());
+        if let Some(order) = control.take_order() {
+            order.decline("manual orders aren't supported in futures mode");
+        }
+        alert_engine.check(&mut reporter).await;
+
+        // Longs open on a bullish cross, shorts (hedge mode) on a bearish one. In one-way mode a
+        // bearish cross closes the long; in hedge mode the short hedges it instead.
+        let mut decisions = Vec::new();
+        for leg in [&mut long, &mut short] {
+            if leg.short && !settings.hedge_mode {
+                continue;
+            }
+            let (name, open_side, close_side, entry_signal, exit_signal) = if leg.short {
+                ("short", models::OrderSide::Sell, models::OrderSide::Buy, models::Signal::Sell, models::Signal::Buy)
+            } else {
+                ("long", models::OrderSide::Buy, models::OrderSide::Sell, models::Signal::Buy, models::Signal::Sell)
+            };
+            let position_side = match (settings.hedge_mode, leg.short) {
+                (false, _) => PositionSide::Both,
+                (true, false) => PositionSide::Long,
+                (true, true) => PositionSide::Short,
+            };
+            let can_trade = matches!(trade_limiter.can_trade(), TradePermission::Allowed { .. });
+
+            if leg.is_open() {
+                leg.track_excursion(current_price);
+                let trigger = leg.exit_trigger(current_price);
+                let exit_reason = match trigger {
+                    Some(trigger) => Some(trigger.label()),
+                    None if signal == exit_signal && (leg.short || !settings.hedge_mode) => {
+                        Some(if leg.short { "Bullish crossover" } else { "Bearish crossover" })
+                    }
+                    None => None,
+                };
+                let decision = if let Some(reason) = exit_reason
+                    && (trigger == Some(ExitTrigger::StopLoss) || trade_limiter.can_exit().is_allowed()) {
+                    if !config.live_trading_enabled {
+                        warn!("🚨 {} ALERT: close {} {} {} perp @ ${:.2}", reason, name, leg.qty, config.symbol, current_price);
+                        format!("{} - close alert only", reason.to_lowercase())
+                    } else {
+                        let qty = leg.qty;
+                        let level = trigger.and_then(|t| leg.exit_level(t));
+                        leg.begin_exit()?;
+                        match client.place_market_order(&config.symbol, close_side, qty, position_side, true).await {
+                            Ok(order) => {
+                                let fill = order.execution(&symbol_info.base, &symbol_info.quote,
+                                    current_price, qty, config.trading_fee_percent);
+                                let gap = leg.stop_gap_alert(fill).filter(|_| trigger == Some(ExitTrigger::StopLoss));
+                                let pnl = leg.exit_filled(fill)?;
+                                if let Some(gap) = gap {
+                                    error!("{}", gap);
+                                    alert_engine.raise(&mut reporter, "stop gap".to_string(), gap).await;
+                                }
+                                info!("✅ Closed perp {} ({}): {} @ ${:.2} | P&L ${:.2} | order #{}",
+                                    name, reason, fill.qty, fill.price, pnl.round_dp(2), order.order_id);
+                                let context = reporter.status().trade_context(reason, level);
+                                record_fill(&mut trade_limiter, &audit, Some(&leg.id), &config.symbol, close_side, &fill, context)?;
+                                trade_limiter.update_pnl(pnl);
+                                reporter.record_trade(close_side, fill.price, fill.qty, Some(pnl), fill.fee);
+                                exited(reason, fill.price)
+                            }
+                            Err(e) => {
+                                leg.exit_failed()?;
+                                error!("❌ Failed to close perp {}: {}", name, e);
+                                format!("{} exit order failed", reason)
+                            }
+                        }
+                    }
+                } else if let Some(reason) = exit_reason {
+                    exit_held_back(reason)
+                } else {
+                    HOLDING.to_string()
+                };
+                decisions.push(format!("{}: {}", leg.id, decision));
+            } else if signal != entry_signal {
+                let cross = if leg.short { "bearish" } else { "bullish" };
+                decisions.push(format!("{}: waiting for a {} crossover", leg.id, cross));
+            } else if !can_trade {
+                decisions.push(format!("{}: {}", leg.id, entry_blocked(&metrics, &mut trade_limiter, None)));
+            } else {
+                // 10% of the free margin, levered up
+                let margin = loss_backoff(wallet.available * config.position_size_percent / dec!(100), reporter.status());
+                let qty = symbol_info.round_qty(margin * Decimal::from(settings.leverage) / current_price);
+                let pause = session_pause.as_ref().map(|reason| (SkipReason::Session, reason))
+                    .or(btc_pause.as_ref().filter(|_| !leg.short).map(|reason| (SkipReason::BtcTrend, reason)))
+                    .or(streak_pause.as_ref().map(|reason| (SkipReason::LossStreak, reason)))
+                    .or(target_pause.as_ref().map(|reason| (SkipReason::TargetAchieved, reason)))
+                    .or(drawdown_halt.as_ref().map(|reason| (SkipReason::Drawdown, reason)))
+                    .or(operator_pause.as_ref().map(|reason| (SkipReason::Paused, reason)));
+                let decision = if let Some((code, reason)) = pause {
+                    info!("⏸️ Skipping {} entry: {}", name, reason);
+                    skipped(&metrics, code, reason)
+                } else if !symbol_info.meets_minimums(qty, current_price) {
+                    warn!("⚠️ Skipping {} entry: {} {} is below the minimum order size", name, qty, config.symbol);
+                    skipped(&metrics, SkipReason::MinSize, "below the minimum order size")
+                } else if !config.live_trading_enabled {
+                    info!("🎯 ENTRY ALERT: {} {} {} perp @ ${:.2}", name, qty, config.symbol, current_price);
+                    "entry alert only - live trading disabled".to_string()
+                } else {
+                    leg.begin_entry(qty)?;
+                    match client.place_market_order(&config.symbol, open_side, qty, position_side, false).await {
+                        Ok(order) => {
+                            let fill = order.execution(&symbol_info.base, &symbol_info.quote, current_price, qty, config.trading_fee_percent);
+                            info!("✅ Opened perp {}: {} @ ${:.2} ({}x) | order #{}", name, fill.qty, fill.price, settings.leverage, order.order_id);
+                            leg.entry_filled(fill)?;
+                            leg.set_percent_targets(config.stop_loss_percent, config.take_profit_percent);
+                            let trigger = if leg.short { "Bearish crossover" } else { "Bullish crossover" };
+                            let context = reporter.status().trade_context(trigger, None);
+                            record_fill(&mut trade_limiter, &audit, Some(&leg.id), &config.symbol, open_side, &fill, context)?;
+                            reporter.record_trade(open_side, fill.price, fill.qty, None, fill.fee);
+                            entered(fill.price)
+                        }
+                        Err(e) => {
+                            leg.entry_failed()?;
+                            error!("❌ Failed to open perp {}: {}", name, e);
+                            "entry order failed".to_string()
+                        }
+                    }
+                };
+                decisions.push(format!("{}: {}", leg.id, decision));
+            }
+        }
+        reporter.record_decision(decisions.join(" | "));
+        if settings.hedge_mode {
+            reporter.update_positions(&[long.clone(), short.clone()]);
+        } else {
+            reporter.update_positions(std::slice::from_ref(&long));
+        }
+
+        rollover.run_if_due(&mut reporter, &paths, &audit).await;
+        reporter.write_if_dirty()?;
+        tokio::time::sleep(Duration::from_secs(PRICE_CHECK_INTERVAL_SECS)).await;
+    }
+}
+
+/// Reconcile the report's balances and the open positions with fresh exchange balances,
+/// alerting on divergence and adopting the exchange figures with DRIFT_AUTO_CORRECT
+async fn check_drift(
+    monitor: &mut DriftMonitor,
+    exchange: &impl Exchange,
+    reporter: &mut PortfolioReporter,
+    positions: &mut [Position],
+    funds: &FundsLedger,
+    base: &str,
+    audit: &AuditLog,
+) {
+    let balance = match exchange.get_balance().await {
+        Ok(balance) => balance,
+        Err(e) => {
+            warn!("⚠️ Drift check skipped: {}", e);
+            return;
+        }
+    };
+    let free: std::collections::HashMap<String, Decimal> = balance.iter().map(|(k, v)| (k.clone(), v.free)).collect();
+    let held = balance.get(base).map_or(dec!(0), |b| b.free + b.locked);
+    let position_qty = positions.iter().filter(|p| p.is_open()).map(|p| p.qty).sum();
+    let drifts = monitor.compare(&reporter.status().balances, &free, base, held, position_qty);
+
+    for drift in monitor.new_drifts(drifts.clone()) {
+        error!("🚨 DRIFT: {}", drift);
+        reporter.raise_alert(format!("🚨 Drift - {}", drift));
+        audit.record(AuditEvent::Drift { subject: drift.subject.clone(), tracked: drift.tracked,
+            exchange: drift.exchange, corrected: monitor.auto_correct });
+        monitor.notify(&format!("🚨 Drift detected\n{}\n{}", drift,
+            if monitor.auto_correct { "Adopted the exchange figures" } else { "Check for missed fills or manual trades" })).await;
+    }
+
+    if monitor.auto_correct && !drifts.is_empty() {
+        reporter.update_balances(free);
+        // Shrink positions, in slot order, to what is actually held
+        let mut remaining = held;
+        for position in positions.iter_mut().filter(|p| p.is_open()) {
+            let qty = position.qty.min(remaining);
+            remaining -= qty;
+            if position.sync(qty, position.entry_price) {
+                info!("🔧 {} position set to {} {} to match the exchange", position.id, qty, base);
+                if qty.is_zero() {
+                    funds.release(&position.ledger_key());
+                }
+            }
+        }
+        reporter.update_positions(positions);
+    }
+}
+
+/// Apply queued user-data stream events to the reporter
+fn apply_stream_events(
+    events: &mut tokio::sync::broadcast::Receiver<BotEvent>,
+    reporter: &mut PortfolioReporter,
+    stream_connected: &mut bool,
+) {
+    use tokio::sync::broadcast::error::TryRecvError;
+    loop {
+        match events.try_recv() {
+            Ok(BotEvent::BalanceUpdate { asset, free, .. }) => reporter.apply_balance_update(&asset, free),
+            Ok(BotEvent::OrderUpdate { symbol, order_id, side, status, last_filled_qty, last_filled_price, .. }) => {
+                info!("📨 Order {} {} {} {}: {} @ {}", order_id, side, symbol, status,
+                    last_filled_qty, last_filled_price);
+                reporter.set_last_event(format!("📨 Order {} {} {}", order_id, side, status));
+            }
+            Ok(BotEvent::StreamStatus { connected }) => *stream_connected = connected,
+            Err(TryRecvError::Lagged(skipped)) => {
+                // Missed balance updates: fall back to polling once to resync
+                warn!("⚠️ Missed {} stream events", skipped);
+                *stream_connected = false;
+            }
+            Err(_) => break,
+        }
+    }
+}
+
+/// Closes the SMA and RSI run on: INDICATOR_INTERVAL candles from the exchange, or the
+/// CoinGecko hourly closes without it (or while the exchange doesn't answer)
+async fn indicator_closes(feed: Option<&mut CandleFeed>, exchange: &impl Exchange, symbol: &str,
+                          hourly: &[Decimal]) -> Vec<Decimal> {
+    let Some(feed) = feed else {
+        return hourly.to_vec();
+    };
+    match feed.candles(exchange, symbol, chrono::Utc::now().timestamp_millis()).await {
+        Ok(candles) if !candles.is_empty() => candles.iter().map(|k| k.close).collect(),
+        Ok(_) => hourly.to_vec(),
+        Err(e) => {
+            warn!("⚠️ Failed to fetch {} candles, using hourly closes: {}", feed.interval().as_str(), e);
+            hourly.to_vec()
+        }
+    }
+}
+
+/// Start the metrics endpoint and report page when METRICS_ADDR is set
+async fn start_metrics(config: &Config, paths: &DataPaths, watchlist: &PriceWatchlist,
+                       analysis: &AnalysisFeed) -> Result<Metrics> {
+    let metrics = Metrics::new();
+    if let Some(addr) = &config.metrics_addr {
+        metrics.serve(addr, paths.html_report_file(), watchlist.routes().merge(analysis.routes())).await?;
+    }
+    Ok(metrics)
+}
+
+/// Start the gRPC control interface when GRPC_ADDR is set
+async fn start_control(config: &Config, audit: &AuditLog) -> Result<BotControl> {
+    let control = BotControl::new();
+    if let Some(addr) = grpc::listen_addr(config)? {
+        ControlService::new(control.clone(), audit.events())
+            .with_token(config.grpc_token.clone())
+            .serve(addr).await?;
+    }
+    Ok(control)
+}
+
+fn update_metrics(metrics: &Metrics, reporter: &PortfolioReporter) {
+    use rust_decimal::prelude::ToPrimitive;
+    let status = reporter.status();
+    metrics.set_gauge("bot_price", "Last price of the traded symbol", status.current_price.to_f64().unwrap_or(0.0));
+    metrics.set_gauge("bot_portfolio_value", "Total portfolio value in the quote asset",
+                      status.total_portfolio_value.to_f64().unwrap_or(0.0));
+    metrics.set_gauge("bot_trades_today", "Trades counted against today's limit", status.trades_today as f64);
+    metrics.inc_counter("bot_cycles_total", "Monitoring loop iterations");
+    if let Some(bytes) = retention::resident_memory_bytes() {
+        metrics.set_gauge("bot_memory_rss_bytes", "Resident memory of the bot process", bytes as f64);
+    }
+}
+
+/// Price other stablecoin/fiat balances (e.g. EUR or USDC when trading against USDT)
+/// in the quote asset so the report's total value includes them
+async fn update_conversion_rates(exchange: &impl Exchange, reporter: &mut PortfolioReporter) {
+    let quote_asset = reporter.status().quote_asset.clone();
+    let assets: Vec<String> = reporter.status().balances.keys()
+        .filter(|a| quote::SUPPORTED_QUOTES.contains(&a.as_str()))
+        .filter(|a| quote::peg_rate(a, &quote_asset).is_none())
+        .cloned()
+        .collect();
+
+    for asset in assets {
+        let rate = match exchange.get_price(&format!("{}{}", asset, quote_asset)).await {
+            Ok(price) => Some(price),
+            Err(_) => exchange.get_price(&format!("{}{}", quote_asset, asset)).await.ok()
+                .filter(|p| !p.is_zero())
+                .map(|p| Decimal::ONE / p),
+        };
+        match rate {
+            Some(rate) => reporter.set_conversion_rate(&asset, rate),
+            None => warn!("⚠️ No {}/{} market to value {} balance", asset, quote_asset, asset),
+        }
+    }
+}
+
+/// What became of [`execute_live_order`]
+#[derive(Debug)]
+pub enum LiveOrder {
+    Placed(models::Order),
+    /// Proposed for approval and not decided yet; the next cycle asks again
+    AwaitingApproval,
+    NotPlaced,
+}
+
+impl LiveOrder {
+    /// Decision text for an order that didn't go out
+    fn not_placed(&self, failed: &str) -> String {
+        match self {
+            LiveOrder::AwaitingApproval => "waiting for approval".to_string(),
+            _ => failed.to_string(),
+        }
+    }
+}
+
+/// Place a live market order, proposing it for approval first when the notional is above
+/// the confirmation threshold. The approval is not waited for: until it arrives the order is
+/// `AwaitingApproval` and the caller retries next cycle. An approved order goes out at no more
+/// than the approved quantity, and is dropped when the price moved past
+/// CONFIRM_PRICE_TOLERANCE_PERCENT meanwhile; protective exits are passed without a confirmer so they
+/// go out right away.
+#[allow(clippy::too_many_arguments)]
+pub async fn execute_live_order(
+    exchange: &impl Exchange,
+    confirmer: Option<&TradeConfirmer>,
+    symbol: &str,
+    position_id: Option<&str>,
+    side: models::OrderSide,
+    mut qty: Decimal,
+    price: Decimal,
+    reason: &str,
+) -> LiveOrder {
+    let notional = qty * price;
+
+    if let Some(confirmer) = confirmer
+        && confirmer.requires_confirmation(notional) {
+        let slot = format!("{}:{}", position_id.unwrap_or(reason), side);
+        let proposal = match confirmer.request(&slot, TradeProposal::new(symbol, side, qty, price, reason)).await {
+            Ok((ConfirmationDecision::Approved { .. }, proposal)) => proposal,
+            Ok((ConfirmationDecision::Pending, _)) => return LiveOrder::AwaitingApproval,
+            Ok(_) => return LiveOrder::NotPlaced,
+            Err(e) => {
+                error!("❌ Trade confirmation failed, skipping order: {}", e);
+                return LiveOrder::NotPlaced;
+            }
+        };
+        // The approval may have taken minutes; a moved market gets a fresh proposal next cycle
+        match exchange.get_price(symbol).await {
+            Ok(now) if confirmer.price_holds(&proposal, now) => {}
+            Ok(now) => {
+                warn!("⚠️ Price moved from ${:.2} to ${:.2} while trade {} waited for approval, skipping order",
+                    proposal.price, now, proposal.id);
+                return LiveOrder::NotPlaced;
+            }
+            Err(e) => {
+                error!("❌ Couldn't re-check the price of approved trade {}, skipping order: {}", proposal.id, e);
+                return LiveOrder::NotPlaced;
+            }
+        }
+        qty = qty.min(proposal.quantity);
+    }
+
+    let placed = match position_id {
+        Some(id) => exchange.place_position_order(id, symbol, side, models::OrderType::Market, qty, None).await,
+        None => exchange.place_order(symbol, side, models::OrderType::Market, qty, None).await,
+    };
+    match placed {
+        Ok(order) => {
+            info!("✅ LIVE {} executed ({}): {} @ ~${:.2} | order #{}",
+                side, reason, qty, price.round_dp(2), order.order_id);
+            LiveOrder::Placed(order)
+        }
+        Err(e) => {
+            error!("❌ LIVE {} order failed: {}", side, e);
+            LiveOrder::NotPlaced
+        }
+    }
+}
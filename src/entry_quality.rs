@@ -0,0 +1,146 @@
+use crate::config::Config;
+use crate::divergence;
+use crate::error::BotError;
+use crate::portfolio::PortfolioStatus;
+use anyhow::Result;
+use rust_decimal::Decimal;
+use rust_decimal_macros::dec;
+use std::fmt;
+
+/// Points each factor is worth; a factor without data scores half
+const FACTOR_POINTS: Decimal = dec!(20);
+/// Above the support by this much, in percent, the support factor scores nothing
+const SUPPORT_RANGE_PERCENT: Decimal = dec!(5);
+/// Added for mostly bullish RSI/MACD divergences, taken off for mostly bearish ones
+const DIVERGENCE_POINTS: Decimal = dec!(10);
+
+/// Composite 0-100 quality of an entry at the current price, 20 points for each of trend
+/// alignment, closeness to support, RSI, volume and AI confidence, adjusted for divergences
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct EntryScore {
+    pub trend: Decimal,
+    pub support: Decimal,
+    pub rsi: Decimal,
+    pub volume: Decimal,
+    pub confidence: Decimal,
+    /// +10, -10 or 0 without divergences (DIVERGENCE_ENABLED) or as many of each
+    pub divergence: Decimal,
+}
+
+impl EntryScore {
+    /// Scored from the indicators, levels and AI confidence of the last analysis
+    pub fn from_status(status: &PortfolioStatus) -> Self {
+        let half = FACTOR_POINTS / dec!(2);
+        let clamp = |points: Decimal| points.clamp(Decimal::ZERO, FACTOR_POINTS);
+        let price = status.current_price;
+        Self {
+            trend: match (status.sma_short, status.sma_long) {
+                (Some(short), Some(long)) if short > long => FACTOR_POINTS,
+                (Some(_), Some(_)) => Decimal::ZERO,
+                _ => half,
+            },
+            // Right at the support scores full; below it the level has broken
+            support: match status.support.filter(|_| price > Decimal::ZERO) {
+                Some(support) if price >= support => {
+                    let above = (price - support) / price * dec!(100);
+                    clamp(FACTOR_POINTS * (Decimal::ONE - above / SUPPORT_RANGE_PERCENT))
+                }
+                _ => half,
+            },
+            // Oversold (30) scores full, overbought (70) nothing
+            rsi: status.rsi.map_or(half, |rsi| clamp(FACTOR_POINTS * (dec!(70) - rsi) / dec!(40))),
+            // Average volume scores half, twice the average full
+            volume: status.volume_ratio.map_or(half, |ratio| clamp(half * ratio)),
+            confidence: status.ai_confidence.map_or(half, |c| clamp(c / dec!(100) * FACTOR_POINTS)),
+            divergence: DIVERGENCE_POINTS * Decimal::from(divergence::bias(&status.divergences).signum()),
+        }
+    }
+
+    pub fn total(&self) -> Decimal {
+        (self.trend + self.support + self.rsi + self.volume + self.confidence + self.divergence)
+            .clamp(Decimal::ZERO, dec!(100))
+    }
+}
+
+/// "62 (trend 20, support 8, rsi 11, volume 10, ai 13)", with ", divergence +10" when adjusted
+impl fmt::Display for EntryScore {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} (trend {}, support {}, rsi {}, volume {}, ai {}", self.total().round_dp(0),
+            self.trend.round_dp(0), self.support.round_dp(0), self.rsi.round_dp(0),
+            self.volume.round_dp(0), self.confidence.round_dp(0))?;
+        if !self.divergence.is_zero() {
+            write!(f, ", divergence {:+}", self.divergence)?;
+        }
+        write!(f, ")")
+    }
+}
+
+/// Holds back buy targets whose entry score is below ENTRY_MIN_SCORE
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct EntryGate {
+    min_score: Decimal,
+}
+
+impl EntryGate {
+    /// `None` without ENTRY_MIN_SCORE
+    pub fn from_config(config: &Config) -> Result<Option<Self>> {
+        let Some(min_score) = config.entry_min_score else {
+            return Ok(None);
+        };
+        if min_score < Decimal::ZERO || min_score > dec!(100) {
+            return Err(BotError::config(format!("ENTRY_MIN_SCORE must be between 0 and 100, got {}", min_score)).into());
+        }
+        Ok(Some(Self { min_score }))
+    }
+
+    pub fn min_score(&self) -> Decimal {
+        self.min_score
+    }
+
+    pub fn admits(&self, score: &EntryScore) -> bool {
+        score.total() >= self.min_score
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::divergence::{Divergence, DivergenceKind, Oscillator};
+
+    #[test]
+    fn test_score_breakdown_and_threshold() {
+        let mut status = PortfolioStatus::new("BTCUSDT", true);
+        status.current_price = dec!(100);
+        let neutral = EntryScore::from_status(&status);
+        assert_eq!(neutral.total(), dec!(50));
+
+        status.sma_short = Some(dec!(101));
+        status.sma_long = Some(dec!(99));
+        status.support = Some(dec!(99));
+        status.rsi = Some(dec!(40));
+        status.volume_ratio = Some(dec!(3));
+        status.ai_confidence = Some(dec!(70));
+        let score = EntryScore::from_status(&status);
+        assert_eq!((score.trend, score.support, score.rsi, score.volume, score.confidence),
+                   (dec!(20), dec!(16), dec!(15), dec!(20), dec!(14)));
+        assert_eq!(score.to_string(), "85 (trend 20, support 16, rsi 15, volume 20, ai 14)");
+
+        let bearish = Divergence {
+            kind: DivergenceKind::Bearish, oscillator: Oscillator::Rsi, open_time: 0,
+            previous_price: dec!(100), price: dec!(101), previous_value: dec!(75), value: dec!(68),
+        };
+        status.divergences = vec![bearish];
+        let diverging = EntryScore::from_status(&status);
+        assert_eq!(diverging.to_string(), "75 (trend 20, support 16, rsi 15, volume 20, ai 14, divergence -10)");
+
+        let mut config = Config::defaults();
+        config.entry_min_score = Some(dec!(90));
+        let gate = EntryGate::from_config(&config).unwrap().unwrap();
+        assert!(!gate.admits(&score));
+        config.entry_min_score = Some(dec!(85));
+        let gate = EntryGate::from_config(&config).unwrap().unwrap();
+        assert!(gate.admits(&score) && !gate.admits(&diverging));
+        config.entry_min_score = Some(dec!(101));
+        assert!(EntryGate::from_config(&config).is_err());
+    }
+}
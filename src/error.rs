@@ -0,0 +1,165 @@
+use reqwest::StatusCode;
+use std::path::PathBuf;
+use thiserror::Error;
+
+/// What went wrong, independent of which component failed. Retry and
+/// back-off logic should branch on this rather than on message text.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ErrorKind {
+    /// HTTP 429/418 or an explicit rate-limit response
+    RateLimited,
+    /// Missing, invalid or under-privileged credentials (401/403)
+    Unauthorized,
+    /// The request did not complete in time
+    Timeout,
+    /// Connection failure or a 5xx response
+    Unavailable,
+    /// The response arrived but could not be parsed or lacked expected fields
+    InvalidResponse,
+    /// The request was understood and refused (other 4xx)
+    Rejected,
+}
+
+impl ErrorKind {
+    pub fn from_status(status: StatusCode) -> Self {
+        match status.as_u16() {
+            418 | 429 => Self::RateLimited,
+            401 | 403 => Self::Unauthorized,
+            408 | 504 => Self::Timeout,
+            s if s >= 500 => Self::Unavailable,
+            _ => Self::Rejected,
+        }
+    }
+
+    pub fn from_reqwest(error: &reqwest::Error) -> Self {
+        if let Some(status) = error.status() {
+            Self::from_status(status)
+        } else if error.is_timeout() {
+            Self::Timeout
+        } else if error.is_decode() {
+            Self::InvalidResponse
+        } else {
+            Self::Unavailable
+        }
+    }
+
+    /// Whether the same request may succeed if tried again later
+    pub fn is_retryable(self) -> bool {
+        matches!(self, Self::RateLimited | Self::Timeout | Self::Unavailable)
+    }
+}
+
+/// Crate-level error type. Functions still return `anyhow::Result`; a `BotError`
+/// at the root of the chain can be recovered with [`BotError::find`].
+#[derive(Debug, Error)]
+pub enum BotError {
+    /// Market data providers (CoinGecko)
+    #[error("{provider} error: {message}")]
+    DataSource { provider: &'static str, kind: ErrorKind, message: String },
+    /// The exchange REST API
+    #[error("Exchange API error {message}")]
+    Exchange { kind: ErrorKind, message: String },
+    /// The AI advisor (Ollama)
+    #[error("Ollama API error: {message}")]
+    Ai { kind: ErrorKind, message: String },
+    /// Invalid or missing configuration; never retryable
+    #[error("{0}")]
+    Config(String),
+    /// State, report and key files
+    #[error("Failed to access {}: {source}", path.display())]
+    Storage { path: PathBuf, source: std::io::Error },
+    /// Another instance is already trading the same account
+    #[error("Another bot instance is running for this account ({holder}); stop it before starting a new one")]
+    InstanceLocked { holder: String },
+}
+
+impl BotError {
+    pub fn data_source(provider: &'static str, status: StatusCode, body: &str) -> Self {
+        Self::DataSource { provider, kind: ErrorKind::from_status(status), message: format!("{}: {}", status, body) }
+    }
+
+    pub fn exchange(status: StatusCode, body: &str) -> Self {
+        Self::Exchange { kind: ErrorKind::from_status(status), message: format!("{}: {}", status, body) }
+    }
+
+    pub fn ai(status: StatusCode) -> Self {
+        Self::Ai { kind: ErrorKind::from_status(status), message: status.to_string() }
+    }
+
+    pub fn config(message: impl Into<String>) -> Self {
+        Self::Config(message.into())
+    }
+
+    pub fn storage(path: impl Into<PathBuf>, source: std::io::Error) -> Self {
+        Self::Storage { path: path.into(), source }
+    }
+
+    /// `None` for configuration, storage and lock errors, which aren't request failures
+    pub fn kind(&self) -> Option<ErrorKind> {
+        match self {
+            Self::DataSource { kind, .. } | Self::Exchange { kind, .. } | Self::Ai { kind, .. } => Some(*kind),
+            Self::Config(_) | Self::Storage { .. } | Self::InstanceLocked { .. } => None,
+        }
+    }
+
+    pub fn is_retryable(&self) -> bool {
+        self.kind().is_some_and(ErrorKind::is_retryable)
+    }
+
+    /// The first `BotError` in an error's chain, if any
+    pub fn find(error: &anyhow::Error) -> Option<&BotError> {
+        error.chain().find_map(|e| e.downcast_ref::<BotError>())
+    }
+
+    /// Rewrite the message text (used to strip secrets) while keeping the variant and kind
+    pub fn map_message(self, f: impl FnOnce(&str) -> String) -> Self {
+        match self {
+            Self::DataSource { provider, kind, message } => Self::DataSource { provider, kind, message: f(&message) },
+            Self::Exchange { kind, message } => Self::Exchange { kind, message: f(&message) },
+            Self::Ai { kind, message } => Self::Ai { kind, message: f(&message) },
+            Self::Config(message) => Self::Config(f(&message)),
+            other @ (Self::Storage { .. } | Self::InstanceLocked { .. }) => other,
+        }
+    }
+}
+
+/// Tag reqwest transport failures (connect, timeout, body decode) with the failing component
+pub trait ReqwestExt<T> {
+    fn or_exchange(self) -> Result<T, BotError>;
+    fn or_data_source(self, provider: &'static str) -> Result<T, BotError>;
+    fn or_ai(self) -> Result<T, BotError>;
+}
+
+impl<T> ReqwestExt<T> for Result<T, reqwest::Error> {
+    fn or_exchange(self) -> Result<T, BotError> {
+        self.map_err(|e| BotError::Exchange { kind: ErrorKind::from_reqwest(&e), message: e.to_string() })
+    }
+
+    fn or_data_source(self, provider: &'static str) -> Result<T, BotError> {
+        self.map_err(|e| BotError::DataSource { provider, kind: ErrorKind::from_reqwest(&e), message: e.to_string() })
+    }
+
+    fn or_ai(self) -> Result<T, BotError> {
+        self.map_err(|e| BotError::Ai { kind: ErrorKind::from_reqwest(&e), message: e.to_string() })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use anyhow::Context;
+
+    #[test]
+    fn test_kind_survives_anyhow_context() {
+        let rate_limited: anyhow::Result<()> = Err(BotError::data_source("CoinGecko", StatusCode::TOO_MANY_REQUESTS, "").into());
+        let err = rate_limited.context("Fetching market data").unwrap_err();
+        let bot_error = BotError::find(&err).unwrap();
+        assert_eq!(bot_error.kind(), Some(ErrorKind::RateLimited));
+        assert!(bot_error.is_retryable());
+
+        let bad_key = BotError::exchange(StatusCode::UNAUTHORIZED, r#"{"code":-2015}"#);
+        assert_eq!(bad_key.kind(), Some(ErrorKind::Unauthorized));
+        assert!(!bad_key.is_retryable());
+        assert_eq!(bad_key.to_string(), r#"Exchange API error 401 Unauthorized: {"code":-2015}"#);
+    }
+}
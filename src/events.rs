@@ -0,0 +1,59 @@
+use crate::models::OrderSide;
+use rust_decimal::Decimal;
+use tokio::sync::broadcast;
+
+/// Capacity of the event channel; slow subscribers lose the oldest events
+const EVENT_BUS_CAPACITY: usize = 256;
+
+/// Events published by background tasks (exchange streams) to the trading loop
+#[derive(Debug, Clone, PartialEq)]
+pub enum BotEvent {
+    /// An order changed state on the exchange (new, partial fill, filled, canceled, ...)
+    OrderUpdate {
+        symbol: String,
+        order_id: i64,
+        side: OrderSide,
+        status: String,
+        last_filled_qty: Decimal,
+        last_filled_price: Decimal,
+        cumulative_qty: Decimal,
+        commission: Decimal,
+        commission_asset: Option<String>,
+    },
+    /// New free/locked amounts of an asset after an account change
+    BalanceUpdate {
+        asset: String,
+        free: Decimal,
+        locked: Decimal,
+    },
+    /// The user-data stream connected or dropped
+    StreamStatus { connected: bool },
+}
+
+/// Broadcast bus connecting event producers with any number of subscribers
+#[derive(Debug, Clone)]
+pub struct EventBus {
+    sender: broadcast::Sender<BotEvent>,
+}
+
+impl EventBus {
+    pub fn new() -> Self {
+        let (sender, _) = broadcast::channel(EVENT_BUS_CAPACITY);
+        Self { sender }
+    }
+
+    /// Publish an event; having no subscribers is not an error
+    pub fn publish(&self, event: BotEvent) {
+        let _ = self.sender.send(event);
+    }
+
+    pub fn subscribe(&self) -> broadcast::Receiver<BotEvent> {
+        self.sender.subscribe()
+    }
+}
+
+impl Default for EventBus {
+    fn default() -> Self {
+        Self::new()
+    }
+}
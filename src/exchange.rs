@@ -1,17 +1,60 @@
 use crate::config::Config;
-use crate::models::{Balance, OrderSide, OrderType, Order};
-use anyhow::Result;
+use crate::error::{BotError, ReqwestExt};
+use crate::models::{Balance, BookTicker, Kline, OpenOrder, OrderBookDepth, OrderSide, OrderType, Order};
+use crate::rate_limit::{WeightBudget, WeightTracker};
+use crate::secrets::Redactor;
+use crate::symbols::{self, SymbolInfo};
+use anyhow::{anyhow, Result};
 use hmac::{Hmac, Mac};
 use rust_decimal::Decimal;
 use sha2::Sha256;
 use std::collections::HashMap;
+use std::future::Future;
 use std::time::{SystemTime, UNIX_EPOCH};
 
 type HmacSha256 = Hmac<Sha256>;
 
+/// Market data and order entry shared by the Binance client and the simulated
+/// exchange, so strategies and execution code can run against either.
+/// Venue-specific calls (listen keys, dust conversion, filters) stay on the concrete types.
+pub trait Exchange: Send + Sync {
+    fn get_price(&self, symbol: &str) -> impl Future<Output = Result<Decimal>> + Send;
+
+    fn get_balance(&self) -> impl Future<Output = Result<HashMap<String, Balance>>> + Send;
+
+    fn place_order(
+        &self,
+        symbol: &str,
+        side: OrderSide,
+        order_type: OrderType,
+        quantity: Decimal,
+        price: Option<Decimal>,
+    ) -> impl Future<Output = Result<Order>> + Send;
+
+    /// `place_order` tagged with the position it belongs to, see [`position_order_id`]
+    fn place_position_order(
+        &self,
+        position_id: &str,
+        symbol: &str,
+        side: OrderSide,
+        order_type: OrderType,
+        quantity: Decimal,
+        price: Option<Decimal>,
+    ) -> impl Future<Output = Result<Order>> + Send;
+
+    fn get_klines(&self, symbol: &str, interval: &str, limit: u32) -> impl Future<Output = Result<Vec<Kline>>> + Send;
+}
+
+/// Client order id tying an order to a position: `<position>-<millis>`
+pub fn position_order_id(position_id: &str) -> String {
+    format!("{}-{}", position_id, chrono::Utc::now().timestamp_millis())
+}
+
 pub struct ExchangeClient {
     config: Config,
     client: reqwest::Client,
+    redactor: Redactor,
+    weights: WeightTracker,
 }
 
 impl ExchangeClient {
@@ -23,11 +66,22 @@ impl ExchangeClient {
         Ok(Self {
             config: config.clone(),
             client,
+            redactor: Redactor::new(&[&config.api_key, &config.api_secret]),
+            weights: WeightTracker::new(config.exchange_weight_limit),
         })
     }
 
+    /// Client for public market data; candles are public, so simulation mode reads them from Binance too
+    pub async fn public(config: &Config) -> Result<Self> {
+        let mut config = config.clone();
+        if config.is_simulation() {
+            config.base_url = "https://api.binance.com".to_string();
+        }
+        Self::new(&config).await
+    }
+
     fn sign(&self, query_string: &str) -> String {
-        let mut mac = HmacSha256::new_from_slice(self.config.api_secret.as_bytes())
+        let mut mac = HmacSha256::new_from_slice(self.config.api_secret.expose().as_bytes())
             .expect("HMAC can take key of any size");
         mac.update(query_string.as_bytes());
         hex::encode(mac.finalize().into_bytes())
@@ -40,40 +94,158 @@ impl ExchangeClient {
             .as_millis()
     }
 
+    fn signed_url(&self, path: &str, params: &[String]) -> String {
+        let mut params = params.to_vec();
+        params.push(format!("timestamp={}", Self::timestamp()));
+        let query = params.join("&");
+        let signature = self.sign(&query);
+        format!("{}{}?{}&signature={}", self.config.base_url, path, query, signature)
+    }
+
+    /// Wait for the request-weight budget, then send the request
+    async fn send_json<T: serde::de::DeserializeOwned>(&self, request: reqwest::RequestBuilder) -> Result<T> {
+        self.weights.acquire().await;
+        self.dispatch(request).await
+    }
+
+    /// A signed request. The weight budget is waited for before the request is timestamped
+    /// and signed, so a throttle never pushes it past Binance's `recvWindow`.
+    async fn signed<T: serde::de::DeserializeOwned>(&self, method: reqwest::Method, path: &str, params: &[String]) -> Result<T> {
+        self.weights.acquire().await;
+        self.send_signed(method, path, params).await
+    }
+
+    /// Timestamp, sign and send a request right away
+    async fn send_signed<T: serde::de::DeserializeOwned>(&self, method: reqwest::Method, path: &str, params: &[String]) -> Result<T> {
+        let url = self.signed_url(path, params);
+        self.dispatch(self.client.request(method, &url).header("X-MBX-APIKEY", self.config.api_key.expose())).await
+    }
+
+    /// Send a request and decode the JSON body. Every error leaving this client goes
+    /// through the redactor so keys and request signatures never reach the logs.
+    async fn dispatch<T: serde::de::DeserializeOwned>(&self, request: reqwest::RequestBuilder) -> Result<T> {
+        let result: Result<T> = async {
+            let response = request.send().await.or_exchange()?;
+            let status = response.status();
+            self.weights.record(response.headers(), status);
+            if !status.is_success() {
+                let body = response.text().await.unwrap_or_default();
+                return Err(BotError::exchange(status, &body).into());
+            }
+            Ok(response.json().await.or_exchange()?)
+        }
+        .await;
+
+        result.map_err(|e| self.redactor.redact_error(e))
+    }
+
+    /// Request-weight budget for the current minute
+    pub fn weight_budget(&self) -> WeightBudget {
+        self.weights.budget()
+    }
+
     pub async fn get_price(&self, symbol: &str) -> Result<Decimal> {
         let url = format!("{}/api/v3/ticker/price?symbol={}", self.config.base_url, symbol);
         
-        let response: serde_json::Value = self.client
-            .get(&url)
-            .send()
-            .await?
-            .json()
-            .await?;
+        let response: serde_json::Value = self.send_json(self.client.get(&url)).await?;
 
         let price_str = response["price"]
             .as_str()
-            .ok_or_else(|| anyhow::anyhow!("Price not found in response"))?;
+            .ok_or_else(|| anyhow!("Price not found in response"))?;
 
         Ok(price_str.parse()?)
     }
 
-    pub async fn get_balance(&self) -> Result<HashMap<String, Balance>> {
-        let timestamp = Self::timestamp();
-        let query = format!("timestamp={}", timestamp);
-        let signature = self.sign(&query);
+    /// Best bid and ask for a symbol
+    pub async fn get_book_ticker(&self, symbol: &str) -> Result<BookTicker> {
+        let url = format!("{}/api/v3/ticker/bookTicker?symbol={}", self.config.base_url, symbol);
+        self.send_json(self.client.get(&url)).await
+    }
 
-        let url = format!(
-            "{}/api/v3/account?{}&signature={}",
-            self.config.base_url, query, signature
-        );
+    /// The best `limit` bid and ask levels of the order book
+    pub async fn get_depth(&self, symbol: &str, limit: u32) -> Result<OrderBookDepth> {
+        let url = format!("{}/api/v3/depth?symbol={}&limit={}", self.config.base_url, symbol, limit);
+        self.send_json(self.client.get(&url)).await
+    }
+
+    /// Symbol metadata from the registry, refined with the exchange's live trading filters
+    pub async fn get_symbol_info(&self, symbol: &str) -> Result<SymbolInfo> {
+        let url = format!("{}/api/v3/exchangeInfo?symbol={}", self.config.base_url, symbol);
+        let response: serde_json::Value = self.send_json(self.client.get(&url)).await?;
+
+        let mut info = symbols::lookup(symbol);
+        let filters = response["symbols"][0]["filters"]
+            .as_array()
+            .ok_or_else(|| anyhow!("No exchange filters for {}", symbol))?;
+        let field = |filter: &serde_json::Value, name: &str| -> Option<Decimal> {
+            filter[name].as_str().and_then(|v| v.parse::<Decimal>().ok()).map(|d| d.normalize())
+        };
+
+        for filter in filters {
+            match filter["filterType"].as_str().unwrap_or_default() {
+                "PRICE_FILTER" => {
+                    if let Some(tick) = field(filter, "tickSize").filter(|t| !t.is_zero()) {
+                        info.price_precision = tick.scale();
+                    }
+                }
+                "LOT_SIZE" => {
+                    if let Some(step) = field(filter, "stepSize").filter(|s| !s.is_zero()) {
+                        info.qty_precision = step.scale();
+                    }
+                    if let Some(min_qty) = field(filter, "minQty") {
+                        info.min_qty = min_qty;
+                    }
+                }
+                "NOTIONAL" | "MIN_NOTIONAL" => {
+                    if let Some(min_notional) = field(filter, "minNotional") {
+                        info.min_notional = min_notional;
+                    }
+                }
+                _ => {}
+            }
+        }
+
+        Ok(info)
+    }
+
+    /// Whether the exchange reports system-wide maintenance (`/sapi/v1/system/status`).
+    /// Returns the status message when under maintenance.
+    pub async fn get_system_maintenance(&self) -> Result<Option<String>> {
+        let url = format!("{}/sapi/v1/system/status", self.config.base_url);
+        let response: serde_json::Value = self.send_json(self.client.get(&url)).await?;
+
+        // 0: normal, 1: system maintenance
+        if response["status"].as_i64() == Some(1) {
+            Ok(Some(response["msg"].as_str().unwrap_or("system maintenance").to_string()))
+        } else {
+            Ok(None)
+        }
+    }
 
-        let response: serde_json::Value = self.client
-            .get(&url)
-            .header("X-MBX-APIKEY", &self.config.api_key)
-            .send()
-            .await?
-            .json()
-            .await?;
+    /// Exchange clock in milliseconds since the epoch; signed requests are rejected when
+    /// the local clock drifts too far from it
+    pub async fn get_server_time(&self) -> Result<i64> {
+        let url = format!("{}/api/v3/time", self.config.base_url);
+        let response: serde_json::Value = self.send_json(self.client.get(&url)).await?;
+        response["serverTime"].as_i64().ok_or_else(|| anyhow!("serverTime not found in response"))
+    }
+
+    /// Trading status of a symbol (`TRADING`, `HALT`, `BREAK`, ...)
+    pub async fn get_symbol_status(&self, symbol: &str) -> Result<String> {
+        let url = format!("{}/api/v3/exchangeInfo?symbol={}", self.config.base_url, symbol);
+        let response: serde_json::Value = self.send_json(self.client.get(&url)).await?;
+
+        response["symbols"][0]["status"]
+            .as_str()
+            .map(|s| s.to_string())
+            .ok_or_else(|| anyhow!("No status for {}", symbol))
+    }
+
+    pub async fn get_balance(&self) -> Result<HashMap<String, Balance>> {
+        if self.config.margin_account {
+            return Ok(self.get_margin_account().await?.balances());
+        }
+        let response: serde_json::Value = self.signed(reqwest::Method::GET, "/api/v3/account", &[]).await?;
 
         let mut balances = HashMap::new();
         
@@ -92,7 +264,12 @@ impl ExchangeClient {
         Ok(balances)
     }
 
-    #[allow(dead_code)]
+    /// Cross margin account: free/locked plus borrowed amounts and accrued interest
+    pub async fn get_margin_account(&self) -> Result<MarginAccount> {
+        let response: serde_json::Value = self.signed(reqwest::Method::GET, "/sapi/v1/margin/account", &[]).await?;
+        Ok(MarginAccount::from_json(&response))
+    }
+
     pub async fn place_order(
         &self,
         symbol: &str,
@@ -101,62 +278,175 @@ impl ExchangeClient {
         quantity: Decimal,
         price: Option<Decimal>,
     ) -> Result<Order> {
-        let timestamp = Self::timestamp();
-        
+        self.submit_order(None, symbol, side, order_type, quantity, price).await
+    }
+
+    /// Place an order tagged with the position it belongs to
+    pub async fn place_position_order(
+        &self,
+        position_id: &str,
+        symbol: &str,
+        side: OrderSide,
+        order_type: OrderType,
+        quantity: Decimal,
+        price: Option<Decimal>,
+    ) -> Result<Order> {
+        self.submit_order(Some(position_order_id(position_id)), symbol, side, order_type, quantity, price).await
+    }
+
+    async fn submit_order(
+        &self,
+        client_order_id: Option<String>,
+        symbol: &str,
+        side: OrderSide,
+        order_type: OrderType,
+        quantity: Decimal,
+        price: Option<Decimal>,
+    ) -> Result<Order> {
         let mut params = vec![
             format!("symbol={}", symbol),
             format!("side={}", side),
             format!("type={}", order_type),
             format!("quantity={}", quantity),
-            format!("timestamp={}", timestamp),
         ];
 
         if let Some(p) = price {
             params.push(format!("price={}", p));
             params.push("timeInForce=GTC".to_string());
         }
+        if let Some(id) = client_order_id {
+            params.push(format!("newClientOrderId={}", id));
+        }
 
-        let query = params.join("&");
-        let signature = self.sign(&query);
-        
-        let url = format!(
-            "{}/api/v3/order?{}&signature={}",
-            self.config.base_url, query, signature
-        );
+        // Margin orders never borrow or repay unless borrowing is explicitly enabled
+        let path = if self.config.margin_account {
+            let side_effect = match (self.config.margin_borrow_enabled, side) {
+                (false, _) => "NO_SIDE_EFFECT",
+                (true, OrderSide::Buy) => "MARGIN_BUY",
+                (true, OrderSide::Sell) => "AUTO_REPAY",
+            };
+            params.push(format!("sideEffectType={}", side_effect));
+            "/sapi/v1/margin/order"
+        } else {
+            "/api/v3/order"
+        };
+
+        // Orders weigh 1 and have a rate limit of their own, so they don't queue behind the
+        // weight throttle (a stop-loss must go out now); during a 429/418 pause they fail instead
+        self.weights.check_ban()?;
+        self.send_signed(reqwest::Method::POST, path, &params).await
+    }
+
+    /// Orders resting on the book for a symbol
+    pub async fn get_open_orders(&self, symbol: &str) -> Result<Vec<OpenOrder>> {
+        self.signed(reqwest::Method::GET, "/api/v3/openOrders", &[format!("symbol={}", symbol)]).await
+    }
+
+    pub async fn cancel_order(&self, symbol: &str, order_id: i64) -> Result<()> {
+        let _: serde_json::Value = self.signed(reqwest::Method::DELETE, "/api/v3/order",
+            &[format!("symbol={}", symbol), format!("orderId={}", order_id)]).await?;
+        Ok(())
+    }
+
+    /// Cancel every open order on a symbol; returns how many were cancelled
+    pub async fn cancel_all_orders(&self, symbol: &str) -> Result<usize> {
+        let response: serde_json::Value = self.signed(reqwest::Method::DELETE, "/api/v3/openOrders",
+            &[format!("symbol={}", symbol)]).await?;
+
+        Ok(response.as_array().map(|a| a.len()).unwrap_or(0))
+    }
+
+    /// Convert small balances of `assets` to BNB (Binance "Convert Small Balance to BNB").
+    /// Returns the amount of BNB received.
+    pub async fn convert_dust(&self, assets: &[&str]) -> Result<Decimal> {
+        let params: Vec<String> = assets.iter().map(|a| format!("asset={}", a)).collect();
+        let response: serde_json::Value = self.signed(reqwest::Method::POST, "/sapi/v1/asset/dust", &params).await?;
+
+        Ok(response["totalTransfered"]
+            .as_str()
+            .and_then(|v| v.parse().ok())
+            .unwrap_or_default())
+    }
+
+    /// Query what the API key is allowed to do. Uses the key-level restrictions
+    /// endpoint and falls back to account flags where it is unavailable (testnet).
+    pub async fn get_api_permissions(&self) -> Result<ApiKeyPermissions> {
+        let restrictions: Result<serde_json::Value> = self.signed(reqwest::Method::GET,
+            "/sapi/v1/account/apiRestrictions", &[]).await;
 
-        let response: Order = self.client
-            .post(&url)
-            .header("X-MBX-APIKEY", &self.config.api_key)
-            .send()
-            .await?
-            .json()
-            .await?;
+        if let Ok(r) = restrictions {
+            return Ok(ApiKeyPermissions {
+                can_read: r["enableReading"].as_bool().unwrap_or(false),
+                can_trade: r["enableSpotAndMarginTrading"].as_bool().unwrap_or(false),
+                can_withdraw: r["enableWithdrawals"].as_bool().unwrap_or(true),
+                ip_restricted: r["ipRestrict"].as_bool(),
+            });
+        }
+
+        // Reading the account proves read access; its flags describe trade/withdraw rights
+        let account: serde_json::Value = self.signed(reqwest::Method::GET, "/api/v3/account", &[]).await?;
+
+        Ok(ApiKeyPermissions {
+            can_read: true,
+            can_trade: account["canTrade"].as_bool().unwrap_or(false),
+            can_withdraw: account["canWithdraw"].as_bool().unwrap_or(false)
+                && self.config.exchange != "binance_testnet",
+            ip_restricted: None,
+        })
+    }
+
+    /// Create a listen key for the user-data stream
+    pub async fn create_listen_key(&self) -> Result<String> {
+        let url = format!("{}/api/v3/userDataStream", self.config.base_url);
+        let response: serde_json::Value = self.send_json(
+            self.client
+                .post(&url)
+                .header("X-MBX-APIKEY", self.config.api_key.expose()),
+        ).await?;
+
+        response["listenKey"]
+            .as_str()
+            .map(|k| k.to_string())
+            .ok_or_else(|| anyhow!("No listenKey in response"))
+    }
+
+    /// Extend a listen key's validity by 60 minutes
+    pub async fn keepalive_listen_key(&self, listen_key: &str) -> Result<()> {
+        let url = format!("{}/api/v3/userDataStream?listenKey={}", self.config.base_url, listen_key);
+        let _: serde_json::Value = self.send_json(
+            self.client
+                .put(&url)
+                .header("X-MBX-APIKEY", self.config.api_key.expose()),
+        ).await?;
+        Ok(())
+    }
 
-        Ok(response)
+    pub async fn close_listen_key(&self, listen_key: &str) -> Result<()> {
+        let url = format!("{}/api/v3/userDataStream?listenKey={}", self.config.base_url, listen_key);
+        let _: serde_json::Value = self.send_json(
+            self.client
+                .delete(&url)
+                .header("X-MBX-APIKEY", self.config.api_key.expose()),
+        ).await?;
+        Ok(())
     }
 
-    #[allow(dead_code)]
     pub async fn get_klines(
         &self,
         symbol: &str,
         interval: &str,
         limit: u32,
-    ) -> Result<Vec<crate::models::Kline>> {
+    ) -> Result<Vec<Kline>> {
         let url = format!(
             "{}/api/v3/klines?symbol={}&interval={}&limit={}",
             self.config.base_url, symbol, interval, limit
         );
 
-        let response: Vec<Vec<serde_json::Value>> = self.client
-            .get(&url)
-            .send()
-            .await?
-            .json()
-            .await?;
+        let response: Vec<Vec<serde_json::Value>> = self.send_json(self.client.get(&url)).await?;
 
         let klines = response
             .into_iter()
-            .map(|k| crate::models::Kline {
+            .map(|k| Kline {
                 open_time: k[0].as_i64().unwrap_or_default(),
                 open: k[1].as_str().unwrap_or("0").parse().unwrap_or_default(),
                 high: k[2].as_str().unwrap_or("0").parse().unwrap_or_default(),
@@ -170,3 +460,165 @@ impl ExchangeClient {
         Ok(klines)
     }
 }
+
+impl Exchange for ExchangeClient {
+    async fn get_price(&self, symbol: &str) -> Result<Decimal> {
+        ExchangeClient::get_price(self, symbol).await
+    }
+
+    async fn get_balance(&self) -> Result<HashMap<String, Balance>> {
+        ExchangeClient::get_balance(self).await
+    }
+
+    async fn place_order(
+        &self,
+        symbol: &str,
+        side: OrderSide,
+        order_type: OrderType,
+        quantity: Decimal,
+        price: Option<Decimal>,
+    ) -> Result<Order> {
+        ExchangeClient::place_order(self, symbol, side, order_type, quantity, price).await
+    }
+
+    async fn place_position_order(
+        &self,
+        position_id: &str,
+        symbol: &str,
+        side: OrderSide,
+        order_type: OrderType,
+        quantity: Decimal,
+        price: Option<Decimal>,
+    ) -> Result<Order> {
+        ExchangeClient::place_position_order(self, position_id, symbol, side, order_type, quantity, price).await
+    }
+
+    async fn get_klines(&self, symbol: &str, interval: &str, limit: u32) -> Result<Vec<Kline>> {
+        ExchangeClient::get_klines(self, symbol, interval, limit).await
+    }
+}
+
+/// What the configured API key is permitted to do
+/// One asset of the cross margin account
+#[derive(Debug, Clone, PartialEq)]
+pub struct MarginAsset {
+    pub free: Decimal,
+    pub locked: Decimal,
+    pub borrowed: Decimal,
+    pub interest: Decimal,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct MarginAccount {
+    pub assets: HashMap<String, MarginAsset>,
+    /// Total assets / total liabilities; Binance liquidates at 1.1
+    pub margin_level: Option<Decimal>,
+}
+
+impl MarginAccount {
+    fn from_json(response: &serde_json::Value) -> Self {
+        let field = |a: &serde_json::Value, key: &str| -> Decimal {
+            a[key].as_str().unwrap_or("0").parse().unwrap_or_default()
+        };
+        let assets = response["userAssets"].as_array().into_iter().flatten()
+            .map(|a| (a["asset"].as_str().unwrap_or_default().to_string(), MarginAsset {
+                free: field(a, "free"),
+                locked: field(a, "locked"),
+                borrowed: field(a, "borrowed"),
+                interest: field(a, "interest"),
+            }))
+            .filter(|(_, a)| !(a.free.is_zero() && a.locked.is_zero() && a.borrowed.is_zero() && a.interest.is_zero()))
+            .collect();
+        Self {
+            assets,
+            margin_level: response["marginLevel"].as_str().and_then(|l| l.parse().ok()),
+        }
+    }
+
+    /// Held amounts in the same shape as the spot wallet (borrowed funds included)
+    pub fn balances(&self) -> HashMap<String, Balance> {
+        self.assets.iter()
+            .filter(|(_, a)| a.free > Decimal::ZERO || a.locked > Decimal::ZERO)
+            .map(|(asset, a)| (asset.clone(), Balance { asset: asset.clone(), free: a.free, locked: a.locked }))
+            .collect()
+    }
+
+    /// Borrowed amount plus accrued interest per asset
+    pub fn liabilities(&self) -> HashMap<String, Decimal> {
+        self.assets.iter()
+            .map(|(asset, a)| (asset.clone(), a.borrowed + a.interest))
+            .filter(|(_, owed)| *owed > Decimal::ZERO)
+            .collect()
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct ApiKeyPermissions {
+    pub can_read: bool,
+    pub can_trade: bool,
+    pub can_withdraw: bool,
+    /// None when the exchange does not report IP restrictions
+    pub ip_restricted: Option<bool>,
+}
+
+impl ApiKeyPermissions {
+    /// Safety posture check: the key must read, must trade when live execution is on,
+    /// and must never be able to withdraw funds.
+    pub fn verify(&self, live_trading_enabled: bool) -> Result<()> {
+        if self.can_withdraw {
+            return Err(anyhow!(
+                "API key has WITHDRAWAL permission enabled - refusing to start. \
+                 Create a key without withdrawal rights."
+            ));
+        }
+        if !self.can_read {
+            return Err(anyhow!("API key cannot read account data"));
+        }
+        if !self.can_trade && live_trading_enabled {
+            return Err(anyhow!(
+                "API key lacks spot trading permission but LIVE_TRADING_ENABLED=true"
+            ));
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_permission_posture() {
+        let mut perms = ApiKeyPermissions {
+            can_read: true,
+            can_trade: true,
+            can_withdraw: false,
+            ip_restricted: Some(true),
+        };
+        assert!(perms.verify(true).is_ok());
+
+        perms.can_withdraw = true;
+        assert!(perms.verify(false).is_err());
+
+        perms.can_withdraw = false;
+        perms.can_trade = false;
+        assert!(perms.verify(false).is_ok());
+        assert!(perms.verify(true).is_err());
+    }
+
+    #[test]
+    fn test_margin_account_liabilities() {
+        let response = serde_json::json!({
+            "marginLevel": "3.5",
+            "userAssets": [
+                {"asset": "USDT", "free": "1500", "locked": "0", "borrowed": "1000", "interest": "0.25"},
+                {"asset": "BTC", "free": "0.02", "locked": "0", "borrowed": "0", "interest": "0"},
+                {"asset": "ETH", "free": "0", "locked": "0", "borrowed": "0", "interest": "0"}
+            ]
+        });
+        let account = MarginAccount::from_json(&response);
+        assert_eq!(account.margin_level, Some(Decimal::new(35, 1)));
+        assert_eq!(account.balances().len(), 2);
+        assert_eq!(account.liabilities(), HashMap::from([("USDT".to_string(), Decimal::new(100025, 2))]));
+    }
+}
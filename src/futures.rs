@@ -0,0 +1,460 @@
+use crate::accounting::NetPosition;
+use crate::config::Config;
+use crate::error::{BotError, ReqwestExt};
+use crate::exchange::Exchange;
+use crate::models::{Balance, Kline, Order, OrderSide, OrderType};
+use crate::rate_limit::WeightTracker;
+use crate::secrets::Redactor;
+use anyhow::{anyhow, Result};
+use hmac::{Hmac, Mac};
+use rust_decimal::Decimal;
+use rust_decimal_macros::dec;
+use serde::Deserialize;
+use sha2::Sha256;
+use std::collections::HashMap;
+use std::fmt;
+use std::time::{SystemTime, UNIX_EPOCH};
+use tracing::info;
+
+type HmacSha256 = Hmac<Sha256>;
+
+/// Used when the leverage brackets can't be loaded (Binance's lowest BTCUSDT tier is 0.4%)
+pub const DEFAULT_MAINTENANCE_MARGIN_RATE: Decimal = dec!(0.005);
+const MAX_LEVERAGE: u32 = 125;
+/// Binance error returned when the margin type is already the requested one
+const NO_MARGIN_TYPE_CHANGE: &str = "-4046";
+/// Binance error returned when the position mode is already the requested one
+const NO_POSITION_MODE_CHANGE: &str = "-4059";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MarginType {
+    Isolated,
+    Cross,
+}
+
+impl MarginType {
+    pub fn parse(value: &str) -> Result<Self> {
+        match value.to_lowercase().as_str() {
+            "isolated" => Ok(Self::Isolated),
+            "cross" | "crossed" => Ok(Self::Cross),
+            other => Err(BotError::config(format!("FUTURES_MARGIN_TYPE must be isolated or cross, got '{}'", other)).into()),
+        }
+    }
+
+    fn api_name(self) -> &'static str {
+        match self {
+            Self::Isolated => "ISOLATED",
+            Self::Cross => "CROSSED",
+        }
+    }
+}
+
+impl fmt::Display for MarginType {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Isolated => write!(f, "isolated"),
+            Self::Cross => write!(f, "cross"),
+        }
+    }
+}
+
+/// Side of a hedge-mode position or order; `Both` in one-way mode
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "UPPERCASE")]
+pub enum PositionSide {
+    #[default]
+    Both,
+    Long,
+    Short,
+}
+
+impl fmt::Display for PositionSide {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Both => write!(f, "BOTH"),
+            Self::Long => write!(f, "LONG"),
+            Self::Short => write!(f, "SHORT"),
+        }
+    }
+}
+
+/// Leverage, margin and position mode applied before trading
+#[derive(Debug, Clone, Copy)]
+pub struct FuturesSettings {
+    pub leverage: u32,
+    pub margin_type: MarginType,
+    /// A long and a short on the same contract can be open at once (FUTURES_HEDGE_MODE)
+    pub hedge_mode: bool,
+}
+
+impl FuturesSettings {
+    pub fn from_config(config: &Config) -> Result<Self> {
+        if !(1..=MAX_LEVERAGE).contains(&config.futures_leverage) {
+            return Err(BotError::config(format!("FUTURES_LEVERAGE must be between 1 and {}, got {}",
+                MAX_LEVERAGE, config.futures_leverage)).into());
+        }
+        Ok(Self {
+            leverage: config.futures_leverage,
+            margin_type: MarginType::parse(&config.futures_margin_type)?,
+            hedge_mode: config.futures_hedge_mode,
+        })
+    }
+}
+
+/// One open leg of the perpetual position as shown in the report
+#[derive(Debug, Clone, PartialEq)]
+pub struct FuturesLeg {
+    pub side: PositionSide,
+    /// Contract quantity; negative for shorts
+    pub amount: Decimal,
+    pub entry_price: Decimal,
+    pub unrealized_pnl: Decimal,
+    /// Our own estimate, see [`liquidation_price`]
+    pub liquidation_price: Option<Decimal>,
+}
+
+/// Open perpetual legs as shown in the report: at most one in one-way mode, a long and
+/// a short in hedge mode
+#[derive(Debug, Clone, PartialEq)]
+pub struct FuturesPosition {
+    pub leverage: u32,
+    pub margin_type: MarginType,
+    pub hedge_mode: bool,
+    pub mark_price: Decimal,
+    pub legs: Vec<FuturesLeg>,
+    /// Funding fees received (positive) or paid (negative) since start
+    pub funding_total: Decimal,
+}
+
+impl FuturesPosition {
+    /// The legs netted into one exposure
+    pub fn net(&self) -> NetPosition {
+        let mut net = NetPosition::default();
+        for leg in &self.legs {
+            net.add(leg.amount, leg.entry_price);
+        }
+        net
+    }
+
+    /// Notional of the net exposure; hedged quantity offsets itself
+    pub fn notional(&self) -> Decimal {
+        self.net().net_qty().abs() * self.mark_price
+    }
+}
+
+/// Position fields from `GET /fapi/v2/positionRisk`
+#[derive(Debug, Clone, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct PositionRisk {
+    #[serde(rename = "positionAmt")]
+    pub amount: Decimal,
+    pub entry_price: Decimal,
+    pub mark_price: Decimal,
+    #[serde(rename = "unRealizedProfit")]
+    pub unrealized_pnl: Decimal,
+    #[serde(default)]
+    pub isolated_wallet: Decimal,
+    #[serde(default)]
+    pub position_side: PositionSide,
+}
+
+/// Wallet of the margin asset from `GET /fapi/v2/balance`
+#[derive(Debug, Clone, Copy)]
+pub struct FuturesWallet {
+    pub balance: Decimal,
+    pub available: Decimal,
+    pub unrealized_pnl: Decimal,
+}
+
+impl FuturesWallet {
+    /// Wallet balance plus open P&L
+    pub fn margin_balance(&self) -> Decimal {
+        self.balance + self.unrealized_pnl
+    }
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct FundingFee {
+    pub income: Decimal,
+    pub time: i64,
+}
+
+/// Liquidation price of a single one-way position, following Binance's formula without the
+/// maintenance amount: `(WB - side·qty·entry) / (qty·MMR - side·qty)`. `wallet` is the position's
+/// isolated margin, or the whole account balance under cross margin.
+pub fn liquidation_price(amount: Decimal, entry: Decimal, wallet: Decimal, maintenance_rate: Decimal) -> Option<Decimal> {
+    if amount.is_zero() {
+        return None;
+    }
+    let side = if amount > Decimal::ZERO { Decimal::ONE } else { -Decimal::ONE };
+    let qty = amount.abs();
+    let denominator = qty * maintenance_rate - side * qty;
+    if denominator.is_zero() {
+        return None;
+    }
+    let price = (wallet - side * qty * entry) / denominator;
+    (price > Decimal::ZERO).then_some(price)
+}
+
+/// Binance USDⓈ-M perpetuals client. Separate from the spot [`crate::exchange::ExchangeClient`]:
+/// different host, endpoints and order semantics (reduce-only closes, leverage, funding).
+pub struct FuturesClient {
+    config: Config,
+    client: reqwest::Client,
+    redactor: Redactor,
+    weights: WeightTracker,
+}
+
+impl FuturesClient {
+    pub fn new(config: &Config) -> Result<Self> {
+        let client = reqwest::Client::builder()
+            .timeout(std::time::Duration::from_secs(30))
+            .build()?;
+
+        Ok(Self {
+            config: config.clone(),
+            client,
+            redactor: Redactor::new(&[&config.api_key, &config.api_secret]),
+            weights: WeightTracker::new(config.exchange_weight_limit),
+        })
+    }
+
+    fn sign(&self, query_string: &str) -> String {
+        let mut mac = HmacSha256::new_from_slice(self.config.api_secret.expose().as_bytes())
+            .expect("HMAC can take key of any size");
+        mac.update(query_string.as_bytes());
+        hex::encode(mac.finalize().into_bytes())
+    }
+
+    fn timestamp() -> u128 {
+        SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap()
+            .as_millis()
+    }
+
+    fn signed_url(&self, path: &str, params: &[String]) -> String {
+        let mut params = params.to_vec();
+        params.push(format!("timestamp={}", Self::timestamp()));
+        let query = params.join("&");
+        let signature = self.sign(&query);
+        format!("{}{}?{}&signature={}", self.config.futures_base_url, path, query, signature)
+    }
+
+    /// Wait for the request-weight budget, then send the request
+    async fn send_json<T: serde::de::DeserializeOwned>(&self, request: reqwest::RequestBuilder) -> Result<T> {
+        self.weights.acquire().await;
+        self.dispatch(request).await
+    }
+
+    async fn dispatch<T: serde::de::DeserializeOwned>(&self, request: reqwest::RequestBuilder) -> Result<T> {
+        let result: Result<T> = async {
+            let response = request.send().await.or_exchange()?;
+            let status = response.status();
+            self.weights.record(response.headers(), status);
+            if !status.is_success() {
+                let body = response.text().await.unwrap_or_default();
+                return Err(BotError::exchange(status, &body).into());
+            }
+            Ok(response.json().await.or_exchange()?)
+        }
+        .await;
+
+        result.map_err(|e| self.redactor.redact_error(e))
+    }
+
+    /// A signed request; the weight budget is waited for before it is timestamped and signed,
+    /// so a throttle never pushes it past `recvWindow`
+    async fn signed<T: serde::de::DeserializeOwned>(&self, method: reqwest::Method, path: &str, params: &[String]) -> Result<T> {
+        self.weights.acquire().await;
+        self.send_signed(method, path, params).await
+    }
+
+    async fn send_signed<T: serde::de::DeserializeOwned>(&self, method: reqwest::Method, path: &str, params: &[String]) -> Result<T> {
+        let url = self.signed_url(path, params);
+        self.dispatch(self.client.request(method, &url).header("X-MBX-APIKEY", self.config.api_key.expose())).await
+    }
+
+    /// Apply the position mode (account-wide), margin type and leverage to `symbol`
+    pub async fn configure(&self, symbol: &str, settings: &FuturesSettings) -> Result<()> {
+        let mode: Result<serde_json::Value> = self.signed(reqwest::Method::POST, "/fapi/v1/positionSide/dual",
+            &[format!("dualSidePosition={}", settings.hedge_mode)]).await;
+        if let Err(e) = mode
+            && !e.to_string().contains(NO_POSITION_MODE_CHANGE) {
+            return Err(e.context(format!("Failed to switch to {} mode (close open positions first)",
+                if settings.hedge_mode { "hedge" } else { "one-way" })));
+        }
+
+        let margin: Result<serde_json::Value> = self.signed(reqwest::Method::POST, "/fapi/v1/marginType",
+            &[format!("symbol={}", symbol), format!("marginType={}", settings.margin_type.api_name())]).await;
+        if let Err(e) = margin
+            && !e.to_string().contains(NO_MARGIN_TYPE_CHANGE) {
+            return Err(e.context(format!("Failed to set {} margin on {}", settings.margin_type, symbol)));
+        }
+
+        let _: serde_json::Value = self.signed(reqwest::Method::POST, "/fapi/v1/leverage",
+            &[format!("symbol={}", symbol), format!("leverage={}", settings.leverage)]).await
+            .map_err(|e| e.context(format!("Failed to set {}x leverage on {}", settings.leverage, symbol)))?;
+        info!("📜 {}: {}x leverage, {} margin, {} mode", symbol, settings.leverage, settings.margin_type,
+            if settings.hedge_mode { "hedge" } else { "one-way" });
+        Ok(())
+    }
+
+    /// Maintenance margin rate of the lowest leverage bracket
+    pub async fn get_maintenance_margin_rate(&self, symbol: &str) -> Result<Decimal> {
+        let response: serde_json::Value = self.signed(reqwest::Method::GET, "/fapi/v1/leverageBracket",
+            &[format!("symbol={}", symbol)]).await?;
+        let entry = response.as_array().and_then(|a| a.first()).unwrap_or(&response);
+        entry["brackets"][0]["maintMarginRatio"].as_f64()
+            .and_then(|r| Decimal::try_from(r).ok())
+            .ok_or_else(|| anyhow!("No leverage brackets for {}", symbol))
+    }
+
+    /// Open legs in `symbol`: at most one in one-way mode, up to a long and a short in hedge mode
+    pub async fn get_positions(&self, symbol: &str) -> Result<Vec<PositionRisk>> {
+        let positions: Vec<PositionRisk> = self.signed(reqwest::Method::GET, "/fapi/v2/positionRisk",
+            &[format!("symbol={}", symbol)]).await?;
+        Ok(positions.into_iter().filter(|p| !p.amount.is_zero()).collect())
+    }
+
+    pub async fn get_wallet(&self, asset: &str) -> Result<FuturesWallet> {
+        let response: Vec<serde_json::Value> = self.signed(reqwest::Method::GET, "/fapi/v2/balance", &[]).await?;
+        let field = |b: &serde_json::Value, key: &str| b[key].as_str().unwrap_or("0").parse().unwrap_or_default();
+        response.iter()
+            .find(|b| b["asset"].as_str() == Some(asset))
+            .map(|b| FuturesWallet {
+                balance: field(b, "balance"),
+                available: field(b, "availableBalance"),
+                unrealized_pnl: field(b, "crossUnPnl"),
+            })
+            .ok_or_else(|| anyhow!("No {} in the futures wallet", asset))
+    }
+
+    /// Funding fees for `symbol` booked at or after `since_ms`, oldest first
+    pub async fn get_funding_fees(&self, symbol: &str, since_ms: i64) -> Result<Vec<FundingFee>> {
+        self.signed(reqwest::Method::GET, "/fapi/v1/income", &[
+            format!("symbol={}", symbol),
+            "incomeType=FUNDING_FEE".to_string(),
+            format!("startTime={}", since_ms),
+        ]).await
+    }
+
+    /// Market order; `reduce_only` closes without ever flipping the position. In hedge mode
+    /// the leg is named by `position_side` instead, and Binance rejects `reduceOnly`.
+    pub async fn place_market_order(&self, symbol: &str, side: OrderSide, quantity: Decimal,
+                                    position_side: PositionSide, reduce_only: bool) -> Result<Order> {
+        let mut params = vec![
+            format!("symbol={}", symbol),
+            format!("side={}", side),
+            "type=MARKET".to_string(),
+            format!("quantity={}", quantity),
+            // RESULT responses carry the executed quantity and average price
+            "newOrderRespType=RESULT".to_string(),
+        ];
+        if position_side != PositionSide::Both {
+            params.push(format!("positionSide={}", position_side));
+        } else if reduce_only {
+            params.push("reduceOnly=true".to_string());
+        }
+        // Orders skip the weight throttle so closing a position never waits; see ExchangeClient
+        self.weights.check_ban()?;
+        self.send_signed(reqwest::Method::POST, "/fapi/v1/order", &params).await
+    }
+
+    pub async fn get_price(&self, symbol: &str) -> Result<Decimal> {
+        let url = format!("{}/fapi/v1/ticker/price?symbol={}", self.config.futures_base_url, symbol);
+        let response: serde_json::Value = self.send_json(self.client.get(&url)).await?;
+        response["price"].as_str()
+            .and_then(|p| p.parse().ok())
+            .ok_or_else(|| anyhow!("Invalid futures price response"))
+    }
+
+    pub async fn get_klines(&self, symbol: &str, interval: &str, limit: u32) -> Result<Vec<Kline>> {
+        let url = format!("{}/fapi/v1/klines?symbol={}&interval={}&limit={}",
+            self.config.futures_base_url, symbol, interval, limit);
+        let response: Vec<Vec<serde_json::Value>> = self.send_json(self.client.get(&url)).await?;
+        Ok(response
+            .into_iter()
+            .map(|k| Kline {
+                open_time: k[0].as_i64().unwrap_or_default(),
+                open: k[1].as_str().unwrap_or("0").parse().unwrap_or_default(),
+                high: k[2].as_str().unwrap_or("0").parse().unwrap_or_default(),
+                low: k[3].as_str().unwrap_or("0").parse().unwrap_or_default(),
+                close: k[4].as_str().unwrap_or("0").parse().unwrap_or_default(),
+                volume: k[5].as_str().unwrap_or("0").parse().unwrap_or_default(),
+                close_time: k[6].as_i64().unwrap_or_default(),
+            })
+            .collect())
+    }
+}
+
+impl Exchange for FuturesClient {
+    async fn get_price(&self, symbol: &str) -> Result<Decimal> {
+        FuturesClient::get_price(self, symbol).await
+    }
+
+    /// The margin asset's wallet; `locked` is margin in use
+    async fn get_balance(&self) -> Result<HashMap<String, Balance>> {
+        let asset = self.config.quote_asset.clone().unwrap_or_else(|| "USDT".to_string());
+        let wallet = self.get_wallet(&asset).await?;
+        let balance = Balance { asset: asset.clone(), free: wallet.available, locked: wallet.balance - wallet.available };
+        Ok(HashMap::from([(asset, balance)]))
+    }
+
+    async fn place_order(
+        &self,
+        symbol: &str,
+        side: OrderSide,
+        order_type: OrderType,
+        quantity: Decimal,
+        price: Option<Decimal>,
+    ) -> Result<Order> {
+        if order_type != OrderType::Market || price.is_some() {
+            return Err(anyhow!("Futures mode only places market orders"));
+        }
+        self.place_market_order(symbol, side, quantity, PositionSide::Both, false).await
+    }
+
+    /// A one-way futures account nets everything into one position per symbol, so
+    /// orders are not tagged
+    async fn place_position_order(
+        &self,
+        _position_id: &str,
+        symbol: &str,
+        side: OrderSide,
+        order_type: OrderType,
+        quantity: Decimal,
+        price: Option<Decimal>,
+    ) -> Result<Order> {
+        Exchange::place_order(self, symbol, side, order_type, quantity, price).await
+    }
+
+    async fn get_klines(&self, symbol: &str, interval: &str, limit: u32) -> Result<Vec<Kline>> {
+        FuturesClient::get_klines(self, symbol, interval, limit).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_liquidation_price_and_settings() {
+        // 1 BTC long at 50k on 10x isolated: 5000 margin, 0.4% maintenance
+        let long = liquidation_price(dec!(1), dec!(50000), dec!(5000), dec!(0.004)).unwrap();
+        assert_eq!(long.round_dp(2), dec!(45180.72));
+        let short = liquidation_price(dec!(-1), dec!(50000), dec!(5000), dec!(0.004)).unwrap();
+        assert_eq!(short.round_dp(2), dec!(54780.88));
+        // Fully collateralized long can't be liquidated
+        assert!(liquidation_price(dec!(1), dec!(50000), dec!(60000), dec!(0.004)).is_none());
+        assert!(liquidation_price(dec!(0), dec!(50000), dec!(5000), dec!(0.004)).is_none());
+
+        let mut config = Config::defaults();
+        config.futures_leverage = 5;
+        config.futures_margin_type = "Cross".to_string();
+        let settings = FuturesSettings::from_config(&config).unwrap();
+        assert_eq!(settings.margin_type, MarginType::Cross);
+        config.futures_leverage = 200;
+        assert!(FuturesSettings::from_config(&config).is_err());
+    }
+}
@@ -0,0 +1,469 @@
+use crate::audit::AuditEvent;
+use crate::config::Config;
+use crate::control::{BotControl, BotStatus};
+use crate::error::BotError;
+use crate::models::OrderSide;
+use crate::secrets::SecretString;
+use anyhow::Result;
+use chrono::Utc;
+use futures_util::{Stream, StreamExt};
+use rust_decimal::Decimal;
+use std::net::SocketAddr;
+use std::pin::Pin;
+use tokio::sync::broadcast;
+use tokio_stream::wrappers::errors::BroadcastStreamRecvError;
+use tokio_stream::wrappers::{BroadcastStream, TcpListenerStream};
+use tonic::{Request, Response, Status};
+use tracing::{error, info, warn};
+
+mod service {
+    include!(concat!(env!("OUT_DIR"), "/trading_bot.BotControl.rs"));
+}
+
+pub use service::bot_control_client::BotControlClient;
+use service::bot_control_server::{BotControl as BotControlRpc, BotControlServer};
+
+// Messages of proto/trading_bot.proto
+
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct StatusRequest {}
+
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct Position {
+    #[prost(string, tag = "1")]
+    pub id: String,
+    #[prost(string, tag = "2")]
+    pub state: String,
+    #[prost(bool, tag = "3")]
+    pub short: bool,
+    #[prost(string, tag = "4")]
+    pub quantity: String,
+    #[prost(string, tag = "5")]
+    pub entry_price: String,
+    #[prost(string, tag = "6")]
+    pub stop_loss: String,
+    #[prost(string, tag = "7")]
+    pub take_profit: String,
+}
+
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct StatusReply {
+    #[prost(string, tag = "1")]
+    pub symbol: String,
+    #[prost(string, tag = "2")]
+    pub updated_at: String,
+    #[prost(string, tag = "3")]
+    pub price: String,
+    #[prost(string, tag = "4")]
+    pub portfolio_value: String,
+    #[prost(message, repeated, tag = "5")]
+    pub positions: Vec<Position>,
+    #[prost(string, tag = "6")]
+    pub realized_pnl: String,
+    #[prost(uint32, tag = "7")]
+    pub trades_today: u32,
+    #[prost(uint32, tag = "8")]
+    pub max_trades_per_day: u32,
+    #[prost(string, tag = "9")]
+    pub entries_paused: String,
+    #[prost(string, tag = "10")]
+    pub recommendation: String,
+    #[prost(string, tag = "11")]
+    pub confidence: String,
+    #[prost(string, tag = "12")]
+    pub stop_loss: String,
+    #[prost(string, tag = "13")]
+    pub take_profit: String,
+    #[prost(string, tag = "14")]
+    pub buy_target: String,
+    #[prost(string, tag = "15")]
+    pub sell_target: String,
+}
+
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct PauseRequest {
+    #[prost(string, tag = "1")]
+    pub reason: String,
+}
+
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct ResumeRequest {}
+
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct ControlReply {
+    #[prost(bool, tag = "1")]
+    pub paused: bool,
+    #[prost(string, tag = "2")]
+    pub message: String,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, prost::Enumeration)]
+#[repr(i32)]
+pub enum Side {
+    Unspecified = 0,
+    Buy = 1,
+    Sell = 2,
+}
+
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct OrderRequest {
+    #[prost(enumeration = "Side", tag = "1")]
+    pub side: i32,
+    #[prost(string, tag = "2")]
+    pub position_id: String,
+}
+
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct OrderReply {
+    #[prost(bool, tag = "1")]
+    pub placed: bool,
+    #[prost(string, tag = "2")]
+    pub outcome: String,
+}
+
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct EventsRequest {}
+
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct Event {
+    #[prost(string, tag = "1")]
+    pub timestamp: String,
+    #[prost(string, tag = "2")]
+    pub kind: String,
+    #[prost(string, tag = "3")]
+    pub json: String,
+}
+
+fn text(value: Option<Decimal>) -> String {
+    value.map(|v| v.to_string()).unwrap_or_default()
+}
+
+impl From<BotStatus> for StatusReply {
+    fn from(s: BotStatus) -> Self {
+        Self {
+            symbol: s.symbol,
+            updated_at: s.updated_at.to_rfc3339(),
+            price: s.price.to_string(),
+            portfolio_value: s.portfolio_value.round_dp(2).to_string(),
+            positions: s.positions.into_iter().map(|p| Position {
+                id: p.id,
+                state: p.state,
+                short: p.short,
+                quantity: p.qty.to_string(),
+                entry_price: text(p.entry_price),
+                stop_loss: text(p.stop_loss),
+                take_profit: text(p.take_profit),
+            }).collect(),
+            realized_pnl: s.realized_pnl.round_dp(2).to_string(),
+            trades_today: s.trades_today,
+            max_trades_per_day: s.max_trades_per_day,
+            entries_paused: s.entries_paused.unwrap_or_default(),
+            recommendation: s.recommendation.unwrap_or_default(),
+            confidence: text(s.confidence.map(|c| c.round_dp(0))),
+            stop_loss: text(s.stop_loss),
+            take_profit: text(s.take_profit),
+            buy_target: text(s.buy_target),
+            sell_target: text(s.sell_target),
+        }
+    }
+}
+
+impl Event {
+    fn from_audit(event: &AuditEvent) -> Self {
+        let json = serde_json::to_value(event).unwrap_or_default();
+        Self {
+            timestamp: Utc::now().to_rfc3339(),
+            kind: json["event"].as_str().unwrap_or_default().to_string(),
+            json: json.to_string(),
+        }
+    }
+}
+
+/// The listen address in GRPC_ADDR; `None` when unset. Anyone who reaches the port can place
+/// orders, so an address other hosts can reach needs GRPC_TOKEN.
+pub fn listen_addr(config: &Config) -> Result<Option<SocketAddr>> {
+    let Some(addr) = config.grpc_addr.as_deref() else {
+        return Ok(None);
+    };
+    let addr: SocketAddr = addr.parse()
+        .map_err(|_| BotError::config(format!("GRPC_ADDR must be host:port, e.g. 127.0.0.1:50051, got {:?}", addr)))?;
+    if !addr.ip().is_loopback() && config.grpc_token.is_none() {
+        return Err(BotError::config(format!(
+            "GRPC_ADDR {} is reachable from other hosts - set GRPC_TOKEN or bind to 127.0.0.1", addr)).into());
+    }
+    Ok(Some(addr))
+}
+
+/// Let a call through when it carries `authorization: Bearer <token>`, or when there is no token
+fn authorize(token: Option<&SecretString>, request: Request<()>) -> Result<Request<()>, Status> {
+    let Some(token) = token else {
+        return Ok(request);
+    };
+    let sent = request.metadata().get("authorization")
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.strip_prefix("Bearer "))
+        .unwrap_or_default();
+    // Compare every byte, so the time taken doesn't tell how much of a guess was right
+    let expected = token.expose().as_bytes();
+    let matches = sent.len() == expected.len()
+        && sent.bytes().zip(expected).fold(0, |diff, (a, b)| diff | (a ^ b)) == 0;
+    if matches {
+        Ok(request)
+    } else {
+        Err(Status::unauthenticated("missing or wrong bearer token"))
+    }
+}
+
+/// The `BotControl` service over the loop's control state and audit events
+pub struct ControlService {
+    control: BotControl,
+    events: broadcast::Sender<AuditEvent>,
+    /// Bearer token every call must carry (GRPC_TOKEN)
+    token: Option<SecretString>,
+}
+
+impl ControlService {
+    pub fn new(control: BotControl, events: broadcast::Sender<AuditEvent>) -> Self {
+        Self { control, events, token: None }
+    }
+
+    /// Refuse calls that don't send `token` as a bearer token
+    pub fn with_token(self, token: Option<SecretString>) -> Self {
+        Self { token, ..self }
+    }
+
+    /// Serve on `addr` in the background; the bound address (useful with port 0)
+    pub async fn serve(self, addr: SocketAddr) -> Result<SocketAddr> {
+        let listener = tokio::net::TcpListener::bind(addr).await?;
+        let local = listener.local_addr()?;
+        info!("🕹️ gRPC control interface at {}{}", local, if self.token.is_some() { " (bearer token required)" } else { "" });
+        let token = self.token.clone();
+        let service = BotControlServer::with_interceptor(self, move |request| authorize(token.as_ref(), request));
+        tokio::spawn(async move {
+            let server = tonic::transport::Server::builder().add_service(service);
+            if let Err(e) = server.serve_with_incoming(TcpListenerStream::new(listener)).await {
+                error!("❌ gRPC server stopped: {}", e);
+            }
+        });
+        Ok(local)
+    }
+
+    fn reply(&self, message: String) -> Response<ControlReply> {
+        Response::new(ControlReply { paused: self.control.pause_reason().is_some(), message })
+    }
+}
+
+type EventStream = Pin<Box<dyn Stream<Item = Result<Event, Status>> + Send>>;
+
+#[tonic::async_trait]
+impl BotControlRpc for ControlService {
+    async fn get_status(&self, _: Request<StatusRequest>) -> Result<Response<StatusReply>, Status> {
+        let status = self.control.status().ok_or_else(|| Status::unavailable("no cycle has run yet"))?;
+        Ok(Response::new(status.into()))
+    }
+
+    async fn pause(&self, request: Request<PauseRequest>) -> Result<Response<ControlReply>, Status> {
+        let message = if self.control.pause(&request.into_inner().reason) { "entries paused" } else { "already paused" };
+        Ok(self.reply(message.to_string()))
+    }
+
+    async fn resume(&self, _: Request<ResumeRequest>) -> Result<Response<ControlReply>, Status> {
+        let message = if self.control.resume() { "entries resumed" } else { "not paused" };
+        Ok(self.reply(message.to_string()))
+    }
+
+    async fn place_order(&self, request: Request<OrderRequest>) -> Result<Response<OrderReply>, Status> {
+        let request = request.into_inner();
+        let side = match request.side() {
+            Side::Buy => OrderSide::Buy,
+            Side::Sell => OrderSide::Sell,
+            Side::Unspecified => return Err(Status::invalid_argument("side must be SIDE_BUY or SIDE_SELL")),
+        };
+        let outcome = self.control.submit(side, Some(request.position_id))
+            .ok_or_else(|| Status::resource_exhausted("too many manual orders waiting for the next cycle"))?;
+        // Dropped when the client gives up, and the loop then skips the order
+        let outcome = outcome.await.map_err(|_| Status::aborted("the trading loop stopped before the order ran"))?;
+        Ok(Response::new(OrderReply { placed: outcome.placed, outcome: outcome.message }))
+    }
+
+    type StreamEventsStream = EventStream;
+
+    async fn stream_events(&self, _: Request<EventsRequest>) -> Result<Response<EventStream>, Status> {
+        let stream = BroadcastStream::new(self.events.subscribe()).filter_map(|event| async move {
+            match event {
+                Ok(event) => Some(Ok(Event::from_audit(&event))),
+                Err(BroadcastStreamRecvError::Lagged(missed)) => {
+                    warn!("⚠️ gRPC event stream fell behind, skipped {} events", missed);
+                    None
+                }
+            }
+        });
+        Ok(Response::new(Box::pin(stream)))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::portfolio::PortfolioStatus;
+    use rust_decimal_macros::dec;
+
+    #[tokio::test]
+    async fn test_control_service_over_grpc() {
+        let control = BotControl::new();
+        let events = broadcast::channel(16).0;
+        let addr = ControlService::new(control.clone(), events.clone())
+            .serve("127.0.0.1:0".parse().unwrap()).await.unwrap();
+        let mut client = BotControlClient::connect(format!("http://{}", addr)).await.unwrap();
+
+        let error = client.get_status(StatusRequest {}).await.unwrap_err();
+        assert_eq!(error.code(), tonic::Code::Unavailable);
+        let mut status = PortfolioStatus::new("BTCUSDT", true);
+        status.current_price = dec!(64000.5);
+        control.update(&status);
+        let reply = client.get_status(StatusRequest {}).await.unwrap().into_inner();
+        assert_eq!((reply.symbol.as_str(), reply.price.as_str(), reply.stop_loss.as_str()), ("BTCUSDT", "64000.5", ""));
+
+        let reply = client.pause(PauseRequest { reason: "FOMC".to_string() }).await.unwrap().into_inner();
+        assert_eq!((reply.paused, reply.message.as_str()), (true, "entries paused"));
+        assert_eq!(control.pause_reason().as_deref(), Some("paused - FOMC"));
+        assert!(!client.resume(ResumeRequest {}).await.unwrap().into_inner().paused);
+
+        let mut stream = client.stream_events(EventsRequest {}).await.unwrap().into_inner();
+        // The loop's next cycle answers the order
+        let cycle = control.clone();
+        tokio::spawn(async move {
+            loop {
+                if let Some(order) = cycle.take_order() {
+                    order.decline("no targets yet");
+                    break;
+                }
+                tokio::time::sleep(std::time::Duration::from_millis(10)).await;
+            }
+        });
+        let reply = client.place_order(OrderRequest { side: Side::Sell as i32, position_id: String::new() })
+            .await.unwrap().into_inner();
+        assert_eq!((reply.placed, reply.outcome.as_str()), (false, "not placed - no targets yet"));
+        let error = client.place_order(OrderRequest::default()).await.unwrap_err();
+        assert_eq!(error.code(), tonic::Code::InvalidArgument);
+
+        events.send(AuditEvent::Alert { symbol: "BTCUSDT".to_string(), rule: "rsi".to_string(), message: "RSI 75".to_string() }).unwrap();
+        let event = stream.message().await.unwrap().unwrap();
+        assert_eq!(event.kind, "alert");
+        assert!(event.json.contains("RSI 75"));
+    }
+
+    #[tokio::test]
+    async fn test_bearer_token() {
+        let control = BotControl::new();
+        let addr = ControlService::new(control.clone(), broadcast::channel(16).0)
+            .with_token(Some(SecretString::new("s3cret")))
+            .serve("127.0.0.1:0".parse().unwrap()).await.unwrap();
+        let channel = tonic::transport::Endpoint::from_shared(format!("http://{}", addr)).unwrap().connect().await.unwrap();
+
+        let mut anonymous = BotControlClient::new(channel.clone());
+        let error = anonymous.pause(PauseRequest::default()).await.unwrap_err();
+        assert_eq!(error.code(), tonic::Code::Unauthenticated);
+        assert!(control.pause_reason().is_none());
+
+        let bearer = |token: &'static str| move |mut request: Request<()>| {
+            request.metadata_mut().insert("authorization", format!("Bearer {}", token).parse().unwrap());
+            Ok(request)
+        };
+        let mut guessing = BotControlClient::with_interceptor(channel.clone(), bearer("s3cre"));
+        assert_eq!(guessing.pause(PauseRequest::default()).await.unwrap_err().code(), tonic::Code::Unauthenticated);
+        let mut client = BotControlClient::with_interceptor(channel, bearer("s3cret"));
+        assert!(client.pause(PauseRequest::default()).await.unwrap().into_inner().paused);
+    }
+
+    #[test]
+    fn test_listen_addr() {
+        let mut config = Config::defaults();
+        assert_eq!(listen_addr(&config).unwrap(), None);
+        config.grpc_addr = Some("127.0.0.1:50051".to_string());
+        assert!(listen_addr(&config).unwrap().is_some());
+        config.grpc_addr = Some("localhost".to_string());
+        assert!(listen_addr(&config).is_err());
+
+        // Other hosts only reach the port with a token set
+        config.grpc_addr = Some("0.0.0.0:50051".to_string());
+        assert!(listen_addr(&config).is_err());
+        config.grpc_token = Some(SecretString::new("s3cret"));
+        assert!(listen_addr(&config).unwrap().is_some());
+    }
+
+    /// The contract as proto/trading_bot.proto declares it, one line per field, enum value and rpc
+    fn proto_descriptor() -> Vec<String> {
+        let mut lines = Vec::new();
+        let mut scope = String::new();
+        for line in include_str!("../proto/trading_bot.proto").lines() {
+            let line = line.split("//").next().unwrap().trim();
+            let words: Vec<&str> = line.split_whitespace().collect();
+            match words.as_slice() {
+                ["message" | "enum", name, ..] => scope = name.to_string(),
+                ["rpc", ..] => lines.push(line.trim_end_matches(';').to_string()),
+                [.., "=", number] if !scope.is_empty() => {
+                    let declaration = words[..words.len() - 2].join(" ");
+                    lines.push(format!("{}: {} = {}", scope, declaration, number.trim_end_matches(';')));
+                }
+                _ => {}
+            }
+        }
+        lines.sort();
+        lines
+    }
+
+    /// The same lines from the prost structs above and the service in build.rs
+    fn rust_descriptor() -> Vec<String> {
+        let source = include_str!("grpc.rs");
+        let start = source.find("// Messages of proto/trading_bot.proto").unwrap();
+        let end = source.find("fn text(").unwrap();
+        let mut lines = Vec::new();
+        let (mut scope, mut field_type) = (String::new(), None::<String>);
+        for line in source[start..end].lines().map(str::trim) {
+            if let Some(name) = line.strip_prefix("pub struct ").or(line.strip_prefix("pub enum ")) {
+                scope = name.split_whitespace().next().unwrap().to_string();
+            } else if let Some(attribute) = line.strip_prefix("#[prost(").and_then(|a| a.strip_suffix(")]")) {
+                let kind = attribute.split(',').next().unwrap();
+                let repeated = attribute.contains("repeated");
+                field_type = Some(match kind.strip_prefix("enumeration = ") {
+                    Some(name) => name.trim_matches('"').to_string(),
+                    None => kind.to_string(),
+                });
+                if repeated {
+                    field_type = field_type.map(|t| format!("repeated {}", t));
+                }
+                let tag = attribute.rsplit("tag = ").next().unwrap().trim_matches('"');
+                field_type = field_type.map(|t| format!("{} = {}", t, tag));
+            } else if let (Some(declaration), Some(name)) = (field_type.take(), line.strip_prefix("pub ")) {
+                let (name, rust_type) = name.split_once(": ").unwrap();
+                let message = rust_type.trim_end_matches(',').trim_start_matches("Vec<").trim_end_matches('>');
+                let (kind, tag) = declaration.split_once(" = ").unwrap();
+                let kind = kind.replace("message", message);
+                lines.push(format!("{}: {} {} = {}", scope, kind, name, tag));
+            } else if let Some((variant, number)) = line.trim_end_matches(',').split_once(" = ") {
+                // prost drops the enum name prefix and camel-cases the values
+                let mut value = scope.to_uppercase();
+                for c in variant.chars() {
+                    if c.is_uppercase() {
+                        value.push('_');
+                    }
+                    value.push(c.to_ascii_uppercase());
+                }
+                lines.push(format!("{}: {} = {}", scope, value, number));
+            }
+        }
+        for line in include_str!("../build.rs").lines().filter(|l| l.trim_start().starts_with(".method(")) {
+            let names: Vec<&str> = line.split('"').skip(1).step_by(2).collect();
+            let stream = if line.contains("server_streaming") { "stream " } else { "" };
+            lines.push(format!("rpc {}({}) returns ({}{})", names[1], names[2], stream, names[3]));
+        }
+        lines.sort();
+        lines
+    }
+
+    #[test]
+    fn test_messages_match_proto() {
+        assert_eq!(rust_descriptor(), proto_descriptor());
+    }
+}
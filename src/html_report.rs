@@ -0,0 +1,184 @@
+use crate::portfolio::PortfolioStatus;
+use chrono::{DateTime, Local, Utc};
+use rust_decimal::prelude::ToPrimitive;
+use rust_decimal::Decimal;
+use std::fmt::Write as _;
+
+const SPARKLINE_WIDTH: f64 = 240.0;
+const SPARKLINE_HEIGHT: f64 = 48.0;
+
+const STYLE: &str = "body{font-family:system-ui,sans-serif;background:#111827;color:#e5e7eb;margin:2rem}\
+h1{font-size:1.4rem}h2{font-size:1rem;color:#9ca3af;border-bottom:1px solid #374151;padding-bottom:.25rem}\
+table{border-collapse:collapse}td,th{padding:.2rem 1rem .2rem 0;text-align:left}\
+.up{color:#10b981}.down{color:#ef4444}.card{display:inline-block;vertical-align:top;margin:0 2rem 1rem 0}";
+
+/// Inline SVG polyline of `values` scaled to the box; empty with fewer than two points
+pub fn sparkline(values: &[Decimal], color: &str) -> String {
+    let points: Vec<f64> = values.iter().filter_map(|v| v.to_f64()).collect();
+    if points.len() < 2 {
+        return String::new();
+    }
+    let min = points.iter().copied().fold(f64::INFINITY, f64::min);
+    let max = points.iter().copied().fold(f64::NEG_INFINITY, f64::max);
+    let range = if max > min { max - min } else { 1.0 };
+    let step = SPARKLINE_WIDTH / (points.len() - 1) as f64;
+    let coords: Vec<String> = points.iter().enumerate()
+        .map(|(i, v)| format!("{:.1},{:.1}", i as f64 * step, SPARKLINE_HEIGHT - (v - min) / range * SPARKLINE_HEIGHT))
+        .collect();
+    format!(
+        r#"<svg width="{w}" height="{h}" viewBox="0 -2 {w} {h2}"><polyline fill="none" stroke="{color}" stroke-width="1.5" points="{points}"/></svg>"#,
+        w = SPARKLINE_WIDTH, h = SPARKLINE_HEIGHT, h2 = SPARKLINE_HEIGHT + 4.0, color = color, points = coords.join(" "),
+    )
+}
+
+fn escape(text: &str) -> String {
+    text.replace('&', "&amp;").replace('<', "&lt;").replace('>', "&gt;").replace('"', "&quot;")
+}
+
+fn price(s: &PortfolioStatus, value: Option<Decimal>, dp: u32) -> String {
+    value.map_or("-".to_string(), |v| s.display.money(v, dp))
+}
+
+fn trend_color(first: Option<&(DateTime<Utc>, Decimal)>, last: Option<&(DateTime<Utc>, Decimal)>) -> &'static str {
+    match (first, last) {
+        (Some((_, a)), Some((_, b))) if b < a => "#ef4444",
+        _ => "#10b981",
+    }
+}
+
+/// The portfolio report as a self-contained HTML page, with sparklines of the last 24h of
+/// price and equity
+pub fn render(s: &PortfolioStatus) -> String {
+    let t = s.language.strings();
+    let label = |text: &str| escape(text.trim_end_matches(':'));
+    let dp = s.price_decimals;
+    let updated: DateTime<Local> = s.last_updated.into();
+    let mode = if s.is_simulation { "Simulation" } else { "Live" };
+    let prices: Vec<Decimal> = s.price_history.iter().map(|(_, p)| *p).collect();
+    let equity: Vec<Decimal> = s.equity_history.iter().map(|(_, v)| *v).collect();
+    let change_class = if s.price_change_24h_percent < Decimal::ZERO { "down" } else { "up" };
+
+    let mut out = String::new();
+    let _ = write!(out, r#"<!DOCTYPE html>
+<html><head><meta charset="utf-8"><meta http-equiv="refresh" content="60">
+<title>{symbol} - Portfolio Status</title><style>{STYLE}</style></head><body>
+<h1>{symbol} &middot; {mode}</h1>
+<p>{updated_label} {updated}</p>
+"#, updated_label = label(t.last_updated), symbol = escape(&s.symbol), updated = updated.format("%Y-%m-%d %H:%M:%S"));
+
+    let _ = write!(out, r#"<div class="card"><h2>{title}</h2>
+<p><b>{price}</b> <span class="{change_class}">{change:+}%</span></p>{spark}
+<p>{high_label} {high} &middot; {low_label} {low}</p></div>
+"#,
+        title = escape(t.price_24h),
+        high_label = label(t.high_24h),
+        low_label = label(t.low_24h),
+        price = price(s, Some(s.current_price), dp),
+        change = s.price_change_24h_percent.round_dp(2),
+        spark = sparkline(&prices, trend_color(s.price_history.first(), s.price_history.last())),
+        high = price(s, Some(s.high_24h), dp),
+        low = price(s, Some(s.low_24h), dp),
+    );
+    let _ = write!(out, r#"<div class="card"><h2>{title}</h2>
+<p><b>{value}</b></p>{spark}
+<p>{realized_label} {realized} &middot; {unrealized_label} {unrealized}</p></div>
+"#,
+        title = escape(t.equity_24h),
+        realized_label = label(t.realized_pnl),
+        unrealized_label = label(t.unrealized_pnl),
+        value = match s.display.currency {
+            Some(_) => s.display.money(s.total_portfolio_value, 2),
+            None => format!("{} {}", s.display.number(s.total_portfolio_value, 2), escape(&s.quote_asset)),
+        },
+        spark = sparkline(&equity, trend_color(s.equity_history.first(), s.equity_history.last())),
+        realized = s.display.money(s.realized_pnl, 2),
+        unrealized = s.display.money(s.unrealized_pnl, 2),
+    );
+
+    let _ = write!(out, r#"<h2>{title}</h2><table>
+<tr><td>{sl_label}</td><td>{sl}</td><td>{tp_label}</td><td>{tp}</td></tr>
+<tr><td>{buy_label}</td><td>{buy}</td><td>{sell_label}</td><td>{sell}</td></tr>
+</table>
+"#,
+        title = escape(t.targets),
+        sl_label = label(t.stop_loss),
+        tp_label = label(t.take_profit),
+        buy_label = label(t.buy_target),
+        sell_label = label(t.sell_target),
+        sl = price(s, s.stop_loss_price, dp),
+        tp = price(s, s.take_profit_price, dp),
+        buy = price(s, s.buy_target_price, dp),
+        sell = price(s, s.sell_target_price, dp),
+    );
+
+    if !s.positions.is_empty() {
+        let _ = writeln!(out, "<h2>{}</h2><table><tr><th>{}</th><th>{}</th><th>{}</th><th>{}</th></tr>", escape(t.position),
+            escape(t.col_id), escape(t.col_size), escape(t.col_entry), escape(t.col_state));
+        for p in &s.positions {
+            let _ = writeln!(out, "<tr><td>{}</td><td>{}</td><td>{}</td><td>{:?}</td></tr>",
+                escape(&p.id), p.qty.round_dp(8), price(s, p.entry_price, dp), p.state);
+        }
+        let _ = writeln!(out, "</table>");
+    }
+
+    let mut balances: Vec<(&String, &Decimal)> = s.balances.iter().filter(|(_, v)| **v > Decimal::ZERO).collect();
+    balances.sort();
+    let _ = writeln!(out, "<h2>{}</h2><table>", escape(t.balances));
+    for (asset, amount) in balances {
+        let _ = writeln!(out, "<tr><td>{}</td><td>{}</td></tr>", escape(asset), amount.round_dp(8));
+    }
+    let _ = writeln!(out, "</table>");
+
+    let _ = write!(out, r#"<h2>{title}</h2><table>
+<tr><td>{trades_label}</td><td>{trades} ({wins} {won}, {losses} {lost})</td></tr>
+<tr><td>{win_rate_label}</td><td>{win_rate}%</td></tr>
+<tr><td>{fees_label}</td><td>{fees}</td></tr>
+</table>
+"#,
+        title = escape(t.performance),
+        trades_label = label(t.total_trades),
+        won = escape(t.won),
+        lost = escape(t.lost),
+        win_rate_label = label(t.win_rate),
+        fees_label = label(t.fees_paid),
+        trades = s.total_trades,
+        wins = s.winning_trades,
+        losses = s.losing_trades,
+        win_rate = s.win_rate.round_dp(1),
+        fees = s.display.money(s.total_fees, 2),
+    );
+
+    let _ = writeln!(out, "<h2>{}</h2><p>{}</p>", escape(t.last_event), escape(&s.last_event));
+    if !s.active_alerts.is_empty() {
+        let _ = writeln!(out, "<ul>");
+        for alert in s.active_alerts.iter().rev().take(5) {
+            let _ = writeln!(out, "<li>{}</li>", escape(alert));
+        }
+        let _ = writeln!(out, "</ul>");
+    }
+    let _ = writeln!(out, "</body></html>");
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rust_decimal_macros::dec;
+
+    #[test]
+    fn test_html_report_with_sparklines() {
+        assert_eq!(sparkline(&[dec!(1)], "red"), "");
+        let line = sparkline(&[dec!(10), dec!(20), dec!(15)], "red");
+        assert!(line.contains(r#"points="0.0,48.0 120.0,0.0 240.0,24.0""#));
+
+        let mut status = PortfolioStatus::new("BTCUSDT", true);
+        status.last_event = "<script>".to_string();
+        let now = Utc::now();
+        status.price_history = vec![(now, dec!(50000)), (now, dec!(49000))];
+        let html = render(&status);
+        assert!(html.starts_with("<!DOCTYPE html>"));
+        assert!(html.contains(r##"stroke="#ef4444""##));
+        assert!(html.contains("&lt;script&gt;"));
+        assert!(!html.contains("<script>"));
+    }
+}
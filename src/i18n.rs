@@ -0,0 +1,619 @@
+use std::fmt::Display;
+
+/// Language of the report and notifications (REPORT_LANGUAGE, falling back to LANG)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Language {
+    #[default]
+    English,
+    Portuguese,
+}
+
+impl Language {
+    /// Reads the language part of a tag like `pt-BR` or `pt_BR.UTF-8`. Languages without a
+    /// translation fall back to English, so any system LANG is accepted.
+    pub fn parse(tag: &str) -> Self {
+        match tag.split(['-', '_', '.']).next().unwrap_or("").to_lowercase().as_str() {
+            "pt" => Self::Portuguese,
+            _ => Self::English,
+        }
+    }
+
+    pub fn strings(self) -> &'static Strings {
+        match self {
+            Self::English => &ENGLISH,
+            Self::Portuguese => &PORTUGUESE,
+        }
+    }
+}
+
+/// Replace `{name}` placeholders in a translated template
+pub fn fill(template: &str, args: &[(&str, &dyn Display)]) -> String {
+    args.iter().fold(template.to_string(), |text, (name, value)| {
+        text.replace(&format!("{{{}}}", name), &value.to_string())
+    })
+}
+
+/// User-facing text of the report and notifications. Labels end with their colon; the
+/// report pads them to a fixed column.
+pub struct Strings {
+    pub banner_simulation: &'static str,
+    pub banner_live: &'static str,
+    pub title: &'static str,
+    pub last_updated: &'static str,
+    pub bot_started: &'static str,
+    pub uptime: &'static str,
+
+    pub market_data: &'static str,
+    pub current_price: &'static str,
+    pub change_24h: &'static str,
+    pub high_24h: &'static str,
+    pub low_24h: &'static str,
+    pub bid_ask: &'static str,
+    pub spread: &'static str,
+    pub put_call: &'static str,
+    pub max_pain: &'static str,
+    pub expiry: &'static str,
+    pub btc_dominance: &'static str,
+    pub total_market_cap: &'static str,
+
+    pub targets: &'static str,
+    pub stop_loss: &'static str,
+    pub take_profit: &'static str,
+    pub buy_target: &'static str,
+    pub sell_target: &'static str,
+    pub not_set: &'static str,
+    pub source_ai: &'static str,
+    pub source_fallback: &'static str,
+    pub source_manual: &'static str,
+
+    pub support_resistance: &'static str,
+    pub strong_resistance: &'static str,
+    pub resistance: &'static str,
+    pub pivot: &'static str,
+    pub support: &'static str,
+    pub strong_support: &'static str,
+    pub not_calculated: &'static str,
+    pub scored_levels: &'static str,
+    pub score: &'static str,
+    pub touches: &'static str,
+    pub ago: &'static str,
+    pub point_of_control: &'static str,
+    pub value_area: &'static str,
+    pub liquidity_walls: &'static str,
+    pub bid_wall: &'static str,
+    pub ask_wall: &'static str,
+    pub snapshots: &'static str,
+
+    pub ai_advisor: &'static str,
+    pub recommendation: &'static str,
+    pub confidence: &'static str,
+    pub analysis: &'static str,
+    pub no_analysis: &'static str,
+    pub status: &'static str,
+    pub ai_not_connected: &'static str,
+    pub to_enable: &'static str,
+    pub ai_enable_hint: &'static str,
+    pub strong_buy: &'static str,
+    pub buy: &'static str,
+    pub hold: &'static str,
+    pub sell: &'static str,
+    pub strong_sell: &'static str,
+
+    pub limits: &'static str,
+    pub trades_today: &'static str,
+    pub can_trade: &'static str,
+    pub yes: &'static str,
+    pub limit_reached: &'static str,
+    pub next_trading_day: &'static str,
+    pub entries_paused: &'static str,
+    pub loss_streak: &'static str,
+    pub losses_in_a_row: &'static str,
+    pub entry_size: &'static str,
+    pub paused_until_win: &'static str,
+
+    pub position: &'static str,
+    pub long: &'static str,
+    pub short: &'static str,
+    pub flat: &'static str,
+    pub no_position: &'static str,
+    pub entry_price: &'static str,
+    pub position_size: &'static str,
+    pub position_value: &'static str,
+    pub unrealized_pnl: &'static str,
+    pub col_id: &'static str,
+    pub col_state: &'static str,
+    pub col_side: &'static str,
+    pub col_size: &'static str,
+    pub col_entry: &'static str,
+    pub col_unrealized: &'static str,
+    pub col_realized: &'static str,
+    pub col_liquidation: &'static str,
+    pub col_excursion: &'static str,
+    pub mae_mfe: &'static str,
+    pub held_for: &'static str,
+
+    pub futures: &'static str,
+    /// `{leverage}`, `{margin}` and `{mode}`
+    pub futures_leverage: &'static str,
+    pub hedge_mode: &'static str,
+    pub one_way_mode: &'static str,
+    pub mark_price: &'static str,
+    /// `{distance}` percent from the mark price
+    pub liquidation_distance: &'static str,
+    pub net_position: &'static str,
+    pub hedged: &'static str,
+    pub locked_pnl: &'static str,
+    pub break_even: &'static str,
+    pub funding_fees: &'static str,
+
+    pub balances: &'static str,
+    pub no_balances: &'static str,
+    pub borrowed: &'static str,
+    pub margin_level: &'static str,
+    pub total_portfolio: &'static str,
+
+    pub open_orders: &'static str,
+    /// `{age}` of an open order
+    pub order_age: &'static str,
+
+    pub performance: &'static str,
+    pub realized_pnl: &'static str,
+    pub total_trades: &'static str,
+    pub winning_trades: &'static str,
+    pub losing_trades: &'static str,
+    pub win_rate: &'static str,
+    pub largest_win: &'static str,
+    pub largest_loss: &'static str,
+    pub fees_paid: &'static str,
+    pub avg_mae_mfe: &'static str,
+    pub avg_holding_time: &'static str,
+    pub longest: &'static str,
+    pub today: &'static str,
+    pub trades: &'static str,
+    pub realized: &'static str,
+    pub net_deposits: &'static str,
+    pub time_weighted_return: &'static str,
+
+    pub strategies: &'static str,
+    pub sessions: &'static str,
+    pub col_session: &'static str,
+    pub col_trades: &'static str,
+    pub col_hourly_range: &'static str,
+    pub risk: &'static str,
+    pub value_at_risk: &'static str,
+    pub parametric: &'static str,
+    pub historical: &'static str,
+    pub var_needs_history: &'static str,
+    pub var_collecting: &'static str,
+    pub entry_limit: &'static str,
+    pub of_equity: &'static str,
+    pub exposure: &'static str,
+    pub at_risk: &'static str,
+    pub leverage: &'static str,
+    pub scanner: &'static str,
+
+    pub signals: &'static str,
+    pub current_signal: &'static str,
+    pub sma_short: &'static str,
+    pub sma_long: &'static str,
+    pub rsi: &'static str,
+    pub volume: &'static str,
+    pub average: &'static str,
+    pub divergence: &'static str,
+
+    pub last_event: &'static str,
+    pub decision_log: &'static str,
+    pub recent_alerts: &'static str,
+
+    pub price_24h: &'static str,
+    pub equity_24h: &'static str,
+
+    pub daily_summary: &'static str,
+    pub value: &'static str,
+    pub won: &'static str,
+    pub lost: &'static str,
+    pub alerts: &'static str,
+
+    pub approval_requested: &'static str,
+    pub notional: &'static str,
+    pub reason: &'static str,
+    /// `{id}` and `{mins}`
+    pub approval_reply: &'static str,
+    /// `{id}` and `{approver}`
+    pub trade_approved: &'static str,
+    pub trade_rejected: &'static str,
+    /// `{id}`
+    pub trade_expired: &'static str,
+}
+
+pub static ENGLISH: Strings = Strings {
+    banner_simulation: "║           🎮 SIMULATION MODE 🎮           ║",
+    banner_live: "║             💰 LIVE TRADING 💰             ║",
+    title: "CRYPTO TRADING BOT - PORTFOLIO STATUS",
+    last_updated: "Last Updated:",
+    bot_started: "Bot Started:",
+    uptime: "Uptime:",
+
+    market_data: "📊 MARKET DATA",
+    current_price: "Current Price:",
+    change_24h: "24h Change:",
+    high_24h: "24h High:",
+    low_24h: "24h Low:",
+    bid_ask: "Bid / Ask:",
+    spread: "spread",
+    put_call: "Put/Call (OI):",
+    max_pain: "Max Pain:",
+    expiry: "expiry",
+    btc_dominance: "BTC Dominance:",
+    total_market_cap: "Total Market Cap:",
+
+    targets: "🎯 TRADING TARGETS",
+    stop_loss: "Stop-Loss:",
+    take_profit: "Take-Profit:",
+    buy_target: "Buy Target:",
+    sell_target: "Sell Target:",
+    not_set: "Not set",
+    source_ai: "AI",
+    source_fallback: "fallback",
+    source_manual: "manual",
+
+    support_resistance: "📐 SUPPORT & RESISTANCE",
+    strong_resistance: "Strong Resistance:",
+    resistance: "Resistance (R1):",
+    pivot: "Pivot Point:",
+    support: "Support (S1):",
+    strong_support: "Strong Support:",
+    not_calculated: "Not calculated",
+    scored_levels: "Scored levels:",
+    score: "score",
+    touches: "touches",
+    ago: "ago",
+    point_of_control: "Point of Control:",
+    value_area: "Value Area:",
+    liquidity_walls: "Order book walls:",
+    bid_wall: "Bid wall",
+    ask_wall: "Ask wall",
+    snapshots: "snapshots",
+
+    ai_advisor: "🧠 AI ADVISOR",
+    recommendation: "Recommendation:",
+    confidence: "Confidence:",
+    analysis: "Analysis:",
+    no_analysis: "No analysis available",
+    status: "Status:",
+    ai_not_connected: "⚠️  Not connected (using fallback)",
+    to_enable: "To enable:",
+    ai_enable_hint: "Install Ollama and run: ollama pull mistral",
+    strong_buy: "STRONG BUY",
+    buy: "BUY",
+    hold: "HOLD",
+    sell: "SELL",
+    strong_sell: "STRONG SELL",
+
+    limits: "📊 DAILY TRADE LIMITS",
+    trades_today: "Trades Today:",
+    can_trade: "Can Trade:",
+    yes: "Yes",
+    limit_reached: "No (limit reached)",
+    next_trading_day: "Next Trading Day:",
+    entries_paused: "Entries Paused:",
+    loss_streak: "Loss Streak:",
+    losses_in_a_row: "losses in a row",
+    entry_size: "entry size",
+    paused_until_win: "entries paused until a win",
+
+    position: "📈 CURRENT POSITION",
+    long: "LONG",
+    short: "SHORT",
+    flat: "FLAT",
+    no_position: "NO POSITION",
+    entry_price: "Entry Price:",
+    position_size: "Position Size:",
+    position_value: "Position Value:",
+    unrealized_pnl: "Unrealized P&L:",
+    col_id: "Id",
+    col_state: "State",
+    col_side: "Side",
+    col_size: "Size",
+    col_entry: "Entry",
+    col_unrealized: "Unrealized",
+    col_realized: "Realized",
+    col_liquidation: "Liquidation",
+    col_excursion: "MAE/MFE",
+    mae_mfe: "MAE / MFE:",
+    held_for: "Held For:",
+
+    futures: "📜 FUTURES",
+    futures_leverage: "{leverage}x ({margin} margin, {mode} mode)",
+    hedge_mode: "hedge",
+    one_way_mode: "one-way",
+    mark_price: "Mark Price:",
+    liquidation_distance: "{distance}% away",
+    net_position: "Net Position:",
+    hedged: "hedged",
+    locked_pnl: "Locked P&L:",
+    break_even: "Break-Even:",
+    funding_fees: "Funding Fees:",
+
+    balances: "💰 BALANCES",
+    no_balances: "No balances",
+    borrowed: "borrowed",
+    margin_level: "Margin Level:",
+    total_portfolio: "Total Portfolio:",
+
+    open_orders: "📋 OPEN ORDERS",
+    order_age: "{age} old",
+
+    performance: "📉 PERFORMANCE STATISTICS",
+    realized_pnl: "Realized P&L:",
+    total_trades: "Total Trades:",
+    winning_trades: "Winning Trades:",
+    losing_trades: "Losing Trades:",
+    win_rate: "Win Rate:",
+    largest_win: "Largest Win:",
+    largest_loss: "Largest Loss:",
+    fees_paid: "Fees Paid:",
+    avg_mae_mfe: "Avg MAE / MFE:",
+    avg_holding_time: "Avg Holding Time:",
+    longest: "longest",
+    today: "Today:",
+    trades: "trades",
+    realized: "realized",
+    net_deposits: "Net Deposits:",
+    time_weighted_return: "Time-Weighted Ret:",
+
+    strategies: "🧩 STRATEGY ALLOCATION",
+    sessions: "🌍 SESSIONS (UTC)",
+    col_session: "Session",
+    col_trades: "Trades",
+    col_hourly_range: "Avg 1h range",
+    risk: "⚖️ RISK",
+    value_at_risk: "1-Day VaR",
+    parametric: "parametric",
+    historical: "historical",
+    var_needs_history: "historical needs 30 days of equity history",
+    var_collecting: "collecting equity history (48h needed)",
+    entry_limit: "Entry Limit:",
+    of_equity: "of equity",
+    exposure: "🎯 EXPOSURE",
+    at_risk: "At Risk to Stop:",
+    leverage: "Leverage:",
+    scanner: "🔭 SCANNER",
+
+    signals: "🤖 STRATEGY SIGNALS",
+    current_signal: "Current Signal:",
+    sma_short: "SMA Short:",
+    sma_long: "SMA Long:",
+    rsi: "RSI (14):",
+    volume: "Volume (1h):",
+    average: "avg",
+    divergence: "Divergence:",
+
+    last_event: "🔔 LAST EVENT",
+    decision_log: "🧭 DECISION LOG",
+    recent_alerts: "⚠️  RECENT ALERTS",
+
+    price_24h: "Price (24h)",
+    equity_24h: "Equity (24h)",
+
+    daily_summary: "daily summary",
+    value: "Value:",
+    won: "won",
+    lost: "lost",
+    alerts: "Alerts:",
+
+    approval_requested: "Trade approval requested",
+    notional: "Notional:",
+    reason: "Reason:",
+    approval_reply: "Reply /confirm {id} or /reject {id} within {mins} min",
+    trade_approved: "Trade [{id}] approved by {approver}",
+    trade_rejected: "Trade [{id}] rejected by {approver}",
+    trade_expired: "Trade [{id}] expired without confirmation",
+};
+
+pub static PORTUGUESE: Strings = Strings {
+    banner_simulation: "║           🎮 MODO SIMULAÇÃO 🎮            ║",
+    banner_live: "║            💰 OPERAÇÃO REAL 💰             ║",
+    title: "ROBÔ DE TRADING CRIPTO - CARTEIRA",
+    last_updated: "Atualizado em:",
+    bot_started: "Iniciado em:",
+    uptime: "Tempo ativo:",
+
+    market_data: "📊 DADOS DE MERCADO",
+    current_price: "Preço Atual:",
+    change_24h: "Variação 24h:",
+    high_24h: "Máxima 24h:",
+    low_24h: "Mínima 24h:",
+    bid_ask: "Compra / Venda:",
+    spread: "spread",
+    put_call: "Put/Call (OI):",
+    max_pain: "Max Pain:",
+    expiry: "vencimento",
+    btc_dominance: "Dominância BTC:",
+    total_market_cap: "Cap. de Mercado:",
+
+    targets: "🎯 ALVOS DE OPERAÇÃO",
+    stop_loss: "Stop-Loss:",
+    take_profit: "Take-Profit:",
+    buy_target: "Alvo de Compra:",
+    sell_target: "Alvo de Venda:",
+    not_set: "Não definido",
+    source_ai: "IA",
+    source_fallback: "reserva",
+    source_manual: "manual",
+
+    support_resistance: "📐 SUPORTE E RESISTÊNCIA",
+    strong_resistance: "Resistência Forte:",
+    resistance: "Resistência (R1):",
+    pivot: "Ponto Pivô:",
+    support: "Suporte (S1):",
+    strong_support: "Suporte Forte:",
+    not_calculated: "Não calculado",
+    scored_levels: "Níveis pontuados:",
+    score: "pontuação",
+    touches: "toques",
+    ago: "atrás",
+    point_of_control: "Ponto de Controle:",
+    value_area: "Área de Valor:",
+    liquidity_walls: "Paredes no livro de ofertas:",
+    bid_wall: "Compra",
+    ask_wall: "Venda",
+    snapshots: "capturas",
+
+    ai_advisor: "🧠 CONSULTOR DE IA",
+    recommendation: "Recomendação:",
+    confidence: "Confiança:",
+    analysis: "Análise:",
+    no_analysis: "Nenhuma análise disponível",
+    status: "Situação:",
+    ai_not_connected: "⚠️  Não conectado (usando alternativa)",
+    to_enable: "Para ativar:",
+    ai_enable_hint: "Instale o Ollama e execute: ollama pull mistral",
+    strong_buy: "COMPRA FORTE",
+    buy: "COMPRA",
+    hold: "MANTER",
+    sell: "VENDA",
+    strong_sell: "VENDA FORTE",
+
+    limits: "📊 LIMITES DIÁRIOS DE TRADES",
+    trades_today: "Trades Hoje:",
+    can_trade: "Pode Operar:",
+    yes: "Sim",
+    limit_reached: "Não (limite atingido)",
+    next_trading_day: "Próximo Dia Útil:",
+    entries_paused: "Entradas Pausadas:",
+    loss_streak: "Sequência de Perdas:",
+    losses_in_a_row: "perdas seguidas",
+    entry_size: "tamanho da entrada",
+    paused_until_win: "entradas pausadas até um ganho",
+
+    position: "📈 POSIÇÃO ATUAL",
+    long: "COMPRADO",
+    short: "VENDIDO",
+    flat: "ZERADO",
+    no_position: "SEM POSIÇÃO",
+    entry_price: "Preço de Entrada:",
+    position_size: "Tamanho:",
+    position_value: "Valor da Posição:",
+    unrealized_pnl: "P&L Não Realizado:",
+    col_id: "Id",
+    col_state: "Estado",
+    col_side: "Lado",
+    col_size: "Tamanho",
+    col_entry: "Entrada",
+    col_unrealized: "Não Real.",
+    col_realized: "Realizado",
+    col_liquidation: "Liquidação",
+    col_excursion: "MAE/MFE",
+    mae_mfe: "MAE / MFE:",
+    held_for: "Mantida Há:",
+
+    futures: "📜 FUTUROS",
+    futures_leverage: "{leverage}x (margem {margin}, modo {mode})",
+    hedge_mode: "hedge",
+    one_way_mode: "unidirecional",
+    mark_price: "Preço de Marcação:",
+    liquidation_distance: "a {distance}%",
+    net_position: "Posição Líquida:",
+    hedged: "protegido",
+    locked_pnl: "P&L Travado:",
+    break_even: "Equilíbrio:",
+    funding_fees: "Taxas de Funding:",
+
+    balances: "💰 SALDOS",
+    no_balances: "Sem saldos",
+    borrowed: "emprestado",
+    margin_level: "Nível de Margem:",
+    total_portfolio: "Carteira Total:",
+
+    open_orders: "📋 ORDENS ABERTAS",
+    order_age: "há {age}",
+
+    performance: "📉 ESTATÍSTICAS DE DESEMPENHO",
+    realized_pnl: "P&L Realizado:",
+    total_trades: "Total de Trades:",
+    winning_trades: "Trades Vencedores:",
+    losing_trades: "Trades Perdedores:",
+    win_rate: "Taxa de Acerto:",
+    largest_win: "Maior Ganho:",
+    largest_loss: "Maior Perda:",
+    fees_paid: "Taxas Pagas:",
+    avg_mae_mfe: "MAE / MFE Médio:",
+    avg_holding_time: "Duração Média:",
+    longest: "mais longa",
+    today: "Hoje:",
+    trades: "trades",
+    realized: "realizado",
+    net_deposits: "Aportes Líquidos:",
+    time_weighted_return: "Retorno (TWR):",
+
+    strategies: "🧩 ALOCAÇÃO POR ESTRATÉGIA",
+    sessions: "🌍 SESSÕES (UTC)",
+    col_session: "Sessão",
+    col_trades: "Trades",
+    col_hourly_range: "Faixa 1h média",
+    risk: "⚖️ RISCO",
+    value_at_risk: "VaR 1 Dia",
+    parametric: "paramétrico",
+    historical: "histórico",
+    var_needs_history: "histórico requer 30 dias de patrimônio",
+    var_collecting: "coletando histórico de patrimônio (48h necessárias)",
+    entry_limit: "Limite de Entrada:",
+    of_equity: "do patrimônio",
+    exposure: "🎯 EXPOSIÇÃO",
+    at_risk: "Em Risco no Stop:",
+    leverage: "Alavancagem:",
+    scanner: "🔭 SCANNER",
+
+    signals: "🤖 SINAIS DA ESTRATÉGIA",
+    current_signal: "Sinal Atual:",
+    sma_short: "SMA Curta:",
+    sma_long: "SMA Longa:",
+    rsi: "RSI (14):",
+    volume: "Volume (1h):",
+    average: "média",
+    divergence: "Divergência:",
+
+    last_event: "🔔 ÚLTIMO EVENTO",
+    decision_log: "🧭 REGISTRO DE DECISÕES",
+    recent_alerts: "⚠️  ALERTAS RECENTES",
+
+    price_24h: "Preço (24h)",
+    equity_24h: "Patrimônio (24h)",
+
+    daily_summary: "resumo diário",
+    value: "Valor:",
+    won: "ganhos",
+    lost: "perdidos",
+    alerts: "Alertas:",
+
+    approval_requested: "Aprovação de trade solicitada",
+    notional: "Nocional:",
+    reason: "Motivo:",
+    approval_reply: "Responda /confirm {id} ou /reject {id} em até {mins} min",
+    trade_approved: "Trade [{id}] aprovado por {approver}",
+    trade_rejected: "Trade [{id}] rejeitado por {approver}",
+    trade_expired: "Trade [{id}] expirou sem confirmação",
+};
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_language_selection_and_templates() {
+        assert_eq!(Language::parse("pt_BR.UTF-8"), Language::Portuguese);
+        assert_eq!(Language::parse("en-US"), Language::English);
+        assert_eq!(Language::parse("de_DE.UTF-8"), Language::English);
+        assert_eq!(Language::parse(""), Language::English);
+
+        let text = fill(Language::Portuguese.strings().trade_approved, &[("id", &"1a"), ("approver", &"ana")]);
+        assert_eq!(text, "Trade [1a] aprovado por ana");
+        // Labels fit the report's 19-column label field
+        for strings in [&ENGLISH, &PORTUGUESE] {
+            for label in [strings.strong_resistance, strings.unrealized_pnl, strings.entry_limit, strings.mark_price, strings.avg_mae_mfe, strings.avg_holding_time] {
+                assert!(label.chars().count() < 19, "{}", label);
+            }
+        }
+    }
+}
@@ -0,0 +1,255 @@
+use crate::ai_advisor::AiTradingTargets;
+use crate::config::Config;
+use crate::error::BotError;
+use crate::exchange::Exchange;
+use crate::liquidity::Wall;
+use crate::models::Kline;
+use anyhow::Result;
+use rust_decimal::Decimal;
+use rust_decimal::prelude::{FromPrimitive, ToPrimitive};
+use rust_decimal_macros::dec;
+use serde::Serialize;
+use tracing::{debug, warn};
+
+/// A week of hourly candles
+const LOOKBACK_HOURS: u32 = 168;
+/// A swing high (low) tops (bottoms) this many candles on each side
+const SWING_STRENGTH: usize = 2;
+/// Swings within this many percent of a level's price touch it
+const CLUSTER_PERCENT: Decimal = dec!(0.5);
+/// A touch on heavy volume counts at most this many times its candle's average
+const MAX_VOLUME_WEIGHT: f64 = 3.0;
+/// Levels kept per refresh, best first
+const MAX_LEVELS: usize = 10;
+
+/// A price the market turned at, scored by how often, how recently and on how much volume
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Level {
+    /// Volume-weighted price of the touches
+    pub price: Decimal,
+    pub touches: u32,
+    /// Open time of the latest touching candle
+    pub last_touch: i64,
+    /// Volume traded on the touching candles
+    pub volume: Decimal,
+    /// Each touch scores 1 plus its volume against the average candle's (up to 3), halved for
+    /// every SR_LEVELS_HALF_LIFE_HOURS since it happened
+    pub score: Decimal,
+    /// Resting quantity of an order book wall at the level (LIQUIDITY_WALLS_ENABLED)
+    pub wall: Option<Decimal>,
+}
+
+/// Swing highs and lows of `klines` (oldest first) clustered into levels, best score first
+pub fn score_levels(klines: &[Kline], now: i64, half_life_hours: Decimal) -> Vec<Level> {
+    if klines.is_empty() || half_life_hours <= Decimal::ZERO {
+        return Vec::new();
+    }
+    let average_volume = klines.iter().map(|k| k.volume).sum::<Decimal>() / Decimal::from(klines.len());
+    let half_life = half_life_hours.to_f64().unwrap_or(24.0);
+    let swing = |i: usize, high: bool| (i - SWING_STRENGTH..=i + SWING_STRENGTH).filter(|&j| j != i)
+        .all(|j| if high { klines[i].high > klines[j].high } else { klines[i].low < klines[j].low });
+
+    // Touch price, weight and candle, lowest price first
+    let mut touches: Vec<(Decimal, f64, &Kline)> = Vec::new();
+    let confirmed = klines.len().saturating_sub(SWING_STRENGTH);
+    for (i, k) in klines.iter().enumerate().take(confirmed).skip(SWING_STRENGTH) {
+        let age_hours = (now - k.open_time).max(0) as f64 / 3_600_000.0;
+        let volume_weight = if average_volume > Decimal::ZERO {
+            (k.volume / average_volume).to_f64().unwrap_or(1.0).min(MAX_VOLUME_WEIGHT)
+        } else {
+            1.0
+        };
+        let weight = (1.0 + volume_weight) * 0.5_f64.powf(age_hours / half_life);
+        for (high, price) in [(true, k.high), (false, k.low)] {
+            if swing(i, high) {
+                touches.push((price, weight, k));
+            }
+        }
+    }
+    touches.sort_by_key(|(price, _, _)| *price);
+
+    let mut levels: Vec<(Level, Decimal)> = Vec::new();
+    for (price, weight, k) in touches {
+        match levels.last_mut() {
+            Some((level, score)) if (price - level.price) <= level.price * CLUSTER_PERCENT / dec!(100) => {
+                // Running volume-weighted price; one is added so quiet candles still count
+                let (total, added) = (level.volume + Decimal::ONE, k.volume + Decimal::ONE);
+                level.price = (level.price * total + price * added) / (total + added);
+                level.touches += 1;
+                level.last_touch = level.last_touch.max(k.open_time);
+                level.volume += k.volume;
+                *score += Decimal::from_f64(weight).unwrap_or_default();
+            }
+            _ => levels.push((Level { price, touches: 1, last_touch: k.open_time, volume: k.volume, score: Decimal::ZERO, wall: None },
+                              Decimal::from_f64(weight).unwrap_or_default())),
+        }
+    }
+    let mut levels: Vec<Level> = levels.into_iter()
+        .map(|(level, score)| Level { score: score.round_dp(2), ..level })
+        .filter(|level| level.score > Decimal::ZERO)
+        .collect();
+    levels.sort_by(|a, b| b.score.cmp(&a.score).then(b.last_touch.cmp(&a.last_touch)));
+    levels.truncate(MAX_LEVELS);
+    levels
+}
+
+/// Scored support/resistance levels from the traded symbol's hourly candles (SR_LEVELS_ENABLED),
+/// which replace the calculated support, resistance and buy/sell targets with the best-scoring
+/// levels near the price
+pub struct LevelBook {
+    half_life_hours: Decimal,
+    range_percent: Decimal,
+    levels: Vec<Level>,
+}
+
+impl LevelBook {
+    /// `None` unless SR_LEVELS_ENABLED is set
+    pub fn from_config(config: &Config) -> Result<Option<Self>> {
+        if !config.sr_levels_enabled {
+            return Ok(None);
+        }
+        if config.sr_levels_half_life_hours <= Decimal::ZERO {
+            return Err(BotError::config("SR_LEVELS_HALF_LIFE_HOURS must be positive").into());
+        }
+        if config.sr_levels_range_percent <= Decimal::ZERO || config.sr_levels_range_percent >= dec!(100) {
+            return Err(BotError::config(format!("SR_LEVELS_RANGE_PERCENT must be between 0 and 100, got {}",
+                config.sr_levels_range_percent)).into());
+        }
+        Ok(Some(Self {
+            half_life_hours: config.sr_levels_half_life_hours,
+            range_percent: config.sr_levels_range_percent,
+            levels: Vec::new(),
+        }))
+    }
+
+    /// Rescore from `exchange`'s hourly candles; the previous levels stay on failure
+    pub async fn refresh(&mut self, exchange: &impl Exchange, symbol: &str) {
+        match exchange.get_klines(symbol, "1h", LOOKBACK_HOURS).await {
+            Ok(klines) => {
+                let now = klines.last().map_or(0, |k| k.close_time + 1);
+                self.levels = score_levels(&klines, now, self.half_life_hours);
+                debug!("📏 Scored levels: {:?}", self.levels);
+            }
+            Err(e) => warn!("⚠️ Level scoring failed, keeping the previous levels: {}", e),
+        }
+    }
+
+    pub fn levels(&self) -> &[Level] {
+        &self.levels
+    }
+
+    /// Add order book walls seen at `now`: a wall scores like a fresh heavy-volume touch,
+    /// scaled by how persistently it stayed on the book, on the level it falls in or on a
+    /// level of its own
+    pub fn merge_walls(&mut self, walls: &[Wall], now: i64) {
+        for wall in walls {
+            let score = ((Decimal::ONE + Decimal::from_f64(MAX_VOLUME_WEIGHT).unwrap_or_default()) * wall.persistence()).round_dp(2);
+            let near = |l: &&mut Level| (l.price - wall.price).abs() <= l.price * CLUSTER_PERCENT / dec!(100);
+            match self.levels.iter_mut().find(near) {
+                Some(level) => {
+                    level.score += score;
+                    level.last_touch = level.last_touch.max(now);
+                    level.wall = Some(level.wall.unwrap_or_default() + wall.quantity);
+                }
+                None => self.levels.push(Level { price: wall.price, touches: 0, last_touch: now,
+                                                 volume: Decimal::ZERO, score, wall: Some(wall.quantity) }),
+            }
+        }
+        self.levels.sort_by(|a, b| b.score.cmp(&a.score).then(b.last_touch.cmp(&a.last_touch)));
+        self.levels.truncate(MAX_LEVELS);
+    }
+
+    /// The best and second-best scoring levels on one side of `price`, within the range
+    fn nearby(&self, price: Decimal, below: bool) -> (Option<Decimal>, Option<Decimal>) {
+        let range = price * self.range_percent / dec!(100);
+        let mut side = self.levels.iter()
+            .filter(|l| if below { l.price < price && l.price >= price - range } else { l.price > price && l.price <= price + range });
+        (side.next().map(|l| l.price), side.next().map(|l| l.price))
+    }
+
+    /// Set the support, resistance and buy/sell targets from the best-scoring levels near
+    /// `price`; the stronger level of each side is the farther of its two best. Sides
+    /// without a level nearby keep their calculated values.
+    pub fn apply(&self, targets: &mut AiTradingTargets, price: Decimal) {
+        if let (Some(best), second) = self.nearby(price, true) {
+            targets.support = Some(second.map_or(best, |s| s.max(best)));
+            targets.strong_support = second.map(|s| s.min(best)).or(targets.strong_support);
+            targets.buy_target_price = Some(best);
+        }
+        if let (Some(best), second) = self.nearby(price, false) {
+            targets.resistance = Some(second.map_or(best, |s| s.min(best)));
+            targets.strong_resistance = second.map(|s| s.max(best)).or(targets.strong_resistance);
+            targets.sell_target_price = Some(best);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn candle(hour: i64, low: Decimal, high: Decimal, volume: Decimal) -> Kline {
+        Kline { open_time: hour * 3_600_000, open: low, high, low, close: high, volume, close_time: hour * 3_600_000 + 3_599_999 }
+    }
+
+    #[test]
+    fn test_levels_are_scored_and_chosen_as_targets() {
+        // Bounces off ~100 three times and turns at ~110 twice, the last time long ago
+        let path = [104, 102, 100, 102, 104, 106, 108, 110, 108, 106, 104, 102, 100, 102, 104,
+                    106, 108, 110, 108, 106, 104, 102, 100, 102, 104, 105];
+        let klines: Vec<Kline> = path.iter().enumerate()
+            .map(|(i, p)| {
+                let p = Decimal::from(*p);
+                candle(i as i64, p - dec!(0.2), p + dec!(0.2), if i == 22 { dec!(30) } else { dec!(10) })
+            })
+            .collect();
+        let now = 26 * 3_600_000;
+        let levels = score_levels(&klines, now, dec!(24));
+        assert_eq!(levels.len(), 2);
+        let (support, resistance) = (&levels[0], &levels[1]);
+        assert_eq!((support.touches, support.last_touch), (3, 22 * 3_600_000));
+        assert_eq!(support.price, dec!(99.8));
+        assert_eq!((resistance.touches, resistance.price), (2, dec!(110.2)));
+        assert!(support.score > resistance.score);
+
+        // Decayed over a week, the levels score far less
+        let later = score_levels(&klines, now + 7 * 24 * 3_600_000, dec!(24));
+        assert!(later[0].score < support.score / dec!(100));
+
+        let mut config = Config::defaults();
+        config.sr_levels_enabled = true;
+        config.sr_levels_half_life_hours = dec!(24);
+        config.sr_levels_range_percent = dec!(5);
+        let mut book = LevelBook::from_config(&config).unwrap().unwrap();
+        book.levels = levels.clone();
+        let mut targets = AiTradingTargets {
+            stop_loss_price: dec!(95), take_profit_price: dec!(115), buy_target_price: Some(dec!(101)),
+            sell_target_price: Some(dec!(109)), confidence: dec!(60), reasoning: String::new(),
+            recommendation: crate::ai_advisor::TradingRecommendation::Hold, support: Some(dec!(101)),
+            strong_support: Some(dec!(98)), resistance: Some(dec!(109)), strong_resistance: Some(dec!(112)),
+            pivot_point: Some(dec!(105)), allocation_percent: None, position_size_percent: None,
+        };
+        book.apply(&mut targets, dec!(105));
+        assert_eq!((targets.buy_target_price, targets.support, targets.strong_support),
+                   (Some(dec!(99.8)), Some(dec!(99.8)), Some(dec!(98))));
+        assert_eq!((targets.sell_target_price, targets.resistance, targets.strong_resistance),
+                   (Some(dec!(110.2)), Some(dec!(110.2)), Some(dec!(112))));
+        // Nothing within 5% of 120: the calculated targets stand
+        let levels_of = |t: &AiTradingTargets| (t.buy_target_price, t.sell_target_price, t.support, t.resistance);
+        let before = levels_of(&targets);
+        book.apply(&mut targets, dec!(120));
+        assert_eq!(levels_of(&targets), before);
+
+        // A persistent bid wall at 100 strengthens the support level, one at 107 makes a new level
+        let wall = |price, seen| Wall { side: crate::models::OrderSide::Buy, price, quantity: dec!(50), seen, snapshots: 4 };
+        book.merge_walls(&[wall(dec!(100), 4), wall(dec!(107), 2)], now);
+        let levels = book.levels();
+        assert_eq!((levels[0].price, levels[0].score, levels[0].wall), (dec!(99.8), support.score + dec!(4), Some(dec!(50))));
+        let at = |price| levels.iter().find(|l| l.price == price).unwrap();
+        assert_eq!((at(dec!(107)).score, at(dec!(107)).touches), (dec!(2), 0));
+        assert_eq!(at(dec!(110.2)).wall, None);
+
+        config.sr_levels_range_percent = dec!(0);
+        assert!(LevelBook::from_config(&config).is_err());
+    }
+}
@@ -0,0 +1,114 @@
+//! Trading engine behind the `crypto_trading_bot` binary.
+//!
+//! The binary only parses arguments and config and hands over to [`engine::run`];
+//! other crates can run the same loops or drive the pieces directly. Anything
+//! implementing [`Exchange`] (the Binance [`ExchangeClient`] or the in-memory
+//! [`SimulationExchange`]) can feed the strategies, the AI advisor and the
+//! [`PortfolioReporter`].
+//!
+//! ```no_run
+//! use crypto_trading_bot::{Config, Exchange, SimulationExchange};
+//! use crypto_trading_bot::strategy::SmaCrossover;
+//!
+//! # async fn run() -> anyhow::Result<()> {
+//! let config = Config::from_env()?;
+//! let exchange = SimulationExchange::new(&config).await?;
+//! let klines = exchange.get_klines("BTCUSDT", "1h", 50).await?;
+//! let signal = SmaCrossover::new(10, 20).generate_signal(&klines);
+//! println!("BTCUSDT: {:?}", signal);
+//! # Ok(())
+//! # }
+//! ```
+
+pub mod accounting;
+pub mod allocation;
+pub mod ai_advisor;
+pub mod alerts;
+pub mod analysis_api;
+pub mod ai_pool;
+pub mod audit;
+pub mod backtest;
+pub mod btc_trend;
+pub mod candles;
+pub mod capital;
+pub mod chaos;
+pub mod coingecko;
+pub mod competition;
+pub mod config;
+pub mod confirmation;
+pub mod control;
+pub mod cycle_gap;
+pub mod dex;
+pub mod divergence;
+pub mod doctor;
+pub mod drill;
+pub mod drift;
+pub mod dust;
+pub mod engine;
+pub mod entry_quality;
+pub mod error;
+pub mod events;
+pub mod exchange;
+pub mod futures;
+pub mod grpc;
+pub mod html_report;
+pub mod levels;
+pub mod liquidity;
+pub mod i18n;
+pub mod locale;
+pub mod maintenance;
+pub mod market_data;
+pub mod metrics;
+pub mod mock_exchange;
+pub mod models;
+pub mod mqtt;
+pub mod notifier;
+pub mod optimizer;
+pub mod options;
+pub mod orders;
+pub mod paths;
+pub mod portfolio;
+pub mod position;
+pub mod post_trade;
+pub mod quote;
+#[cfg(feature = "python")]
+pub mod python;
+pub mod rate_limit;
+pub mod resample;
+pub mod retention;
+pub mod review;
+pub mod risk;
+pub mod risk_profile;
+pub mod rollover;
+pub mod runtime_state;
+pub mod scanner;
+pub mod secrets;
+pub mod self_test;
+pub mod sessions;
+pub mod simulation;
+pub mod sizing;
+pub mod snapshot;
+pub mod state_crypto;
+pub mod state_store;
+pub mod state_version;
+pub mod stop_watch;
+pub mod strategy;
+pub mod symbols;
+pub mod targets;
+pub mod trade_limiter;
+pub mod tradingview;
+pub mod user_stream;
+pub mod volume;
+pub mod volume_profile;
+pub mod watchdog;
+pub mod whales;
+
+pub use ai_advisor::{AiTradingTargets, FallbackTargetCalculator, GenerationOptions, MarketContext, OllamaClient, TradingRecommendation};
+pub use config::Config;
+pub use error::{BotError, ErrorKind};
+pub use exchange::{Exchange, ExchangeClient};
+pub use mock_exchange::{MockExchange, OrderFault};
+pub use models::{Balance, Kline, Order, OrderSide, OrderType, Signal};
+pub use portfolio::{PortfolioReporter, PortfolioStatus};
+pub use simulation::SimulationExchange;
+pub use strategy::{RsiStrategy, SmaCrossover};
@@ -0,0 +1,194 @@
+use crate::config::Config;
+use crate::error::BotError;
+use crate::exchange::ExchangeClient;
+use crate::models::{OrderBookDepth, OrderSide};
+use anyhow::Result;
+use rust_decimal::Decimal;
+use rust_decimal_macros::dec;
+use serde::Serialize;
+use std::collections::VecDeque;
+use std::time::{Duration, Instant};
+use tracing::{debug, warn};
+
+/// Bid and ask levels read per snapshot (weight 5 at Binance up to 100, 25 up to 500)
+const DEPTH_LIMIT: u32 = 500;
+/// Snapshots the heatmap is built from
+const HISTORY: usize = 30;
+/// Price levels within this many percent of each other are one bucket
+const BUCKET_PERCENT: Decimal = dec!(0.1);
+
+/// A large resting order cluster that stayed on the book
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Wall {
+    /// Buy for a bid wall (support), sell for an ask wall (resistance)
+    pub side: OrderSide,
+    /// Quantity-weighted price of the bucket
+    pub price: Decimal,
+    /// Average resting quantity over the snapshots it was seen in
+    pub quantity: Decimal,
+    pub seen: usize,
+    pub snapshots: usize,
+}
+
+impl Wall {
+    /// Share of the snapshots the wall was seen in, 0-1
+    pub fn persistence(&self) -> Decimal {
+        if self.snapshots == 0 {
+            return Decimal::ZERO;
+        }
+        Decimal::from(self.seen) / Decimal::from(self.snapshots)
+    }
+}
+
+/// One side of the book grouped into buckets; those holding `multiple` times the side's
+/// average bucket or more are walls, as (price, quantity)
+pub fn find_walls(levels: &[(Decimal, Decimal)], multiple: Decimal) -> Vec<(Decimal, Decimal)> {
+    let mut buckets: Vec<(Decimal, Decimal)> = Vec::new();
+    let mut sorted = levels.to_vec();
+    sorted.sort_by_key(|(price, _)| *price);
+    for (price, qty) in sorted.into_iter().filter(|(_, qty)| *qty > Decimal::ZERO) {
+        match buckets.last_mut() {
+            Some((bucket, total)) if price - *bucket <= *bucket * BUCKET_PERCENT / dec!(100) => {
+                *bucket = (*bucket * *total + price * qty) / (*total + qty);
+                *total += qty;
+            }
+            _ => buckets.push((price, qty)),
+        }
+    }
+    if buckets.is_empty() {
+        return Vec::new();
+    }
+    let average = buckets.iter().map(|(_, qty)| *qty).sum::<Decimal>() / Decimal::from(buckets.len());
+    buckets.into_iter().filter(|(_, qty)| *qty >= average * multiple).collect()
+}
+
+/// Order book snapshots of the traded symbol in live mode (LIQUIDITY_WALLS_ENABLED). Walls
+/// seen in at least half of the recent snapshots count; ones that come and go are ignored.
+pub struct LiquidityMap {
+    multiple: Decimal,
+    interval: Duration,
+    last_snapshot: Option<Instant>,
+    /// Walls of each recent snapshot, oldest first
+    history: VecDeque<Vec<(OrderSide, Decimal, Decimal)>>,
+    walls: Vec<Wall>,
+}
+
+impl LiquidityMap {
+    /// `None` unless LIQUIDITY_WALLS_ENABLED is set
+    pub fn from_config(config: &Config) -> Result<Option<Self>> {
+        if !config.liquidity_walls_enabled {
+            return Ok(None);
+        }
+        if config.liquidity_wall_multiple <= Decimal::ONE {
+            return Err(BotError::config(format!("LIQUIDITY_WALL_MULTIPLE must be above 1, got {}",
+                config.liquidity_wall_multiple)).into());
+        }
+        if config.liquidity_snapshot_secs < 10 {
+            return Err(BotError::config("LIQUIDITY_SNAPSHOT_SECS must be at least 10").into());
+        }
+        Ok(Some(Self {
+            multiple: config.liquidity_wall_multiple,
+            interval: Duration::from_secs(config.liquidity_snapshot_secs),
+            last_snapshot: None,
+            history: VecDeque::new(),
+            walls: Vec::new(),
+        }))
+    }
+
+    /// Snapshot the order book when one is due; the walls stay as they were on failure
+    pub async fn poll(&mut self, exchange: &ExchangeClient, symbol: &str) {
+        if self.last_snapshot.is_some_and(|t| t.elapsed() < self.interval) {
+            return;
+        }
+        self.last_snapshot = Some(Instant::now());
+        match exchange.get_depth(symbol, DEPTH_LIMIT).await {
+            Ok(book) => {
+                self.record(&book);
+                debug!("🧱 Liquidity walls: {:?}", self.walls);
+            }
+            Err(e) => warn!("⚠️ Order book snapshot failed: {}", e),
+        }
+    }
+
+    fn record(&mut self, book: &OrderBookDepth) {
+        let snapshot = [(OrderSide::Buy, &book.bids), (OrderSide::Sell, &book.asks)].into_iter()
+            .flat_map(|(side, levels)| find_walls(levels, self.multiple).into_iter().map(move |(p, q)| (side, p, q)))
+            .collect();
+        self.history.push_back(snapshot);
+        if self.history.len() > HISTORY {
+            self.history.pop_front();
+        }
+        self.walls = self.heatmap();
+    }
+
+    /// Walls of all snapshots clustered by side and price, kept when seen in at least half
+    fn heatmap(&self) -> Vec<Wall> {
+        let snapshots = self.history.len();
+        let mut seen: Vec<(OrderSide, Decimal, Decimal)> = self.history.iter().flatten().copied().collect();
+        seen.sort_by_key(|(side, price, _)| (*side == OrderSide::Sell, *price));
+
+        // Per cluster: the wall so far and the quantity seen in it over all snapshots
+        let mut clusters: Vec<(Wall, Decimal)> = Vec::new();
+        for (side, price, qty) in seen {
+            match clusters.last_mut() {
+                Some((wall, total)) if wall.side == side && price - wall.price <= wall.price * BUCKET_PERCENT / dec!(100) => {
+                    wall.price = (wall.price * *total + price * qty) / (*total + qty);
+                    *total += qty;
+                    wall.seen += 1;
+                }
+                _ => clusters.push((Wall { side, price, quantity: Decimal::ZERO, seen: 1, snapshots }, qty)),
+            }
+        }
+        let mut walls: Vec<Wall> = clusters.into_iter()
+            .map(|(wall, total)| Wall {
+                price: wall.price.round_dp(8),
+                quantity: (total / Decimal::from(wall.seen)).round_dp(8),
+                seen: wall.seen.min(snapshots),
+                ..wall
+            })
+            .filter(|wall| wall.seen * 2 >= snapshots)
+            .collect();
+        walls.sort_by_key(|wall| std::cmp::Reverse(wall.quantity));
+        walls
+    }
+
+    /// Persistent walls, largest first
+    pub fn walls(&self) -> &[Wall] {
+        &self.walls
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn map() -> LiquidityMap {
+        LiquidityMap { multiple: dec!(5), interval: Duration::from_secs(60), last_snapshot: None,
+                       history: VecDeque::new(), walls: Vec::new() }
+    }
+
+    #[test]
+    fn test_persistent_walls_are_kept() {
+        let thin = |from: i64, step: i64| (0..20).map(|i| (Decimal::from(from + i * step), dec!(1))).collect::<Vec<_>>();
+        // 100 sits at 99.95 and 100.00 (one bucket), 30 in all; the asks get a wall only once
+        let mut bids = thin(90, -1);
+        bids.extend([(dec!(100), dec!(20)), (dec!(99.95), dec!(10))]);
+        let book = OrderBookDepth { bids: bids.clone(), asks: thin(101, 1) };
+        assert_eq!(find_walls(&book.bids, dec!(5)).len(), 1);
+
+        let mut map = map();
+        map.record(&book);
+        map.record(&book);
+        let mut asks = thin(101, 1);
+        asks.push((dec!(105.5), dec!(40)));
+        map.record(&OrderBookDepth { bids, asks });
+        map.record(&book);
+
+        let walls = map.walls();
+        assert_eq!(walls.len(), 1);
+        let wall = &walls[0];
+        assert_eq!((wall.side, wall.seen, wall.snapshots), (OrderSide::Buy, 4, 4));
+        assert_eq!((wall.quantity, wall.price.round_dp(2)), (dec!(30), dec!(99.98)));
+        assert_eq!(wall.persistence(), Decimal::ONE);
+    }
+}
@@ -0,0 +1,225 @@
+use crate::config::Config;
+use crate::error::{BotError, ReqwestExt};
+use crate::quote;
+use anyhow::Result;
+use rust_decimal::Decimal;
+use serde::Deserialize;
+use std::collections::HashMap;
+use std::str::FromStr;
+use std::time::{Duration, Instant};
+use tracing::{info, warn};
+
+/// Reference rates are published once a day; hourly keeps them fresh enough
+const FX_REFRESH_INTERVAL: Duration = Duration::from_secs(3600);
+
+/// Digit grouping and decimal mark for report figures (REPORT_LOCALE)
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum NumberLocale {
+    /// No grouping, `.` decimals (`1234.5`)
+    #[default]
+    Plain,
+    /// `1,234.5`
+    English,
+    /// `1.234,5` (de, es, it, nl, pt, ...)
+    Continental,
+    /// `1 234,5` (fr)
+    French,
+}
+
+impl NumberLocale {
+    /// Accepts a locale tag like `en-US`, `pt-BR` or `fr`; unset means plain
+    pub fn parse(tag: &str) -> Result<Self> {
+        let language = tag.split(['-', '_']).next().unwrap_or("").to_lowercase();
+        match language.as_str() {
+            "" | "plain" => Ok(Self::Plain),
+            "en" => Ok(Self::English),
+            "de" | "es" | "it" | "nl" | "pt" | "da" | "id" | "tr" => Ok(Self::Continental),
+            "fr" => Ok(Self::French),
+            _ => Err(BotError::config(format!("Unsupported REPORT_LOCALE '{}'", tag)).into()),
+        }
+    }
+
+    fn separators(self) -> Option<(char, char)> {
+        match self {
+            Self::Plain => None,
+            Self::English => Some((',', '.')),
+            Self::Continental => Some(('.', ',')),
+            Self::French => Some((' ', ',')),
+        }
+    }
+
+    /// `value` rounded to `dp` decimals with this locale's separators
+    pub fn format(self, value: Decimal, dp: u32) -> String {
+        let rounded = value.round_dp(dp);
+        let Some((group, decimal)) = self.separators() else {
+            return rounded.to_string();
+        };
+        let text = format!("{:.*}", dp as usize, rounded.abs());
+        let (int, frac) = text.split_once('.').unwrap_or((&text, ""));
+        let mut out = String::new();
+        if rounded < Decimal::ZERO {
+            out.push('-');
+        }
+        for (i, digit) in int.chars().enumerate() {
+            if i > 0 && (int.len() - i) % 3 == 0 {
+                out.push(group);
+            }
+            out.push(digit);
+        }
+        if !frac.is_empty() {
+            out.push(decimal);
+            out.push_str(frac);
+        }
+        out
+    }
+}
+
+/// Currency the report's money figures are shown in, with its rate from the quote asset
+#[derive(Debug, Clone, PartialEq)]
+pub struct DisplayCurrency {
+    pub code: String,
+    /// Units of `code` per unit of the quote asset
+    pub rate: Decimal,
+}
+
+impl DisplayCurrency {
+    fn symbol(&self) -> String {
+        match self.code.as_str() {
+            "USD" => "$".to_string(),
+            "EUR" => "€".to_string(),
+            "GBP" => "£".to_string(),
+            "BRL" => "R$".to_string(),
+            "JPY" => "¥".to_string(),
+            other => format!("{} ", other),
+        }
+    }
+}
+
+/// How money and numbers are shown in the report and notifications. The default renders
+/// quote-asset figures as `$1234.56`, as before localization existed.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct DisplayFormat {
+    pub locale: NumberLocale,
+    /// Converted display currency, once its rate is known (DISPLAY_CURRENCY)
+    pub currency: Option<DisplayCurrency>,
+}
+
+impl DisplayFormat {
+    pub fn number(&self, value: Decimal, dp: u32) -> String {
+        self.locale.format(value, dp)
+    }
+
+    /// A quote-asset amount as money in the display currency
+    pub fn money(&self, value: Decimal, dp: u32) -> String {
+        match &self.currency {
+            Some(currency) => {
+                let converted = value * currency.rate;
+                let sign = if converted < Decimal::ZERO { "-" } else { "" };
+                format!("{}{}{}", sign, currency.symbol(), self.locale.format(converted.abs(), dp))
+            }
+            None => format!("${}", self.locale.format(value, dp)),
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct RatesResponse {
+    rates: HashMap<String, f64>,
+}
+
+/// Exchange rate from the quote asset's fiat to DISPLAY_CURRENCY, from a Frankfurter-compatible
+/// API (ECB reference rates)
+pub struct FxFeed {
+    client: reqwest::Client,
+    base_url: String,
+    from: String,
+    to: String,
+    fetched: Option<Instant>,
+    rate: Option<Decimal>,
+}
+
+impl FxFeed {
+    /// `None` without DISPLAY_CURRENCY, when it matches the quote's fiat, or when the quote
+    /// asset is not pegged to a fiat currency
+    pub fn from_config(config: &Config, quote_asset: &str) -> Result<Option<Self>> {
+        let Some(to) = config.display_currency.as_ref().map(|c| c.to_uppercase()) else {
+            return Ok(None);
+        };
+        let from = if quote::is_usd_stable(quote_asset) {
+            "USD"
+        } else if quote_asset == "EUR" {
+            "EUR"
+        } else {
+            warn!("⚠️ DISPLAY_CURRENCY ignored: {} is not a fiat-pegged quote asset", quote_asset);
+            return Ok(None);
+        };
+        if to.len() != 3 || !to.chars().all(|c| c.is_ascii_alphabetic()) {
+            return Err(BotError::config(format!("DISPLAY_CURRENCY must be an ISO code like EUR or BRL, got '{}'", to)).into());
+        }
+        if to == from {
+            return Ok(None);
+        }
+        Ok(Some(Self {
+            client: reqwest::Client::builder().timeout(Duration::from_secs(15)).build()?,
+            base_url: config.fx_api_url.trim_end_matches('/').to_string(),
+            from: from.to_string(),
+            to,
+            fetched: None,
+            rate: None,
+        }))
+    }
+
+    /// The display currency with its latest rate, refetched hourly. A failed fetch keeps the
+    /// previous rate; until one succeeds figures stay in the quote asset.
+    pub async fn refresh(&mut self) -> Option<DisplayCurrency> {
+        if self.fetched.is_none_or(|t| t.elapsed() >= FX_REFRESH_INTERVAL) {
+            self.fetched = Some(Instant::now());
+            match self.fetch().await {
+                Ok(rate) => {
+                    info!("💱 1 {} = {} {}", self.from, rate, self.to);
+                    self.rate = Some(rate);
+                }
+                Err(e) => warn!("⚠️ Failed to fetch the {} rate: {}", self.to, e),
+            }
+        }
+        self.rate.map(|rate| DisplayCurrency { code: self.to.clone(), rate })
+    }
+
+    async fn fetch(&self) -> Result<Decimal> {
+        let url = format!("{}/latest?from={}&to={}", self.base_url, self.from, self.to);
+        let response = self.client.get(&url).send().await.or_data_source("FX rates")?;
+        if !response.status().is_success() {
+            let status = response.status();
+            let body = response.text().await.unwrap_or_default();
+            return Err(BotError::data_source("FX rates", status, &body).into());
+        }
+        let rates: RatesResponse = response.json().await.or_data_source("FX rates")?;
+        let rate = rates.rates.get(&self.to)
+            .ok_or_else(|| anyhow::anyhow!("No {} rate in the response", self.to))?;
+        Ok(Decimal::from_str(&rate.to_string())?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rust_decimal_macros::dec;
+
+    #[test]
+    fn test_locale_number_and_money_formats() {
+        assert_eq!(NumberLocale::parse("").unwrap().format(dec!(1234567.891), 2), "1234567.89");
+        assert_eq!(NumberLocale::parse("en-US").unwrap().format(dec!(1234567.891), 2), "1,234,567.89");
+        assert_eq!(NumberLocale::parse("pt_BR").unwrap().format(dec!(-1234.5), 2), "-1.234,50");
+        assert_eq!(NumberLocale::parse("fr").unwrap().format(dec!(999), 0), "999");
+        assert!(NumberLocale::parse("xx").is_err());
+
+        let plain = DisplayFormat::default();
+        assert_eq!(plain.money(dec!(42000.001), 2), "$42000.00");
+        let brl = DisplayFormat {
+            locale: NumberLocale::Continental,
+            currency: Some(DisplayCurrency { code: "BRL".to_string(), rate: dec!(5) }),
+        };
+        assert_eq!(brl.money(dec!(1000.5), 2), "R$5.002,50");
+        assert_eq!(brl.money(dec!(-10), 2), "-R$50,00");
+    }
+}
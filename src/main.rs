@@ -1,28 +1,42 @@
-mod ai_advisor;
-mod coingecko;
-mod config;
-mod exchange;
-mod models;
-mod portfolio;
-mod simulation;
-mod strategy;
-mod trade_limiter;
+use crypto_trading_bot::{
+    audit,
+    backtest,
+    candles,
+    competition,
+    config,
+    doctor,
+    drill,
+    engine,
+    error,
+    exchange,
+    optimizer,
+    paths,
+    quote,
+    resample,
+    review,
+    risk,
+    secrets,
+    snapshot,
+    state_crypto,
+    state_store,
+    trade_limiter,
+};
 
-use ai_advisor::{AiTradingTargets, FallbackTargetCalculator, MarketContext, OllamaClient};
 use anyhow::Result;
-use coingecko::CoinGeckoClient;
-use portfolio::PortfolioReporter;
-use rust_decimal::Decimal;
-use rust_decimal_macros::dec;
-use trade_limiter::{TradeLimiter, TradePermission};
-use tracing::{error, info, warn};
+use audit::AuditLog;
+use candles::{CandleFeed, CandleInterval};
+use backtest::BacktestReport;
+use drill::Drill;
+use error::BotError;
+use optimizer::{GaSettings, Genome, HallOfFame};
+use paths::DataPaths;
+use risk::DrawdownKillSwitch;
+use snapshot::Snapshot;
+use state_crypto::StateCipher;
+use state_store::{StateStore, HALL_OF_FAME_KEY, HIGH_WATER_MARK_KEY, TRADE_STATE_KEY};
+use trade_limiter::LimitHistory;
+use tracing::info;
 use tracing_subscriber::{layer::SubscriberExt, util::SubscriberInitExt};
-use std::time::Duration;
-
-// How often to check prices (in seconds)
-const PRICE_CHECK_INTERVAL_SECS: u64 = 30;
-// How often to recalculate targets with AI (in seconds)
-const AI_RECALC_INTERVAL_SECS: u64 = 300; // 5 minutes
 
 #[tokio::main]
 async fn main() -> Result<()> {
@@ -40,455 +54,218 @@ async fn main() -> Result<()> {
     info!("🚀 Crypto Trading Bot starting...");
 
     // Load configuration
-    let config = config::Config::from_env()?;
-    
-    if config.is_simulation() {
-        info!("🎮 Running in SIMULATION MODE - no real trades will be executed");
-        run_simulation_loop(config).await
-    } else {
-        info!("💰 Running in LIVE MODE on exchange: {}", config.exchange);
-        warn!("⚠️  Real money is at risk!");
-        run_live_loop(config).await
+    let config = secrets::resolve_config_secrets(config::Config::from_env()?).await?;
+
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    match args.first().map(String::as_str) {
+        Some("snapshot") => return create_snapshot(&config, args.get(1).map(String::as_str)).await,
+        Some("restore") => return restore_snapshot(&config, &args[1..]).await,
+        Some("doctor") => return run_doctor(&config).await,
+        Some("drill" | "--drill") => return run_drill(&config).await,
+        Some("compete") => return engine::run_competition(config).await,
+        Some("optimize") => return run_optimizer(config).await,
+        Some("backtest") => return run_backtest(config, args.get(1).map(String::as_str)).await,
+        Some("limits") => return show_limit_history(&config).await,
+        Some("reset-drawdown") => return reset_drawdown(&config).await,
+        Some("cancel-orders") => return cancel_open_orders(&config).await,
+        Some("review") => {
+            let paths = DataPaths::from_config(&config)?;
+            let now = chrono::Utc::now();
+            let audit = AuditLog::new(&paths.audit_log_file()).with_cipher(StateCipher::from_config(&config)?);
+            return review::write_weekly(&audit, &paths.weekly_review_file(now.date_naive()), now);
+        }
+        Some(other) => return Err(anyhow::anyhow!("Unknown command '{}' (expected snapshot, restore, doctor, drill, compete, optimize, backtest, limits, reset-drawdown, cancel-orders or review)", other)),
+        None => {}
     }
+    engine::run(config).await
 }
 
-/// Continuous monitoring loop for simulation mode
-async fn run_simulation_loop(config: config::Config) -> Result<()> {
-    let exchange = simulation::SimulationExchange::new(&config).await?;
-    info!("✅ Simulation exchange initialized");
-
-    // Initialize components
-    let mut reporter = PortfolioReporter::new(&config.symbol, true, &config.report_path);
-    let coingecko = CoinGeckoClient::new();
-    let mut trade_limiter = TradeLimiter::new(&format!(
-        "{}/trade_state.json",
-        std::env::current_dir()?.display()
-    ));
-
-    // Set trading parameters
-    {
-        let status = reporter.status_mut();
-        status.stop_loss_percent = config.stop_loss_percent;
-        status.take_profit_percent = config.take_profit_percent;
+/// `bot doctor`: check config, credentials, data sources, paths and clock before a live run
+async fn run_doctor(config: &config::Config) -> Result<()> {
+    let checks = doctor::run(config).await;
+    println!("\n🩺 Crypto Trading Bot doctor ({} mode)\n", if config.is_simulation() { "simulation" } else { "live" });
+    for check in &checks {
+        println!("  {}", check);
     }
-
-    // Get initial balance
-    let balance = exchange.get_balance().await?;
-    let balance_map: std::collections::HashMap<String, Decimal> = balance
-        .iter()
-        .map(|(k, v)| (k.clone(), v.free))
-        .collect();
-    reporter.update_balances(balance_map);
-    info!("💰 Starting balance: {:?}", balance);
-
-    // Track state
-    let mut last_ai_update = std::time::Instant::now();
-    let mut current_targets: Option<AiTradingTargets> = None;
-    let mut in_position = false;
-    let mut position_qty = dec!(0);
-    let mut loop_count: u64 = 0;
-
-    info!("━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━");
-    info!("🔄 Starting CONTINUOUS monitoring loop...");
-    info!("   Price check interval: {}s", PRICE_CHECK_INTERVAL_SECS);
-    info!("   AI recalculation interval: {}s", AI_RECALC_INTERVAL_SECS);
-    info!("   Press Ctrl+C to stop");
-    info!("━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━");
-
-    loop {
-        loop_count += 1;
-        info!("");
-        info!("━━━ Monitoring cycle #{} ━━━", loop_count);
-
-        // Fetch real market data from CoinGecko
-        let market_data = match coingecko.fetch_market_data(&config.symbol).await {
-            Ok(data) => {
-                info!("✅ CoinGecko: {} @ ${:.2}", data.symbol, data.current_price);
-                Some(data)
-            }
-            Err(e) => {
-                warn!("⚠️ CoinGecko fetch failed: {}", e);
-                None
-            }
-        };
-
-        // Get current price (from CoinGecko or simulated)
-        let current_price = if let Some(ref data) = market_data {
-            data.current_price
-        } else {
-            exchange.get_price(&config.symbol).await.unwrap_or(dec!(0))
-        };
-
-        if current_price == dec!(0) {
-            warn!("❌ Could not get current price, skipping cycle");
-            tokio::time::sleep(Duration::from_secs(PRICE_CHECK_INTERVAL_SECS)).await;
-            continue;
-        }
-
-        // Update reporter with price
-        if let Some(event) = reporter.update_price(current_price) {
-            info!("🔔 ALERT: {}", event);
-        }
-
-        // Calculate support/resistance if we have market data
-        let (sma_short, sma_long, rsi, high_24h, low_24h, change_24h) = if let Some(ref data) = market_data {
-            let closes: Vec<Decimal> = data.hourly_data_24h.iter().map(|d| d.close).collect();
-            let sma_s = strategy::SmaCrossover::calculate_sma(&closes, 10);
-            let sma_l = strategy::SmaCrossover::calculate_sma(&closes, 20);
-            let rsi_val = strategy::RsiStrategy::calculate_rsi(&closes, 14);
-            (sma_s, sma_l, rsi_val, data.high_24h, data.low_24h, data.price_change_24h_percent)
-        } else {
-            (None, None, None, current_price * dec!(1.02), current_price * dec!(0.98), dec!(0))
-        };
-
-        // Build market context
-        let market_context = MarketContext {
-            symbol: config.symbol.clone(),
-            current_price,
-            high_24h,
-            low_24h,
-            price_change_24h_percent: change_24h,
-            sma_short,
-            sma_long,
-            rsi,
-            volume_24h: market_data.as_ref().map(|d| d.total_volume),
-            position_entry_price: reporter.status().entry_price,
-            account_balance: reporter.status().total_portfolio_value,
-            hourly_data_summary: market_data.as_ref().map(|d| coingecko.format_for_ai(d)),
-            high_12h: market_data.as_ref().and_then(|d| d.hourly_data_12h.iter().map(|h| h.high).max()),
-            low_12h: market_data.as_ref().and_then(|d| d.hourly_data_12h.iter().map(|h| h.low).min()),
-            high_48h: market_data.as_ref().and_then(|d| d.hourly_data_48h.iter().map(|h| h.high).max()),
-            low_48h: market_data.as_ref().and_then(|d| d.hourly_data_48h.iter().map(|h| h.low).min()),
-        };
-
-        // Update reporter market data
-        {
-            let status = reporter.status_mut();
-            status.current_price = current_price;
-            status.high_24h = high_24h;
-            status.low_24h = low_24h;
-            status.price_change_24h_percent = change_24h;
-        }
-
-        // Update signals
-        let signal = if let (Some(short), Some(long)) = (sma_short, sma_long) {
-            if short > long { models::Signal::Buy } else { models::Signal::Sell }
-        } else {
-            models::Signal::Hold
-        };
-        reporter.update_signals(signal, sma_short, sma_long, rsi);
-
-        // Recalculate targets periodically or if we don't have any
-        let should_recalc = current_targets.is_none() 
-            || last_ai_update.elapsed().as_secs() >= AI_RECALC_INTERVAL_SECS;
-
-        if should_recalc {
-            info!("🔄 Recalculating trading targets...");
-            
-            // Always calculate fallback first
-            let fallback = FallbackTargetCalculator::calculate_targets(&market_context);
-            current_targets = Some(fallback.clone());
-            reporter.update_ai_targets(&fallback);
-            info!("📊 Fallback: {} @ {}% confidence", fallback.recommendation, fallback.confidence.round_dp(0));
-
-            // Try AI if enabled (non-blocking with timeout)
-            if config.ollama_enabled {
-                match OllamaClient::new(Some(&config.ollama_url), Some(&config.ollama_model)) {
-                    Ok(ollama) => {
-                        if ollama.health_check().await.unwrap_or(false) {
-                            info!("🤖 Requesting AI analysis (timeout: 120s)...");
-                            
-                            match tokio::time::timeout(
-                                Duration::from_secs(120),
-                                ollama.calculate_targets(&market_context)
-                            ).await {
-                                Ok(Ok(targets)) => {
-                                    info!("🧠 AI: {} @ {}% confidence", 
-                                        targets.recommendation, targets.confidence.round_dp(0));
-                                    current_targets = Some(targets.clone());
-                                    reporter.update_ai_targets(&targets);
-                                }
-                                Ok(Err(e)) => warn!("⚠️ AI analysis failed: {}", e),
-                                Err(_) => warn!("⚠️ AI analysis timed out"),
-                            }
-                        }
-                    }
-                    Err(e) => warn!("⚠️ Ollama client error: {}", e),
-                }
-            }
-
-            last_ai_update = std::time::Instant::now();
-        }
-
-        // Check trade limits
-        let trade_status = trade_limiter.get_status();
-        reporter.update_trade_limits(
-            trade_status.trades_executed,
-            trade_status.can_trade,
-            if trade_status.can_trade { None } else { Some(trade_status.date.clone()) },
-        );
-
-        // Trading logic - check if targets are hit
-        if let Some(ref targets) = current_targets {
-            let can_trade = matches!(trade_limiter.can_trade(), TradePermission::Allowed { .. });
-
-            if in_position {
-                // We have a position - check for exit signals
-                let entry = reporter.status().entry_price.unwrap_or(current_price);
-                
-                // Check stop-loss
-                if current_price <= targets.stop_loss_price {
-                    info!("🔴 STOP-LOSS TRIGGERED at ${:.2}!", current_price);
-                    if can_trade {
-                        let pnl = (current_price - entry) * position_qty;
-                        execute_sell(&exchange, &config.symbol, position_qty, current_price, pnl, 
-                                    &mut reporter, &mut trade_limiter).await?;
-                        in_position = false;
-                        position_qty = dec!(0);
-                    } else {
-                        warn!("⚠️ Cannot execute - daily trade limit reached");
-                    }
-                }
-                // Check take-profit
-                else if current_price >= targets.take_profit_price {
-                    info!("🟢 TAKE-PROFIT TRIGGERED at ${:.2}!", current_price);
-                    if can_trade {
-                        let pnl = (current_price - entry) * position_qty;
-                        execute_sell(&exchange, &config.symbol, position_qty, current_price, pnl,
-                                    &mut reporter, &mut trade_limiter).await?;
-                        in_position = false;
-                        position_qty = dec!(0);
-                    } else {
-                        warn!("⚠️ Cannot execute - daily trade limit reached");
-                    }
-                }
-                // Check sell target
-                else if let Some(sell_target) = targets.sell_target_price {
-                    if current_price >= sell_target {
-                        info!("💜 SELL TARGET reached at ${:.2}!", current_price);
-                        if can_trade {
-                            let pnl = (current_price - entry) * position_qty;
-                            execute_sell(&exchange, &config.symbol, position_qty, current_price, pnl,
-                                        &mut reporter, &mut trade_limiter).await?;
-                            in_position = false;
-                            position_qty = dec!(0);
-                        }
-                    }
-                }
-            } else {
-                // No position - check for entry signals
-                if let Some(buy_target) = targets.buy_target_price {
-                    if current_price <= buy_target && can_trade {
-                        info!("💚 BUY TARGET reached at ${:.2}!", current_price);
-                        
-                        // Calculate position size (use 10% of balance for simulation)
-                        let balance = reporter.status().balances.get("USDT").copied().unwrap_or(dec!(0));
-                        let trade_amount = balance * dec!(0.10); // 10% of balance
-                        let qty = trade_amount / current_price;
-                        
-                        if qty > dec!(0) {
-                            execute_buy(&exchange, &config.symbol, qty, current_price,
-                                       &mut reporter, &mut trade_limiter).await?;
-                            in_position = true;
-                            position_qty = qty;
-                        }
-                    }
-                }
-            }
-        }
-
-        // Update balances
-        let balance = exchange.get_balance().await?;
-        let balance_map: std::collections::HashMap<String, Decimal> = balance
-            .iter()
-            .map(|(k, v)| (k.clone(), v.free))
-            .collect();
-        reporter.update_balances(balance_map);
-
-        // Write report
-        reporter.force_write()?;
-
-        // Log current state summary
-        if let Some(ref targets) = current_targets {
-            info!("📍 Price: ${:.2} | SL: ${:.2} | TP: ${:.2}", 
-                current_price, targets.stop_loss_price, targets.take_profit_price);
-            if let (Some(buy), Some(sell)) = (targets.buy_target_price, targets.sell_target_price) {
-                info!("   Buy Target: ${:.2} | Sell Target: ${:.2}", buy, sell);
-            }
-            info!("   Position: {} | Trades today: {}/2", 
-                if in_position { "LONG" } else { "NONE" }, trade_status.trades_executed);
-        }
-
-        // Wait before next cycle
-        info!("💤 Sleeping {}s until next check...", PRICE_CHECK_INTERVAL_SECS);
-        tokio::time::sleep(Duration::from_secs(PRICE_CHECK_INTERVAL_SECS)).await;
+    let failed = checks.iter().filter(|c| c.status == doctor::CheckStatus::Fail).count();
+    println!();
+    if failed > 0 {
+        return Err(BotError::config(format!("{} of {} checks failed", failed, checks.len())).into());
     }
+    info!("✅ All checks passed");
+    Ok(())
 }
 
-async fn execute_buy(
-    exchange: &simulation::SimulationExchange,
-    symbol: &str,
-    qty: Decimal,
-    price: Decimal,
-    reporter: &mut PortfolioReporter,
-    trade_limiter: &mut TradeLimiter,
-) -> Result<()> {
-    let _order = exchange.place_order(
-        symbol,
-        models::OrderSide::Buy,
-        models::OrderType::Market,
-        qty,
-        None,
-    ).await?;
-    
-    trade_limiter.record_trade(symbol, "BUY", price, qty)?;
-    reporter.record_trade(models::OrderSide::Buy, price, qty, None);
-    
-    info!("✅ BUY executed: {} @ ${:.2}", qty.round_dp(6), price.round_dp(2));
+/// `bot drill`: rehearse exchange, data and AI outages mid-position against a scripted exchange
+async fn run_drill(config: &config::Config) -> Result<()> {
+    let paths = DataPaths::from_config(config)?;
+    let checks = Drill::new(config).run().await;
+    let failed = checks.iter().filter(|c| c.status == doctor::CheckStatus::Fail).count();
+    let mut report = format!("🚒 Outage drill on {} - {}\n\n", config.symbol, chrono::Utc::now().format("%Y-%m-%d %H:%M UTC"));
+    for check in &checks {
+        report.push_str(&format!("  {}\n", check));
+    }
+    report.push_str(&format!("\n{}\n", if failed > 0 { "❌ DRILL FAILED" } else { "✅ DRILL PASSED" }));
+    println!("\n{}", report);
+    paths::write_atomic(paths.drill_report_file(), &report)?;
+    info!("📄 Drill report written to {}", paths.drill_report_file().display());
+    if failed > 0 {
+        return Err(BotError::config(format!("{} of {} drill steps failed", failed, checks.len())).into());
+    }
     Ok(())
 }
 
-async fn execute_sell(
-    exchange: &simulation::SimulationExchange,
-    symbol: &str,
-    qty: Decimal,
-    price: Decimal,
-    pnl: Decimal,
-    reporter: &mut PortfolioReporter,
-    trade_limiter: &mut TradeLimiter,
-) -> Result<()> {
-    let _order = exchange.place_order(
-        symbol,
-        models::OrderSide::Sell,
-        models::OrderType::Market,
-        qty,
-        None,
-    ).await?;
-    
-    trade_limiter.record_trade(symbol, "SELL", price, qty)?;
-    trade_limiter.update_pnl(pnl);
-    reporter.record_trade(models::OrderSide::Sell, price, qty, Some(pnl));
-    
-    let pnl_emoji = if pnl >= dec!(0) { "🟢" } else { "🔴" };
-    info!("{} SELL executed: {} @ ${:.2} | P&L: ${:.2}", 
-        pnl_emoji, qty.round_dp(6), price.round_dp(2), pnl.round_dp(2));
+/// `bot limits`: how often the daily trade limit was hit, month by month
+async fn show_limit_history(config: &config::Config) -> Result<()> {
+    let paths = DataPaths::from_config(config)?;
+    let backend = state_store::backend_from_config(config, &paths).await?;
+    let store = StateStore::new(backend, TRADE_STATE_KEY, StateCipher::from_config(config)?);
+    println!("\n{}", LimitHistory::load(&store.companion("history")).render());
     Ok(())
 }
 
-/// Continuous monitoring loop for live trading
-async fn run_live_loop(config: config::Config) -> Result<()> {
-    let exchange = exchange::ExchangeClient::new(&config).await?;
-    info!("✅ Connected to exchange");
-
-    let mut reporter = PortfolioReporter::new(&config.symbol, false, &config.report_path);
-    let coingecko = CoinGeckoClient::new();
-    let trade_limiter = TradeLimiter::new(&format!(
-        "{}/trade_state.json",
-        std::env::current_dir()?.display()
-    ));
-
-    {
-        let status = reporter.status_mut();
-        status.stop_loss_percent = config.stop_loss_percent;
-        status.take_profit_percent = config.take_profit_percent;
+/// `bot reset-drawdown`: lift a MAX_DRAWDOWN_PERCENT halt; the high-water mark restarts from
+/// the next equity reading
+async fn reset_drawdown(config: &config::Config) -> Result<()> {
+    let paths = DataPaths::from_config(config)?;
+    let backend = state_store::backend_from_config(config, &paths).await?;
+    let store = StateStore::new(backend, HIGH_WATER_MARK_KEY, StateCipher::from_config(config)?);
+    let previous = DrawdownKillSwitch::reset(&store);
+    match previous.halted {
+        Some(reason) => info!("✅ Drawdown halt lifted (was: {})", reason),
+        None => info!("✅ Drawdown kill switch was not tripped"),
     }
+    info!("📈 High-water mark {} cleared - it restarts from the current equity", previous.peak.round_dp(2));
+    Ok(())
+}
 
-    let balance = exchange.get_balance().await?;
-    let balance_map: std::collections::HashMap<String, Decimal> = balance
-        .iter()
-        .map(|(k, v)| (k.clone(), v.free))
-        .collect();
-    reporter.update_balances(balance_map);
-    info!("💰 Account balance: {:?}", balance);
-
-    let mut last_ai_update = std::time::Instant::now();
-    let mut current_targets: Option<AiTradingTargets> = None;
-
-    info!("━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━");
-    info!("🔄 Starting LIVE monitoring loop...");
-    warn!("⚠️ This will execute REAL trades!");
-    info!("   Press Ctrl+C to stop");
-    info!("━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━");
-
-    loop {
-        // Fetch real price from exchange
-        let current_price = match exchange.get_price(&config.symbol).await {
-            Ok(p) => p,
-            Err(e) => {
-                error!("❌ Failed to get price: {}", e);
-                tokio::time::sleep(Duration::from_secs(PRICE_CHECK_INTERVAL_SECS)).await;
-                continue;
-            }
-        };
-
-        info!("📊 {} @ ${:.2}", config.symbol, current_price);
-
-        // Update reporter
-        if let Some(event) = reporter.update_price(current_price) {
-            info!("🔔 ALERT: {}", event);
-        }
-
-        // Recalculate targets periodically
-        if current_targets.is_none() || last_ai_update.elapsed().as_secs() >= AI_RECALC_INTERVAL_SECS {
-            if let Ok(market_data) = coingecko.fetch_market_data(&config.symbol).await {
-                let closes: Vec<Decimal> = market_data.hourly_data_24h.iter().map(|d| d.close).collect();
-                
-                let market_context = MarketContext {
-                    symbol: config.symbol.clone(),
-                    current_price,
-                    high_24h: market_data.high_24h,
-                    low_24h: market_data.low_24h,
-                    price_change_24h_percent: market_data.price_change_24h_percent,
-                    sma_short: strategy::SmaCrossover::calculate_sma(&closes, 10),
-                    sma_long: strategy::SmaCrossover::calculate_sma(&closes, 20),
-                    rsi: strategy::RsiStrategy::calculate_rsi(&closes, 14),
-                    volume_24h: Some(market_data.total_volume),
-                    position_entry_price: reporter.status().entry_price,
-                    account_balance: reporter.status().total_portfolio_value,
-                    hourly_data_summary: Some(coingecko.format_for_ai(&market_data)),
-                    high_12h: market_data.hourly_data_12h.iter().map(|h| h.high).max(),
-                    low_12h: market_data.hourly_data_12h.iter().map(|h| h.low).min(),
-                    high_48h: market_data.hourly_data_48h.iter().map(|h| h.high).max(),
-                    low_48h: market_data.hourly_data_48h.iter().map(|h| h.low).min(),
-                };
-
-                let fallback = FallbackTargetCalculator::calculate_targets(&market_context);
-                current_targets = Some(fallback.clone());
-                reporter.update_ai_targets(&fallback);
-
-                {
-                    let status = reporter.status_mut();
-                    status.high_24h = market_data.high_24h;
-                    status.low_24h = market_data.low_24h;
-                    status.price_change_24h_percent = market_data.price_change_24h_percent;
-                }
-            }
-
-            last_ai_update = std::time::Instant::now();
-        }
+/// `bot cancel-orders`: cancel every order resting on the traded symbol, e.g. after stopping
+/// the bot or before handing the account over
+async fn cancel_open_orders(config: &config::Config) -> Result<()> {
+    if config.is_simulation() || config.is_futures() {
+        return Err(BotError::config("cancel-orders needs a live spot exchange").into());
+    }
+    config.check_required()?;
+    let (symbol, _) = quote::resolve_pair(&config.symbol, config.quote_asset.as_deref(), None)?;
+    let exchange = exchange::ExchangeClient::new(config).await?;
+    // Binance answers a cancel-all on an empty book with an error
+    if exchange.get_open_orders(&symbol).await?.is_empty() {
+        info!("✅ No open orders on {}", symbol);
+        return Ok(());
+    }
+    let cancelled = exchange.cancel_all_orders(&symbol).await?;
+    info!("🗑️ Cancelled {} open order(s) on {}", cancelled, symbol);
+    Ok(())
+}
 
-        // Update trade limits
-        let trade_status = trade_limiter.get_status();
-        reporter.update_trade_limits(
-            trade_status.trades_executed,
-            trade_status.can_trade,
-            if trade_status.can_trade { None } else { Some(trade_status.date.clone()) },
-        );
+/// `bot optimize`: evolve SMA/stop-loss/take-profit parameters over Binance hourly candles
+/// and add the best genomes to the hall of fame
+async fn run_optimizer(mut config: config::Config) -> Result<()> {
+    use rust_decimal::prelude::ToPrimitive;
+    let settings = GaSettings::from_config(&config)?;
+    let (symbol, _) = quote::resolve_pair(&config.symbol, config.quote_asset.as_deref(), None)?;
+    config.symbol = symbol;
+    let klines = exchange::ExchangeClient::public(&config).await?.get_klines(&config.symbol, "1h", 1000).await?;
+    let closes: Vec<f64> = klines.iter().filter_map(|k| k.close.to_f64()).collect();
+    info!("🧬 Evolving {} genomes over {} generations on {} hourly {} candles",
+        settings.population, settings.generations, closes.len(), config.symbol);
+
+    let best = optimizer::evolve(&settings, &closes, &mut rand::thread_rng());
+
+    let paths = DataPaths::from_config(&config)?;
+    let backend = state_store::backend_from_config(&config, &paths).await?;
+    let store = StateStore::new(backend, HALL_OF_FAME_KEY, StateCipher::from_config(&config)?);
+    let mut hall = HallOfFame::load(&store);
+    hall.merge(&config.symbol, closes.len(), &best);
+    hall.save(&store);
+
+    println!("\n🏆 Hall of fame for {} (fitness = return - {}×max drawdown)\n", config.symbol, optimizer::DRAWDOWN_PENALTY);
+    for (rank, entry) in hall.for_symbol(&config.symbol).enumerate() {
+        let g = &entry.genome;
+        println!("  {:>2}. {:<12} SL -{:.1}% TP {:.1}%  return {:+.2}%  max DD {:.2}%  {} trades  ({})",
+            rank + 1, g.competition_spec(), g.stop_loss_percent, g.take_profit_percent,
+            entry.result.return_percent, entry.result.max_drawdown_percent, entry.result.trades,
+            entry.found.format("%Y-%m-%d"));
+    }
+    if let Some(top) = best.first() {
+        println!("\n  Race the top genomes: COMPETITION_BOTS={}", best.iter().take(4)
+            .map(|s| s.genome.competition_spec()).collect::<Vec<_>>().join(","));
+        println!("  Or trade the best: STOP_LOSS_PERCENT=-{:.1} TAKE_PROFIT_PERCENT={:.1}",
+            top.genome.stop_loss_percent, top.genome.take_profit_percent);
+    }
+    Ok(())
+}
 
-        // In LIVE mode, we only ALERT - don't auto-execute
-        if let Some(ref targets) = current_targets {
-            if current_price <= targets.stop_loss_price {
-                warn!("🚨 STOP-LOSS ALERT: Price ${:.2} <= SL ${:.2}", 
-                    current_price, targets.stop_loss_price);
-            }
-            if current_price >= targets.take_profit_price {
-                info!("🎯 TAKE-PROFIT ALERT: Price ${:.2} >= TP ${:.2}", 
-                    current_price, targets.take_profit_price);
-            }
+/// `bot backtest [sma:SHORT/LONG]`: replay a crossover over the last 1000 hourly candles, or
+/// INDICATOR_INTERVAL bars, and write a per-trade report. Without a spec the best hall-of-fame genome is used, or 10/20;
+/// a spec or the fallback trades with STOP_LOSS_PERCENT and TAKE_PROFIT_PERCENT.
+async fn run_backtest(mut config: config::Config, spec: Option<&str>) -> Result<()> {
+    use rust_decimal::prelude::ToPrimitive;
+    let (symbol, _) = quote::resolve_pair(&config.symbol, config.quote_asset.as_deref(), None)?;
+    config.symbol = symbol;
+    let paths = DataPaths::from_config(&config)?;
+    let configured = |short: usize, long: usize| Genome {
+        sma_short: short as u32,
+        sma_long: long as u32,
+        stop_loss_percent: config.stop_loss_percent.abs().to_f64().unwrap_or(2.0),
+        take_profit_percent: config.take_profit_percent.to_f64().unwrap_or(5.0),
+    };
+    let genome = match spec.map(competition::BotStrategy::parse).transpose()? {
+        Some(competition::BotStrategy::Sma { short, long }) => configured(short, long),
+        Some(_) => return Err(BotError::config("backtest replays SMA crossovers only, e.g. sma:10/20").into()),
+        None => {
+            let backend = state_store::backend_from_config(&config, &paths).await?;
+            let store = StateStore::new(backend, HALL_OF_FAME_KEY, StateCipher::from_config(&config)?);
+            HallOfFame::load(&store).for_symbol(&config.symbol).next()
+                .map_or_else(|| configured(10, 20), |entry| entry.genome)
         }
+    };
+    let interval = CandleFeed::from_config(&config)?.map_or(CandleInterval::H1, |feed| feed.interval());
+    let client = exchange::ExchangeClient::public(&config).await?;
+    // Sub-hour bars come straight from the exchange; 4h bars are rolled up from the hourly
+    // candles the optimizer scores genomes on, so both replays cover the same weeks
+    let klines = if interval < CandleInterval::H1 {
+        client.get_klines(&config.symbol, interval.as_str(), 1000).await?
+    } else {
+        resample::resample(&client.get_klines(&config.symbol, "1h", 1000).await?, interval)
+    };
+    info!("📈 Backtesting {} on {} {} {} candles", genome.competition_spec(), klines.len(), interval.as_str(), config.symbol);
+
+    let report = BacktestReport::run(&config.symbol, &genome, interval, &klines);
+    let text = report.render_text();
+    let file = paths.backtest_report_file();
+    paths::write_atomic(&file, &text)?;
+    paths::write_atomic(file.with_extension("html"), report.render_html())?;
+    println!("\n{}", text);
+    info!("📄 Backtest report written to {}", file.display());
+    Ok(())
+}
 
-        reporter.force_write()?;
+/// `bot snapshot [FILE]`: bundle the state backend and the audit log into one file
+async fn create_snapshot(config: &config::Config, out: Option<&str>) -> Result<()> {
+    let paths = DataPaths::from_config(config)?;
+    let backend = state_store::backend_from_config(config, &paths).await?;
+    let audit = AuditLog::new(&paths.audit_log_file()).with_cipher(StateCipher::from_config(config)?);
+    let snapshot = Snapshot::capture(config, backend.as_ref(), &audit)?;
+    let default_name = format!("bot-snapshot-{}.json", snapshot.created_at.format("%Y%m%d-%H%M%S"));
+    let out = std::path::PathBuf::from(out.unwrap_or(&default_name));
+    snapshot.save(&out)?;
+    info!("📦 Snapshot of {} files written to {} (config {})", snapshot.files.len(), out.display(), snapshot.config_hash);
+    Ok(())
+}
 
-        tokio::time::sleep(Duration::from_secs(PRICE_CHECK_INTERVAL_SECS)).await;
-    }
+/// `bot restore FILE [--force]`: write a snapshot back into the state backend and DATA_DIR;
+/// run with the bot stopped
+async fn restore_snapshot(config: &config::Config, args: &[String]) -> Result<()> {
+    let force = args.iter().any(|a| a == "--force");
+    let file = args.iter().find(|a| !a.starts_with("--"))
+        .ok_or_else(|| anyhow::anyhow!("Usage: crypto_trading_bot restore <FILE> [--force]"))?;
+    let paths = DataPaths::from_config(config)?;
+    let snapshot = Snapshot::load(std::path::Path::new(file))?;
+    info!("📦 Snapshot from {} ({} files, {}, bot v{})", snapshot.created_at, snapshot.files.len(),
+        snapshot.symbol, snapshot.bot_version);
+    let backend = state_store::backend_from_config(config, &paths).await?;
+    let restored = snapshot.restore(&paths, config, backend.as_ref(), force)?;
+    info!("✅ Restored {} entries into {}", restored.len(), backend.describe());
+    Ok(())
 }
@@ -0,0 +1,129 @@
+use crate::config::Config;
+use crate::exchange::ExchangeClient;
+use anyhow::{anyhow, Result};
+use chrono::{DateTime, Utc};
+use std::time::{Duration, Instant};
+use tracing::{debug, info, warn};
+
+/// How often the exchange status endpoints are polled
+const STATUS_CHECK_INTERVAL: Duration = Duration::from_secs(60);
+
+/// A planned downtime window, e.g. from an exchange announcement
+#[derive(Debug, Clone, PartialEq)]
+pub struct MaintenanceWindow {
+    pub start: DateTime<Utc>,
+    pub end: DateTime<Utc>,
+}
+
+impl MaintenanceWindow {
+    /// Parse `<start>/<end>` with RFC 3339 timestamps
+    fn parse(value: &str) -> Result<Self> {
+        let (start, end) = value
+            .split_once('/')
+            .ok_or_else(|| anyhow!("Expected <start>/<end> in MAINTENANCE_WINDOWS, got '{}'", value))?;
+        let window = Self {
+            start: DateTime::parse_from_rfc3339(start.trim())?.with_timezone(&Utc),
+            end: DateTime::parse_from_rfc3339(end.trim())?.with_timezone(&Utc),
+        };
+        if window.end <= window.start {
+            return Err(anyhow!("Maintenance window '{}' ends before it starts", value));
+        }
+        Ok(window)
+    }
+
+    fn contains(&self, time: DateTime<Utc>) -> bool {
+        time >= self.start && time < self.end
+    }
+}
+
+/// Pauses new entries while the exchange is under maintenance, the symbol is not
+/// trading, or a planned downtime window is active. Entries resume automatically
+/// once the condition clears.
+pub struct MaintenanceMonitor {
+    planned: Vec<MaintenanceWindow>,
+    exchange_reason: Option<String>,
+    last_check: Option<Instant>,
+    pause_reason: Option<String>,
+}
+
+impl MaintenanceMonitor {
+    pub fn from_config(config: &Config) -> Result<Self> {
+        let planned = config
+            .maintenance_windows
+            .iter()
+            .map(|w| MaintenanceWindow::parse(w))
+            .collect::<Result<Vec<_>>>()?;
+
+        Ok(Self {
+            planned,
+            exchange_reason: None,
+            last_check: None,
+            pause_reason: None,
+        })
+    }
+
+    /// Re-evaluate the pause state. The exchange is polled at most once a minute;
+    /// pass None when there is no exchange to ask (simulation).
+    pub async fn update(&mut self, exchange: Option<&ExchangeClient>, symbol: &str) -> Option<&str> {
+        if let Some(exchange) = exchange
+            && self.last_check.is_none_or(|t| t.elapsed() >= STATUS_CHECK_INTERVAL) {
+            self.exchange_reason = Self::check_exchange(exchange, symbol).await;
+            self.last_check = Some(Instant::now());
+        }
+
+        let now = Utc::now();
+        let reason = self.exchange_reason.clone().or_else(|| {
+            self.planned
+                .iter()
+                .find(|w| w.contains(now))
+                .map(|w| format!("planned maintenance until {}", w.end.format("%Y-%m-%d %H:%M UTC")))
+        });
+
+        match (&self.pause_reason, &reason) {
+            (None, Some(r)) => warn!("⏸️ Entries paused: {}", r),
+            (Some(_), None) => info!("▶️ Entries resumed"),
+            _ => {}
+        }
+        self.pause_reason = reason;
+        self.pause_reason.as_deref()
+    }
+
+    async fn check_exchange(exchange: &ExchangeClient, symbol: &str) -> Option<String> {
+        // The status endpoints are unavailable on some environments (e.g. testnet); a
+        // failed check never pauses trading on its own
+        match exchange.get_system_maintenance().await {
+            Ok(Some(msg)) => return Some(format!("exchange maintenance ({})", msg)),
+            Ok(None) => {}
+            Err(e) => debug!("System status check failed: {}", e),
+        }
+
+        match exchange.get_symbol_status(symbol).await {
+            Ok(status) if status != "TRADING" => Some(format!("{} trading status is {}", symbol, status)),
+            Ok(_) => None,
+            Err(e) => {
+                debug!("Symbol status check failed: {}", e);
+                None
+            }
+        }
+    }
+
+    /// Next planned window that has not ended yet
+    pub fn next_planned(&self) -> Option<&MaintenanceWindow> {
+        let now = Utc::now();
+        self.planned.iter().filter(|w| w.end > now).min_by_key(|w| w.start)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_window() {
+        let window = MaintenanceWindow::parse("2024-06-01T02:00:00Z/2024-06-01T04:00:00Z").unwrap();
+        assert!(window.contains(DateTime::parse_from_rfc3339("2024-06-01T03:00:00Z").unwrap().with_timezone(&Utc)));
+        assert!(!window.contains(window.end));
+        assert!(MaintenanceWindow::parse("2024-06-01T04:00:00Z/2024-06-01T02:00:00Z").is_err());
+        assert!(MaintenanceWindow::parse("tomorrow").is_err());
+    }
+}
@@ -0,0 +1,110 @@
+use crate::coingecko::CoinGeckoMarketData;
+use crate::config::Config;
+use crate::error::{BotError, ErrorKind};
+use crate::models::BookTicker;
+use anyhow::Result;
+use rust_decimal::Decimal;
+use std::future::Future;
+use std::time::Duration;
+use tracing::warn;
+
+/// How long each data source may take before the cycle continues without it
+#[derive(Debug, Clone, Copy)]
+pub struct SourceTimeouts {
+    /// Exchange price and order book requests
+    pub exchange: Duration,
+    /// CoinGecko market data (two requests plus chart parsing)
+    pub coingecko: Duration,
+}
+
+impl SourceTimeouts {
+    pub fn from_config(config: &Config) -> Self {
+        Self {
+            exchange: Duration::from_secs(config.exchange_fetch_timeout_secs),
+            coingecko: Duration::from_secs(config.coingecko_fetch_timeout_secs),
+        }
+    }
+}
+
+/// Everything a monitoring cycle reads from external sources. A source that
+/// fails or times out is `None`; the cycle still uses the others.
+#[derive(Debug, Default)]
+pub struct MarketSnapshot {
+    pub price: Option<Decimal>,
+    pub market: Option<CoinGeckoMarketData>,
+    pub book: Option<BookTicker>,
+}
+
+/// Fetch the exchange price, CoinGecko market data and top of the order book
+/// concurrently, so a cycle waits for the slowest source rather than the sum of all.
+/// `market` and `book` are skipped when `None`.
+pub async fn fetch_snapshot(
+    price: impl Future<Output = Result<Decimal>>,
+    market: Option<impl Future<Output = Result<CoinGeckoMarketData>>>,
+    book: Option<impl Future<Output = Result<BookTicker>>>,
+    timeouts: SourceTimeouts,
+) -> MarketSnapshot {
+    let (price, market, book) = tokio::join!(
+        with_timeout("Exchange price", price, timeouts.exchange),
+        async {
+            match market {
+                Some(f) => with_timeout("CoinGecko", f, timeouts.coingecko).await,
+                None => None,
+            }
+        },
+        async {
+            match book {
+                Some(f) => with_timeout("Order book", f, timeouts.exchange).await,
+                None => None,
+            }
+        },
+    );
+
+    MarketSnapshot { price, market, book }
+}
+
+async fn with_timeout<T>(source: &str, fetch: impl Future<Output = Result<T>>, timeout: Duration) -> Option<T> {
+    match tokio::time::timeout(timeout, fetch).await {
+        Ok(Ok(value)) => Some(value),
+        Ok(Err(e)) => {
+            match BotError::find(&e).and_then(BotError::kind) {
+                Some(ErrorKind::RateLimited) => warn!("⚠️ {} rate limited, skipping this cycle: {}", source, e),
+                _ => warn!("⚠️ {} fetch failed: {}", source, e),
+            }
+            None
+        }
+        Err(_) => {
+            warn!("⚠️ {} fetch timed out after {:?}", source, timeout);
+            None
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rust_decimal_macros::dec;
+
+    #[tokio::test]
+    async fn test_slow_source_does_not_block_others() {
+        let timeouts = SourceTimeouts {
+            exchange: Duration::from_millis(200),
+            coingecko: Duration::from_millis(50),
+        };
+        let started = std::time::Instant::now();
+
+        let snapshot = fetch_snapshot(
+            async { Ok(dec!(42000)) },
+            Some(async {
+                tokio::time::sleep(Duration::from_secs(5)).await;
+                Err(anyhow::anyhow!("unreachable"))
+            }),
+            None::<std::future::Ready<Result<BookTicker>>>,
+            timeouts,
+        ).await;
+
+        assert_eq!(snapshot.price, Some(dec!(42000)));
+        assert!(snapshot.market.is_none());
+        assert!(started.elapsed() < Duration::from_secs(1));
+    }
+}
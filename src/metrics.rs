@@ -0,0 +1,150 @@
+use anyhow::Result;
+use axum::http::StatusCode;
+use axum::response::{Html, IntoResponse};
+use axum::{routing::get, Router};
+use std::collections::BTreeMap;
+use std::fmt::Write;
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+use tracing::{error, info};
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum MetricKind {
+    Gauge,
+    Counter,
+}
+
+#[derive(Debug, Clone)]
+struct Metric {
+    kind: MetricKind,
+    help: &'static str,
+    value: f64,
+}
+
+/// In-process metrics registry rendered in the Prometheus text format, keyed by name and
+/// rendered label set (empty for unlabeled metrics). Cheap to clone; clones share the same values.
+#[derive(Debug, Clone, Default)]
+pub struct Metrics {
+    values: Arc<Mutex<BTreeMap<(&'static str, String), Metric>>>,
+}
+
+impl Metrics {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn set_gauge(&self, name: &'static str, help: &'static str, value: f64) {
+        self.values.lock().unwrap().insert((name, String::new()), Metric { kind: MetricKind::Gauge, help, value });
+    }
+
+    /// Set one series of a gauge, e.g. `bot_cycle_stage_seconds{stage="ai"}`
+    pub fn set_labeled_gauge(&self, name: &'static str, help: &'static str, labels: &[(&str, &str)], value: f64) {
+        self.values.lock().unwrap().insert((name, render_labels(labels)), Metric { kind: MetricKind::Gauge, help, value });
+    }
+
+    pub fn inc_counter(&self, name: &'static str, help: &'static str) {
+        self.inc_labeled_counter(name, help, &[]);
+    }
+
+    /// Increment one series of a counter, e.g. `bot_entries_skipped_total{reason="limit"}`
+    pub fn inc_labeled_counter(&self, name: &'static str, help: &'static str, labels: &[(&str, &str)]) {
+        self.values
+            .lock()
+            .unwrap()
+            .entry((name, render_labels(labels)))
+            .or_insert(Metric { kind: MetricKind::Counter, help, value: 0.0 })
+            .value += 1.0;
+    }
+
+    #[allow(dead_code)]
+    pub fn get(&self, name: &str) -> Option<f64> {
+        self.values.lock().unwrap().iter()
+            .find(|((n, labels), _)| *n == name && labels.is_empty())
+            .map(|(_, m)| m.value)
+    }
+
+    pub fn render(&self) -> String {
+        let mut out = String::new();
+        let mut previous = None;
+        for ((name, labels), metric) in self.values.lock().unwrap().iter() {
+            // HELP and TYPE once per metric, ahead of all its series
+            if previous != Some(*name) {
+                let kind = match metric.kind {
+                    MetricKind::Gauge => "gauge",
+                    MetricKind::Counter => "counter",
+                };
+                let _ = writeln!(out, "# HELP {} {}", name, metric.help);
+                let _ = writeln!(out, "# TYPE {} {}", name, kind);
+                previous = Some(*name);
+            }
+            if labels.is_empty() {
+                let _ = writeln!(out, "{} {}", name, metric.value);
+            } else {
+                let _ = writeln!(out, "{}{{{}}} {}", name, labels, metric.value);
+            }
+        }
+        out
+    }
+
+    /// Serve `GET /metrics` on `addr` in the background, the latest HTML report at `GET /`
+    /// and the `control` routes
+    pub async fn serve(&self, addr: &str, html_report: PathBuf, control: Router) -> Result<()> {
+        let metrics = self.clone();
+        let app = Router::new()
+            .route("/metrics", get(move || {
+                let metrics = metrics.clone();
+                async move { metrics.render() }
+            }))
+            .route("/", get(move || {
+                let path = html_report.clone();
+                async move {
+                    match tokio::fs::read_to_string(&path).await {
+                        Ok(html) => Html(html).into_response(),
+                        Err(_) => (StatusCode::NOT_FOUND, "No report written yet").into_response(),
+                    }
+                }
+            }))
+            .merge(control);
+
+        let listener = tokio::net::TcpListener::bind(addr).await?;
+        let local = listener.local_addr()?;
+        info!("📈 Metrics available at http://{}/metrics, report at http://{}/", local, local);
+        tokio::spawn(async move {
+            if let Err(e) = axum::serve(listener, app).await {
+                error!("❌ Metrics server stopped: {}", e);
+            }
+        });
+        Ok(())
+    }
+}
+
+/// `key="value"` pairs joined by commas, with the values escaped
+fn render_labels(labels: &[(&str, &str)]) -> String {
+    labels.iter()
+        .map(|(key, value)| format!("{}=\"{}\"", key, value.replace('\\', "\\\\").replace('"', "\\\"")))
+        .collect::<Vec<_>>()
+        .join(",")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_render() {
+        let metrics = Metrics::new();
+        metrics.set_gauge("bot_price", "Last price", 42000.5);
+        metrics.inc_counter("bot_cycles_total", "Loop iterations");
+        metrics.inc_counter("bot_cycles_total", "Loop iterations");
+        metrics.inc_labeled_counter("bot_skips_total", "Skips", &[("reason", "limit")]);
+        metrics.inc_labeled_counter("bot_skips_total", "Skips", &[("reason", "risk")]);
+        metrics.inc_labeled_counter("bot_skips_total", "Skips", &[("reason", "limit")]);
+
+        let text = metrics.render();
+        assert!(text.contains("# TYPE bot_price gauge\nbot_price 42000.5\n"));
+        assert!(text.contains("# TYPE bot_cycles_total counter\nbot_cycles_total 2\n"));
+        assert!(text.contains("# TYPE bot_skips_total counter\n\
+            bot_skips_total{reason=\"limit\"} 2\nbot_skips_total{reason=\"risk\"} 1\n"));
+        assert_eq!(metrics.get("bot_cycles_total"), Some(2.0));
+    }
+}
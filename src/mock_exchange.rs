@@ -0,0 +1,234 @@
+use crate::error::BotError;
+use crate::exchange::Exchange;
+use crate::models::{Balance, Fill, Kline, Order, OrderSide, OrderType};
+use crate::symbols;
+use anyhow::Result;
+use reqwest::StatusCode;
+use rust_decimal::Decimal;
+use rust_decimal_macros::dec;
+use std::collections::{HashMap, HashSet};
+use std::sync::Mutex;
+
+/// How a scripted order goes wrong
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum OrderFault {
+    /// The request times out; nothing is executed
+    Timeout,
+    /// The exchange refuses the order, as for an insufficient balance
+    Rejected,
+    /// Only this fraction of the quantity fills
+    PartialFill(Decimal),
+}
+
+/// Scripted [`Exchange`] for tests: each `get_price` call returns the next price of the
+/// path (the last one repeats once it runs out), market orders fill at the latest price,
+/// and faults are injected at chosen price calls and orders.
+pub struct MockExchange {
+    symbol: String,
+    fee_percent: Decimal,
+    state: Mutex<MockState>,
+}
+
+#[derive(Default)]
+struct MockState {
+    prices: Vec<Decimal>,
+    price_calls: usize,
+    price_timeouts: HashSet<usize>,
+    order_calls: usize,
+    order_faults: HashMap<usize, OrderFault>,
+    balances: HashMap<String, Balance>,
+    orders: Vec<Order>,
+}
+
+impl MockExchange {
+    /// An exchange for `symbol` holding `quote_balance` of its quote asset
+    pub fn new(symbol: &str, quote_balance: Decimal) -> Self {
+        let quote = symbols::lookup(symbol).quote;
+        let balances = HashMap::from([(quote.clone(), Balance { asset: quote, free: quote_balance, locked: Decimal::ZERO })]);
+        Self {
+            symbol: symbol.to_string(),
+            fee_percent: dec!(0.1),
+            state: Mutex::new(MockState { balances, ..Default::default() }),
+        }
+    }
+
+    pub fn with_prices(self, prices: impl IntoIterator<Item = Decimal>) -> Self {
+        self.state.lock().unwrap().prices = prices.into_iter().collect();
+        self
+    }
+
+    /// Commission charged in the quote asset (default 0.1%)
+    pub fn with_fee_percent(mut self, fee_percent: Decimal) -> Self {
+        self.fee_percent = fee_percent;
+        self
+    }
+
+    /// Make the `call`-th price request (from 0) time out; its price is skipped
+    pub fn timeout_at(self, call: usize) -> Self {
+        self.state.lock().unwrap().price_timeouts.insert(call);
+        self
+    }
+
+    /// Make the `order`-th order (from 0) fail with `fault`
+    pub fn fail_order(self, order: usize, fault: OrderFault) -> Self {
+        self.state.lock().unwrap().order_faults.insert(order, fault);
+        self
+    }
+
+    /// Orders that executed, oldest first; failed ones are not kept
+    pub fn orders(&self) -> Vec<Order> {
+        self.state.lock().unwrap().orders.clone()
+    }
+
+    pub fn free_balance(&self, asset: &str) -> Decimal {
+        self.state.lock().unwrap().balances.get(asset).map_or(Decimal::ZERO, |b| b.free)
+    }
+
+    fn fill(&self, symbol: &str, side: OrderSide, order_type: OrderType, quantity: Decimal) -> Result<Order> {
+        let mut state = self.state.lock().unwrap();
+        let number = state.order_calls;
+        state.order_calls += 1;
+        let fault = state.order_faults.get(&number).copied();
+        let qty = match fault {
+            Some(OrderFault::Timeout) => return Err(timeout()),
+            Some(OrderFault::Rejected) => return Err(rejected("Order rejected by the scenario")),
+            Some(OrderFault::PartialFill(fraction)) => quantity * fraction,
+            None => quantity,
+        };
+        let price = state.price_calls.checked_sub(1)
+            .and_then(|last_call| state.prices.get(last_call).or(state.prices.last())).copied()
+            .ok_or_else(|| anyhow::anyhow!("MockExchange: no price yet - call get_price first"))?;
+
+        let symbols::SymbolInfo { base, quote, .. } = symbols::lookup(symbol);
+        let value = qty * price;
+        let fee = value * self.fee_percent / dec!(100);
+        let (base_delta, quote_delta) = match side {
+            OrderSide::Buy => (qty, -value - fee),
+            OrderSide::Sell => (-qty, value - fee),
+        };
+        for (asset, delta) in [(&base, base_delta), (&quote, quote_delta)] {
+            if state.balances.get(asset).map_or(Decimal::ZERO, |b| b.free) + delta < Decimal::ZERO {
+                return Err(rejected("Account has insufficient balance for requested action."));
+            }
+        }
+        for (asset, delta) in [(base, base_delta), (quote.clone(), quote_delta)] {
+            state.balances.entry(asset.clone())
+                .or_insert(Balance { asset, free: Decimal::ZERO, locked: Decimal::ZERO })
+                .free += delta;
+        }
+
+        let order = Order {
+            symbol: symbol.to_string(),
+            order_id: state.orders.len() as i64 + 1,
+            client_order_id: format!("mock_{}", state.orders.len() + 1),
+            price: "0".to_string(),
+            orig_qty: quantity.to_string(),
+            executed_qty: qty.to_string(),
+            status: if qty < quantity { "EXPIRED" } else { "FILLED" }.to_string(),
+            side,
+            order_type,
+            fills: vec![Fill { price, qty, commission: fee, commission_asset: quote }],
+            avg_price: None,
+        };
+        state.orders.push(order.clone());
+        Ok(order)
+    }
+}
+
+fn timeout() -> anyhow::Error {
+    BotError::exchange(StatusCode::REQUEST_TIMEOUT, "Request timed out (scripted)").into()
+}
+
+fn rejected(message: &str) -> anyhow::Error {
+    BotError::exchange(StatusCode::BAD_REQUEST, &format!(r#"{{"code":-2010,"msg":"{}"}}"#, message)).into()
+}
+
+impl Exchange for MockExchange {
+    async fn get_price(&self, symbol: &str) -> Result<Decimal> {
+        if symbol != self.symbol {
+            return Err(rejected("Invalid symbol."));
+        }
+        let mut state = self.state.lock().unwrap();
+        let call = state.price_calls;
+        state.price_calls += 1;
+        if state.price_timeouts.contains(&call) {
+            return Err(timeout());
+        }
+        state.prices.get(call).or(state.prices.last()).copied()
+            .ok_or_else(|| anyhow::anyhow!("MockExchange: empty price path"))
+    }
+
+    async fn get_balance(&self) -> Result<HashMap<String, Balance>> {
+        Ok(self.state.lock().unwrap().balances.clone())
+    }
+
+    async fn place_order(
+        &self,
+        symbol: &str,
+        side: OrderSide,
+        order_type: OrderType,
+        quantity: Decimal,
+        _price: Option<Decimal>,
+    ) -> Result<Order> {
+        self.fill(symbol, side, order_type, quantity)
+    }
+
+    async fn place_position_order(
+        &self,
+        _position_id: &str,
+        symbol: &str,
+        side: OrderSide,
+        order_type: OrderType,
+        quantity: Decimal,
+        _price: Option<Decimal>,
+    ) -> Result<Order> {
+        self.fill(symbol, side, order_type, quantity)
+    }
+
+    /// One candle per price served so far, oldest first
+    async fn get_klines(&self, _symbol: &str, _interval: &str, limit: u32) -> Result<Vec<Kline>> {
+        let state = self.state.lock().unwrap();
+        let served = &state.prices[..state.price_calls.min(state.prices.len())];
+        let start = served.len().saturating_sub(limit as usize);
+        Ok(served.iter().enumerate().skip(start).map(|(i, close)| {
+            let open = if i > 0 { served[i - 1] } else { *close };
+            let open_time = i as i64 * 3_600_000;
+            Kline {
+                open_time,
+                open,
+                high: open.max(*close),
+                low: open.min(*close),
+                close: *close,
+                volume: Decimal::ONE,
+                close_time: open_time + 3_599_999,
+            }
+        }).collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::error::ErrorKind;
+
+    #[tokio::test]
+    async fn test_path_balances_and_klines() {
+        let exchange = MockExchange::new("BTCUSDT", dec!(100)).with_prices([dec!(10), dec!(12)]);
+        assert!(exchange.place_order("BTCUSDT", OrderSide::Buy, OrderType::Market, dec!(1), None).await.is_err());
+        assert_eq!(exchange.get_price("BTCUSDT").await.unwrap(), dec!(10));
+
+        // Orders beyond the balance are refused like Binance's -2010
+        let error = exchange.place_order("BTCUSDT", OrderSide::Buy, OrderType::Market, dec!(10), None).await.unwrap_err();
+        assert_eq!(error.downcast_ref::<BotError>().unwrap().kind(), Some(ErrorKind::Rejected));
+        exchange.place_order("BTCUSDT", OrderSide::Buy, OrderType::Market, dec!(5), None).await.unwrap();
+        assert_eq!(exchange.free_balance("BTC"), dec!(5));
+        assert_eq!(exchange.free_balance("USDT"), dec!(49.95));
+
+        // The path holds its last price
+        assert_eq!(exchange.get_price("BTCUSDT").await.unwrap(), dec!(12));
+        assert_eq!(exchange.get_price("BTCUSDT").await.unwrap(), dec!(12));
+        let klines = exchange.get_klines("BTCUSDT", "1h", 10).await.unwrap();
+        assert_eq!(klines.iter().map(|k| k.close).collect::<Vec<_>>(), [dec!(10), dec!(12)]);
+        assert_eq!(klines[1].open, dec!(10));
+    }
+}
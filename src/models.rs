@@ -9,7 +9,7 @@ pub struct Balance {
     pub locked: Decimal,
 }
 
-#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(rename_all = "UPPERCASE")]
 pub enum OrderSide {
     Buy,
@@ -25,11 +25,12 @@ impl fmt::Display for OrderSide {
     }
 }
 
-#[derive(Debug, Clone, Copy, Serialize, Deserialize)]
-#[serde(rename_all = "UPPERCASE")]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
 pub enum OrderType {
     Market,
     Limit,
+    LimitMaker,
     StopLoss,
     StopLossLimit,
     TakeProfit,
@@ -41,6 +42,7 @@ impl fmt::Display for OrderType {
         match self {
             OrderType::Market => write!(f, "MARKET"),
             OrderType::Limit => write!(f, "LIMIT"),
+            OrderType::LimitMaker => write!(f, "LIMIT_MAKER"),
             OrderType::StopLoss => write!(f, "STOP_LOSS"),
             OrderType::StopLossLimit => write!(f, "STOP_LOSS_LIMIT"),
             OrderType::TakeProfit => write!(f, "TAKE_PROFIT"),
@@ -62,6 +64,123 @@ pub struct Order {
     pub side: OrderSide,
     #[serde(rename = "type")]
     pub order_type: OrderType,
+    /// Fills of a FULL order response; empty for ACK responses and futures orders
+    #[serde(default)]
+    pub fills: Vec<Fill>,
+    /// Average fill price of a futures RESULT response
+    #[serde(default)]
+    pub avg_price: Option<Decimal>,
+}
+
+/// Price, quantity and commission (in the quote asset) an order actually filled with
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Execution {
+    pub price: Decimal,
+    pub qty: Decimal,
+    pub fee: Decimal,
+}
+
+/// One fill of an order, with the commission charged for it
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct Fill {
+    pub price: Decimal,
+    pub qty: Decimal,
+    pub commission: Decimal,
+    pub commission_asset: String,
+}
+
+impl Order {
+    /// Volume-weighted price of the fills, else the futures `avgPrice` or the order price
+    pub fn average_fill_price(&self) -> Option<Decimal> {
+        let filled: Decimal = self.fills.iter().map(|f| f.qty).sum();
+        if filled > Decimal::ZERO {
+            return Some(self.fills.iter().map(|f| f.price * f.qty).sum::<Decimal>() / filled);
+        }
+        [self.avg_price, self.price.parse().ok()].into_iter().flatten().find(|p| *p > Decimal::ZERO)
+    }
+
+    /// Quantity the exchange reports as executed
+    pub fn executed_quantity(&self) -> Option<Decimal> {
+        self.executed_qty.parse().ok().filter(|q| *q > Decimal::ZERO)
+    }
+
+    /// What the order filled with; the expected `price` and `qty` stand in for whatever
+    /// the response leaves out
+    pub fn execution(&self, base: &str, quote: &str, price: Decimal, qty: Decimal, fee_percent: Decimal) -> Execution {
+        let price = self.average_fill_price().unwrap_or(price);
+        let qty = self.executed_quantity().unwrap_or(qty);
+        Execution { price, qty, fee: self.fee_in_quote(base, quote, price * qty, fee_percent) }
+    }
+
+    /// Commission paid for this order, valued in `quote`. Commissions taken in the base
+    /// asset are valued at the fill price; those in a third asset (e.g. BNB), and orders
+    /// without fills, are estimated at `fee_percent` of the notional.
+    pub fn fee_in_quote(&self, base: &str, quote: &str, notional: Decimal, fee_percent: Decimal) -> Decimal {
+        if self.fills.is_empty() {
+            return notional * fee_percent / Decimal::ONE_HUNDRED;
+        }
+        self.fills.iter().map(|fill| {
+            if fill.commission_asset == quote {
+                fill.commission
+            } else if fill.commission_asset == base {
+                fill.commission * fill.price
+            } else {
+                fill.price * fill.qty * fee_percent / Decimal::ONE_HUNDRED
+            }
+        }).sum()
+    }
+}
+
+/// An order resting on the book (from `GET /api/v3/openOrders`)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct OpenOrder {
+    pub symbol: String,
+    pub order_id: i64,
+    pub price: Decimal,
+    pub orig_qty: Decimal,
+    pub executed_qty: Decimal,
+    pub status: String,
+    pub side: OrderSide,
+    #[serde(rename = "type")]
+    pub order_type: OrderType,
+    /// Creation time in milliseconds since the epoch
+    pub time: i64,
+}
+
+impl OpenOrder {
+    pub fn age(&self, now: chrono::DateTime<chrono::Utc>) -> chrono::Duration {
+        now - chrono::DateTime::from_timestamp_millis(self.time).unwrap_or(now)
+    }
+}
+
+/// Resting bids and asks as (price, quantity), best first (from `GET /api/v3/depth`)
+#[derive(Debug, Clone, Default, Deserialize)]
+pub struct OrderBookDepth {
+    pub bids: Vec<(Decimal, Decimal)>,
+    pub asks: Vec<(Decimal, Decimal)>,
+}
+
+/// Best bid/ask on the order book (from `GET /api/v3/ticker/bookTicker`)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "camelCase")]
+pub struct BookTicker {
+    pub bid_price: Decimal,
+    pub bid_qty: Decimal,
+    pub ask_price: Decimal,
+    pub ask_qty: Decimal,
+}
+
+impl BookTicker {
+    /// Bid/ask spread as a percentage of the mid price
+    pub fn spread_percent(&self) -> Decimal {
+        let mid = (self.bid_price + self.ask_price) / Decimal::TWO;
+        if mid.is_zero() {
+            return Decimal::ZERO;
+        }
+        (self.ask_price - self.bid_price) / mid * Decimal::ONE_HUNDRED
+    }
 }
 
 #[allow(dead_code)]
@@ -76,7 +195,7 @@ pub struct Kline {
     pub close_time: i64,
 }
 
-#[derive(Debug, Clone, Copy, PartialEq)]
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
 pub enum Signal {
     Buy,
     Sell,
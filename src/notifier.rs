@@ -0,0 +1,133 @@
+use anyhow::{anyhow, Result};
+use serde::Deserialize;
+use tracing::{debug, warn};
+
+/// Telegram Bot API client used for notifications and interactive commands
+pub struct TelegramNotifier {
+    client: reqwest::Client,
+    base_url: String,
+    chat_id: String,
+}
+
+/// A text message received by the bot
+#[derive(Debug, Clone)]
+pub struct IncomingMessage {
+    pub chat_id: String,
+    pub from_id: String,
+    pub from_username: Option<String>,
+    pub text: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct UpdatesResponse {
+    ok: bool,
+    #[serde(default)]
+    result: Vec<Update>,
+}
+
+#[derive(Debug, Deserialize)]
+struct Update {
+    update_id: i64,
+    message: Option<Message>,
+}
+
+#[derive(Debug, Deserialize)]
+struct Message {
+    chat: Chat,
+    from: Option<User>,
+    text: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct Chat {
+    id: i64,
+}
+
+#[derive(Debug, Deserialize)]
+struct User {
+    id: i64,
+    username: Option<String>,
+}
+
+impl TelegramNotifier {
+    pub fn new(bot_token: &str, chat_id: &str) -> Result<Self> {
+        let client = reqwest::Client::builder()
+            .timeout(std::time::Duration::from_secs(30))
+            .build()?;
+
+        Ok(Self {
+            client,
+            base_url: format!("https://api.telegram.org/bot{}", bot_token),
+            chat_id: chat_id.to_string(),
+        })
+    }
+
+    /// Send a plain text message to the configured chat
+    pub async fn send_message(&self, text: &str) -> Result<()> {
+        let url = format!("{}/sendMessage", self.base_url);
+        let body = serde_json::json!({
+            "chat_id": self.chat_id,
+            "text": text,
+        });
+
+        // Errors carry the request URL, which embeds the bot token
+        let response = self.client.post(&url).json(&body).send().await.map_err(|e| e.without_url())?;
+        if !response.status().is_success() {
+            return Err(anyhow!("Telegram sendMessage error: {}", response.status()));
+        }
+
+        debug!("Telegram message sent to chat {}", self.chat_id);
+        Ok(())
+    }
+
+    /// Fetch new messages from the configured chat, starting at `offset`.
+    /// Uses Telegram long polling so the call waits up to `wait_secs` for new updates.
+    /// Returns the offset to pass on the next call along with the messages.
+    pub async fn get_updates(&self, offset: i64, wait_secs: u64) -> Result<(i64, Vec<IncomingMessage>)> {
+        let url = format!(
+            "{}/getUpdates?offset={}&timeout={}",
+            self.base_url, offset, wait_secs
+        );
+
+        let response: UpdatesResponse = self.client
+            .get(&url)
+            .timeout(std::time::Duration::from_secs(wait_secs + 10))
+            .send()
+            .await
+            .map_err(|e| e.without_url())?
+            .json()
+            .await
+            .map_err(|e| e.without_url())?;
+
+        if !response.ok {
+            warn!("⚠️ Telegram getUpdates returned ok=false");
+            return Ok((offset, Vec::new()));
+        }
+
+        let next_offset = response
+            .result
+            .iter()
+            .map(|u| u.update_id + 1)
+            .max()
+            .unwrap_or(offset);
+
+        let messages = response
+            .result
+            .into_iter()
+            .filter_map(|u| {
+                let message = u.message?;
+                let text = message.text?;
+                let from = message.from;
+                Some(IncomingMessage {
+                    chat_id: message.chat.id.to_string(),
+                    from_id: from.as_ref().map(|f| f.id.to_string()).unwrap_or_default(),
+                    from_username: from.and_then(|f| f.username),
+                    text,
+                })
+            })
+            .filter(|m| m.chat_id == self.chat_id)
+            .collect();
+
+        Ok((next_offset, messages))
+    }
+}
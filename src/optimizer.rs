@@ -0,0 +1,327 @@
+use crate::config::Config;
+use crate::error::BotError;
+use crate::state_store::StateStore;
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+use tracing::{info, warn};
+
+/// Taker fee charged on each side of a backtested trade
+pub const FEE_RATE: f64 = 0.001;
+/// Fitness is the return minus this much of the max drawdown, so steady curves beat lucky ones
+pub const DRAWDOWN_PENALTY: f64 = 0.5;
+/// Best genomes kept per symbol across runs
+pub const HALL_OF_FAME_SIZE: usize = 10;
+/// Fittest genomes copied unchanged into the next generation
+const ELITE: usize = 2;
+const TOURNAMENT_SIZE: usize = 3;
+
+const SHORT_RANGE: (u32, u32) = (2, 50);
+const LONG_RANGE: (u32, u32) = (5, 200);
+const STOP_LOSS_RANGE: (f64, f64) = (0.5, 15.0);
+const TAKE_PROFIT_RANGE: (f64, f64) = (0.5, 30.0);
+
+/// SMA crossover parameters with a stop-loss and take-profit, as the bot trades them
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct Genome {
+    pub sma_short: u32,
+    pub sma_long: u32,
+    /// Percent below entry, as a positive number
+    pub stop_loss_percent: f64,
+    pub take_profit_percent: f64,
+}
+
+impl Genome {
+    fn random(rng: &mut impl Rng) -> Self {
+        let mut genome = Self {
+            sma_short: rng.gen_range(SHORT_RANGE.0..=SHORT_RANGE.1),
+            sma_long: rng.gen_range(LONG_RANGE.0..=LONG_RANGE.1),
+            stop_loss_percent: rng.gen_range(STOP_LOSS_RANGE.0..=STOP_LOSS_RANGE.1),
+            take_profit_percent: rng.gen_range(TAKE_PROFIT_RANGE.0..=TAKE_PROFIT_RANGE.1),
+        };
+        genome.repair();
+        genome
+    }
+
+    /// Each gene comes from either parent
+    fn crossover(&self, other: &Self, rng: &mut impl Rng) -> Self {
+        let mut child = Self {
+            sma_short: if rng.gen_bool(0.5) { self.sma_short } else { other.sma_short },
+            sma_long: if rng.gen_bool(0.5) { self.sma_long } else { other.sma_long },
+            stop_loss_percent: if rng.gen_bool(0.5) { self.stop_loss_percent } else { other.stop_loss_percent },
+            take_profit_percent: if rng.gen_bool(0.5) { self.take_profit_percent } else { other.take_profit_percent },
+        };
+        child.repair();
+        child
+    }
+
+    /// Nudge each gene with probability `rate` by up to a fifth of its range
+    fn mutate(&mut self, rate: f64, rng: &mut impl Rng) {
+        let nudge_int = |value: u32, (lo, hi): (u32, u32), rng: &mut _| {
+            let step = ((hi - lo) / 5).max(1) as i64;
+            (value as i64 + Rng::gen_range(rng, -step..=step)) as u32
+        };
+        let nudge_float = |value: f64, (lo, hi): (f64, f64), rng: &mut _| {
+            let step = (hi - lo) / 5.0;
+            value + Rng::gen_range(rng, -step..=step)
+        };
+        if rng.gen_bool(rate) {
+            self.sma_short = nudge_int(self.sma_short, SHORT_RANGE, rng);
+        }
+        if rng.gen_bool(rate) {
+            self.sma_long = nudge_int(self.sma_long, LONG_RANGE, rng);
+        }
+        if rng.gen_bool(rate) {
+            self.stop_loss_percent = nudge_float(self.stop_loss_percent, STOP_LOSS_RANGE, rng);
+        }
+        if rng.gen_bool(rate) {
+            self.take_profit_percent = nudge_float(self.take_profit_percent, TAKE_PROFIT_RANGE, rng);
+        }
+        self.repair();
+    }
+
+    /// Clamp to the search space, keep the short SMA shorter and round percents to 0.1
+    fn repair(&mut self) {
+        self.sma_short = self.sma_short.clamp(SHORT_RANGE.0, SHORT_RANGE.1);
+        self.sma_long = self.sma_long.clamp(LONG_RANGE.0, LONG_RANGE.1).max(self.sma_short + 1);
+        let round = |v: f64, (lo, hi): (f64, f64)| (v.clamp(lo, hi) * 10.0).round() / 10.0;
+        self.stop_loss_percent = round(self.stop_loss_percent, STOP_LOSS_RANGE);
+        self.take_profit_percent = round(self.take_profit_percent, TAKE_PROFIT_RANGE);
+    }
+
+    /// `COMPETITION_BOTS` entry for racing this genome against others
+    pub fn competition_spec(&self) -> String {
+        format!("sma:{}/{}", self.sma_short, self.sma_long)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub struct BacktestResult {
+    pub return_percent: f64,
+    pub max_drawdown_percent: f64,
+    pub trades: u32,
+}
+
+impl BacktestResult {
+    pub fn fitness(&self) -> f64 {
+        self.return_percent - DRAWDOWN_PENALTY * self.max_drawdown_percent
+    }
+}
+
+/// All-in/all-out long-only backtest on closes, checking exits on each close
+pub fn backtest(genome: &Genome, closes: &[f64]) -> BacktestResult {
+    let (short, long) = (genome.sma_short as usize, genome.sma_long as usize);
+    let mut prefix = Vec::with_capacity(closes.len() + 1);
+    prefix.push(0.0);
+    for close in closes {
+        prefix.push(prefix.last().copied().unwrap_or(0.0) + close);
+    }
+    let sma = |i: usize, n: usize| (prefix[i + 1] - prefix[i + 1 - n]) / n as f64;
+
+    let (mut cash, mut qty, mut entry) = (1.0, 0.0, None::<f64>);
+    let (mut peak, mut max_drawdown, mut trades) = (1.0_f64, 0.0_f64, 0);
+    for (i, &price) in closes.iter().enumerate().skip(long.saturating_sub(1)) {
+        let bullish = sma(i, short) > sma(i, long);
+        if let Some(entry_price) = entry {
+            let change = (price - entry_price) / entry_price * 100.0;
+            if change <= -genome.stop_loss_percent || change >= genome.take_profit_percent || !bullish {
+                cash = qty * price * (1.0 - FEE_RATE);
+                qty = 0.0;
+                entry = None;
+                trades += 1;
+            }
+        } else if bullish {
+            qty = cash * (1.0 - FEE_RATE) / price;
+            cash = 0.0;
+            entry = Some(price);
+            trades += 1;
+        }
+        let equity = cash + qty * price;
+        peak = peak.max(equity);
+        max_drawdown = max_drawdown.max((peak - equity) / peak * 100.0);
+    }
+    let equity = cash + qty * closes.last().copied().unwrap_or(0.0);
+    BacktestResult { return_percent: (equity - 1.0) * 100.0, max_drawdown_percent: max_drawdown, trades }
+}
+
+/// Population size, generations and operator rates for [`evolve`]
+#[derive(Debug, Clone, Copy)]
+pub struct GaSettings {
+    pub population: usize,
+    pub generations: usize,
+    pub crossover_rate: f64,
+    pub mutation_rate: f64,
+}
+
+impl GaSettings {
+    pub fn from_config(config: &Config) -> Result<Self> {
+        if config.ga_population < ELITE + 2 {
+            return Err(BotError::config(format!("GA_POPULATION must be at least {}", ELITE + 2)).into());
+        }
+        if config.ga_generations == 0 {
+            return Err(BotError::config("GA_GENERATIONS must be at least 1").into());
+        }
+        for (name, rate) in [("GA_CROSSOVER_RATE", config.ga_crossover_rate), ("GA_MUTATION_RATE", config.ga_mutation_rate)] {
+            if !(0.0..=1.0).contains(&rate) {
+                return Err(BotError::config(format!("{} must be between 0 and 1, got {}", name, rate)).into());
+            }
+        }
+        Ok(Self {
+            population: config.ga_population,
+            generations: config.ga_generations,
+            crossover_rate: config.ga_crossover_rate,
+            mutation_rate: config.ga_mutation_rate,
+        })
+    }
+}
+
+/// A genome and how it did on the backtest data
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Scored {
+    pub genome: Genome,
+    pub result: BacktestResult,
+}
+
+/// Breed genomes over `closes` for the configured generations; returns the best distinct
+/// genomes seen in any generation, fittest first
+pub fn evolve(settings: &GaSettings, closes: &[f64], rng: &mut impl Rng) -> Vec<Scored> {
+    let mut population: Vec<Genome> = (0..settings.population).map(|_| Genome::random(rng)).collect();
+    let mut hall: Vec<Scored> = Vec::new();
+
+    for generation in 1..=settings.generations {
+        let mut scored: Vec<Scored> = population.iter()
+            .map(|genome| Scored { genome: *genome, result: backtest(genome, closes) })
+            .collect();
+        scored.sort_by(|a, b| b.result.fitness().total_cmp(&a.result.fitness()));
+        for candidate in &scored {
+            if !hall.iter().any(|h| h.genome == candidate.genome) {
+                hall.push(candidate.clone());
+            }
+        }
+        hall.sort_by(|a, b| b.result.fitness().total_cmp(&a.result.fitness()));
+        hall.truncate(HALL_OF_FAME_SIZE);
+        info!("🧬 Generation {}/{}: best {} ({:+.2}%, max DD {:.2}%)", generation, settings.generations,
+            scored[0].genome.competition_spec(), scored[0].result.return_percent, scored[0].result.max_drawdown_percent);
+
+        let mut next: Vec<Genome> = scored.iter().take(ELITE).map(|s| s.genome).collect();
+        while next.len() < settings.population {
+            let first = tournament(&scored, rng);
+            let mut child = if rng.gen_bool(settings.crossover_rate) {
+                first.crossover(tournament(&scored, rng), rng)
+            } else {
+                *first
+            };
+            child.mutate(settings.mutation_rate, rng);
+            next.push(child);
+        }
+        population = next;
+    }
+    hall
+}
+
+/// Fittest of a few random picks
+fn tournament<'a>(scored: &'a [Scored], rng: &mut impl Rng) -> &'a Genome {
+    let best = (0..TOURNAMENT_SIZE)
+        .map(|_| &scored[rng.gen_range(0..scored.len())])
+        .max_by(|a, b| a.result.fitness().total_cmp(&b.result.fitness()))
+        .expect("tournament size is non-zero");
+    &best.genome
+}
+
+/// A hall-of-fame genome with the run that found it
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HallOfFameEntry {
+    pub symbol: String,
+    pub genome: Genome,
+    pub result: BacktestResult,
+    /// Hourly candles the backtest covered
+    pub candles: usize,
+    pub found: DateTime<Utc>,
+}
+
+/// Best genomes per symbol, kept in the state backend across runs
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct HallOfFame {
+    pub entries: Vec<HallOfFameEntry>,
+}
+
+impl HallOfFame {
+    pub fn load(store: &StateStore) -> Self {
+        store.read()
+            .and_then(|content| Ok(content.map(|c| serde_json::from_str::<HallOfFame>(&c)).transpose()?))
+            .unwrap_or_else(|e| {
+                warn!("⚠️ Failed to read hall of fame: {}", e);
+                None
+            })
+            .unwrap_or_default()
+    }
+
+    pub fn save(&self, store: &StateStore) {
+        if let Ok(json) = serde_json::to_string_pretty(self)
+            && let Err(e) = store.write(&json) {
+            warn!("Failed to save hall of fame: {}", e);
+        }
+    }
+
+    /// Add a run's results, keeping the fittest distinct genomes for `symbol`
+    pub fn merge(&mut self, symbol: &str, candles: usize, results: &[Scored]) {
+        let now = Utc::now();
+        for scored in results {
+            self.entries.retain(|e| e.symbol != symbol || e.genome != scored.genome);
+            self.entries.push(HallOfFameEntry {
+                symbol: symbol.to_string(),
+                genome: scored.genome,
+                result: scored.result,
+                candles,
+                found: now,
+            });
+        }
+        self.entries.sort_by(|a, b| b.result.fitness().total_cmp(&a.result.fitness()));
+        let mut kept = 0;
+        self.entries.retain(|e| {
+            if e.symbol != symbol {
+                return true;
+            }
+            kept += 1;
+            kept <= HALL_OF_FAME_SIZE
+        });
+    }
+
+    pub fn for_symbol<'a>(&'a self, symbol: &'a str) -> impl Iterator<Item = &'a HallOfFameEntry> + 'a {
+        self.entries.iter().filter(move |e| e.symbol == symbol)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::SeedableRng;
+    use rand::rngs::StdRng;
+
+    #[test]
+    fn test_evolution_finds_and_keeps_profitable_genomes() {
+        // Slow uptrend with regular pullbacks
+        let closes: Vec<f64> = (0..600)
+            .map(|i| 100.0 + i as f64 * 0.2 + 8.0 * (i as f64 / 15.0).sin())
+            .collect();
+        let trend = Genome { sma_short: 5, sma_long: 40, stop_loss_percent: 15.0, take_profit_percent: 30.0 };
+        let result = backtest(&trend, &closes);
+        assert!(result.trades > 0);
+        assert!(result.return_percent > 0.0);
+
+        let settings = GaSettings { population: 20, generations: 8, crossover_rate: 0.7, mutation_rate: 0.2 };
+        let hall = evolve(&settings, &closes, &mut StdRng::seed_from_u64(7));
+        assert!(!hall.is_empty() && hall.len() <= HALL_OF_FAME_SIZE);
+        assert!(hall.windows(2).all(|w| w[0].result.fitness() >= w[1].result.fitness()));
+        assert!(hall.iter().all(|s| s.genome.sma_short < s.genome.sma_long));
+        assert!(hall[0].result.fitness() > 0.0);
+
+        let mut fame = HallOfFame::default();
+        fame.merge("BTCUSDT", closes.len(), &hall);
+        fame.merge("BTCUSDT", closes.len(), &hall);
+        fame.merge("ETHUSDT", closes.len(), &hall[..1]);
+        assert_eq!(fame.for_symbol("BTCUSDT").count(), hall.len());
+        assert_eq!(fame.for_symbol("ETHUSDT").count(), 1);
+    }
+}
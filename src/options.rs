@@ -0,0 +1,223 @@
+use crate::config::Config;
+use crate::error::{BotError, ReqwestExt};
+use anyhow::{anyhow, Result};
+use chrono::NaiveDate;
+use rust_decimal::Decimal;
+use serde::Deserialize;
+use std::collections::BTreeMap;
+use std::time::{Duration, Instant};
+use tracing::{info, warn};
+
+/// Open interest moves slowly; Deribit is asked at most this often
+const REFRESH_INTERVAL: Duration = Duration::from_secs(15 * 60);
+/// Currencies with listed options on Deribit
+const SUPPORTED: [&str; 2] = ["BTC", "ETH"];
+
+/// Positioning in the options market for one currency
+#[derive(Debug, Clone, PartialEq)]
+pub struct OptionsMetrics {
+    pub currency: String,
+    /// Put open interest / call open interest, all expiries
+    pub put_call_ratio: Option<Decimal>,
+    /// Strike where option holders of the nearest expiry collect the least
+    pub max_pain: Option<Decimal>,
+    pub max_pain_expiry: Option<NaiveDate>,
+    pub call_open_interest: Decimal,
+    pub put_open_interest: Decimal,
+}
+
+impl OptionsMetrics {
+    /// One line per metric for the AI prompt
+    pub fn format_for_ai(&self, current_price: Decimal) -> String {
+        let mut lines = Vec::new();
+        if let Some(ratio) = self.put_call_ratio {
+            let bias = if ratio > Decimal::ONE { "more puts - hedging/bearish" } else { "more calls - bullish" };
+            lines.push(format!("- Put/Call Ratio (open interest): {:.2} ({})", ratio, bias));
+        }
+        if let (Some(pain), Some(expiry)) = (self.max_pain, self.max_pain_expiry) {
+            let distance = if current_price > Decimal::ZERO {
+                (pain - current_price) / current_price * Decimal::ONE_HUNDRED
+            } else {
+                Decimal::ZERO
+            };
+            lines.push(format!("- Max Pain ({} expiry): ${:.0} ({:+.1}% from price)", expiry.format("%d %b"), pain, distance));
+        }
+        lines.push(format!("- Open Interest: {:.0} calls / {:.0} puts ({})",
+            self.call_open_interest, self.put_open_interest, self.currency));
+        lines.join("\n")
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct BookSummaryResponse {
+    result: Vec<BookSummary>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+struct BookSummary {
+    instrument_name: String,
+    #[serde(default)]
+    open_interest: f64,
+}
+
+/// `BTC-27JUN25-60000-C` -> (expiry, strike, is_call)
+fn parse_instrument(name: &str) -> Option<(NaiveDate, Decimal, bool)> {
+    let mut parts = name.split('-');
+    let (_, expiry, strike, kind) = (parts.next()?, parts.next()?, parts.next()?, parts.next()?);
+    let expiry = NaiveDate::parse_from_str(expiry, "%d%b%y").ok()?;
+    let strike: Decimal = strike.parse().ok()?;
+    match kind {
+        "C" => Some((expiry, strike, true)),
+        "P" => Some((expiry, strike, false)),
+        _ => None,
+    }
+}
+
+/// Put/call ratio over every expiry and max pain of the nearest one
+fn compute_metrics(currency: &str, summaries: &[BookSummary]) -> OptionsMetrics {
+    // expiry -> strike -> (call OI, put OI)
+    let mut chains: BTreeMap<NaiveDate, BTreeMap<Decimal, (Decimal, Decimal)>> = BTreeMap::new();
+    let (mut calls, mut puts) = (Decimal::ZERO, Decimal::ZERO);
+    for summary in summaries {
+        let Some((expiry, strike, is_call)) = parse_instrument(&summary.instrument_name) else {
+            continue;
+        };
+        let oi = Decimal::try_from(summary.open_interest).unwrap_or_default();
+        let entry = chains.entry(expiry).or_default().entry(strike).or_default();
+        if is_call {
+            entry.0 += oi;
+            calls += oi;
+        } else {
+            entry.1 += oi;
+            puts += oi;
+        }
+    }
+
+    let nearest = chains.iter().next();
+    let max_pain = nearest.and_then(|(_, chain)| {
+        chain.keys()
+            .map(|&settle| {
+                let payout: Decimal = chain.iter()
+                    .map(|(&strike, &(call_oi, put_oi))| {
+                        call_oi * (settle - strike).max(Decimal::ZERO) + put_oi * (strike - settle).max(Decimal::ZERO)
+                    })
+                    .sum();
+                (settle, payout)
+            })
+            .min_by(|a, b| a.1.cmp(&b.1))
+            .map(|(settle, _)| settle)
+    });
+
+    OptionsMetrics {
+        currency: currency.to_string(),
+        put_call_ratio: (calls > Decimal::ZERO).then(|| (puts / calls).round_dp(4)),
+        max_pain,
+        max_pain_expiry: nearest.map(|(expiry, _)| *expiry),
+        call_open_interest: calls,
+        put_open_interest: puts,
+    }
+}
+
+/// Deribit public API (no key needed)
+pub struct DeribitClient {
+    client: reqwest::Client,
+    base_url: String,
+}
+
+impl DeribitClient {
+    pub fn new() -> Result<Self> {
+        Ok(Self {
+            client: reqwest::Client::builder().timeout(Duration::from_secs(15)).build()?,
+            base_url: "https://www.deribit.com/api/v2".to_string(),
+        })
+    }
+
+    pub async fn fetch_metrics(&self, currency: &str) -> Result<OptionsMetrics> {
+        let url = format!("{}/public/get_book_summary_by_currency?currency={}&kind=option", self.base_url, currency);
+        let response = self.client.get(&url).send().await.or_data_source("Deribit")?;
+        let status = response.status();
+        if !status.is_success() {
+            let body = response.text().await.unwrap_or_default();
+            return Err(BotError::data_source("Deribit", status, &body).into());
+        }
+        let body: BookSummaryResponse = response.json().await.or_data_source("Deribit")?;
+        if body.result.is_empty() {
+            return Err(anyhow!("Deribit lists no {} options", currency));
+        }
+        Ok(compute_metrics(currency, &body.result))
+    }
+}
+
+/// Cached options metrics for the traded asset, refreshed every 15 minutes
+pub struct OptionsFeed {
+    client: DeribitClient,
+    currency: String,
+    latest: Option<OptionsMetrics>,
+    fetched: Option<Instant>,
+}
+
+impl OptionsFeed {
+    /// `None` when OPTIONS_DATA_ENABLED is off or Deribit has no options on `base_asset`
+    pub fn from_config(config: &Config, base_asset: &str) -> Result<Option<Self>> {
+        if !config.options_data_enabled {
+            return Ok(None);
+        }
+        if !SUPPORTED.contains(&base_asset) {
+            info!("🎲 No Deribit options for {} - options context disabled", base_asset);
+            return Ok(None);
+        }
+        Ok(Some(Self {
+            client: DeribitClient::new()?,
+            currency: base_asset.to_string(),
+            latest: None,
+            fetched: None,
+        }))
+    }
+
+    /// Latest metrics, fetching new ones when the cache is stale. A failed fetch keeps the
+    /// previous values until the next interval.
+    pub async fn refresh(&mut self) -> Option<&OptionsMetrics> {
+        if self.fetched.is_none_or(|t| t.elapsed() >= REFRESH_INTERVAL) {
+            match self.client.fetch_metrics(&self.currency).await {
+                Ok(metrics) => {
+                    info!("🎲 {} options: P/C {} | max pain {}", self.currency,
+                        metrics.put_call_ratio.map_or("n/a".to_string(), |r| r.round_dp(2).to_string()),
+                        metrics.max_pain.map_or("n/a".to_string(), |p| p.to_string()));
+                    self.latest = Some(metrics);
+                }
+                Err(e) => warn!("⚠️ Failed to fetch Deribit options data: {}", e),
+            }
+            self.fetched = Some(Instant::now());
+        }
+        self.latest.as_ref()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rust_decimal_macros::dec;
+
+    #[test]
+    fn test_put_call_ratio_and_max_pain() {
+        let summary = |name: &str, oi: f64| BookSummary { instrument_name: name.to_string(), open_interest: oi };
+        let summaries = vec![
+            summary("BTC-7JUN25-60000-C", 100.0),
+            summary("BTC-7JUN25-65000-C", 50.0),
+            summary("BTC-7JUN25-55000-P", 80.0),
+            summary("BTC-7JUN25-60000-P", 20.0),
+            // Later expiry counts toward the ratio only
+            summary("BTC-27JUN25-70000-C", 50.0),
+            summary("BTC-27JUN25-50000-P", 100.0),
+            summary("BTC-PERPETUAL", 1000.0),
+        ];
+        let metrics = compute_metrics("BTC", &summaries);
+        assert_eq!(metrics.call_open_interest, dec!(200));
+        assert_eq!(metrics.put_open_interest, dec!(200));
+        assert_eq!(metrics.put_call_ratio, Some(dec!(1)));
+        assert_eq!(metrics.max_pain_expiry, NaiveDate::from_ymd_opt(2025, 6, 7));
+        // Settling at 60000: calls pay 0, puts pay 0 -> least pain
+        assert_eq!(metrics.max_pain, Some(dec!(60000)));
+        assert!(metrics.format_for_ai(dec!(62000)).contains("Max Pain (07 Jun expiry): $60000 (-3.2% from price)"));
+    }
+}
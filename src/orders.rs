@@ -0,0 +1,77 @@
+use crate::exchange::ExchangeClient;
+use crate::models::{OpenOrder, OrderType};
+use chrono::{DateTime, Utc};
+use tracing::{info, warn};
+
+/// Tracks orders resting on the book and cancels limit orders that have been
+/// waiting longer than `STALE_ORDER_MINUTES`
+pub struct OrderMonitor {
+    stale_after: Option<chrono::Duration>,
+}
+
+impl OrderMonitor {
+    pub fn new(stale_order_minutes: Option<u64>) -> Self {
+        Self {
+            stale_after: stale_order_minutes.map(|m| chrono::Duration::minutes(m as i64)),
+        }
+    }
+
+    /// Fetch the open orders for `symbol`, cancelling stale ones.
+    /// Returns the orders still resting afterwards.
+    pub async fn refresh(&self, exchange: &ExchangeClient, symbol: &str) -> anyhow::Result<Vec<OpenOrder>> {
+        let mut orders = exchange.get_open_orders(symbol).await?;
+        let Some(max_age) = self.stale_after else {
+            return Ok(orders);
+        };
+
+        let now = Utc::now();
+        let stale: Vec<i64> = stale_orders(&orders, max_age, now).map(|o| o.order_id).collect();
+        for order_id in stale {
+            match exchange.cancel_order(symbol, order_id).await {
+                Ok(()) => {
+                    info!("🗑️ Cancelled stale order #{} on {} (open > {} min)",
+                        order_id, symbol, max_age.num_minutes());
+                    orders.retain(|o| o.order_id != order_id);
+                }
+                Err(e) => warn!("⚠️ Failed to cancel stale order #{}: {}", order_id, e),
+            }
+        }
+        Ok(orders)
+    }
+}
+
+/// Limit orders older than `max_age`; stop orders are left alone since they
+/// are expected to rest until triggered
+fn stale_orders(
+    orders: &[OpenOrder],
+    max_age: chrono::Duration,
+    now: DateTime<Utc>,
+) -> impl Iterator<Item = &OpenOrder> {
+    orders.iter().filter(move |o| {
+        matches!(o.order_type, OrderType::Limit | OrderType::LimitMaker) && o.age(now) > max_age
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_stale_limit_orders() {
+        let json = r#"[
+            {"symbol":"BTCUSDT","orderId":1,"price":"60000.00","origQty":"0.01","executedQty":"0",
+             "status":"NEW","side":"BUY","type":"LIMIT","time":1700000000000},
+            {"symbol":"BTCUSDT","orderId":2,"price":"55000.00","origQty":"0.01","executedQty":"0",
+             "status":"NEW","side":"SELL","type":"STOP_LOSS_LIMIT","time":1700000000000},
+            {"symbol":"BTCUSDT","orderId":3,"price":"61000.00","origQty":"0.01","executedQty":"0",
+             "status":"NEW","side":"BUY","type":"LIMIT","time":1700003000000}
+        ]"#;
+        let orders: Vec<OpenOrder> = serde_json::from_str(json).unwrap();
+        let now = DateTime::from_timestamp_millis(1700003600000).unwrap();
+
+        let stale: Vec<i64> = stale_orders(&orders, chrono::Duration::minutes(30), now)
+            .map(|o| o.order_id)
+            .collect();
+        assert_eq!(stale, vec![1]);
+    }
+}
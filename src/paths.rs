@@ -0,0 +1,191 @@
+use crate::config::Config;
+use crate::error::BotError;
+use anyhow::Result;
+use std::fs::{self, File};
+use std::io::{self, BufWriter, Write};
+use std::path::{Path, PathBuf};
+use tracing::info;
+
+/// Centralized layout of everything the bot writes to disk:
+///
+/// ```text
+/// DATA_DIR/
+/// ├── state/     # trade limiter and other persisted runtime state
+/// ├── reports/   # portfolio status report and archived reports
+/// ├── logs/      # log and audit files
+/// └── cache/     # cached market data
+/// ```
+#[derive(Debug, Clone)]
+pub struct DataPaths {
+    root: PathBuf,
+    report_override: Option<PathBuf>,
+}
+
+impl DataPaths {
+    /// Resolve the layout from config and create any missing directories
+    pub fn from_config(config: &Config) -> Result<Self> {
+        let paths = Self {
+            root: PathBuf::from(&config.data_dir),
+            report_override: config.report_path.as_ref().map(PathBuf::from),
+        };
+        paths.ensure_dirs()?;
+        paths.migrate_legacy_files();
+        Ok(paths)
+    }
+
+    fn ensure_dirs(&self) -> Result<()> {
+        for dir in [self.state_dir(), self.reports_dir(), self.logs_dir(), self.cache_dir()] {
+            fs::create_dir_all(&dir).map_err(|e| BotError::storage(&dir, e))?;
+        }
+        Ok(())
+    }
+
+    /// Move state written by older versions (into the working directory) into `state/`
+    fn migrate_legacy_files(&self) {
+        let legacy = self.root.join("trade_state.json");
+        let target = self.trade_state_file();
+        if legacy.exists() && !target.exists() && fs::rename(&legacy, &target).is_ok() {
+            info!("📦 Moved {} to {}", legacy.display(), target.display());
+        }
+    }
+
+    pub fn root(&self) -> &Path {
+        &self.root
+    }
+
+    pub fn state_dir(&self) -> PathBuf {
+        self.root.join("state")
+    }
+
+    pub fn reports_dir(&self) -> PathBuf {
+        self.root.join("reports")
+    }
+
+    pub fn logs_dir(&self) -> PathBuf {
+        self.root.join("logs")
+    }
+
+    pub fn cache_dir(&self) -> PathBuf {
+        self.root.join("cache")
+    }
+
+    pub fn trade_state_file(&self) -> PathBuf {
+        self.state_dir().join("trade_state.json")
+    }
+
+    pub fn runtime_state_file(&self) -> PathBuf {
+        self.state_dir().join("runtime_state.json")
+    }
+
+    pub fn audit_log_file(&self) -> PathBuf {
+        self.logs_dir().join("audit.jsonl")
+    }
+
+    /// The report as it stood at the end of `date`
+    pub fn daily_report_file(&self, date: chrono::NaiveDate) -> PathBuf {
+        self.reports_dir().join(format!("{}.txt", date.format("%Y-%m-%d")))
+    }
+
+    /// Weekly AI vs fallback review for the ISO week containing `date`
+    pub fn weekly_review_file(&self, date: chrono::NaiveDate) -> PathBuf {
+        self.reports_dir().join(format!("ai-review-{}.md", date.format("%G-W%V")))
+    }
+
+    /// One row of start/end equity per day
+    pub fn daily_equity_file(&self) -> PathBuf {
+        self.reports_dir().join("daily_equity.csv")
+    }
+
+    /// Per-trade report of the last `backtest` run
+    pub fn backtest_report_file(&self) -> PathBuf {
+        self.reports_dir().join("backtest.txt")
+    }
+
+    /// Pass/fail report of the last outage `drill`
+    pub fn drill_report_file(&self) -> PathBuf {
+        self.reports_dir().join("drill.txt")
+    }
+
+    /// Leaderboard of the paper-trading competition
+    pub fn competition_file(&self) -> PathBuf {
+        self.reports_dir().join("competition.txt")
+    }
+
+    /// The live portfolio report; `REPORT_PATH` overrides the default location
+    pub fn report_file(&self) -> PathBuf {
+        self.report_override
+            .clone()
+            .unwrap_or_else(|| self.reports_dir().join("portfolio_status.txt"))
+    }
+
+    /// HTML version of the live report, next to it
+    pub fn html_report_file(&self) -> PathBuf {
+        self.report_file().with_extension("html")
+    }
+}
+
+/// Write a file atomically: the contents go to a temporary file in the same directory,
+/// which is fsynced and then renamed over the target. A crash mid-write leaves either
+/// the old or the new file, never a truncated one.
+pub fn write_atomic(path: impl AsRef<Path>, contents: impl AsRef<[u8]>) -> io::Result<()> {
+    write_atomic_with(path, |w| w.write_all(contents.as_ref()))
+}
+
+/// Like [`write_atomic`], but the contents are streamed by `render` into a buffered
+/// writer instead of being built in memory first
+pub fn write_atomic_with<F>(path: impl AsRef<Path>, render: F) -> io::Result<()>
+where
+    F: FnOnce(&mut BufWriter<File>) -> io::Result<()>,
+{
+    let path = path.as_ref();
+    let dir = path
+        .parent()
+        .filter(|p| !p.as_os_str().is_empty())
+        .unwrap_or(Path::new("."));
+    let file_name = path
+        .file_name()
+        .ok_or_else(|| io::Error::new(io::ErrorKind::InvalidInput, "path has no file name"))?;
+    let tmp = dir.join(format!(".{}.{}.tmp", file_name.to_string_lossy(), std::process::id()));
+
+    let result = (|| {
+        let mut writer = BufWriter::new(File::create(&tmp)?);
+        render(&mut writer)?;
+        let file = writer.into_inner().map_err(|e| e.into_error())?;
+        file.sync_all()?;
+        fs::rename(&tmp, path)
+    })();
+
+    if result.is_err() {
+        let _ = fs::remove_file(&tmp);
+        return result;
+    }
+
+    // Persist the rename itself
+    #[cfg(unix)]
+    if let Ok(d) = File::open(dir) {
+        let _ = d.sync_all();
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_write_atomic_replaces_file() {
+        let dir = std::env::temp_dir().join(format!("ctb_atomic_{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let target = dir.join("state.json");
+
+        write_atomic(&target, "first").unwrap();
+        write_atomic(&target, "second").unwrap();
+
+        assert_eq!(fs::read_to_string(&target).unwrap(), "second");
+        let leftovers = fs::read_dir(&dir).unwrap().count();
+        assert_eq!(leftovers, 1, "temporary file should not remain");
+
+        fs::remove_dir_all(&dir).ok();
+    }
+}
@@ -1,11 +1,37 @@
 use crate::ai_advisor::{AiTradingTargets, TradingRecommendation};
-use crate::models::{OrderSide, Signal};
+use crate::allocation::StrategySummary;
+use crate::coingecko::GlobalMarket;
+use crate::divergence::Divergence;
+use crate::error::BotError;
+use crate::futures::FuturesPosition;
+use crate::options::OptionsMetrics;
+use crate::models::{BookTicker, OpenOrder, OrderSide, Signal};
+use crate::html_report;
+use crate::levels::Level;
+use crate::liquidity::Wall;
+use crate::volume_profile::VolumeProfile;
+use crate::retention::Retention;
+use crate::tradingview;
+use crate::i18n::{self, Language};
+use crate::locale::DisplayFormat;
+use crate::paths::{write_atomic, write_atomic_with};
+use crate::position::{ClosedTrades, Excursion, Position};
+use crate::sessions::{Session, SessionVolatility};
+use crate::quote;
+use crate::risk::VarEstimate;
+use crate::scanner::Candidate;
+use crate::symbols;
+use crate::trade_limiter::TradeContext;
+use crate::targets::{TargetSource, TargetSources};
 use anyhow::Result;
-use chrono::{DateTime, Local, Utc};
+use chrono::{DateTime, Local, NaiveDate, Utc};
 use rust_decimal::Decimal;
 use rust_decimal_macros::dec;
-use std::collections::HashMap;
-use std::fs;
+use sha2::{Digest, Sha256};
+use std::collections::{HashMap, VecDeque};
+use std::fmt;
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
 use tracing::info;
 
 /// Portfolio status that gets written to file on every update
@@ -30,6 +56,19 @@ pub struct PortfolioStatus {
     pub price_change_24h_percent: Decimal,
     pub high_24h: Decimal,
     pub low_24h: Decimal,
+    /// Deribit options positioning (OPTIONS_DATA_ENABLED, BTC/ETH)
+    pub options: Option<OptionsMetrics>,
+    /// BTC dominance and total market cap from CoinGecko `/global`
+    pub global_market: Option<GlobalMarket>,
+    /// Top of the order book (live mode)
+    pub best_bid: Option<Decimal>,
+    pub best_ask: Option<Decimal>,
+    /// Last 24h of prices, one sample per report heartbeat, for the HTML sparklines
+    pub price_history: Vec<(DateTime<Utc>, Decimal)>,
+    /// Number format and display currency of money figures (REPORT_LOCALE, DISPLAY_CURRENCY)
+    pub display: DisplayFormat,
+    /// Language of the report text (REPORT_LANGUAGE or LANG)
+    pub language: Language,
     
     // Position info
     pub position_side: Option<OrderSide>,
@@ -38,10 +77,35 @@ pub struct PortfolioStatus {
     pub position_value: Decimal,
     pub unrealized_pnl: Decimal,
     pub unrealized_pnl_percent: Decimal,
+    /// Every position slot when more than one is traded (SCALP_ENABLED)
+    pub positions: Vec<Position>,
+    /// MAE/MFE of the open trade when a single one is held
+    pub open_excursion: Option<Excursion>,
+    /// Entry time of the open trade when a single one is held
+    pub opened_at: Option<DateTime<Utc>>,
+    /// MAE/MFE, holding times and session P&L over the trades the positions closed
+    pub closed_trades: ClosedTrades,
+    /// Hourly price ranges by trading session since start
+    pub session_volatility: SessionVolatility,
     
     // Balances
     pub balances: HashMap<String, Decimal>,
     pub total_portfolio_value: Decimal,
+    /// Last 24h of portfolio values, sampled like `price_history`
+    pub equity_history: Vec<(DateTime<Utc>, Decimal)>,
+    /// Borrowed amount plus interest per asset (margin account)
+    pub liabilities: HashMap<String, Decimal>,
+    /// Margin account's assets / liabilities
+    pub margin_level: Option<Decimal>,
+    /// Asset all values are expressed in (the pair's quote asset)
+    pub quote_asset: String,
+    pub base_asset: String,
+    /// Decimal places for prices of this symbol
+    pub price_decimals: u32,
+    /// Price of other held assets in the quote asset (e.g. EUR -> USDT)
+    pub conversion_rates: HashMap<String, Decimal>,
+    /// Orders resting on the book (live mode)
+    pub open_orders: Vec<OpenOrder>,
     
     // Performance stats
     pub realized_pnl: Decimal,
@@ -51,15 +115,43 @@ pub struct PortfolioStatus {
     pub win_rate: Decimal,
     pub largest_win: Decimal,
     pub largest_loss: Decimal,
+    /// Commissions paid on all fills, in the quote asset
+    pub total_fees: Decimal,
+    /// Portfolio value when the current day began; reset by the end-of-day rollover
+    pub day_start_value: Option<Decimal>,
+    pub day_realized_pnl: Decimal,
+    pub day_trades: u32,
+    pub day_winning_trades: u32,
+    pub day_losing_trades: u32,
+    /// Deposits minus withdrawals since start, in the quote asset (live mode)
+    pub net_capital_flows: Decimal,
+    /// Return excluding deposits/withdrawals, in percent (live mode)
+    pub time_weighted_return: Option<Decimal>,
+    /// Per-strategy sub-portfolios when STRATEGY_ALLOCATION is set
+    pub strategies: Vec<StrategySummary>,
+    /// One-day VaR of the equity curve, once two days of history exist
+    pub value_at_risk: Option<VarEstimate>,
+    /// MAX_VAR_PERCENT, shown next to the estimate
+    pub max_var_percent: Option<Decimal>,
+    /// Perpetual position, leverage and funding in futures mode
+    pub futures: Option<FuturesPosition>,
+    /// Watchlist symbols with a setup, best first (SCANNER_ENABLED)
+    pub scanner: Vec<Candidate>,
     
     // Strategy signals
     pub current_signal: Signal,
     pub sma_short: Option<Decimal>,
     pub sma_long: Option<Decimal>,
     pub rsi: Option<Decimal>,
+    /// Current hour's volume over its trailing average (VOLUME_SPIKE_ENABLED)
+    pub volume_ratio: Option<Decimal>,
+    /// RSI/MACD divergences on the hourly candles (DIVERGENCE_ENABLED)
+    pub divergences: Vec<Divergence>,
     
     // AI Advisor
     pub ai_enabled: bool,
+    /// Where each active target came from, once targets are set
+    pub target_sources: Option<TargetSources>,
     pub ai_recommendation: Option<TradingRecommendation>,
     pub ai_confidence: Option<Decimal>,
     pub ai_reasoning: Option<String>,
@@ -70,21 +162,97 @@ pub struct PortfolioStatus {
     pub resistance: Option<Decimal>,
     pub strong_resistance: Option<Decimal>,
     pub pivot_point: Option<Decimal>,
+    /// Scored levels of the hourly candles, best first (SR_LEVELS_ENABLED)
+    pub levels: Vec<Level>,
+    /// Point of control and value area of the hourly volume (VOLUME_PROFILE_ENABLED)
+    pub volume_profile: Option<VolumeProfile>,
+    /// Persistent order book walls, largest first (LIQUIDITY_WALLS_ENABLED, live)
+    pub liquidity_walls: Vec<Wall>,
     
     // Trade limiter
     pub trades_today: u32,
     pub max_trades_per_day: u32,
     pub can_trade: bool,
     pub next_trading_day: Option<String>,
+    /// Why new entries are paused (exchange maintenance), if they are
+    pub entries_paused: Option<String>,
+    /// Losing trades since the last winner
+    pub consecutive_losses: u32,
+    /// Share of the usual entry size while LOSS_STREAK_LIMIT cuts it back
+    pub loss_backoff_percent: Option<Decimal>,
     
     // Alerts
-    pub active_alerts: Vec<String>,
+    /// Latest alerts, oldest first (HISTORY_RETENTION)
+    pub active_alerts: VecDeque<String>,
+    /// Alerts raised since the day started, including trimmed ones
+    pub day_alerts: usize,
     pub last_event: String,
+    /// Latest cycle decisions, oldest first
+    pub decisions: VecDeque<Decision>,
     
     // Mode
     pub is_simulation: bool,
 }
 
+/// What a trading cycle did with each position slot and why
+#[derive(Debug, Clone, PartialEq)]
+pub struct Decision {
+    pub at: DateTime<Utc>,
+    pub text: String,
+    /// Consecutive cycles that reached the same decision
+    pub cycles: u32,
+}
+
+/// Notional held per asset and what the open position stands to lose at its stop
+#[derive(Debug, Clone, PartialEq)]
+pub struct Exposure {
+    /// (asset, notional in the quote asset, percent of equity), largest first
+    pub assets: Vec<(String, Decimal, Decimal)>,
+    pub gross_notional: Decimal,
+    /// Loss if the position is stopped out: distance to the stop × size
+    pub at_risk: Option<Decimal>,
+    pub at_risk_percent: Option<Decimal>,
+    /// Gross notional / equity; at most 1x on a spot account
+    pub leverage: Decimal,
+}
+
+/// One day's results, produced by the end-of-day rollover
+#[derive(Debug, Clone, PartialEq)]
+pub struct DaySummary {
+    pub date: NaiveDate,
+    pub quote_asset: String,
+    pub start_value: Decimal,
+    pub end_value: Decimal,
+    pub realized_pnl: Decimal,
+    pub trades: u32,
+    pub winning_trades: u32,
+    pub losing_trades: u32,
+    pub alerts: usize,
+}
+
+impl DaySummary {
+    pub fn change_percent(&self) -> Decimal {
+        if self.start_value > Decimal::ZERO {
+            (self.end_value - self.start_value) / self.start_value * dec!(100)
+        } else {
+            Decimal::ZERO
+        }
+    }
+
+    /// Daily summary notification, in the report's language and display format
+    pub fn format_message(&self, symbol: &str, display: &DisplayFormat, language: Language) -> String {
+        let t = language.strings();
+        let (start, end, realized) = match display.currency {
+            Some(_) => (display.money(self.start_value, 2), display.money(self.end_value, 2), display.money(self.realized_pnl, 2)),
+            None => (display.number(self.start_value, 2), format!("{} {}", display.number(self.end_value, 2), self.quote_asset),
+                format!("{} {}", display.number(self.realized_pnl, 2), self.quote_asset)),
+        };
+        format!("📅 {} {} ({})\n{} {} → {} ({:+}%)\n{} {}\nTrades: {} ({} {}, {} {})\n{} {}",
+            symbol, t.daily_summary, self.date, t.value, start, end, self.change_percent().round_dp(2), t.realized_pnl, realized,
+            self.trades, self.winning_trades, t.won, self.losing_trades, t.lost, t.alerts, self.alerts)
+    }
+}
+
 impl Default for PortfolioStatus {
     fn default() -> Self {
         let now = Utc::now();
@@ -103,14 +271,34 @@ impl Default for PortfolioStatus {
             price_change_24h_percent: Decimal::ZERO,
             high_24h: Decimal::ZERO,
             low_24h: Decimal::ZERO,
+            options: None,
+            global_market: None,
+            best_bid: None,
+            best_ask: None,
+            price_history: Vec::new(),
+            display: DisplayFormat::default(),
+            language: Language::default(),
             position_side: None,
             entry_price: None,
             position_size: Decimal::ZERO,
             position_value: Decimal::ZERO,
             unrealized_pnl: Decimal::ZERO,
             unrealized_pnl_percent: Decimal::ZERO,
+            positions: Vec::new(),
+            open_excursion: None,
+            opened_at: None,
+            closed_trades: ClosedTrades::default(),
+            session_volatility: SessionVolatility::default(),
             balances: HashMap::new(),
             total_portfolio_value: Decimal::ZERO,
+            equity_history: Vec::new(),
+            liabilities: HashMap::new(),
+            margin_level: None,
+            quote_asset: "USDT".to_string(),
+            base_asset: "BTC".to_string(),
+            price_decimals: 2,
+            conversion_rates: HashMap::new(),
+            open_orders: Vec::new(),
             realized_pnl: Decimal::ZERO,
             total_trades: 0,
             winning_trades: 0,
@@ -118,11 +306,27 @@ impl Default for PortfolioStatus {
             win_rate: Decimal::ZERO,
             largest_win: Decimal::ZERO,
             largest_loss: Decimal::ZERO,
+            total_fees: Decimal::ZERO,
+            day_start_value: None,
+            day_realized_pnl: Decimal::ZERO,
+            day_trades: 0,
+            day_winning_trades: 0,
+            day_losing_trades: 0,
+            net_capital_flows: Decimal::ZERO,
+            time_weighted_return: None,
+            strategies: Vec::new(),
+            value_at_risk: None,
+            max_var_percent: None,
+            futures: None,
+            scanner: Vec::new(),
             current_signal: Signal::Hold,
             sma_short: None,
             sma_long: None,
             rsi: None,
+            volume_ratio: None,
+            divergences: Vec::new(),
             ai_enabled: false,
+            target_sources: None,
             ai_recommendation: None,
             ai_confidence: None,
             ai_reasoning: None,
@@ -131,12 +335,20 @@ impl Default for PortfolioStatus {
             resistance: None,
             strong_resistance: None,
             pivot_point: None,
+            levels: Vec::new(),
+            volume_profile: None,
+            liquidity_walls: Vec::new(),
             trades_today: 0,
             max_trades_per_day: 2,
             can_trade: true,
             next_trading_day: None,
-            active_alerts: Vec::new(),
+            entries_paused: None,
+            consecutive_losses: 0,
+            loss_backoff_percent: None,
+            active_alerts: VecDeque::new(),
+            day_alerts: 0,
             last_event: "Bot started".to_string(),
+            decisions: VecDeque::new(),
             is_simulation: false,
         }
     }
@@ -144,60 +356,158 @@ impl Default for PortfolioStatus {
 
 impl PortfolioStatus {
     pub fn new(symbol: &str, is_simulation: bool) -> Self {
+        let info = symbols::lookup(symbol);
         Self {
             symbol: symbol.to_string(),
+            quote_asset: info.quote,
+            base_asset: info.base,
+            price_decimals: info.display_decimals,
             is_simulation,
             ..Default::default()
         }
     }
 
-    /// Update stop-loss and take-profit prices based on entry price
+    /// Sample the price and portfolio value for the sparklines, at most once per heartbeat
+    fn record_history(&mut self, now: DateTime<Utc>) {
+        let (price, value) = (self.current_price, self.total_portfolio_value);
+        for (history, sample) in [(&mut self.price_history, price), (&mut self.equity_history, value)] {
+            if sample <= Decimal::ZERO
+                || history.last().is_some_and(|(t, _)| (now - *t).num_seconds() < REPORT_HEARTBEAT_SECS) {
+                continue;
+            }
+            history.push((now, sample));
+            history.retain(|(t, _)| now - *t <= chrono::Duration::hours(24));
+        }
+    }
+
+    /// Targets, indicators and AI analysis in effect when `trigger` fired a trade
+    pub fn trade_context(&self, trigger: &str, target_price: Option<Decimal>) -> TradeContext {
+        TradeContext {
+            trigger: trigger.to_string(),
+            target_price,
+            stop_loss: self.stop_loss_price,
+            take_profit: self.take_profit_price,
+            rsi: self.rsi.map(|r| r.round_dp(2)),
+            sma_short: self.sma_short,
+            sma_long: self.sma_long,
+            volume_ratio: self.volume_ratio,
+            ai_recommendation: self.ai_recommendation.as_ref().map(|r| format!("{:?}", r)),
+            ai_confidence: self.ai_confidence,
+            ai_reasoning_sha256: self.ai_reasoning.as_ref().map(|text| hex::encode(Sha256::digest(text.as_bytes()))),
+        }
+    }
+
+    /// Value of `amount` of `asset` in the quote asset, if a price is known
+    pub fn value_in_quote(&self, asset: &str, amount: Decimal) -> Option<Decimal> {
+        if asset == self.base_asset {
+            return Some(amount * self.current_price);
+        }
+        quote::peg_rate(asset, &self.quote_asset)
+            .or_else(|| self.conversion_rates.get(asset).copied())
+            .map(|rate| amount * rate)
+    }
+
+    /// Exposure of the non-quote holdings relative to equity
+    pub fn exposure(&self) -> Exposure {
+        let equity = self.total_portfolio_value;
+        let percent_of_equity = |value: Decimal| if equity > Decimal::ZERO { value / equity * dec!(100) } else { Decimal::ZERO };
+
+        let mut assets: Vec<(String, Decimal, Decimal)> = self.balances.iter()
+            .filter(|(asset, amount)| **asset != self.quote_asset && **amount > Decimal::ZERO)
+            .filter_map(|(asset, amount)| {
+                let value = self.value_in_quote(asset, *amount)?;
+                Some((asset.clone(), value, percent_of_equity(value)))
+            })
+            .collect();
+        if let Some(futures) = self.futures.as_ref().filter(|f| !f.notional().is_zero()) {
+            let notional = futures.notional();
+            assets.push((format!("{} (perp)", self.base_asset), notional, percent_of_equity(notional)));
+        }
+        assets.sort_by(|a, b| b.1.cmp(&a.1).then_with(|| a.0.cmp(&b.0)));
+        let gross_notional: Decimal = assets.iter().map(|(_, value, _)| *value).sum();
+
+        let at_risk = self.stop_loss_price
+            .filter(|_| self.position_size > Decimal::ZERO)
+            .map(|stop| ((self.current_price - stop) * self.position_size).max(Decimal::ZERO));
+
+        Exposure {
+            assets,
+            gross_notional,
+            at_risk,
+            at_risk_percent: at_risk.map(percent_of_equity),
+            leverage: if equity > Decimal::ZERO { gross_notional / equity } else { Decimal::ZERO },
+        }
+    }
+
+    /// Total value of all balances net of margin liabilities, in the quote asset;
+    /// unpriced assets are left out
+    pub fn update_total_value(&mut self) {
+        let held: Decimal = self
+            .balances
+            .iter()
+            .filter_map(|(asset, amount)| self.value_in_quote(asset, *amount))
+            .sum();
+        let owed: Decimal = self
+            .liabilities
+            .iter()
+            .filter_map(|(asset, amount)| self.value_in_quote(asset, *amount))
+            .sum();
+        self.total_portfolio_value = held - owed;
+        if self.day_start_value.is_none() && self.total_portfolio_value > Decimal::ZERO {
+            self.day_start_value = Some(self.total_portfolio_value);
+        }
+    }
+
+    /// Balance of `asset` that isn't borrowed
+    pub fn owned_balance(&self, asset: &str) -> Decimal {
+        let held = self.balances.get(asset).copied().unwrap_or_default();
+        let owed = self.liabilities.get(asset).copied().unwrap_or_default();
+        (held - owed).max(Decimal::ZERO)
+    }
+
+    /// Update stop-loss and take-profit prices based on entry price; the stop percent is a
+    /// distance below the entry whatever its sign
     pub fn update_targets(&mut self) {
         if let Some(entry) = self.entry_price {
-            self.stop_loss_price = Some(entry * (dec!(1) + self.stop_loss_percent / dec!(100)));
-            self.take_profit_price = Some(entry * (dec!(1) + self.take_profit_percent / dec!(100)));
+            self.stop_loss_price = Some(entry * (dec!(1) - self.stop_loss_percent.abs() / dec!(100)));
+            self.take_profit_price = Some(entry * (dec!(1) + self.take_profit_percent.abs() / dec!(100)));
         }
     }
 
     /// Calculate unrealized P&L
     pub fn update_unrealized_pnl(&mut self) {
-        if let Some(entry) = self.entry_price {
-            if self.position_size > Decimal::ZERO {
-                self.position_value = self.position_size * self.current_price;
-                let entry_value = self.position_size * entry;
-                self.unrealized_pnl = self.position_value - entry_value;
-                
-                if entry_value > Decimal::ZERO {
-                    self.unrealized_pnl_percent = (self.unrealized_pnl / entry_value) * dec!(100);
-                }
+        if let Some(entry) = self.entry_price
+            && self.position_size > Decimal::ZERO {
+            self.position_value = self.position_size * self.current_price;
+            let entry_value = self.position_size * entry;
+            self.unrealized_pnl = self.position_value - entry_value;
+            
+            if entry_value > Decimal::ZERO {
+                self.unrealized_pnl_percent = (self.unrealized_pnl / entry_value) * dec!(100);
             }
         }
     }
 
     /// Check if any price targets are hit
     pub fn check_targets(&self) -> Option<String> {
-        if let Some(stop_loss) = self.stop_loss_price {
-            if self.current_price <= stop_loss {
-                return Some(format!("🔴 STOP-LOSS HIT at {}", self.current_price));
-            }
+        if let Some(stop_loss) = self.stop_loss_price
+            && self.current_price <= stop_loss {
+            return Some(format!("🔴 STOP-LOSS HIT at {}", self.current_price));
         }
         
-        if let Some(take_profit) = self.take_profit_price {
-            if self.current_price >= take_profit {
-                return Some(format!("🟢 TAKE-PROFIT HIT at {}", self.current_price));
-            }
+        if let Some(take_profit) = self.take_profit_price
+            && self.current_price >= take_profit {
+            return Some(format!("🟢 TAKE-PROFIT HIT at {}", self.current_price));
         }
         
-        if let Some(buy_target) = self.buy_target_price {
-            if self.current_price <= buy_target {
-                return Some(format!("🔵 BUY TARGET HIT at {}", self.current_price));
-            }
+        if let Some(buy_target) = self.buy_target_price
+            && self.current_price <= buy_target {
+            return Some(format!("🔵 BUY TARGET HIT at {}", self.current_price));
         }
         
-        if let Some(sell_target) = self.sell_target_price {
-            if self.current_price >= sell_target {
-                return Some(format!("🟠 SELL TARGET HIT at {}", self.current_price));
-            }
+        if let Some(sell_target) = self.sell_target_price
+            && self.current_price >= sell_target {
+            return Some(format!("🟠 SELL TARGET HIT at {}", self.current_price));
         }
         
         None
@@ -211,20 +521,69 @@ impl PortfolioStatus {
     }
 }
 
+/// Report sections whose contents changed since the report was last written
+mod dirty {
+    pub const MARKET: u16 = 1 << 0;
+    pub const TARGETS: u16 = 1 << 1;
+    pub const POSITION: u16 = 1 << 2;
+    pub const BALANCES: u16 = 1 << 3;
+    pub const STATS: u16 = 1 << 4;
+    pub const SIGNALS: u16 = 1 << 5;
+    pub const LIMITS: u16 = 1 << 6;
+    pub const ORDERS: u16 = 1 << 7;
+    pub const EVENTS: u16 = 1 << 8;
+    pub const ALL: u16 = u16::MAX;
+}
+
+/// Rewrite the report at least this often so "Last Updated" and uptime stay current
+const REPORT_HEARTBEAT_SECS: i64 = 300;
+
+/// Cycle decisions kept for the report's decision log
+const DECISION_LOG_SIZE: usize = 10;
+
 pub struct PortfolioReporter {
     status: PortfolioStatus,
-    report_path: String,
+    report_path: PathBuf,
+    /// HTML rendering of the report, next to it
+    html_path: PathBuf,
+    /// TradingView exports of the targets and levels, next to it
+    pine_path: PathBuf,
+    levels_path: PathBuf,
+    /// `dirty::*` flags set by updates that change what the report shows
+    dirty: u16,
+    last_written: Option<DateTime<Utc>>,
+    /// Bounds the alerts list
+    retention: Retention,
 }
 
 impl PortfolioReporter {
-    pub fn new(symbol: &str, is_simulation: bool, report_path: &str) -> Self {
+    pub fn new(symbol: &str, is_simulation: bool, report_path: &Path) -> Self {
         Self {
             status: PortfolioStatus::new(symbol, is_simulation),
-            report_path: report_path.to_string(),
+            report_path: report_path.to_path_buf(),
+            html_path: report_path.with_extension("html"),
+            pine_path: report_path.with_file_name("tradingview.pine"),
+            levels_path: report_path.with_file_name("levels.csv"),
+            dirty: dirty::ALL,
+            last_written: None,
+            retention: Retention::unbounded(),
         }
     }
 
+    /// Keep the alerts list within `retention`
+    pub fn set_retention(&mut self, retention: Retention) {
+        self.retention = retention;
+    }
+
+    fn push_alert(&mut self, alert: String) {
+        self.status.active_alerts.push_back(alert);
+        self.status.day_alerts += 1;
+        self.retention.enforce("alerts", &mut self.status.active_alerts);
+    }
+
+    /// Direct access to the status; everything is assumed to have changed
     pub fn status_mut(&mut self) -> &mut PortfolioStatus {
+        self.dirty = dirty::ALL;
         &mut self.status
     }
 
@@ -232,16 +591,26 @@ impl PortfolioReporter {
         &self.status
     }
 
+    fn mark(&mut self, flags: u16) {
+        self.dirty |= flags;
+    }
+
     /// Update price and check for events
     pub fn update_price(&mut self, price: Decimal) -> Option<String> {
+        // Moves below the displayed precision don't change the report
+        if price.round_dp(self.status.price_decimals) != self.status.current_price.round_dp(self.status.price_decimals) {
+            self.mark(dirty::MARKET | dirty::POSITION | dirty::BALANCES);
+        }
         self.status.current_price = price;
         self.status.update_unrealized_pnl();
         self.status.last_updated = Utc::now();
+        self.status.session_volatility.update(price, self.status.last_updated);
         
         // Check if any targets were hit
         if let Some(event) = self.status.check_targets() {
             self.status.last_event = event.clone();
-            self.status.active_alerts.push(event.clone());
+            self.push_alert(event.clone());
+            self.mark(dirty::EVENTS);
             self.write_report().ok();
             return Some(event);
         }
@@ -249,20 +618,114 @@ impl PortfolioReporter {
         None
     }
 
-    /// Record a trade execution
-    pub fn record_trade(&mut self, side: OrderSide, price: Decimal, quantity: Decimal, pnl: Option<Decimal>) {
+    /// Update 24h market statistics
+    pub fn update_market_data(&mut self, high_24h: Decimal, low_24h: Decimal, change_24h_percent: Decimal) {
+        let s = &mut self.status;
+        if (s.high_24h, s.low_24h, s.price_change_24h_percent) != (high_24h, low_24h, change_24h_percent) {
+            s.high_24h = high_24h;
+            s.low_24h = low_24h;
+            s.price_change_24h_percent = change_24h_percent;
+            self.mark(dirty::MARKET);
+        }
+    }
+
+    pub fn update_options(&mut self, metrics: Option<OptionsMetrics>) {
+        if self.status.options != metrics {
+            self.status.options = metrics;
+            self.mark(dirty::MARKET);
+        }
+    }
+
+    pub fn update_global_market(&mut self, global: Option<GlobalMarket>) {
+        if self.status.global_market != global {
+            self.status.global_market = global;
+            self.mark(dirty::MARKET);
+        }
+    }
+
+    pub fn set_language(&mut self, language: Language) {
+        if self.status.language != language {
+            self.status.language = language;
+            self.mark(dirty::ALL);
+        }
+    }
+
+    /// Switch number format or display currency; every money figure changes
+    pub fn update_display(&mut self, display: DisplayFormat) {
+        if self.status.display != display {
+            self.status.display = display;
+            self.mark(dirty::ALL);
+        }
+    }
+
+    pub fn update_order_book(&mut self, book: &BookTicker) {
+        let dp = self.status.price_decimals;
+        let (bid, ask) = (book.bid_price.round_dp(dp), book.ask_price.round_dp(dp));
+        if (self.status.best_bid, self.status.best_ask) != (Some(bid), Some(ask)) {
+            self.status.best_bid = Some(bid);
+            self.status.best_ask = Some(ask);
+            self.mark(dirty::MARKET);
+        }
+    }
+
+    /// Show the position slots, e.g. after a restart or a trade. With several, the headline
+    /// position is the longs' combined size at their quantity-weighted entry price.
+    pub fn update_positions(&mut self, positions: &[Position]) {
+        self.status.positions = if positions.len() > 1 { positions.to_vec() } else { Vec::new() };
+        let open: Vec<&Position> = positions.iter()
+            .filter(|p| !p.short && p.entry_price.is_some() && !p.is_flat())
+            .collect();
+        let size: Decimal = open.iter().map(|p| p.qty).sum();
+        (self.status.open_excursion, self.status.opened_at) = match open.as_slice() {
+            [single] => (Some(single.excursion), single.opened_at),
+            _ => (None, None),
+        };
+        self.status.closed_trades = ClosedTrades::default();
+        for position in positions {
+            self.status.closed_trades.merge(&position.closed_trades);
+        }
+        if size > Decimal::ZERO {
+            let cost: Decimal = open.iter().map(|p| p.qty * p.entry_price.unwrap_or_default()).sum();
+            self.status.entry_price = Some(cost / size);
+            self.status.position_size = size;
+            self.status.position_side = Some(OrderSide::Buy);
+            self.status.update_targets();
+        } else {
+            self.status.entry_price = None;
+            self.status.position_size = Decimal::ZERO;
+            self.status.position_side = None;
+            self.status.stop_loss_price = None;
+            self.status.take_profit_price = None;
+            self.status.position_value = Decimal::ZERO;
+            self.status.unrealized_pnl = Decimal::ZERO;
+            self.status.unrealized_pnl_percent = Decimal::ZERO;
+        }
+        self.status.update_unrealized_pnl();
+        self.mark(dirty::POSITION | dirty::TARGETS);
+    }
+
+    /// Record a trade execution. `fee` is the commission in the quote asset; `pnl` of a
+    /// sell is expected net of the entry and exit fees (see `Position::exit_filled`).
+    pub fn record_trade(&mut self, side: OrderSide, price: Decimal, quantity: Decimal, pnl: Option<Decimal>, fee: Decimal) {
         self.status.total_trades += 1;
+        self.status.day_trades += 1;
+        self.status.total_fees += fee;
         
         if let Some(profit) = pnl {
             self.status.realized_pnl += profit;
+            self.status.day_realized_pnl += profit;
             
             if profit > Decimal::ZERO {
+                self.status.consecutive_losses = 0;
                 self.status.winning_trades += 1;
+                self.status.day_winning_trades += 1;
                 if profit > self.status.largest_win {
                     self.status.largest_win = profit;
                 }
             } else {
+                self.status.consecutive_losses += 1;
                 self.status.losing_trades += 1;
+                self.status.day_losing_trades += 1;
                 if profit < self.status.largest_loss {
                     self.status.largest_loss = profit;
                 }
@@ -289,19 +752,46 @@ impl PortfolioReporter {
         
         self.status.update_stats();
         self.status.last_updated = Utc::now();
+        self.mark(dirty::POSITION | dirty::TARGETS | dirty::STATS | dirty::EVENTS);
         self.write_report().ok();
     }
 
     /// Update balances
     pub fn update_balances(&mut self, balances: HashMap<String, Decimal>) {
-        self.status.balances = balances;
-        self.status.total_portfolio_value = self.status.balances.values().sum();
+        if self.status.balances != balances {
+            self.status.balances = balances;
+            self.status.update_total_value();
+            self.mark(dirty::BALANCES);
+        }
+        self.status.last_updated = Utc::now();
+    }
+
+    /// Apply a single balance change (e.g. from the user-data stream)
+    pub fn apply_balance_update(&mut self, asset: &str, free: Decimal) {
+        if free.is_zero() {
+            self.status.balances.remove(asset);
+        } else {
+            self.status.balances.insert(asset.to_string(), free);
+        }
+        self.status.update_total_value();
         self.status.last_updated = Utc::now();
+        self.mark(dirty::BALANCES);
+    }
+
+    /// Set the price of another held asset (e.g. EUR or a different stablecoin) in the quote asset
+    pub fn set_conversion_rate(&mut self, asset: &str, rate: Decimal) {
+        if self.status.conversion_rates.insert(asset.to_string(), rate) != Some(rate) {
+            self.status.update_total_value();
+            self.mark(dirty::BALANCES);
+        }
     }
 
     /// Update strategy signals
     pub fn update_signals(&mut self, signal: Signal, sma_short: Option<Decimal>, sma_long: Option<Decimal>, rsi: Option<Decimal>) {
         let old_signal = self.status.current_signal;
+        if (self.status.sma_short, self.status.sma_long, self.status.rsi) != (sma_short, sma_long, rsi) {
+            self.mark(dirty::SIGNALS);
+        }
         self.status.current_signal = signal;
         self.status.sma_short = sma_short;
         self.status.sma_long = sma_long;
@@ -311,6 +801,7 @@ impl PortfolioReporter {
         if old_signal != signal {
             self.status.last_event = format!("📊 Signal changed: {:?} -> {:?}", old_signal, signal);
             self.status.last_updated = Utc::now();
+            self.mark(dirty::SIGNALS | dirty::EVENTS);
             self.write_report().ok();
         }
     }
@@ -336,266 +827,808 @@ impl PortfolioReporter {
         self.status.last_event = format!("🤖 AI targets updated: {} ({}% confidence)", 
             targets.recommendation, targets.confidence.round_dp(0));
         self.status.last_updated = Utc::now();
+        self.mark(dirty::TARGETS | dirty::EVENTS);
         self.write_report().ok();
     }
 
+    /// Record where each active target came from
+    pub fn update_target_sources(&mut self, sources: TargetSources) {
+        if self.status.target_sources != Some(sources) {
+            self.status.target_sources = Some(sources);
+            self.mark(dirty::TARGETS);
+        }
+    }
+
     /// Update trade limiter status
     pub fn update_trade_limits(&mut self, trades_today: u32, can_trade: bool, next_day: Option<String>) {
-        self.status.trades_today = trades_today;
-        self.status.can_trade = can_trade;
-        self.status.next_trading_day = next_day;
+        let s = &mut self.status;
+        if (s.trades_today, s.can_trade, &s.next_trading_day) != (trades_today, can_trade, &next_day) {
+            s.trades_today = trades_today;
+            s.can_trade = can_trade;
+            s.next_trading_day = next_day;
+            self.mark(dirty::LIMITS);
+        }
         self.status.last_updated = Utc::now();
     }
 
+    pub fn update_capital(&mut self, net_flows: Decimal, time_weighted_return: Decimal) {
+        if (self.status.net_capital_flows, self.status.time_weighted_return) != (net_flows, Some(time_weighted_return)) {
+            self.status.net_capital_flows = net_flows;
+            self.status.time_weighted_return = Some(time_weighted_return);
+            self.mark(dirty::STATS);
+        }
+    }
+
+    pub fn update_strategies(&mut self, strategies: Vec<StrategySummary>) {
+        if self.status.strategies != strategies {
+            self.status.strategies = strategies;
+            self.mark(dirty::STATS);
+        }
+    }
+
+    pub fn update_value_at_risk(&mut self, estimate: Option<VarEstimate>, max_percent: Option<Decimal>) {
+        if (self.status.value_at_risk, self.status.max_var_percent) != (estimate, max_percent) {
+            self.status.value_at_risk = estimate;
+            self.status.max_var_percent = max_percent;
+            self.mark(dirty::STATS);
+        }
+    }
+
+    pub fn update_futures(&mut self, position: Option<FuturesPosition>) {
+        if self.status.futures != position {
+            self.status.futures = position;
+            self.mark(dirty::POSITION);
+        }
+    }
+
+    pub fn update_volume_ratio(&mut self, ratio: Option<Decimal>) {
+        if self.status.volume_ratio != ratio {
+            self.status.volume_ratio = ratio;
+            self.mark(dirty::SIGNALS);
+        }
+    }
+
+    pub fn update_levels(&mut self, levels: &[Level]) {
+        if self.status.levels != levels {
+            self.status.levels = levels.to_vec();
+            self.mark(dirty::TARGETS);
+        }
+    }
+
+    pub fn update_liquidity_walls(&mut self, walls: &[Wall]) {
+        if self.status.liquidity_walls != walls {
+            self.status.liquidity_walls = walls.to_vec();
+            self.mark(dirty::TARGETS);
+        }
+    }
+
+    pub fn update_volume_profile(&mut self, profile: Option<&VolumeProfile>) {
+        if self.status.volume_profile.as_ref() != profile {
+            self.status.volume_profile = profile.cloned();
+            self.mark(dirty::TARGETS);
+        }
+    }
+
+    pub fn update_divergences(&mut self, divergences: &[Divergence]) {
+        if self.status.divergences != divergences {
+            self.status.divergences = divergences.to_vec();
+            self.mark(dirty::SIGNALS);
+        }
+    }
+
+    pub fn update_scanner(&mut self, candidates: Vec<Candidate>) {
+        if self.status.scanner != candidates {
+            self.status.scanner = candidates;
+            self.mark(dirty::SIGNALS);
+        }
+    }
+
+    /// Trade another symbol with the same quote asset from now on; the old symbol's
+    /// market data, targets and positions are dropped
+    pub fn switch_symbol(&mut self, symbol: &str) {
+        let info = symbols::lookup(symbol);
+        let s = &mut self.status;
+        s.symbol = symbol.to_string();
+        s.base_asset = info.base;
+        s.price_decimals = info.display_decimals;
+        s.stop_loss_price = None;
+        s.take_profit_price = None;
+        s.buy_target_price = None;
+        s.sell_target_price = None;
+        s.options = None;
+        s.best_bid = None;
+        s.best_ask = None;
+        s.price_history.clear();
+        s.positions.clear();
+        s.volume_ratio = None;
+        s.divergences.clear();
+        s.ai_recommendation = None;
+        s.ai_confidence = None;
+        s.ai_reasoning = None;
+        s.support = None;
+        s.strong_support = None;
+        s.resistance = None;
+        s.strong_resistance = None;
+        s.pivot_point = None;
+        s.levels.clear();
+        s.volume_profile = None;
+        s.liquidity_walls.clear();
+        s.last_event = format!("🔀 Switched to {}", symbol);
+        self.dirty = dirty::ALL;
+    }
+
+    /// Margin liabilities and level; they reduce the portfolio value
+    pub fn update_margin(&mut self, liabilities: HashMap<String, Decimal>, margin_level: Option<Decimal>) {
+        if (&self.status.liabilities, self.status.margin_level) != (&liabilities, margin_level) {
+            self.status.liabilities = liabilities;
+            self.status.margin_level = margin_level;
+            self.status.update_total_value();
+            self.mark(dirty::BALANCES);
+        }
+    }
+
+    /// Book a funding payment into realized P&L
+    pub fn record_funding(&mut self, amount: Decimal) {
+        self.status.realized_pnl += amount;
+        self.status.day_realized_pnl += amount;
+        if let Some(futures) = self.status.futures.as_mut() {
+            futures.funding_total += amount;
+        }
+        self.mark(dirty::POSITION | dirty::STATS);
+    }
+
+    pub fn update_open_orders(&mut self, orders: Vec<OpenOrder>) {
+        let unchanged = orders.len() == self.status.open_orders.len()
+            && orders.iter().zip(&self.status.open_orders)
+                .all(|(a, b)| a.order_id == b.order_id && a.executed_qty == b.executed_qty);
+        if !unchanged {
+            self.mark(dirty::ORDERS);
+        }
+        self.status.open_orders = orders;
+    }
+
+    /// Update the maintenance pause shown in the report
+    pub fn update_entries_paused(&mut self, reason: Option<String>) {
+        if self.status.entries_paused != reason {
+            self.status.last_event = match &reason {
+                Some(r) => format!("⏸️ Entries paused: {}", r),
+                None => "▶️ Entries resumed".to_string(),
+            };
+            self.mark(dirty::LIMITS | dirty::EVENTS);
+        }
+        self.status.entries_paused = reason;
+    }
+
+    /// Update the losing-streak size cut shown in the report
+    pub fn update_loss_backoff(&mut self, percent: Option<Decimal>) {
+        if self.status.loss_backoff_percent != percent {
+            self.status.last_event = match percent {
+                Some(p) if p.is_zero() => format!("🧊 {} losses in a row - entries paused until a win", self.status.consecutive_losses),
+                Some(p) => format!("🧊 {} losses in a row - entry size cut to {}%", self.status.consecutive_losses, p.normalize()),
+                None => "▶️ Losing streak over - full entry size".to_string(),
+            };
+            self.status.loss_backoff_percent = percent;
+            self.mark(dirty::LIMITS | dirty::EVENTS);
+        }
+    }
+
+    pub fn set_last_event(&mut self, event: String) {
+        self.status.last_event = event;
+        self.mark(dirty::EVENTS);
+    }
+
+    /// Show a problem as the last event and in the alerts list
+    pub fn raise_alert(&mut self, alert: String) {
+        self.status.last_event = alert.clone();
+        self.push_alert(alert);
+        self.mark(dirty::EVENTS);
+    }
+
+    /// Log a cycle's decision; repeats of the previous one only bump its cycle count
+    pub fn record_decision(&mut self, text: String) {
+        let now = Utc::now();
+        let decisions = &mut self.status.decisions;
+        if let Some(last) = decisions.back_mut()
+            && last.text == text {
+            last.at = now;
+            last.cycles += 1;
+            return;
+        }
+        if decisions.len() == DECISION_LOG_SIZE {
+            decisions.pop_front();
+        }
+        decisions.push_back(Decision { at: now, text, cycles: 1 });
+        self.mark(dirty::EVENTS);
+    }
+
+    /// End the day: archive the current report to `archive_path`, then start a new day from
+    /// the current value with no trades or alerts. Returns the finished day's figures.
+    pub fn close_day(&mut self, date: NaiveDate, archive_path: &Path) -> Result<DaySummary> {
+        self.force_write()?;
+        std::fs::copy(&self.report_path, archive_path).map_err(|e| BotError::storage(archive_path, e))?;
+
+        let s = &mut self.status;
+        let summary = DaySummary {
+            date,
+            quote_asset: s.quote_asset.clone(),
+            start_value: s.day_start_value.unwrap_or(s.total_portfolio_value),
+            end_value: s.total_portfolio_value,
+            realized_pnl: s.day_realized_pnl,
+            trades: s.day_trades,
+            winning_trades: s.day_winning_trades,
+            losing_trades: s.day_losing_trades,
+            alerts: s.day_alerts,
+        };
+        s.day_start_value = (s.total_portfolio_value > Decimal::ZERO).then_some(s.total_portfolio_value);
+        s.day_realized_pnl = Decimal::ZERO;
+        s.day_trades = 0;
+        s.day_winning_trades = 0;
+        s.day_losing_trades = 0;
+        s.active_alerts.clear();
+        s.day_alerts = 0;
+        s.last_event = format!("📅 Day {} closed", date);
+        self.mark(dirty::STATS | dirty::EVENTS);
+        Ok(summary)
+    }
+
+    /// Write the report if anything shown in it changed, or the heartbeat interval passed
+    pub fn write_if_dirty(&mut self) -> Result<()> {
+        let stale = self.last_written
+            .is_none_or(|t| (Utc::now() - t).num_seconds() >= REPORT_HEARTBEAT_SECS);
+        if self.dirty == 0 && !stale {
+            return Ok(());
+        }
+        self.force_write()
+    }
+
     /// Force write report
     pub fn force_write(&mut self) -> Result<()> {
         self.status.last_updated = Utc::now();
         self.write_report()
     }
 
-    /// Write the portfolio report to file
-    pub fn write_report(&self) -> Result<()> {
-        let s = &self.status;
-        let local_time: DateTime<Local> = s.last_updated.into();
-        let started_local: DateTime<Local> = s.bot_started.into();
+    /// Write the portfolio report to file, streaming it through a buffered writer, and
+    /// its HTML version alongside; the TradingView exports when the levels changed
+    pub fn write_report(&mut self) -> Result<()> {
+        self.status.record_history(Utc::now());
+        write_atomic_with(&self.report_path, |w| render_report(w, &self.status))?;
+        write_atomic(&self.html_path, html_report::render(&self.status))?;
+        if self.dirty & (dirty::TARGETS | dirty::POSITION) != 0 {
+            write_atomic(&self.pine_path, tradingview::pine_script(&self.status))?;
+            write_atomic(&self.levels_path, tradingview::levels_csv(&self.status))?;
+        }
+        self.dirty = 0;
+        self.last_written = Some(Utc::now());
+        info!("📄 Portfolio report written to {}", self.report_path.display());
         
-        let mode_banner = if s.is_simulation {
-            "║           🎮 SIMULATION MODE 🎮           ║"
-        } else {
-            "║             💰 LIVE TRADING 💰             ║"
-        };
+        Ok(())
+    }
+}
 
-        let position_status = match &s.position_side {
-            Some(OrderSide::Buy) => "LONG",
-            Some(OrderSide::Sell) => "SHORT",
-            None => "NO POSITION",
-        };
+/// One row per position slot, each with its own targets and P&L
+fn write_positions(w: &mut impl Write, s: &PortfolioStatus) -> io::Result<()> {
+    if s.positions.is_empty() {
+        return Ok(());
+    }
+    let t = s.language.strings();
+    let dp = s.price_decimals;
+    writeln!(w, "  {:<8} {:<8} {:>12} {:>12} {:>12} {:>12} {:>12} {:>12} {:>14}",
+        t.col_id, t.col_state, t.col_size, t.col_entry, t.stop_loss.trim_end_matches(':'),
+        t.take_profit.trim_end_matches(':'), t.col_unrealized, t.col_realized, t.col_excursion)?;
+    for p in &s.positions {
+        let excursion = if p.is_open() { excursion_text(&s.display, p.excursion) } else { "-".to_string() };
+        writeln!(w, "  {:<8} {:<8} {:>12} {:>12} {:>12} {:>12} {:>12} {:>12} {:>14}",
+            p.id, p.state, p.qty.round_dp(6), Price(p.entry_price, dp, "-", &s.display).to_string(),
+            Price(p.stop_loss, dp, "-", &s.display).to_string(), Price(p.take_profit, dp, "-", &s.display).to_string(),
+            s.display.money(p.unrealized_pnl(s.current_price), 2), s.display.money(p.realized_pnl, 2), excursion)?;
+    }
+    writeln!(w)
+}
 
-        let signal_emoji = match s.current_signal {
-            Signal::Buy => "🟢 BUY",
-            Signal::Sell => "🔴 SELL",
-            Signal::Hold => "⚪ HOLD",
-        };
+/// `-1.25% / +3.40%`
+fn excursion_text(display: &DisplayFormat, excursion: Excursion) -> String {
+    let signed = |v: Decimal| format!("{}{}%", if v > Decimal::ZERO { "+" } else { "" }, display.number(v, 2));
+    format!("{} / {}", signed(excursion.mae_percent), signed(excursion.mfe_percent))
+}
 
-        // Format AI section
-        let rec_emoji = match &s.ai_recommendation {
-            Some(TradingRecommendation::StrongBuy) => "🟢🟢 STRONG BUY",
-            Some(TradingRecommendation::Buy) => "🟢 BUY",
-            Some(TradingRecommendation::Hold) => "⚪ HOLD",
-            Some(TradingRecommendation::Sell) => "🔴 SELL",
-            Some(TradingRecommendation::StrongSell) => "🔴🔴 STRONG SELL",
-            None => "N/A",
-        };
-        let confidence = s.ai_confidence.map(|c| format!("{}%", c.round_dp(0))).unwrap_or_else(|| "N/A".to_string());
-        let reasoning = s.ai_reasoning.as_deref().unwrap_or("No analysis available");
-        
-        // Format support/resistance section
-        let sr_section = if s.support.is_some() || s.resistance.is_some() {
-            format!(r#"
-━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━
-📐 SUPPORT & RESISTANCE
-━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━
-  Strong Resistance: {strong_res}
-  Resistance (R1):   {res}
-  ─── Pivot Point:   {pivot} ───
-  Support (S1):      {sup}
-  Strong Support:    {strong_sup}
-"#,
-                strong_res = s.strong_resistance.map(|p| format!("${:.2}", p)).unwrap_or_else(|| "Not calculated".to_string()),
-                res = s.resistance.map(|p| format!("${:.2}", p)).unwrap_or_else(|| "Not calculated".to_string()),
-                pivot = s.pivot_point.map(|p| format!("${:.2}", p)).unwrap_or_else(|| "N/A".to_string()),
-                sup = s.support.map(|p| format!("${:.2}", p)).unwrap_or_else(|| "Not calculated".to_string()),
-                strong_sup = s.strong_support.map(|p| format!("${:.2}", p)).unwrap_or_else(|| "Not calculated".to_string()),
-            )
-        } else {
-            String::new()
-        };
-        
-        // Format trade limiter section
-        let trade_limit_section = format!(r#"
-━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━
-📊 DAILY TRADE LIMITS
-━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━
-  Trades Today:      {trades}/{max}
-  Can Trade:         {can_trade}
-  {next_day}
-"#,
-            trades = s.trades_today,
-            max = s.max_trades_per_day,
-            can_trade = if s.can_trade { "✅ Yes" } else { "❌ No (limit reached)" },
-            next_day = s.next_trading_day.as_ref().map(|d| format!("Next Trading Day: {}", d)).unwrap_or_default(),
-        );
-
-        let ai_section = if s.ai_enabled {
-            format!(r#"
-━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━
-🧠 AI ADVISOR (Ollama)
-━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━
-  Recommendation:    {rec}
-  Confidence:        {conf}
-  Analysis:          {reason}
-"#, rec = rec_emoji, conf = confidence, reason = reasoning)
-        } else {
-            format!(r#"
-━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━
-🧠 AI ADVISOR
-━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━
-  Status:            ⚠️  Not connected (using fallback)
-  To enable:         Install Ollama and run: ollama pull mistral
-"#)
-        };
+/// Price in the display currency rounded to `dp` decimals, or a placeholder when unset
+struct Price<'a>(Option<Decimal>, u32, &'a str, &'a DisplayFormat);
+
+impl fmt::Display for Price<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.0 {
+            Some(p) => f.write_str(&self.3.money(p, self.1)),
+            None => f.write_str(self.2),
+        }
+    }
+}
+
+/// A value rounded to `dp` decimals, or "N/A" when unset
+struct Value(Option<Decimal>, u32);
+
+impl fmt::Display for Value {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.0 {
+            Some(v) => write!(f, "{}", v.round_dp(self.1)),
+            None => f.write_str("N/A"),
+        }
+    }
+}
+
+const RULE: &str = "━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━";
+/// Values start at this column after the two-space indent, whatever the label's language
+const LABEL_WIDTH: usize = 19;
+
+fn section_header(w: &mut impl Write, title: &str) -> io::Result<()> {
+    write!(w, "{RULE}\n{title}\n{RULE}\n")
+}
+
+/// One `Label: value` line of the report
+fn field(w: &mut impl Write, label: &str, value: impl fmt::Display) -> io::Result<()> {
+    writeln!(w, "  {:<LABEL_WIDTH$}{}", label, value)
+}
 
-        let report = format!(r#"
-╔════════════════════════════════════════════╗
+fn render_report(w: &mut impl Write, s: &PortfolioStatus) -> io::Result<()> {
+    let t = s.language.strings();
+    let local_time: DateTime<Local> = s.last_updated.into();
+    let started_local: DateTime<Local> = s.bot_started.into();
+    let dp = s.price_decimals;
+    
+    let mode_banner = if s.is_simulation { t.banner_simulation } else { t.banner_live };
+
+    write!(w, r#"╔════════════════════════════════════════════╗
 {mode_banner}
 ╠════════════════════════════════════════════╣
-║  CRYPTO TRADING BOT - PORTFOLIO STATUS     ║
+║  {title:<42}║
 ╚════════════════════════════════════════════╝
 
-📅 Last Updated: {last_updated}
-🚀 Bot Started:  {started}
-⏱️  Uptime:       {uptime}
-
-━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━
-📊 MARKET DATA - {symbol}
-━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━
-  Current Price:     ${current_price}
-  24h Change:        ${change_24h} ({change_percent}%)
-  24h High:          ${high_24h}
-  24h Low:           ${low_24h}
-
-━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━
-🎯 TRADING TARGETS
-━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━
-  Stop-Loss:         {stop_loss} ({stop_loss_pct}%)
-  Take-Profit:       {take_profit} ({take_profit_pct}%)
-  Buy Target:        {buy_target}
-  Sell Target:       {sell_target}
-{sr_section}{ai_section}{trade_limit_section}
-━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━
-📈 CURRENT POSITION
-━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━
-  Status:            {position_status}
-  Entry Price:       {entry_price}
-  Position Size:     {position_size}
-  Position Value:    ${position_value}
-  Unrealized P&L:    ${unrealized_pnl} ({unrealized_pnl_pct}%)
-
-━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━
-💰 BALANCES
-━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━
-{balances}
-  ─────────────────────────────────
-  Total Portfolio:   ${total_value}
-
-━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━
-📉 PERFORMANCE STATISTICS
-━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━
-  Realized P&L:      ${realized_pnl}
-  Total Trades:      {total_trades}
-  Winning Trades:    {winning_trades}
-  Losing Trades:     {losing_trades}
-  Win Rate:          {win_rate}%
-  Largest Win:       ${largest_win}
-  Largest Loss:      ${largest_loss}
-
-━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━
-🤖 STRATEGY SIGNALS
-━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━
-  Current Signal:    {signal}
-  SMA Short:         {sma_short}
-  SMA Long:          {sma_long}
-  RSI (14):          {rsi}
-
-━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━
-🔔 LAST EVENT
-━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━
-  {last_event}
-
-{alerts_section}
-━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━
+📅 {last_updated_label:<14}{last_updated}
+🚀 {started_label:<14}{started}
+⏱️  {uptime_label:<14}{uptime}
+
 "#,
-            mode_banner = mode_banner,
-            last_updated = local_time.format("%Y-%m-%d %H:%M:%S"),
-            started = started_local.format("%Y-%m-%d %H:%M:%S"),
-            uptime = format_duration(s.last_updated.signed_duration_since(s.bot_started)),
-            symbol = s.symbol,
-            current_price = s.current_price.round_dp(2),
-            change_24h = s.price_change_24h.round_dp(2),
-            change_percent = s.price_change_24h_percent.round_dp(2),
-            high_24h = s.high_24h.round_dp(2),
-            low_24h = s.low_24h.round_dp(2),
-            stop_loss = s.stop_loss_price.map(|p| format!("${}", p.round_dp(2))).unwrap_or_else(|| "Not set".to_string()),
-            stop_loss_pct = s.stop_loss_percent,
-            take_profit = s.take_profit_price.map(|p| format!("${}", p.round_dp(2))).unwrap_or_else(|| "Not set".to_string()),
-            take_profit_pct = s.take_profit_percent,
-            buy_target = s.buy_target_price.map(|p| format!("${}", p.round_dp(2))).unwrap_or_else(|| "Not set".to_string()),
-            sell_target = s.sell_target_price.map(|p| format!("${}", p.round_dp(2))).unwrap_or_else(|| "Not set".to_string()),
-            sr_section = sr_section,
-            ai_section = ai_section,
-            trade_limit_section = trade_limit_section,
-            position_status = position_status,
-            entry_price = s.entry_price.map(|p| format!("${}", p.round_dp(2))).unwrap_or_else(|| "N/A".to_string()),
-            position_size = s.position_size.round_dp(6),
-            position_value = s.position_value.round_dp(2),
-            unrealized_pnl = s.unrealized_pnl.round_dp(2),
-            unrealized_pnl_pct = s.unrealized_pnl_percent.round_dp(2),
-            balances = format_balances(&s.balances),
-            total_value = s.total_portfolio_value.round_dp(2),
-            realized_pnl = s.realized_pnl.round_dp(2),
-            total_trades = s.total_trades,
-            winning_trades = s.winning_trades,
-            losing_trades = s.losing_trades,
-            win_rate = s.win_rate.round_dp(1),
-            largest_win = s.largest_win.round_dp(2),
-            largest_loss = s.largest_loss.round_dp(2),
-            signal = signal_emoji,
-            sma_short = s.sma_short.map(|v| format!("{}", v.round_dp(2))).unwrap_or_else(|| "N/A".to_string()),
-            sma_long = s.sma_long.map(|v| format!("{}", v.round_dp(2))).unwrap_or_else(|| "N/A".to_string()),
-            rsi = s.rsi.map(|v| format!("{}", v.round_dp(2))).unwrap_or_else(|| "N/A".to_string()),
-            last_event = s.last_event,
-            alerts_section = format_alerts(&s.active_alerts),
-        );
-
-        // Write to file (overwrites completely)
-        fs::write(&self.report_path, report.trim())?;
-        info!("📄 Portfolio report written to {}", self.report_path);
-        
-        Ok(())
+        title = t.title,
+        last_updated_label = t.last_updated,
+        last_updated = local_time.format("%Y-%m-%d %H:%M:%S"),
+        started_label = t.bot_started,
+        started = started_local.format("%Y-%m-%d %H:%M:%S"),
+        uptime_label = t.uptime,
+        uptime = format_duration(s.last_updated.signed_duration_since(s.bot_started)),
+    )?;
+
+    section_header(w, &format!("{} - {}", t.market_data, s.symbol))?;
+    let money = &s.display;
+    field(w, t.current_price, money.money(s.current_price, dp))?;
+    field(w, t.change_24h, format!("{} ({}%)", money.money(s.price_change_24h, 2), money.number(s.price_change_24h_percent, 2)))?;
+    field(w, t.high_24h, money.money(s.high_24h, dp))?;
+    field(w, t.low_24h, money.money(s.low_24h, dp))?;
+    if let (Some(bid), Some(ask)) = (s.best_bid, s.best_ask) {
+        let book = BookTicker { bid_price: bid, bid_qty: Decimal::ZERO, ask_price: ask, ask_qty: Decimal::ZERO };
+        field(w, t.bid_ask, format!("{} / {} ({} {}%)", money.money(bid, dp), money.money(ask, dp), t.spread,
+            book.spread_percent().round_dp(3)))?;
     }
-}
+    if let Some(options) = &s.options {
+        if let Some(ratio) = options.put_call_ratio {
+            field(w, t.put_call, ratio.round_dp(2))?;
+        }
+        if let (Some(pain), Some(expiry)) = (options.max_pain, options.max_pain_expiry) {
+            field(w, t.max_pain, format!("{} ({} {})", money.money(pain, 0), expiry.format("%d %b"), t.expiry))?;
+        }
+    }
+    if let Some(global) = &s.global_market {
+        field(w, t.btc_dominance, format!("{}%", global.btc_dominance.round_dp(2)))?;
+        field(w, t.total_market_cap, format!("{}B ({:+}% 24h)", money.money(global.total_market_cap / dec!(1_000_000_000), 0),
+            global.market_cap_change_24h_percent.round_dp(2)))?;
+    }
+    writeln!(w)?;
+
+    section_header(w, t.targets)?;
+    let sources = s.target_sources.unwrap_or_default();
+    let source = |source: Option<TargetSource>| match (s.target_sources, source) {
+        (Some(_), Some(TargetSource::Ai)) => format!(" [{}]", t.source_ai),
+        (Some(_), Some(TargetSource::Fallback)) => format!(" [{}]", t.source_fallback),
+        (Some(_), Some(TargetSource::Manual)) => format!(" [{}]", t.source_manual),
+        _ => String::new(),
+    };
+    field(w, t.stop_loss, format!("{} ({}%){}", Price(s.stop_loss_price, dp, t.not_set, &s.display), s.stop_loss_percent,
+        source(Some(sources.stop_loss))))?;
+    field(w, t.take_profit, format!("{} ({}%){}", Price(s.take_profit_price, dp, t.not_set, &s.display), s.take_profit_percent,
+        source(Some(sources.take_profit))))?;
+    field(w, t.buy_target, format!("{}{}", Price(s.buy_target_price, dp, t.not_set, &s.display), source(sources.buy_target)))?;
+    field(w, t.sell_target, format!("{}{}", Price(s.sell_target_price, dp, t.not_set, &s.display), source(sources.sell_target)))?;
 
-fn format_balances(balances: &HashMap<String, Decimal>) -> String {
-    if balances.is_empty() {
-        return "  No balances".to_string();
+    if s.support.is_some() || s.resistance.is_some() {
+        writeln!(w)?;
+        section_header(w, t.support_resistance)?;
+        field(w, t.strong_resistance, Price(s.strong_resistance, dp, t.not_calculated, &s.display))?;
+        field(w, t.resistance, Price(s.resistance, dp, t.not_calculated, &s.display))?;
+        writeln!(w, "  ─── {:<15}{} ───", t.pivot, Price(s.pivot_point, dp, "N/A", &s.display))?;
+        field(w, t.support, Price(s.support, dp, t.not_calculated, &s.display))?;
+        field(w, t.strong_support, Price(s.strong_support, dp, t.not_calculated, &s.display))?;
+        if !s.levels.is_empty() {
+            writeln!(w, "  {}", t.scored_levels)?;
+            for level in &s.levels {
+                let side = if level.price > s.current_price { "R" } else { "S" };
+                let age = (s.last_updated.timestamp_millis() - level.last_touch).max(0) / 3_600_000;
+                let wall = if level.wall.is_some() { " 🧱" } else { "" };
+                writeln!(w, "    {} {:<16} {} {:<6} | {} {} | {}h {}{}", side, Price(Some(level.price), dp, "", &s.display).to_string(),
+                    t.score, level.score.to_string(), level.touches, t.touches, age, t.ago, wall)?;
+            }
+        }
+        if !s.liquidity_walls.is_empty() {
+            writeln!(w, "  {}", t.liquidity_walls)?;
+            for wall in &s.liquidity_walls {
+                let side = if wall.side == OrderSide::Buy { t.bid_wall } else { t.ask_wall };
+                writeln!(w, "    🧱 {:<9} {:<16} {} | {}/{} {}", side, Price(Some(wall.price), dp, "", &s.display).to_string(),
+                    wall.quantity.normalize(), wall.seen, wall.snapshots, t.snapshots)?;
+            }
+        }
+        if let Some(profile) = &s.volume_profile {
+            field(w, t.point_of_control, Price(Some(profile.point_of_control), dp, "", &s.display))?;
+            field(w, t.value_area, format!("{} - {}", Price(Some(profile.value_area_low), dp, "", &s.display),
+                Price(Some(profile.value_area_high), dp, "", &s.display)))?;
+        }
     }
-    
-    let mut result = Vec::new();
-    for (asset, amount) in balances {
+
+    writeln!(w)?;
+    if s.ai_enabled {
+        let recommendation = match &s.ai_recommendation {
+            Some(TradingRecommendation::StrongBuy) => format!("🟢🟢 {}", t.strong_buy),
+            Some(TradingRecommendation::Buy) => format!("🟢 {}", t.buy),
+            Some(TradingRecommendation::Hold) => format!("⚪ {}", t.hold),
+            Some(TradingRecommendation::Sell) => format!("🔴 {}", t.sell),
+            Some(TradingRecommendation::StrongSell) => format!("🔴🔴 {}", t.strong_sell),
+            None => "N/A".to_string(),
+        };
+        section_header(w, &format!("{} (Ollama)", t.ai_advisor))?;
+        field(w, t.recommendation, recommendation)?;
+        field(w, t.confidence, s.ai_confidence.map(|c| format!("{}%", c.round_dp(0))).as_deref().unwrap_or("N/A"))?;
+        field(w, t.analysis, s.ai_reasoning.as_deref().unwrap_or(t.no_analysis))?;
+    } else {
+        section_header(w, t.ai_advisor)?;
+        field(w, t.status, t.ai_not_connected)?;
+        field(w, t.to_enable, t.ai_enable_hint)?;
+    }
+
+    writeln!(w)?;
+    section_header(w, t.limits)?;
+    field(w, t.trades_today, format!("{}/{}", s.trades_today, s.max_trades_per_day))?;
+    field(w, t.can_trade, if s.can_trade { format!("✅ {}", t.yes) } else { format!("❌ {}", t.limit_reached) })?;
+    if let Some(day) = &s.next_trading_day {
+        field(w, t.next_trading_day, day)?;
+    }
+    if let Some(reason) = &s.entries_paused {
+        field(w, t.entries_paused, format!("⏸️ {}", reason))?;
+    }
+    if s.consecutive_losses > 0 {
+        let backoff = match s.loss_backoff_percent {
+            Some(p) if p.is_zero() => format!(" - 🧊 {}", t.paused_until_win),
+            Some(p) => format!(" - 🧊 {} {}%", t.entry_size, p.normalize()),
+            None => String::new(),
+        };
+        field(w, t.loss_streak, format!("{} {}{}", s.consecutive_losses, t.losses_in_a_row, backoff))?;
+    }
+
+    let position_status = match &s.position_side {
+        Some(OrderSide::Buy) => t.long,
+        Some(OrderSide::Sell) => t.short,
+        None => t.no_position,
+    };
+    writeln!(w)?;
+    section_header(w, t.position)?;
+    field(w, t.status, position_status)?;
+    field(w, t.entry_price, Price(s.entry_price, dp, "N/A", &s.display))?;
+    field(w, t.position_size, s.position_size.round_dp(6))?;
+    field(w, t.position_value, s.display.money(s.position_value, 2))?;
+    field(w, t.unrealized_pnl, format!("{} ({}%)", s.display.money(s.unrealized_pnl, 2), s.unrealized_pnl_percent.round_dp(2)))?;
+    if let Some(excursion) = s.open_excursion {
+        field(w, t.mae_mfe, excursion_text(&s.display, excursion))?;
+    }
+    if let Some(opened) = s.opened_at {
+        field(w, t.held_for, format_duration(s.last_updated.signed_duration_since(opened)))?;
+    }
+    writeln!(w)?;
+    write_positions(w, s)?;
+
+    write_futures(w, s)?;
+    section_header(w, t.balances)?;
+    write_balances(w, s)?;
+    write_liabilities(w, s)?;
+    writeln!(w, "  ─────────────────────────────────")?;
+    field(w, t.total_portfolio, format!("{} {}", s.total_portfolio_value.round_dp(2), s.quote_asset))?;
+    if let Some(currency) = &s.display.currency {
+        field(w, "", format!("≈ {} ({})", s.display.money(s.total_portfolio_value, 2), currency.code))?;
+    }
+    write_open_orders(w, s)?;
+
+    writeln!(w)?;
+    section_header(w, t.performance)?;
+    field(w, t.realized_pnl, s.display.money(s.realized_pnl, 2))?;
+    field(w, t.total_trades, s.total_trades)?;
+    field(w, t.winning_trades, s.winning_trades)?;
+    field(w, t.losing_trades, s.losing_trades)?;
+    field(w, t.win_rate, format!("{}%", s.win_rate.round_dp(1)))?;
+    field(w, t.largest_win, s.display.money(s.largest_win, 2))?;
+    field(w, t.largest_loss, s.display.money(s.largest_loss, 2))?;
+    field(w, t.fees_paid, s.display.money(s.total_fees, 2))?;
+    if let Some(average) = s.closed_trades.average_excursion() {
+        field(w, t.avg_mae_mfe, format!("{} ({} {})", excursion_text(&s.display, average),
+            s.closed_trades.trades, t.trades))?;
+    }
+    if let Some(average) = s.closed_trades.average_holding() {
+        field(w, t.avg_holding_time, format!("{} ({} {})", format_duration(average),
+            t.longest, format_duration(s.closed_trades.longest_holding())))?;
+    }
+    if let Some(start) = s.day_start_value.filter(|v| *v > Decimal::ZERO) {
+        let change = s.total_portfolio_value - start;
+        field(w, t.today, format!("{}{} ({:+}%) | {} {} | {} {}",
+            if change >= Decimal::ZERO { "+" } else { "-" }, s.display.money(change.abs(), 2),
+            (change / start * dec!(100)).round_dp(2), s.day_trades, t.trades, t.realized, s.display.money(s.day_realized_pnl, 2)))?;
+    }
+    if let Some(twr) = s.time_weighted_return {
+        field(w, t.net_deposits, format!("{} {}", s.net_capital_flows.round_dp(2), s.quote_asset))?;
+        field(w, t.time_weighted_return, format!("{}%", twr.round_dp(2)))?;
+    }
+    write_strategies(w, s)?;
+    write_sessions(w, s)?;
+    write_risk(w, s)?;
+    write_exposure(w, s)?;
+
+    write_scanner(w, s)?;
+
+    let signal = match s.current_signal {
+        Signal::Buy => format!("🟢 {}", t.buy),
+        Signal::Sell => format!("🔴 {}", t.sell),
+        Signal::Hold => format!("⚪ {}", t.hold),
+    };
+    writeln!(w)?;
+    section_header(w, t.signals)?;
+    field(w, t.current_signal, signal)?;
+    field(w, t.sma_short, Value(s.sma_short, dp))?;
+    field(w, t.sma_long, Value(s.sma_long, dp))?;
+    field(w, t.rsi, Value(s.rsi, 2))?;
+    if let Some(ratio) = s.volume_ratio {
+        field(w, t.volume, format!("{}x {}", ratio, t.average))?;
+    }
+    for divergence in &s.divergences {
+        field(w, t.divergence, divergence)?;
+    }
+    writeln!(w)?;
+
+    section_header(w, t.last_event)?;
+    writeln!(w, "  {}", s.last_event)?;
+    writeln!(w)?;
+    write_decisions(w, s)?;
+    write_alerts(w, s)?;
+    write!(w, "{RULE}")
+}
+
+fn write_balances(w: &mut impl Write, s: &PortfolioStatus) -> io::Result<()> {
+    let mut any = false;
+    for (asset, amount) in &s.balances {
         if *amount > Decimal::ZERO {
-            result.push(format!("  {:<18} {}", format!("{}:", asset), amount.round_dp(6)));
+            any = true;
+            write!(w, "  {:<18} {}", format!("{}:", asset), amount.round_dp(6))?;
+            if *asset != s.quote_asset
+                && let Some(value) = s.value_in_quote(asset, *amount) {
+                write!(w, " (≈ {} {})", value.round_dp(2), s.quote_asset)?;
+            }
+            writeln!(w)?;
         }
     }
-    
-    if result.is_empty() {
-        "  No balances".to_string()
-    } else {
-        result.join("\n")
+
+    if !any {
+        writeln!(w, "  {}", s.language.strings().no_balances)?;
     }
+    Ok(())
 }
 
-fn format_alerts(alerts: &[String]) -> String {
-    if alerts.is_empty() {
-        return String::new();
+fn write_liabilities(w: &mut impl Write, s: &PortfolioStatus) -> io::Result<()> {
+    let t = s.language.strings();
+    let mut owed: Vec<_> = s.liabilities.iter().filter(|(_, amount)| **amount > Decimal::ZERO).collect();
+    owed.sort();
+    for (asset, amount) in owed {
+        write!(w, "  {:<18} -{}", format!("{} {}:", asset, t.borrowed), amount.round_dp(6))?;
+        if *asset != s.quote_asset
+            && let Some(value) = s.value_in_quote(asset, *amount) {
+            write!(w, " (≈ -{} {})", value.round_dp(2), s.quote_asset)?;
+        }
+        writeln!(w)?;
     }
-    
-    let mut result = String::from("━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━\n⚠️  RECENT ALERTS\n━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━━\n");
-    
-    for alert in alerts.iter().rev().take(5) {
-        result.push_str(&format!("  • {}\n", alert));
+    if let Some(level) = s.margin_level {
+        field(w, t.margin_level, level.round_dp(2))?;
+    }
+    Ok(())
+}
+
+fn write_strategies(w: &mut impl Write, s: &PortfolioStatus) -> io::Result<()> {
+    if s.strategies.is_empty() {
+        return Ok(());
+    }
+
+    let t = s.language.strings();
+    writeln!(w)?;
+    section_header(w, t.strategies)?;
+    for strategy in &s.strategies {
+        let change_24h = strategy.change_24h_percent
+            .map(|c| format!(", 24h {}%", c.round_dp(2)))
+            .unwrap_or_default();
+        writeln!(w, "  {:<18} {}% | {} {} ({}%{}) | P&L {} | {} {} | {} {}",
+            format!("{}:", strategy.name), strategy.weight_percent.normalize(),
+            strategy.equity.round_dp(2), s.quote_asset, strategy.return_percent.round_dp(2), change_24h,
+            strategy.realized_pnl.round_dp(2), strategy.position_qty.round_dp(6), s.base_asset, strategy.trades, t.trades)?;
+    }
+    Ok(())
+}
+
+fn write_scanner(w: &mut impl Write, s: &PortfolioStatus) -> io::Result<()> {
+    if s.scanner.is_empty() {
+        return Ok(());
+    }
+
+    writeln!(w)?;
+    section_header(w, s.language.strings().scanner)?;
+    for c in &s.scanner {
+        let setups: Vec<&str> = c.setups.iter().map(|setup| setup.label()).collect();
+        writeln!(w, "  {:<14} {:>14} | RSI {:>6} | vol {:>6}x | {}", c.symbol, c.price,
+            c.rsi.map_or("-".to_string(), |r| r.round_dp(1).to_string()),
+            c.volume_ratio.map_or("-".to_string(), |r| r.to_string()), setups.join(", "))?;
+    }
+    Ok(())
+}
+
+/// Trades, win rate and P&L by entry session, with each session's typical hourly range
+fn write_sessions(w: &mut impl Write, s: &PortfolioStatus) -> io::Result<()> {
+    let stats = &s.closed_trades.by_session;
+    if stats.is_empty() && s.session_volatility.is_empty() {
+        return Ok(());
+    }
+
+    let t = s.language.strings();
+    writeln!(w)?;
+    section_header(w, t.sessions)?;
+    writeln!(w, "  {:<8} {:<6} {:>7} {:>9} {:>12} {:>15}", t.col_session, "UTC", t.col_trades,
+        t.win_rate.trim_end_matches(':'), "P&L", t.col_hourly_range)?;
+    for session in Session::ALL {
+        let tally = stats.get(session);
+        writeln!(w, "  {:<8} {:<6} {:>7} {:>9} {:>12} {:>15}", session.label(), session.hours(), tally.trades,
+            tally.win_rate().map_or("-".to_string(), |r| format!("{}%", s.display.number(r, 1))),
+            s.display.money(tally.pnl, 2),
+            s.session_volatility.average_range(session).map_or("-".to_string(), |r| format!("{}%", s.display.number(r, 2))))?;
+    }
+    Ok(())
+}
+
+fn write_risk(w: &mut impl Write, s: &PortfolioStatus) -> io::Result<()> {
+    if s.value_at_risk.is_none() && s.max_var_percent.is_none() {
+        return Ok(());
+    }
+
+    let t = s.language.strings();
+    writeln!(w)?;
+    section_header(w, t.risk)?;
+    let amount = |percent: Decimal| (percent / dec!(100) * s.total_portfolio_value).round_dp(2);
+    match &s.value_at_risk {
+        Some(var) => {
+            let confidence = Decimal::try_from(var.confidence * 100.0).unwrap_or_default().round_dp(1);
+            field(w, &format!("{} ({}%):", t.value_at_risk, confidence.normalize()), format!("{}% ({} {}) {}",
+                var.parametric_percent.round_dp(2), amount(var.parametric_percent), s.quote_asset, t.parametric))?;
+            match var.historical_percent {
+                Some(h) => field(w, "", format!("{}% ({} {}) {}", h.round_dp(2), amount(h), s.quote_asset, t.historical))?,
+                None => field(w, "", t.var_needs_history)?,
+            }
+        }
+        None => field(w, &format!("{}:", t.value_at_risk), t.var_collecting)?,
+    }
+    if let Some(max) = s.max_var_percent {
+        field(w, t.entry_limit, format!("{}% {}", max.normalize(), t.of_equity))?;
+    }
+    Ok(())
+}
+
+fn write_exposure(w: &mut impl Write, s: &PortfolioStatus) -> io::Result<()> {
+    let exposure = s.exposure();
+    if exposure.assets.is_empty() {
+        return Ok(());
+    }
+
+    let t = s.language.strings();
+    writeln!(w)?;
+    section_header(w, t.exposure)?;
+    for (asset, value, percent) in &exposure.assets {
+        writeln!(w, "  {:<18} {} {} ({}% {})", format!("{}:", asset), value.round_dp(2), s.quote_asset,
+            percent.round_dp(1), t.of_equity)?;
+    }
+    if let (Some(at_risk), Some(percent)) = (exposure.at_risk, exposure.at_risk_percent) {
+        field(w, t.at_risk, format!("{} {} ({}% {})", at_risk.round_dp(2), s.quote_asset, percent.round_dp(2), t.of_equity))?;
+    }
+    field(w, t.leverage, format!("{}x", exposure.leverage.round_dp(2)))?;
+    Ok(())
+}
+
+fn write_futures(w: &mut impl Write, s: &PortfolioStatus) -> io::Result<()> {
+    let Some(f) = &s.futures else {
+        return Ok(());
+    };
+
+    let t = s.language.strings();
+    let dp = s.price_decimals;
+    section_header(w, t.futures)?;
+    field(w, t.leverage, i18n::fill(t.futures_leverage, &[("leverage", &f.leverage), ("margin", &f.margin_type),
+        ("mode", &if f.hedge_mode { t.hedge_mode } else { t.one_way_mode })]))?;
+    if !f.legs.is_empty() {
+        field(w, t.mark_price, f.mark_price.round_dp(dp))?;
+        writeln!(w, "  {:<6} {:>16} {:>12} {:>16} {:>22}", t.col_side, t.col_size, t.col_entry,
+            t.unrealized_pnl.trim_end_matches(':'), t.col_liquidation)?;
+        for leg in &f.legs {
+            let side = if leg.amount > Decimal::ZERO { t.long } else { t.short };
+            let liquidation = leg.liquidation_price.map_or("-".to_string(), |l| {
+                let distance = (l - f.mark_price).abs() / f.mark_price * dec!(100);
+                format!("{} ({})", l.round_dp(dp), i18n::fill(t.liquidation_distance, &[("distance", &distance.round_dp(1))]))
+            });
+            writeln!(w, "  {:<6} {:>16} {:>12} {:>16} {:>22}", side, format!("{} {}", leg.amount.abs(), s.base_asset),
+                leg.entry_price.round_dp(dp), format!("{} {}", leg.unrealized_pnl.round_dp(2), s.quote_asset), liquidation)?;
+        }
+        if f.legs.len() > 1 {
+            let net = f.net();
+            let side = match net.net_qty().cmp(&Decimal::ZERO) {
+                std::cmp::Ordering::Greater => t.long,
+                std::cmp::Ordering::Less => t.short,
+                std::cmp::Ordering::Equal => t.flat,
+            };
+            field(w, t.net_position, format!("{} {} {} ({} {})", side, net.net_qty().abs(), s.base_asset, net.hedged_qty(), t.hedged))?;
+            field(w, t.locked_pnl, format!("{} {}", net.locked_pnl().round_dp(2), s.quote_asset))?;
+            if let Some(break_even) = net.break_even() {
+                field(w, t.break_even, break_even.round_dp(dp))?;
+            }
+        }
+    }
+    field(w, t.funding_fees, format!("{} {}", f.funding_total.round_dp(4), s.quote_asset))?;
+    writeln!(w)
+}
+
+fn write_open_orders(w: &mut impl Write, s: &PortfolioStatus) -> io::Result<()> {
+    if s.open_orders.is_empty() {
+        return Ok(());
+    }
+
+    let t = s.language.strings();
+    let now = Utc::now();
+    writeln!(w)?;
+    section_header(w, t.open_orders)?;
+    for order in &s.open_orders {
+        writeln!(w, "  #{} {} {} {}/{} @ {} ({})",
+            order.order_id, order.side, order.order_type,
+            order.executed_qty.normalize(), order.orig_qty.normalize(),
+            s.display.money(order.price, s.price_decimals),
+            i18n::fill(t.order_age, &[("age", &format_duration(order.age(now)))]))?;
+    }
+    Ok(())
+}
+
+fn write_decisions(w: &mut impl Write, s: &PortfolioStatus) -> io::Result<()> {
+    if s.decisions.is_empty() {
+        return Ok(());
+    }
+
+    section_header(w, s.language.strings().decision_log)?;
+    for decision in s.decisions.iter().rev() {
+        let repeated = if decision.cycles > 1 { format!(" (×{})", decision.cycles) } else { String::new() };
+        writeln!(w, "  {} {}{}", decision.at.with_timezone(&Local).format("%H:%M:%S"), decision.text, repeated)?;
+    }
+    writeln!(w)
+}
+
+fn write_alerts(w: &mut impl Write, s: &PortfolioStatus) -> io::Result<()> {
+    if s.active_alerts.is_empty() {
+        return Ok(());
     }
     
-    result
+    section_header(w, s.language.strings().recent_alerts)?;
+    for alert in s.active_alerts.iter().rev().take(5) {
+        writeln!(w, "  • {}", alert)?;
+    }
+    Ok(())
 }
 
 fn format_duration(duration: chrono::Duration) -> String {
@@ -612,3 +1645,77 @@ fn format_duration(duration: chrono::Duration) -> String {
         format!("{}s", seconds)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_report_written_only_when_dirty() {
+        let dir = std::env::temp_dir().join(format!("ctb_report_{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("portfolio_status.txt");
+
+        let mut reporter = PortfolioReporter::new("BTCUSDT", true, &path);
+        reporter.update_price(dec!(42000.001));
+        reporter.write_if_dirty().unwrap();
+        let report = std::fs::read_to_string(&path).unwrap();
+        assert!(report.starts_with("╔"));
+        assert!(report.contains("Current Price:     $42000.00"));
+        assert!(dir.join("portfolio_status.html").exists());
+
+        // A move below the displayed precision leaves the report untouched
+        std::fs::remove_file(&path).unwrap();
+        reporter.update_price(dec!(42000.002));
+        reporter.write_if_dirty().unwrap();
+        assert!(!path.exists());
+
+        reporter.update_price(dec!(42001));
+        reporter.write_if_dirty().unwrap();
+        assert!(std::fs::read_to_string(&path).unwrap().contains("$42001"));
+
+        // Repeated decisions collapse into one line, and the log keeps the latest ten
+        reporter.record_decision("main: holding - no exit target hit".to_string());
+        reporter.record_decision("main: holding - no exit target hit".to_string());
+        reporter.write_if_dirty().unwrap();
+        assert!(std::fs::read_to_string(&path).unwrap().contains("main: holding - no exit target hit (×2)"));
+        for i in 0..12 {
+            reporter.record_decision(format!("main: entered @ {}", i));
+        }
+        assert_eq!(reporter.status().decisions.len(), DECISION_LOG_SIZE);
+        assert_eq!(reporter.status().decisions[0].text, "main: entered @ 2");
+
+        std::fs::remove_dir_all(&dir).ok();
+    }
+
+    #[test]
+    fn test_exposure_per_asset_and_at_risk() {
+        let mut status = PortfolioStatus::new("BTCUSDT", false);
+        status.current_price = dec!(50000);
+        status.conversion_rates.insert("ETH".to_string(), dec!(2500));
+        status.balances.insert("USDT".to_string(), dec!(5000));
+        status.balances.insert("BTC".to_string(), dec!(0.06)); // 3000
+        status.balances.insert("ETH".to_string(), dec!(0.8)); // 2000
+        status.balances.insert("XYZ".to_string(), dec!(10)); // unpriced
+        status.update_total_value();
+        status.position_size = dec!(0.06);
+        status.stop_loss_price = Some(dec!(47500));
+
+        let exposure = status.exposure();
+        assert_eq!(exposure.assets, vec![
+            ("BTC".to_string(), dec!(3000), dec!(30)),
+            ("ETH".to_string(), dec!(2000), dec!(20)),
+        ]);
+        assert_eq!(exposure.gross_notional, dec!(5000));
+        assert_eq!(exposure.at_risk, Some(dec!(150)));
+        assert_eq!(exposure.at_risk_percent, Some(dec!(1.5)));
+        assert_eq!(exposure.leverage, dec!(0.5));
+
+        // Borrowed USDT is owed back: equity drops to 6000 and leverage rises
+        status.liabilities.insert("USDT".to_string(), dec!(4000));
+        status.update_total_value();
+        assert_eq!(status.total_portfolio_value, dec!(6000));
+        assert_eq!(status.owned_balance("USDT"), dec!(1000));
+        assert_eq!(status.exposure().leverage.round_dp(4), dec!(0.8333));
+    }
+}
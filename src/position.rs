@@ -0,0 +1,571 @@
+use crate::config::Config;
+use crate::error::BotError;
+use crate::models::Execution;
+use crate::sessions::{Session, SessionStats};
+use anyhow::{anyhow, Result};
+use chrono::{DateTime, Duration, Utc};
+use rust_decimal::Decimal;
+use rust_decimal_macros::dec;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+/// Lifecycle of a position: an entry order is `Pending` until it fills, an exit order
+/// is `Closing` until it fills. A failed order returns to the previous state.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PositionState {
+    Pending,
+    Open,
+    Closing,
+    Closed,
+}
+
+impl fmt::Display for PositionState {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Pending => write!(f, "PENDING"),
+            Self::Open => write!(f, "OPEN"),
+            Self::Closing => write!(f, "CLOSING"),
+            Self::Closed => write!(f, "CLOSED"),
+        }
+    }
+}
+
+/// The position trading the AI/fallback targets
+pub const MAIN_POSITION: &str = "main";
+/// The optional short-horizon position with fixed exits (SCALP_ENABLED)
+pub const SCALP_POSITION: &str = "scalp";
+/// The short leg of a futures account in hedge mode
+pub const SHORT_POSITION: &str = "short";
+
+fn main_id() -> String {
+    MAIN_POSITION.to_string()
+}
+
+/// A holding limit in hours as a duration; unset means none
+pub fn max_holding(hours: Option<Decimal>, name: &str) -> Result<Option<Duration>> {
+    match hours {
+        None => Ok(None),
+        Some(h) if h > Decimal::ZERO => Ok(Some(Duration::seconds((h * dec!(3600)).round().try_into().unwrap_or(i64::MAX)))),
+        Some(h) => Err(BotError::config(format!("{} must be positive, got {}", name, h)).into()),
+    }
+}
+
+/// Which target an open position crossed
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ExitTrigger {
+    StopLoss,
+    TakeProfit,
+    SellTarget,
+    /// Held longer than the slot's max holding time
+    MaxHoldingTime,
+    /// Sold on request through the control interface
+    Manual,
+}
+
+impl ExitTrigger {
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::StopLoss => "Stop-loss",
+            Self::TakeProfit => "Take-profit",
+            Self::SellTarget => "Sell target",
+            Self::MaxHoldingTime => "Max holding time",
+            Self::Manual => "Manual",
+        }
+    }
+
+    /// Exits that limit a loss or lock in a gain; these never wait for an approval
+    pub fn is_protective(self) -> bool {
+        matches!(self, Self::StopLoss | Self::TakeProfit)
+    }
+}
+
+/// Worst and best unrealized move of a trade while open, in percent of the entry price:
+/// the maximum adverse (<= 0) and favorable (>= 0) excursion
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub struct Excursion {
+    pub mae_percent: Decimal,
+    pub mfe_percent: Decimal,
+}
+
+/// Running totals of the excursions and holding times of closed trades
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub struct ClosedTrades {
+    pub trades: u32,
+    pub mae_sum: Decimal,
+    pub mfe_sum: Decimal,
+    #[serde(default)]
+    pub held_seconds: i64,
+    #[serde(default)]
+    pub longest_held_seconds: i64,
+    /// P&L by the session each trade was entered in
+    #[serde(default)]
+    pub by_session: SessionStats,
+}
+
+impl ClosedTrades {
+    pub fn add(&mut self, excursion: Excursion, held: Duration) {
+        self.trades += 1;
+        self.mae_sum += excursion.mae_percent;
+        self.mfe_sum += excursion.mfe_percent;
+        self.held_seconds += held.num_seconds();
+        self.longest_held_seconds = self.longest_held_seconds.max(held.num_seconds());
+    }
+
+    pub fn merge(&mut self, other: &ClosedTrades) {
+        self.trades += other.trades;
+        self.mae_sum += other.mae_sum;
+        self.mfe_sum += other.mfe_sum;
+        self.held_seconds += other.held_seconds;
+        self.longest_held_seconds = self.longest_held_seconds.max(other.longest_held_seconds);
+        self.by_session.merge(&other.by_session);
+    }
+
+    /// Mean time from entry fill to exit fill
+    pub fn average_holding(&self) -> Option<Duration> {
+        (self.trades > 0).then(|| Duration::seconds(self.held_seconds / i64::from(self.trades)))
+    }
+
+    pub fn longest_holding(&self) -> Duration {
+        Duration::seconds(self.longest_held_seconds)
+    }
+
+    pub fn average_excursion(&self) -> Option<Excursion> {
+        (self.trades > 0).then(|| Excursion {
+            mae_percent: self.mae_sum / Decimal::from(self.trades),
+            mfe_percent: self.mfe_sum / Decimal::from(self.trades),
+        })
+    }
+}
+
+/// A position in one symbol: quantity, cost basis, exit targets and where it is in its
+/// lifecycle. Entry and exit math lives here; the reporter only displays it. Several
+/// positions may be held in one symbol at once, told apart by `id`. Long unless `short`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Position {
+    #[serde(default = "main_id")]
+    pub id: String,
+    pub symbol: String,
+    pub state: PositionState,
+    pub qty: Decimal,
+    pub entry_price: Option<Decimal>,
+    /// Commission paid to open; charged to the realized P&L on exit
+    pub entry_fee: Decimal,
+    pub stop_loss: Option<Decimal>,
+    pub take_profit: Option<Decimal>,
+    pub sell_target: Option<Decimal>,
+    pub opened_at: Option<DateTime<Utc>>,
+    /// Fixed stop-loss/take-profit percentages from the entry; when unset the position
+    /// follows the latest AI/fallback targets
+    #[serde(default)]
+    pub fixed_exits: Option<(Decimal, Decimal)>,
+    /// Realized P&L of all closed trades of this position, net of fees
+    #[serde(default)]
+    pub realized_pnl: Decimal,
+    /// Profits when the price falls (futures hedge mode)
+    #[serde(default)]
+    pub short: bool,
+    /// MAE/MFE of the open trade so far
+    #[serde(default)]
+    pub excursion: Excursion,
+    /// MAE/MFE and holding times of the trades this position closed
+    #[serde(default)]
+    pub closed_trades: ClosedTrades,
+    /// Close after this long regardless of the targets (MAX_HOLDING_HOURS, SCALP_MAX_HOLDING_HOURS)
+    #[serde(skip)]
+    pub max_holding: Option<Duration>,
+    /// The price crossed the stop while no cycle was checking (a cycle gap); exits as a
+    /// stop-loss at the next check whatever the price
+    #[serde(default)]
+    pub missed_stop: bool,
+}
+
+impl Position {
+    /// No position yet
+    pub fn new(symbol: &str) -> Self {
+        Self {
+            id: main_id(),
+            symbol: symbol.to_string(),
+            state: PositionState::Closed,
+            qty: Decimal::ZERO,
+            entry_price: None,
+            entry_fee: Decimal::ZERO,
+            stop_loss: None,
+            take_profit: None,
+            sell_target: None,
+            opened_at: None,
+            fixed_exits: None,
+            realized_pnl: Decimal::ZERO,
+            short: false,
+            excursion: Excursion::default(),
+            closed_trades: ClosedTrades::default(),
+            max_holding: None,
+            missed_stop: false,
+        }
+    }
+
+    /// No short position yet
+    pub fn new_short(symbol: &str) -> Self {
+        Self { id: SHORT_POSITION.to_string(), short: true, ..Self::new(symbol) }
+    }
+
+    /// The position slots a loop trades: the main position and, with SCALP_ENABLED, a
+    /// scalp with fixed exits. Saved positions with a matching id are carried over.
+    pub fn slots(config: &Config, saved: &[Position]) -> Result<Vec<Self>> {
+        let mut slots = vec![Self {
+            max_holding: max_holding(config.max_holding_hours, "MAX_HOLDING_HOURS")?,
+            ..Self::new(&config.symbol)
+        }];
+        if config.scalp_enabled {
+            if config.scalp_stop_loss_percent >= Decimal::ZERO || config.scalp_take_profit_percent <= Decimal::ZERO {
+                return Err(BotError::config("SCALP_STOP_LOSS_PERCENT must be negative and SCALP_TAKE_PROFIT_PERCENT positive").into());
+            }
+            if config.scalp_size_percent <= Decimal::ZERO || config.scalp_size_percent > dec!(100) {
+                return Err(BotError::config(format!("SCALP_SIZE_PERCENT must be in (0, 100], got {}", config.scalp_size_percent)).into());
+            }
+            slots.push(Self {
+                id: SCALP_POSITION.to_string(),
+                fixed_exits: Some((config.scalp_stop_loss_percent, config.scalp_take_profit_percent)),
+                max_holding: max_holding(config.scalp_max_holding_hours, "SCALP_MAX_HOLDING_HOURS")?,
+                ..Self::new(&config.symbol)
+            });
+        }
+        for slot in &mut slots {
+            if let Some(saved) = saved.iter().find(|p| p.id == slot.id && p.symbol == slot.symbol) {
+                *slot = Self { fixed_exits: slot.fixed_exits, max_holding: slot.max_holding, ..saved.clone() };
+            }
+        }
+        Ok(slots)
+    }
+
+    pub fn is_main(&self) -> bool {
+        self.id == MAIN_POSITION
+    }
+
+    /// Key for the funds ledger: the symbol for the main position, `symbol:id` otherwise
+    pub fn ledger_key(&self) -> String {
+        if self.is_main() { self.symbol.clone() } else { format!("{}:{}", self.symbol, self.id) }
+    }
+
+    /// An open position found on the exchange or in saved state
+    pub fn resumed(symbol: &str, qty: Decimal, entry_price: Option<Decimal>) -> Self {
+        Self { state: PositionState::Open, qty, entry_price, ..Self::new(symbol) }
+    }
+
+    /// Follow a quantity held on the exchange: adopt it when it was opened or resized
+    /// outside the loop, go flat when it is gone. Returns whether anything changed.
+    pub fn sync(&mut self, qty: Decimal, entry_price: Option<Decimal>) -> bool {
+        let flat = Self {
+            id: self.id.clone(),
+            short: self.short,
+            realized_pnl: self.realized_pnl,
+            closed_trades: self.closed_trades,
+            max_holding: self.max_holding,
+            ..Self::new(&self.symbol)
+        };
+        if qty > Decimal::ZERO && (!self.is_open() || qty != self.qty) {
+            *self = Self { state: PositionState::Open, qty, entry_price, ..flat };
+            true
+        } else if qty.is_zero() && self.is_open() {
+            *self = flat;
+            true
+        } else {
+            false
+        }
+    }
+
+    pub fn is_open(&self) -> bool {
+        self.state == PositionState::Open
+    }
+
+    pub fn is_flat(&self) -> bool {
+        self.state == PositionState::Closed
+    }
+
+    fn transition(&mut self, from: PositionState, to: PositionState) -> Result<()> {
+        if self.state != from {
+            return Err(anyhow!("{} position is {}, cannot move from {} to {}", self.symbol, self.state, from, to));
+        }
+        self.state = to;
+        Ok(())
+    }
+
+    /// An entry order for `qty` is about to be placed
+    pub fn begin_entry(&mut self, qty: Decimal) -> Result<()> {
+        self.transition(PositionState::Closed, PositionState::Pending)?;
+        self.qty = qty;
+        Ok(())
+    }
+
+    pub fn entry_filled(&mut self, fill: Execution) -> Result<()> {
+        self.transition(PositionState::Pending, PositionState::Open)?;
+        self.qty = fill.qty;
+        self.entry_price = Some(fill.price);
+        self.entry_fee = fill.fee;
+        self.opened_at = Some(Utc::now());
+        if let Some((stop_loss_percent, take_profit_percent)) = self.fixed_exits {
+            self.set_percent_targets(stop_loss_percent, take_profit_percent);
+        }
+        Ok(())
+    }
+
+    pub fn entry_failed(&mut self) -> Result<()> {
+        self.transition(PositionState::Pending, PositionState::Closed)?;
+        self.reset();
+        Ok(())
+    }
+
+    /// An exit order is about to be placed
+    pub fn begin_exit(&mut self) -> Result<()> {
+        self.transition(PositionState::Open, PositionState::Closing)
+    }
+
+    /// The exit filled; returns the realized P&L net of the entry and exit fees
+    pub fn exit_filled(&mut self, fill: Execution) -> Result<Decimal> {
+        if self.state == PositionState::Closing {
+            self.track_excursion(fill.price);
+        }
+        self.transition(PositionState::Closing, PositionState::Closed)?;
+        let pnl = self.exit_pnl(fill);
+        self.realized_pnl += pnl;
+        let now = Utc::now();
+        self.closed_trades.add(self.excursion, self.held_for(now).unwrap_or_default());
+        self.closed_trades.by_session.record(Session::of(self.opened_at.unwrap_or(now)), pnl);
+        self.reset();
+        Ok(pnl)
+    }
+
+    pub fn exit_failed(&mut self) -> Result<()> {
+        self.transition(PositionState::Closing, PositionState::Open)
+    }
+
+    /// Close without an order (e.g. only unsellable dust is left)
+    pub fn abandon(&mut self) -> Result<()> {
+        self.transition(PositionState::Open, PositionState::Closed)?;
+        self.reset();
+        Ok(())
+    }
+
+    fn reset(&mut self) {
+        self.qty = Decimal::ZERO;
+        self.entry_price = None;
+        self.entry_fee = Decimal::ZERO;
+        self.opened_at = None;
+        self.excursion = Excursion::default();
+        self.missed_stop = false;
+        if self.fixed_exits.is_some() {
+            self.stop_loss = None;
+            self.take_profit = None;
+        }
+    }
+
+    /// Price move in the position's favour
+    fn gain(&self, from: Decimal, to: Decimal) -> Decimal {
+        if self.short { from - to } else { to - from }
+    }
+
+    /// P&L of an exit fill, net of the entry and exit fees
+    pub fn exit_pnl(&self, fill: Execution) -> Decimal {
+        self.gain(self.entry_price.unwrap_or(fill.price), fill.price) * fill.qty - self.entry_fee - fill.fee
+    }
+
+    pub fn unrealized_pnl(&self, price: Decimal) -> Decimal {
+        self.entry_price.map_or(Decimal::ZERO, |entry| self.gain(entry, price) * self.qty)
+    }
+
+    /// Time since the entry filled
+    pub fn held_for(&self, now: DateTime<Utc>) -> Option<Duration> {
+        self.opened_at.map(|opened| now - opened)
+    }
+
+    /// Widen the open trade's MAE/MFE to include `price`; call on every price seen while open
+    pub fn track_excursion(&mut self, price: Decimal) {
+        if let Some(entry) = self.entry_price.filter(|e| *e > Decimal::ZERO)
+            && !self.is_flat() {
+            let change = self.gain(entry, price) / entry * dec!(100);
+            self.excursion.mae_percent = self.excursion.mae_percent.min(change);
+            self.excursion.mfe_percent = self.excursion.mfe_percent.max(change);
+        }
+    }
+
+    /// Take the stop-loss, take-profit and optional sell target from the latest targets;
+    /// positions with fixed exits keep theirs, and the targets are for longs only
+    pub fn set_targets(&mut self, stop_loss: Decimal, take_profit: Decimal, sell_target: Option<Decimal>) {
+        if self.fixed_exits.is_some() || self.short {
+            return;
+        }
+        self.stop_loss = Some(stop_loss);
+        self.take_profit = Some(take_profit);
+        self.sell_target = sell_target;
+    }
+
+    /// Fixed stop-loss/take-profit percentages from the entry price, mirrored for shorts.
+    /// Both are distances: the stop always lands on the losing side whatever its sign.
+    pub fn set_percent_targets(&mut self, stop_loss_percent: Decimal, take_profit_percent: Decimal) {
+        let direction = if self.short { -dec!(1) } else { dec!(1) };
+        if let Some(entry) = self.entry_price {
+            self.stop_loss = Some(entry * (dec!(1) - direction * stop_loss_percent.abs() / dec!(100)));
+            self.take_profit = Some(entry * (dec!(1) + direction * take_profit_percent.abs() / dec!(100)));
+        }
+    }
+
+    /// How far a stop-loss exit filled past the stop, as (price gap, slippage in the quote
+    /// asset): the price gapped through the stop between two checks. `None` at or better than the stop.
+    pub fn stop_slippage(&self, fill: Execution) -> Option<(Decimal, Decimal)> {
+        let gap = -self.gain(self.stop_loss?, fill.price);
+        (gap > Decimal::ZERO).then_some((gap, gap * fill.qty))
+    }
+
+    /// Whether the price crossed the stop somewhere within `low`..`high`, traded while no
+    /// cycle was checking; if so the stop counts as hit until the position exits
+    pub fn check_missed_stop(&mut self, low: Decimal, high: Decimal) -> bool {
+        self.track_excursion(low);
+        self.track_excursion(high);
+        let worst = if self.short { high } else { low };
+        if self.stop_loss.is_some_and(|sl| self.gain(sl, worst) <= Decimal::ZERO) {
+            self.missed_stop = true;
+        }
+        self.missed_stop
+    }
+
+    /// Alert text when a stop-loss exit filled past the stop: the price gapped through it
+    /// between two checks
+    pub fn stop_gap_alert(&self, fill: Execution) -> Option<String> {
+        let (gap, slippage) = self.stop_slippage(fill)?;
+        Some(format!("🚨 STOP-LOSS GAPPED ({}): stop ${:.2}, filled @ ${:.2} - ${:.2} past the stop, ${:.2} slippage",
+            self.id, self.stop_loss?.round_dp(2), fill.price.round_dp(2), gap.round_dp(2), slippage.round_dp(2)))
+    }
+
+    /// Level `trigger` fires at
+    pub fn exit_level(&self, trigger: ExitTrigger) -> Option<Decimal> {
+        match trigger {
+            ExitTrigger::StopLoss => self.stop_loss,
+            ExitTrigger::TakeProfit => self.take_profit,
+            ExitTrigger::SellTarget => self.sell_target,
+            ExitTrigger::MaxHoldingTime | ExitTrigger::Manual => None,
+        }
+    }
+
+    /// The target an open position has crossed at `price`, stop-loss first, or the
+    /// holding time limit once it has passed
+    pub fn exit_trigger(&self, price: Decimal) -> Option<ExitTrigger> {
+        if !self.is_open() {
+            return None;
+        }
+        let reached = |target: Option<Decimal>| target.is_some_and(|t| self.gain(t, price) >= Decimal::ZERO);
+        if self.missed_stop || self.stop_loss.is_some_and(|sl| self.gain(sl, price) <= Decimal::ZERO) {
+            Some(ExitTrigger::StopLoss)
+        } else if reached(self.take_profit) {
+            Some(ExitTrigger::TakeProfit)
+        } else if reached(self.sell_target) {
+            Some(ExitTrigger::SellTarget)
+        } else if let (Some(limit), Some(held)) = (self.max_holding, self.held_for(Utc::now()))
+            && held >= limit {
+            Some(ExitTrigger::MaxHoldingTime)
+        } else {
+            None
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_position_lifecycle() {
+        let mut position = Position::new("BTCUSDT");
+        assert!(position.begin_exit().is_err());
+
+        position.begin_entry(dec!(1)).unwrap();
+        position.entry_failed().unwrap();
+        assert!(position.is_flat());
+
+        position.begin_entry(dec!(1)).unwrap();
+        assert!(position.begin_entry(dec!(1)).is_err());
+        position.entry_filled(Execution { price: dec!(100), qty: dec!(0.999), fee: dec!(0.1) }).unwrap();
+        position.set_targets(dec!(95), dec!(110), Some(dec!(105)));
+        assert_eq!(position.exit_trigger(dec!(100)), None);
+        assert_eq!(position.exit_trigger(dec!(94)), Some(ExitTrigger::StopLoss));
+        assert_eq!(position.exit_trigger(dec!(106)), Some(ExitTrigger::SellTarget));
+        assert!(ExitTrigger::StopLoss.is_protective() && !ExitTrigger::SellTarget.is_protective());
+
+        position.begin_exit().unwrap();
+        position.exit_failed().unwrap();
+        assert!(position.is_open());
+        position.track_excursion(dec!(94));
+        position.track_excursion(dec!(106));
+        assert_eq!(position.excursion, Excursion { mae_percent: dec!(-6), mfe_percent: dec!(6) });
+
+        position.begin_exit().unwrap();
+        let pnl = position.exit_filled(Execution { price: dec!(110), qty: dec!(0.999), fee: dec!(0.11) }).unwrap();
+        assert_eq!(pnl, dec!(9.78));
+        assert!(position.is_flat());
+        // The exit fill is the best price seen
+        assert_eq!(position.closed_trades.average_excursion(), Some(Excursion { mae_percent: dec!(-6), mfe_percent: dec!(10) }));
+        assert_eq!(position.excursion, Excursion::default());
+        assert_eq!((position.qty, position.entry_price), (dec!(0), None));
+    }
+
+    #[test]
+    fn test_scalp_slot_keeps_fixed_exits() {
+        let mut config = Config::defaults();
+        config.symbol = "BTCUSDT".to_string();
+        config.scalp_enabled = true;
+        config.scalp_stop_loss_percent = dec!(-0.5);
+        config.scalp_take_profit_percent = dec!(1);
+        config.max_holding_hours = None;
+        config.scalp_max_holding_hours = Some(dec!(2));
+        let saved = vec![Position { id: SCALP_POSITION.to_string(), ..Position::resumed("BTCUSDT", dec!(0.5), Some(dec!(200))) }];
+        let mut slots = Position::slots(&config, &saved).unwrap();
+        assert_eq!(slots.iter().map(|p| p.ledger_key()).collect::<Vec<_>>(), vec!["BTCUSDT", "BTCUSDT:scalp"]);
+        assert!(slots[0].is_flat() && slots[1].is_open());
+
+        let scalp = &mut slots[1];
+        scalp.begin_exit().unwrap();
+        scalp.exit_filled(Execution { price: dec!(202), qty: dec!(0.5), fee: dec!(0) }).unwrap();
+        scalp.begin_entry(dec!(1)).unwrap();
+        scalp.entry_filled(Execution { price: dec!(100), qty: dec!(1), fee: dec!(0) }).unwrap();
+        // The AI targets move the main position only
+        scalp.set_targets(dec!(90), dec!(120), None);
+        assert_eq!((scalp.stop_loss, scalp.take_profit), (Some(dec!(99.5)), Some(dec!(101))));
+        assert_eq!(scalp.realized_pnl, dec!(1));
+
+        // Only the scalp is closed after its holding limit
+        scalp.opened_at = Some(Utc::now() - Duration::hours(3));
+        assert_eq!(scalp.exit_trigger(dec!(100)), Some(ExitTrigger::MaxHoldingTime));
+        assert_eq!(slots[0].max_holding, None);
+        let scalp = &mut slots[1];
+        scalp.begin_exit().unwrap();
+        scalp.exit_filled(Execution { price: dec!(100), qty: dec!(1), fee: dec!(0) }).unwrap();
+        assert_eq!(scalp.closed_trades.trades, 2);
+        assert!(scalp.closed_trades.longest_holding() >= Duration::hours(3));
+
+        config.scalp_stop_loss_percent = dec!(0.5);
+        assert!(Position::slots(&config, &[]).is_err());
+        config.scalp_stop_loss_percent = dec!(-0.5);
+        config.scalp_max_holding_hours = Some(dec!(0));
+        assert!(Position::slots(&config, &[]).is_err());
+    }
+
+    #[test]
+    fn test_short_position_mirrors_targets_and_pnl() {
+        let mut short = Position::new_short("BTCUSDT");
+        assert!(short.sync(dec!(2), Some(dec!(100))));
+        assert!(!short.sync(dec!(2), Some(dec!(100))));
+        short.set_percent_targets(dec!(-5), dec!(10));
+        assert_eq!((short.stop_loss, short.take_profit), (Some(dec!(105)), Some(dec!(90))));
+        assert_eq!(short.exit_trigger(dec!(104)), None);
+        assert_eq!(short.exit_trigger(dec!(105)), Some(ExitTrigger::StopLoss));
+        assert_eq!(short.exit_trigger(dec!(89)), Some(ExitTrigger::TakeProfit));
+        assert_eq!(short.unrealized_pnl(dec!(95)), dec!(10));
+        // Filling above a short's stop is slippage
+        assert_eq!(short.stop_slippage(Execution { price: dec!(107), qty: dec!(2), fee: dec!(0) }), Some((dec!(2), dec!(4))));
+        assert_eq!(short.stop_slippage(Execution { price: dec!(104), qty: dec!(2), fee: dec!(0) }), None);
+
+        short.begin_exit().unwrap();
+        let pnl = short.exit_filled(Execution { price: dec!(90), qty: dec!(2), fee: dec!(0.2) }).unwrap();
+        assert_eq!(pnl, dec!(19.8));
+        assert!(short.short && short.is_flat());
+    }
+}
@@ -0,0 +1,191 @@
+use crate::ai_advisor::OllamaClient;
+use crate::audit::{AuditEvent, AuditLog};
+use crate::config::Config;
+use crate::position::Position;
+use anyhow::Result;
+use chrono::Utc;
+use rust_decimal::Decimal;
+use rust_decimal_macros::dec;
+use std::time::Duration;
+use tracing::{info, warn};
+
+/// Critique tags the model picks from, so the weekly review can count recurring ones
+pub const TAGS: [&str; 10] = [
+    "early_entry",
+    "late_entry",
+    "stop_too_tight",
+    "stop_too_wide",
+    "target_too_close",
+    "target_too_far",
+    "against_trend",
+    "held_too_long",
+    "good_entry",
+    "good_exit",
+];
+
+/// How long the model may take over one critique
+const REVIEW_TIMEOUT: Duration = Duration::from_secs(120);
+
+/// A trade that just closed, as the model is told about it
+#[derive(Debug, Clone, PartialEq)]
+pub struct ClosedTrade {
+    pub symbol: String,
+    pub position_id: String,
+    pub entry_price: Decimal,
+    pub exit_price: Decimal,
+    pub qty: Decimal,
+    /// Net of fees
+    pub pnl: Decimal,
+    /// What fired the exit, e.g. `Stop-loss`
+    pub exit_reason: String,
+    pub stop_loss: Option<Decimal>,
+    pub take_profit: Option<Decimal>,
+    pub held_hours: Option<Decimal>,
+    pub mae_percent: Decimal,
+    pub mfe_percent: Decimal,
+}
+
+impl ClosedTrade {
+    /// From the position as it was just before the exit; `None` without an entry price
+    pub fn from_position(before: &Position, exit_price: Decimal, pnl: Decimal, exit_reason: &str) -> Option<Self> {
+        Some(Self {
+            symbol: before.symbol.clone(),
+            position_id: before.id.clone(),
+            entry_price: before.entry_price?,
+            exit_price,
+            qty: before.qty,
+            pnl,
+            exit_reason: exit_reason.to_string(),
+            stop_loss: before.stop_loss,
+            take_profit: before.take_profit,
+            held_hours: before.held_for(Utc::now())
+                .map(|held| Decimal::from(held.num_minutes()) / dec!(60)),
+            mae_percent: before.excursion.mae_percent,
+            mfe_percent: before.excursion.mfe_percent,
+        })
+    }
+
+    fn prompt(&self) -> String {
+        let level = |v: Option<Decimal>| v.map_or("none".to_string(), |v| format!("${:.2}", v));
+        let return_percent = ((self.exit_price - self.entry_price) / self.entry_price * dec!(100)).round_dp(2);
+        format!(
+            "You are reviewing a closed {symbol} spot trade made by a trading bot.\n\
+             Entry: ${entry:.2} | Exit: ${exit:.2} ({ret:+.2}%) | Quantity: {qty}\n\
+             P&L after fees: {pnl:+.2}\n\
+             Exit reason: {reason}\n\
+             Stop-loss: {sl} | Take-profit: {tp}\n\
+             Held: {held}\n\
+             Worst drawdown while open (MAE): {mae:.2}% | Best gain while open (MFE): {mfe:.2}%\n\n\
+             In one or two sentences, say what went right or wrong with this trade (entry timing, \
+             stop and target placement, holding time). Then list the tags that apply.\n\
+             Respond in EXACTLY this format:\n\
+             CRITIQUE: <one or two sentences>\n\
+             TAGS: <comma-separated, from: {tags}>",
+            symbol = self.symbol,
+            entry = self.entry_price,
+            exit = self.exit_price,
+            ret = return_percent,
+            qty = self.qty.normalize(),
+            pnl = self.pnl.round_dp(2),
+            reason = self.exit_reason,
+            sl = level(self.stop_loss),
+            tp = level(self.take_profit),
+            held = self.held_hours.map_or("unknown".to_string(), |h| format!("{:.1}h", h)),
+            mae = self.mae_percent.round_dp(2),
+            mfe = self.mfe_percent.round_dp(2),
+            tags = TAGS.join(", "),
+        )
+    }
+}
+
+/// The model's verdict on one trade
+#[derive(Debug, Clone, PartialEq)]
+pub struct Critique {
+    pub text: String,
+    /// Known tags only, in [`TAGS`] spelling
+    pub tags: Vec<String>,
+}
+
+/// Read `CRITIQUE:` and `TAGS:` lines; without a `CRITIQUE:` line the whole answer is the critique
+pub fn parse_critique(response: &str) -> Critique {
+    let line_after = |label: &str| response.lines()
+        .find_map(|line| line.trim().strip_prefix(label).map(|rest| rest.trim().to_string()));
+    let text = line_after("CRITIQUE:")
+        .unwrap_or_else(|| response.split_whitespace().collect::<Vec<_>>().join(" "));
+    let tags = line_after("TAGS:").unwrap_or_default()
+        .split(',')
+        .map(|tag| tag.trim().to_lowercase().replace([' ', '-'], "_"))
+        .filter(|tag| TAGS.contains(&tag.as_str()))
+        .fold(Vec::new(), |mut tags, tag| {
+            if !tags.contains(&tag) {
+                tags.push(tag);
+            }
+            tags
+        });
+    Critique { text, tags }
+}
+
+/// With AI_TRADE_REVIEW, asks the model to critique each closed trade and keeps the answer
+/// in the audit log next to the trade; the weekly review counts the recurring tags
+pub struct PostTradeReviewer {
+    client: OllamaClient,
+    audit: AuditLog,
+}
+
+impl PostTradeReviewer {
+    /// `None` unless AI_TRADE_REVIEW and OLLAMA_ENABLED are both on
+    pub fn from_config(config: &Config, audit: &AuditLog) -> Result<Option<Self>> {
+        if !config.ai_trade_review || !config.ollama_enabled {
+            return Ok(None);
+        }
+        Ok(Some(Self { client: OllamaClient::from_config(config)?, audit: audit.clone() }))
+    }
+
+    /// Review in the background, so a slow model never holds up the trading loop
+    pub fn spawn(&self, trade: ClosedTrade) {
+        let client = self.client.clone();
+        let audit = self.audit.clone();
+        tokio::spawn(async move {
+            match tokio::time::timeout(REVIEW_TIMEOUT, client.generate(trade.prompt())).await {
+                Ok(Ok(response)) => {
+                    let critique = parse_critique(&response);
+                    info!("🔍 Post-trade review ({} {}): {}", trade.symbol, trade.position_id, critique.text);
+                    audit.record(AuditEvent::TradeReview {
+                        symbol: trade.symbol,
+                        position_id: trade.position_id,
+                        pnl: trade.pnl,
+                        exit_reason: trade.exit_reason,
+                        critique: critique.text,
+                        tags: critique.tags,
+                    });
+                }
+                Ok(Err(e)) => warn!("⚠️ Post-trade review failed: {}", e),
+                Err(_) => warn!("⚠️ Post-trade review timed out"),
+            }
+        });
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_trade_prompt_and_critique() {
+        let mut position = Position::new("BTCUSDT");
+        position.entry_price = Some(dec!(60000));
+        position.qty = dec!(0.01);
+        position.stop_loss = Some(dec!(58800));
+        let trade = ClosedTrade::from_position(&position, dec!(58700), dec!(-13.5), "Stop-loss").unwrap();
+        let prompt = trade.prompt();
+        assert!(prompt.contains("Exit: $58700.00 (-2.17%)"));
+        assert!(prompt.contains("Stop-loss: $58800.00 | Take-profit: none"));
+        assert!(ClosedTrade::from_position(&Position::new("BTCUSDT"), dec!(1), dec!(0), "Stop-loss").is_none());
+
+        let critique = parse_critique("Sure.\nCRITIQUE: Bought into resistance; the stop sat inside normal noise.\n\
+                                       TAGS: early_entry, Stop too tight, stop_too_tight, moon");
+        assert_eq!(critique.text, "Bought into resistance; the stop sat inside normal noise.");
+        assert_eq!(critique.tags, ["early_entry", "stop_too_tight"]);
+        assert_eq!(parse_critique("  Fine\n trade ").text, "Fine trade");
+    }
+}
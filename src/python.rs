@@ -0,0 +1,242 @@
+//! Python bindings for the backtesting components (feature `python`): the indicators, the
+//! optimizer's fast backtest for parameter sweeps and the trade-by-trade backtest report.
+//! Build with `maturin develop --release`; see the README.
+
+use crate::backtest::{BacktestReport, BacktestTrade};
+use crate::candles::CandleInterval;
+use crate::models::Kline;
+use crate::optimizer::{self, Genome};
+use crate::strategy::{Atr, RsiStrategy, SmaCrossover};
+use pyo3::exceptions::PyValueError;
+use pyo3::prelude::*;
+use rust_decimal::Decimal;
+use rust_decimal::prelude::{FromPrimitive, ToPrimitive};
+
+/// `(open_time_ms, open, high, low, close, volume)`, oldest first
+type Candle = (i64, f64, f64, f64, f64, f64);
+
+fn decimal(value: f64) -> PyResult<Decimal> {
+    Decimal::from_f64(value).ok_or_else(|| PyValueError::new_err(format!("{} is not a finite price", value)))
+}
+
+fn decimals(values: &[f64]) -> PyResult<Vec<Decimal>> {
+    values.iter().map(|v| decimal(*v)).collect()
+}
+
+fn klines(candles: &[Candle], interval: CandleInterval) -> PyResult<Vec<Kline>> {
+    candles.iter().map(|&(open_time, open, high, low, close, volume)| Ok(Kline {
+        open_time,
+        open: decimal(open)?,
+        high: decimal(high)?,
+        low: decimal(low)?,
+        close: decimal(close)?,
+        volume: decimal(volume)?,
+        close_time: open_time + interval.millis() - 1,
+    })).collect()
+}
+
+fn interval(name: &str) -> PyResult<CandleInterval> {
+    CandleInterval::parse(name).map_err(|e| PyValueError::new_err(e.to_string()))
+}
+
+/// Simple moving average of the last `period` prices
+#[pyfunction]
+fn sma(prices: Vec<f64>, period: usize) -> PyResult<Option<f64>> {
+    Ok(SmaCrossover::calculate_sma(&decimals(&prices)?, period).and_then(|v| v.to_f64()))
+}
+
+/// RSI over the last `period` price changes
+#[pyfunction]
+fn rsi(prices: Vec<f64>, period: usize) -> PyResult<Option<f64>> {
+    Ok(RsiStrategy::calculate_rsi(&decimals(&prices)?, period).and_then(|v| v.to_f64()))
+}
+
+/// Average true range of the last `period` candles
+#[pyfunction]
+#[pyo3(signature = (candles, period, interval = "1h"))]
+fn atr(candles: Vec<Candle>, period: usize, interval: &str) -> PyResult<Option<f64>> {
+    let klines = klines(&candles, self::interval(interval)?)?;
+    Ok(Atr::calculate(&klines, period).and_then(|v| v.to_f64()))
+}
+
+/// SMA crossover parameters with a stop-loss and take-profit, as the bot trades them
+#[pyclass(name = "Genome", eq, frozen, get_all)]
+#[derive(Clone, PartialEq)]
+struct PyGenome {
+    sma_short: u32,
+    sma_long: u32,
+    stop_loss_percent: f64,
+    take_profit_percent: f64,
+}
+
+#[pymethods]
+impl PyGenome {
+    #[new]
+    fn new(sma_short: u32, sma_long: u32, stop_loss_percent: f64, take_profit_percent: f64) -> PyResult<Self> {
+        if sma_short == 0 || sma_long <= sma_short {
+            return Err(PyValueError::new_err(format!("need 0 < sma_short < sma_long, got {}/{}", sma_short, sma_long)));
+        }
+        if stop_loss_percent <= 0.0 || take_profit_percent <= 0.0 {
+            return Err(PyValueError::new_err("stop_loss_percent and take_profit_percent must be positive"));
+        }
+        Ok(Self { sma_short, sma_long, stop_loss_percent, take_profit_percent })
+    }
+
+    fn __repr__(&self) -> String {
+        format!("Genome(sma_short={}, sma_long={}, stop_loss_percent={:?}, take_profit_percent={:?})",
+            self.sma_short, self.sma_long, self.stop_loss_percent, self.take_profit_percent)
+    }
+}
+
+impl From<&PyGenome> for Genome {
+    fn from(g: &PyGenome) -> Self {
+        Self {
+            sma_short: g.sma_short,
+            sma_long: g.sma_long,
+            stop_loss_percent: g.stop_loss_percent,
+            take_profit_percent: g.take_profit_percent,
+        }
+    }
+}
+
+/// How a genome did in the optimizer's backtest
+#[pyclass(name = "Score", frozen, get_all)]
+struct PyScore {
+    return_percent: f64,
+    max_drawdown_percent: f64,
+    trades: u32,
+    /// Return minus a share of the drawdown, what the optimizer ranks by
+    fitness: f64,
+}
+
+/// The optimizer's backtest on closes: no trade list, fast enough for sweeps over many genomes
+#[pyfunction]
+fn score(py: Python<'_>, genome: &PyGenome, closes: Vec<f64>) -> PyScore {
+    let genome = Genome::from(genome);
+    let result = py.allow_threads(|| optimizer::backtest(&genome, &closes));
+    PyScore {
+        return_percent: result.return_percent,
+        max_drawdown_percent: result.max_drawdown_percent,
+        trades: result.trades,
+        fitness: result.fitness(),
+    }
+}
+
+/// One round trip of a backtest
+#[pyclass(name = "Trade", frozen, get_all)]
+struct PyTrade {
+    entry_time_ms: i64,
+    entry_price: f64,
+    exit_time_ms: i64,
+    exit_price: f64,
+    /// stop-loss, take-profit, crossover or open
+    exit: &'static str,
+    return_percent: f64,
+    mae_percent: f64,
+    mfe_percent: f64,
+    candles_held: usize,
+    holding_hours: f64,
+}
+
+impl From<&BacktestTrade> for PyTrade {
+    fn from(t: &BacktestTrade) -> Self {
+        Self {
+            entry_time_ms: t.entry_time.timestamp_millis(),
+            entry_price: t.entry_price,
+            exit_time_ms: t.exit_time.timestamp_millis(),
+            exit_price: t.exit_price,
+            exit: t.exit.label(),
+            return_percent: t.return_percent,
+            mae_percent: t.mae_percent,
+            mfe_percent: t.mfe_percent,
+            candles_held: t.candles_held,
+            holding_hours: t.holding_hours(),
+        }
+    }
+}
+
+/// A genome replayed over candles trade by trade
+#[pyclass(name = "BacktestReport", frozen)]
+struct PyBacktestReport {
+    report: BacktestReport,
+}
+
+#[pymethods]
+impl PyBacktestReport {
+    #[getter]
+    fn return_percent(&self) -> f64 {
+        self.report.return_percent
+    }
+
+    #[getter]
+    fn max_drawdown_percent(&self) -> f64 {
+        self.report.max_drawdown_percent
+    }
+
+    #[getter]
+    fn buy_and_hold_percent(&self) -> f64 {
+        self.report.buy_and_hold_percent
+    }
+
+    /// Percent of closed trades that won; `None` without closed trades
+    #[getter]
+    fn win_rate(&self) -> Option<f64> {
+        self.report.win_rate()
+    }
+
+    /// `None` without a losing trade
+    #[getter]
+    fn profit_factor(&self) -> Option<f64> {
+        self.report.profit_factor()
+    }
+
+    #[getter]
+    fn trades(&self) -> Vec<PyTrade> {
+        self.report.trades.iter().map(PyTrade::from).collect()
+    }
+
+    /// `(time_ms, equity)` after each evaluated candle, starting from 1.0
+    #[getter]
+    fn equity(&self) -> Vec<(i64, f64)> {
+        self.report.equity.iter().map(|(time, equity)| (time.timestamp_millis(), *equity)).collect()
+    }
+
+    fn render_text(&self) -> String {
+        self.report.render_text()
+    }
+
+    fn render_html(&self) -> String {
+        self.report.render_html()
+    }
+
+    fn __repr__(&self) -> String {
+        format!("BacktestReport({} {} candles, {} trades, return {:+.2}%)",
+            self.report.symbol, self.report.candles, self.report.trades.len(), self.report.return_percent)
+    }
+}
+
+/// Replay `genome` over candles trade by trade, as the `backtest` command does
+#[pyfunction]
+#[pyo3(signature = (genome, candles, interval = "1h", symbol = "BTCUSDT"))]
+fn backtest(py: Python<'_>, genome: &PyGenome, candles: Vec<Candle>, interval: &str, symbol: &str) -> PyResult<PyBacktestReport> {
+    let interval = self::interval(interval)?;
+    let klines = klines(&candles, interval)?;
+    let genome = Genome::from(genome);
+    let report = py.allow_threads(|| BacktestReport::run(symbol, &genome, interval, &klines));
+    Ok(PyBacktestReport { report })
+}
+
+#[pymodule]
+fn crypto_trading_bot(m: &Bound<'_, PyModule>) -> PyResult<()> {
+    m.add_function(wrap_pyfunction!(sma, m)?)?;
+    m.add_function(wrap_pyfunction!(rsi, m)?)?;
+    m.add_function(wrap_pyfunction!(atr, m)?)?;
+    m.add_function(wrap_pyfunction!(score, m)?)?;
+    m.add_function(wrap_pyfunction!(backtest, m)?)?;
+    m.add_class::<PyGenome>()?;
+    m.add_class::<PyScore>()?;
+    m.add_class::<PyTrade>()?;
+    m.add_class::<PyBacktestReport>()?;
+    m.add("FEE_RATE", optimizer::FEE_RATE)?;
+    Ok(())
+}
@@ -0,0 +1,92 @@
+use anyhow::{anyhow, Result};
+use rust_decimal::Decimal;
+use std::collections::HashMap;
+
+/// Quote assets the bot can trade against, in order of preference for automatic selection
+pub const SUPPORTED_QUOTES: &[&str] = &["USDT", "USDC", "FDUSD", "EUR"];
+
+/// Quote suffixes recognised when splitting a symbol, longest first so FDUSD wins over USD
+const KNOWN_QUOTES: &[&str] = &["FDUSD", "USDT", "USDC", "BUSD", "TUSD", "EUR", "BTC", "ETH", "BNB", "USD"];
+
+/// Stablecoins treated as 1:1 with each other
+const USD_STABLECOINS: &[&str] = &["USDT", "USDC", "FDUSD", "BUSD", "TUSD", "DAI", "USD"];
+
+/// Split a pair like `BTCFDUSD` into `("BTC", "FDUSD")`
+pub fn split_symbol(symbol: &str) -> Option<(&str, &str)> {
+    KNOWN_QUOTES.iter().find_map(|quote| {
+        symbol
+            .strip_suffix(quote)
+            .filter(|base| !base.is_empty())
+            .map(|base| (base, *quote))
+    })
+}
+
+pub fn is_usd_stable(asset: &str) -> bool {
+    USD_STABLECOINS.contains(&asset)
+}
+
+/// Conversion rate from `asset` to `quote` that needs no market price
+pub fn peg_rate(asset: &str, quote: &str) -> Option<Decimal> {
+    if asset == quote || (is_usd_stable(asset) && is_usd_stable(quote)) {
+        Some(Decimal::ONE)
+    } else {
+        None
+    }
+}
+
+/// Resolve the pair and quote asset to trade.
+///
+/// `QUOTE_ASSET` unset keeps the quote of `SYMBOL`. An explicit asset swaps the quote
+/// of `SYMBOL` (BTCUSDT + USDC -> BTCUSDC). `auto` picks the supported quote asset
+/// with the largest free balance, falling back to the symbol's quote without balances.
+pub fn resolve_pair(
+    symbol: &str,
+    quote_asset: Option<&str>,
+    balances: Option<&HashMap<String, Decimal>>,
+) -> Result<(String, String)> {
+    let (base, symbol_quote) = split_symbol(symbol)
+        .ok_or_else(|| anyhow!("Cannot determine the quote asset of SYMBOL {}", symbol))?;
+
+    let quote = match quote_asset.map(|q| q.to_uppercase()) {
+        None => symbol_quote.to_string(),
+        Some(q) if q == "AUTO" => balances
+            .and_then(|b| {
+                SUPPORTED_QUOTES
+                    .iter()
+                    .filter_map(|q| b.get(*q).filter(|v| **v > Decimal::ZERO).map(|v| (*q, *v)))
+                    .max_by_key(|(_, v)| *v)
+                    .map(|(q, _)| q.to_string())
+            })
+            .unwrap_or_else(|| symbol_quote.to_string()),
+        Some(q) if SUPPORTED_QUOTES.contains(&q.as_str()) => q,
+        Some(q) => return Err(anyhow!(
+            "Unsupported QUOTE_ASSET {} (expected one of {} or auto)", q, SUPPORTED_QUOTES.join(", ")
+        )),
+    };
+
+    Ok((format!("{}{}", base, quote), quote))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rust_decimal_macros::dec;
+
+    #[test]
+    fn test_resolve_pair() {
+        assert_eq!(split_symbol("BTCFDUSD"), Some(("BTC", "FDUSD")));
+        assert_eq!(split_symbol("ETHEUR"), Some(("ETH", "EUR")));
+
+        let (symbol, quote) = resolve_pair("BTCUSDT", Some("usdc"), None).unwrap();
+        assert_eq!((symbol.as_str(), quote.as_str()), ("BTCUSDC", "USDC"));
+
+        let balances = HashMap::from([
+            ("USDT".to_string(), dec!(50)),
+            ("FDUSD".to_string(), dec!(900)),
+            ("BTC".to_string(), dec!(2)),
+        ]);
+        let (symbol, _) = resolve_pair("BTCUSDT", Some("auto"), Some(&balances)).unwrap();
+        assert_eq!(symbol, "BTCFDUSD");
+        assert!(resolve_pair("BTCUSDT", Some("GBP"), None).is_err());
+    }
+}
@@ -0,0 +1,178 @@
+use crate::error::{BotError, ErrorKind};
+use chrono::Utc;
+use reqwest::header::HeaderMap;
+use reqwest::StatusCode;
+use std::sync::Mutex;
+use std::time::{Duration, Instant};
+use tracing::warn;
+
+/// Start throttling once this share of the per-minute weight is used
+const THROTTLE_THRESHOLD: f64 = 0.8;
+
+#[derive(Debug, Default)]
+struct WeightState {
+    used: u32,
+    /// Unix minute the `used` value belongs to; Binance resets weights every clock minute
+    minute: i64,
+    banned_until: Option<Instant>,
+    throttle_count: u64,
+}
+
+/// Remaining request-weight budget for the current minute
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct WeightBudget {
+    pub used: u32,
+    pub limit: u32,
+    pub throttle_count: u64,
+}
+
+impl WeightBudget {
+    pub fn remaining(&self) -> u32 {
+        self.limit.saturating_sub(self.used)
+    }
+}
+
+/// Tracks Binance request weight from the `X-MBX-USED-WEIGHT-1M` response header and
+/// delays requests before the limit is hit, so bursts don't get the key banned (HTTP 418)
+#[derive(Debug)]
+pub struct WeightTracker {
+    limit: u32,
+    state: Mutex<WeightState>,
+}
+
+impl WeightTracker {
+    pub fn new(limit: u32) -> Self {
+        Self {
+            limit,
+            state: Mutex::new(WeightState::default()),
+        }
+    }
+
+    /// Time left of a pause after a 429/418 response
+    fn ban_remaining(state: &mut WeightState) -> Option<Duration> {
+        let until = state.banned_until?;
+        let now = Instant::now();
+        if until > now {
+            return Some(until - now);
+        }
+        state.banned_until = None;
+        None
+    }
+
+    /// How long to wait before the next request may be sent
+    fn delay(&self) -> Option<Duration> {
+        self.delay_at(Utc::now().timestamp())
+    }
+
+    /// `delay` at unix time `now` (seconds)
+    fn delay_at(&self, now: i64) -> Option<Duration> {
+        let mut state = self.state.lock().unwrap();
+
+        if let Some(left) = Self::ban_remaining(&mut state) {
+            return Some(left);
+        }
+
+        if state.minute == now / 60
+            && state.used as f64 >= self.limit as f64 * THROTTLE_THRESHOLD {
+            state.throttle_count += 1;
+            return Some(Duration::from_secs(60 - now.rem_euclid(60) as u64));
+        }
+
+        None
+    }
+
+    /// Wait until the request fits in the budget
+    pub async fn acquire(&self) {
+        if let Some(delay) = self.delay() {
+            warn!("⏳ Exchange request weight near limit ({}/{}), waiting {}s",
+                self.budget().used, self.limit, delay.as_secs());
+            tokio::time::sleep(delay).await;
+        }
+    }
+
+    /// For requests that can't wait for the budget (orders): fails right away during a
+    /// 429/418 pause, since sending then would only extend the ban
+    pub fn check_ban(&self) -> Result<(), BotError> {
+        match Self::ban_remaining(&mut self.state.lock().unwrap()) {
+            Some(left) => Err(BotError::Exchange {
+                kind: ErrorKind::RateLimited,
+                message: format!("requests paused for another {}s after a rate limit response", left.as_secs()),
+            }),
+            None => Ok(()),
+        }
+    }
+
+    /// Update from a response's headers; 429/418 responses pause requests for `Retry-After`
+    pub fn record(&self, headers: &HeaderMap, status: StatusCode) {
+        self.record_at(headers, status, Utc::now().timestamp());
+    }
+
+    fn record_at(&self, headers: &HeaderMap, status: StatusCode, now: i64) {
+        let mut state = self.state.lock().unwrap();
+
+        if let Some(used) = headers
+            .get("x-mbx-used-weight-1m")
+            .and_then(|v| v.to_str().ok())
+            .and_then(|v| v.parse().ok()) {
+            state.used = used;
+            state.minute = now / 60;
+        }
+
+        if status == StatusCode::TOO_MANY_REQUESTS || status.as_u16() == 418 {
+            let retry_after = headers
+                .get("retry-after")
+                .and_then(|v| v.to_str().ok())
+                .and_then(|v| v.parse().ok())
+                .unwrap_or(60);
+            warn!("🚫 Exchange rate limit hit ({}), pausing requests for {}s", status, retry_after);
+            state.banned_until = Some(Instant::now() + Duration::from_secs(retry_after));
+        }
+    }
+
+    pub fn budget(&self) -> WeightBudget {
+        self.budget_at(Utc::now().timestamp())
+    }
+
+    fn budget_at(&self, now: i64) -> WeightBudget {
+        let state = self.state.lock().unwrap();
+        let used = if state.minute == now / 60 { state.used } else { 0 };
+        WeightBudget {
+            used,
+            limit: self.limit,
+            throttle_count: state.throttle_count,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use reqwest::header::HeaderValue;
+
+    #[test]
+    fn test_throttles_near_limit() {
+        let tracker = WeightTracker::new(100);
+        let mut headers = HeaderMap::new();
+
+        // 10 seconds into a minute
+        let now = 1_700_000_050;
+
+        headers.insert("x-mbx-used-weight-1m", HeaderValue::from_static("50"));
+        tracker.record_at(&headers, StatusCode::OK, now);
+        assert_eq!(tracker.budget_at(now).remaining(), 50);
+        assert!(tracker.delay_at(now).is_none());
+
+        headers.insert("x-mbx-used-weight-1m", HeaderValue::from_static("85"));
+        tracker.record_at(&headers, StatusCode::OK, now);
+        assert_eq!(tracker.budget_at(now).used, 85);
+        assert_eq!(tracker.delay_at(now), Some(Duration::from_secs(50)));
+        // The weight resets with the next minute
+        assert_eq!(tracker.budget_at(now + 50).used, 0);
+        assert!(tracker.delay_at(now + 50).is_none());
+
+        headers.insert("retry-after", HeaderValue::from_static("30"));
+        tracker.record(&headers, StatusCode::TOO_MANY_REQUESTS);
+        assert!(tracker.delay().unwrap() > Duration::from_secs(20));
+        assert!(tracker.check_ban().is_err());
+    }
+}
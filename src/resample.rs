@@ -0,0 +1,71 @@
+use crate::candles::CandleInterval;
+use crate::models::Kline;
+
+/// Roll `klines` (oldest first, any interval shorter than `interval`) up into `interval` bars
+/// aligned to UTC: the first open, the highest high, the lowest low, the last close and the
+/// summed volume. A leading bar missing its first candle is dropped, as its open would be
+/// wrong; the trailing one is kept while it forms, as the exchange does.
+pub fn resample(klines: &[Kline], interval: CandleInterval) -> Vec<Kline> {
+    let ms = interval.millis();
+    let mut bars: Vec<Kline> = Vec::new();
+    for kline in klines {
+        let offset = kline.open_time.rem_euclid(ms);
+        let start = kline.open_time - offset;
+        match bars.last_mut() {
+            Some(bar) if bar.open_time == start => {
+                bar.high = bar.high.max(kline.high);
+                bar.low = bar.low.min(kline.low);
+                bar.close = kline.close;
+                bar.volume += kline.volume;
+            }
+            last if last.is_some() || offset <= kline.close_time - kline.open_time => bars.push(Kline {
+                open_time: start,
+                open: kline.open,
+                high: kline.high,
+                low: kline.low,
+                close: kline.close,
+                volume: kline.volume,
+                close_time: start + ms - 1,
+            }),
+            _ => {}
+        }
+    }
+    bars
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rust_decimal::Decimal;
+    use rust_decimal_macros::dec;
+
+    fn minute(i: i64, open: Decimal, high: Decimal, low: Decimal, close: Decimal) -> Kline {
+        Kline { open_time: i * 60_000, open, high, low, close, volume: dec!(2), close_time: (i + 1) * 60_000 - 1 }
+    }
+
+    #[test]
+    fn test_minutes_roll_up_into_aligned_bars() {
+        // Starts mid-bar at 00:03 and ends two minutes into the 00:10 bar
+        let klines: Vec<Kline> = (3..12)
+            .map(|i| {
+                let base = Decimal::from(100 + i);
+                minute(i, base, base + dec!(3), base - dec!(1), base + dec!(1))
+            })
+            .collect();
+
+        let bars = resample(&klines, CandleInterval::M5);
+        assert_eq!(bars.len(), 2);
+        let full = &bars[0];
+        assert_eq!((full.open_time, full.close_time), (300_000, 599_999));
+        assert_eq!((full.open, full.high, full.low, full.close), (dec!(105), dec!(112), dec!(104), dec!(110)));
+        assert_eq!(full.volume, dec!(10));
+        let forming = &bars[1];
+        assert_eq!((forming.open_time, forming.open, forming.close, forming.volume), (600_000, dec!(110), dec!(112), dec!(4)));
+
+        assert_eq!(resample(&klines[2..], CandleInterval::H1).len(), 0);
+        // Samples taken a little after the boundary, like CoinGecko's, still open their bar
+        let late = Kline { open_time: 330_000, close_time: 389_999, ..klines[0].clone() };
+        assert_eq!(resample(&[late], CandleInterval::M5)[0].open_time, 300_000);
+        assert_eq!(resample(&[], CandleInterval::M15).len(), 0);
+    }
+}
@@ -0,0 +1,104 @@
+use crate::config::Config;
+use crate::error::BotError;
+use crate::state_crypto::StateCipher;
+use crate::state_store::{StateBackend, StateStore};
+use anyhow::Result;
+use serde::Serialize;
+use std::collections::VecDeque;
+use std::sync::Arc;
+use tracing::{info, warn};
+
+/// Records kept past the limit before a batch is trimmed, as a share of the limit, so
+/// spills come in batches instead of one document per record
+const SPILL_BATCH_DIVISOR: usize = 10;
+
+/// How many alerts, simulated orders and trades stay in memory (HISTORY_RETENTION); older
+/// ones are written to `<name>_archive_<unix ms>` documents in the state backend
+/// (HISTORY_SPILL) or dropped
+#[derive(Clone)]
+pub struct Retention {
+    limit: usize,
+    archive: Option<(Arc<dyn StateBackend>, Option<StateCipher>)>,
+}
+
+impl Retention {
+    /// Without a backend to spill to until [`Retention::spill_to`]
+    pub fn from_config(config: &Config) -> Result<Self> {
+        if config.history_retention < 10 {
+            return Err(BotError::config(format!("HISTORY_RETENTION must be at least 10, got {}",
+                config.history_retention)).into());
+        }
+        Ok(Self { limit: config.history_retention, archive: None })
+    }
+
+    /// Keep everything, until a configured retention replaces it
+    pub fn unbounded() -> Self {
+        Self { limit: usize::MAX, archive: None }
+    }
+
+    /// Spill trimmed records to `backend` when HISTORY_SPILL is set
+    pub fn spill_to(self, config: &Config, backend: Arc<dyn StateBackend>, cipher: Option<StateCipher>) -> Self {
+        Self { archive: config.history_spill.then_some((backend, cipher)), ..self }
+    }
+
+    /// Trim `records` (oldest first) back to the limit once they run a batch past it, spilling
+    /// the trimmed ones as one `<name>_archive` document; returns how many were trimmed
+    pub fn enforce<T: Serialize>(&self, name: &str, records: &mut VecDeque<T>) -> usize {
+        let batch = (self.limit / SPILL_BATCH_DIVISOR).max(1);
+        if records.len() < self.limit.saturating_add(batch) {
+            return 0;
+        }
+        let trimmed: Vec<T> = records.drain(..records.len() - self.limit).collect();
+        if let Some((backend, cipher)) = &self.archive {
+            let store = StateStore::new(backend.clone(), &format!("{}_archive", name), cipher.clone())
+                .companion(&chrono::Utc::now().timestamp_millis().to_string());
+            match serde_json::to_string(&trimmed).map_err(anyhow::Error::from).and_then(|json| store.write(&json)) {
+                Ok(()) => info!("🗃️ Archived {} old {} records", trimmed.len(), name),
+                Err(e) => warn!("⚠️ Failed to archive {} old {} records, dropping them: {}", trimmed.len(), name, e),
+            }
+        }
+        trimmed.len()
+    }
+}
+
+/// Resident memory of this process from `/proc/self/status`; `None` off Linux
+pub fn resident_memory_bytes() -> Option<u64> {
+    let status = std::fs::read_to_string("/proc/self/status").ok()?;
+    let kb = status.lines().find_map(|line| line.strip_prefix("VmRSS:"))?
+        .trim().trim_end_matches("kB").trim().parse::<u64>().ok()?;
+    Some(kb * 1024)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::state_store::FileBackend;
+
+    #[test]
+    fn test_trimmed_records_are_archived() {
+        let dir = std::env::temp_dir().join(format!("ctb_retention_{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let mut config = Config::defaults();
+        config.history_retention = 20;
+        config.history_spill = true;
+        let backend: Arc<dyn StateBackend> = Arc::new(FileBackend::new(&dir));
+        let retention = Retention::from_config(&config).unwrap().spill_to(&config, backend.clone(), None);
+
+        // Nothing happens until the records run a batch (2) past the limit
+        let mut records: VecDeque<u32> = (0..21).collect();
+        assert_eq!(retention.enforce("alerts", &mut records), 0);
+        records.push_back(21);
+        assert_eq!(retention.enforce("alerts", &mut records), 2);
+        assert_eq!((records.len(), records.front()), (20, Some(&2)));
+
+        let keys = backend.keys().unwrap();
+        assert_eq!(keys.len(), 1);
+        assert!(keys[0].starts_with("alerts_archive_"));
+        assert_eq!(backend.load(&keys[0]).unwrap().unwrap(), b"[0,1]");
+        std::fs::remove_dir_all(&dir).ok();
+
+        config.history_retention = 5;
+        assert!(Retention::from_config(&config).is_err());
+        assert!(cfg!(not(target_os = "linux")) || resident_memory_bytes().is_some_and(|b| b > 0));
+    }
+}
@@ -0,0 +1,277 @@
+use crate::audit::AuditLog;
+use crate::error::BotError;
+use crate::paths::write_atomic;
+use anyhow::Result;
+use chrono::{DateTime, Duration, Utc};
+use rust_decimal::Decimal;
+use rust_decimal_macros::dec;
+use serde::Deserialize;
+use std::collections::BTreeMap;
+use std::fmt::Write as _;
+use std::path::Path;
+use tracing::info;
+
+/// How long after a decision its targets are given to play out
+const HORIZON_HOURS: i64 = 24;
+
+/// A `targets` line of the audit log
+#[derive(Debug, Clone, Deserialize)]
+pub struct Decision {
+    pub timestamp: DateTime<Utc>,
+    pub symbol: String,
+    pub source: String,
+    pub price: Decimal,
+    pub stop_loss: Decimal,
+    pub take_profit: Decimal,
+    pub support: Option<Decimal>,
+    pub resistance: Option<Decimal>,
+}
+
+/// A `trade_review` line of the audit log: the model's critique of a closed trade
+#[derive(Debug, Clone, Deserialize)]
+pub struct TradeCritique {
+    pub timestamp: DateTime<Utc>,
+    pub symbol: String,
+    pub pnl: Decimal,
+    pub exit_reason: String,
+    pub critique: String,
+    #[serde(default)]
+    pub tags: Vec<String>,
+}
+
+fn read_audit_lines(audit: &AuditLog, event: &str) -> Result<Vec<String>> {
+    let marker = format!("\"event\":\"{}\"", event);
+    Ok(audit.read_lines()?.into_iter().filter(|line| line.contains(&marker)).collect())
+}
+
+/// `targets` events logged in `[from, to)`; other events and unreadable lines are skipped
+pub fn read_decisions(audit: &AuditLog, from: DateTime<Utc>, to: DateTime<Utc>) -> Result<Vec<Decision>> {
+    Ok(read_audit_lines(audit, "targets")?.iter()
+        .filter_map(|line| serde_json::from_str::<Decision>(line).ok())
+        .filter(|d| d.timestamp >= from && d.timestamp < to)
+        .collect())
+}
+
+/// `trade_review` events logged in `[from, to)`
+pub fn read_critiques(audit: &AuditLog, from: DateTime<Utc>, to: DateTime<Utc>) -> Result<Vec<TradeCritique>> {
+    Ok(read_audit_lines(audit, "trade_review")?.iter()
+        .filter_map(|line| serde_json::from_str::<TradeCritique>(line).ok())
+        .filter(|c| c.timestamp >= from && c.timestamp < to)
+        .collect())
+}
+
+/// Decisions of one source (AI or fallback) scored against the prices that followed
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct SourceReview {
+    pub decisions: usize,
+    /// Take-profit reached before the stop-loss within the horizon
+    pub take_profit_hits: usize,
+    pub stop_loss_hits: usize,
+    support_errors: Vec<Decimal>,
+    resistance_errors: Vec<Decimal>,
+}
+
+impl SourceReview {
+    /// Take-profit hits out of decisions that resolved either way
+    pub fn hit_rate(&self) -> Option<Decimal> {
+        let resolved = self.take_profit_hits + self.stop_loss_hits;
+        (resolved > 0).then(|| Decimal::from(self.take_profit_hits) / Decimal::from(resolved) * dec!(100))
+    }
+
+    /// Mean distance between predicted support and the realized low, in percent
+    pub fn support_error(&self) -> Option<Decimal> {
+        mean(&self.support_errors)
+    }
+
+    /// Mean distance between predicted resistance and the realized high, in percent
+    pub fn resistance_error(&self) -> Option<Decimal> {
+        mean(&self.resistance_errors)
+    }
+}
+
+fn mean(values: &[Decimal]) -> Option<Decimal> {
+    (!values.is_empty()).then(|| values.iter().sum::<Decimal>() / Decimal::from(values.len()))
+}
+
+fn percent_error(predicted: Decimal, realized: Decimal) -> Option<Decimal> {
+    (realized > Decimal::ZERO).then(|| ((predicted - realized).abs() / realized * dec!(100)).round_dp(4))
+}
+
+/// Score each decision against the prices of the decisions logged after it (one per
+/// recalculation) within the horizon; grouped by source
+pub fn review(decisions: &[Decision]) -> BTreeMap<String, SourceReview> {
+    let mut reviews: BTreeMap<String, SourceReview> = BTreeMap::new();
+    for (i, decision) in decisions.iter().enumerate() {
+        let horizon = decision.timestamp + Duration::hours(HORIZON_HOURS);
+        let path: Vec<Decimal> = decisions[i + 1..].iter()
+            .filter(|d| d.symbol == decision.symbol && d.timestamp <= horizon)
+            .map(|d| d.price)
+            .collect();
+        let entry = reviews.entry(decision.source.clone()).or_default();
+        entry.decisions += 1;
+        if path.is_empty() {
+            continue;
+        }
+
+        for &price in &path {
+            if price >= decision.take_profit {
+                entry.take_profit_hits += 1;
+                break;
+            }
+            if price <= decision.stop_loss {
+                entry.stop_loss_hits += 1;
+                break;
+            }
+        }
+        let low = path.iter().copied().chain([decision.price]).min().unwrap_or(decision.price);
+        let high = path.iter().copied().chain([decision.price]).max().unwrap_or(decision.price);
+        if let Some(error) = decision.support.and_then(|s| percent_error(s, low)) {
+            entry.support_errors.push(error);
+        }
+        if let Some(error) = decision.resistance.and_then(|r| percent_error(r, high)) {
+            entry.resistance_errors.push(error);
+        }
+    }
+    reviews
+}
+
+/// Markdown report comparing the sources over `[from, to)`
+pub fn render_markdown(from: DateTime<Utc>, to: DateTime<Utc>, reviews: &BTreeMap<String, SourceReview>) -> String {
+    let fmt = |v: Option<Decimal>, suffix: &str| v.map_or("-".to_string(), |v| format!("{}{}", v.round_dp(2), suffix));
+    let mut out = String::new();
+    let _ = writeln!(out, "# Weekly AI Performance Review");
+    let _ = writeln!(out);
+    let _ = writeln!(out, "{} to {}", from.format("%Y-%m-%d %H:%M UTC"), to.format("%Y-%m-%d %H:%M UTC"));
+    let _ = writeln!(out);
+    if reviews.is_empty() {
+        let _ = writeln!(out, "No target decisions were logged this week.");
+        return out;
+    }
+    let _ = writeln!(out, "| Source | Decisions | TP hit first | SL hit first | Hit rate | Support error | Resistance error |");
+    let _ = writeln!(out, "|---|---:|---:|---:|---:|---:|---:|");
+    for (source, r) in reviews {
+        let _ = writeln!(out, "| {} | {} | {} | {} | {} | {} | {} |", source, r.decisions, r.take_profit_hits,
+            r.stop_loss_hits, fmt(r.hit_rate(), "%"), fmt(r.support_error(), "%"), fmt(r.resistance_error(), "%"));
+    }
+    let _ = writeln!(out);
+    let _ = writeln!(out, "- **Hit rate**: take-profit reached before the stop-loss within {}h, out of decisions that resolved", HORIZON_HOURS);
+    let _ = writeln!(out, "- **Support/resistance error**: mean distance from the predicted level to the realized low/high over the same {}h, in percent", HORIZON_HOURS);
+    let _ = writeln!(out, "- Realized prices are those logged at later recalculations, so brief wicks between them are missed");
+
+    if let (Some(ai), Some(fallback)) = (reviews.get("ai"), reviews.get("fallback"))
+        && let (Some(a), Some(f)) = (ai.hit_rate(), fallback.hit_rate()) {
+        let _ = writeln!(out);
+        let verdict = match a.cmp(&f) {
+            std::cmp::Ordering::Greater => "AI targets outperformed the fallback",
+            std::cmp::Ordering::Less => "The fallback outperformed the AI targets",
+            std::cmp::Ordering::Equal => "AI and fallback targets performed alike",
+        };
+        let _ = writeln!(out, "**{}** ({}% vs {}% hit rate).", verdict, a.round_dp(1), f.round_dp(1));
+    }
+    out
+}
+
+/// How many of the latest critiques the report quotes
+const QUOTED_CRITIQUES: usize = 5;
+
+/// Markdown section of the week's post-trade critiques: the recurring tags, then the latest ones
+pub fn render_critiques(critiques: &[TradeCritique]) -> String {
+    let mut counts: BTreeMap<&str, (usize, Decimal)> = BTreeMap::new();
+    for critique in critiques {
+        for tag in &critique.tags {
+            let entry = counts.entry(tag.as_str()).or_default();
+            entry.0 += 1;
+            entry.1 += critique.pnl;
+        }
+    }
+    let mut recurring: Vec<_> = counts.into_iter().collect();
+    recurring.sort_by_key(|(_, (trades, _))| std::cmp::Reverse(*trades));
+
+    let mut out = String::new();
+    let _ = writeln!(out);
+    let _ = writeln!(out, "## Post-Trade Critiques");
+    let _ = writeln!(out);
+    let losing = critiques.iter().filter(|c| c.pnl < Decimal::ZERO).count();
+    let _ = writeln!(out, "{} closed trades reviewed ({} losing).", critiques.len(), losing);
+    if !recurring.is_empty() {
+        let _ = writeln!(out);
+        let _ = writeln!(out, "| Critique | Trades | P&L |");
+        let _ = writeln!(out, "|---|---:|---:|");
+        for (tag, (trades, pnl)) in &recurring {
+            let _ = writeln!(out, "| {} | {} | {} |", tag.replace('_', " "), trades, pnl.round_dp(2));
+        }
+    }
+    let _ = writeln!(out);
+    for c in critiques.iter().rev().take(QUOTED_CRITIQUES) {
+        let _ = writeln!(out, "- {} {} ({}, {}): {}", c.timestamp.format("%m-%d %H:%M"), c.symbol,
+            c.exit_reason, c.pnl.round_dp(2), c.critique);
+    }
+    out
+}
+
+/// Review the week before `to` from the audit log and write the markdown report
+pub fn write_weekly(audit: &AuditLog, report: &Path, to: DateTime<Utc>) -> Result<()> {
+    let from = to - Duration::days(7);
+    let decisions = read_decisions(audit, from, to)?;
+    let mut markdown = render_markdown(from, to, &review(&decisions));
+    let critiques = read_critiques(audit, from, to)?;
+    if !critiques.is_empty() {
+        markdown.push_str(&render_critiques(&critiques));
+    }
+    write_atomic(report, markdown).map_err(|e| BotError::storage(report, e))?;
+    info!("📝 Weekly AI review ({} decisions) written to {}", decisions.len(), report.display());
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_review_scores_sources_from_the_audit_log() {
+        let start: DateTime<Utc> = "2025-03-03T00:00:00Z".parse().unwrap();
+        let line = |minutes: i64, source: &str, price: u32, sl: u32, tp: u32, support: u32, resistance: u32| format!(
+            r#"{{"timestamp":"{}","event":"targets","symbol":"BTCUSDT","source":"{}","price":"{}","stop_loss":"{}","take_profit":"{}","support":"{}","resistance":"{}","recommendation":"Buy","confidence":"70"}}"#,
+            (start + Duration::minutes(minutes)).to_rfc3339(), source, price, sl, tp, support, resistance);
+        let log = [
+            line(0, "ai", 100, 95, 104, 98, 106),
+            r#"{"timestamp":"2025-03-03T00:01:00Z","event":"capital_flow","asset":"USDT","amount":"10","value":null}"#.to_string(),
+            line(5, "fallback", 102, 97, 110, 96, 112),
+            line(10, "ai", 105, 100, 115, 99, 120),
+            line(15, "fallback", 99, 96, 108, 97, 109),
+            line(20, "ai", 97, 90, 130, 95, 125),
+            r#"{"timestamp":"2025-03-03T00:25:00Z","event":"trade_review","symbol":"BTCUSDT","position_id":"p1","pnl":"-4.2","exit_reason":"Stop-loss","critique":"Stop inside the noise.","tags":["stop_too_tight","early_entry"]}"#.to_string(),
+            r#"{"timestamp":"2025-03-03T00:30:00Z","event":"trade_review","symbol":"BTCUSDT","position_id":"p2","pnl":"-1.8","exit_reason":"Stop-loss","critique":"Same tight stop.","tags":["stop_too_tight"]}"#.to_string(),
+        ].join("\n");
+        let dir = std::env::temp_dir().join(format!("review-test-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let path = dir.join("audit.jsonl");
+        std::fs::write(&path, log).unwrap();
+        let audit = AuditLog::new(&path);
+
+        let decisions = read_decisions(&audit, start, start + Duration::days(7)).unwrap();
+        assert_eq!(decisions.len(), 5);
+        let reviews = review(&decisions);
+
+        // AI #1 hits 104 at 105; AI #2 hits its stop at 99; AI #3 has no later prices
+        let ai = &reviews["ai"];
+        assert_eq!((ai.decisions, ai.take_profit_hits, ai.stop_loss_hits), (3, 1, 1));
+        assert_eq!(ai.hit_rate(), Some(dec!(50)));
+        // AI #1: support 98 vs low 97, AI #2: support 99 vs low 97
+        assert_eq!(ai.support_error(), Some((dec!(1.0309) + dec!(2.0619)) / dec!(2)));
+        // Fallback #1 drops to its 97 stop; #2 reaches neither
+        let fallback = &reviews["fallback"];
+        assert_eq!((fallback.take_profit_hits, fallback.stop_loss_hits), (0, 1));
+
+        let report = render_markdown(start, start + Duration::days(7), &reviews);
+        assert!(report.contains("| ai | 3 | 1 | 1 | 50"));
+        assert!(report.contains("AI targets outperformed the fallback"));
+
+        let critiques = read_critiques(&audit, start, start + Duration::days(7)).unwrap();
+        let section = render_critiques(&critiques);
+        assert!(section.contains("2 closed trades reviewed (2 losing)"));
+        assert!(section.contains("| stop too tight | 2 | -6.0 |\n| early entry | 1 | -4.2 |"));
+        assert!(section.find("Same tight stop").unwrap() < section.find("Stop inside the noise").unwrap());
+        std::fs::remove_dir_all(&dir).ok();
+    }
+}
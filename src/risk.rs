@@ -0,0 +1,532 @@
+use crate::config::Config;
+use crate::error::BotError;
+use crate::exchange::Exchange;
+use crate::portfolio::PortfolioStatus;
+use crate::runtime_state::EquityPoint;
+use crate::state_store::StateStore;
+use crate::state_version::Schema;
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+use rust_decimal::prelude::ToPrimitive;
+use rust_decimal::Decimal;
+use rust_decimal_macros::dec;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+use tracing::{error, info, warn};
+
+/// Hourly candles used for the correlation of held assets
+const RETURN_WINDOW_HOURS: u32 = 72;
+/// Fewer overlapping returns than this and a pair is treated as uncorrelated
+const MIN_RETURNS: usize = 12;
+const REFRESH_INTERVAL: Duration = Duration::from_secs(3600);
+/// Hourly equity points needed before VaR is reported (two days)
+const MIN_VAR_POINTS: usize = 48;
+/// Daily returns needed for the historical estimate
+const MIN_HISTORICAL_DAYS: usize = 30;
+
+/// Caps the share of equity held in assets that move together, so a BTC entry isn't
+/// stacked on top of ETH and SOL positions that would fall with it
+pub struct ExposureLimits {
+    /// Max percent of equity in one correlated group; `None` disables the check
+    max_percent: Option<Decimal>,
+    threshold: f64,
+    /// Hourly returns per asset
+    returns: HashMap<String, Vec<f64>>,
+    refreshed: Option<Instant>,
+}
+
+impl ExposureLimits {
+    pub fn from_config(config: &Config) -> Result<Self> {
+        if let Some(max) = config.max_correlated_exposure_percent
+            && (max <= Decimal::ZERO || max > dec!(100)) {
+            return Err(BotError::config(format!("MAX_CORRELATED_EXPOSURE_PERCENT must be in (0, 100], got {}", max)).into());
+        }
+        if !(0.0..=1.0).contains(&config.correlation_threshold) {
+            return Err(BotError::config(format!("CORRELATION_THRESHOLD must be between 0 and 1, got {}",
+                config.correlation_threshold)).into());
+        }
+        Ok(Self {
+            max_percent: config.max_correlated_exposure_percent,
+            threshold: config.correlation_threshold,
+            returns: HashMap::new(),
+            refreshed: None,
+        })
+    }
+
+    pub fn is_enabled(&self) -> bool {
+        self.max_percent.is_some()
+    }
+
+    /// Record an asset's recent closes (oldest first)
+    pub fn update_closes(&mut self, asset: &str, closes: &[Decimal]) {
+        let prices: Vec<f64> = closes.iter().filter_map(|c| c.to_f64()).filter(|c| *c > 0.0).collect();
+        let returns = prices.windows(2).map(|w| w[1] / w[0] - 1.0).collect();
+        self.returns.insert(asset.to_string(), returns);
+    }
+
+    /// Fetch hourly candles for the other assets held, at most once an hour
+    pub async fn refresh(&mut self, exchange: &impl Exchange, status: &PortfolioStatus) {
+        if !self.is_enabled() || self.refreshed.is_some_and(|t| t.elapsed() < REFRESH_INTERVAL) {
+            return;
+        }
+        for asset in held_assets(status) {
+            if asset == status.base_asset {
+                continue;
+            }
+            let pair = format!("{}{}", asset, status.quote_asset);
+            match exchange.get_klines(&pair, "1h", RETURN_WINDOW_HOURS).await {
+                Ok(klines) => {
+                    let closes: Vec<Decimal> = klines.iter().map(|k| k.close).collect();
+                    self.update_closes(&asset, &closes);
+                }
+                Err(e) => warn!("⚠️ No price history for {} - treating it as uncorrelated: {}", pair, e),
+            }
+        }
+        self.refreshed = Some(Instant::now());
+    }
+
+    /// Hourly returns recorded for `asset`
+    pub fn returns(&self, asset: &str) -> Option<&[f64]> {
+        self.returns.get(asset).map(Vec::as_slice)
+    }
+
+    /// Correlation of two assets' overlapping recent returns
+    pub fn correlation(&self, a: &str, b: &str) -> Option<f64> {
+        if a == b {
+            return Some(1.0);
+        }
+        let (ra, rb) = (self.returns.get(a)?, self.returns.get(b)?);
+        let n = ra.len().min(rb.len());
+        if n < MIN_RETURNS {
+            return None;
+        }
+        pearson(&ra[ra.len() - n..], &rb[rb.len() - n..])
+    }
+
+    /// Reject a `value` entry into `asset` if it would push the asset's correlated group
+    /// past the configured share of equity
+    pub fn check_entry(&self, asset: &str, value: Decimal, status: &PortfolioStatus) -> Result<(), String> {
+        let Some(max_percent) = self.max_percent else {
+            return Ok(());
+        };
+        let equity = status.total_portfolio_value;
+        if equity <= Decimal::ZERO {
+            return Ok(());
+        }
+
+        let mut group = vec![asset.to_string()];
+        let mut exposure = value;
+        for held in held_assets(status) {
+            let Some(held_value) = status.balances.get(&held).and_then(|amount| status.value_in_quote(&held, *amount)) else {
+                continue;
+            };
+            if self.correlation(asset, &held).is_some_and(|c| c > self.threshold) {
+                exposure += held_value;
+                if held != asset {
+                    group.push(held);
+                }
+            }
+        }
+
+        let percent = exposure / equity * dec!(100);
+        if percent > max_percent {
+            return Err(format!("correlated exposure in {} would be {}% of equity (max {}%)",
+                group.join("/"), percent.round_dp(1), max_percent));
+        }
+        info!("🧮 Correlated exposure after entry: {}% of equity ({})", percent.round_dp(1), group.join("/"));
+        Ok(())
+    }
+}
+
+/// One-day Value-at-Risk as a percent of equity: the loss not exceeded with `confidence`
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct VarEstimate {
+    pub confidence: f64,
+    /// Normal approximation from the mean and volatility of hourly returns
+    pub parametric_percent: Decimal,
+    /// Empirical quantile of 24h returns, once a month of history exists
+    pub historical_percent: Option<Decimal>,
+}
+
+impl VarEstimate {
+    /// The more conservative of the two estimates
+    pub fn worst_percent(&self) -> Decimal {
+        self.historical_percent.map_or(self.parametric_percent, |h| h.max(self.parametric_percent))
+    }
+}
+
+/// Daily VaR of the recorded hourly equity curve
+pub fn equity_var(history: &[EquityPoint], confidence: f64) -> Option<VarEstimate> {
+    if history.len() < MIN_VAR_POINTS {
+        return None;
+    }
+    let values: Vec<f64> = history.iter().filter_map(|p| p.value.to_f64()).collect();
+    let hourly: Vec<f64> = values.windows(2).filter(|w| w[0] > 0.0).map(|w| w[1] / w[0] - 1.0).collect();
+    let daily: Vec<f64> = values.windows(25).filter(|w| w[0] > 0.0).map(|w| w[24] / w[0] - 1.0).collect();
+
+    Some(VarEstimate {
+        confidence,
+        parametric_percent: to_percent(parametric_var(&hourly, confidence)?),
+        historical_percent: (daily.len() >= MIN_HISTORICAL_DAYS).then(|| to_percent(historical_var(&daily, confidence))),
+    })
+}
+
+/// Daily VaR fraction from hourly returns, scaling mean by 24 and volatility by sqrt(24)
+pub fn parametric_var(hourly_returns: &[f64], confidence: f64) -> Option<f64> {
+    if hourly_returns.len() < 2 {
+        return None;
+    }
+    let n = hourly_returns.len() as f64;
+    let mean = hourly_returns.iter().sum::<f64>() / n;
+    let variance = hourly_returns.iter().map(|r| (r - mean).powi(2)).sum::<f64>() / (n - 1.0);
+    let daily_mean = mean * 24.0;
+    let daily_sigma = (variance * 24.0).sqrt();
+    Some((inverse_normal(confidence) * daily_sigma - daily_mean).max(0.0))
+}
+
+fn historical_var(daily_returns: &[f64], confidence: f64) -> f64 {
+    let mut sorted = daily_returns.to_vec();
+    sorted.sort_by(f64::total_cmp);
+    let index = (((1.0 - confidence) * sorted.len() as f64).floor() as usize).min(sorted.len() - 1);
+    (-sorted[index]).max(0.0)
+}
+
+fn to_percent(fraction: f64) -> Decimal {
+    Decimal::try_from(fraction * 100.0).unwrap_or_default().round_dp(4)
+}
+
+/// Quantile of the standard normal distribution (Acklam's approximation, |error| < 1.2e-9)
+fn inverse_normal(p: f64) -> f64 {
+    const A: [f64; 6] = [-3.969683028665376e1, 2.209460984245205e2, -2.759285104469687e2,
+        1.38357751867269e2, -3.066479806614716e1, 2.506628277459239];
+    const B: [f64; 5] = [-5.447609879822406e1, 1.615858368580409e2, -1.556989798598866e2,
+        6.680131188771972e1, -1.328068155288572e1];
+    const C: [f64; 6] = [-7.784894002430293e-3, -3.223964580411365e-1, -2.400758277161838,
+        -2.549732539343734, 4.374664141464968, 2.938163982698783];
+    const D: [f64; 4] = [7.784695709041462e-3, 3.224671290700398e-1, 2.445134137142996, 3.754408661907416];
+    const P_LOW: f64 = 0.02425;
+
+    let tail = |q: f64| (((((C[0] * q + C[1]) * q + C[2]) * q + C[3]) * q + C[4]) * q + C[5])
+        / ((((D[0] * q + D[1]) * q + D[2]) * q + D[3]) * q + 1.0);
+    if p < P_LOW {
+        tail((-2.0 * p.ln()).sqrt())
+    } else if p > 1.0 - P_LOW {
+        -tail((-2.0 * (1.0 - p).ln()).sqrt())
+    } else {
+        let q = p - 0.5;
+        let r = q * q;
+        (((((A[0] * r + A[1]) * r + A[2]) * r + A[3]) * r + A[4]) * r + A[5]) * q
+            / (((((B[0] * r + B[1]) * r + B[2]) * r + B[3]) * r + B[4]) * r + 1.0)
+    }
+}
+
+/// Halts new entries when the projected one-day VaR would exceed a share of equity
+pub struct VarLimit {
+    max_percent: Option<Decimal>,
+    confidence: f64,
+}
+
+impl VarLimit {
+    pub fn from_config(config: &Config) -> Result<Self> {
+        if !(0.5..1.0).contains(&config.var_confidence) {
+            return Err(BotError::config(format!("VAR_CONFIDENCE must be between 0.5 and 1, got {}", config.var_confidence)).into());
+        }
+        if config.max_var_percent.is_some_and(|m| m <= Decimal::ZERO) {
+            return Err(BotError::config("MAX_VAR_PERCENT must be positive").into());
+        }
+        Ok(Self { max_percent: config.max_var_percent, confidence: config.var_confidence })
+    }
+
+    pub fn confidence(&self) -> f64 {
+        self.confidence
+    }
+
+    pub fn max_percent(&self) -> Option<Decimal> {
+        self.max_percent
+    }
+
+    /// Projected VaR = the portfolio's current VaR plus the VaR of the new position, from
+    /// the asset's own hourly returns
+    pub fn check_entry(&self, portfolio: Option<&VarEstimate>, asset_returns: Option<&[f64]>, value: Decimal,
+                       equity: Decimal) -> Result<(), String> {
+        let Some(max_percent) = self.max_percent else {
+            return Ok(());
+        };
+        if equity <= Decimal::ZERO {
+            return Ok(());
+        }
+        let current = portfolio.map_or(Decimal::ZERO, |v| v.worst_percent() / dec!(100) * equity);
+        let position = asset_returns
+            .and_then(|r| parametric_var(r, self.confidence))
+            .map_or(Decimal::ZERO, |fraction| value * Decimal::try_from(fraction).unwrap_or_default());
+        let projected = (current + position) / equity * dec!(100);
+        if projected > max_percent {
+            return Err(format!("projected 1-day VaR {}% of equity exceeds MAX_VAR_PERCENT {}%",
+                projected.round_dp(2), max_percent));
+        }
+        Ok(())
+    }
+}
+
+/// Anti-tilt rule: after LOSS_STREAK_LIMIT losing trades in a row, entries are cut to
+/// LOSS_STREAK_SIZE_PERCENT of their size (0 pauses them) until a trade wins
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct LossStreak {
+    limit: u32,
+    size_percent: Decimal,
+}
+
+impl LossStreak {
+    /// `None` without LOSS_STREAK_LIMIT
+    pub fn from_config(config: &Config) -> Result<Option<Self>> {
+        if config.loss_streak_limit == 0 {
+            return Ok(None);
+        }
+        if config.loss_streak_size_percent < Decimal::ZERO || config.loss_streak_size_percent >= dec!(100) {
+            return Err(BotError::config(format!("LOSS_STREAK_SIZE_PERCENT must be at least 0 and below 100, got {}",
+                config.loss_streak_size_percent)).into());
+        }
+        Ok(Some(Self { limit: config.loss_streak_limit, size_percent: config.loss_streak_size_percent }))
+    }
+
+    /// Share of the usual entry size allowed after `losses` losing trades in a row, while cut
+    pub fn backoff_percent(&self, losses: u32) -> Option<Decimal> {
+        (losses >= self.limit).then_some(self.size_percent)
+    }
+
+    pub fn scale(&self, budget: Decimal, losses: u32) -> Decimal {
+        self.backoff_percent(losses).map_or(budget, |percent| budget * percent / dec!(100))
+    }
+
+    /// Why entries are paused, when the streak has cut them to nothing
+    pub fn pause_reason(&self, losses: u32) -> Option<String> {
+        self.backoff_percent(losses)
+            .filter(|percent| percent.is_zero())
+            .map(|_| format!("{} losing trades in a row - paused until a win", losses))
+    }
+}
+
+const HIGH_WATER_MARK_SCHEMA: Schema = Schema::new("equity high-water mark", &[]);
+
+/// The account's all-time equity peak, and the halt it tripped
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct HighWaterMark {
+    pub peak: Decimal,
+    pub peak_at: Option<DateTime<Utc>>,
+    /// Why trading is halted, until `bot reset-drawdown`
+    pub halted: Option<String>,
+}
+
+impl HighWaterMark {
+    pub fn load(store: &StateStore) -> Self {
+        store.load(&HIGH_WATER_MARK_SCHEMA).unwrap_or_else(|e| {
+            error!("❌ Equity high-water mark unusable, tracking restarts from the current equity: {}", e);
+            None
+        }).unwrap_or_default()
+    }
+
+    pub fn save(&self, store: &StateStore) {
+        if let Err(e) = store.save(&HIGH_WATER_MARK_SCHEMA, self) {
+            warn!("Failed to save equity high-water mark: {}", e);
+        }
+    }
+}
+
+/// Global kill switch against a slow bleed over many days: once equity falls MAX_DRAWDOWN_PERCENT
+/// below its all-time high, new entries halt until the halt is reset by hand. The mark lives
+/// in the state backend, so restarts don't clear it.
+pub struct DrawdownKillSwitch {
+    store: StateStore,
+    max_percent: Decimal,
+    mark: HighWaterMark,
+}
+
+impl DrawdownKillSwitch {
+    /// MAX_DRAWDOWN_PERCENT, checked
+    pub fn max_percent(config: &Config) -> Result<Option<Decimal>> {
+        match config.max_drawdown_percent {
+            Some(percent) if percent <= Decimal::ZERO || percent >= dec!(100) => Err(BotError::config(
+                format!("MAX_DRAWDOWN_PERCENT must be above 0 and below 100, got {}", percent)).into()),
+            percent => Ok(percent),
+        }
+    }
+
+    /// `None` without MAX_DRAWDOWN_PERCENT
+    pub fn from_config(config: &Config, store: StateStore) -> Result<Option<Self>> {
+        let Some(max_percent) = Self::max_percent(config)? else {
+            return Ok(None);
+        };
+        let mark = HighWaterMark::load(&store);
+        if let Some(reason) = &mark.halted {
+            error!("🛑 Trading is halted: {} - run `crypto_trading_bot reset-drawdown` to resume", reason);
+        }
+        Ok(Some(Self { store, max_percent, mark }))
+    }
+
+    pub fn mark(&self) -> &HighWaterMark {
+        &self.mark
+    }
+
+    /// Raise the peak or check the drawdown from it; the halt message the first time it trips
+    pub fn update(&mut self, equity: Decimal, now: DateTime<Utc>) -> Option<String> {
+        if equity <= Decimal::ZERO || self.mark.halted.is_some() {
+            return None;
+        }
+        if equity > self.mark.peak {
+            self.mark.peak = equity;
+            self.mark.peak_at = Some(now);
+            self.mark.save(&self.store);
+            return None;
+        }
+        let drawdown = (self.mark.peak - equity) / self.mark.peak * dec!(100);
+        if drawdown < self.max_percent {
+            return None;
+        }
+        let reason = format!("equity {} is {}% below its high-water mark {} (MAX_DRAWDOWN_PERCENT {}%)",
+            equity.round_dp(2), drawdown.round_dp(2).normalize(), self.mark.peak.round_dp(2), self.max_percent);
+        self.mark.halted = Some(reason.clone());
+        self.mark.save(&self.store);
+        Some(reason)
+    }
+
+    /// Why entries are halted, until reset
+    pub fn halt_reason(&self) -> Option<String> {
+        self.mark.halted.as_ref().map(|reason| format!("drawdown kill switch - {}", reason))
+    }
+
+    /// Clear the halt and the peak, which restarts from the next equity reading
+    pub fn reset(store: &StateStore) -> HighWaterMark {
+        let previous = HighWaterMark::load(store);
+        HighWaterMark::default().save(store);
+        previous
+    }
+}
+
+/// Non-quote assets with a balance
+fn held_assets(status: &PortfolioStatus) -> Vec<String> {
+    let mut assets: Vec<String> = status.balances.iter()
+        .filter(|(asset, amount)| **asset != status.quote_asset && **amount > Decimal::ZERO)
+        .map(|(asset, _)| asset.clone())
+        .collect();
+    assets.sort();
+    assets
+}
+
+fn pearson(a: &[f64], b: &[f64]) -> Option<f64> {
+    let n = a.len() as f64;
+    let (mean_a, mean_b) = (a.iter().sum::<f64>() / n, b.iter().sum::<f64>() / n);
+    let (mut cov, mut var_a, mut var_b) = (0.0, 0.0, 0.0);
+    for (x, y) in a.iter().zip(b) {
+        cov += (x - mean_a) * (y - mean_b);
+        var_a += (x - mean_a).powi(2);
+        var_b += (y - mean_b).powi(2);
+    }
+    (var_a > 0.0 && var_b > 0.0).then(|| cov / (var_a * var_b).sqrt())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_drawdown_from_the_high_water_mark_halts_until_reset() {
+        let dir = std::env::temp_dir().join(format!("ctb_hwm_{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let store = StateStore::file(&dir.join("high_water_mark.json"), None);
+        let mut config = Config::defaults();
+        config.max_drawdown_percent = Some(dec!(20));
+        let now = Utc::now();
+
+        let mut switch = DrawdownKillSwitch::from_config(&config, store.clone()).unwrap().unwrap();
+        assert_eq!(switch.update(dec!(1000), now), None);
+        assert_eq!(switch.update(dec!(1200), now), None);
+        assert_eq!(switch.update(dec!(961), now), None);
+        assert!(switch.update(dec!(960), now).unwrap().contains("20% below its high-water mark 1200"));
+        assert_eq!(switch.update(dec!(900), now), None);
+
+        // Still halted after a restart, and back to a fresh peak once reset
+        let restarted = DrawdownKillSwitch::from_config(&config, store.clone()).unwrap().unwrap();
+        assert!(restarted.halt_reason().unwrap().starts_with("drawdown kill switch"));
+        assert_eq!(DrawdownKillSwitch::reset(&store).peak, dec!(1200));
+        let reset = DrawdownKillSwitch::from_config(&config, store).unwrap().unwrap();
+        assert_eq!((reset.halt_reason(), reset.mark().peak), (None, Decimal::ZERO));
+        std::fs::remove_dir_all(&dir).ok();
+    }
+
+    #[test]
+    fn test_loss_streak_backs_off_until_a_win() {
+        let mut config = Config::defaults();
+        config.loss_streak_limit = 3;
+        config.loss_streak_size_percent = dec!(50);
+        let streak = LossStreak::from_config(&config).unwrap().unwrap();
+        assert_eq!(streak.scale(dec!(100), 2), dec!(100));
+        assert_eq!(streak.scale(dec!(100), 3), dec!(50));
+        assert_eq!(streak.pause_reason(4), None);
+
+        config.loss_streak_size_percent = Decimal::ZERO;
+        let pausing = LossStreak::from_config(&config).unwrap().unwrap();
+        assert_eq!(pausing.scale(dec!(100), 3), Decimal::ZERO);
+        assert!(pausing.pause_reason(3).unwrap().contains("3 losing trades in a row"));
+        config.loss_streak_limit = 0;
+        assert_eq!(LossStreak::from_config(&config).unwrap(), None);
+    }
+
+    #[test]
+    fn test_correlated_holdings_count_against_new_entries() {
+        let mut config = Config::defaults();
+        config.max_correlated_exposure_percent = Some(dec!(30));
+        config.correlation_threshold = 0.8;
+        let mut limits = ExposureLimits::from_config(&config).unwrap();
+
+        let btc: Vec<Decimal> = (0..30).map(|i| Decimal::from(100 + (i % 5) * 3 + i)).collect();
+        let eth: Vec<Decimal> = btc.iter().map(|p| p / dec!(20)).collect();
+        let doge: Vec<Decimal> = (0..30).map(|i| Decimal::from(50 + (i % 3) * 7 - (i % 4) * 5)).collect();
+        limits.update_closes("BTC", &btc);
+        limits.update_closes("ETH", &eth);
+        limits.update_closes("DOGE", &doge);
+        assert!(limits.correlation("BTC", "ETH").unwrap() > 0.99);
+        assert!(limits.correlation("BTC", "DOGE").unwrap() < 0.8);
+
+        let mut status = PortfolioStatus::new("BTCUSDT", false);
+        status.current_price = dec!(100);
+        status.conversion_rates.insert("ETH".to_string(), dec!(5));
+        status.conversion_rates.insert("DOGE".to_string(), dec!(1));
+        status.balances.insert("USDT".to_string(), dec!(6000));
+        status.balances.insert("ETH".to_string(), dec!(400)); // 2000
+        status.balances.insert("DOGE".to_string(), dec!(2000)); // 2000
+        status.update_total_value();
+
+        // 20% in ETH already; a 1500 BTC entry would make BTC/ETH 35%
+        assert!(limits.check_entry("BTC", dec!(1500), &status).unwrap_err().contains("BTC/ETH"));
+        assert!(limits.check_entry("BTC", dec!(900), &status).is_ok());
+    }
+
+    #[test]
+    fn test_value_at_risk_estimates_and_entry_limit() {
+        assert!((inverse_normal(0.95) - 1.644854).abs() < 1e-5);
+
+        // Equity alternating +1%/-1% hourly: 1-day sigma ~4.9%, parametric 95% VaR ~8%
+        let start = chrono::Utc::now();
+        let mut value = 1000.0;
+        let history: Vec<EquityPoint> = (0..24 * 40).map(|h| {
+            value *= if h % 2 == 0 { 1.01 } else { 0.99 };
+            EquityPoint { timestamp: start + chrono::Duration::hours(h), value: Decimal::try_from(value).unwrap() }
+        }).collect();
+        assert!(equity_var(&history[..24], 0.95).is_none());
+        let var = equity_var(&history, 0.95).unwrap();
+        assert!(var.parametric_percent > dec!(7.5) && var.parametric_percent < dec!(8.5));
+        assert!(var.historical_percent.unwrap() < dec!(1));
+        assert_eq!(var.worst_percent(), var.parametric_percent);
+
+        let mut config = Config::defaults();
+        config.max_var_percent = Some(dec!(10));
+        config.var_confidence = 0.95;
+        let limit = VarLimit::from_config(&config).unwrap();
+        let returns: Vec<f64> = (0..48).map(|h| if h % 2 == 0 { 0.01 } else { -0.01 }).collect();
+        // 8% now, plus ~4% of a 500 entry into a 1000 account
+        assert!(limit.check_entry(Some(&var), Some(&returns), dec!(500), dec!(1000)).is_err());
+        assert!(limit.check_entry(Some(&var), Some(&returns), dec!(100), dec!(1000)).is_ok());
+        assert!(limit.check_entry(None, Some(&returns), dec!(500), dec!(1000)).is_ok());
+    }
+}
@@ -0,0 +1,85 @@
+use crate::error::BotError;
+use anyhow::Result;
+use rust_decimal::Decimal;
+use rust_decimal_macros::dec;
+
+/// Named bundle of risk settings picked with RISK_PROFILE; each value is only a default,
+/// so an explicitly set STOP_LOSS_PERCENT, MAX_TRADES_PER_DAY, ... still wins
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RiskProfile {
+    pub stop_loss_percent: Decimal,
+    pub take_profit_percent: Decimal,
+    /// Share of the free quote balance an entry uses
+    pub position_size_percent: Decimal,
+    pub max_trades_per_day: u32,
+    /// AI targets below this confidence are set aside for the fallback's
+    pub min_ai_confidence: Decimal,
+}
+
+/// Without RISK_PROFILE: the settings the bot has always used
+impl Default for RiskProfile {
+    fn default() -> Self {
+        Self {
+            stop_loss_percent: dec!(-5),
+            take_profit_percent: dec!(10),
+            position_size_percent: dec!(10),
+            max_trades_per_day: 2,
+            min_ai_confidence: Decimal::ZERO,
+        }
+    }
+}
+
+impl RiskProfile {
+    pub const NAMES: [&str; 3] = ["conservative", "balanced", "aggressive"];
+
+    /// The preset called `name` (case-insensitive); empty means none
+    pub fn parse(name: &str) -> Result<Self> {
+        let profile = match name.trim().to_lowercase().as_str() {
+            "" => Self::default(),
+            "conservative" => Self {
+                stop_loss_percent: dec!(-3),
+                take_profit_percent: dec!(6),
+                position_size_percent: dec!(5),
+                max_trades_per_day: 2,
+                min_ai_confidence: dec!(70),
+            },
+            "balanced" => Self {
+                min_ai_confidence: dec!(55),
+                ..Self::default()
+            },
+            "aggressive" => Self {
+                stop_loss_percent: dec!(-8),
+                take_profit_percent: dec!(16),
+                position_size_percent: dec!(20),
+                max_trades_per_day: 6,
+                min_ai_confidence: dec!(40),
+            },
+            other => return Err(BotError::config(format!(
+                "Invalid RISK_PROFILE '{}' (expected {})", other, Self::NAMES.join(", ")
+            )).into()),
+        };
+        Ok(profile)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_presets_scale_risk() {
+        assert_eq!(RiskProfile::parse("").unwrap(), RiskProfile::default());
+        let conservative = RiskProfile::parse("Conservative").unwrap();
+        let balanced = RiskProfile::parse("balanced").unwrap();
+        let aggressive = RiskProfile::parse(" aggressive ").unwrap();
+        for (safer, riskier) in [(conservative, balanced), (balanced, aggressive)] {
+            assert!(safer.stop_loss_percent > riskier.stop_loss_percent);
+            assert!(safer.position_size_percent < riskier.position_size_percent);
+            assert!(safer.max_trades_per_day <= riskier.max_trades_per_day);
+            assert!(safer.min_ai_confidence > riskier.min_ai_confidence);
+            // Every preset aims for twice what it risks
+            assert_eq!(riskier.take_profit_percent, -riskier.stop_loss_percent * dec!(2));
+        }
+        assert!(RiskProfile::parse("yolo").unwrap_err().to_string().contains("conservative, balanced, aggressive"));
+    }
+}
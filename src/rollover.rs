@@ -0,0 +1,134 @@
+use crate::audit::AuditLog;
+use crate::config::Config;
+use crate::error::BotError;
+use crate::notifier::TelegramNotifier;
+use crate::paths::DataPaths;
+use crate::portfolio::{DaySummary, PortfolioReporter};
+use crate::review;
+use anyhow::Result;
+use chrono::{DateTime, Datelike, NaiveDate, Utc, Weekday};
+use chrono_tz::Tz;
+use std::fs::OpenOptions;
+use std::io::Write;
+use std::path::Path;
+use tracing::{info, warn};
+
+/// Marks local midnight (TRADE_DAY_TIMEZONE): archives the day's report, snapshots equity,
+/// resets the daily stats and alerts and sends the daily summary. After Sundays it also
+/// writes the weekly AI review.
+pub struct DayRollover {
+    tz: Tz,
+    day: NaiveDate,
+    telegram: Option<TelegramNotifier>,
+}
+
+impl DayRollover {
+    pub fn from_config(config: &Config) -> Result<Self> {
+        let tz: Tz = config.trade_day_timezone.parse().map_err(|_| BotError::config(format!(
+            "Invalid TRADE_DAY_TIMEZONE '{}' (expected an IANA name like America/Sao_Paulo)",
+            config.trade_day_timezone
+        )))?;
+        let telegram = match (&config.telegram_bot_token, &config.telegram_chat_id) {
+            (Some(token), Some(chat)) => Some(TelegramNotifier::new(token.expose(), chat)?),
+            _ => None,
+        };
+        Ok(Self { tz, day: Utc::now().with_timezone(&tz).date_naive(), telegram })
+    }
+
+    /// The day that just ended, once `now` is past its local midnight
+    pub fn finished_day(&mut self, now: DateTime<Utc>) -> Option<NaiveDate> {
+        let today = now.with_timezone(&self.tz).date_naive();
+        if today <= self.day {
+            return None;
+        }
+        Some(std::mem::replace(&mut self.day, today))
+    }
+
+    /// Run the rollover if the day changed. Failures are logged; trading continues.
+    pub async fn run_if_due(&mut self, reporter: &mut PortfolioReporter, paths: &DataPaths, audit: &AuditLog) {
+        let Some(date) = self.finished_day(Utc::now()) else {
+            return;
+        };
+        let summary = match reporter.close_day(date, &paths.daily_report_file(date)) {
+            Ok(summary) => summary,
+            Err(e) => {
+                warn!("⚠️ End-of-day rollover for {} failed: {}", date, e);
+                return;
+            }
+        };
+        if let Err(e) = append_equity_snapshot(&paths.daily_equity_file(), &summary) {
+            warn!("⚠️ Failed to record daily equity: {}", e);
+        }
+        if date.weekday() == Weekday::Sun
+            && let Err(e) = review::write_weekly(audit, &paths.weekly_review_file(date), Utc::now()) {
+            warn!("⚠️ Failed to write the weekly AI review: {}", e);
+        }
+        let status = reporter.status();
+        let message = summary.format_message(&status.symbol, &status.display, status.language);
+        info!("{}", message.replace('\n', " | "));
+        if let Some(telegram) = &self.telegram
+            && let Err(e) = telegram.send_message(&message).await {
+            warn!("⚠️ Failed to send daily summary: {}", e);
+        }
+    }
+}
+
+/// One CSV row per day: date, start and end value, change and realized P&L
+fn append_equity_snapshot(path: &Path, summary: &DaySummary) -> Result<()> {
+    let new_file = !path.exists();
+    let mut file = OpenOptions::new().create(true).append(true).open(path)
+        .map_err(|e| BotError::storage(path, e))?;
+    let mut row = String::new();
+    if new_file {
+        row.push_str("date,start_value,end_value,change_percent,realized_pnl,trades\n");
+    }
+    row.push_str(&format!("{},{},{},{},{},{}\n", summary.date, summary.start_value.round_dp(2),
+        summary.end_value.round_dp(2), summary.change_percent().round_dp(4), summary.realized_pnl.round_dp(2), summary.trades));
+    file.write_all(row.as_bytes()).map_err(|e| BotError::storage(path, e))?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::models::OrderSide;
+    use rust_decimal_macros::dec;
+
+    #[test]
+    fn test_rollover_archives_and_resets_the_day() {
+        let mut rollover = DayRollover {
+            tz: "America/Sao_Paulo".parse().unwrap(),
+            day: NaiveDate::from_ymd_opt(2025, 3, 10).unwrap(),
+            telegram: None,
+        };
+        // 23:30 local (UTC-3) is still the same day; 03:30 UTC the next morning is past midnight
+        assert_eq!(rollover.finished_day("2025-03-11T02:30:00Z".parse().unwrap()), None);
+        assert_eq!(rollover.finished_day("2025-03-11T03:30:00Z".parse().unwrap()), NaiveDate::from_ymd_opt(2025, 3, 10));
+        assert_eq!(rollover.finished_day("2025-03-11T04:00:00Z".parse().unwrap()), None);
+
+        let dir = std::env::temp_dir().join(format!("rollover-test-{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let mut reporter = PortfolioReporter::new("BTCUSDT", true, &dir.join("portfolio_status.txt"));
+        reporter.update_balances([("USDT".to_string(), dec!(1000))].into_iter().collect());
+        reporter.record_trade(OrderSide::Buy, dec!(100), dec!(1), None, dec!(0));
+        reporter.record_trade(OrderSide::Sell, dec!(110), dec!(1), Some(dec!(10)), dec!(0));
+        reporter.update_balances([("USDT".to_string(), dec!(1010))].into_iter().collect());
+
+        let date = NaiveDate::from_ymd_opt(2025, 3, 10).unwrap();
+        let summary = reporter.close_day(date, &dir.join("2025-03-10.txt")).unwrap();
+        assert_eq!((summary.start_value, summary.end_value), (dec!(1000), dec!(1010)));
+        assert_eq!((summary.trades, summary.winning_trades, summary.realized_pnl), (2, 1, dec!(10)));
+        assert_eq!(summary.change_percent(), dec!(1));
+        assert!(dir.join("2025-03-10.txt").exists());
+
+        let status = reporter.status();
+        assert_eq!((status.day_trades, status.day_realized_pnl), (0, dec!(0)));
+        assert_eq!(status.day_start_value, Some(dec!(1010)));
+        assert_eq!(status.total_trades, 2);
+
+        append_equity_snapshot(&dir.join("daily_equity.csv"), &summary).unwrap();
+        let csv = std::fs::read_to_string(dir.join("daily_equity.csv")).unwrap();
+        assert_eq!(csv.lines().nth(1), Some("2025-03-10,1000,1010,1.00,10,2"));
+        std::fs::remove_dir_all(&dir).ok();
+    }
+}
@@ -0,0 +1,176 @@
+use crate::ai_advisor::AiTradingTargets;
+use crate::position::Position;
+use crate::state_store::StateStore;
+use crate::state_version::Schema;
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+use rust_decimal::Decimal;
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value};
+use tracing::{error, info, warn};
+
+/// One equity point per hour, 90 days deep
+const EQUITY_INTERVAL_SECS: i64 = 3600;
+const MAX_EQUITY_POINTS: usize = 24 * 90;
+
+const RUNTIME_STATE_SCHEMA: Schema = Schema::new("runtime state", &[position_slots]);
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EquityPoint {
+    pub timestamp: DateTime<Utc>,
+    pub value: Decimal,
+}
+
+/// What the monitoring loop needs to resume mid-position after a restart or a
+/// move to another machine. Saved to the state backend (`state/runtime_state.json`
+/// by default) every cycle.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RuntimeState {
+    pub symbol: String,
+    /// Positions that are not flat, by id
+    #[serde(default)]
+    pub positions: Vec<Position>,
+    pub targets: Option<AiTradingTargets>,
+    #[serde(default)]
+    pub equity_history: Vec<EquityPoint>,
+    /// Losing trades since the last winner, for LOSS_STREAK_LIMIT
+    #[serde(default)]
+    pub consecutive_losses: u32,
+    pub saved_at: DateTime<Utc>,
+}
+
+impl RuntimeState {
+    pub fn new(symbol: &str) -> Self {
+        Self {
+            symbol: symbol.to_string(),
+            positions: Vec::new(),
+            targets: None,
+            equity_history: Vec::new(),
+            consecutive_losses: 0,
+            saved_at: Utc::now(),
+        }
+    }
+
+    /// Append the portfolio value if the last point is at least an hour old
+    pub fn record_equity(&mut self, value: Decimal, now: DateTime<Utc>) {
+        push_equity_point(&mut self.equity_history, value, now);
+    }
+}
+
+/// Version 1 held a single position, first as bare `in_position`/`position_qty`/`entry_price`
+/// fields and later as `position`; version 2 keeps every open slot in `positions`
+fn position_slots(document: &mut Map<String, Value>) -> Result<()> {
+    let position = document.remove("position").filter(|p| !p.is_null());
+    let in_position = document.remove("in_position").and_then(|v| v.as_bool()).unwrap_or(false);
+    let qty = document.remove("position_qty");
+    let entry_price = document.remove("entry_price");
+    if document.get("positions").and_then(Value::as_array).is_some_and(|p| !p.is_empty()) {
+        return Ok(());
+    }
+    let position = match position {
+        Some(position) => Some(position),
+        None if in_position => {
+            let symbol = document.get("symbol").and_then(Value::as_str).unwrap_or_default();
+            let qty: Decimal = qty.map(serde_json::from_value).transpose()?.unwrap_or_default();
+            let entry_price: Option<Decimal> = entry_price.map(serde_json::from_value).transpose()?.flatten();
+            Some(serde_json::to_value(Position::resumed(symbol, qty, entry_price))?)
+        }
+        None => None,
+    };
+    document.insert("positions".to_string(), Value::Array(position.into_iter().collect()));
+    Ok(())
+}
+
+/// Append `value` to an hourly equity curve capped at 90 days
+pub fn push_equity_point(history: &mut Vec<EquityPoint>, value: Decimal, now: DateTime<Utc>) {
+    if history.last().is_some_and(|p| (now - p.timestamp).num_seconds() < EQUITY_INTERVAL_SECS) {
+        return;
+    }
+    history.push(EquityPoint { timestamp: now, value });
+    if history.len() > MAX_EQUITY_POINTS {
+        let excess = history.len() - MAX_EQUITY_POINTS;
+        history.drain(..excess);
+    }
+}
+
+/// Loads and saves [`RuntimeState`] in the configured state backend
+pub struct RuntimeStore {
+    store: StateStore,
+}
+
+impl RuntimeStore {
+    pub fn new(store: StateStore) -> Self {
+        Self { store }
+    }
+
+    /// The saved state for `symbol`, or a fresh one if there is none (or it belongs to another symbol)
+    pub fn load(&self, symbol: &str) -> RuntimeState {
+        match self.store.load::<RuntimeState>(&RUNTIME_STATE_SCHEMA) {
+            Ok(None) => RuntimeState::new(symbol),
+            Ok(Some(state)) if state.symbol == symbol => {
+                for position in &state.positions {
+                    info!("♻️ Resuming {} {} position: {} @ {}", symbol, position.id, position.qty,
+                        position.entry_price.map_or("?".to_string(), |p| p.to_string()));
+                }
+                state
+            }
+            Ok(Some(state)) => {
+                warn!("⚠️ Saved runtime state is for {}, not {} - starting fresh", state.symbol, symbol);
+                RuntimeState::new(symbol)
+            }
+            Err(e) => {
+                error!("❌ Runtime state unusable, starting without the saved positions: {}", e);
+                RuntimeState::new(symbol)
+            }
+        }
+    }
+
+    pub fn save(&self, state: &mut RuntimeState) {
+        state.saved_at = Utc::now();
+        if let Err(e) = self.store.save(&RUNTIME_STATE_SCHEMA, state) {
+            warn!("Failed to save runtime state: {}", e);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rust_decimal_macros::dec;
+
+    #[test]
+    fn test_equity_history_is_hourly_and_bounded() {
+        let mut state = RuntimeState::new("BTCUSDT");
+        let start = Utc::now();
+        state.record_equity(dec!(1000), start);
+        state.record_equity(dec!(1001), start + chrono::Duration::minutes(30));
+        assert_eq!(state.equity_history.len(), 1);
+
+        for h in 1..=(MAX_EQUITY_POINTS as i64 + 5) {
+            state.record_equity(Decimal::from(1000 + h), start + chrono::Duration::hours(h));
+        }
+        assert_eq!(state.equity_history.len(), MAX_EQUITY_POINTS);
+        assert_eq!(state.equity_history[0].value, dec!(1006));
+    }
+
+    #[test]
+    fn test_single_position_state_migrates_to_slots() {
+        let dir = std::env::temp_dir().join(format!("ctb_runtime_migration_{}", std::process::id()));
+        std::fs::create_dir_all(&dir).unwrap();
+        let store = StateStore::file(&dir.join("runtime_state.json"), None);
+        store.write(r#"{"symbol": "BTCUSDT", "in_position": true, "position_qty": "0.5", "entry_price": "60000",
+            "targets": null, "saved_at": "2025-01-01T00:00:00Z"}"#).unwrap();
+        let runtime = RuntimeStore::new(store.clone());
+        let mut state = runtime.load("BTCUSDT");
+        assert_eq!(state.positions.len(), 1);
+        assert_eq!((state.positions[0].qty, state.positions[0].entry_price), (dec!(0.5), Some(dec!(60000))));
+
+        // Saved back as the current version; a newer one is kept aside rather than overwritten
+        runtime.save(&mut state);
+        assert!(store.read().unwrap().unwrap().contains(r#""version": 2"#));
+        store.write(r#"{"version": 3, "symbol": "BTCUSDT"}"#).unwrap();
+        assert!(runtime.load("BTCUSDT").positions.is_empty());
+        assert!(store.companion("unreadable").read().unwrap().unwrap().contains(r#""version": 3"#));
+        std::fs::remove_dir_all(&dir).ok();
+    }
+}
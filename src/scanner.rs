@@ -0,0 +1,220 @@
+use crate::coingecko::CoinGeckoClient;
+use crate::config::Config;
+use crate::error::BotError;
+use crate::exchange::Exchange;
+use crate::models::Kline;
+use crate::quote;
+use crate::strategy::RsiStrategy;
+use crate::symbols;
+use crate::volume;
+use anyhow::Result;
+use rust_decimal::Decimal;
+use rust_decimal_macros::dec;
+use std::time::{Duration, Instant};
+use tracing::{info, warn};
+
+/// RSI(14) below this counts as oversold
+const OVERSOLD_RSI: Decimal = dec!(30);
+/// Price within this percent of the 24h low counts as near support
+const NEAR_SUPPORT_PERCENT: Decimal = dec!(2);
+const SUPPORT_LOOKBACK: usize = 24;
+/// Last hour's volume at this multiple of the trailing average counts as a spike
+const VOLUME_SPIKE_MULTIPLE: Decimal = dec!(3);
+const VOLUME_LOOKBACK: usize = 20;
+/// Hourly candles fetched per symbol
+const CANDLES: u32 = 50;
+
+/// What the scanner does with its findings (SCANNER_ACTION)
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ScannerAction {
+    /// Log and report the candidates
+    Alert,
+    /// Also switch trading to the best candidate once all positions are flat
+    Promote,
+}
+
+impl ScannerAction {
+    pub fn parse(value: &str) -> Result<Self> {
+        match value.to_lowercase().as_str() {
+            "alert" => Ok(Self::Alert),
+            "promote" => Ok(Self::Promote),
+            other => Err(BotError::config(format!("SCANNER_ACTION must be alert or promote, got '{}'", other)).into()),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Setup {
+    OversoldNearSupport,
+    VolumeSpike,
+}
+
+impl Setup {
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::OversoldNearSupport => "RSI oversold near support",
+            Self::VolumeSpike => "volume spike",
+        }
+    }
+}
+
+/// One watchlist symbol after screening
+#[derive(Debug, Clone, PartialEq)]
+pub struct Candidate {
+    pub symbol: String,
+    pub price: Decimal,
+    pub rsi: Option<Decimal>,
+    /// Lowest low of the last 24 hours
+    pub support: Option<Decimal>,
+    /// Last hour's volume over the trailing average
+    pub volume_ratio: Option<Decimal>,
+    pub setups: Vec<Setup>,
+}
+
+impl Candidate {
+    /// Screen hourly candles, oldest first
+    pub fn screen(symbol: &str, klines: &[Kline]) -> Option<Self> {
+        let price = klines.last()?.close;
+        let closes: Vec<Decimal> = klines.iter().map(|k| k.close).collect();
+        let rsi = RsiStrategy::calculate_rsi(&closes, 14);
+        let support = klines.iter().rev().take(SUPPORT_LOOKBACK).map(|k| k.low).min();
+        let volume_ratio = volume::volume_ratio(klines, VOLUME_LOOKBACK);
+
+        let mut setups = Vec::new();
+        let near_support = support.is_some_and(|s| s > Decimal::ZERO && (price - s) / s * dec!(100) <= NEAR_SUPPORT_PERCENT);
+        if rsi.is_some_and(|r| r < OVERSOLD_RSI) && near_support {
+            setups.push(Setup::OversoldNearSupport);
+        }
+        if volume_ratio.is_some_and(|r| r >= VOLUME_SPIKE_MULTIPLE) {
+            setups.push(Setup::VolumeSpike);
+        }
+        Some(Self { symbol: symbol.to_string(), price, rsi, support, volume_ratio, setups })
+    }
+}
+
+/// Candidates with a setup, most setups first, then the lowest RSI
+pub fn rank(candidates: &mut Vec<Candidate>) {
+    candidates.retain(|c| !c.setups.is_empty());
+    candidates.sort_by(|a, b| b.setups.len().cmp(&a.setups.len())
+        .then_with(|| a.rsi.unwrap_or(dec!(100)).cmp(&b.rsi.unwrap_or(dec!(100)))));
+}
+
+/// Periodically screens the top-N coins by volume (SCANNER_ENABLED) for setups
+pub struct Scanner {
+    action: ScannerAction,
+    top_n: u32,
+    interval: Duration,
+    quote: String,
+    coingecko: CoinGeckoClient,
+    last_scan: Option<Instant>,
+    /// Ranked candidates of the last scan
+    pub candidates: Vec<Candidate>,
+}
+
+impl Scanner {
+    pub fn from_config(config: &Config, quote: &str) -> Result<Option<Self>> {
+        if !config.scanner_enabled {
+            return Ok(None);
+        }
+        if config.scanner_top_n == 0 || config.scanner_interval_minutes == 0 {
+            return Err(BotError::config("SCANNER_TOP_N and SCANNER_INTERVAL_MINUTES must be positive").into());
+        }
+        Ok(Some(Self {
+            action: ScannerAction::parse(&config.scanner_action)?,
+            top_n: config.scanner_top_n,
+            interval: Duration::from_secs(config.scanner_interval_minutes * 60),
+            quote: quote.to_string(),
+            coingecko: CoinGeckoClient::new(),
+            last_scan: None,
+            candidates: Vec::new(),
+        }))
+    }
+
+    pub fn is_due(&self) -> bool {
+        self.last_scan.is_none_or(|t| t.elapsed() >= self.interval)
+    }
+
+    /// Screen the watchlist on `exchange`'s hourly candles; symbols without candles are skipped
+    pub async fn scan(&mut self, exchange: &impl Exchange) -> Result<()> {
+        self.last_scan = Some(Instant::now());
+        let vs_currency = if self.quote == "EUR" { "eur" } else { "usd" };
+        let coins = self.coingecko.fetch_top_by_volume(vs_currency, self.top_n).await?;
+        let mut candidates = Vec::new();
+        for coin in coins {
+            if coin == self.quote || quote::is_usd_stable(&coin) {
+                continue;
+            }
+            let symbol = format!("{}{}", coin, self.quote);
+            match exchange.get_klines(&symbol, "1h", CANDLES).await {
+                Ok(klines) => candidates.extend(Candidate::screen(&symbol, &klines)),
+                Err(e) => warn!("⚠️ Scanner skipped {}: {}", symbol, e),
+            }
+        }
+        rank(&mut candidates);
+        for c in &candidates {
+            let setups: Vec<&str> = c.setups.iter().map(|s| s.label()).collect();
+            info!("🔭 {} @ {}: {} (RSI {}, volume {}x)", c.symbol, c.price, setups.join(", "),
+                c.rsi.map_or("-".to_string(), |r| r.round_dp(1).to_string()),
+                c.volume_ratio.map_or("-".to_string(), |r| r.to_string()));
+        }
+        if candidates.is_empty() {
+            info!("🔭 Scanner: no setups in the top {} by volume", self.top_n);
+        }
+        self.candidates = candidates;
+        Ok(())
+    }
+
+    /// The best candidate to trade instead of `current`, when promoting. Only symbols with
+    /// built-in metadata qualify, since the loops need their CoinGecko id.
+    pub fn promotion(&self, current: &str) -> Option<&str> {
+        if self.action != ScannerAction::Promote {
+            return None;
+        }
+        self.candidates.iter()
+            .find(|c| symbols::lookup(&c.symbol).coingecko_id.is_some())
+            .map(|c| c.symbol.as_str())
+            .filter(|symbol| *symbol != current)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn candle(close: Decimal, low: Decimal, volume: Decimal) -> Kline {
+        Kline { open_time: 0, open: close, high: close, low, close, volume, close_time: 0 }
+    }
+
+    #[test]
+    fn test_screen_finds_oversold_and_volume_spikes() {
+        // A steady slide into the 24h low on triple volume
+        let mut falling: Vec<Kline> = (0..30).map(|i| {
+            let close = Decimal::from(200 - i);
+            candle(close, close - dec!(1), dec!(100))
+        }).collect();
+        falling.last_mut().unwrap().volume = dec!(400);
+        let sliding = Candidate::screen("SOLUSDT", &falling).unwrap();
+        assert_eq!(sliding.setups, vec![Setup::OversoldNearSupport, Setup::VolumeSpike]);
+        assert_eq!(sliding.volume_ratio, Some(dec!(4)));
+
+        let flat: Vec<Kline> = (0..30).map(|_| candle(dec!(10), dec!(9), dec!(100))).collect();
+        let quiet = Candidate::screen("ADAUSDT", &flat).unwrap();
+        assert!(quiet.setups.is_empty());
+
+        let mut spike = flat.clone();
+        spike.last_mut().unwrap().volume = dec!(300);
+        let spiking = Candidate::screen("FOOUSDT", &spike).unwrap();
+
+        let mut candidates = vec![quiet, spiking, sliding];
+        rank(&mut candidates);
+        assert_eq!(candidates.iter().map(|c| c.symbol.as_str()).collect::<Vec<_>>(), vec!["SOLUSDT", "FOOUSDT"]);
+
+        let mut config = Config::defaults();
+        config.scanner_enabled = true;
+        config.scanner_action = "promote".to_string();
+        let mut scanner = Scanner::from_config(&config, "USDT").unwrap().unwrap();
+        scanner.candidates = candidates;
+        assert_eq!(scanner.promotion("BTCUSDT"), Some("SOLUSDT"));
+        assert_eq!(scanner.promotion("SOLUSDT"), None);
+    }
+}
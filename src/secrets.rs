@@ -0,0 +1,222 @@
+use crate::config::Config;
+use crate::error::BotError;
+use anyhow::{anyhow, Context, Result};
+use std::fmt;
+use tracing::info;
+
+/// A string that never prints its contents in Debug/Display output
+#[derive(Clone, Default, PartialEq)]
+pub struct SecretString(String);
+
+impl SecretString {
+    pub fn new(value: impl Into<String>) -> Self {
+        Self(value.into())
+    }
+
+    /// Access the raw secret value. Only call this where the value is sent to its destination.
+    pub fn expose(&self) -> &str {
+        &self.0
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+}
+
+impl fmt::Debug for SecretString {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.0.is_empty() {
+            write!(f, "\"\"")
+        } else {
+            write!(f, "\"***\"")
+        }
+    }
+}
+
+impl fmt::Display for SecretString {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "***")
+    }
+}
+
+/// Resolve secrets referenced by the configuration.
+///
+/// Each secret can come from (in order of precedence):
+/// - `<NAME>_FILE`: path to a file containing the secret (e.g. Docker/systemd credentials)
+/// - `<NAME>=keyring:<service>/<account>`: the OS keyring (`secret-tool` on Linux, `security` on macOS)
+/// - `<NAME>=vault:<path>#<field>`: HashiCorp Vault KV (v1 or v2), using `VAULT_ADDR` and `VAULT_TOKEN`
+/// - `<NAME>=<value>`: a plain environment variable
+pub async fn resolve_config_secrets(mut config: Config) -> Result<Config> {
+    config.api_key = resolve_secret("API_KEY", &config.api_key).await?;
+    config.api_secret = resolve_secret("API_SECRET", &config.api_secret).await?;
+    let token = config.telegram_bot_token.clone().unwrap_or_default();
+    let token = resolve_secret("TELEGRAM_BOT_TOKEN", &token).await?;
+    config.telegram_bot_token = (!token.is_empty()).then_some(token);
+    let passphrase = config.state_passphrase.clone().unwrap_or_default();
+    let passphrase = resolve_secret("STATE_PASSPHRASE", &passphrase).await?;
+    config.state_passphrase = (!passphrase.is_empty()).then_some(passphrase);
+    let state_url = config.state_url.clone().unwrap_or_default();
+    let state_url = resolve_secret("STATE_URL", &state_url).await?;
+    config.state_url = (!state_url.is_empty()).then_some(state_url);
+    let whale_key = config.whale_alert_api_key.clone().unwrap_or_default();
+    let whale_key = resolve_secret("WHALE_ALERT_API_KEY", &whale_key).await?;
+    config.whale_alert_api_key = (!whale_key.is_empty()).then_some(whale_key);
+    let grpc_token = config.grpc_token.clone().unwrap_or_default();
+    let grpc_token = resolve_secret("GRPC_TOKEN", &grpc_token).await?;
+    config.grpc_token = (!grpc_token.is_empty()).then_some(grpc_token);
+    Ok(config)
+}
+
+async fn resolve_secret(name: &str, value: &SecretString) -> Result<SecretString> {
+    if let Ok(path) = std::env::var(format!("{}_FILE", name)) {
+        let content = std::fs::read_to_string(&path)
+            .with_context(|| format!("Failed to read {}_FILE at {}", name, path))?;
+        info!("🔑 {} loaded from file", name);
+        return Ok(SecretString::new(content.trim()));
+    }
+
+    let raw = value.expose();
+    if let Some(reference) = raw.strip_prefix("keyring:") {
+        let secret = read_keyring(reference).with_context(|| format!("Failed to read {} from keyring", name))?;
+        info!("🔑 {} loaded from OS keyring", name);
+        return Ok(SecretString::new(secret));
+    }
+
+    if let Some(reference) = raw.strip_prefix("vault:") {
+        let secret = read_vault(reference).await.with_context(|| format!("Failed to read {} from Vault", name))?;
+        info!("🔑 {} loaded from Vault", name);
+        return Ok(SecretString::new(secret));
+    }
+
+    Ok(value.clone())
+}
+
+/// Look up `<service>/<account>` in the OS keyring using the platform CLI
+fn read_keyring(reference: &str) -> Result<String> {
+    let (service, account) = reference
+        .split_once('/')
+        .ok_or_else(|| anyhow!("Expected keyring:<service>/<account>"))?;
+
+    let output = if cfg!(target_os = "macos") {
+        std::process::Command::new("security")
+            .args(["find-generic-password", "-s", service, "-a", account, "-w"])
+            .output()?
+    } else {
+        std::process::Command::new("secret-tool")
+            .args(["lookup", "service", service, "username", account])
+            .output()?
+    };
+
+    if !output.status.success() {
+        return Err(anyhow!("No keyring entry for service '{}' account '{}'", service, account));
+    }
+
+    let secret = String::from_utf8(output.stdout)?.trim().to_string();
+    if secret.is_empty() {
+        return Err(anyhow!("Keyring entry for '{}' is empty", reference));
+    }
+    Ok(secret)
+}
+
+/// Read `<path>#<field>` from Vault, supporting both KV v1 and v2 response layouts
+async fn read_vault(reference: &str) -> Result<String> {
+    let (path, field) = reference
+        .split_once('#')
+        .ok_or_else(|| anyhow!("Expected vault:<path>#<field>"))?;
+
+    let addr = std::env::var("VAULT_ADDR").map_err(|_| anyhow!("VAULT_ADDR is not set"))?;
+    let token = match std::env::var("VAULT_TOKEN_FILE") {
+        Ok(file) => std::fs::read_to_string(file)?.trim().to_string(),
+        Err(_) => std::env::var("VAULT_TOKEN").map_err(|_| anyhow!("VAULT_TOKEN is not set"))?,
+    };
+
+    let url = format!("{}/v1/{}", addr.trim_end_matches('/'), path.trim_start_matches('/'));
+    let response = reqwest::Client::new()
+        .get(&url)
+        .header("X-Vault-Token", &token)
+        .timeout(std::time::Duration::from_secs(10))
+        .send()
+        .await?;
+
+    if !response.status().is_success() {
+        return Err(anyhow!("Vault returned {} for {}", response.status(), path));
+    }
+
+    let body: serde_json::Value = response.json().await?;
+    let data = &body["data"];
+    let value = data["data"][field].as_str().or_else(|| data[field].as_str());
+
+    value
+        .map(|v| v.to_string())
+        .ok_or_else(|| anyhow!("Field '{}' not found at {}", field, path))
+}
+
+/// Scrubs known secret values (and request signatures) from text before it is logged
+#[derive(Clone, Default)]
+pub struct Redactor {
+    secrets: Vec<String>,
+}
+
+impl Redactor {
+    pub fn new(secrets: &[&SecretString]) -> Self {
+        Self {
+            secrets: secrets
+                .iter()
+                .filter(|s| s.expose().len() >= 4)
+                .map(|s| s.expose().to_string())
+                .collect(),
+        }
+    }
+
+    pub fn redact(&self, text: &str) -> String {
+        let mut result = text.to_string();
+        for secret in &self.secrets {
+            result = result.replace(secret, "***");
+        }
+        redact_signature(&result)
+    }
+
+    /// Rebuild an error with secrets removed from the whole context chain
+    pub fn redact_error(&self, error: anyhow::Error) -> anyhow::Error {
+        match error.downcast::<BotError>() {
+            Ok(bot_error) => bot_error.map_message(|m| self.redact(m)).into(),
+            Err(error) => anyhow!(self.redact(&format!("{:#}", error))),
+        }
+    }
+}
+
+fn redact_signature(text: &str) -> String {
+    let mut result = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(pos) = rest.find("signature=") {
+        let (before, after) = rest.split_at(pos + "signature=".len());
+        result.push_str(before);
+        result.push_str("***");
+        rest = after.trim_start_matches(|c: char| c.is_ascii_hexdigit());
+    }
+    result.push_str(rest);
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_secret_string_is_not_printed() {
+        let secret = SecretString::new("super-secret-key");
+        assert_eq!(format!("{:?}", secret), "\"***\"");
+        assert_eq!(format!("{}", secret), "***");
+    }
+
+    #[test]
+    fn test_redactor() {
+        let key = SecretString::new("abcd1234key");
+        let redactor = Redactor::new(&[&key]);
+        let text = "error for key abcd1234key at /api/v3/account?timestamp=1&signature=deadbeef01 done";
+        assert_eq!(
+            redactor.redact(text),
+            "error for key *** at /api/v3/account?timestamp=1&signature=*** done"
+        );
+    }
+}
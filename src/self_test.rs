@@ -0,0 +1,101 @@
+use crate::config::Config;
+use crate::exchange::ExchangeClient;
+use crate::models::{OrderSide, OrderType};
+use crate::simulation::SimulationExchange;
+use crate::symbols::SymbolInfo;
+use anyhow::{anyhow, Context, Result};
+use rust_decimal::Decimal;
+use rust_decimal_macros::dec;
+use tracing::{info, warn};
+
+/// The test buy rests this far below the market so it can't fill before it is cancelled
+const TEST_ORDER_DISCOUNT: Decimal = dec!(0.90);
+
+/// Whether SELF_TEST_ORDER applies: only on the testnet or in simulation, never with real funds
+pub fn enabled(config: &Config) -> bool {
+    if !config.self_test_order {
+        return false;
+    }
+    if config.is_simulation() || config.exchange == "binance_testnet" {
+        return true;
+    }
+    warn!("⚠️ SELF_TEST_ORDER ignored: the startup test order only runs on binance_testnet or in simulation");
+    false
+}
+
+/// Minimum-size limit buy well below `price`, rounded to the symbol's filters
+fn test_order(symbol_info: &SymbolInfo, price: Decimal) -> (Decimal, Decimal) {
+    let limit_price = symbol_info.round_price(price * TEST_ORDER_DISCOUNT);
+    (symbol_info.min_order_qty(limit_price), limit_price)
+}
+
+/// Place a minimum-size limit buy below the market, check it rests on the book with the
+/// quantity and price sent, then cancel it: exercises signing, precision and response parsing
+pub async fn run_live(exchange: &ExchangeClient, symbol_info: &SymbolInfo) -> Result<()> {
+    let price = exchange.get_price(&symbol_info.symbol).await.context("Self-test: price lookup failed")?;
+    let (qty, limit_price) = test_order(symbol_info, price);
+    info!("🧪 Self-test: placing a {} {} limit buy @ {}", qty, symbol_info.symbol, limit_price);
+
+    let order = exchange.place_order(&symbol_info.symbol, OrderSide::Buy, OrderType::Limit, qty, Some(limit_price)).await
+        .context("Self-test: test order rejected")?;
+    let check = async {
+        let orig_qty: Decimal = order.orig_qty.parse().context("Self-test: unparseable order quantity")?;
+        if orig_qty != qty {
+            return Err(anyhow!("Self-test: order echoed quantity {} instead of {}", orig_qty, qty));
+        }
+        let open = exchange.get_open_orders(&symbol_info.symbol).await.context("Self-test: open orders lookup failed")?;
+        match open.iter().find(|o| o.order_id == order.order_id) {
+            Some(resting) if resting.price == limit_price => Ok(()),
+            Some(resting) => Err(anyhow!("Self-test: order rests at {} instead of {}", resting.price, limit_price)),
+            None => Err(anyhow!("Self-test: order #{} is not on the book (status {})", order.order_id, order.status)),
+        }
+    }.await;
+
+    // Cancel even when a check failed, so no test order is left behind
+    let cancelled = exchange.cancel_order(&symbol_info.symbol, order.order_id).await
+        .context("Self-test: cancelling the test order failed");
+    check?;
+    cancelled?;
+    info!("✅ Self-test passed: order #{} placed, found on the book and cancelled", order.order_id);
+    Ok(())
+}
+
+/// The simulated exchange fills orders at once, so the test buys the minimum size and
+/// sells it straight back; the round trip costs two fees of the simulated balance
+pub async fn run_simulated(exchange: &SimulationExchange, symbol_info: &SymbolInfo) -> Result<()> {
+    let price = exchange.get_price(&symbol_info.symbol).await.context("Self-test: price lookup failed")?;
+    let qty = symbol_info.min_order_qty(price);
+    info!("🧪 Self-test: round-tripping {} {} through the simulated exchange", qty, symbol_info.symbol);
+
+    for side in [OrderSide::Buy, OrderSide::Sell] {
+        let order = exchange.place_order(&symbol_info.symbol, side, OrderType::Market, qty, None).await
+            .with_context(|| format!("Self-test: simulated {} failed", side))?;
+        let filled: Decimal = order.executed_qty.parse().context("Self-test: unparseable filled quantity")?;
+        if filled != qty {
+            return Err(anyhow!("Self-test: simulated {} filled {} instead of {}", side, filled, qty));
+        }
+    }
+    info!("✅ Self-test passed: simulated buy and sell filled");
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::symbols;
+
+    #[test]
+    fn test_order_meets_filters_below_market() {
+        let btc = symbols::lookup("BTCUSDT");
+        let (qty, price) = test_order(&btc, dec!(60000.123));
+        assert_eq!(price, dec!(54000.11));
+        assert_eq!(qty, dec!(0.0001));
+        assert!(btc.meets_minimums(qty, price));
+
+        // Rounded up to the lot step so the notional still clears the minimum
+        let xrp = symbols::lookup("XRPUSDT");
+        let (qty, price) = test_order(&xrp, dec!(0.6));
+        assert_eq!(qty, dec!(10));
+        assert!(xrp.meets_minimums(qty, price));
+    }
+}
@@ -0,0 +1,209 @@
+use crate::config::Config;
+use crate::error::BotError;
+use anyhow::Result;
+use chrono::{DateTime, DurationRound, TimeDelta, Timelike, Utc};
+use rust_decimal::Decimal;
+use rust_decimal_macros::dec;
+use serde::{Deserialize, Serialize};
+
+/// Main trading session by UTC hour: Asia from Sydney's open, Europe from London's, the US
+/// from New York's pre-market until its close
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Session {
+    /// 22:00-07:00 UTC
+    Asia,
+    /// 07:00-13:00 UTC
+    Europe,
+    /// 13:00-22:00 UTC
+    Us,
+}
+
+impl Session {
+    pub const ALL: [Session; 3] = [Self::Asia, Self::Europe, Self::Us];
+
+    pub fn of(time: DateTime<Utc>) -> Self {
+        match time.hour() {
+            7..13 => Self::Europe,
+            13..22 => Self::Us,
+            _ => Self::Asia,
+        }
+    }
+
+    fn parse(name: &str) -> Option<Self> {
+        match name.trim().to_lowercase().as_str() {
+            "asia" => Some(Self::Asia),
+            "europe" | "eu" => Some(Self::Europe),
+            "us" | "america" => Some(Self::Us),
+            _ => None,
+        }
+    }
+
+    pub fn label(self) -> &'static str {
+        match self {
+            Self::Asia => "Asia",
+            Self::Europe => "Europe",
+            Self::Us => "US",
+        }
+    }
+
+    pub fn hours(self) -> &'static str {
+        match self {
+            Self::Asia => "22-07",
+            Self::Europe => "07-13",
+            Self::Us => "13-22",
+        }
+    }
+
+    fn index(self) -> usize {
+        self as usize
+    }
+}
+
+/// Holds back entries outside the sessions listed in TRADING_SESSIONS
+pub struct SessionFilter {
+    allowed: Vec<Session>,
+}
+
+impl SessionFilter {
+    /// `None` without TRADING_SESSIONS
+    pub fn from_config(config: &Config) -> Result<Option<Self>> {
+        if config.trading_sessions.is_empty() {
+            return Ok(None);
+        }
+        let allowed = config.trading_sessions.iter()
+            .map(|name| Session::parse(name).ok_or_else(|| BotError::config(
+                format!("Unknown TRADING_SESSIONS entry '{}' (expected asia, europe or us)", name))))
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(Some(Self { allowed }))
+    }
+
+    /// Why entries are paused at `now`, if they are
+    pub fn pause_reason(&self, now: DateTime<Utc>) -> Option<String> {
+        let session = Session::of(now);
+        (!self.allowed.contains(&session)).then(|| format!("{} session - entries only in {}", session.label(),
+            self.allowed.iter().map(|s| s.label()).collect::<Vec<_>>().join(", ")))
+    }
+}
+
+/// Closed trades of one session
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub struct SessionTally {
+    pub trades: u32,
+    pub wins: u32,
+    pub pnl: Decimal,
+}
+
+impl SessionTally {
+    pub fn win_rate(&self) -> Option<Decimal> {
+        (self.trades > 0).then(|| Decimal::from(self.wins) / Decimal::from(self.trades) * dec!(100))
+    }
+}
+
+/// Closed trades bucketed by the session they were entered in
+#[derive(Debug, Clone, Copy, Default, PartialEq, Serialize, Deserialize)]
+pub struct SessionStats {
+    tallies: [SessionTally; 3],
+}
+
+impl SessionStats {
+    pub fn record(&mut self, session: Session, pnl: Decimal) {
+        let tally = &mut self.tallies[session.index()];
+        tally.trades += 1;
+        tally.pnl += pnl;
+        if pnl > Decimal::ZERO {
+            tally.wins += 1;
+        }
+    }
+
+    pub fn merge(&mut self, other: &SessionStats) {
+        for (tally, other) in self.tallies.iter_mut().zip(&other.tallies) {
+            tally.trades += other.trades;
+            tally.wins += other.wins;
+            tally.pnl += other.pnl;
+        }
+    }
+
+    pub fn get(&self, session: Session) -> &SessionTally {
+        &self.tallies[session.index()]
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.tallies.iter().all(|t| t.trades == 0)
+    }
+}
+
+/// Average hourly high-low range per session, from the prices seen each cycle
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct SessionVolatility {
+    /// Start, high and low of the hour being sampled
+    hour: Option<(DateTime<Utc>, Decimal, Decimal)>,
+    /// Sum of completed hourly ranges in percent, and their count
+    ranges: [(Decimal, u32); 3],
+}
+
+impl SessionVolatility {
+    pub fn update(&mut self, price: Decimal, now: DateTime<Utc>) {
+        let start = now.duration_trunc(TimeDelta::hours(1)).unwrap_or(now);
+        match &mut self.hour {
+            Some((hour, high, low)) if *hour == start => {
+                *high = (*high).max(price);
+                *low = (*low).min(price);
+            }
+            previous => {
+                if let Some((hour, high, low)) = previous.take()
+                    && low > Decimal::ZERO {
+                    let (sum, count) = &mut self.ranges[Session::of(hour).index()];
+                    *sum += (high - low) / low * dec!(100);
+                    *count += 1;
+                }
+                *previous = Some((start, price, price));
+            }
+        }
+    }
+
+    /// Mean hourly range in percent, once an hour of the session has completed
+    pub fn average_range(&self, session: Session) -> Option<Decimal> {
+        let (sum, count) = self.ranges[session.index()];
+        (count > 0).then(|| sum / Decimal::from(count))
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.ranges.iter().all(|(_, count)| *count == 0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::TimeZone;
+
+    #[test]
+    fn test_sessions_filter_and_volatility() {
+        let at = |hour, minute| Utc.with_ymd_and_hms(2026, 3, 2, hour, minute, 0).unwrap();
+        assert_eq!(Session::of(at(23, 0)), Session::Asia);
+        assert_eq!(Session::of(at(6, 59)), Session::Asia);
+        assert_eq!(Session::of(at(7, 0)), Session::Europe);
+        assert_eq!(Session::of(at(13, 30)), Session::Us);
+
+        let mut config = Config::defaults();
+        config.trading_sessions = vec!["europe".to_string(), "US".to_string()];
+        let filter = SessionFilter::from_config(&config).unwrap().unwrap();
+        assert_eq!(filter.pause_reason(at(14, 0)), None);
+        assert_eq!(filter.pause_reason(at(3, 0)).unwrap(), "Asia session - entries only in Europe, US");
+        config.trading_sessions = vec!["mars".to_string()];
+        assert!(SessionFilter::from_config(&config).is_err());
+
+        let mut volatility = SessionVolatility::default();
+        volatility.update(dec!(100), at(8, 0));
+        volatility.update(dec!(102), at(8, 30));
+        assert_eq!(volatility.average_range(Session::Europe), None);
+        volatility.update(dec!(101), at(9, 0));
+        assert_eq!(volatility.average_range(Session::Europe), Some(dec!(2)));
+
+        let mut stats = SessionStats::default();
+        stats.record(Session::Us, dec!(5));
+        stats.record(Session::Us, dec!(-2));
+        assert_eq!(stats.get(Session::Us).win_rate(), Some(dec!(50)));
+        assert_eq!(stats.get(Session::Us).pnl, dec!(3));
+    }
+}
@@ -1,25 +1,45 @@
+use crate::chaos::Chaos;
 use crate::config::Config;
-use crate::models::{Balance, Order, OrderSide, OrderType};
+use crate::exchange::{position_order_id, Exchange};
+use crate::models::{Balance, Fill, Kline, Order, OrderSide, OrderType};
+use crate::retention::Retention;
+use crate::symbols;
 use anyhow::Result;
 use rust_decimal::Decimal;
 use rust_decimal_macros::dec;
-use std::collections::HashMap;
-use std::sync::{Arc, Mutex};
+use serde::Serialize;
+use std::collections::{HashMap, VecDeque};
+use std::sync::Mutex;
 use std::time::{SystemTime, UNIX_EPOCH};
+use tokio::sync::RwLock;
 use tracing::info;
 
-/// Simulated exchange for testing trading strategies without real money
+/// Simulated exchange for testing trading strategies without real money.
+///
+/// All mutable state sits behind a single async `RwLock`, so an order updates
+/// prices, balances and history atomically and readers never block the runtime.
+/// Each symbol's price has its own cell, so a price tick only needs the read lock.
 pub struct SimulationExchange {
     config: Config,
-    balances: Arc<Mutex<HashMap<String, Balance>>>,
-    current_prices: Arc<Mutex<HashMap<String, Decimal>>>,
-    orders: Arc<Mutex<Vec<Order>>>,
-    order_id_counter: Arc<Mutex<i64>>,
-    trade_history: Arc<Mutex<Vec<SimulatedTrade>>>,
+    state: RwLock<SimState>,
+    chaos: Option<Chaos>,
+    /// Bounds the order and trade history (HISTORY_RETENTION)
+    retention: Retention,
+}
+
+struct SimState {
+    balances: HashMap<String, Balance>,
+    /// Never held across an await, so a plain mutex per symbol
+    current_prices: HashMap<String, Mutex<Decimal>>,
+    orders: VecDeque<Order>,
+    next_order_id: i64,
+    trade_history: VecDeque<SimulatedTrade>,
+    /// Trades trimmed from the history, so the count stays right
+    archived_trades: usize,
 }
 
 #[allow(dead_code)]
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, Serialize)]
 pub struct SimulatedTrade {
     pub timestamp: i64,
     pub symbol: String,
@@ -33,22 +53,24 @@ pub struct SimulatedTrade {
 impl SimulationExchange {
     pub async fn new(config: &Config) -> Result<Self> {
         let mut balances = HashMap::new();
+        let pair = symbols::lookup(&config.symbol);
+        let (base_asset, quote_asset) = (pair.base.as_str(), pair.quote.as_str());
         
-        // Initialize with simulation balance (USDT)
+        // Initialize with simulation balance in the quote asset
         balances.insert(
-            "USDT".to_string(),
+            quote_asset.to_string(),
             Balance {
-                asset: "USDT".to_string(),
+                asset: quote_asset.to_string(),
                 free: config.simulation_initial_balance,
                 locked: Decimal::ZERO,
             },
         );
         
-        // Start with 0 BTC
+        // Start with no base asset
         balances.insert(
-            "BTC".to_string(),
+            base_asset.to_string(),
             Balance {
-                asset: "BTC".to_string(),
+                asset: base_asset.to_string(),
                 free: Decimal::ZERO,
                 locked: Decimal::ZERO,
             },
@@ -59,20 +81,46 @@ impl SimulationExchange {
         prices.insert("BTCUSDT".to_string(), dec!(42000.00));
         prices.insert("ETHUSDT".to_string(), dec!(2500.00));
         prices.insert("BNBUSDT".to_string(), dec!(300.00));
+        // Same starting price for the pair in any USD stablecoin
+        if let Some(price) = prices.get(&format!("{}USDT", base_asset)).copied() {
+            prices.entry(config.symbol.clone()).or_insert(price);
+        }
 
         info!("🎮 Simulation exchange initialized");
-        info!("💰 Starting balance: {} USDT", config.simulation_initial_balance);
+        info!("💰 Starting balance: {} {}", config.simulation_initial_balance, quote_asset);
 
         Ok(Self {
             config: config.clone(),
-            balances: Arc::new(Mutex::new(balances)),
-            current_prices: Arc::new(Mutex::new(prices)),
-            orders: Arc::new(Mutex::new(Vec::new())),
-            order_id_counter: Arc::new(Mutex::new(1)),
-            trade_history: Arc::new(Mutex::new(Vec::new())),
+            state: RwLock::new(SimState {
+                balances,
+                current_prices: prices.into_iter().map(|(symbol, price)| (symbol, Mutex::new(price))).collect(),
+                orders: VecDeque::new(),
+                next_order_id: 1,
+                trade_history: VecDeque::new(),
+                archived_trades: 0,
+            }),
+            chaos: None,
+            retention: Retention::from_config(config)?,
         })
     }
 
+    /// Fail and delay calls from now on at the chaos mode's rates
+    pub fn with_chaos(self, chaos: Option<Chaos>) -> Self {
+        Self { chaos, ..self }
+    }
+
+    /// Trim the order and trade history with `retention`, e.g. one that spills to the state backend
+    pub fn with_retention(self, retention: Retention) -> Self {
+        Self { retention, ..self }
+    }
+
+    async fn disrupt(&self, call: &str) -> Result<()> {
+        match &self.chaos {
+            Some(chaos) => chaos.disrupt(call).await,
+            None => Ok(()),
+        }
+    }
+
     fn timestamp() -> i64 {
         SystemTime::now()
             .duration_since(UNIX_EPOCH)
@@ -95,53 +143,111 @@ impl SimulationExchange {
         if new_price > Decimal::ZERO { new_price } else { current_price }
     }
 
+    /// Last price of `symbol`: the latest random-walk step or observed market price
+    fn last_price(state: &SimState, symbol: &str) -> Decimal {
+        state.current_prices.get(symbol).map(|cell| *cell.lock().unwrap()).unwrap_or(dec!(42000.00))
+    }
+
+    /// Advance the random walk for `symbol` and return the new price
+    fn tick_price(&self, cell: &Mutex<Decimal>) -> Decimal {
+        let mut price = cell.lock().unwrap();
+        *price = self.simulate_price_movement(*price);
+        *price
+    }
+
     pub async fn get_price(&self, symbol: &str) -> Result<Decimal> {
-        let mut prices = self.current_prices.lock().unwrap();
-        
-        let current_price = prices.get(symbol).copied().unwrap_or(dec!(42000.00));
-        let new_price = self.simulate_price_movement(current_price);
-        prices.insert(symbol.to_string(), new_price);
-        
-        Ok(new_price)
+        self.disrupt("get_price").await?;
+        if let Some(cell) = self.state.read().await.current_prices.get(symbol) {
+            return Ok(self.tick_price(cell));
+        }
+        // First sighting of the symbol: the only tick that needs the write lock
+        let mut state = self.state.write().await;
+        let cell = state.current_prices.entry(symbol.to_string()).or_insert_with(|| Mutex::new(dec!(42000.00)));
+        Ok(self.tick_price(cell))
+    }
+
+    /// Move the random walk to an externally observed price, so fills follow a real market
+    pub async fn set_price(&self, symbol: &str, price: Decimal) {
+        if let Some(cell) = self.state.read().await.current_prices.get(symbol) {
+            *cell.lock().unwrap() = price;
+            return;
+        }
+        self.state.write().await.current_prices.insert(symbol.to_string(), Mutex::new(price));
     }
 
     pub async fn get_balance(&self) -> Result<HashMap<String, Balance>> {
-        let balances = self.balances.lock().unwrap();
-        Ok(balances.clone())
+        self.disrupt("get_balance").await?;
+        Ok(self.state.read().await.balances.clone())
+    }
+
+    /// Free balance of a single asset, without copying the whole balance map; never disrupted,
+    /// so checks on the account hold under chaos mode
+    pub async fn free_balance(&self, asset: &str) -> Decimal {
+        self.state.read().await.balances.get(asset).map(|b| b.free).unwrap_or_default()
     }
 
     pub async fn place_order(
         &self,
         symbol: &str,
         side: OrderSide,
-        _order_type: OrderType,
+        order_type: OrderType,
+        quantity: Decimal,
+        price: Option<Decimal>,
+    ) -> Result<Order> {
+        self.fill_order(None, symbol, side, order_type, quantity, price).await
+    }
+
+    /// Place an order tagged with the position it belongs to
+    pub async fn place_position_order(
+        &self,
+        position_id: &str,
+        symbol: &str,
+        side: OrderSide,
+        order_type: OrderType,
+        quantity: Decimal,
+        price: Option<Decimal>,
+    ) -> Result<Order> {
+        self.fill_order(Some(position_order_id(position_id)), symbol, side, order_type, quantity, price).await
+    }
+
+    async fn fill_order(
+        &self,
+        client_order_id: Option<String>,
+        symbol: &str,
+        side: OrderSide,
+        order_type: OrderType,
         quantity: Decimal,
         _price: Option<Decimal>,
     ) -> Result<Order> {
-        let current_price = self.get_price(symbol).await?;
-        let order_value = quantity * current_price;
-        
         // Get base and quote assets from symbol (e.g., BTCUSDT -> BTC, USDT)
-        let base_asset = symbol.replace("USDT", "");
-        let quote_asset = "USDT".to_string();
-        
-        let mut balances = self.balances.lock().unwrap();
+        let symbols::SymbolInfo { base: base_asset, quote: quote_asset, .. } = symbols::lookup(symbol);
+        // An injected failure happens before anything fills
+        self.disrupt("place_order").await?;
+
+        // Market orders fill at the price last seen, however far it gapped past a stop
+        let mut state = self.state.write().await;
+        let current_price = Self::last_price(&state, symbol);
+        let order_value = quantity * current_price;
+        // Fees are charged in the quote asset on both sides
+        let fee = order_value * self.config.trading_fee_percent / dec!(100);
+        let balances = &mut state.balances;
         
         match side {
             OrderSide::Buy => {
-                // Check if we have enough USDT
-                let usdt_balance = balances.get(&quote_asset).map(|b| b.free).unwrap_or(Decimal::ZERO);
-                if usdt_balance < order_value {
+                // Check if we have enough of the quote asset
+                let quote_balance = balances.get(&quote_asset).map(|b| b.free).unwrap_or(Decimal::ZERO);
+                if quote_balance < order_value + fee {
                     return Err(anyhow::anyhow!(
-                        "Insufficient balance: need {} USDT, have {}",
-                        order_value,
-                        usdt_balance
+                        "Insufficient balance: need {} {}, have {}",
+                        order_value + fee,
+                        quote_asset,
+                        quote_balance
                     ));
                 }
                 
-                // Deduct USDT
+                // Deduct quote asset
                 if let Some(balance) = balances.get_mut(&quote_asset) {
-                    balance.free -= order_value;
+                    balance.free -= order_value + fee;
                 }
                 
                 // Add base asset
@@ -152,7 +258,7 @@ impl SimulationExchange {
                 });
                 base_balance.free += quantity;
                 
-                info!("🟢 SIMULATED BUY: {} {} @ {} = {} USDT", quantity, base_asset, current_price, order_value);
+                info!("🟢 SIMULATED BUY: {} {} @ {} = {} {}", quantity, base_asset, current_price, order_value, quote_asset);
             }
             OrderSide::Sell => {
                 // Check if we have enough base asset
@@ -171,37 +277,43 @@ impl SimulationExchange {
                     balance.free -= quantity;
                 }
                 
-                // Add USDT
-                let usdt_balance = balances.entry(quote_asset.clone()).or_insert(Balance {
-                    asset: quote_asset,
+                // Add quote asset
+                let quote_balance = balances.entry(quote_asset.clone()).or_insert(Balance {
+                    asset: quote_asset.clone(),
                     free: Decimal::ZERO,
                     locked: Decimal::ZERO,
                 });
-                usdt_balance.free += order_value;
+                quote_balance.free += order_value - fee;
                 
-                info!("🔴 SIMULATED SELL: {} {} @ {} = {} USDT", quantity, base_asset, current_price, order_value);
+                info!("🔴 SIMULATED SELL: {} {} @ {} = {} {}", quantity, base_asset, current_price, order_value, quote_asset);
             }
         }
         
         // Create order
-        let mut order_id = self.order_id_counter.lock().unwrap();
-        let id = *order_id;
-        *order_id += 1;
+        let id = state.next_order_id;
+        state.next_order_id += 1;
         
         let order = Order {
             symbol: symbol.to_string(),
             order_id: id,
-            client_order_id: format!("sim_{}", id),
+            client_order_id: client_order_id.unwrap_or_else(|| format!("sim_{}", id)),
             price: current_price.to_string(),
             orig_qty: quantity.to_string(),
             executed_qty: quantity.to_string(),
             status: "FILLED".to_string(),
             side,
-            order_type: _order_type,
+            order_type,
+            fills: vec![Fill {
+                price: current_price,
+                qty: quantity,
+                commission: fee,
+                commission_asset: quote_asset,
+            }],
+            avg_price: None,
         };
         
         // Store trade history
-        let trade = SimulatedTrade {
+        state.trade_history.push_back(SimulatedTrade {
             timestamp: Self::timestamp(),
             symbol: symbol.to_string(),
             side: format!("{:?}", side),
@@ -209,14 +321,14 @@ impl SimulationExchange {
             quantity,
             value: order_value,
             pnl: Decimal::ZERO, // Would need entry price tracking for real PnL
-        };
-        self.trade_history.lock().unwrap().push(trade);
-        self.orders.lock().unwrap().push(order.clone());
+        });
+        state.orders.push_back(order.clone());
+        state.archived_trades += self.retention.enforce("sim_trades", &mut state.trade_history);
+        self.retention.enforce("sim_orders", &mut state.orders);
         
         Ok(order)
     }
 
-    #[allow(dead_code)]
     pub async fn get_klines(
         &self,
         symbol: &str,
@@ -224,9 +336,8 @@ impl SimulationExchange {
         limit: u32,
     ) -> Result<Vec<crate::models::Kline>> {
         use rand::Rng;
-        let mut rng = rand::thread_rng();
-        
         let current_price = self.get_price(symbol).await?;
+        let mut rng = rand::thread_rng();
         let mut klines = Vec::new();
         let mut price = current_price;
         
@@ -260,16 +371,14 @@ impl SimulationExchange {
 
     /// Get summary of simulation performance
     #[allow(dead_code)]
-    pub fn get_performance_summary(&self) -> SimulationSummary {
-        let balances = self.balances.lock().unwrap();
-        let trades = self.trade_history.lock().unwrap();
+    pub async fn get_performance_summary(&self) -> SimulationSummary {
+        let state = self.state.read().await;
         
-        let usdt_balance = balances.get("USDT").map(|b| b.free).unwrap_or(Decimal::ZERO);
-        let btc_balance = balances.get("BTC").map(|b| b.free).unwrap_or(Decimal::ZERO);
+        let usdt_balance = state.balances.get("USDT").map(|b| b.free).unwrap_or(Decimal::ZERO);
+        let btc_balance = state.balances.get("BTC").map(|b| b.free).unwrap_or(Decimal::ZERO);
         
         // Estimate total value in USDT
-        let prices = self.current_prices.lock().unwrap();
-        let btc_price = prices.get("BTCUSDT").copied().unwrap_or(dec!(42000));
+        let btc_price = Self::last_price(&state, "BTCUSDT");
         let total_value = usdt_balance + (btc_balance * btc_price);
         
         let pnl = total_value - self.config.simulation_initial_balance;
@@ -284,7 +393,7 @@ impl SimulationExchange {
             current_balance: total_value,
             pnl,
             pnl_percent,
-            total_trades: trades.len(),
+            total_trades: state.archived_trades + state.trade_history.len(),
             usdt_balance,
             btc_balance,
         }
@@ -329,3 +438,65 @@ impl std::fmt::Display for SimulationSummary {
         )
     }
 }
+impl Exchange for SimulationExchange {
+    async fn get_price(&self, symbol: &str) -> Result<Decimal> {
+        SimulationExchange::get_price(self, symbol).await
+    }
+
+    async fn get_balance(&self) -> Result<HashMap<String, Balance>> {
+        SimulationExchange::get_balance(self).await
+    }
+
+    async fn place_order(
+        &self,
+        symbol: &str,
+        side: OrderSide,
+        order_type: OrderType,
+        quantity: Decimal,
+        price: Option<Decimal>,
+    ) -> Result<Order> {
+        SimulationExchange::place_order(self, symbol, side, order_type, quantity, price).await
+    }
+
+    async fn place_position_order(
+        &self,
+        position_id: &str,
+        symbol: &str,
+        side: OrderSide,
+        order_type: OrderType,
+        quantity: Decimal,
+        price: Option<Decimal>,
+    ) -> Result<Order> {
+        SimulationExchange::place_position_order(self, position_id, symbol, side, order_type, quantity, price).await
+    }
+
+    async fn get_klines(&self, symbol: &str, interval: &str, limit: u32) -> Result<Vec<Kline>> {
+        SimulationExchange::get_klines(self, symbol, interval, limit).await
+    }
+}
+
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_prices_and_free_balance() {
+        let mut config = Config::defaults();
+        config.symbol = "BTCUSDT".to_string();
+        let sim = SimulationExchange::new(&config).await.unwrap();
+
+        // An unseen symbol starts its own walk; a set price is where the next tick starts from
+        assert!(sim.get_price("SOLUSDT").await.unwrap() > Decimal::ZERO);
+        sim.set_price("SOLUSDT", dec!(150)).await;
+        let tick = sim.get_price("SOLUSDT").await.unwrap();
+        let step = dec!(150) * Decimal::try_from(config.simulation_price_volatility).unwrap();
+        assert!((tick - dec!(150)).abs() <= step);
+
+        assert_eq!(sim.free_balance("USDT").await, config.simulation_initial_balance);
+        assert_eq!(sim.free_balance("BTC").await, Decimal::ZERO);
+        sim.place_order("BTCUSDT", OrderSide::Buy, OrderType::Market, dec!(0.01), None).await.unwrap();
+        assert_eq!(sim.free_balance("BTC").await, dec!(0.01));
+        assert_eq!(sim.free_balance("DOGE").await, Decimal::ZERO);
+    }
+}
@@ -0,0 +1,85 @@
+use crate::ai_advisor::AiTradingTargets;
+use crate::config::Config;
+use crate::error::BotError;
+use crate::portfolio::PortfolioStatus;
+use anyhow::Result;
+use rust_decimal::Decimal;
+use rust_decimal_macros::dec;
+
+/// Guardrails on the AI advisor's portfolio allocation and position size (AI_SIZING)
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct AiSizing {
+    max_position_size_percent: Decimal,
+    max_allocation_percent: Decimal,
+}
+
+impl AiSizing {
+    /// `None` unless AI_SIZING is on: entries keep POSITION_SIZE_PERCENT
+    pub fn from_config(config: &Config) -> Result<Option<Self>> {
+        let percent = |name: &str, value: Decimal| {
+            if value <= Decimal::ZERO || value > dec!(100) {
+                return Err(BotError::config(format!("{} must be above 0 and at most 100, got {}", name, value)));
+            }
+            Ok(value)
+        };
+        let sizing = Self {
+            max_position_size_percent: percent("AI_MAX_POSITION_SIZE_PERCENT", config.ai_max_position_size_percent)?,
+            max_allocation_percent: percent("AI_MAX_ALLOCATION_PERCENT", config.ai_max_allocation_percent)?,
+        };
+        Ok(config.ai_sizing.then_some(sizing))
+    }
+
+    /// Quote amount a main-position entry may use out of `available`. The model's position
+    /// size replaces `configured` (POSITION_SIZE_PERCENT's budget), capped at
+    /// AI_MAX_POSITION_SIZE_PERCENT; the base asset held after the entry stays within its
+    /// allocation, or AI_MAX_ALLOCATION_PERCENT when that is lower or missing.
+    pub fn entry_budget(&self, targets: &AiTradingTargets, configured: Decimal, available: Decimal,
+                        status: &PortfolioStatus) -> Decimal {
+        let budget = targets.position_size_percent
+            .map_or(configured, |size| available * size.min(self.max_position_size_percent) / dec!(100));
+        let total = status.total_portfolio_value;
+        if total <= Decimal::ZERO {
+            return budget;
+        }
+        let allocation = targets.allocation_percent
+            .map_or(self.max_allocation_percent, |share| share.min(self.max_allocation_percent));
+        let held = status.value_in_quote(&status.base_asset, status.owned_balance(&status.base_asset)).unwrap_or_default();
+        budget.min((total * allocation / dec!(100) - held).max(Decimal::ZERO))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ai_advisor::{FallbackTargetCalculator, MarketContext};
+
+    #[test]
+    fn test_ai_size_stays_within_guardrails() {
+        let mut config = Config::defaults();
+        config.ai_sizing = true;
+        config.ai_max_position_size_percent = dec!(25);
+        config.ai_max_allocation_percent = dec!(50);
+        let sizing = AiSizing::from_config(&config).unwrap().unwrap();
+        config.ai_max_allocation_percent = dec!(120);
+        assert!(AiSizing::from_config(&config).unwrap_err().to_string().contains("AI_MAX_ALLOCATION_PERCENT"));
+
+        let mut status = PortfolioStatus::new("BTCUSDT", true);
+        status.current_price = dec!(100);
+        status.balances.insert("USDT".to_string(), dec!(800));
+        status.balances.insert("BTC".to_string(), dec!(2));
+        status.update_total_value();
+        let context = MarketContext { symbol: "BTCUSDT".to_string(), current_price: dec!(100), ..Default::default() };
+        let mut targets = FallbackTargetCalculator::calculate_targets(&context);
+
+        // Nothing suggested: the configured budget, within the 50% allocation cap
+        assert_eq!(sizing.entry_budget(&targets, dec!(80), dec!(800), &status), dec!(80));
+        // 40% asked, 25% allowed
+        targets.position_size_percent = Some(dec!(40));
+        assert_eq!(sizing.entry_budget(&targets, dec!(80), dec!(800), &status), dec!(200));
+        // 30% allocation of 1000 with 200 already held leaves room for 100
+        targets.allocation_percent = Some(dec!(30));
+        assert_eq!(sizing.entry_budget(&targets, dec!(80), dec!(800), &status), dec!(100));
+        targets.allocation_percent = Some(dec!(10));
+        assert_eq!(sizing.entry_budget(&targets, dec!(80), dec!(800), &status), Decimal::ZERO);
+    }
+}
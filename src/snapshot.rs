@@ -0,0 +1,172 @@
+use crate::audit::AuditLog;
+use crate::config::Config;
+use crate::error::BotError;
+use crate::paths::{write_atomic, DataPaths};
+use crate::state_store::StateBackend;
+use anyhow::{anyhow, Result};
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::Path;
+use tracing::{info, warn};
+
+const SNAPSHOT_VERSION: u32 = 1;
+const BACKUP_SUFFIX: &str = ".pre-restore";
+
+/// Everything needed to resume the bot on another machine: every document in the state
+/// backend (trade limiter, position, targets, equity history) and the audit log, in one
+/// file. State is stored byte-for-byte, so encrypted state stays encrypted; the audit log
+/// is read through the state key and every entry stored encrypted when one is set.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct Snapshot {
+    pub version: u32,
+    pub created_at: DateTime<Utc>,
+    pub bot_version: String,
+    pub symbol: String,
+    pub config_hash: String,
+    /// Path relative to DATA_DIR -> hex-encoded contents
+    pub files: BTreeMap<String, String>,
+}
+
+/// Fingerprint of the settings that change how saved state is interpreted.
+/// Secrets, paths and timeouts are left out so a move between machines keeps the hash.
+pub fn config_hash(config: &Config) -> String {
+    let canonical = format!(
+        "exchange={}\nsymbol={}\nquote={}\nsimulation={}\nstop_loss={}\ntake_profit={}\n\
+         trade_day_timezone={}\ntrade_limit_window={}\ntrade_limit_mode={}\nlive_trading={}\n",
+        config.exchange,
+        config.symbol,
+        config.quote_asset.as_deref().unwrap_or(""),
+        config.is_simulation(),
+        config.stop_loss_percent.normalize(),
+        config.take_profit_percent.normalize(),
+        config.trade_day_timezone,
+        config.trade_limit_window.to_lowercase(),
+        config.trade_limit_mode.to_lowercase(),
+        config.live_trading_enabled,
+    );
+    hex::encode(&Sha256::digest(canonical.as_bytes())[..8])
+}
+
+impl Snapshot {
+    /// Capture the current state documents and audit log
+    pub fn capture(config: &Config, backend: &dyn StateBackend, audit: &AuditLog) -> Result<Self> {
+        let mut files = BTreeMap::new();
+        // Backups from earlier restores stay behind
+        for key in backend.keys()?.into_iter().filter(|k| !k.ends_with(BACKUP_SUFFIX)) {
+            if let Some(data) = backend.load(&key)? {
+                files.insert(format!("state/{}.json", key), hex::encode(data));
+            }
+        }
+        let log = audit.export()?;
+        if !log.is_empty() {
+            files.insert("logs/audit.jsonl".to_string(), hex::encode(log));
+        }
+
+        Ok(Self {
+            version: SNAPSHOT_VERSION,
+            created_at: Utc::now(),
+            bot_version: env!("CARGO_PKG_VERSION").to_string(),
+            symbol: config.symbol.clone(),
+            config_hash: config_hash(config),
+            files,
+        })
+    }
+
+    pub fn save(&self, path: &Path) -> Result<()> {
+        write_atomic(path, serde_json::to_vec_pretty(self)?).map_err(|e| BotError::storage(path, e))?;
+        Ok(())
+    }
+
+    pub fn load(path: &Path) -> Result<Self> {
+        let data = fs::read(path).map_err(|e| BotError::storage(path, e))?;
+        let snapshot: Self = serde_json::from_slice(&data)?;
+        if snapshot.version > SNAPSHOT_VERSION {
+            return Err(anyhow!("Snapshot format v{} is newer than this bot supports (v{})",
+                snapshot.version, SNAPSHOT_VERSION));
+        }
+        Ok(snapshot)
+    }
+
+    /// Write the captured state into the backend and the audit log into DATA_DIR. Refuses
+    /// when the snapshot was taken with different trading settings unless `force` is set.
+    /// Replaced documents are kept as `<key>.pre-restore`. Returns the restored entries.
+    pub fn restore(&self, paths: &DataPaths, config: &Config, backend: &dyn StateBackend, force: bool)
+        -> Result<Vec<String>> {
+        let current_hash = config_hash(config);
+        if self.config_hash != current_hash {
+            let message = format!(
+                "Snapshot config hash {} ({}) differs from the current config {} ({})",
+                self.config_hash, self.symbol, current_hash, config.symbol
+            );
+            if !force {
+                return Err(BotError::config(format!("{} - rerun with --force to restore anyway", message)).into());
+            }
+            warn!("⚠️ {}", message);
+        }
+
+        let mut restored = Vec::new();
+        for (entry, contents) in &self.files {
+            let bytes = hex::decode(contents).map_err(|e| anyhow!("Corrupt entry '{}': {}", entry, e))?;
+
+            if let Some(key) = entry.strip_prefix("state/").and_then(|k| k.strip_suffix(".json"))
+                && !key.is_empty() && !key.contains(['/', '\\']) && !key.starts_with('.') {
+                if let Some(previous) = backend.load(key)? {
+                    backend.save(&format!("{}{}", key, BACKUP_SUFFIX), &previous)?;
+                }
+                backend.save(key, &bytes)?;
+            } else if entry == "logs/audit.jsonl" {
+                let target = paths.audit_log_file();
+                if target.exists() {
+                    let mut backup = target.clone().into_os_string();
+                    backup.push(BACKUP_SUFFIX);
+                    fs::copy(&target, &backup).map_err(|e| BotError::storage(&target, e))?;
+                }
+                write_atomic(&target, bytes).map_err(|e| BotError::storage(&target, e))?;
+            } else {
+                return Err(anyhow!("Refusing to restore unexpected entry '{}'", entry));
+            }
+            info!("📥 Restored {}", entry);
+            restored.push(entry.clone());
+        }
+        Ok(restored)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::state_store::FileBackend;
+
+    #[test]
+    fn test_snapshot_round_trip_between_data_dirs() {
+        let base = std::env::temp_dir().join(format!("snapshot_test_{}", std::process::id()));
+        let mut config = Config::defaults();
+        config.data_dir = base.join("a").to_string_lossy().into_owned();
+        config.report_path = None;
+        let source = DataPaths::from_config(&config).unwrap();
+        fs::write(source.trade_state_file(), r#"{"date":"2024-01-01"}"#).unwrap();
+        fs::write(source.runtime_state_file(), r#"{"in_position":true}"#).unwrap();
+
+        let snapshot = Snapshot::capture(&config, &FileBackend::new(&source.state_dir()), &AuditLog::new(&source.audit_log_file())).unwrap();
+        let archive = base.join("bot.snapshot");
+        snapshot.save(&archive).unwrap();
+
+        config.data_dir = base.join("b").to_string_lossy().into_owned();
+        let target = DataPaths::from_config(&config).unwrap();
+        let backend = FileBackend::new(&target.state_dir());
+        let restored = Snapshot::load(&archive).unwrap().restore(&target, &config, &backend, false).unwrap();
+        assert_eq!(restored, vec!["state/runtime_state.json", "state/trade_state.json"]);
+        assert_eq!(fs::read_to_string(target.runtime_state_file()).unwrap(), r#"{"in_position":true}"#);
+
+        // Different trading settings need --force
+        config.stop_loss_percent += rust_decimal::Decimal::ONE;
+        assert!(snapshot.restore(&target, &config, &backend, false).is_err());
+        assert!(snapshot.restore(&target, &config, &backend, true).is_ok());
+        assert!(target.state_dir().join("runtime_state.pre-restore.json").exists());
+
+        fs::remove_dir_all(&base).ok();
+    }
+}
@@ -0,0 +1,150 @@
+use crate::config::Config;
+use crate::error::BotError;
+use anyhow::{anyhow, Result};
+use chacha20poly1305::aead::{Aead, KeyInit};
+use chacha20poly1305::{Key, XChaCha20Poly1305, XNonce};
+use rand::RngCore;
+use sha2::{Digest, Sha256};
+use std::collections::HashMap;
+use std::fs;
+use std::sync::{Arc, Mutex};
+
+/// Header identifying an encrypted state file (format version 1)
+const MAGIC: &[u8; 6] = b"CTBEN1";
+const SALT_LEN: usize = 16;
+const NONCE_LEN: usize = 24;
+/// PBKDF2-HMAC-SHA256 rounds for passphrase-derived keys
+const PBKDF2_ROUNDS: u32 = 600_000;
+
+#[derive(Clone)]
+enum KeySource {
+    Passphrase(Vec<u8>),
+    KeyFile(Vec<u8>),
+}
+
+/// Encrypts state files at rest with XChaCha20-Poly1305.
+///
+/// File layout: `MAGIC | salt (16) | nonce (24) | ciphertext+tag`. The key is derived
+/// from a passphrase (PBKDF2) or a keyfile (SHA-256) combined with the per-file salt.
+/// The derived key for this process's own salt is cached, so saves stay cheap, and so are
+/// the keys of other salts once derived (an audit log holds one salt per run that wrote it).
+#[derive(Clone)]
+pub struct StateCipher {
+    source: KeySource,
+    salt: [u8; SALT_LEN],
+    key: [u8; 32],
+    other_keys: Arc<Mutex<HashMap<Vec<u8>, [u8; 32]>>>,
+}
+
+impl StateCipher {
+    /// Build the cipher from `STATE_KEY_FILE` or `STATE_PASSPHRASE`; None if neither is set
+    pub fn from_config(config: &Config) -> Result<Option<Self>> {
+        if let Some(path) = &config.state_key_file {
+            let bytes = fs::read(path).map_err(|e| BotError::storage(path, e))?;
+            if bytes.len() < 16 {
+                return Err(BotError::config("STATE_KEY_FILE must contain at least 16 bytes").into());
+            }
+            return Ok(Some(Self::new(KeySource::KeyFile(bytes))));
+        }
+
+        if let Some(passphrase) = &config.state_passphrase {
+            return Ok(Some(Self::from_passphrase(passphrase.expose())));
+        }
+
+        Ok(None)
+    }
+
+    pub fn from_passphrase(passphrase: &str) -> Self {
+        Self::new(KeySource::Passphrase(passphrase.as_bytes().to_vec()))
+    }
+
+    #[cfg(test)]
+    pub(crate) fn from_key_bytes(bytes: &[u8]) -> Self {
+        Self::new(KeySource::KeyFile(bytes.to_vec()))
+    }
+
+    fn new(source: KeySource) -> Self {
+        let mut salt = [0u8; SALT_LEN];
+        rand::thread_rng().fill_bytes(&mut salt);
+        let key = Self::derive_key(&source, &salt);
+        Self { source, salt, key, other_keys: Arc::default() }
+    }
+
+    fn derive_key(source: &KeySource, salt: &[u8]) -> [u8; 32] {
+        match source {
+            KeySource::Passphrase(passphrase) => {
+                pbkdf2::pbkdf2_hmac_array::<Sha256, 32>(passphrase, salt, PBKDF2_ROUNDS)
+            }
+            KeySource::KeyFile(bytes) => {
+                let mut hasher = Sha256::new();
+                hasher.update(salt);
+                hasher.update(bytes);
+                hasher.finalize().into()
+            }
+        }
+    }
+
+    /// Whether the data starts with the encrypted-file header
+    pub fn is_encrypted(data: &[u8]) -> bool {
+        data.starts_with(MAGIC)
+    }
+
+    pub fn encrypt(&self, plaintext: &[u8]) -> Result<Vec<u8>> {
+        let mut nonce = [0u8; NONCE_LEN];
+        rand::thread_rng().fill_bytes(&mut nonce);
+
+        let cipher = XChaCha20Poly1305::new(Key::from_slice(&self.key));
+        let ciphertext = cipher
+            .encrypt(XNonce::from_slice(&nonce), plaintext)
+            .map_err(|_| anyhow!("State encryption failed"))?;
+
+        let mut out = Vec::with_capacity(MAGIC.len() + SALT_LEN + NONCE_LEN + ciphertext.len());
+        out.extend_from_slice(MAGIC);
+        out.extend_from_slice(&self.salt);
+        out.extend_from_slice(&nonce);
+        out.extend_from_slice(&ciphertext);
+        Ok(out)
+    }
+
+    pub fn decrypt(&self, data: &[u8]) -> Result<Vec<u8>> {
+        if !Self::is_encrypted(data) || data.len() < MAGIC.len() + SALT_LEN + NONCE_LEN {
+            return Err(anyhow!("Not an encrypted state file"));
+        }
+
+        let salt = &data[MAGIC.len()..MAGIC.len() + SALT_LEN];
+        let nonce = &data[MAGIC.len() + SALT_LEN..MAGIC.len() + SALT_LEN + NONCE_LEN];
+        let ciphertext = &data[MAGIC.len() + SALT_LEN + NONCE_LEN..];
+
+        // Files written by a previous run carry a different salt
+        let key = if salt == self.salt {
+            self.key
+        } else {
+            let mut keys = self.other_keys.lock().unwrap();
+            *keys.entry(salt.to_vec()).or_insert_with(|| Self::derive_key(&self.source, salt))
+        };
+
+        XChaCha20Poly1305::new(Key::from_slice(&key))
+            .decrypt(XNonce::from_slice(nonce), ciphertext)
+            .map_err(|_| anyhow!("State decryption failed (wrong passphrase/keyfile or corrupted file)"))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_round_trip() {
+        let cipher = StateCipher::from_key_bytes(b"0123456789abcdef0123456789abcdef");
+        let encrypted = cipher.encrypt(b"{\"date\":\"2024-01-01\"}").unwrap();
+        assert!(StateCipher::is_encrypted(&encrypted));
+        assert_eq!(cipher.decrypt(&encrypted).unwrap(), b"{\"date\":\"2024-01-01\"}");
+
+        // A new process with the same keyfile (different salt) can still read it
+        let other = StateCipher::from_key_bytes(b"0123456789abcdef0123456789abcdef");
+        assert!(other.decrypt(&encrypted).is_ok());
+
+        let wrong = StateCipher::from_key_bytes(b"fedcba9876543210fedcba9876543210");
+        assert!(wrong.decrypt(&encrypted).is_err());
+    }
+}
@@ -0,0 +1,456 @@
+use crate::config::Config;
+use crate::error::BotError;
+use crate::paths::{write_atomic, DataPaths};
+use crate::state_crypto::StateCipher;
+use crate::state_version::Schema;
+use anyhow::{anyhow, Result};
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use sha2::{Digest, Sha256};
+use std::fs;
+use std::io::{Read, Seek, Write};
+use std::path::{Path, PathBuf};
+use std::sync::mpsc;
+use std::sync::Arc;
+use std::time::Duration;
+use tracing::{error, info};
+
+pub const TRADE_STATE_KEY: &str = "trade_state";
+pub const RUNTIME_STATE_KEY: &str = "runtime_state";
+pub const STRATEGY_BOOK_KEY: &str = "strategy_book";
+pub const HALL_OF_FAME_KEY: &str = "hall_of_fame";
+pub const HIGH_WATER_MARK_KEY: &str = "high_water_mark";
+
+/// A Redis lock expires this long after its holder stops renewing it
+const REDIS_LOCK_TTL: Duration = Duration::from_secs(30);
+
+/// Key/value storage for persisted state documents (`trade_state`, `runtime_state`).
+///
+/// The methods are synchronous because the trade limiter saves from synchronous code;
+/// the Postgres backend bridges to async with `block_in_place`, so it needs the
+/// multi-threaded runtime the binary runs on.
+pub trait StateBackend: Send + Sync {
+    fn load(&self, key: &str) -> Result<Option<Vec<u8>>>;
+    fn save(&self, key: &str, data: &[u8]) -> Result<()>;
+    /// All stored keys, for snapshots
+    fn keys(&self) -> Result<Vec<String>>;
+    /// Where the state lives, for logs
+    fn describe(&self) -> String;
+    /// Take the exclusive lock `name` for as long as the returned guard lives, or fail with
+    /// [`BotError::InstanceLocked`] if another process holds it
+    fn lock(&self, name: &str) -> Result<InstanceLock>;
+}
+
+/// Held for the lifetime of the trading loop; dropping it releases the lock
+pub struct InstanceLock {
+    holder: String,
+    _guard: Box<dyn Send>,
+}
+
+impl InstanceLock {
+    /// `host:pid` of this process
+    pub fn holder(&self) -> &str {
+        &self.holder
+    }
+}
+
+/// One lock per exchange account, so instances with different DATA_DIRs or namespaces
+/// still can't trade the same account at once
+pub fn account_lock_name(config: &Config) -> String {
+    let digest = Sha256::digest(format!("{}:{}", config.exchange, config.api_key.expose()).as_bytes());
+    format!("instance-{}-{}", config.exchange.to_lowercase(), hex::encode(&digest[..6]))
+}
+
+fn instance_id() -> String {
+    let host = std::env::var("HOSTNAME").ok()
+        .or_else(|| fs::read_to_string("/etc/hostname").ok())
+        .map(|h| h.trim().to_string())
+        .filter(|h| !h.is_empty())
+        .unwrap_or_else(|| "unknown".to_string());
+    format!("{}:{}", host, std::process::id())
+}
+
+/// Build the backend selected by `STATE_BACKEND` (`file`, `redis` or `postgres`)
+pub async fn backend_from_config(config: &Config, paths: &DataPaths) -> Result<Arc<dyn StateBackend>> {
+    let url = || {
+        config.state_url.as_ref().filter(|u| !u.is_empty()).ok_or_else(|| {
+            BotError::config(format!("STATE_BACKEND={} requires STATE_URL", config.state_backend))
+        })
+    };
+    let backend: Arc<dyn StateBackend> = match config.state_backend.to_lowercase().as_str() {
+        "file" => Arc::new(FileBackend::new(&paths.state_dir())),
+        "redis" => Arc::new(RedisBackend::connect(url()?.expose(), &config.state_namespace)?),
+        "postgres" | "postgresql" => Arc::new(PostgresBackend::connect(url()?.expose(), &config.state_namespace).await?),
+        other => return Err(BotError::config(format!(
+            "Invalid STATE_BACKEND '{}' (expected file, redis or postgres)", other)).into()),
+    };
+    info!("🗄️ State backend: {}", backend.describe());
+    Ok(backend)
+}
+
+/// One state document in a backend, encrypted when a state key is configured
+#[derive(Clone)]
+pub struct StateStore {
+    backend: Arc<dyn StateBackend>,
+    key: String,
+    cipher: Option<StateCipher>,
+}
+
+impl StateStore {
+    pub fn new(backend: Arc<dyn StateBackend>, key: &str, cipher: Option<StateCipher>) -> Self {
+        Self { backend, key: key.to_string(), cipher }
+    }
+
+    /// A single local file (the key is the file stem)
+    pub fn file(path: &Path, cipher: Option<StateCipher>) -> Self {
+        let dir = path.parent().filter(|p| !p.as_os_str().is_empty()).unwrap_or(Path::new("."));
+        let key = path.file_stem().map(|s| s.to_string_lossy().into_owned()).unwrap_or_default();
+        Self::new(Arc::new(FileBackend::new(dir)), &key, cipher)
+    }
+
+    /// Another document in the same backend named after this one, e.g. `trade_state_history`
+    pub fn companion(&self, suffix: &str) -> Self {
+        Self::new(self.backend.clone(), &format!("{}_{}", self.key, suffix), self.cipher.clone())
+    }
+
+    /// The decrypted document, or `None` if nothing has been saved yet
+    pub fn read(&self) -> Result<Option<String>> {
+        let Some(data) = self.backend.load(&self.key)? else {
+            return Ok(None);
+        };
+        if StateCipher::is_encrypted(&data) {
+            let cipher = self.cipher.as_ref().ok_or_else(|| anyhow!(
+                "State '{}' is encrypted but no STATE_PASSPHRASE/STATE_KEY_FILE is configured", self.key))?;
+            return Ok(Some(String::from_utf8(cipher.decrypt(&data)?)?));
+        }
+        Ok(Some(String::from_utf8(data)?))
+    }
+
+    pub fn write(&self, contents: &str) -> Result<()> {
+        match &self.cipher {
+            Some(cipher) => self.backend.save(&self.key, &cipher.encrypt(contents.as_bytes())?),
+            None => self.backend.save(&self.key, contents.as_bytes()),
+        }
+    }
+
+    /// The document parsed with `schema`, migrated from an older version first. One that
+    /// can't be parsed is copied to `<key>_unreadable` before the error is returned, so a
+    /// caller starting over never loses it.
+    pub fn load<T: DeserializeOwned>(&self, schema: &Schema) -> Result<Option<T>> {
+        let Some(content) = self.read()? else {
+            return Ok(None);
+        };
+        schema.parse(&content).map(Some).map_err(|e| {
+            let copy = self.companion("unreadable");
+            match copy.write(&content) {
+                Ok(()) => anyhow!("{:#} - kept a copy as {}", e, copy.key),
+                Err(write_error) => anyhow!("{:#} - and keeping a copy failed: {}", e, write_error),
+            }
+        })
+    }
+
+    /// Write `state` stamped with `schema`'s version
+    pub fn save<T: Serialize>(&self, schema: &Schema, state: &T) -> Result<()> {
+        self.write(&schema.to_json(state)?)
+    }
+}
+
+/// `<dir>/<key>.json`, written atomically
+pub struct FileBackend {
+    dir: PathBuf,
+}
+
+impl FileBackend {
+    pub fn new(dir: &Path) -> Self {
+        Self { dir: dir.to_path_buf() }
+    }
+
+    fn path(&self, key: &str) -> PathBuf {
+        self.dir.join(format!("{}.json", key))
+    }
+}
+
+impl StateBackend for FileBackend {
+    fn load(&self, key: &str) -> Result<Option<Vec<u8>>> {
+        let path = self.path(key);
+        match fs::read(&path) {
+            Ok(data) => Ok(Some(data)),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => Ok(None),
+            Err(e) => Err(BotError::storage(path, e).into()),
+        }
+    }
+
+    fn save(&self, key: &str, data: &[u8]) -> Result<()> {
+        let path = self.path(key);
+        write_atomic(&path, data).map_err(|e| BotError::storage(path, e))?;
+        Ok(())
+    }
+
+    fn keys(&self) -> Result<Vec<String>> {
+        let mut keys = Vec::new();
+        for entry in fs::read_dir(&self.dir).map_err(|e| BotError::storage(&self.dir, e))? {
+            let path = entry?.path();
+            // Hidden files are in-flight atomic writes
+            if let Some(name) = path.file_name().and_then(|n| n.to_str())
+                && !name.starts_with('.')
+                && let Some(key) = name.strip_suffix(".json") {
+                keys.push(key.to_string());
+            }
+        }
+        keys.sort();
+        Ok(keys)
+    }
+
+    fn describe(&self) -> String {
+        format!("files in {}", self.dir.display())
+    }
+
+    /// An OS file lock on `<dir>/.<name>.lock`, released by the kernel if the process dies
+    fn lock(&self, name: &str) -> Result<InstanceLock> {
+        let path = self.dir.join(format!(".{}.lock", name));
+        let mut file = fs::OpenOptions::new().read(true).write(true).create(true).truncate(false).open(&path)
+            .map_err(|e| BotError::storage(&path, e))?;
+        if file.try_lock().is_err() {
+            let mut holder = String::new();
+            file.read_to_string(&mut holder).ok();
+            let holder = if holder.trim().is_empty() { path.display().to_string() } else { holder.trim().to_string() };
+            return Err(BotError::InstanceLocked { holder }.into());
+        }
+        let holder = instance_id();
+        file.set_len(0)
+            .and_then(|_| file.rewind())
+            .and_then(|_| file.write_all(holder.as_bytes()))
+            .map_err(|e| BotError::storage(&path, e))?;
+        Ok(InstanceLock { holder, _guard: Box::new(file) })
+    }
+}
+
+/// Keys `crypto_trading_bot:<namespace>:<key>` in Redis
+pub struct RedisBackend {
+    client: redis::Client,
+    prefix: String,
+}
+
+impl RedisBackend {
+    pub fn connect(url: &str, namespace: &str) -> Result<Self> {
+        let client = redis::Client::open(url)?;
+        // Fail at startup rather than on the first save
+        redis::cmd("PING").query::<String>(&mut client.get_connection()?)?;
+        Ok(Self { client, prefix: format!("crypto_trading_bot:{}:", namespace) })
+    }
+
+    fn connection(&self) -> Result<redis::Connection> {
+        self.client.get_connection().map_err(|e| storage_unavailable("Redis", e))
+    }
+}
+
+impl StateBackend for RedisBackend {
+    fn load(&self, key: &str) -> Result<Option<Vec<u8>>> {
+        let value: Option<Vec<u8>> = redis::cmd("GET")
+            .arg(format!("{}{}", self.prefix, key))
+            .query(&mut self.connection()?)
+            .map_err(|e| storage_unavailable("Redis", e))?;
+        Ok(value)
+    }
+
+    fn save(&self, key: &str, data: &[u8]) -> Result<()> {
+        redis::cmd("SET")
+            .arg(format!("{}{}", self.prefix, key))
+            .arg(data)
+            .query::<()>(&mut self.connection()?)
+            .map_err(|e| storage_unavailable("Redis", e))?;
+        Ok(())
+    }
+
+    fn keys(&self) -> Result<Vec<String>> {
+        let mut conn = self.connection()?;
+        let mut keys: Vec<String> = redis::cmd("SCAN")
+            .cursor_arg(0)
+            .arg("MATCH")
+            .arg(format!("{}*", self.prefix))
+            .clone()
+            .iter::<String>(&mut conn)
+            .map_err(|e| storage_unavailable("Redis", e))?
+            .filter_map(|k| k.strip_prefix(&self.prefix).map(str::to_string))
+            .collect();
+        keys.sort();
+        Ok(keys)
+    }
+
+    fn describe(&self) -> String {
+        format!("Redis keys {}*", self.prefix)
+    }
+
+    /// `SET NX` with a TTL, renewed by a background thread. If the process dies the
+    /// key expires after [`REDIS_LOCK_TTL`] and a standby instance can take over.
+    fn lock(&self, name: &str) -> Result<InstanceLock> {
+        let key = format!("{}{}", self.prefix, name);
+        let holder = instance_id();
+        let ttl_ms = REDIS_LOCK_TTL.as_millis() as u64;
+        let acquired: Option<String> = redis::cmd("SET").arg(&key).arg(&holder).arg("NX").arg("PX").arg(ttl_ms)
+            .query(&mut self.connection()?)
+            .map_err(|e| storage_unavailable("Redis", e))?;
+        if acquired.is_none() {
+            let current: Option<String> = redis::cmd("GET").arg(&key).query(&mut self.connection()?).ok().flatten();
+            return Err(BotError::InstanceLocked { holder: current.unwrap_or(key) }.into());
+        }
+
+        // Dropping the sender stops the renewals and releases the key
+        let (stop, stopped) = mpsc::channel::<()>();
+        let client = self.client.clone();
+        let owner = holder.clone();
+        std::thread::spawn(move || {
+            // Only touch the key while it still belongs to this instance
+            let renew = "if redis.call('GET', KEYS[1]) == ARGV[1] then return redis.call('PEXPIRE', KEYS[1], ARGV[2]) else return 0 end";
+            let release = "if redis.call('GET', KEYS[1]) == ARGV[1] then return redis.call('DEL', KEYS[1]) else return 0 end";
+            while let Err(mpsc::RecvTimeoutError::Timeout) = stopped.recv_timeout(REDIS_LOCK_TTL / 3) {
+                let renewed: redis::RedisResult<i64> = client.get_connection().and_then(|mut conn| {
+                    redis::cmd("EVAL").arg(renew).arg(1).arg(&key).arg(&owner).arg(ttl_ms).query(&mut conn)
+                });
+                match renewed {
+                    Ok(1) => {}
+                    Ok(_) => error!("🚨 Instance lock {} was taken over by another instance", key),
+                    Err(e) => error!("🚨 Failed to renew instance lock {}: {}", key, e),
+                }
+            }
+            if let Ok(mut conn) = client.get_connection() {
+                let _: redis::RedisResult<i64> = redis::cmd("EVAL").arg(release).arg(1).arg(&key).arg(&owner).query(&mut conn);
+            }
+        });
+        Ok(InstanceLock { holder, _guard: Box::new(stop) })
+    }
+}
+
+/// Rows of a `bot_state (namespace, key, value)` table, created on first connect
+pub struct PostgresBackend {
+    pool: sqlx::PgPool,
+    namespace: String,
+}
+
+impl PostgresBackend {
+    pub async fn connect(url: &str, namespace: &str) -> Result<Self> {
+        let pool = sqlx::postgres::PgPoolOptions::new().max_connections(2).connect(url).await
+            .map_err(|e| storage_unavailable("Postgres", e))?;
+        sqlx::query(
+            "CREATE TABLE IF NOT EXISTS bot_state (
+                namespace TEXT NOT NULL,
+                key TEXT NOT NULL,
+                value BYTEA NOT NULL,
+                updated_at TIMESTAMPTZ NOT NULL DEFAULT now(),
+                PRIMARY KEY (namespace, key)
+            )",
+        )
+        .execute(&pool)
+        .await?;
+        Ok(Self { pool, namespace: namespace.to_string() })
+    }
+}
+
+impl StateBackend for PostgresBackend {
+    fn load(&self, key: &str) -> Result<Option<Vec<u8>>> {
+        let row: Option<(Vec<u8>,)> = block_on(
+            sqlx::query_as("SELECT value FROM bot_state WHERE namespace = $1 AND key = $2")
+                .bind(&self.namespace)
+                .bind(key)
+                .fetch_optional(&self.pool),
+        )
+        .map_err(|e| storage_unavailable("Postgres", e))?;
+        Ok(row.map(|(value,)| value))
+    }
+
+    fn save(&self, key: &str, data: &[u8]) -> Result<()> {
+        block_on(
+            sqlx::query(
+                "INSERT INTO bot_state (namespace, key, value, updated_at) VALUES ($1, $2, $3, now())
+                 ON CONFLICT (namespace, key) DO UPDATE SET value = EXCLUDED.value, updated_at = now()",
+            )
+            .bind(&self.namespace)
+            .bind(key)
+            .bind(data)
+            .execute(&self.pool),
+        )
+        .map_err(|e| storage_unavailable("Postgres", e))?;
+        Ok(())
+    }
+
+    fn keys(&self) -> Result<Vec<String>> {
+        let rows: Vec<(String,)> = block_on(
+            sqlx::query_as("SELECT key FROM bot_state WHERE namespace = $1 ORDER BY key")
+                .bind(&self.namespace)
+                .fetch_all(&self.pool),
+        )
+        .map_err(|e| storage_unavailable("Postgres", e))?;
+        Ok(rows.into_iter().map(|(key,)| key).collect())
+    }
+
+    fn describe(&self) -> String {
+        format!("Postgres table bot_state (namespace '{}')", self.namespace)
+    }
+
+    /// A session-level advisory lock on a dedicated connection; Postgres releases it when
+    /// the connection closes, including when the process dies
+    fn lock(&self, name: &str) -> Result<InstanceLock> {
+        let digest = Sha256::digest(name.as_bytes());
+        let lock_id = i64::from_be_bytes(digest[..8].try_into()?);
+        let (acquired, conn) = block_on(async {
+            let mut conn = self.pool.acquire().await?.detach();
+            let (acquired,): (bool,) = sqlx::query_as("SELECT pg_try_advisory_lock($1)")
+                .bind(lock_id)
+                .fetch_one(&mut conn)
+                .await?;
+            Ok::<_, sqlx::Error>((acquired, conn))
+        })
+        .map_err(|e| storage_unavailable("Postgres", e))?;
+        if !acquired {
+            return Err(BotError::InstanceLocked { holder: format!("Postgres advisory lock {} for {}", lock_id, name) }.into());
+        }
+        Ok(InstanceLock { holder: instance_id(), _guard: Box::new(conn) })
+    }
+}
+
+fn block_on<F: std::future::Future>(future: F) -> F::Output {
+    tokio::task::block_in_place(|| tokio::runtime::Handle::current().block_on(future))
+}
+
+fn storage_unavailable(store: &'static str, error: impl std::fmt::Display) -> anyhow::Error {
+    BotError::DataSource {
+        provider: store,
+        kind: crate::error::ErrorKind::Unavailable,
+        message: error.to_string(),
+    }
+    .into()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_file_store_round_trip_with_encryption() {
+        let dir = std::env::temp_dir().join(format!("state_store_test_{}", std::process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        let backend: Arc<dyn StateBackend> = Arc::new(FileBackend::new(&dir));
+        let cipher = StateCipher::from_passphrase("correct horse");
+
+        let store = StateStore::new(backend.clone(), "runtime_state", Some(cipher));
+        assert_eq!(store.read().unwrap(), None);
+        store.write(r#"{"in_position":true}"#).unwrap();
+        assert_eq!(store.read().unwrap().as_deref(), Some(r#"{"in_position":true}"#));
+
+        // Stored encrypted, and readable through the single-file constructor
+        assert!(StateCipher::is_encrypted(&backend.load("runtime_state").unwrap().unwrap()));
+        let by_path = StateStore::file(&dir.join("runtime_state.json"), None);
+        assert!(by_path.read().is_err());
+        assert_eq!(backend.keys().unwrap(), vec!["runtime_state"]);
+
+        // Only one holder of the instance lock at a time
+        let lock = backend.lock("instance-test").unwrap();
+        let err = backend.lock("instance-test").err().unwrap();
+        assert!(matches!(BotError::find(&err), Some(BotError::InstanceLocked { holder }) if holder == lock.holder()));
+        drop(lock);
+        assert!(backend.lock("instance-test").is_ok());
+        assert_eq!(backend.keys().unwrap(), vec!["runtime_state"]);
+
+        fs::remove_dir_all(&dir).ok();
+    }
+}
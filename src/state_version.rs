@@ -0,0 +1,109 @@
+use anyhow::{anyhow, Context, Result};
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use serde_json::{Map, Value};
+use tracing::info;
+
+/// Field every versioned document carries its format version in
+const VERSION_FIELD: &str = "version";
+
+/// Rewrites a document of one version into the next, in place
+pub type Migration = fn(&mut Map<String, Value>) -> Result<()>;
+
+/// Format history of one persisted document. `migrations[i]` upgrades version `i + 1` to
+/// `i + 2`, so the current version is one past the last migration; documents saved before
+/// versioning have no version field and count as version 1. A format change that old
+/// files can't be parsed into adds a migration rather than dropping the state.
+pub struct Schema {
+    name: &'static str,
+    migrations: &'static [Migration],
+}
+
+impl Schema {
+    pub const fn new(name: &'static str, migrations: &'static [Migration]) -> Self {
+        Self { name, migrations }
+    }
+
+    pub fn name(&self) -> &'static str {
+        self.name
+    }
+
+    /// The version documents are written with
+    pub fn version(&self) -> u64 {
+        self.migrations.len() as u64 + 1
+    }
+
+    /// Parse `content`, migrating it from an older version first
+    pub fn parse<T: DeserializeOwned>(&self, content: &str) -> Result<T> {
+        let mut value: Value = serde_json::from_str(content).with_context(|| format!("{} is not valid JSON", self.name))?;
+        let document = value.as_object_mut().ok_or_else(|| anyhow!("{} is not a JSON object", self.name))?;
+        let version = match document.remove(VERSION_FIELD) {
+            None => 1,
+            Some(version) => version.as_u64().filter(|v| *v >= 1)
+                .ok_or_else(|| anyhow!("{} has an invalid version {}", self.name, version))?,
+        };
+        if version > self.version() {
+            return Err(anyhow!("{} is version {}, newer than the {} this build reads - written by a newer release?",
+                self.name, version, self.version()));
+        }
+        for (from, migrate) in self.migrations.iter().enumerate().skip(version as usize - 1) {
+            migrate(document).with_context(|| format!("migrating {} from version {}", self.name, from + 1))?;
+            info!("🔧 Migrated {} from version {} to {}", self.name, from + 1, from + 2);
+        }
+        serde_json::from_value(value).with_context(|| format!("{} doesn't match version {}", self.name, self.version()))
+    }
+
+    /// `state` as pretty JSON, stamped with the current version
+    pub fn to_json<T: Serialize>(&self, state: &T) -> Result<String> {
+        let mut value = serde_json::to_value(state)?;
+        value.as_object_mut()
+            .ok_or_else(|| anyhow!("{} is not a JSON object", self.name))?
+            .insert(VERSION_FIELD.to_string(), self.version().into());
+        Ok(serde_json::to_string_pretty(&value)?)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde::Deserialize;
+
+    #[derive(Debug, PartialEq, Serialize, Deserialize)]
+    struct Limits {
+        trades: Vec<String>,
+        max_trades: u32,
+    }
+
+    /// v1 counted trades, v2 lists them
+    fn list_trades(document: &mut Map<String, Value>) -> Result<()> {
+        let count = document.remove("trade_count").and_then(|c| c.as_u64()).ok_or_else(|| anyhow!("no trade_count"))?;
+        document.insert("trades".to_string(), vec!["unknown"; count as usize].into());
+        Ok(())
+    }
+
+    /// v2 had no limit, v3 defaults it
+    fn default_max_trades(document: &mut Map<String, Value>) -> Result<()> {
+        document.entry("max_trades").or_insert(2.into());
+        Ok(())
+    }
+
+    const SCHEMA: Schema = Schema::new("limits", &[list_trades, default_max_trades]);
+
+    #[test]
+    fn test_older_versions_migrate_and_newer_ones_refuse() {
+        assert_eq!(SCHEMA.version(), 3);
+        let expected = Limits { trades: vec!["unknown".to_string(); 2], max_trades: 2 };
+        // Saved before versioning
+        assert_eq!(SCHEMA.parse::<Limits>(r#"{"trade_count": 2}"#).unwrap(), expected);
+        assert_eq!(SCHEMA.parse::<Limits>(r#"{"version": 2, "trades": ["unknown", "unknown"]}"#).unwrap(), expected);
+
+        let json = SCHEMA.to_json(&expected).unwrap();
+        assert!(json.contains(r#""version": 3"#));
+        assert_eq!(SCHEMA.parse::<Limits>(&json).unwrap(), expected);
+
+        let error = SCHEMA.parse::<Limits>(r#"{"version": 4, "trades": []}"#).unwrap_err();
+        assert!(error.to_string().contains("newer release"));
+        assert!(SCHEMA.parse::<Limits>(r#"{"version": 1}"#).unwrap_err().chain().any(|e| e.to_string() == "no trade_count"));
+        assert!(SCHEMA.parse::<Limits>("[]").is_err());
+    }
+}
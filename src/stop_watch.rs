@@ -0,0 +1,175 @@
+use crate::position::Position;
+use anyhow::{anyhow, Result};
+use futures_util::{SinkExt, StreamExt};
+use rust_decimal::Decimal;
+use serde_json::Value;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::sync::{watch, Notify};
+use tokio_tungstenite::tungstenite::Message;
+use tracing::{debug, info, warn};
+
+const MAX_RECONNECT_DELAY_SECS: u64 = 60;
+
+/// Stop of an open position, as the stream checks it
+#[derive(Debug, Clone, PartialEq)]
+struct WatchedStop {
+    position_id: String,
+    stop: Decimal,
+    short: bool,
+}
+
+impl WatchedStop {
+    fn hit_by(&self, price: Decimal) -> bool {
+        if self.short { price >= self.stop } else { price <= self.stop }
+    }
+}
+
+#[derive(Debug, Clone, Default, PartialEq)]
+struct Watched {
+    symbol: String,
+    stops: Vec<WatchedStop>,
+}
+
+/// Checks the open positions' stops against every trade on the exchange's market stream,
+/// between the trading loop's cycles. A hit is logged at once and wakes the loop, which
+/// exits through its usual order path right away; the heavier analysis keeps its cadence.
+pub struct StopWatcher {
+    watched: watch::Sender<Watched>,
+    hit: Arc<Notify>,
+}
+
+impl StopWatcher {
+    /// Runs in the background until dropped, reconnecting when the stream drops
+    pub fn spawn(ws_url: String) -> Self {
+        let (watched, mut receiver) = watch::channel(Watched::default());
+        let hit = Arc::new(Notify::new());
+        let notify = hit.clone();
+        tokio::spawn(async move {
+            let mut delay = 1;
+            loop {
+                match run_session(&ws_url, &mut receiver, &notify).await {
+                    Ok(()) => delay = 1,
+                    // The trading loop is gone
+                    Err(_) if receiver.has_changed().is_err() => return,
+                    Err(e) => {
+                        warn!("⚠️ Stop watcher stream error: {}", e);
+                        delay = (delay * 2).min(MAX_RECONNECT_DELAY_SECS);
+                    }
+                }
+                tokio::time::sleep(Duration::from_secs(delay)).await;
+            }
+        });
+        Self { watched, hit }
+    }
+
+    /// Watch the stops of the open `positions` on `symbol`
+    pub fn update(&self, symbol: &str, positions: &[Position]) {
+        let stops = positions.iter()
+            .filter(|p| p.is_open())
+            .filter_map(|p| Some(WatchedStop { position_id: p.id.clone(), stop: p.stop_loss?, short: p.short }))
+            .collect();
+        let next = Watched { symbol: symbol.to_string(), stops };
+        self.watched.send_if_modified(|watched| {
+            let changed = *watched != next;
+            *watched = next;
+            changed
+        });
+    }
+
+    /// Sleep for `period`, or until the stream hits a stop; true when a stop cut it short
+    pub async fn wait(&self, period: Duration) -> bool {
+        tokio::select! {
+            _ = tokio::time::sleep(period) => false,
+            _ = self.hit.notified() => true,
+        }
+    }
+}
+
+/// Price of a trade stream message
+fn trade_price(text: &str) -> Option<Decimal> {
+    let payload: Value = serde_json::from_str(text).ok()?;
+    payload["p"].as_str()?.parse().ok()
+}
+
+/// Stream the watched symbol's trades until it changes or the connection drops. Each stop
+/// wakes the loop once; it is watched again when its level changes.
+async fn run_session(ws_url: &str, receiver: &mut watch::Receiver<Watched>, hit: &Notify) -> Result<()> {
+    let symbol = receiver.borrow_and_update().symbol.clone();
+    if symbol.is_empty() {
+        receiver.changed().await?;
+        return Ok(());
+    }
+    let url = format!("{}/{}@trade", ws_url.trim_end_matches('/'), symbol.to_lowercase());
+    let (mut ws, _) = tokio_tungstenite::connect_async(url.as_str())
+        .await
+        .map_err(|e| anyhow!("connect failed: {}", e))?;
+    info!("🔌 Stop watcher streaming {} trades", symbol);
+
+    let mut fired: Vec<WatchedStop> = Vec::new();
+    loop {
+        tokio::select! {
+            changed = receiver.changed() => {
+                changed?;
+                let watched = receiver.borrow_and_update();
+                if watched.symbol != symbol {
+                    return Ok(());
+                }
+                fired.retain(|stop| watched.stops.contains(stop));
+            }
+            message = ws.next() => match message {
+                Some(Ok(Message::Text(text))) => {
+                    let Some(price) = trade_price(&text) else {
+                        debug!("Ignoring unparseable trade message");
+                        continue;
+                    };
+                    for stop in receiver.borrow().stops.iter().filter(|s| s.hit_by(price)) {
+                        if !fired.contains(stop) {
+                            warn!("⚡ STOP-LOSS hit on the stream ({}): {} @ ${:.2}, stop ${:.2} - exiting now",
+                                stop.position_id, symbol, price, stop.stop);
+                            fired.push(stop.clone());
+                            hit.notify_one();
+                        }
+                    }
+                }
+                Some(Ok(Message::Ping(data))) => {
+                    ws.send(Message::Pong(data)).await.ok();
+                }
+                Some(Ok(Message::Close(_))) | None => return Err(anyhow!("connection closed")),
+                Some(Ok(_)) => {}
+                Some(Err(e)) => return Err(anyhow!("websocket error: {}", e)),
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rust_decimal_macros::dec;
+
+    #[tokio::test]
+    async fn test_stops_of_open_positions_are_watched() {
+        assert_eq!(trade_price(r#"{"e":"trade","s":"BTCUSDT","p":"64990.50","q":"0.01"}"#), Some(dec!(64990.50)));
+        assert_eq!(trade_price("not json"), None);
+
+        let mut long = Position::resumed("BTCUSDT", dec!(1), Some(dec!(100)));
+        long.set_percent_targets(dec!(-5), dec!(10));
+        let mut short = Position::new_short("BTCUSDT");
+        short.sync(dec!(1), Some(dec!(100)));
+        short.set_percent_targets(dec!(-5), dec!(10));
+        let flat = Position::new("BTCUSDT");
+
+        let watcher = StopWatcher::spawn("ws://127.0.0.1:9".to_string());
+        watcher.update("BTCUSDT", &[long, short, flat]);
+        let stops = watcher.watched.borrow().stops.clone();
+        assert_eq!(stops.len(), 2);
+        assert!(stops[0].hit_by(dec!(95)) && !stops[0].hit_by(dec!(95.1)));
+        assert!(stops[1].hit_by(dec!(105)) && !stops[1].hit_by(dec!(104.9)));
+
+        // A hit wakes the loop even when it arrives before the loop waits
+        watcher.hit.notify_one();
+        assert!(watcher.wait(Duration::from_secs(5)).await);
+        assert!(!watcher.wait(Duration::from_millis(10)).await);
+    }
+}
@@ -1,3 +1,4 @@
+use crate::candles::CandleInterval;
 use crate::models::{Kline, Signal};
 use rust_decimal::Decimal;
 use rust_decimal_macros::dec;
@@ -9,6 +10,8 @@ use rust_decimal_macros::dec;
 pub struct SmaCrossover {
     pub short_period: usize,
     pub long_period: usize,
+    /// Candles the averages are taken over (hourly by default)
+    pub interval: CandleInterval,
 }
 
 impl SmaCrossover {
@@ -17,11 +20,17 @@ impl SmaCrossover {
         Self {
             short_period,
             long_period,
+            interval: CandleInterval::default(),
         }
     }
 
+    pub fn with_interval(mut self, interval: CandleInterval) -> Self {
+        self.interval = interval;
+        self
+    }
+
     pub fn calculate_sma(prices: &[Decimal], period: usize) -> Option<Decimal> {
-        if prices.len() < period {
+        if period == 0 || prices.len() < period {
             return None;
         }
 
@@ -59,6 +68,8 @@ pub struct RsiStrategy {
     pub period: usize,
     pub oversold: Decimal,
     pub overbought: Decimal,
+    /// Candles the RSI is taken over (hourly by default)
+    pub interval: CandleInterval,
 }
 
 impl RsiStrategy {
@@ -68,11 +79,17 @@ impl RsiStrategy {
             period,
             oversold,
             overbought,
+            interval: CandleInterval::default(),
         }
     }
 
+    pub fn with_interval(mut self, interval: CandleInterval) -> Self {
+        self.interval = interval;
+        self
+    }
+
     pub fn calculate_rsi(prices: &[Decimal], period: usize) -> Option<Decimal> {
-        if prices.len() < period + 1 {
+        if period == 0 || prices.len() < period + 1 {
             return None;
         }
 
@@ -120,6 +137,31 @@ impl RsiStrategy {
     }
 }
 
+/// Average True Range: the mean of the last `period` true ranges, where a candle's
+/// true range also covers any gap from the previous close
+pub struct Atr;
+
+impl Atr {
+    pub fn calculate(klines: &[Kline], period: usize) -> Option<Decimal> {
+        if period == 0 || klines.len() < period + 1 {
+            return None;
+        }
+
+        let sum: Decimal = klines
+            .windows(2)
+            .rev()
+            .take(period)
+            .map(|pair| {
+                let (prev_close, k) = (pair[0].close, &pair[1]);
+                (k.high - k.low)
+                    .max((k.high - prev_close).abs())
+                    .max((k.low - prev_close).abs())
+            })
+            .sum();
+        Some(sum / Decimal::from(period))
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -144,4 +186,93 @@ mod tests {
         let rsi = RsiStrategy::calculate_rsi(&prices, 14);
         assert!(rsi.is_some());
     }
+
+    fn candle(open: Decimal, high: Decimal, low: Decimal, close: Decimal) -> Kline {
+        Kline { open_time: 0, open, high, low, close, volume: Decimal::ONE, close_time: 0 }
+    }
+
+    #[test]
+    fn test_atr_includes_gaps() {
+        let klines = vec![
+            candle(dec!(100), dec!(105), dec!(95), dec!(100)),
+            candle(dec!(100), dec!(104), dec!(98), dec!(102)),  // TR 6
+            candle(dec!(110), dec!(112), dec!(109), dec!(111)), // gap up: TR 112 - 102 = 10
+        ];
+        assert_eq!(Atr::calculate(&klines, 2), Some(dec!(8)));
+        assert_eq!(Atr::calculate(&klines, 3), None);
+    }
+
+    mod properties {
+        use super::*;
+        use proptest::prelude::*;
+
+        /// Prices in cents between 0.01 and 1,000,000
+        fn price() -> impl Strategy<Value = Decimal> {
+            (1i64..100_000_000).prop_map(|cents| Decimal::new(cents, 2))
+        }
+
+        fn candles() -> impl Strategy<Value = Vec<Kline>> {
+            prop::collection::vec((price(), price(), price()), 2..60).prop_map(|raw| {
+                raw.into_iter()
+                    .map(|(a, b, c)| {
+                        let (low, high) = (a.min(b), a.max(b));
+                        let close = c.clamp(low, high);
+                        candle(low, high, low, close)
+                    })
+                    .collect()
+            })
+        }
+
+        proptest! {
+            #[test]
+            fn sma_lies_within_window_range(prices in prop::collection::vec(price(), 1..100), period in 1usize..50) {
+                match SmaCrossover::calculate_sma(&prices, period) {
+                    Some(sma) => {
+                        let window = &prices[prices.len() - period..];
+                        prop_assert!(sma >= *window.iter().min().unwrap());
+                        prop_assert!(sma <= *window.iter().max().unwrap());
+                    }
+                    None => prop_assert!(prices.len() < period),
+                }
+            }
+
+            #[test]
+            fn sma_of_constant_series_is_the_constant(p in price(), len in 1usize..50) {
+                prop_assert_eq!(SmaCrossover::calculate_sma(&vec![p; len], len), Some(p));
+            }
+
+            #[test]
+            fn sma_is_monotonic_in_the_latest_price(prices in prop::collection::vec(price(), 5..50), bump in price()) {
+                let mut higher = prices.clone();
+                *higher.last_mut().unwrap() += bump;
+                prop_assert!(SmaCrossover::calculate_sma(&higher, 5) > SmaCrossover::calculate_sma(&prices, 5));
+            }
+
+            #[test]
+            fn rsi_is_bounded(prices in prop::collection::vec(price(), 2..100), period in 1usize..30) {
+                if let Some(rsi) = RsiStrategy::calculate_rsi(&prices, period) {
+                    prop_assert!(rsi >= Decimal::ZERO && rsi <= dec!(100), "rsi = {}", rsi);
+                }
+            }
+
+            #[test]
+            fn rsi_extremes_for_one_way_series(start in price(), step in 1i64..10_000, len in 16usize..40) {
+                let rising: Vec<Decimal> = (0..len).map(|i| start + Decimal::new(step * i as i64, 2)).collect();
+                let falling: Vec<Decimal> = rising.iter().rev().copied().collect();
+                prop_assert_eq!(RsiStrategy::calculate_rsi(&rising, 14), Some(dec!(100)));
+                prop_assert_eq!(RsiStrategy::calculate_rsi(&falling, 14), Some(Decimal::ZERO));
+            }
+
+            #[test]
+            fn atr_is_at_least_the_average_candle_range(klines in candles(), period in 1usize..20) {
+                if let Some(atr) = Atr::calculate(&klines, period) {
+                    let ranges: Decimal = klines.iter().rev().take(period).map(|k| k.high - k.low).sum();
+                    prop_assert!(atr >= ranges / Decimal::from(period));
+                    prop_assert!(atr >= Decimal::ZERO);
+                } else {
+                    prop_assert!(klines.len() <= period);
+                }
+            }
+        }
+    }
 }
@@ -0,0 +1,122 @@
+use crate::quote;
+use rust_decimal::{Decimal, RoundingStrategy};
+use rust_decimal_macros::dec;
+
+/// Per-symbol trading metadata
+#[derive(Debug, Clone, PartialEq)]
+pub struct SymbolInfo {
+    pub symbol: String,
+    pub base: String,
+    pub quote: String,
+    /// Decimal places accepted for order prices (tick size)
+    pub price_precision: u32,
+    /// Decimal places accepted for order quantities (lot step size)
+    pub qty_precision: u32,
+    pub min_qty: Decimal,
+    /// Minimum order value in the quote asset
+    pub min_notional: Decimal,
+    pub coingecko_id: Option<&'static str>,
+    /// Decimal places used when showing prices in logs and reports
+    pub display_decimals: u32,
+}
+
+/// Built-in metadata by base asset:
+/// (base, CoinGecko id, price precision, qty precision, display decimals)
+const KNOWN_ASSETS: &[(&str, &str, u32, u32, u32)] = &[
+    ("BTC", "bitcoin", 2, 5, 2),
+    ("ETH", "ethereum", 2, 4, 2),
+    ("BNB", "binancecoin", 2, 3, 2),
+    ("XRP", "ripple", 4, 0, 4),
+    ("ADA", "cardano", 4, 1, 4),
+    ("SOL", "solana", 2, 3, 2),
+    ("DOT", "polkadot", 3, 2, 3),
+    ("DOGE", "dogecoin", 5, 0, 5),
+    ("MATIC", "matic-network", 4, 1, 4),
+    ("LTC", "litecoin", 2, 3, 2),
+    ("AVAX", "avalanche-2", 2, 2, 2),
+    ("LINK", "chainlink", 3, 2, 3),
+    ("ATOM", "cosmos", 3, 2, 3),
+    ("UNI", "uniswap", 3, 2, 3),
+    ("XLM", "stellar", 5, 0, 5),
+];
+
+/// Binance's default minimum order value for stablecoin and EUR pairs
+const DEFAULT_MIN_NOTIONAL: Decimal = dec!(5);
+
+/// Look up metadata for a pair (`BTCUSDT`) or a bare base asset (`btc`).
+/// Unknown symbols get conservative defaults.
+pub fn lookup(symbol: &str) -> SymbolInfo {
+    let symbol = symbol.to_uppercase();
+    let (base, quote) = quote::split_symbol(&symbol)
+        .map(|(b, q)| (b.to_string(), q.to_string()))
+        .unwrap_or_else(|| (symbol.clone(), "USDT".to_string()));
+
+    let known = KNOWN_ASSETS.iter().find(|(b, ..)| *b == base);
+    let (coingecko_id, price_precision, qty_precision, display_decimals) = match known {
+        Some((_, id, price, qty, display)) => (Some(*id), *price, *qty, *display),
+        None => (None, 4, 2, 4),
+    };
+
+    SymbolInfo {
+        symbol: format!("{}{}", base, quote),
+        base,
+        quote,
+        price_precision,
+        qty_precision,
+        min_qty: Decimal::new(1, qty_precision),
+        min_notional: DEFAULT_MIN_NOTIONAL,
+        coingecko_id,
+        display_decimals,
+    }
+}
+
+impl SymbolInfo {
+    /// Round a quantity down to the lot step so the order is never rejected for precision
+    pub fn round_qty(&self, qty: Decimal) -> Decimal {
+        qty.round_dp_with_strategy(self.qty_precision, RoundingStrategy::ToZero)
+    }
+
+    pub fn round_price(&self, price: Decimal) -> Decimal {
+        price.round_dp(self.price_precision)
+    }
+
+    /// Smallest quantity at `price` that passes the minimum size filters, on the lot step
+    pub fn min_order_qty(&self, price: Decimal) -> Decimal {
+        let for_notional = (self.min_notional / price).round_dp_with_strategy(self.qty_precision, RoundingStrategy::AwayFromZero);
+        for_notional.max(self.min_qty)
+    }
+
+    /// Whether an order of `qty` at `price` passes the minimum size filters
+    pub fn meets_minimums(&self, qty: Decimal, price: Decimal) -> bool {
+        qty >= self.min_qty && qty * price >= self.min_notional
+    }
+
+    /// A balance too small to sell: after rounding to the lot step it fails the
+    /// minimum quantity or minimum notional filter. Such leftovers ("dust") don't
+    /// count as an open position.
+    pub fn is_dust(&self, qty: Decimal, price: Decimal) -> bool {
+        !self.meets_minimums(self.round_qty(qty), price)
+    }
+
+    /// CoinGecko `vs_currency` matching the quote asset
+    pub fn coingecko_vs_currency(&self) -> &'static str {
+        if self.quote == "EUR" { "eur" } else { "usd" }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_lookup() {
+        let btc = lookup("BTCFDUSD");
+        assert_eq!((btc.base.as_str(), btc.quote.as_str()), ("BTC", "FDUSD"));
+        assert_eq!(btc.coingecko_id, Some("bitcoin"));
+        assert_eq!(btc.round_qty(dec!(0.123456789)), dec!(0.12345));
+        assert!(!btc.meets_minimums(dec!(0.00001), dec!(42000)));
+
+        assert_eq!(lookup("doge").symbol, "DOGEUSDT");
+        assert_eq!(lookup("FOOUSDT").coingecko_id, None);
+    }
+}
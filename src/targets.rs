@@ -0,0 +1,189 @@
+use crate::ai_advisor::AiTradingTargets;
+use crate::config::Config;
+use crate::error::BotError;
+use anyhow::Result;
+use rust_decimal::Decimal;
+
+/// Where an active target came from
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum TargetSource {
+    Ai,
+    #[default]
+    Fallback,
+    /// Set as an absolute price in the TARGET_*_PRICE settings
+    Manual,
+}
+
+impl TargetSource {
+    /// `ai` or `fallback`, as audited for the weekly review
+    pub fn from_label(label: &str) -> Self {
+        if label == "ai" { Self::Ai } else { Self::Fallback }
+    }
+}
+
+/// Source of each target shown in the report
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct TargetSources {
+    pub stop_loss: TargetSource,
+    pub take_profit: TargetSource,
+    pub buy_target: Option<TargetSource>,
+    pub sell_target: Option<TargetSource>,
+}
+
+/// Who wins when a level is both configured and calculated
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum TargetPriority {
+    /// A configured price always replaces the calculated one
+    #[default]
+    Manual,
+    /// AI targets win; configured prices stand in for the fallback's
+    Ai,
+    /// The safer of the two: the higher stop, the nearer take-profit and sell target,
+    /// the lower buy target
+    Conservative,
+}
+
+/// Absolute target prices from TARGET_*_PRICE, merged into each set of AI/fallback targets
+#[derive(Debug, Clone, Default)]
+pub struct ManualTargets {
+    pub stop_loss: Option<Decimal>,
+    pub take_profit: Option<Decimal>,
+    pub buy_target: Option<Decimal>,
+    pub sell_target: Option<Decimal>,
+    pub priority: TargetPriority,
+}
+
+impl ManualTargets {
+    pub fn from_config(config: &Config) -> Result<Self> {
+        let priority = match config.target_priority.to_lowercase().as_str() {
+            "manual" => TargetPriority::Manual,
+            "ai" => TargetPriority::Ai,
+            "conservative" => TargetPriority::Conservative,
+            other => return Err(BotError::config(format!(
+                "Invalid TARGET_PRIORITY '{}' (expected manual, ai or conservative)", other)).into()),
+        };
+        let targets = Self {
+            stop_loss: config.target_stop_loss_price,
+            take_profit: config.target_take_profit_price,
+            buy_target: config.target_buy_price,
+            sell_target: config.target_sell_price,
+            priority,
+        };
+        let prices = [
+            ("TARGET_STOP_LOSS_PRICE", targets.stop_loss),
+            ("TARGET_TAKE_PROFIT_PRICE", targets.take_profit),
+            ("TARGET_BUY_PRICE", targets.buy_target),
+            ("TARGET_SELL_PRICE", targets.sell_target),
+        ];
+        if let Some((name, Some(price))) = prices.iter().find(|(_, p)| p.is_some_and(|p| p <= Decimal::ZERO)) {
+            return Err(BotError::config(format!("{} must be a positive price, got {}", name, price)).into());
+        }
+        if let (Some(stop), Some(take)) = (targets.stop_loss, targets.take_profit)
+            && stop >= take {
+            return Err(BotError::config(format!(
+                "TARGET_STOP_LOSS_PRICE ({}) must be below TARGET_TAKE_PROFIT_PRICE ({})", stop, take)).into());
+        }
+        if let (Some(buy), Some(sell)) = (targets.buy_target, targets.sell_target)
+            && buy >= sell {
+            return Err(BotError::config(format!(
+                "TARGET_BUY_PRICE ({}) must be below TARGET_SELL_PRICE ({})", buy, sell)).into());
+        }
+        Ok(targets)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        [self.stop_loss, self.take_profit, self.buy_target, self.sell_target].iter().all(Option::is_none)
+    }
+
+    /// Pick between a configured and a calculated level; `higher_is_safer` says which way to lean
+    /// under the conservative policy
+    fn pick(&self, manual: Option<Decimal>, calculated: Option<Decimal>, source: TargetSource,
+            higher_is_safer: bool) -> (Option<Decimal>, Option<TargetSource>) {
+        let (manual, calculated) = match (manual, calculated) {
+            (None, calculated) => return (calculated, calculated.map(|_| source)),
+            (Some(manual), None) => return (Some(manual), Some(TargetSource::Manual)),
+            (Some(manual), Some(calculated)) => (manual, calculated),
+        };
+        let manual_wins = match self.priority {
+            TargetPriority::Manual => true,
+            TargetPriority::Ai => source != TargetSource::Ai,
+            TargetPriority::Conservative if higher_is_safer => manual > calculated,
+            TargetPriority::Conservative => manual < calculated,
+        };
+        if manual_wins { (Some(manual), Some(TargetSource::Manual)) } else { (Some(calculated), Some(source)) }
+    }
+
+    /// `targets` from `source` with the configured prices merged in by the priority policy
+    pub fn apply(&self, mut targets: AiTradingTargets, source: TargetSource) -> (AiTradingTargets, TargetSources) {
+        let (stop_loss, stop_source) = self.pick(self.stop_loss, Some(targets.stop_loss_price), source, true);
+        let (take_profit, take_source) = self.pick(self.take_profit, Some(targets.take_profit_price), source, false);
+        let (buy_target, buy_source) = self.pick(self.buy_target, targets.buy_target_price, source, false);
+        let (sell_target, sell_source) = self.pick(self.sell_target, targets.sell_target_price, source, false);
+        targets.stop_loss_price = stop_loss.unwrap_or(targets.stop_loss_price);
+        targets.take_profit_price = take_profit.unwrap_or(targets.take_profit_price);
+        targets.buy_target_price = buy_target;
+        targets.sell_target_price = sell_target;
+        let sources = TargetSources {
+            stop_loss: stop_source.unwrap_or(source),
+            take_profit: take_source.unwrap_or(source),
+            buy_target: buy_source,
+            sell_target: sell_source,
+        };
+        (targets, sources)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ai_advisor::{FallbackTargetCalculator, MarketContext};
+    use rust_decimal_macros::dec;
+
+    #[test]
+    fn test_priority_policies() {
+        let context = MarketContext {
+            symbol: "BTCUSDT".to_string(),
+            current_price: dec!(60000),
+            high_24h: dec!(62000),
+            low_24h: dec!(58000),
+            ..Default::default()
+        };
+        let calculated = AiTradingTargets {
+            stop_loss_price: dec!(58000),
+            take_profit_price: dec!(64000),
+            buy_target_price: Some(dec!(59500)),
+            sell_target_price: None,
+            ..FallbackTargetCalculator::calculate_targets(&context)
+        };
+        let mut manual = ManualTargets {
+            stop_loss: Some(dec!(57000)),
+            sell_target: Some(dec!(70000)),
+            ..Default::default()
+        };
+
+        let (targets, sources) = manual.apply(calculated.clone(), TargetSource::Ai);
+        assert_eq!((targets.stop_loss_price, sources.stop_loss), (dec!(57000), TargetSource::Manual));
+        assert_eq!((targets.take_profit_price, sources.take_profit), (dec!(64000), TargetSource::Ai));
+        assert_eq!((targets.sell_target_price, sources.sell_target), (Some(dec!(70000)), Some(TargetSource::Manual)));
+        assert_eq!(sources.buy_target, Some(TargetSource::Ai));
+
+        // AI priority: the AI's stop stands, the fallback's gives way
+        manual.priority = TargetPriority::Ai;
+        assert_eq!(manual.apply(calculated.clone(), TargetSource::Ai).0.stop_loss_price, dec!(58000));
+        assert_eq!(manual.apply(calculated.clone(), TargetSource::Fallback).0.stop_loss_price, dec!(57000));
+
+        // Conservative: the higher stop wins either way
+        manual.priority = TargetPriority::Conservative;
+        assert_eq!(manual.apply(calculated.clone(), TargetSource::Ai).1.stop_loss, TargetSource::Ai);
+        manual.stop_loss = Some(dec!(59000));
+        assert_eq!(manual.apply(calculated, TargetSource::Ai).1.stop_loss, TargetSource::Manual);
+
+        let mut config = Config::defaults();
+        config.target_stop_loss_price = Some(dec!(65000));
+        config.target_take_profit_price = Some(dec!(64000));
+        assert!(ManualTargets::from_config(&config).is_err());
+        config.target_stop_loss_price = None;
+        config.target_priority = "loudest".to_string();
+        assert!(ManualTargets::from_config(&config).is_err());
+    }
+}
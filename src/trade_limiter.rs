@@ -1,9 +1,25 @@
+use crate::config::Config;
+use crate::error::BotError;
+use crate::state_crypto::StateCipher;
+use crate::state_store::StateStore;
+use crate::state_version::Schema;
 use anyhow::Result;
-use chrono::{DateTime, Utc};
+use chrono::{DateTime, NaiveDate, Utc};
+use chrono_tz::Tz;
 use rust_decimal::Decimal;
 use serde::{Deserialize, Serialize};
-use std::fs;
-use tracing::{info, warn};
+use std::collections::BTreeMap;
+use std::fmt::Write;
+use std::path::Path;
+use tracing::{error, info, warn};
+
+/// Halt reason of a day that reached DAILY_PROFIT_TARGET
+pub const TARGET_ACHIEVED: &str = "target achieved";
+
+/// Formats of the saved daily state and limit history; a change old files can't be read
+/// into adds a migration
+const TRADE_STATE_SCHEMA: Schema = Schema::new("trade state", &[]);
+const LIMIT_HISTORY_SCHEMA: Schema = Schema::new("trade limit history", &[]);
 
 /// Trade record for tracking daily limits
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -14,6 +30,39 @@ pub struct TradeRecord {
     pub price: Decimal,
     pub quantity: Decimal,
     pub is_first_trade: bool,
+    /// Position the trade belongs to, when several are held in one symbol
+    #[serde(default)]
+    pub position_id: Option<String>,
+    /// Why the trade was made
+    #[serde(default)]
+    pub context: Option<TradeContext>,
+}
+
+/// Decision context of a trade, captured when it executed so post-mortems don't have to
+/// piece it together from the logs
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct TradeContext {
+    /// What fired the order, e.g. `Buy target`, `Stop-loss` or `SMA sleeve`
+    pub trigger: String,
+    /// Level that was hit, when a target fired
+    pub target_price: Option<Decimal>,
+    pub stop_loss: Option<Decimal>,
+    pub take_profit: Option<Decimal>,
+    pub rsi: Option<Decimal>,
+    pub sma_short: Option<Decimal>,
+    pub sma_long: Option<Decimal>,
+    pub volume_ratio: Option<Decimal>,
+    pub ai_recommendation: Option<String>,
+    pub ai_confidence: Option<Decimal>,
+    /// SHA-256 of the AI analysis the targets came from; identifies it without storing the text
+    pub ai_reasoning_sha256: Option<String>,
+}
+
+/// Realized P&L of one closed trade, kept so it can leave a rolling window
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RealizedPnl {
+    pub timestamp: DateTime<Utc>,
+    pub pnl: Decimal,
 }
 
 /// Daily trading state
@@ -21,97 +70,380 @@ pub struct TradeRecord {
 pub struct DailyTradingState {
     pub date: String, // YYYY-MM-DD format
     pub trades_today: Vec<TradeRecord>,
-    pub first_trade_executed: bool,
-    pub second_trade_executed: bool,
     pub daily_pnl: Decimal,
+    /// The closed trades `daily_pnl` sums up
+    #[serde(default)]
+    pub realized: Vec<RealizedPnl>,
+}
+
+/// Trade limit usage on one trading day
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct LimitDay {
+    pub date: String,
+    /// Trades that counted against the limit
+    pub trades: u32,
+    pub max_trades: u32,
+    /// When the quota was used up, if it was
+    pub limit_hit_at: Option<DateTime<Utc>>,
+    /// Cycles in which the limit held back an entry whose target was hit
+    pub blocked_entries: u32,
+    pub pnl: Decimal,
+    /// Why new entries were halted for the rest of the day, if they were
+    #[serde(default)]
+    pub halt_reason: Option<String>,
+}
+
+/// Per-day limit usage kept for TRADE_LIMIT_HISTORY_DAYS, so months of data show how often
+/// the limit binds and how those days went. Only days with trades or blocked entries are kept.
+#[derive(Debug, Clone, Default, PartialEq, Serialize, Deserialize)]
+pub struct LimitHistory {
+    pub days: Vec<LimitDay>,
+}
+
+impl LimitHistory {
+    pub fn load(store: &StateStore) -> Self {
+        store.load(&LIMIT_HISTORY_SCHEMA).unwrap_or_else(|e| {
+            warn!("Failed to read trade limit history: {}", e);
+            None
+        }).unwrap_or_default()
+    }
+
+    fn day_mut(&mut self, date: &str, max_trades: u32) -> &mut LimitDay {
+        let index = match self.days.iter().position(|d| d.date == date) {
+            Some(index) => index,
+            None => {
+                self.days.push(LimitDay {
+                    date: date.to_string(),
+                    trades: 0,
+                    max_trades,
+                    limit_hit_at: None,
+                    blocked_entries: 0,
+                    pnl: Decimal::ZERO,
+                    halt_reason: None,
+                });
+                self.days.len() - 1
+            }
+        };
+        &mut self.days[index]
+    }
+
+    /// Drop days more than `keep_days` before `today`
+    fn prune(&mut self, today: NaiveDate, keep_days: u32) {
+        let cutoff = today - chrono::Duration::days(keep_days as i64);
+        self.days.retain(|d| NaiveDate::parse_from_str(&d.date, "%Y-%m-%d").is_ok_and(|date| date > cutoff));
+    }
+
+    /// Monthly summary: how often the limit was hit, and the average P&L of those days
+    /// against the others
+    pub fn render(&self) -> String {
+        let mut months: BTreeMap<&str, Vec<&LimitDay>> = BTreeMap::new();
+        for day in &self.days {
+            months.entry(day.date.get(..7).unwrap_or(&day.date)).or_default().push(day);
+        }
+        let average = |days: &[&LimitDay]| {
+            if days.is_empty() {
+                "-".to_string()
+            } else {
+                format!("${:.2}", days.iter().map(|d| d.pnl).sum::<Decimal>() / Decimal::from(days.len()))
+            }
+        };
+
+        let hit_days = self.days.iter().filter(|d| d.limit_hit_at.is_some()).count();
+        let target_days = |days: &[&LimitDay]| days.iter().filter(|d| d.halt_reason.as_deref() == Some(TARGET_ACHIEVED)).count();
+        let mut out = format!("📅 Trade limit history: {} active days, limit hit on {}
+
+", self.days.len(), hit_days);
+        let _ = writeln!(out, "{:<9}{:>6}{:>11}{:>9}{:>12}{:>15}{:>15}", "Month", "Days", "Limit hit", "Blocked",
+            "Target hit", "Avg P&L (hit)", "Avg P&L (other)");
+        for (month, days) in &months {
+            let (hit, other): (Vec<&LimitDay>, Vec<&LimitDay>) = days.iter().partition(|d| d.limit_hit_at.is_some());
+            let _ = writeln!(out, "{:<9}{:>6}{:>11}{:>9}{:>12}{:>15}{:>15}", month, days.len(), hit.len(),
+                days.iter().map(|d| d.blocked_entries).sum::<u32>(), target_days(days), average(&hit), average(&other));
+        }
+        out
+    }
+}
+
+/// How the trading "day" for the daily limit is determined
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum DayBoundary {
+    /// Calendar day in the given timezone; the limit resets at local midnight
+    Calendar(Tz),
+    /// Rolling 24-hour window; each trade stops counting 24h after it was made
+    Rolling24h,
+}
+
+impl DayBoundary {
+    pub fn from_config(config: &Config) -> Result<Self> {
+        match config.trade_limit_window.to_lowercase().as_str() {
+            "calendar" => {
+                let tz: Tz = config.trade_day_timezone.parse().map_err(|_| BotError::config(format!(
+                    "Invalid TRADE_DAY_TIMEZONE '{}' (expected an IANA name like America/Sao_Paulo)",
+                    config.trade_day_timezone
+                )))?;
+                Ok(DayBoundary::Calendar(tz))
+            }
+            "rolling" | "rolling24h" => Ok(DayBoundary::Rolling24h),
+            other => Err(BotError::config(format!("Invalid TRADE_LIMIT_WINDOW '{}' (expected calendar or rolling)", other)).into()),
+        }
+    }
+}
+
+impl Default for DayBoundary {
+    fn default() -> Self {
+        DayBoundary::Calendar(Tz::UTC)
+    }
 }
 
-/// Trade limiter - enforces max 2 trades per day rule
+/// What counts against the daily trade limit
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum LimitMode {
+    /// Every order counts (a buy and its exit use two slots)
+    #[default]
+    Orders,
+    /// Only entries count; exits that close a position are always allowed
+    RoundTrips,
+}
+
+impl LimitMode {
+    pub fn from_config(config: &Config) -> Result<Self> {
+        match config.trade_limit_mode.to_lowercase().as_str() {
+            "orders" => Ok(LimitMode::Orders),
+            "round_trips" | "roundtrips" | "round-trips" => Ok(LimitMode::RoundTrips),
+            other => Err(BotError::config(format!("Invalid TRADE_LIMIT_MODE '{}' (expected orders or round_trips)", other)).into()),
+        }
+    }
+}
+
+/// Trade limiter rules
+#[derive(Debug, Clone)]
+pub struct LimiterSettings {
+    pub max_trades_per_day: u32,
+    pub day_boundary: DayBoundary,
+    pub mode: LimitMode,
+    /// Days of limit history to keep (0 = none)
+    pub history_days: u32,
+    /// Realized P&L after which no new positions are opened for the rest of the day
+    pub daily_profit_target: Option<Decimal>,
+}
+
+impl Default for LimiterSettings {
+    fn default() -> Self {
+        Self {
+            max_trades_per_day: 2,
+            day_boundary: DayBoundary::default(),
+            mode: LimitMode::default(),
+            history_days: 365,
+            daily_profit_target: None,
+        }
+    }
+}
+
+impl LimiterSettings {
+    pub fn from_config(config: &Config) -> Result<Self> {
+        if config.max_trades_per_day == 0 {
+            return Err(BotError::config("MAX_TRADES_PER_DAY must be at least 1").into());
+        }
+        if config.daily_profit_target.is_some_and(|t| t <= Decimal::ZERO) {
+            return Err(BotError::config("DAILY_PROFIT_TARGET must be positive").into());
+        }
+        Ok(Self {
+            max_trades_per_day: config.max_trades_per_day,
+            day_boundary: DayBoundary::from_config(config)?,
+            mode: LimitMode::from_config(config)?,
+            history_days: config.trade_limit_history_days,
+            daily_profit_target: config.daily_profit_target,
+        })
+    }
+}
+
+/// Trade limiter - enforces the MAX_TRADES_PER_DAY rule (2 by default)
 pub struct TradeLimiter {
-    state_file: String,
+    store: StateStore,
     current_state: DailyTradingState,
+    /// Per-day usage, kept next to the state in the same backend
+    history_store: StateStore,
+    history: LimitHistory,
+    history_days: u32,
     max_trades_per_day: u32,
+    day_boundary: DayBoundary,
+    mode: LimitMode,
+    daily_profit_target: Option<Decimal>,
+    /// Day the profit target was last announced and recorded
+    target_reached_on: Option<String>,
 }
 
 impl TradeLimiter {
-    pub fn new(state_file: &str) -> Self {
+    #[allow(dead_code)]
+    pub fn new(state_file: &Path, cipher: Option<StateCipher>) -> Self {
+        Self::with_settings(state_file, cipher, LimiterSettings::default())
+    }
+
+    pub fn with_settings(state_file: &Path, cipher: Option<StateCipher>, settings: LimiterSettings) -> Self {
+        Self::with_store(StateStore::file(state_file, cipher), settings)
+    }
+
+    /// Limiter whose state lives in any state backend (file, Redis, Postgres)
+    pub fn with_store(store: StateStore, settings: LimiterSettings) -> Self {
+        let history_store = store.companion("history");
         let mut limiter = Self {
-            state_file: state_file.to_string(),
-            current_state: DailyTradingState::new_for_today(),
-            max_trades_per_day: 2,
+            store,
+            current_state: DailyTradingState::new_for_date(String::new()),
+            history: LimitHistory::load(&history_store),
+            history_store,
+            history_days: settings.history_days,
+            max_trades_per_day: settings.max_trades_per_day,
+            day_boundary: settings.day_boundary,
+            mode: settings.mode,
+            daily_profit_target: settings.daily_profit_target,
+            target_reached_on: None,
         };
+        limiter.current_state = DailyTradingState::new_for_date(limiter.today_string());
         limiter.load_state();
         limiter
     }
 
     /// Load state from file, reset if it's a new day
     fn load_state(&mut self) {
-        let today = Self::today_string();
+        let today = self.today_string();
         
-        if let Ok(content) = fs::read_to_string(&self.state_file) {
-            if let Ok(state) = serde_json::from_str::<DailyTradingState>(&content) {
-                if state.date == today {
-                    self.current_state = state;
-                    info!("Loaded trading state for today: {} trades executed", 
-                          self.current_state.trades_today.len());
-                    return;
-                } else {
-                    info!("New trading day detected, resetting state");
-                }
+        // Unreadable state resets today's count, so say so loudly; the old document is kept
+        let state = self.store.load::<DailyTradingState>(&TRADE_STATE_SCHEMA).unwrap_or_else(|e| {
+            error!("❌ Trade limiter state unusable, today's trade count starts from zero: {}", e);
+            None
+        });
+
+        if let Some(state) = state {
+            if self.day_boundary == DayBoundary::Rolling24h {
+                // Trades stay relevant for 24h regardless of the calendar date
+                self.current_state = state;
+                self.prune_rolling_window();
+                info!("Loaded trading state: {} trades in the last 24h",
+                      self.current_state.trades_today.len());
+                return;
+            } else if state.date == today {
+                self.current_state = state;
+                info!("Loaded trading state for today: {} trades executed", 
+                      self.current_state.trades_today.len());
+                return;
+            } else {
+                info!("New trading day detected, resetting state");
             }
         }
         
         // Start fresh for today
-        self.current_state = DailyTradingState::new_for_today();
+        self.current_state = DailyTradingState::new_for_date(today);
         self.save_state();
     }
 
     /// Save state to file
     fn save_state(&self) {
-        if let Ok(json) = serde_json::to_string_pretty(&self.current_state) {
-            if let Err(e) = fs::write(&self.state_file, json) {
-                warn!("Failed to save trade limiter state: {}", e);
+        if let Err(e) = self.store.save(&TRADE_STATE_SCHEMA, &self.current_state) {
+            warn!("Failed to save trade limiter state: {}", e);
+        }
+    }
+
+    /// Apply `update` to today's history entry, prune expired days and save
+    fn update_history(&mut self, update: impl FnOnce(&mut LimitDay)) {
+        if self.history_days == 0 {
+            return;
+        }
+        let today = self.today_string();
+        update(self.history.day_mut(&today, self.max_trades_per_day));
+        if let Ok(date) = NaiveDate::parse_from_str(&today, "%Y-%m-%d") {
+            self.history.prune(date, self.history_days);
+        }
+        if let Err(e) = self.history_store.save(&LIMIT_HISTORY_SCHEMA, &self.history) {
+            warn!("Failed to save trade limit history: {}", e);
+        }
+    }
+
+    /// Current trading date (YYYY-MM-DD) in the configured timezone
+    fn today_string(&self) -> String {
+        let tz = match self.day_boundary {
+            DayBoundary::Calendar(tz) => tz,
+            DayBoundary::Rolling24h => Tz::UTC,
+        };
+        Utc::now().with_timezone(&tz).format("%Y-%m-%d").to_string()
+    }
+
+    /// Drop trades and realized P&L older than 24h (rolling window mode)
+    fn prune_rolling_window(&mut self) {
+        let cutoff = Utc::now() - chrono::Duration::hours(24);
+        self.current_state.trades_today.retain(|t| t.timestamp > cutoff);
+        self.current_state.realized.retain(|r| r.timestamp > cutoff);
+        self.current_state.daily_pnl = self.current_state.realized.iter().map(|r| r.pnl).sum();
+        self.current_state.date = self.today_string();
+    }
+
+    /// Realized P&L of the current day, or of the last 24h in rolling mode
+    fn window_pnl(&self) -> Decimal {
+        match self.day_boundary {
+            DayBoundary::Calendar(_) if self.current_state.date == self.today_string() => self.current_state.daily_pnl,
+            DayBoundary::Calendar(_) => Decimal::ZERO,
+            DayBoundary::Rolling24h => {
+                let cutoff = Utc::now() - chrono::Duration::hours(24);
+                self.current_state.realized.iter().filter(|r| r.timestamp > cutoff).map(|r| r.pnl).sum()
             }
         }
     }
 
-    fn today_string() -> String {
-        Utc::now().format("%Y-%m-%d").to_string()
+    /// Trades that count against the limit right now
+    fn trades_in_window(&self) -> Vec<&TradeRecord> {
+        match self.day_boundary {
+            DayBoundary::Calendar(_) => {
+                if self.current_state.date == self.today_string() {
+                    self.current_state.trades_today.iter().collect()
+                } else {
+                    Vec::new()
+                }
+            }
+            DayBoundary::Rolling24h => {
+                let cutoff = Utc::now() - chrono::Duration::hours(24);
+                self.current_state.trades_today.iter().filter(|t| t.timestamp > cutoff).collect()
+            }
+        }
     }
 
-    /// Check if trading is allowed
-    pub fn can_trade(&self) -> TradePermission {
-        let today = Self::today_string();
-        
-        // Reset if it's a new day
-        if self.current_state.date != today {
-            return TradePermission::Allowed {
-                is_first_trade: true,
-                trades_remaining: 2,
-            };
+    /// Trades in the window that use up the daily quota
+    fn counted_trades(&self) -> Vec<&TradeRecord> {
+        let trades = self.trades_in_window();
+        match self.mode {
+            LimitMode::Orders => trades,
+            LimitMode::RoundTrips => trades.into_iter().filter(|t| t.side == "BUY").collect(),
         }
+    }
 
-        let trades_count = self.current_state.trades_today.len() as u32;
+    /// Check if trading is allowed (opening a new position)
+    pub fn can_trade(&self) -> TradePermission {
+        let trades_count = self.counted_trades().len() as u32;
 
         if trades_count >= self.max_trades_per_day {
             TradePermission::DailyLimitReached {
                 trades_executed: trades_count,
                 next_trading_day: self.next_trading_day(),
             }
-        } else if trades_count == 0 {
-            TradePermission::Allowed {
-                is_first_trade: true,
-                trades_remaining: 2,
-            }
         } else {
             // Second trade is only allowed if first trade was executed
             TradePermission::Allowed {
-                is_first_trade: false,
-                trades_remaining: 1,
+                is_first_trade: trades_count == 0,
+                trades_remaining: self.max_trades_per_day - trades_count,
             }
         }
     }
 
+    /// Check if closing an open position is allowed. In round-trip mode exits never
+    /// count against the limit, so a position can't be stranded by its own entry.
+    pub fn can_exit(&self) -> TradePermission {
+        match self.mode {
+            LimitMode::Orders => self.can_trade(),
+            LimitMode::RoundTrips => TradePermission::Allowed {
+                is_first_trade: self.trades_in_window().is_empty(),
+                trades_remaining: self.max_trades_per_day.saturating_sub(self.counted_trades().len() as u32),
+            },
+        }
+    }
+
     /// Record a trade
     pub fn record_trade(
         &mut self,
@@ -120,11 +452,28 @@ impl TradeLimiter {
         price: Decimal,
         quantity: Decimal,
     ) -> Result<()> {
-        let today = Self::today_string();
-        
-        // Reset if new day
-        if self.current_state.date != today {
-            self.current_state = DailyTradingState::new_for_today();
+        self.record_position_trade(None, symbol, side, price, quantity, None)
+    }
+
+    /// Record a trade of one of several positions held in `symbol`, with why it was made
+    pub fn record_position_trade(
+        &mut self,
+        position_id: Option<&str>,
+        symbol: &str,
+        side: &str,
+        price: Decimal,
+        quantity: Decimal,
+        context: Option<TradeContext>,
+    ) -> Result<()> {
+        match self.day_boundary {
+            DayBoundary::Calendar(_) => {
+                // Reset if new day
+                let today = self.today_string();
+                if self.current_state.date != today {
+                    self.current_state = DailyTradingState::new_for_date(today);
+                }
+            }
+            DayBoundary::Rolling24h => self.prune_rolling_window(),
         }
 
         let is_first = self.current_state.trades_today.is_empty();
@@ -136,22 +485,29 @@ impl TradeLimiter {
             price,
             quantity,
             is_first_trade: is_first,
+            position_id: position_id.map(str::to_string),
+            context,
         };
 
         self.current_state.trades_today.push(record);
-        
-        if is_first {
-            self.current_state.first_trade_executed = true;
-        } else {
-            self.current_state.second_trade_executed = true;
-        }
-
         self.save_state();
+
+        let counts = self.mode == LimitMode::Orders || side == "BUY";
+        let counted = self.counted_trades().len() as u32;
+        let limit_hit = counted >= self.max_trades_per_day;
+        self.update_history(|day| {
+            if counts {
+                day.trades += 1;
+            }
+            if limit_hit && day.limit_hit_at.is_none() {
+                day.limit_hit_at = Some(Utc::now());
+            }
+        });
         
         info!(
-            "Trade recorded: {} {} {} @ {}. Trades today: {}/{}",
-            side, quantity, symbol, price,
-            self.current_state.trades_today.len(),
+            "Trade recorded: {} {} {}{} @ {}. Trades today: {}/{}",
+            side, quantity, symbol, position_id.map_or(String::new(), |id| format!(" ({})", id)), price,
+            counted,
             self.max_trades_per_day
         );
 
@@ -160,42 +516,73 @@ impl TradeLimiter {
 
     /// Get current trading status
     pub fn get_status(&self) -> TradingStatus {
-        let today = Self::today_string();
-        
-        if self.current_state.date != today {
-            return TradingStatus {
-                date: today,
-                trades_executed: 0,
-                trades_remaining: 2,
-                first_trade: None,
-                second_trade: None,
-                daily_pnl: Decimal::ZERO,
-                can_trade: true,
-            };
-        }
+        let trades = self.counted_trades();
+        let trades_count = trades.len();
+        let can_trade = trades_count < self.max_trades_per_day as usize;
 
-        let trades_count = self.current_state.trades_today.len();
-        
         TradingStatus {
-            date: self.current_state.date.clone(),
+            date: self.today_string(),
             trades_executed: trades_count as u32,
             trades_remaining: (self.max_trades_per_day as usize).saturating_sub(trades_count) as u32,
-            first_trade: self.current_state.trades_today.first().cloned(),
-            second_trade: self.current_state.trades_today.get(1).cloned(),
-            daily_pnl: self.current_state.daily_pnl,
-            can_trade: trades_count < self.max_trades_per_day as usize,
+            max_trades: self.max_trades_per_day,
+            trades: trades.into_iter().cloned().collect(),
+            daily_pnl: self.window_pnl(),
+            can_trade,
+            next_trading_day: if can_trade { None } else { Some(self.next_trading_day()) },
         }
     }
 
-    /// Update daily P&L
+    /// Add a closed trade's realized P&L (net of fees) to today's total
     pub fn update_pnl(&mut self, pnl: Decimal) {
-        self.current_state.daily_pnl = pnl;
+        self.current_state.daily_pnl += pnl;
+        self.current_state.realized.push(RealizedPnl { timestamp: Utc::now(), pnl });
         self.save_state();
+        self.update_history(|day| day.pnl += pnl);
+    }
+
+    /// Why new positions stay closed for the rest of the day once realized P&L reaches
+    /// DAILY_PROFIT_TARGET; exits are unaffected. The first time each day, "target achieved" is
+    /// recorded as the day's halt reason.
+    pub fn profit_target_reached(&mut self) -> Option<String> {
+        let target = self.daily_profit_target?;
+        let pnl = self.get_status().daily_pnl;
+        if pnl < target {
+            return None;
+        }
+        let today = self.today_string();
+        if self.target_reached_on.as_deref() != Some(today.as_str()) {
+            info!("🔒 Daily profit target reached: ${:.2} >= ${} - no new positions for the rest of the day", pnl, target);
+            self.update_history(|day| day.halt_reason = Some(TARGET_ACHIEVED.to_string()));
+            self.target_reached_on = Some(today);
+        }
+        Some(format!("{} - day's realized P&L ${:.2} reached DAILY_PROFIT_TARGET ${}", TARGET_ACHIEVED, pnl, target))
+    }
+
+    /// Note a cycle in which the limit held back an entry at its target
+    pub fn record_blocked_entry(&mut self) {
+        self.update_history(|day| day.blocked_entries += 1);
     }
 
+    /// When trading is next allowed: local midnight for calendar days, or the time
+    /// the oldest trade leaves the window in rolling mode
     fn next_trading_day(&self) -> String {
-        let tomorrow = Utc::now() + chrono::Duration::days(1);
-        tomorrow.format("%Y-%m-%d").to_string()
+        match self.day_boundary {
+            DayBoundary::Calendar(tz) => {
+                let tomorrow = Utc::now().with_timezone(&tz).date_naive() + chrono::Duration::days(1);
+                if tz == Tz::UTC {
+                    tomorrow.format("%Y-%m-%d").to_string()
+                } else {
+                    format!("{} ({})", tomorrow.format("%Y-%m-%d"), tz.name())
+                }
+            }
+            DayBoundary::Rolling24h => self
+                .counted_trades()
+                .iter()
+                .map(|t| t.timestamp)
+                .min()
+                .map(|oldest| (oldest + chrono::Duration::hours(24)).format("%Y-%m-%d %H:%M UTC").to_string())
+                .unwrap_or_else(|| "now".to_string()),
+        }
     }
 
     /// Get trades for today
@@ -206,13 +593,12 @@ impl TradeLimiter {
 }
 
 impl DailyTradingState {
-    fn new_for_today() -> Self {
+    fn new_for_date(date: String) -> Self {
         Self {
-            date: Utc::now().format("%Y-%m-%d").to_string(),
+            date,
             trades_today: Vec::new(),
-            first_trade_executed: false,
-            second_trade_executed: false,
             daily_pnl: Decimal::ZERO,
+            realized: Vec::new(),
         }
     }
 }
@@ -232,7 +618,6 @@ pub enum TradePermission {
 }
 
 impl TradePermission {
-    #[allow(dead_code)]
     pub fn is_allowed(&self) -> bool {
         matches!(self, TradePermission::Allowed { .. })
     }
@@ -245,19 +630,22 @@ pub struct TradingStatus {
     pub date: String,
     pub trades_executed: u32,
     pub trades_remaining: u32,
-    pub first_trade: Option<TradeRecord>,
-    pub second_trade: Option<TradeRecord>,
+    pub max_trades: u32,
+    /// Trades counted against the limit, oldest first
+    pub trades: Vec<TradeRecord>,
     pub daily_pnl: Decimal,
     pub can_trade: bool,
+    pub next_trading_day: Option<String>,
 }
 
 impl std::fmt::Display for TradingStatus {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         write!(
             f,
-            "Date: {}, Trades: {}/2, P&L: ${:.2}, Can Trade: {}",
+            "Date: {}, Trades: {}/{}, P&L: ${:.2}, Can Trade: {}",
             self.date,
             self.trades_executed,
+            self.max_trades,
             self.daily_pnl,
             if self.can_trade { "Yes" } else { "No" }
         )
@@ -267,11 +655,146 @@ impl std::fmt::Display for TradingStatus {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use rust_decimal_macros::dec;
 
     #[test]
     fn test_new_limiter() {
-        let limiter = TradeLimiter::new("/tmp/test_trade_state.json");
+        let limiter = TradeLimiter::new(Path::new("/tmp/test_trade_state.json"), None);
+        assert!(limiter.can_trade().is_allowed());
+    }
+
+    #[test]
+    fn test_rolling_window_limit() {
+        let path = std::env::temp_dir().join(format!("ctb_rolling_{}.json", std::process::id()));
+        let settings = LimiterSettings {
+            max_trades_per_day: 2,
+            day_boundary: DayBoundary::Rolling24h,
+            ..Default::default()
+        };
+        let mut limiter = TradeLimiter::with_settings(&path, None, settings);
+        limiter.record_trade("BTCUSDT", "BUY", dec!(100), dec!(1)).unwrap();
+        limiter.record_trade("BTCUSDT", "SELL", dec!(110), dec!(1)).unwrap();
+        assert!(!limiter.can_trade().is_allowed());
+        assert!(limiter.get_status().next_trading_day.unwrap().ends_with("UTC"));
+        // Realized P&L accumulates over the day's closed trades
+        limiter.update_pnl(dec!(9.8));
+        limiter.update_pnl(dec!(-0.3));
+        assert_eq!(limiter.current_state.daily_pnl, dec!(9.5));
+
+        // Trades older than 24h no longer count
+        for trade in &mut limiter.current_state.trades_today {
+            trade.timestamp -= chrono::Duration::hours(25);
+        }
         assert!(limiter.can_trade().is_allowed());
+        std::fs::remove_file(&path).ok();
+    }
+
+    #[test]
+    fn test_round_trip_mode_allows_exits() {
+        let path = std::env::temp_dir().join(format!("ctb_round_trips_{}.json", std::process::id()));
+        let settings = LimiterSettings {
+            max_trades_per_day: 1,
+            mode: LimitMode::RoundTrips,
+            ..Default::default()
+        };
+        let mut limiter = TradeLimiter::with_settings(&path, None, settings);
+        limiter.record_trade("BTCUSDT", "BUY", dec!(100), dec!(1)).unwrap();
+
+        // The entry used the quota, but the exit is still allowed and doesn't count
+        assert!(!limiter.can_trade().is_allowed());
+        assert!(limiter.can_exit().is_allowed());
+        limiter.record_trade("BTCUSDT", "SELL", dec!(95), dec!(1)).unwrap();
+        let status = limiter.get_status();
+        assert_eq!((status.trades_executed, status.trades[0].side.as_str()), (1, "BUY"));
+        assert!(status.to_string().contains("Trades: 1/1"));
+        assert!(!limiter.can_trade().is_allowed());
+        std::fs::remove_file(&path).ok();
+    }
+
+    #[test]
+    fn test_trade_context_is_persisted() {
+        let path = std::env::temp_dir().join(format!("ctb_trade_context_{}.json", std::process::id()));
+        let context = TradeContext {
+            trigger: "Stop-loss".to_string(),
+            target_price: Some(dec!(95)),
+            rsi: Some(dec!(28.5)),
+            ai_reasoning_sha256: Some("ab12".to_string()),
+            ..Default::default()
+        };
+        let mut limiter = TradeLimiter::new(&path, None);
+        limiter.record_position_trade(Some("main"), "BTCUSDT", "SELL", dec!(94.8), dec!(1), Some(context.clone())).unwrap();
+
+        let reloaded = TradeLimiter::new(&path, None);
+        let trade = &reloaded.current_state.trades_today[0];
+        assert_eq!(trade.position_id.as_deref(), Some("main"));
+        assert_eq!(trade.context.as_ref(), Some(&context));
+        std::fs::remove_file(&path).ok();
+    }
+
+    #[test]
+    fn test_limit_history_is_kept_and_pruned() {
+        let path = std::env::temp_dir().join(format!("ctb_limit_history_{}.json", std::process::id()));
+        let settings = LimiterSettings { max_trades_per_day: 1, ..Default::default() };
+        let mut limiter = TradeLimiter::with_settings(&path, None, settings.clone());
+        limiter.record_trade("BTCUSDT", "BUY", dec!(100), dec!(1)).unwrap();
+        limiter.update_pnl(dec!(-2.5));
+        limiter.record_blocked_entry();
+
+        let reloaded = TradeLimiter::with_settings(&path, None, settings);
+        let day = &reloaded.history.days[0];
+        assert_eq!((day.trades, day.blocked_entries, day.pnl), (1, 1, dec!(-2.5)));
+        assert!(day.limit_hit_at.is_some());
+        assert!(reloaded.history.render().contains("limit hit on 1"));
+
+        let mut history = reloaded.history.clone();
+        history.days[0].date = "2025-01-01".to_string();
+        history.prune(NaiveDate::from_ymd_opt(2026, 1, 2).unwrap(), 365);
+        assert!(history.days.is_empty());
+        std::fs::remove_file(&path).ok();
+        std::fs::remove_file(path.with_file_name(format!("ctb_limit_history_{}_history.json", std::process::id()))).ok();
+    }
+
+    #[test]
+    fn test_profit_target_halts_entries_for_the_day() {
+        let path = std::env::temp_dir().join(format!("ctb_profit_target_{}.json", std::process::id()));
+        let settings = LimiterSettings { daily_profit_target: Some(dec!(50)), ..Default::default() };
+        let mut limiter = TradeLimiter::with_settings(&path, None, settings);
+        limiter.update_pnl(dec!(30));
+        assert_eq!(limiter.profit_target_reached(), None);
+        limiter.update_pnl(dec!(25));
+        assert!(limiter.profit_target_reached().unwrap().starts_with("target achieved - day's realized P&L $55.00"));
+        assert_eq!(limiter.history.days[0].halt_reason.as_deref(), Some(TARGET_ACHIEVED));
+        // The trade limit itself is untouched, so exits go on
+        assert!(limiter.can_exit().is_allowed());
+        std::fs::remove_file(&path).ok();
+        std::fs::remove_file(path.with_file_name(format!("ctb_profit_target_{}_history.json", std::process::id()))).ok();
+    }
+
+    #[test]
+    fn test_rolling_profit_target_expires() {
+        let path = std::env::temp_dir().join(format!("ctb_rolling_target_{}.json", std::process::id()));
+        let settings = LimiterSettings {
+            day_boundary: DayBoundary::Rolling24h,
+            daily_profit_target: Some(dec!(50)),
+            ..Default::default()
+        };
+        let mut limiter = TradeLimiter::with_settings(&path, None, settings.clone());
+        limiter.update_pnl(dec!(60));
+        assert!(limiter.profit_target_reached().is_some());
+
+        // A day later the profitable trade has left the window, so entries open again
+        limiter.current_state.realized[0].timestamp -= chrono::Duration::hours(25);
+        limiter.save_state();
+        assert_eq!(limiter.profit_target_reached(), None);
+        limiter.update_pnl(dec!(10));
+        assert_eq!(limiter.get_status().daily_pnl, dec!(10));
+        assert_eq!(limiter.profit_target_reached(), None);
+
+        // Pruning on a restart drops the expired P&L from the stored total as well
+        let limiter = TradeLimiter::with_settings(&path, None, settings);
+        assert_eq!(limiter.current_state.daily_pnl, dec!(10));
+        std::fs::remove_file(&path).ok();
+        std::fs::remove_file(path.with_file_name(format!("ctb_rolling_target_{}_history.json", std::process::id()))).ok();
     }
 
     #[test]
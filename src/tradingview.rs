@@ -0,0 +1,101 @@
+use crate::portfolio::PortfolioStatus;
+use rust_decimal::Decimal;
+use std::cmp::Reverse;
+use std::fmt::Write as _;
+
+/// One horizontal level: label, price and Pine Script colour
+struct Level {
+    label: &'static str,
+    price: Decimal,
+    color: &'static str,
+}
+
+/// The bot's targets and support/resistance levels, highest price first
+fn levels(s: &PortfolioStatus) -> Vec<Level> {
+    let candidates = [
+        ("Strong resistance", s.strong_resistance, "color.maroon"),
+        ("Resistance", s.resistance, "color.red"),
+        ("Take profit", s.take_profit_price, "color.lime"),
+        ("Sell target", s.sell_target_price, "color.orange"),
+        ("Pivot", s.pivot_point, "color.gray"),
+        ("Entry", s.entry_price, "color.yellow"),
+        ("Buy target", s.buy_target_price, "color.teal"),
+        ("Support", s.support, "color.green"),
+        ("Strong support", s.strong_support, "color.navy"),
+        ("Stop loss", s.stop_loss_price, "color.fuchsia"),
+    ];
+    let mut levels: Vec<Level> = candidates.into_iter()
+        .filter_map(|(label, price, color)| price.filter(|p| *p > Decimal::ZERO)
+            .map(|p| Level { label, price: p.round_dp(s.price_decimals).normalize(), color }))
+        .collect();
+    levels.sort_by_key(|l| Reverse(l.price));
+    levels
+}
+
+/// "BUY @ 72% confidence" when the AI gave a recommendation
+fn recommendation(s: &PortfolioStatus) -> Option<String> {
+    let recommendation = s.ai_recommendation.as_ref()?;
+    Some(match s.ai_confidence {
+        Some(confidence) => format!("{} @ {}% confidence", recommendation, confidence.round_dp(0)),
+        None => recommendation.to_string(),
+    })
+}
+
+/// Pine Script v5 indicator drawing every level as a labelled horizontal line; paste it
+/// into the Pine Editor and add it to the symbol's chart
+pub fn pine_script(s: &PortfolioStatus) -> String {
+    let mut out = String::new();
+    let _ = writeln!(out, "//@version=5");
+    let _ = writeln!(out, "// {} levels from the trading bot, {}", s.symbol, s.last_updated.format("%Y-%m-%d %H:%M UTC"));
+    if let Some(recommendation) = recommendation(s) {
+        let _ = writeln!(out, "// AI: {}", recommendation);
+    }
+    let _ = writeln!(out, "indicator(\"Bot levels {}\", overlay = true)", s.symbol);
+    for level in levels(s) {
+        let _ = writeln!(out, "hline({}, \"{}\", color = {}, linestyle = hline.style_dashed)",
+            level.price, level.label, level.color);
+    }
+    let _ = writeln!(out, "var table legend = table.new(position.top_right, 1, 1)");
+    let _ = writeln!(out, "if barstate.islast");
+    let _ = writeln!(out, "    table.cell(legend, 0, 0, \"{}\", text_color = color.gray)",
+        recommendation(s).unwrap_or_else(|| "no AI recommendation".to_string()));
+    out
+}
+
+/// `price,label` per level, for tools that import plain level lists
+pub fn levels_csv(s: &PortfolioStatus) -> String {
+    let mut out = String::from("price,label\n");
+    for level in levels(s) {
+        let _ = writeln!(out, "{},{}", level.price, level.label);
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ai_advisor::TradingRecommendation;
+    use rust_decimal_macros::dec;
+
+    #[test]
+    fn test_levels_export_sorted_with_labels() {
+        let mut status = PortfolioStatus::new("BTCUSDT", true);
+        status.price_decimals = 2;
+        status.support = Some(dec!(61000.123));
+        status.resistance = Some(dec!(65000));
+        status.stop_loss_price = Some(dec!(60000));
+        status.take_profit_price = Some(dec!(66000));
+        status.pivot_point = Some(Decimal::ZERO);
+        status.ai_recommendation = Some(TradingRecommendation::Buy);
+        status.ai_confidence = Some(dec!(72.4));
+
+        assert_eq!(levels_csv(&status),
+            "price,label\n66000,Take profit\n65000,Resistance\n61000.12,Support\n60000,Stop loss\n");
+        let pine = pine_script(&status);
+        assert!(pine.starts_with("//@version=5\n"));
+        assert!(pine.contains("// AI: BUY @ 72% confidence\n"));
+        assert!(pine.contains("indicator(\"Bot levels BTCUSDT\", overlay = true)"));
+        assert!(pine.contains("hline(61000.12, \"Support\", color = color.green, linestyle = hline.style_dashed)"));
+        assert!(!pine.contains("Pivot"));
+    }
+}
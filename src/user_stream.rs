@@ -0,0 +1,157 @@
+use crate::events::{BotEvent, EventBus};
+use crate::exchange::ExchangeClient;
+use crate::models::OrderSide;
+use anyhow::{anyhow, Result};
+use futures_util::{SinkExt, StreamExt};
+use rust_decimal::Decimal;
+use serde_json::Value;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio_tungstenite::tungstenite::Message;
+use tracing::{debug, info, warn};
+
+/// Listen keys expire after 60 minutes without a keepalive
+const KEEPALIVE_INTERVAL: Duration = Duration::from_secs(30 * 60);
+/// Binance closes every connection after 24h; reconnect a bit earlier
+const MAX_CONNECTION_AGE: Duration = Duration::from_secs(23 * 60 * 60);
+const MAX_RECONNECT_DELAY_SECS: u64 = 60;
+
+/// Binance user-data stream: real-time order and balance events published on the event bus.
+///
+/// Runs in the background: creates a listen key, keeps it alive, and reconnects with a
+/// fresh key when the connection drops or the key expires.
+pub fn spawn(exchange: Arc<ExchangeClient>, ws_url: String, bus: EventBus) {
+    tokio::spawn(async move {
+        let mut delay = 1;
+        loop {
+            match run_session(&exchange, &ws_url, &bus).await {
+                Ok(()) => delay = 1,
+                Err(e) => {
+                    warn!("⚠️ User-data stream error: {}", e);
+                    delay = (delay * 2).min(MAX_RECONNECT_DELAY_SECS);
+                }
+            }
+            bus.publish(BotEvent::StreamStatus { connected: false });
+            tokio::time::sleep(Duration::from_secs(delay)).await;
+        }
+    });
+}
+
+async fn run_session(exchange: &ExchangeClient, ws_url: &str, bus: &EventBus) -> Result<()> {
+    let listen_key = exchange.create_listen_key().await?;
+    let url = format!("{}/{}", ws_url.trim_end_matches('/'), listen_key);
+    let (mut ws, _) = tokio_tungstenite::connect_async(url.as_str())
+        .await
+        .map_err(|e| anyhow!("connect failed: {}", e))?;
+    info!("🔌 User-data stream connected");
+    bus.publish(BotEvent::StreamStatus { connected: true });
+
+    let mut keepalive = tokio::time::interval(KEEPALIVE_INTERVAL);
+    keepalive.tick().await;
+    let expires = tokio::time::sleep(MAX_CONNECTION_AGE);
+    tokio::pin!(expires);
+
+    let result = loop {
+        tokio::select! {
+            _ = keepalive.tick() => {
+                if let Err(e) = exchange.keepalive_listen_key(&listen_key).await {
+                    break Err(anyhow!("listen key keepalive failed: {}", e));
+                }
+                debug!("User-data listen key kept alive");
+            }
+            _ = &mut expires => {
+                info!("🔄 Refreshing user-data stream connection");
+                break Ok(());
+            }
+            message = ws.next() => match message {
+                Some(Ok(Message::Text(text))) => {
+                    let payload: Value = match serde_json::from_str(&text) {
+                        Ok(v) => v,
+                        Err(e) => {
+                            debug!("Ignoring unparseable stream message: {}", e);
+                            continue;
+                        }
+                    };
+                    if payload["e"] == "listenKeyExpired" {
+                        break Err(anyhow!("listen key expired"));
+                    }
+                    for event in parse_event(&payload) {
+                        bus.publish(event);
+                    }
+                }
+                Some(Ok(Message::Ping(data))) => {
+                    ws.send(Message::Pong(data)).await.ok();
+                }
+                Some(Ok(Message::Close(_))) | None => break Err(anyhow!("connection closed")),
+                Some(Ok(_)) => {}
+                Some(Err(e)) => break Err(anyhow!("websocket error: {}", e)),
+            }
+        }
+    };
+
+    exchange.close_listen_key(&listen_key).await.ok();
+    result
+}
+
+fn decimal(value: &Value) -> Decimal {
+    value.as_str().and_then(|v| v.parse().ok()).unwrap_or_default()
+}
+
+/// Translate a user-data stream payload into bus events
+fn parse_event(payload: &Value) -> Vec<BotEvent> {
+    match payload["e"].as_str() {
+        Some("executionReport") => {
+            let side = match payload["S"].as_str() {
+                Some("SELL") => OrderSide::Sell,
+                _ => OrderSide::Buy,
+            };
+            vec![BotEvent::OrderUpdate {
+                symbol: payload["s"].as_str().unwrap_or_default().to_string(),
+                order_id: payload["i"].as_i64().unwrap_or_default(),
+                side,
+                status: payload["X"].as_str().unwrap_or_default().to_string(),
+                last_filled_qty: decimal(&payload["l"]),
+                last_filled_price: decimal(&payload["L"]),
+                cumulative_qty: decimal(&payload["z"]),
+                commission: decimal(&payload["n"]),
+                commission_asset: payload["N"].as_str().map(|s| s.to_string()),
+            }]
+        }
+        Some("outboundAccountPosition") => payload["B"]
+            .as_array()
+            .map(|balances| {
+                balances
+                    .iter()
+                    .map(|b| BotEvent::BalanceUpdate {
+                        asset: b["a"].as_str().unwrap_or_default().to_string(),
+                        free: decimal(&b["f"]),
+                        locked: decimal(&b["l"]),
+                    })
+                    .collect()
+            })
+            .unwrap_or_default(),
+        _ => Vec::new(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rust_decimal_macros::dec;
+
+    #[test]
+    fn test_parse_events() {
+        let fill: Value = serde_json::from_str(r#"{"e":"executionReport","s":"BTCUSDT","i":42,"S":"BUY",
+            "X":"FILLED","l":"0.001","L":"42000.5","z":"0.001","n":"0.00000100","N":"BTC"}"#).unwrap();
+        match &parse_event(&fill)[0] {
+            BotEvent::OrderUpdate { order_id, status, last_filled_price, .. } => {
+                assert_eq!((*order_id, status.as_str(), *last_filled_price), (42, "FILLED", dec!(42000.5)));
+            }
+            other => panic!("unexpected event {:?}", other),
+        }
+
+        let balances: Value = serde_json::from_str(r#"{"e":"outboundAccountPosition",
+            "B":[{"a":"USDT","f":"958.0","l":"0"},{"a":"BTC","f":"0.001","l":"0"}]}"#).unwrap();
+        assert_eq!(parse_event(&balances).len(), 2);
+    }
+}
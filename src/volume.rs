@@ -0,0 +1,145 @@
+use crate::config::Config;
+use crate::error::BotError;
+use crate::exchange::Exchange;
+use crate::models::Kline;
+use anyhow::Result;
+use rust_decimal::Decimal;
+use std::time::{Duration, Instant};
+use tracing::{debug, warn};
+
+/// The hourly candles change slowly enough that once a minute is plenty
+const POLL_INTERVAL: Duration = Duration::from_secs(60);
+
+/// Volume of the last candle over the average of the `lookback` candles before it
+pub fn volume_ratio(klines: &[Kline], lookback: usize) -> Option<Decimal> {
+    let (last, earlier) = klines.split_last()?;
+    if lookback == 0 || earlier.len() < lookback {
+        return None;
+    }
+    let average = earlier.iter().rev().take(lookback).map(|k| k.volume).sum::<Decimal>() / Decimal::from(lookback);
+    (average > Decimal::ZERO).then(|| (last.volume / average).round_dp(2))
+}
+
+/// An hour trading at a multiple of its usual volume
+#[derive(Debug, Clone, PartialEq)]
+pub struct VolumeSpike {
+    /// Open time of the spiking candle
+    pub open_time: i64,
+    pub volume: Decimal,
+    pub average: Decimal,
+    pub ratio: Decimal,
+}
+
+/// Watches the traded symbol's hourly volume (VOLUME_SPIKE_ENABLED) for unusual activity and,
+/// with VOLUME_CONFIRMATION, holds buy targets back until volume confirms the move
+pub struct VolumeMonitor {
+    multiple: Decimal,
+    lookback: usize,
+    confirmation: bool,
+    last_poll: Option<Instant>,
+    /// Current hour's volume over the trailing average, from the last poll
+    ratio: Option<Decimal>,
+    /// Open time of the last candle reported as a spike, so each hour is reported once
+    reported: Option<i64>,
+}
+
+impl VolumeMonitor {
+    /// `None` unless VOLUME_SPIKE_ENABLED or VOLUME_CONFIRMATION is set
+    pub fn from_config(config: &Config) -> Result<Option<Self>> {
+        if !config.volume_spike_enabled && !config.volume_confirmation {
+            return Ok(None);
+        }
+        if config.volume_spike_multiple <= Decimal::ONE || config.volume_spike_lookback_hours == 0 {
+            return Err(BotError::config("VOLUME_SPIKE_MULTIPLE must be above 1 and VOLUME_SPIKE_LOOKBACK_HOURS positive").into());
+        }
+        Ok(Some(Self {
+            multiple: config.volume_spike_multiple,
+            lookback: config.volume_spike_lookback_hours,
+            confirmation: config.volume_confirmation,
+            last_poll: None,
+            ratio: None,
+            reported: None,
+        }))
+    }
+
+    /// Refresh the ratio from `exchange`'s hourly candles (at most once a minute); returns a
+    /// spike the first time an hour crosses the multiple
+    pub async fn poll(&mut self, exchange: &impl Exchange, symbol: &str) -> Option<VolumeSpike> {
+        if self.last_poll.is_some_and(|t| t.elapsed() < POLL_INTERVAL) {
+            return None;
+        }
+        self.last_poll = Some(Instant::now());
+        match exchange.get_klines(symbol, "1h", self.lookback as u32 + 1).await {
+            Ok(klines) => self.update(&klines),
+            Err(e) => {
+                warn!("⚠️ Volume check failed: {}", e);
+                None
+            }
+        }
+    }
+
+    fn update(&mut self, klines: &[Kline]) -> Option<VolumeSpike> {
+        self.ratio = volume_ratio(klines, self.lookback);
+        debug!("📊 Hourly volume ratio: {:?}", self.ratio);
+        let ratio = self.ratio.filter(|r| *r >= self.multiple)?;
+        let last = klines.last()?;
+        if self.reported == Some(last.open_time) {
+            return None;
+        }
+        self.reported = Some(last.open_time);
+        Some(VolumeSpike { open_time: last.open_time, volume: last.volume, average: last.volume / ratio, ratio })
+    }
+
+    pub fn ratio(&self) -> Option<Decimal> {
+        self.ratio
+    }
+
+    pub fn is_spiking(&self) -> bool {
+        self.ratio.is_some_and(|r| r >= self.multiple)
+    }
+
+    /// Whether a buy target may be acted on; always true without VOLUME_CONFIRMATION
+    pub fn confirms_entry(&self) -> bool {
+        !self.confirmation || self.is_spiking()
+    }
+
+    /// Volume line for the AI prompt
+    pub fn format_for_ai(&self) -> Option<String> {
+        let ratio = self.ratio?;
+        let flag = if self.is_spiking() { "UNUSUAL ACTIVITY - volume spike" } else { "normal" };
+        Some(format!("Current hour volume is {}x the {}h average ({})", ratio, self.lookback, flag))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rust_decimal_macros::dec;
+
+    fn candle(open_time: i64, volume: Decimal) -> Kline {
+        Kline { open_time, open: dec!(1), high: dec!(1), low: dec!(1), close: dec!(1), volume, close_time: open_time + 3_599_999 }
+    }
+
+    #[test]
+    fn test_spikes_are_reported_once_per_hour_and_gate_entries() {
+        let mut config = Config::defaults();
+        config.volume_confirmation = true;
+        let mut monitor = VolumeMonitor::from_config(&config).unwrap().unwrap();
+        let mut klines: Vec<Kline> = (0..21).map(|i| candle(i, dec!(100))).collect();
+
+        assert_eq!(monitor.update(&klines), None);
+        assert_eq!(monitor.ratio(), Some(dec!(1)));
+        assert!(!monitor.confirms_entry());
+
+        klines.last_mut().unwrap().volume = dec!(450);
+        let spike = monitor.update(&klines).unwrap();
+        assert_eq!((spike.ratio, spike.average), (dec!(4.5), dec!(100)));
+        assert!(monitor.confirms_entry());
+        assert!(monitor.format_for_ai().unwrap().contains("UNUSUAL ACTIVITY"));
+        // Same hour, still spiking: not reported again
+        assert_eq!(monitor.update(&klines), None);
+        assert!(monitor.is_spiking());
+
+        assert_eq!(volume_ratio(&klines[..10], 20), None);
+    }
+}
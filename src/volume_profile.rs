@@ -0,0 +1,186 @@
+use crate::ai_advisor::AiTradingTargets;
+use crate::config::Config;
+use crate::error::BotError;
+use crate::exchange::Exchange;
+use crate::models::Kline;
+use anyhow::Result;
+use rust_decimal::Decimal;
+use rust_decimal_macros::dec;
+use serde::Serialize;
+use std::time::{Duration, Instant};
+use tracing::{debug, warn};
+
+/// The profile moves slowly; hourly candles are re-read at most this often
+const POLL_INTERVAL: Duration = Duration::from_secs(300);
+/// Price buckets between the lowest low and the highest high
+const BUCKETS: usize = 24;
+/// Share of the volume, in percent, the value area holds
+const VALUE_AREA_PERCENT: Decimal = dec!(70);
+
+/// Where the volume of recent candles traded: the busiest price and the range around it
+/// holding 70% of the volume
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct VolumeProfile {
+    /// Middle of the bucket with the most volume
+    pub point_of_control: Decimal,
+    pub value_area_low: Decimal,
+    pub value_area_high: Decimal,
+    pub hours: usize,
+}
+
+impl VolumeProfile {
+    /// Spread each candle's volume evenly over the buckets its range covers; `None` without
+    /// a price range or volume
+    pub fn from_klines(klines: &[Kline]) -> Option<Self> {
+        let low = klines.iter().map(|k| k.low).min()?;
+        let high = klines.iter().map(|k| k.high).max()?;
+        if high <= low {
+            return None;
+        }
+        let step = (high - low) / Decimal::from(BUCKETS);
+        let bucket = |price: Decimal| (((price - low) / step).floor().try_into().unwrap_or(0usize)).min(BUCKETS - 1);
+        let mut volume = [Decimal::ZERO; BUCKETS];
+        for k in klines {
+            let (first, last) = (bucket(k.low), bucket(k.high));
+            let share = k.volume / Decimal::from(last - first + 1);
+            volume[first..=last].iter_mut().for_each(|v| *v += share);
+        }
+        let total: Decimal = volume.iter().sum();
+        if total <= Decimal::ZERO {
+            return None;
+        }
+
+        let poc = (0..BUCKETS).max_by(|a, b| volume[*a].cmp(&volume[*b]).then(b.cmp(a)))?;
+        // Grow from the POC toward the busier neighbour until the area holds its share
+        let (mut from, mut to, mut inside) = (poc, poc, volume[poc]);
+        while inside < total * VALUE_AREA_PERCENT / dec!(100) {
+            let below = (from > 0).then(|| volume[from - 1]);
+            let above = (to + 1 < BUCKETS).then(|| volume[to + 1]);
+            match (below, above) {
+                (Some(b), Some(a)) if b > a => from -= 1,
+                (_, Some(_)) => to += 1,
+                (Some(_), None) => from -= 1,
+                (None, None) => break,
+            }
+            inside = volume[from..=to].iter().sum();
+        }
+        let edge = |i: usize| low + step * Decimal::from(i);
+        Some(Self {
+            point_of_control: (edge(poc) + step / dec!(2)).round_dp(8),
+            value_area_low: edge(from).round_dp(8),
+            value_area_high: edge(to + 1).round_dp(8),
+            hours: klines.len(),
+        })
+    }
+
+    /// Value-area low, point of control and value-area high, lowest first
+    pub fn levels(&self) -> [Decimal; 3] {
+        [self.value_area_low, self.point_of_control, self.value_area_high]
+    }
+
+    /// Fill the support, resistance and buy/sell targets nothing else set with the nearest
+    /// profile levels on each side of `price`
+    pub fn apply(&self, targets: &mut AiTradingTargets, price: Decimal) {
+        let mut below = self.levels().into_iter().rev().filter(|l| *l < price);
+        let (nearest, next) = (below.next(), below.next());
+        targets.support = targets.support.or(nearest);
+        targets.strong_support = targets.strong_support.or(next);
+        targets.buy_target_price = targets.buy_target_price.or(nearest);
+        let mut above = self.levels().into_iter().filter(|l| *l > price);
+        let (nearest, next) = (above.next(), above.next());
+        targets.resistance = targets.resistance.or(nearest);
+        targets.strong_resistance = targets.strong_resistance.or(next);
+        targets.sell_target_price = targets.sell_target_price.or(nearest);
+    }
+
+    /// Volume profile line for the AI prompt
+    pub fn format_for_ai(&self) -> String {
+        format!("Point of control ${:.2}, value area ${:.2} - ${:.2} (70% of the last {}h volume)",
+            self.point_of_control, self.value_area_low, self.value_area_high, self.hours)
+    }
+}
+
+/// The traded symbol's volume profile over the last VOLUME_PROFILE_HOURS hourly candles
+/// (VOLUME_PROFILE_ENABLED)
+pub struct VolumeProfiler {
+    hours: usize,
+    last_poll: Option<Instant>,
+    profile: Option<VolumeProfile>,
+}
+
+impl VolumeProfiler {
+    /// `None` unless VOLUME_PROFILE_ENABLED is set
+    pub fn from_config(config: &Config) -> Result<Option<Self>> {
+        if !config.volume_profile_enabled {
+            return Ok(None);
+        }
+        if !(2..=1000).contains(&config.volume_profile_hours) {
+            return Err(BotError::config(format!("VOLUME_PROFILE_HOURS must be between 2 and 1000, got {}",
+                config.volume_profile_hours)).into());
+        }
+        Ok(Some(Self { hours: config.volume_profile_hours, last_poll: None, profile: None }))
+    }
+
+    /// Recompute from `exchange`'s hourly candles, at most every five minutes; the previous
+    /// profile stays on failure
+    pub async fn poll(&mut self, exchange: &impl Exchange, symbol: &str) {
+        if self.last_poll.is_some_and(|t| t.elapsed() < POLL_INTERVAL) {
+            return;
+        }
+        self.last_poll = Some(Instant::now());
+        match exchange.get_klines(symbol, "1h", self.hours as u32).await {
+            Ok(klines) => {
+                self.profile = VolumeProfile::from_klines(&klines);
+                debug!("📊 Volume profile: {:?}", self.profile);
+            }
+            Err(e) => warn!("⚠️ Volume profile failed: {}", e),
+        }
+    }
+
+    pub fn profile(&self) -> Option<&VolumeProfile> {
+        self.profile.as_ref()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn candle(low: Decimal, high: Decimal, volume: Decimal) -> Kline {
+        Kline { open_time: 0, open: low, high, low, close: high, volume, close_time: 3_599_999 }
+    }
+
+    #[test]
+    fn test_point_of_control_and_value_area() {
+        // 100-124 in 1.0 buckets; most volume trades around 110
+        let mut klines = vec![candle(dec!(100), dec!(124), dec!(24))];
+        klines.extend((0..5).map(|_| candle(dec!(110), dec!(110.5), dec!(40))));
+        klines.push(candle(dec!(108), dec!(112.9), dec!(50)));
+        let profile = VolumeProfile::from_klines(&klines).unwrap();
+        assert_eq!(profile.point_of_control, dec!(110.5));
+        // 274 in all: 201 at 110, 10 each at 108, 109, 111 and 112, 1 elsewhere
+        assert_eq!((profile.value_area_low, profile.value_area_high), (dec!(110), dec!(111)));
+        assert!(profile.format_for_ai().starts_with("Point of control $110.50, value area $110.00 - $111.00"));
+
+        klines.push(candle(dec!(111), dec!(111.9), dec!(40)));
+        let profile = VolumeProfile::from_klines(&klines).unwrap();
+        assert_eq!((profile.value_area_low, profile.value_area_high), (dec!(110), dec!(112)));
+
+        // Only the levels nothing else set are filled
+        let mut targets = AiTradingTargets {
+            stop_loss_price: dec!(105), take_profit_price: dec!(120), buy_target_price: None,
+            sell_target_price: Some(dec!(118)), confidence: dec!(60), reasoning: String::new(),
+            recommendation: crate::ai_advisor::TradingRecommendation::Hold, support: None,
+            strong_support: None, resistance: None, strong_resistance: Some(dec!(119)),
+            pivot_point: None, allocation_percent: None, position_size_percent: None,
+        };
+        profile.apply(&mut targets, dec!(110.8));
+        assert_eq!((targets.buy_target_price, targets.support, targets.strong_support),
+                   (Some(dec!(110.5)), Some(dec!(110.5)), Some(dec!(110))));
+        assert_eq!((targets.sell_target_price, targets.resistance, targets.strong_resistance),
+                   (Some(dec!(118)), Some(dec!(112)), Some(dec!(119))));
+
+        assert_eq!(VolumeProfile::from_klines(&[candle(dec!(1), dec!(1), dec!(5))]), None);
+        assert_eq!(VolumeProfile::from_klines(&[]), None);
+    }
+}
@@ -0,0 +1,243 @@
+use crate::config::Config;
+use crate::error::BotError;
+use crate::metrics::Metrics;
+use anyhow::Result;
+use std::future::Future;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+use tracing::{error, warn};
+
+/// A hung runtime gets this long to drop its tasks (and release their ports) before the restart
+const SHUTDOWN_GRACE: Duration = Duration::from_secs(10);
+/// The heartbeat is checked at least this often
+const MAX_CHECK_INTERVAL: Duration = Duration::from_secs(5);
+/// A restarted loop may find the hung one's instance lock still held (the redis lock
+/// expires after 30s); it is tried again this often, this many times
+const LOCK_RETRY_INTERVAL: Duration = Duration::from_secs(10);
+const LOCK_RETRIES: u32 = 6;
+
+/// Timed parts of a monitoring cycle
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Stage {
+    /// Price, order book and market data
+    Fetch,
+    /// Candles and the SMA/RSI calculation
+    Indicators,
+    /// The AI analysis, when targets are recalculated
+    Ai,
+    /// Report rollover and write
+    Report,
+}
+
+impl Stage {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Stage::Fetch => "fetch",
+            Stage::Indicators => "indicators",
+            Stage::Ai => "ai",
+            Stage::Report => "report",
+        }
+    }
+}
+
+/// When the cycle in progress started and how many live orders it has in flight; shared
+/// between a loop and its supervisor
+#[derive(Debug, Clone, Default)]
+pub struct Heartbeat {
+    started: Arc<Mutex<Option<Instant>>>,
+    orders: Arc<AtomicUsize>,
+}
+
+impl Heartbeat {
+    /// A cycle starts
+    pub fn begin(&self) {
+        *self.started.lock().unwrap() = Some(Instant::now());
+    }
+
+    /// The cycle is done; the sleep until the next one doesn't count
+    pub fn end(&self) {
+        *self.started.lock().unwrap() = None;
+    }
+
+    /// A live order is about to be placed; until the guard drops the cycle is never
+    /// taken as hung, so a restart can't lose a fill that isn't recorded yet
+    pub fn order_in_flight(&self) -> OrderInFlight {
+        self.orders.fetch_add(1, Ordering::SeqCst);
+        OrderInFlight(self.orders.clone())
+    }
+
+    /// Whether the cycle in progress has run longer than `limit` with no order in flight
+    pub fn stalled(&self, limit: Duration) -> bool {
+        self.orders.load(Ordering::SeqCst) == 0
+            && self.started.lock().unwrap().is_some_and(|started| started.elapsed() > limit)
+    }
+}
+
+/// Holds the watchdog off while a live order is placed and its fill recorded
+#[must_use]
+pub struct OrderInFlight(Arc<AtomicUsize>);
+
+impl Drop for OrderInFlight {
+    fn drop(&mut self) {
+        self.0.fetch_sub(1, Ordering::SeqCst);
+    }
+}
+
+/// CYCLE_HARD_LIMIT_SECS, zero when off
+pub fn hard_limit(config: &Config) -> Duration {
+    Duration::from_secs(config.cycle_hard_limit_secs)
+}
+
+/// Times each stage of a cycle against its CYCLE_BUDGET_*_MS, warning on overruns and
+/// publishing the timings as metrics
+pub struct CycleTimer {
+    budgets: [Duration; 4],
+    heartbeat: Heartbeat,
+    metrics: Metrics,
+}
+
+impl CycleTimer {
+    pub fn from_config(config: &Config, heartbeat: Heartbeat, metrics: Metrics) -> Self {
+        Self {
+            budgets: [config.cycle_budget_fetch_ms, config.cycle_budget_indicators_ms,
+                      config.cycle_budget_ai_ms, config.cycle_budget_report_ms].map(Duration::from_millis),
+            heartbeat,
+            metrics,
+        }
+    }
+
+    pub fn budget(&self, stage: Stage) -> Duration {
+        self.budgets[stage as usize]
+    }
+
+    pub fn begin_cycle(&self) {
+        self.heartbeat.begin();
+    }
+
+    pub fn end_cycle(&self) {
+        self.heartbeat.end();
+    }
+
+    /// See [`Heartbeat::order_in_flight`]
+    pub fn order_in_flight(&self) -> OrderInFlight {
+        self.heartbeat.order_in_flight()
+    }
+
+    /// Record that `stage` took `elapsed`; true when it went over its budget
+    pub fn record(&self, stage: Stage, elapsed: Duration) -> bool {
+        let labels = [("stage", stage.as_str())];
+        self.metrics.set_labeled_gauge("bot_cycle_stage_seconds", "Duration of the last run of each cycle stage",
+                                       &labels, elapsed.as_secs_f64());
+        let budget = self.budget(stage);
+        if elapsed <= budget {
+            return false;
+        }
+        warn!("🐢 Cycle stage '{}' took {}ms, over its {}ms budget", stage.as_str(), elapsed.as_millis(), budget.as_millis());
+        self.metrics.inc_labeled_counter("bot_cycle_budget_overruns_total", "Cycle stages that went over their budget",
+                                         &labels);
+        true
+    }
+}
+
+/// Run the monitoring loop `run` under a watchdog (CYCLE_HARD_LIMIT_SECS): each attempt
+/// gets a runtime of its own, and a cycle running past the limit shuts that runtime down,
+/// with every task the loop spawned, and starts the loop again. The loop's own result is
+/// returned as is, unless it's a restart running into the previous instance lock.
+pub async fn supervise<F, Fut>(config: Config, run: F) -> Result<()>
+where
+    F: Fn(Config, Heartbeat) -> Fut,
+    Fut: Future<Output = Result<()>> + Send + 'static,
+{
+    let limit = hard_limit(&config);
+    if limit.is_zero() {
+        return run(config, Heartbeat::default()).await;
+    }
+    let check_interval = (limit / 4).min(MAX_CHECK_INTERVAL);
+    let (mut restarted, mut lock_retries) = (false, 0);
+    loop {
+        let heartbeat = Heartbeat::default();
+        let runtime = tokio::runtime::Builder::new_multi_thread().enable_all().build()?;
+        let mut task = runtime.spawn(run(config.clone(), heartbeat.clone()));
+        let result = loop {
+            tokio::select! {
+                result = &mut task => break Some(result.map_err(anyhow::Error::from).and_then(|r| r)),
+                _ = tokio::time::sleep(check_interval) => {
+                    if heartbeat.stalled(limit) {
+                        break None;
+                    }
+                }
+            }
+        };
+        tokio::task::spawn_blocking(move || runtime.shutdown_timeout(SHUTDOWN_GRACE)).await?;
+        match result {
+            Some(Err(e)) if restarted && lock_retries < LOCK_RETRIES
+                && matches!(BotError::find(&e), Some(BotError::InstanceLocked { .. })) => {
+                lock_retries += 1;
+                warn!("🐕 The restarted loop found the instance lock still held - retrying in {}s", LOCK_RETRY_INTERVAL.as_secs());
+                tokio::time::sleep(LOCK_RETRY_INTERVAL).await;
+            }
+            Some(result) => return result,
+            None => {
+                error!("🐕 A monitoring cycle ran past CYCLE_HARD_LIMIT_SECS ({}s) - restarting the loop", limit.as_secs());
+                (restarted, lock_retries) = (true, 0);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicU32, Ordering};
+
+    #[test]
+    fn test_stage_over_budget() {
+        let mut config = Config::defaults();
+        config.cycle_budget_report_ms = 100;
+        let metrics = Metrics::new();
+        let heartbeat = Heartbeat::default();
+        let timer = CycleTimer::from_config(&config, heartbeat.clone(), metrics.clone());
+        assert!(!timer.record(Stage::Report, Duration::from_millis(40)));
+        assert!(timer.record(Stage::Report, Duration::from_millis(250)));
+        let text = metrics.render();
+        assert!(text.contains("bot_cycle_stage_seconds{stage=\"report\"} 0.25\n"));
+        assert!(text.contains("bot_cycle_budget_overruns_total{stage=\"report\"} 1\n"));
+
+        timer.begin_cycle();
+        std::thread::sleep(Duration::from_millis(5));
+        assert!(!heartbeat.stalled(Duration::from_secs(60)));
+        assert!(heartbeat.stalled(Duration::from_millis(1)));
+        timer.end_cycle();
+        assert!(!heartbeat.stalled(Duration::ZERO));
+
+        // An order in flight holds the watchdog off until its fill is recorded
+        timer.begin_cycle();
+        std::thread::sleep(Duration::from_millis(5));
+        let order = timer.order_in_flight();
+        assert!(!heartbeat.stalled(Duration::from_millis(1)));
+        drop(order);
+        assert!(heartbeat.stalled(Duration::from_millis(1)));
+    }
+
+    #[tokio::test]
+    async fn test_hung_cycle_restarts_the_loop() {
+        let mut config = Config::defaults();
+        config.cycle_hard_limit_secs = 1;
+        let attempts = Arc::new(AtomicU32::new(0));
+        let counter = attempts.clone();
+        let result = supervise(config, move |_, heartbeat| {
+            let attempt = counter.fetch_add(1, Ordering::SeqCst);
+            async move {
+                heartbeat.begin();
+                if attempt == 0 {
+                    std::future::pending::<()>().await;
+                }
+                heartbeat.end();
+                Err(anyhow::anyhow!("stopped on attempt {}", attempt))
+            }
+        }).await;
+        assert_eq!(result.unwrap_err().to_string(), "stopped on attempt 1");
+        assert_eq!(attempts.load(Ordering::SeqCst), 2);
+    }
+}
@@ -0,0 +1,196 @@
+use crate::config::Config;
+use crate::error::{BotError, ReqwestExt};
+use crate::secrets::SecretString;
+use anyhow::Result;
+use chrono::{DateTime, Utc};
+use rust_decimal::Decimal;
+use serde::Deserialize;
+use std::time::{Duration, Instant};
+use tracing::{debug, warn};
+
+/// Whale Alert's free tier allows 10 requests a minute; once a minute is plenty
+const POLL_INTERVAL: Duration = Duration::from_secs(60);
+/// The free tier only serves the last hour
+const MAX_LOOKBACK_SECS: i64 = 3600;
+
+/// A large on-chain transfer into an exchange wallet
+#[derive(Debug, Clone, PartialEq)]
+pub struct WhaleTransfer {
+    pub asset: String,
+    pub amount: Decimal,
+    pub value_usd: Decimal,
+    pub exchange: String,
+    pub hash: String,
+    pub timestamp: DateTime<Utc>,
+}
+
+#[derive(Debug, Deserialize)]
+struct TransactionsResponse {
+    #[serde(default)]
+    transactions: Vec<Transaction>,
+}
+
+#[derive(Debug, Deserialize)]
+struct Transaction {
+    symbol: String,
+    hash: String,
+    timestamp: i64,
+    amount: f64,
+    amount_usd: f64,
+    from: Party,
+    to: Party,
+}
+
+#[derive(Debug, Deserialize)]
+struct Party {
+    #[serde(default)]
+    owner: Option<String>,
+    #[serde(default)]
+    owner_type: Option<String>,
+}
+
+impl Party {
+    fn is_exchange(&self) -> bool {
+        self.owner_type.as_deref() == Some("exchange")
+    }
+}
+
+/// Watches Whale Alert for large transfers of the traded asset into exchanges (potential
+/// sell pressure) and optionally pauses new entries for a cooldown after one
+pub struct WhaleMonitor {
+    client: reqwest::Client,
+    base_url: String,
+    api_key: SecretString,
+    min_value_usd: Decimal,
+    cooldown: Option<chrono::Duration>,
+    last_poll: Option<Instant>,
+    /// Unix seconds of the newest transfer seen
+    cursor: i64,
+    paused_until: Option<(DateTime<Utc>, String)>,
+}
+
+impl WhaleMonitor {
+    /// `None` without WHALE_ALERT_API_KEY
+    pub fn from_config(config: &Config) -> Result<Option<Self>> {
+        let Some(api_key) = config.whale_alert_api_key.clone() else {
+            return Ok(None);
+        };
+        if config.whale_min_value_usd < Decimal::from(500_000) {
+            return Err(BotError::config("WHALE_MIN_VALUE_USD must be at least 500000 (Whale Alert's minimum)").into());
+        }
+        Ok(Some(Self {
+            client: reqwest::Client::builder().timeout(Duration::from_secs(15)).build()?,
+            base_url: "https://api.whale-alert.io/v1".to_string(),
+            api_key,
+            min_value_usd: config.whale_min_value_usd,
+            cooldown: (config.whale_pause_minutes > 0).then(|| chrono::Duration::minutes(config.whale_pause_minutes as i64)),
+            last_poll: None,
+            cursor: Utc::now().timestamp() - POLL_INTERVAL.as_secs() as i64,
+            paused_until: None,
+        }))
+    }
+
+    /// New exchange inflows of `asset` since the last poll (at most once a minute)
+    pub async fn poll(&mut self, asset: &str) -> Vec<WhaleTransfer> {
+        if self.last_poll.is_some_and(|t| t.elapsed() < POLL_INTERVAL) {
+            return Vec::new();
+        }
+        self.last_poll = Some(Instant::now());
+
+        let start = self.cursor.max(Utc::now().timestamp() - MAX_LOOKBACK_SECS) + 1;
+        let transfers = match self.fetch(asset, start).await {
+            Ok(response) => exchange_inflows(response, asset, self.min_value_usd),
+            Err(e) => {
+                warn!("⚠️ Whale Alert unavailable: {}", e);
+                return Vec::new();
+            }
+        };
+        debug!("🐋 {} {} transfers since {}", transfers.len(), asset, start);
+
+        if let Some(latest) = transfers.iter().map(|t| t.timestamp.timestamp()).max() {
+            self.cursor = self.cursor.max(latest);
+        }
+        if let (Some(cooldown), Some(last)) = (self.cooldown, transfers.last()) {
+            let until = Utc::now() + cooldown;
+            let reason = format!("🐋 {} {} (${}) moved to {} - cooling down until {}",
+                last.amount.round_dp(2), last.asset, last.value_usd.round_dp(0), last.exchange, until.format("%H:%M UTC"));
+            self.paused_until = Some((until, reason));
+        }
+        transfers
+    }
+
+    /// Why entries are paused, while a whale cooldown is active
+    pub fn pause_reason(&mut self) -> Option<String> {
+        if self.paused_until.as_ref().is_some_and(|(until, _)| Utc::now() >= *until) {
+            self.paused_until = None;
+        }
+        self.paused_until.as_ref().map(|(_, reason)| reason.clone())
+    }
+
+    async fn fetch(&self, asset: &str, start: i64) -> Result<TransactionsResponse> {
+        let url = format!("{}/transactions?api_key={}&min_value={}&start={}&currency={}",
+            self.base_url, self.api_key.expose(), self.min_value_usd.trunc(), start, asset.to_lowercase());
+        let response = self.client.get(&url).send().await
+            .map_err(|e| e.without_url())
+            .or_data_source("Whale Alert")?;
+        let status = response.status();
+        if !status.is_success() {
+            let body = response.text().await.unwrap_or_default();
+            return Err(BotError::data_source("Whale Alert", status, &body).into());
+        }
+        Ok(response.json().await.or_data_source("Whale Alert")?)
+    }
+}
+
+/// Transfers of `asset` worth at least `min_value_usd` from a non-exchange wallet into an exchange
+fn exchange_inflows(response: TransactionsResponse, asset: &str, min_value_usd: Decimal) -> Vec<WhaleTransfer> {
+    let mut transfers: Vec<WhaleTransfer> = response.transactions.into_iter()
+        .filter(|t| t.symbol.eq_ignore_ascii_case(asset) && t.to.is_exchange() && !t.from.is_exchange())
+        .filter_map(|t| {
+            let value_usd = Decimal::try_from(t.amount_usd).ok()?;
+            (value_usd >= min_value_usd).then(|| WhaleTransfer {
+                asset: asset.to_uppercase(),
+                amount: Decimal::try_from(t.amount).unwrap_or_default(),
+                value_usd,
+                exchange: t.to.owner.unwrap_or_else(|| "unknown exchange".to_string()),
+                hash: t.hash,
+                timestamp: DateTime::from_timestamp(t.timestamp, 0).unwrap_or_else(Utc::now),
+            })
+        })
+        .collect();
+    transfers.sort_by_key(|t| t.timestamp);
+    transfers
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rust_decimal_macros::dec;
+
+    #[test]
+    fn test_only_large_inflows_to_exchanges_count() {
+        let response: TransactionsResponse = serde_json::from_value(serde_json::json!({
+            "result": "success",
+            "transactions": [
+                {"symbol": "btc", "hash": "a", "timestamp": 1_700_000_100, "amount": 500.0, "amount_usd": 30_000_000.0,
+                 "from": {"owner_type": "unknown"}, "to": {"owner": "binance", "owner_type": "exchange"}},
+                // Exchange to exchange is internal shuffling
+                {"symbol": "btc", "hash": "b", "timestamp": 1_700_000_200, "amount": 500.0, "amount_usd": 30_000_000.0,
+                 "from": {"owner": "coinbase", "owner_type": "exchange"}, "to": {"owner": "binance", "owner_type": "exchange"}},
+                // Outflow to cold storage
+                {"symbol": "btc", "hash": "c", "timestamp": 1_700_000_300, "amount": 500.0, "amount_usd": 30_000_000.0,
+                 "from": {"owner": "binance", "owner_type": "exchange"}, "to": {"owner_type": "unknown"}},
+                {"symbol": "btc", "hash": "d", "timestamp": 1_700_000_050, "amount": 100.0, "amount_usd": 6_000_000.0,
+                 "from": {"owner_type": "unknown"}, "to": {"owner": "kraken", "owner_type": "exchange"}},
+                {"symbol": "eth", "hash": "e", "timestamp": 1_700_000_400, "amount": 9000.0, "amount_usd": 30_000_000.0,
+                 "from": {"owner_type": "unknown"}, "to": {"owner": "binance", "owner_type": "exchange"}}
+            ]
+        })).unwrap();
+
+        let transfers = exchange_inflows(response, "BTC", dec!(10_000_000));
+        assert_eq!(transfers.len(), 1);
+        assert_eq!(transfers[0].hash, "a");
+        assert_eq!(transfers[0].exchange, "binance");
+        assert_eq!(transfers[0].value_usd, dec!(30000000));
+    }
+}
@@ -0,0 +1,266 @@
+//! End-to-end cycles against mocked CoinGecko, Binance and Ollama servers
+
+use crypto_trading_bot::coingecko::CoinGeckoClient;
+use crypto_trading_bot::{
+    BotError, Config, ErrorKind, ExchangeClient, FallbackTargetCalculator, GenerationOptions, MarketContext, OllamaClient,
+    OrderSide, OrderType, PortfolioReporter, SimulationExchange, SmaCrossover, RsiStrategy, TradingRecommendation,
+};
+use rust_decimal::Decimal;
+use rust_decimal_macros::dec;
+use serde_json::json;
+use wiremock::matchers::{body_partial_json, method, path, query_param};
+use wiremock::{Mock, MockServer, ResponseTemplate};
+
+/// 48 hourly points rising from 58,000 to 60,350
+fn hourly_chart() -> serde_json::Value {
+    let start = 1_700_000_000_000_i64;
+    let prices: Vec<_> = (0..48).map(|i| json!([start + i * 3_600_000, 58_000.0 + i as f64 * 50.0])).collect();
+    json!({ "prices": prices })
+}
+
+async fn mock_coingecko() -> MockServer {
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/coins/markets"))
+        .and(query_param("ids", "bitcoin"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!([{
+            "id": "bitcoin",
+            "symbol": "btc",
+            "name": "Bitcoin",
+            "current_price": 60350.0,
+            "high_24h": 60400.0,
+            "low_24h": 59100.0,
+            "price_change_percentage_24h": 1.9,
+            "market_cap": 1.2e12,
+            "total_volume": 3.1e10
+        }])))
+        .mount(&server)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/coins/bitcoin/market_chart"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(hourly_chart()))
+        .mount(&server)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/global"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({ "data": {
+            "total_market_cap": { "usd": 2.4e12, "eur": 2.2e12 },
+            "market_cap_percentage": { "btc": 52.4, "eth": 16.9 },
+            "market_cap_change_percentage_24h_usd": -1.25
+        }})))
+        .mount(&server)
+        .await;
+    server
+}
+
+async fn mock_ollama() -> MockServer {
+    let server = MockServer::start().await;
+    Mock::given(method("POST"))
+        .and(path("/api/generate"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+            "response": "RECOMMENDATION: BUY\nCONFIDENCE: 72%\nSTOP_LOSS: $57000\nTAKE_PROFIT: $66000\n\
+                         BUY_TARGET: $60000\nSELL_TARGET: $65000\nREASONING: steady uptrend"
+        })))
+        .mount(&server)
+        .await;
+    server
+}
+
+fn context_from(market: &crypto_trading_bot::coingecko::CoinGeckoMarketData, balance: Decimal) -> MarketContext {
+    let closes: Vec<Decimal> = market.hourly_data_24h.iter().map(|d| d.close).collect();
+    MarketContext {
+        symbol: market.symbol.clone(),
+        current_price: market.current_price,
+        high_24h: market.high_24h,
+        low_24h: market.low_24h,
+        price_change_24h_percent: market.price_change_24h_percent,
+        sma_short: SmaCrossover::calculate_sma(&closes, 10),
+        sma_long: SmaCrossover::calculate_sma(&closes, 20),
+        rsi: RsiStrategy::calculate_rsi(&closes, 14),
+        volume_24h: Some(market.total_volume),
+        position_entry_price: None,
+        account_balance: balance,
+        hourly_data_summary: None,
+        high_12h: market.hourly_data_12h.iter().map(|h| h.high).max(),
+        low_12h: market.hourly_data_12h.iter().map(|h| h.low).min(),
+        high_48h: market.hourly_data_48h.iter().map(|h| h.high).max(),
+        low_48h: market.hourly_data_48h.iter().map(|h| h.low).min(),
+        options_summary: None,
+        volume_summary: None,
+        divergence_summary: None,
+        volume_profile_summary: None,
+        btc_dominance: None,
+        market_cap_change_24h_percent: None,
+    }
+}
+
+#[tokio::test]
+async fn simulation_cycle_with_mocked_data_and_ai() {
+    let coingecko_server = mock_coingecko().await;
+    let ollama_server = mock_ollama().await;
+
+    let mut config = Config::defaults();
+    config.simulation_mode = true;
+    let exchange = SimulationExchange::new(&config).await.unwrap();
+    let report_path = std::env::temp_dir().join(format!("mock_cycle_report_{}.txt", std::process::id()));
+    let mut reporter = PortfolioReporter::new("BTCUSDT", true, &report_path);
+
+    // Market data stage
+    let coingecko = CoinGeckoClient::with_base_url(&coingecko_server.uri());
+    let market = coingecko.fetch_market_data("BTCUSDT").await.unwrap();
+    assert_eq!(market.current_price, dec!(60350));
+    assert_eq!(market.hourly_data_48h.len(), 48);
+    assert_eq!(market.hourly_data_24h.len(), 24);
+    let global = coingecko.fetch_global().await.unwrap();
+    assert_eq!((global.btc_dominance, global.market_cap_change_24h_percent), (dec!(52.4), dec!(-1.25)));
+    assert_eq!(global.total_market_cap, dec!(2400000000000));
+    reporter.update_global_market(Some(global.clone()));
+
+    // Target stage: fallback first, then the AI advisor
+    let mut context = context_from(&market, config.simulation_initial_balance);
+    context.btc_dominance = Some(global.btc_dominance);
+    context.market_cap_change_24h_percent = Some(global.market_cap_change_24h_percent);
+    assert_eq!(context.sma_short.map(|s| s > context.sma_long.unwrap()), Some(true));
+    let fallback = FallbackTargetCalculator::calculate_targets(&context);
+    assert!(fallback.stop_loss_price < market.current_price);
+    assert!(fallback.take_profit_price > market.current_price);
+
+    let ollama = OllamaClient::new(Some(&ollama_server.uri()), Some("mistral")).unwrap();
+    let targets = ollama.calculate_targets(&context).await.unwrap();
+    assert_eq!(targets.recommendation, TradingRecommendation::Buy);
+    assert_eq!(targets.confidence, dec!(72));
+    assert_eq!(targets.stop_loss_price, dec!(57000));
+    assert_eq!(targets.take_profit_price, dec!(66000));
+    reporter.update_ai_targets(&targets);
+
+    // Execution stage on the simulated exchange
+    let price = exchange.get_price("BTCUSDT").await.unwrap();
+    let qty = dec!(0.01);
+    let order = exchange.place_order("BTCUSDT", OrderSide::Buy, OrderType::Market, qty, None).await.unwrap();
+    let fill = order.execution("BTC", "USDT", price, qty, config.trading_fee_percent);
+    assert_eq!(fill.qty, qty);
+    assert_eq!(fill.fee, fill.price * qty * config.trading_fee_percent / dec!(100));
+    reporter.record_trade(OrderSide::Buy, fill.price, fill.qty, None, fill.fee);
+    let balances = exchange.get_balance().await.unwrap();
+    assert_eq!(balances["BTC"].free, qty);
+    reporter.update_balances(balances.iter().map(|(k, v)| (k.clone(), v.free)).collect());
+    reporter.write_if_dirty().unwrap();
+
+    assert_eq!(reporter.status().total_trades, 1);
+    assert_eq!(reporter.status().total_fees, fill.fee);
+    assert_eq!(reporter.status().entry_price, Some(fill.price));
+    let report = std::fs::read_to_string(&report_path).unwrap();
+    assert!(report.contains("BTCUSDT"));
+    assert!(report.contains("BTC Dominance:     52.4%"));
+    std::fs::remove_file(&report_path).ok();
+    std::fs::remove_file(report_path.with_extension("html")).ok();
+}
+
+#[tokio::test]
+async fn binance_client_against_mocked_api() {
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/api/v3/ticker/price"))
+        .and(query_param("symbol", "BTCUSDT"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({"symbol": "BTCUSDT", "price": "60350.12"})))
+        .mount(&server)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/api/v3/account"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({"balances": [
+            {"asset": "USDT", "free": "1000.00", "locked": "0.00"},
+            {"asset": "BTC", "free": "0.00000000", "locked": "0.00000000"}
+        ]})))
+        .mount(&server)
+        .await;
+    Mock::given(method("POST"))
+        .and(path("/api/v3/order"))
+        .and(query_param("side", "BUY"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({
+            "symbol": "BTCUSDT", "orderId": 42, "clientOrderId": "abc", "price": "0.00",
+            "origQty": "0.01", "executedQty": "0.01", "status": "FILLED", "side": "BUY", "type": "MARKET"
+        })))
+        .mount(&server)
+        .await;
+
+    let mut config = Config::defaults();
+    config.base_url = server.uri();
+    let exchange = ExchangeClient::new(&config).await.unwrap();
+
+    assert_eq!(exchange.get_price("BTCUSDT").await.unwrap(), dec!(60350.12));
+    let balances = exchange.get_balance().await.unwrap();
+    assert_eq!(balances.len(), 1);
+    assert_eq!(balances["USDT"].free, dec!(1000));
+
+    let order = exchange.place_order("BTCUSDT", OrderSide::Buy, OrderType::Market, dec!(0.01), None).await.unwrap();
+    assert_eq!(order.order_id, 42);
+    assert_eq!(order.status, "FILLED");
+}
+
+#[tokio::test]
+async fn upstream_failures_are_classified() {
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/coins/markets"))
+        .respond_with(ResponseTemplate::new(429).set_body_string("rate limited"))
+        .mount(&server)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/coins/bitcoin/market_chart"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(hourly_chart()))
+        .mount(&server)
+        .await;
+    Mock::given(method("GET"))
+        .and(path("/api/v3/account"))
+        .respond_with(ResponseTemplate::new(401).set_body_json(json!({"code": -2015, "msg": "Invalid API-key"})))
+        .mount(&server)
+        .await;
+
+    let coingecko = CoinGeckoClient::with_base_url(&server.uri());
+    let err = coingecko.fetch_market_data("BTCUSDT").await.unwrap_err();
+    let kind = BotError::find(&err).and_then(BotError::kind);
+    assert_eq!(kind, Some(ErrorKind::RateLimited));
+
+    let mut config = Config::defaults();
+    config.base_url = server.uri();
+    let exchange = ExchangeClient::new(&config).await.unwrap();
+    let err = exchange.get_balance().await.unwrap_err();
+    let bot_error = BotError::find(&err).unwrap();
+    assert_eq!(bot_error.kind(), Some(ErrorKind::Unauthorized));
+    assert!(!bot_error.is_retryable());
+}
+
+#[tokio::test]
+async fn ollama_warm_up_and_missing_model() {
+    let server = MockServer::start().await;
+    Mock::given(method("GET"))
+        .and(path("/api/tags"))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({"models": [{"name": "mistral:latest"}]})))
+        .mount(&server)
+        .await;
+    // An empty prompt only loads the model
+    Mock::given(method("POST"))
+        .and(path("/api/generate"))
+        .and(body_partial_json(json!({"model": "mistral", "prompt": "", "keep_alive": "10m"})))
+        .respond_with(ResponseTemplate::new(200).set_body_json(json!({"response": "", "done": true})))
+        .expect(1)
+        .mount(&server)
+        .await;
+    Mock::given(method("POST"))
+        .and(path("/api/generate"))
+        .and(body_partial_json(json!({"model": "llama3"})))
+        .respond_with(ResponseTemplate::new(404).set_body_json(json!({"error": "model 'llama3' not found"})))
+        .mount(&server)
+        .await;
+
+    let options = GenerationOptions { keep_alive: Some("10m".to_string()), ..Default::default() };
+    let mistral = OllamaClient::new(Some(&server.uri()), Some("mistral")).unwrap().with_options(options);
+    mistral.verify_model().await.unwrap();
+    mistral.warm_up().await.unwrap();
+
+    let llama = OllamaClient::new(Some(&server.uri()), Some("llama3")).unwrap();
+    let err = llama.verify_model().await.unwrap_err().to_string();
+    assert!(err.contains("ollama pull llama3") && err.contains("mistral:latest"), "{}", err);
+    let err = llama.warm_up().await.unwrap_err().to_string();
+    assert!(err.contains("ollama pull llama3"), "{}", err);
+}
@@ -0,0 +1,196 @@
+//! Scripted trading scenarios against the MockExchange: stop-loss and take-profit exits,
+//! the daily trade limit, and exchange faults; plus a chaos-mode simulation run
+
+use crypto_trading_bot::mock_exchange::{MockExchange, OrderFault};
+use crypto_trading_bot::position::{ExitTrigger, Position};
+use crypto_trading_bot::trade_limiter::{LimitMode, LimiterSettings, TradeLimiter, TradePermission};
+use crypto_trading_bot::chaos::Chaos;
+use crypto_trading_bot::{Config, Exchange, OrderSide, OrderType, SimulationExchange};
+use rust_decimal::Decimal;
+use rust_decimal_macros::dec;
+use std::path::PathBuf;
+
+const SYMBOL: &str = "BTCUSDT";
+const FEE_PERCENT: Decimal = dec!(0.1);
+
+#[derive(Debug, PartialEq)]
+enum Outcome {
+    Entered(Decimal),
+    Holding,
+    Exited(ExitTrigger, Decimal),
+    /// A non-stop exit waiting for the trade limit
+    HeldBack(ExitTrigger),
+    LimitReached,
+    PriceUnavailable,
+    OrderFailed,
+}
+
+/// One trading slot driven by the main loop's rules: buy with a fixed budget when flat,
+/// exit at -2% / +3%, a stop-loss always honored and other exits only when the limiter
+/// allows them
+struct Bot<E> {
+    exchange: E,
+    position: Position,
+    limiter: TradeLimiter,
+    state_file: PathBuf,
+}
+
+impl<E: Exchange> Bot<E> {
+    fn new(name: &str, exchange: E, settings: LimiterSettings) -> Self {
+        let state_file = std::env::temp_dir().join(format!("ctb_scenario_{}_{}.json", name, std::process::id()));
+        std::fs::remove_file(&state_file).ok();
+        let limiter = TradeLimiter::with_settings(&state_file, None, LimiterSettings { history_days: 0, ..settings });
+        Self { exchange, position: Position::new(SYMBOL), limiter, state_file }
+    }
+
+    async fn cycle(&mut self) -> Outcome {
+        let Ok(price) = self.exchange.get_price(SYMBOL).await else {
+            return Outcome::PriceUnavailable;
+        };
+        if self.position.is_open() {
+            self.position.track_excursion(price);
+            let Some(trigger) = self.position.exit_trigger(price) else {
+                return Outcome::Holding;
+            };
+            if trigger != ExitTrigger::StopLoss && !self.limiter.can_exit().is_allowed() {
+                return Outcome::HeldBack(trigger);
+            }
+            self.position.begin_exit().unwrap();
+            let qty = self.position.qty;
+            match self.exchange.place_order(SYMBOL, OrderSide::Sell, OrderType::Market, qty, None).await {
+                Ok(order) => {
+                    let fill = order.execution("BTC", "USDT", price, qty, FEE_PERCENT);
+                    let pnl = self.position.exit_filled(fill).unwrap();
+                    self.limiter.record_trade(SYMBOL, "SELL", fill.price, fill.qty).unwrap();
+                    Outcome::Exited(trigger, pnl)
+                }
+                Err(_) => {
+                    self.position.exit_failed().unwrap();
+                    Outcome::OrderFailed
+                }
+            }
+        } else {
+            if !self.limiter.can_trade().is_allowed() {
+                return Outcome::LimitReached;
+            }
+            let qty = (dec!(500) / price).round_dp(5);
+            self.position.begin_entry(qty).unwrap();
+            match self.exchange.place_order(SYMBOL, OrderSide::Buy, OrderType::Market, qty, None).await {
+                Ok(order) => {
+                    let fill = order.execution("BTC", "USDT", price, qty, FEE_PERCENT);
+                    self.position.entry_filled(fill).unwrap();
+                    self.position.set_percent_targets(dec!(-2), dec!(3));
+                    self.limiter.record_trade(SYMBOL, "BUY", fill.price, fill.qty).unwrap();
+                    Outcome::Entered(fill.qty)
+                }
+                Err(_) => {
+                    self.position.entry_failed().unwrap();
+                    Outcome::OrderFailed
+                }
+            }
+        }
+    }
+}
+
+impl<E> Drop for Bot<E> {
+    fn drop(&mut self) {
+        std::fs::remove_file(&self.state_file).ok();
+    }
+}
+
+#[tokio::test]
+async fn test_stop_loss_exits_at_a_loss() {
+    let exchange = MockExchange::new(SYMBOL, dec!(1000)).with_prices([dec!(100), dec!(101), dec!(97.5)]);
+    let mut bot = Bot::new("stop_loss", exchange, LimiterSettings::default());
+
+    assert_eq!(bot.cycle().await, Outcome::Entered(dec!(5)));
+    assert_eq!(bot.cycle().await, Outcome::Holding);
+    let Outcome::Exited(ExitTrigger::StopLoss, pnl) = bot.cycle().await else { panic!("stop-loss not taken") };
+    assert!(pnl < Decimal::ZERO);
+    assert!(bot.position.is_flat());
+    assert_eq!(bot.exchange.free_balance("BTC"), Decimal::ZERO);
+    assert_eq!(bot.exchange.free_balance("USDT"), dec!(1000) + pnl);
+}
+
+#[tokio::test]
+async fn test_take_profit_exits_at_a_gain() {
+    let exchange = MockExchange::new(SYMBOL, dec!(1000)).with_prices([dec!(100), dec!(102), dec!(103.5)]);
+    let mut bot = Bot::new("take_profit", exchange, LimiterSettings::default());
+
+    bot.cycle().await;
+    assert_eq!(bot.cycle().await, Outcome::Holding);
+    let Outcome::Exited(ExitTrigger::TakeProfit, pnl) = bot.cycle().await else { panic!("take-profit not taken") };
+    assert!(pnl > Decimal::ZERO);
+    assert_eq!(bot.exchange.orders().len(), 2);
+}
+
+#[tokio::test]
+async fn test_trade_limit_holds_take_profit_but_not_stop_loss() {
+    let exchange = MockExchange::new(SYMBOL, dec!(1000)).with_prices([dec!(100), dec!(104), dec!(104), dec!(97), dec!(100)]);
+    let settings = LimiterSettings { max_trades_per_day: 1, ..Default::default() };
+    let mut bot = Bot::new("limit_orders", exchange, settings);
+
+    assert_eq!(bot.cycle().await, Outcome::Entered(dec!(5)));
+    assert_eq!(bot.cycle().await, Outcome::HeldBack(ExitTrigger::TakeProfit));
+    assert_eq!(bot.cycle().await, Outcome::HeldBack(ExitTrigger::TakeProfit));
+    assert!(matches!(bot.cycle().await, Outcome::Exited(ExitTrigger::StopLoss, _)));
+    assert_eq!(bot.cycle().await, Outcome::LimitReached);
+
+    // Counting round trips, the entry's exit is never held back
+    let exchange = MockExchange::new(SYMBOL, dec!(1000)).with_prices([dec!(100), dec!(104), dec!(100)]);
+    let settings = LimiterSettings { max_trades_per_day: 1, mode: LimitMode::RoundTrips, ..Default::default() };
+    let mut bot = Bot::new("limit_round_trips", exchange, settings);
+
+    bot.cycle().await;
+    assert!(matches!(bot.cycle().await, Outcome::Exited(ExitTrigger::TakeProfit, _)));
+    assert_eq!(bot.cycle().await, Outcome::LimitReached);
+}
+
+#[tokio::test]
+async fn test_faults_leave_consistent_state() {
+    let exchange = MockExchange::new(SYMBOL, dec!(1000))
+        .with_prices([dec!(100), dec!(100), dec!(100), dec!(101), dec!(97), dec!(96)])
+        .timeout_at(1)
+        .fail_order(0, OrderFault::Rejected)
+        .fail_order(1, OrderFault::PartialFill(dec!(0.5)))
+        .fail_order(2, OrderFault::Timeout);
+    let mut bot = Bot::new("faults", exchange, LimiterSettings::default());
+
+    // A rejected entry leaves the slot flat and uses no trade
+    assert_eq!(bot.cycle().await, Outcome::OrderFailed);
+    assert!(bot.position.is_flat());
+    assert!(matches!(bot.limiter.can_trade(), TradePermission::Allowed { trades_remaining: 2, .. }));
+    // A price timeout skips the cycle
+    assert_eq!(bot.cycle().await, Outcome::PriceUnavailable);
+    // Only half the entry fills: the position holds what filled
+    assert_eq!(bot.cycle().await, Outcome::Entered(dec!(2.5)));
+    assert_eq!(bot.exchange.orders()[0].status, "EXPIRED");
+    assert_eq!(bot.cycle().await, Outcome::Holding);
+    // The stop-loss order times out; the position stays open and the exit is retried
+    assert_eq!(bot.cycle().await, Outcome::OrderFailed);
+    assert!(bot.position.is_open());
+    assert!(matches!(bot.cycle().await, Outcome::Exited(ExitTrigger::StopLoss, _)));
+    assert_eq!(bot.exchange.free_balance("BTC"), Decimal::ZERO);
+}
+
+#[tokio::test]
+async fn test_chaos_mode_never_double_trades() {
+    let mut config = Config::defaults();
+    config.simulation_mode = true;
+    config.symbol = SYMBOL.to_string();
+    config.chaos_failure_percent = dec!(30);
+    let chaos = Chaos::from_config(&config).unwrap();
+    let exchange = SimulationExchange::new(&config).await.unwrap().with_chaos(chaos);
+    let settings = LimiterSettings { max_trades_per_day: 1000, ..Default::default() };
+    let mut bot = Bot::new("chaos", exchange, settings);
+
+    let mut outcomes = Vec::new();
+    for _ in 0..300 {
+        outcomes.push(bot.cycle().await);
+        // Whatever failed, the account holds exactly what the position says
+        assert_eq!(bot.exchange.free_balance("BTC").await, bot.position.qty);
+    }
+    assert!(outcomes.contains(&Outcome::PriceUnavailable));
+    assert!(outcomes.contains(&Outcome::OrderFailed));
+    assert!(outcomes.iter().any(|o| matches!(o, Outcome::Entered(_))));
+}